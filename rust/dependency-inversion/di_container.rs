@@ -0,0 +1,409 @@
+// DEPENDENCY INVERSION - WIRING WITH A DEPENDENCY INJECTION CONTAINER
+//
+// CorrectUserService from the single-responsibility example depends only on
+// traits such as UserRepository, EmailService and ActivityLogger. Something
+// still has to choose the implementations and build the object graph, and
+// this file shows two ways of doing it:
+// - manual construction: the composition root calls every constructor itself
+// - a Container: factories are registered against the abstraction's type
+//   (e.g. `dyn EmailService`) and the container builds whatever is resolved,
+//   including each factory's own dependencies
+//
+// Neither way changes CorrectUserService. The container is a type map keyed
+// by TypeId, so an unregistered abstraction is a run-time error rather than a
+// compile error - the price of deciding the wiring at run time.
+
+use std::any::{type_name, Any, TypeId};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt;
+use std::rc::Rc;
+use correct_user_service::testing::MockEmailService;
+use correct_user_service::{
+    ActivityLogger, CorrectUserService, DefaultUserFormatter, DefaultUserValidator, EmailService, FirebaseNotificationService,
+    GoogleAnalyticsService, InMemoryUserRepository, SMTPEmailService, TracingActivityLogger, UserRepository,
+};
+
+/// CorrectUserService with the three collaborators the container supplies
+/// held as trait objects; the rest are fixed at compile time
+pub type ContainerUserService = CorrectUserService<
+    Rc<dyn UserRepository>,
+    Rc<dyn EmailService>,
+    Rc<dyn ActivityLogger>,
+    DefaultUserValidator,
+    DefaultUserFormatter,
+    GoogleAnalyticsService,
+    FirebaseNotificationService,
+>;
+
+/// How long an instance built by the container lives
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Lifetime {
+    /// Built on the first resolve and shared by every later one
+    Singleton,
+    /// Built anew on every resolve
+    Transient,
+}
+
+/// Why the container could not register or resolve a type
+#[derive(Debug, Clone, PartialEq)]
+pub enum ContainerError {
+    /// Nothing is registered for the requested type
+    NotRegistered { type_name: &'static str },
+    /// The type already has a registration
+    AlreadyRegistered { type_name: &'static str },
+    /// Building a type needs the type itself. The chain runs from its first
+    /// request to the repeat.
+    CircularDependency { chain: Vec<&'static str> },
+}
+
+impl fmt::Display for ContainerError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ContainerError::NotRegistered { type_name } => write!(f, "No registration for {}", type_name),
+            ContainerError::AlreadyRegistered { type_name } => write!(f, "{} is already registered", type_name),
+            ContainerError::CircularDependency { chain } => write!(f, "Circular dependency: {}", chain.join(" -> ")),
+        }
+    }
+}
+
+impl std::error::Error for ContainerError {}
+
+type Factory<T> = Box<dyn Fn(&Container) -> Result<Rc<T>, ContainerError>>;
+
+struct Registration {
+    lifetime: Lifetime,
+    /// The `Factory<T>` of the type this is registered under
+    factory: Box<dyn Any>,
+}
+
+/// Minimal dependency injection container: one factory per type, looked up
+/// by the type's TypeId. Types are usually trait objects such as
+/// `dyn EmailService`, so callers ask for an abstraction and never learn
+/// which implementation they got.
+#[derive(Default)]
+pub struct Container {
+    registrations: HashMap<TypeId, Registration>,
+    /// Singletons built so far, each an `Rc<T>`
+    singletons: RefCell<HashMap<TypeId, Box<dyn Any>>>,
+    /// Types being built, outermost first, to catch cycles
+    resolving: RefCell<Vec<(TypeId, &'static str)>>,
+}
+
+impl Container {
+    pub fn new() -> Self {
+        Container::default()
+    }
+
+    /// Registers `factory` as the way to build `T`. The factory receives the
+    /// container, so it can resolve its own dependencies. Registering a type
+    /// twice is an error, so one wiring cannot silently replace another.
+    pub fn register<T: ?Sized + 'static>(
+        &mut self,
+        lifetime: Lifetime,
+        factory: impl Fn(&Container) -> Result<Rc<T>, ContainerError> + 'static,
+    ) -> Result<(), ContainerError> {
+        let id = TypeId::of::<T>();
+        if self.registrations.contains_key(&id) {
+            return Err(ContainerError::AlreadyRegistered { type_name: type_name::<T>() });
+        }
+        let factory: Factory<T> = Box::new(factory);
+        self.registrations.insert(
+            id,
+            Registration {
+                lifetime,
+                factory: Box::new(factory),
+            },
+        );
+        Ok(())
+    }
+
+    pub fn is_registered<T: ?Sized + 'static>(&self) -> bool {
+        self.registrations.contains_key(&TypeId::of::<T>())
+    }
+
+    /// The `T` built by its registered factory: the shared instance for a
+    /// singleton, a new one for a transient. Fails if `T`, or anything its
+    /// factory resolves, is unregistered or needs itself to be built.
+    pub fn resolve<T: ?Sized + 'static>(&self) -> Result<Rc<T>, ContainerError> {
+        let id = TypeId::of::<T>();
+        let registration = self
+            .registrations
+            .get(&id)
+            .ok_or(ContainerError::NotRegistered { type_name: type_name::<T>() })?;
+        if let Some(instance) = self.singletons.borrow().get(&id) {
+            let instance = instance.downcast_ref::<Rc<T>>().expect("singletons are stored as Rc<T>");
+            return Ok(Rc::clone(instance));
+        }
+
+        let resolving = self.resolving.borrow();
+        if let Some(start) = resolving.iter().position(|(resolving, _)| *resolving == id) {
+            let mut chain: Vec<&'static str> = resolving[start..].iter().map(|(_, name)| *name).collect();
+            chain.push(type_name::<T>());
+            return Err(ContainerError::CircularDependency { chain });
+        }
+        drop(resolving);
+
+        let factory = registration
+            .factory
+            .downcast_ref::<Factory<T>>()
+            .expect("factories are stored under the TypeId of their type");
+        self.resolving.borrow_mut().push((id, type_name::<T>()));
+        let instance = factory(self);
+        self.resolving.borrow_mut().pop();
+        let instance = instance?;
+
+        if registration.lifetime == Lifetime::Singleton {
+            self.singletons.borrow_mut().insert(id, Box::new(Rc::clone(&instance)));
+        }
+        Ok(instance)
+    }
+}
+
+/// Manual wiring: the composition root calls each constructor itself
+pub fn build_manually() -> CorrectUserService<
+    InMemoryUserRepository,
+    SMTPEmailService,
+    TracingActivityLogger,
+    DefaultUserValidator,
+    DefaultUserFormatter,
+    GoogleAnalyticsService,
+    FirebaseNotificationService,
+> {
+    CorrectUserService::new(
+        InMemoryUserRepository::new(),
+        SMTPEmailService::new("smtp.example.com".to_string()),
+        TracingActivityLogger::new(),
+        DefaultUserValidator::new(),
+        DefaultUserFormatter::new(),
+        GoogleAnalyticsService::new("GA-XXXXX-X".to_string()),
+        Some(FirebaseNotificationService::new("firebase-api-key".to_string())),
+    )
+}
+
+/// Builds a CorrectUserService from whatever the container has registered
+/// for its repository, email service and logger
+pub fn user_service_factory(container: &Container) -> Result<Rc<ContainerUserService>, ContainerError> {
+    Ok(Rc::new(CorrectUserService::new(
+        container.resolve()?,
+        container.resolve()?,
+        container.resolve()?,
+        DefaultUserValidator::new(),
+        DefaultUserFormatter::new(),
+        GoogleAnalyticsService::new("GA-XXXXX-X".to_string()),
+        Some(FirebaseNotificationService::new("firebase-api-key".to_string())),
+    )))
+}
+
+/// Container wiring: one shared repository and email service, a new logger
+/// and a new CorrectUserService for every resolve
+pub fn production_container() -> Result<Container, ContainerError> {
+    let mut container = Container::new();
+    container.register::<dyn UserRepository>(Lifetime::Singleton, |_| Ok(Rc::new(InMemoryUserRepository::new())))?;
+    container.register::<dyn EmailService>(Lifetime::Singleton, |_| Ok(Rc::new(SMTPEmailService::new("smtp.example.com".to_string()))))?;
+    container.register::<dyn ActivityLogger>(Lifetime::Transient, |_| Ok(Rc::new(TracingActivityLogger::new())))?;
+    container.register::<ContainerUserService>(Lifetime::Transient, user_service_factory)?;
+    Ok(container)
+}
+
+/// The same wiring with email kept in `recorder` instead of sent
+pub fn recording_container(recorder: MockEmailService) -> Result<Container, ContainerError> {
+    let mut container = Container::new();
+    container.register::<dyn UserRepository>(Lifetime::Singleton, |_| Ok(Rc::new(InMemoryUserRepository::new())))?;
+    container.register::<dyn EmailService>(Lifetime::Singleton, move |_| Ok(Rc::new(recorder.clone())))?;
+    container.register::<dyn ActivityLogger>(Lifetime::Transient, |_| Ok(Rc::new(TracingActivityLogger::new())))?;
+    container.register::<ContainerUserService>(Lifetime::Transient, user_service_factory)?;
+    Ok(container)
+}
+
+/// Example usage demonstrating manual and container wiring
+pub fn demonstrate_di_container() {
+    println!("=== DEPENDENCY INJECTION CONTAINER ===");
+    println!("CorrectUserService depends on traits; the wiring is chosen outside it");
+    println!();
+
+    println!("Manual construction:");
+    let service = build_manually();
+    if let Err(e) = service.create_user("alice".to_string(), "alice@example.com".to_string()) {
+        println!("Error: {}", e);
+    }
+    println!();
+
+    println!("Container construction:");
+    let wired = production_container().and_then(|container| {
+        let first = container.resolve::<ContainerUserService>()?;
+        let second = container.resolve::<ContainerUserService>()?;
+        Ok((container, first, second))
+    });
+    match wired {
+        Ok((container, first, second)) => {
+            if let Err(e) = first.create_user("bob".to_string(), "bob@example.com".to_string()) {
+                println!("Error: {}", e);
+            }
+            // Each resolve built a new service, but the singleton repository is shared
+            println!("Same service instance: {}", Rc::ptr_eq(&first, &second));
+            let found = container.resolve::<dyn UserRepository>().map(|users| users.find_by_email("bob@example.com"));
+            println!("Shared repository has bob: {}", matches!(found, Ok(Ok(Some(_)))));
+        }
+        Err(e) => println!("Error: {}", e),
+    }
+    println!();
+
+    // Swapping an implementation is a registration change, not a code change
+    println!("Container with a recording email service:");
+    let recorder = MockEmailService::default();
+    match recording_container(recorder.clone()).and_then(|container| container.resolve::<ContainerUserService>()) {
+        Ok(service) => {
+            if let Err(e) = service.create_user("carol".to_string(), "carol@example.com".to_string()) {
+                println!("Error: {}", e);
+            }
+            let sent: Vec<String> = recorder.sent().iter().map(|message| format!("{}: {}", message.to, message.subject)).collect();
+            println!("Recorded instead of sent: {:?}", sent);
+        }
+        Err(e) => println!("Error: {}", e),
+    }
+    println!();
+
+    println!("Mistakes are reported, not silently wired:");
+    if let Err(e) = Container::new().resolve::<dyn EmailService>() {
+        println!("Missing: {}", e);
+    }
+    let mut duplicate = Container::new();
+    let _ = duplicate.register::<dyn ActivityLogger>(Lifetime::Singleton, |_| Ok(Rc::new(TracingActivityLogger::new())));
+    if let Err(e) = duplicate.register::<dyn ActivityLogger>(Lifetime::Singleton, |_| Ok(Rc::new(TracingActivityLogger::new()))) {
+        println!("Duplicate: {}", e);
+    }
+    let mut cyclic = Container::new();
+    let _ = cyclic.register::<dyn ActivityLogger>(Lifetime::Transient, |container| {
+        let _ = container.resolve::<dyn EmailService>()?;
+        Ok(Rc::new(TracingActivityLogger::new()))
+    });
+    let _ = cyclic.register::<dyn EmailService>(Lifetime::Transient, |container| {
+        let _ = container.resolve::<dyn ActivityLogger>()?;
+        Ok(Rc::new(SMTPEmailService::new("smtp.example.com".to_string())))
+    });
+    if let Err(e) = cyclic.resolve::<dyn ActivityLogger>() {
+        println!("Cycle: {}", e);
+    }
+
+    println!();
+    println!("Manual construction versus a container:");
+    println!("1. Manual wiring is checked by the compiler; a container checks at run time");
+    println!("2. A container builds nested dependencies and manages their lifetimes");
+    println!("3. Either way, CorrectUserService only ever sees the traits");
+}
+
+fn main() {
+    demonstrate_di_container();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+    use correct_user_service::patterns::null_object::NoopLogger;
+    use correct_user_service::Pagination;
+
+    // Registers `dyn ActivityLogger` with `lifetime`; the returned counter
+    // tells how many times its factory ran
+    fn counted_logger(container: &mut Container, lifetime: Lifetime) -> Rc<Cell<usize>> {
+        let built = Rc::new(Cell::new(0));
+        let counter = Rc::clone(&built);
+        container
+            .register::<dyn ActivityLogger>(lifetime, move |_| {
+                counter.set(counter.get() + 1);
+                Ok(Rc::new(NoopLogger))
+            })
+            .unwrap();
+        built
+    }
+
+    #[test]
+    fn resolving_an_unregistered_type_names_it() {
+        let error = Container::new().resolve::<dyn EmailService>().err().unwrap();
+        assert_eq!(error, ContainerError::NotRegistered { type_name: type_name::<dyn EmailService>() });
+        assert!(error.to_string().starts_with("No registration for dyn "));
+        assert!(error.to_string().ends_with("EmailService"));
+    }
+
+    #[test]
+    fn a_missing_dependency_fails_the_whole_resolve() {
+        let mut container = Container::new();
+        container.register::<dyn UserRepository>(Lifetime::Singleton, |_| Ok(Rc::new(InMemoryUserRepository::new()))).unwrap();
+        container.register::<dyn ActivityLogger>(Lifetime::Transient, |_| Ok(Rc::new(NoopLogger))).unwrap();
+        container.register::<ContainerUserService>(Lifetime::Transient, user_service_factory).unwrap();
+
+        let error = container.resolve::<ContainerUserService>().err().unwrap();
+        assert_eq!(error, ContainerError::NotRegistered { type_name: type_name::<dyn EmailService>() });
+    }
+
+    #[test]
+    fn registering_a_type_twice_is_rejected() {
+        let mut container = Container::new();
+        counted_logger(&mut container, Lifetime::Singleton);
+        let error = container.register::<dyn ActivityLogger>(Lifetime::Transient, |_| Ok(Rc::new(NoopLogger))).err().unwrap();
+        assert_eq!(error, ContainerError::AlreadyRegistered { type_name: type_name::<dyn ActivityLogger>() });
+        assert!(container.is_registered::<dyn ActivityLogger>());
+        assert!(!container.is_registered::<dyn EmailService>());
+    }
+
+    #[test]
+    fn a_singleton_is_built_once_and_shared() {
+        let mut container = Container::new();
+        let built = counted_logger(&mut container, Lifetime::Singleton);
+        assert_eq!(built.get(), 0, "nothing is built before the first resolve");
+
+        let first = container.resolve::<dyn ActivityLogger>().unwrap();
+        let second = container.resolve::<dyn ActivityLogger>().unwrap();
+        assert_eq!(built.get(), 1);
+        assert!(Rc::ptr_eq(&first, &second));
+    }
+
+    #[test]
+    fn a_transient_is_built_on_every_resolve() {
+        let mut container = Container::new();
+        let built = counted_logger(&mut container, Lifetime::Transient);
+        for _ in 0..3 {
+            container.resolve::<dyn ActivityLogger>().unwrap();
+        }
+        assert_eq!(built.get(), 3);
+    }
+
+    #[test]
+    fn transient_services_share_the_singleton_repository() {
+        let recorder = MockEmailService::default();
+        let container = recording_container(recorder.clone()).unwrap();
+        let first = container.resolve::<ContainerUserService>().unwrap();
+        let second = container.resolve::<ContainerUserService>().unwrap();
+        assert!(!Rc::ptr_eq(&first, &second));
+
+        let user = first.create_user("alice".to_string(), "alice@example.com".to_string()).unwrap();
+        let listed: Vec<String> = second.list_users(Pagination::default()).unwrap().items.iter().map(|user| user.email().to_string()).collect();
+        assert_eq!(listed, [user.email()]);
+        let sent: Vec<String> = recorder.sent().into_iter().map(|message| message.to).collect();
+        assert_eq!(sent, ["alice@example.com"]);
+    }
+
+    #[test]
+    fn a_cycle_is_reported_with_its_chain() {
+        let mut container = Container::new();
+        container
+            .register::<dyn ActivityLogger>(Lifetime::Transient, |container| {
+                container.resolve::<dyn EmailService>()?;
+                Ok(Rc::new(NoopLogger))
+            })
+            .unwrap();
+        container
+            .register::<dyn EmailService>(Lifetime::Singleton, |container| {
+                container.resolve::<dyn ActivityLogger>()?;
+                Ok(Rc::new(MockEmailService::default()))
+            })
+            .unwrap();
+
+        let error = container.resolve::<dyn ActivityLogger>().err().unwrap();
+        let logger = type_name::<dyn ActivityLogger>();
+        assert_eq!(error, ContainerError::CircularDependency { chain: vec![logger, type_name::<dyn EmailService>(), logger] });
+        // A failed resolve leaves no half-built singleton or stale cycle state behind
+        assert!(matches!(container.resolve::<dyn EmailService>(), Err(ContainerError::CircularDependency { .. })));
+    }
+}
@@ -0,0 +1,58 @@
+// ADAPTER - the single-responsibility ActivityLogger over the `log` facade (feature `log`)
+//
+// The adapter only emits records. Where they go is decided by whichever
+// backend the application installs: env_logger, syslog, ... or StdoutLog.
+
+use correct_user_service::ActivityLogger;
+use log::{Level, LevelFilter, Log, Metadata, Record};
+
+#[derive(Debug)]
+pub struct LogActivityLogger {
+    target: String,
+}
+
+impl LogActivityLogger {
+    /// Records are emitted under `target`, so backends can filter on it
+    pub fn new(target: &str) -> Self {
+        LogActivityLogger { target: target.to_string() }
+    }
+}
+
+// Audits keep the trait's default and arrive as activity records
+impl ActivityLogger for LogActivityLogger {
+    fn log_user_activity(&self, username: &str, activity: &str) {
+        log::info!(target: &self.target, "{}: {}", username, activity);
+    }
+
+    fn log_error(&self, error: &str, context: &str) {
+        log::error!(target: &self.target, "{}: {}", context, error);
+    }
+}
+
+/// A minimal `log` backend printing to stdout, for the demo
+pub struct StdoutLog;
+
+static STDOUT_LOG: StdoutLog = StdoutLog;
+
+impl StdoutLog {
+    /// Installs the backend; only the first call in a process takes effect
+    pub fn install() {
+        if log::set_logger(&STDOUT_LOG).is_ok() {
+            log::set_max_level(LevelFilter::Info);
+        }
+    }
+}
+
+impl Log for StdoutLog {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= Level::Info
+    }
+
+    fn log(&self, record: &Record) {
+        if self.enabled(record.metadata()) {
+            println!("  {} {}: {}", record.level(), record.target(), record.args());
+        }
+    }
+
+    fn flush(&self) {}
+}
@@ -0,0 +1,240 @@
+// DEPENDENCY INVERSION AT CRATE BOUNDARIES - FRAMEWORK ADAPTERS
+//
+// The abstractions are the single-responsibility example's own:
+// UserRepository, ActivityLogger and EmailService, with CorrectUserService
+// written against them. That crate already reaches three ecosystem crates
+// through one implementation each:
+// - SqliteUserRepository (its `sqlite` feature): UserRepository over rusqlite
+// - TracingActivityLogger: ActivityLogger over tracing
+// - LettreEmailService (its `smtp` feature): EmailService over lettre
+//
+// This binary adds the one crate it lacks, through the same kind of module:
+// - log_adapter.rs (feature `log`): ActivityLogger over the log facade
+//
+// The crates point at the traits, never the other way round: dropping a
+// feature removes its implementation, and CorrectUserService doesn't change.
+
+#[cfg(feature = "log")]
+mod log_adapter;
+
+use correct_user_service::patterns::null_object::NoopAnalytics;
+use correct_user_service::{
+    ActivityLogger, ConfiguredUserService, EmailService, InMemoryUserRepository, Pagination, SMTPEmailService,
+    TracingActivityLogger, UserRepository, UserServiceBuilder,
+};
+
+/// The cargo features this binary was built with
+fn enabled_features() -> Vec<&'static str> {
+    let mut features = Vec::new();
+    if cfg!(feature = "sqlite") {
+        features.push("sqlite");
+    }
+    if cfg!(feature = "log") {
+        features.push("log");
+    }
+    if cfg!(feature = "smtp") {
+        features.push("smtp");
+    }
+    features
+}
+
+fn not_compiled_in(kind: &str, port: &str) -> String {
+    format!("No {} adapter named {} in this build (features: {:?})", port, kind, enabled_features())
+}
+
+// Runtime wiring: one function per trait, as a configuration file would pick.
+// Arms for implementations behind a disabled feature are compiled out.
+
+fn repository_from_config(kind: &str) -> Result<Box<dyn UserRepository>, String> {
+    match kind {
+        "memory" => Ok(Box::new(InMemoryUserRepository::new())),
+        #[cfg(feature = "sqlite")]
+        "sqlite" => Ok(Box::new(correct_user_service::SqliteUserRepository::in_memory()?)),
+        other => Err(not_compiled_in(other, "repository")),
+    }
+}
+
+fn logger_from_config(kind: &str) -> Result<Box<dyn ActivityLogger>, String> {
+    match kind {
+        "tracing" => {
+            // Fails harmlessly if a subscriber is already installed
+            let _ = tracing_subscriber::fmt().with_target(false).with_ansi(false).try_init();
+            Ok(Box::new(TracingActivityLogger::new()))
+        }
+        #[cfg(feature = "log")]
+        "log" => {
+            // Once tracing's subscriber is installed, it receives log records too
+            log_adapter::StdoutLog::install();
+            Ok(Box::new(log_adapter::LogActivityLogger::new("users")))
+        }
+        other => Err(not_compiled_in(other, "logger")),
+    }
+}
+
+fn email_from_config(kind: &str) -> Result<Box<dyn EmailService>, String> {
+    match kind {
+        "console" => Ok(Box::new(SMTPEmailService::new("smtp.example.com".to_string()))),
+        // A local catcher such as MailHog; with none running, delivery fails
+        // and the service logs it without failing the registration
+        #[cfg(feature = "smtp")]
+        "smtp" => Ok(Box::new(correct_user_service::LettreEmailService::local("localhost", 1025, "noreply@example.com")?)),
+        other => Err(not_compiled_in(other, "email")),
+    }
+}
+
+/// The composition root: the only function that turns names into implementations
+fn wire(repository: &str, logger: &str, email: &str) -> Result<ConfiguredUserService, String> {
+    Ok(UserServiceBuilder::new()
+        .repository(repository_from_config(repository)?)
+        .logger(logger_from_config(logger)?)
+        .email_service(email_from_config(email)?)
+        .analytics(NoopAnalytics)
+        .without_notifications()
+        .build())
+}
+
+fn register_everyone(service: &ConfiguredUserService) {
+    let requests = [
+        ("ada", "ada@example.com"),
+        ("grace", "grace@example.com"),
+        ("nobody", "not-an-email"),
+    ];
+    for (username, email) in requests {
+        match service.create_user(username.to_string(), email.to_string()) {
+            Ok(user) => println!("  -> {}", service.format_user(&user)),
+            Err(e) => println!("  Rejected {}: {}", username, e),
+        }
+    }
+    match service.list_users(Pagination::default()) {
+        Ok(users) => println!("  {} users stored", users.total),
+        Err(e) => println!("  Cannot list users: {}", e),
+    }
+}
+
+/// Example usage: the same CorrectUserService over built-in and ecosystem implementations
+pub fn demonstrate_framework_adapters() {
+    println!("=== DIP AT CRATE BOUNDARIES (FRAMEWORK ADAPTERS) ===");
+    println!("CorrectUserService depends on its traits; third-party crates appear only in implementations");
+    println!("Built with features: {:?}", enabled_features());
+    if enabled_features().is_empty() {
+        println!("(build with --features sqlite,log,smtp to add the optional implementations)");
+    }
+    println!();
+
+    // The default wiring, then one trait at a time swapped for each optional implementation built in
+    let mut wirings = vec![("memory", "tracing", "console")];
+    for feature in enabled_features() {
+        wirings.push(match feature {
+            "sqlite" => ("sqlite", "tracing", "console"),
+            "smtp" => ("memory", "tracing", "smtp"),
+            logger => ("memory", logger, "console"),
+        });
+    }
+    for (repository, logger, email) in wirings {
+        println!("Wiring: repository={}, logger={}, email={}", repository, logger, email);
+        match wire(repository, logger, email) {
+            Ok(service) => register_everyone(&service),
+            Err(e) => println!("  Error: {}", e),
+        }
+        println!();
+    }
+
+    // Asking for an implementation whose feature is off is a wiring error, not a build error
+    for missing in ["sqlite", "postgres"] {
+        if let Err(e) = repository_from_config(missing) {
+            println!("repository={}: {}", missing, e);
+        }
+    }
+
+    println!();
+    println!("Benefits of this approach:");
+    println!("1. CorrectUserService compiles against the traits alone");
+    println!("2. Each crate is confined to one implementation behind one feature");
+    println!("3. Replacing rusqlite or lettre means a new implementation, not a change to the service");
+    println!("4. Tests wire the in-memory implementations, without compiling the optional crates");
+}
+
+fn main() {
+    demonstrate_framework_adapters();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "log")]
+    mod capture {
+        use log::{LevelFilter, Log, Metadata, Record};
+        use std::sync::{Mutex, Once};
+
+        /// A `log` backend that keeps the records it receives
+        struct CaptureLog {
+            lines: Mutex<Vec<String>>,
+        }
+
+        static CAPTURE: CaptureLog = CaptureLog { lines: Mutex::new(Vec::new()) };
+        static INSTALL: Once = Once::new();
+
+        impl Log for CaptureLog {
+            fn enabled(&self, _metadata: &Metadata) -> bool {
+                true
+            }
+
+            fn log(&self, record: &Record) {
+                let line = format!("{} {}: {}", record.level(), record.target(), record.args());
+                self.lines.lock().unwrap().push(line);
+            }
+
+            fn flush(&self) {}
+        }
+
+        /// Installs the capture backend before any wiring can install another
+        pub fn install() {
+            INSTALL.call_once(|| {
+                log::set_logger(&CAPTURE).expect("no logger installed before the capture");
+                log::set_max_level(LevelFilter::Info);
+            });
+        }
+
+        pub fn lines_mentioning(text: &str) -> Vec<String> {
+            CAPTURE.lines.lock().unwrap().iter().filter(|line| line.contains(text)).cloned().collect()
+        }
+    }
+
+    fn wired(repository: &str, logger: &str, email: &str) -> Result<ConfiguredUserService, String> {
+        #[cfg(feature = "log")]
+        capture::install();
+        wire(repository, logger, email)
+    }
+
+    #[test]
+    fn memory_wiring_runs_the_service() {
+        let service = wired("memory", "tracing", "console").unwrap();
+        service.create_user("ada".to_string(), "ada@example.com".to_string()).unwrap();
+        service.create_user("grace".to_string(), "grace@example.com".to_string()).unwrap();
+        assert!(service.create_user("nobody".to_string(), "not-an-email".to_string()).is_err());
+        assert_eq!(service.list_users(Pagination::default()).unwrap().total, 2);
+    }
+
+    #[test]
+    fn unknown_implementations_are_wiring_errors() {
+        let error = wired("postgres", "tracing", "console").err().unwrap();
+        assert!(error.starts_with("No repository adapter named postgres"), "{}", error);
+        assert!(wired("memory", "syslog", "console").is_err());
+        assert!(wired("memory", "tracing", "fax").is_err());
+        #[cfg(not(feature = "sqlite"))]
+        assert!(wired("sqlite", "tracing", "console").is_err());
+    }
+
+    #[cfg(feature = "log")]
+    #[test]
+    fn log_wiring_emits_activity_records() {
+        let service = wired("memory", "log", "console").unwrap();
+        service.create_user("linus".to_string(), "linus@example.com".to_string()).unwrap();
+        assert!(service.create_user("margaret".to_string(), "not-an-email".to_string()).is_err());
+
+        assert_eq!(capture::lines_mentioning("linus"), ["INFO users: linus: User created"]);
+        let failures = capture::lines_mentioning("User creation failed");
+        assert!(failures.iter().any(|line| line.starts_with("ERROR users: User creation failed: Invalid user data")), "{:?}", failures);
+    }
+}
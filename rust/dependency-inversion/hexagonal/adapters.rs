@@ -0,0 +1,158 @@
+// ADAPTERS - the outside world, behind the driven ports
+//
+// In-memory implementations, enough to run the whole application without a
+// database, payment provider or mail server. A deployment swaps them for
+// adapters over those systems; the domain and application do not change.
+
+use std::cell::{Cell, RefCell};
+use std::collections::BTreeMap;
+
+use crate::domain::{Money, Order, OrderId};
+use crate::ports::{Notifier, OrderRepository, PaymentGateway};
+
+/// Persistence adapter: orders kept in a map, numbered from 1
+#[derive(Debug, Default)]
+pub struct InMemoryOrderRepository {
+    orders: RefCell<BTreeMap<OrderId, Order>>,
+    last_id: Cell<u64>,
+}
+
+impl InMemoryOrderRepository {
+    pub fn new() -> Self {
+        InMemoryOrderRepository::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.orders.borrow().len()
+    }
+}
+
+impl OrderRepository for InMemoryOrderRepository {
+    fn next_id(&self) -> OrderId {
+        self.last_id.set(self.last_id.get() + 1);
+        OrderId(self.last_id.get())
+    }
+
+    fn save(&self, order: &Order) -> Result<(), String> {
+        self.orders.borrow_mut().insert(order.id, order.clone());
+        Ok(())
+    }
+
+    fn find(&self, id: OrderId) -> Option<Order> {
+        self.orders.borrow().get(&id).cloned()
+    }
+}
+
+/// Persistence adapter for a store that is down: every save fails
+#[derive(Debug, Default)]
+pub struct UnavailableOrderRepository {
+    last_id: Cell<u64>,
+}
+
+impl OrderRepository for UnavailableOrderRepository {
+    fn next_id(&self) -> OrderId {
+        self.last_id.set(self.last_id.get() + 1);
+        OrderId(self.last_id.get())
+    }
+
+    fn save(&self, order: &Order) -> Result<(), String> {
+        Err(format!("Order store unavailable while saving {}", order.id))
+    }
+
+    fn find(&self, _id: OrderId) -> Option<Order> {
+        None
+    }
+}
+
+/// A charge taken by InMemoryPaymentGateway
+#[derive(Debug, Clone, PartialEq)]
+pub struct Charge {
+    pub reference: String,
+    pub customer_email: String,
+    pub amount: Money,
+    pub refunded: bool,
+}
+
+/// Payment adapter: customer balances in memory. A charge above the balance
+/// is declined, and unknown customers have a balance of zero.
+#[derive(Debug, Default)]
+pub struct InMemoryPaymentGateway {
+    balances: RefCell<BTreeMap<String, Money>>,
+    charges: RefCell<Vec<Charge>>,
+}
+
+impl InMemoryPaymentGateway {
+    pub fn new() -> Self {
+        InMemoryPaymentGateway::default()
+    }
+
+    pub fn with_balance(self, customer_email: &str, balance: Money) -> Self {
+        self.balances.borrow_mut().insert(customer_email.to_string(), balance);
+        self
+    }
+
+    pub fn balance(&self, customer_email: &str) -> Money {
+        self.balances.borrow().get(customer_email).copied().unwrap_or_default()
+    }
+
+    pub fn charges(&self) -> Vec<Charge> {
+        self.charges.borrow().clone()
+    }
+}
+
+impl PaymentGateway for InMemoryPaymentGateway {
+    fn charge(&self, customer_email: &str, amount: Money) -> Result<String, String> {
+        let balance = self.balance(customer_email);
+        let remaining = balance
+            .checked_sub(amount)
+            .ok_or_else(|| format!("{} has {} available, {} requested", customer_email, balance, amount))?;
+        self.balances.borrow_mut().insert(customer_email.to_string(), remaining);
+
+        let mut charges = self.charges.borrow_mut();
+        let reference = format!("PAY-{}", charges.len() + 1);
+        charges.push(Charge {
+            reference: reference.clone(),
+            customer_email: customer_email.to_string(),
+            amount,
+            refunded: false,
+        });
+        Ok(reference)
+    }
+
+    fn refund(&self, reference: &str) -> Result<(), String> {
+        let mut charges = self.charges.borrow_mut();
+        let charge = charges
+            .iter_mut()
+            .find(|charge| charge.reference == reference && !charge.refunded)
+            .ok_or_else(|| format!("No refundable charge {}", reference))?;
+        charge.refunded = true;
+        let balance = self.balance(&charge.customer_email) + charge.amount;
+        self.balances.borrow_mut().insert(charge.customer_email.clone(), balance);
+        Ok(())
+    }
+}
+
+/// Notification adapter: keeps the messages it would have sent
+#[derive(Debug, Default)]
+pub struct InMemoryNotifier {
+    messages: RefCell<Vec<String>>,
+}
+
+impl InMemoryNotifier {
+    pub fn new() -> Self {
+        InMemoryNotifier::default()
+    }
+
+    pub fn messages(&self) -> Vec<String> {
+        self.messages.borrow().clone()
+    }
+}
+
+impl Notifier for InMemoryNotifier {
+    fn order_placed(&self, order: &Order) {
+        self.messages.borrow_mut().push(format!(
+            "To {}: order {} confirmed, {} charged ({})",
+            order.customer_email, order.id, order.quote.total, order.payment_reference
+        ));
+    }
+}
@@ -0,0 +1,138 @@
+// APPLICATION - the order placement use case
+//
+// OrderService runs the domain rules and coordinates the driven ports. It is
+// generic over the ports, so it compiles against the traits alone and never
+// names an adapter.
+
+use crate::domain::{self, Order, OrderError, OrderId, PlaceOrder};
+use crate::ports::{Notifier, OrderRepository, PaymentGateway, PlaceOrderUseCase};
+
+pub struct OrderService<R, P, N> {
+    orders: R,
+    payments: P,
+    notifier: N,
+}
+
+impl<R: OrderRepository, P: PaymentGateway, N: Notifier> OrderService<R, P, N> {
+    pub fn new(orders: R, payments: P, notifier: N) -> Self {
+        OrderService {
+            orders,
+            payments,
+            notifier,
+        }
+    }
+}
+
+impl<R: OrderRepository, P: PaymentGateway, N: Notifier> PlaceOrderUseCase for OrderService<R, P, N> {
+    /// Prices the request, charges the customer, stores the order and then
+    /// notifies the customer. A declined payment stores nothing, and an
+    /// order that cannot be stored has its charge refunded.
+    fn place_order(&self, request: PlaceOrder) -> Result<Order, OrderError> {
+        let quote = domain::quote(&request)?;
+        let payment_reference = self
+            .payments
+            .charge(&request.customer_email, quote.total)
+            .map_err(OrderError::PaymentDeclined)?;
+
+        let order = Order::new(self.orders.next_id(), request, quote, payment_reference);
+        if let Err(e) = self.orders.save(&order) {
+            return Err(match self.payments.refund(&order.payment_reference) {
+                Ok(()) => OrderError::Storage(e),
+                Err(refund) => OrderError::Storage(format!(
+                    "{}; refunding {} also failed: {}",
+                    e, order.payment_reference, refund
+                )),
+            });
+        }
+
+        self.notifier.order_placed(&order);
+        Ok(order)
+    }
+
+    fn find_order(&self, id: OrderId) -> Option<Order> {
+        self.orders.find(id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use super::*;
+    use crate::adapters::{InMemoryNotifier, InMemoryOrderRepository, InMemoryPaymentGateway, UnavailableOrderRepository};
+    use crate::domain::Money;
+
+    fn payments() -> Rc<InMemoryPaymentGateway> {
+        Rc::new(InMemoryPaymentGateway::new().with_balance("alice@example.com", Money::from_cents(20000)))
+    }
+
+    fn widgets(customer_email: &str) -> PlaceOrder {
+        PlaceOrder::new(customer_email)
+            .with_line("widget", Money::from_cents(1250), 2)
+            .with_line("cable", Money::from_cents(400), 1)
+    }
+
+    #[test]
+    fn a_paid_order_is_stored_and_notified() {
+        let repository = Rc::new(InMemoryOrderRepository::new());
+        let payments = payments();
+        let notifier = Rc::new(InMemoryNotifier::new());
+        let service = OrderService::new(Rc::clone(&repository), Rc::clone(&payments), Rc::clone(&notifier));
+        let use_case: &dyn PlaceOrderUseCase = &service;
+
+        let order = use_case.place_order(widgets("alice@example.com")).unwrap();
+        assert_eq!(order.quote.total, Money::from_cents(2900 + 499));
+        assert_eq!(use_case.find_order(order.id), Some(order.clone()));
+        assert_eq!(payments.balance("alice@example.com"), Money::from_cents(20000 - 3399));
+        assert_eq!(notifier.messages().len(), 1);
+        assert!(notifier.messages()[0].contains(&order.payment_reference));
+    }
+
+    #[test]
+    fn a_declined_payment_stores_and_sends_nothing() {
+        let repository = Rc::new(InMemoryOrderRepository::new());
+        let notifier = Rc::new(InMemoryNotifier::new());
+        let service = OrderService::new(Rc::clone(&repository), payments(), Rc::clone(&notifier));
+
+        let error = service
+            .place_order(PlaceOrder::new("alice@example.com").with_line("desk", Money::from_cents(30000), 1))
+            .unwrap_err();
+        assert!(matches!(error, OrderError::PaymentDeclined(_)), "{:?}", error);
+        assert_eq!(repository.len(), 0);
+        assert!(notifier.messages().is_empty());
+    }
+
+    #[test]
+    fn an_order_the_store_refuses_is_refunded() {
+        let payments = payments();
+        let notifier = Rc::new(InMemoryNotifier::new());
+        let service = OrderService::new(UnavailableOrderRepository::default(), Rc::clone(&payments), Rc::clone(&notifier));
+
+        let error = service.place_order(widgets("alice@example.com")).unwrap_err();
+        assert!(matches!(&error, OrderError::Storage(reason) if reason.starts_with("Order store unavailable")), "{:?}", error);
+        let charges = payments.charges();
+        assert_eq!(charges.len(), 1);
+        assert!(charges[0].refunded);
+        assert_eq!(payments.balance("alice@example.com"), Money::from_cents(20000));
+        assert!(notifier.messages().is_empty());
+    }
+
+    #[test]
+    fn invalid_requests_are_rejected_before_charging() {
+        let payments = payments();
+        let service = OrderService::new(InMemoryOrderRepository::new(), Rc::clone(&payments), InMemoryNotifier::new());
+
+        let cases = [
+            (widgets("alice"), OrderError::InvalidEmail("alice".to_string())),
+            (PlaceOrder::new("alice@example.com"), OrderError::EmptyOrder),
+            (
+                widgets("alice@example.com").with_line("mouse", Money::from_cents(2500), 0),
+                OrderError::ZeroQuantity { sku: "mouse".to_string() },
+            ),
+        ];
+        for (request, expected) in cases {
+            assert_eq!(service.place_order(request), Err(expected));
+        }
+        assert!(payments.charges().is_empty());
+    }
+}
@@ -0,0 +1,172 @@
+// DOMAIN - order placement rules
+//
+// Plain data and pure functions. Nothing here knows about storage, payment
+// providers or email; the application reaches those through the ports.
+
+use std::fmt;
+use std::ops::Add;
+
+/// An amount of money, in cents
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub struct Money(u64);
+
+impl Money {
+    pub const fn from_cents(cents: u64) -> Self {
+        Money(cents)
+    }
+
+    pub fn times(self, quantity: u32) -> Money {
+        Money(self.0 * quantity as u64)
+    }
+
+    /// `self - other`, or `None` if `other` is larger
+    pub fn checked_sub(self, other: Money) -> Option<Money> {
+        self.0.checked_sub(other.0).map(Money)
+    }
+}
+
+impl Add for Money {
+    type Output = Money;
+
+    fn add(self, other: Money) -> Money {
+        Money(self.0 + other.0)
+    }
+}
+
+impl fmt::Display for Money {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "${}.{:02}", self.0 / 100, self.0 % 100)
+    }
+}
+
+/// Orders at or above this subtotal ship free
+pub const FREE_SHIPPING_FROM: Money = Money::from_cents(5000);
+/// Flat shipping charge below the free shipping threshold
+pub const SHIPPING: Money = Money::from_cents(499);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct OrderId(pub u64);
+
+impl fmt::Display for OrderId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "#{}", self.0)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct OrderLine {
+    pub sku: String,
+    pub unit_price: Money,
+    pub quantity: u32,
+}
+
+impl OrderLine {
+    pub fn new(sku: &str, unit_price: Money, quantity: u32) -> Self {
+        OrderLine {
+            sku: sku.to_string(),
+            unit_price,
+            quantity,
+        }
+    }
+
+    pub fn total(&self) -> Money {
+        self.unit_price.times(self.quantity)
+    }
+}
+
+/// What a customer asks for; it becomes an Order once it has been paid for
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlaceOrder {
+    pub customer_email: String,
+    pub lines: Vec<OrderLine>,
+}
+
+impl PlaceOrder {
+    pub fn new(customer_email: &str) -> Self {
+        PlaceOrder {
+            customer_email: customer_email.to_string(),
+            lines: Vec::new(),
+        }
+    }
+
+    pub fn with_line(mut self, sku: &str, unit_price: Money, quantity: u32) -> Self {
+        self.lines.push(OrderLine::new(sku, unit_price, quantity));
+        self
+    }
+}
+
+/// The price of a valid request
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Quote {
+    pub subtotal: Money,
+    pub shipping: Money,
+    pub total: Money,
+}
+
+/// Checks a request and prices it, including shipping
+pub fn quote(request: &PlaceOrder) -> Result<Quote, OrderError> {
+    if !request.customer_email.contains('@') {
+        return Err(OrderError::InvalidEmail(request.customer_email.clone()));
+    }
+    if request.lines.is_empty() {
+        return Err(OrderError::EmptyOrder);
+    }
+    if let Some(line) = request.lines.iter().find(|line| line.quantity == 0) {
+        return Err(OrderError::ZeroQuantity { sku: line.sku.clone() });
+    }
+
+    let subtotal = request.lines.iter().fold(Money::default(), |total, line| total + line.total());
+    let shipping = if subtotal >= FREE_SHIPPING_FROM { Money::default() } else { SHIPPING };
+    Ok(Quote {
+        subtotal,
+        shipping,
+        total: subtotal + shipping,
+    })
+}
+
+/// A placed, paid-for order
+#[derive(Debug, Clone, PartialEq)]
+pub struct Order {
+    pub id: OrderId,
+    pub customer_email: String,
+    pub lines: Vec<OrderLine>,
+    pub quote: Quote,
+    /// The payment provider's reference for the charge
+    pub payment_reference: String,
+}
+
+impl Order {
+    pub fn new(id: OrderId, request: PlaceOrder, quote: Quote, payment_reference: String) -> Self {
+        Order {
+            id,
+            customer_email: request.customer_email,
+            lines: request.lines,
+            quote,
+            payment_reference,
+        }
+    }
+}
+
+/// Why an order was not placed
+#[derive(Debug, Clone, PartialEq)]
+pub enum OrderError {
+    InvalidEmail(String),
+    EmptyOrder,
+    ZeroQuantity { sku: String },
+    /// The payment port refused the charge
+    PaymentDeclined(String),
+    /// The order could not be stored; any charge has been refunded
+    Storage(String),
+}
+
+impl fmt::Display for OrderError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            OrderError::InvalidEmail(email) => write!(f, "Invalid customer email: {}", email),
+            OrderError::EmptyOrder => write!(f, "An order needs at least one line"),
+            OrderError::ZeroQuantity { sku } => write!(f, "Quantity of {} must be at least 1", sku),
+            OrderError::PaymentDeclined(reason) => write!(f, "Payment declined: {}", reason),
+            OrderError::Storage(reason) => write!(f, "Order not stored: {}", reason),
+        }
+    }
+}
@@ -0,0 +1,96 @@
+// DEPENDENCY INVERSION AT ARCHITECTURE SCALE - HEXAGONAL ARCHITECTURE
+//
+// Ports and adapters: the application core depends on nothing outside it,
+// and everything outside depends on the core's ports.
+// - domain.rs: order placement rules, pure data and functions
+// - ports.rs: the traits the core offers (driving) and needs (driven)
+// - application.rs: OrderService, the use case, written against the ports
+// - adapters.rs: in-memory persistence, payment and notification
+// - main.rs: the composition root, the only place that names every adapter
+//
+// Swapping the in-memory adapters for a database, a payment provider or a
+// mail server means writing new adapters; domain, ports and application stay
+// as they are.
+
+mod adapters;
+mod application;
+mod domain;
+mod ports;
+
+use std::rc::Rc;
+
+use adapters::{InMemoryNotifier, InMemoryOrderRepository, InMemoryPaymentGateway, UnavailableOrderRepository};
+use application::OrderService;
+use domain::{Money, PlaceOrder};
+use ports::PlaceOrderUseCase;
+
+/// A driving adapter would parse a request and call this; it sees only the port
+fn checkout(orders: &dyn PlaceOrderUseCase, request: PlaceOrder) {
+    let customer = request.customer_email.clone();
+    match orders.place_order(request) {
+        Ok(order) => {
+            println!(
+                "Placed {} for {}: subtotal {}, shipping {}, total {}",
+                order.id, customer, order.quote.subtotal, order.quote.shipping, order.quote.total
+            );
+            if let Some(stored) = orders.find_order(order.id) {
+                println!("  Stored with payment {}", stored.payment_reference);
+            }
+        }
+        Err(e) => println!("Rejected order for {}: {}", customer, e),
+    }
+}
+
+/// Example usage: the application wired to in-memory adapters
+pub fn demonstrate_hexagonal() {
+    println!("=== HEXAGONAL ARCHITECTURE (PORTS AND ADAPTERS) ===");
+    println!("OrderService depends on ports; main plugs the adapters in");
+    println!();
+
+    let repository = Rc::new(InMemoryOrderRepository::new());
+    let payments = Rc::new(
+        InMemoryPaymentGateway::new()
+            .with_balance("alice@example.com", Money::from_cents(20000))
+            .with_balance("bob@example.com", Money::from_cents(1000)),
+    );
+    let notifier = Rc::new(InMemoryNotifier::new());
+    let service = OrderService::new(Rc::clone(&repository), Rc::clone(&payments), Rc::clone(&notifier));
+
+    checkout(
+        &service,
+        PlaceOrder::new("alice@example.com")
+            .with_line("widget", Money::from_cents(1250), 2)
+            .with_line("cable", Money::from_cents(400), 1),
+    );
+    checkout(&service, PlaceOrder::new("alice@example.com").with_line("keyboard", Money::from_cents(6000), 1));
+    checkout(&service, PlaceOrder::new("bob@example.com").with_line("keyboard", Money::from_cents(6000), 1));
+    checkout(&service, PlaceOrder::new("bob@example.com"));
+    checkout(&service, PlaceOrder::new("carol").with_line("widget", Money::from_cents(1250), 1));
+    println!();
+
+    // Same core, a failing persistence adapter: the charge is refunded
+    let outage = OrderService::new(UnavailableOrderRepository::default(), Rc::clone(&payments), Rc::clone(&notifier));
+    checkout(&outage, PlaceOrder::new("alice@example.com").with_line("mouse", Money::from_cents(2500), 1));
+    println!();
+
+    println!("Adapters after the run:");
+    println!("  Orders stored: {}", repository.len());
+    for charge in payments.charges() {
+        let status = if charge.refunded { " (refunded)" } else { "" };
+        println!("  {} {} {}{}", charge.reference, charge.customer_email, charge.amount, status);
+    }
+    println!("  alice's balance: {}", payments.balance("alice@example.com"));
+    for message in notifier.messages() {
+        println!("  Sent: {}", message);
+    }
+
+    println!();
+    println!("Benefits of this approach:");
+    println!("1. The domain and use case compile without any adapter");
+    println!("2. Adapters are swapped in main, e.g. an outage store to test the refund path");
+    println!("3. Tests drive the core through PlaceOrderUseCase with in-memory adapters");
+}
+
+fn main() {
+    demonstrate_hexagonal();
+}
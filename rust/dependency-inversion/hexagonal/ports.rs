@@ -0,0 +1,69 @@
+// PORTS - the application's boundary, as traits
+//
+// The driving port is what the application offers; a CLI, an HTTP handler or
+// a test calls it. Driven ports are what the application needs from the
+// outside world; adapters implement them. Ports speak in domain types only,
+// so the dependency arrows all point inwards.
+
+use std::rc::Rc;
+
+use crate::domain::{Money, Order, OrderError, OrderId, PlaceOrder};
+
+/// Driving port: placing and looking up orders
+pub trait PlaceOrderUseCase {
+    fn place_order(&self, request: PlaceOrder) -> Result<Order, OrderError>;
+    fn find_order(&self, id: OrderId) -> Option<Order>;
+}
+
+/// Driven port: order persistence
+pub trait OrderRepository {
+    fn next_id(&self) -> OrderId;
+    fn save(&self, order: &Order) -> Result<(), String>;
+    fn find(&self, id: OrderId) -> Option<Order>;
+}
+
+/// Driven port: taking payment
+pub trait PaymentGateway {
+    /// Charges the customer and returns the provider's reference for the charge
+    fn charge(&self, customer_email: &str, amount: Money) -> Result<String, String>;
+    fn refund(&self, reference: &str) -> Result<(), String>;
+}
+
+/// Driven port: telling the customer. Delivery is the adapter's concern, so
+/// a paid order is never failed because a message could not be sent.
+pub trait Notifier {
+    fn order_placed(&self, order: &Order);
+}
+
+// A shared adapter is still an adapter, so the composition root can keep a
+// handle to one it has plugged in
+
+impl<T: OrderRepository + ?Sized> OrderRepository for Rc<T> {
+    fn next_id(&self) -> OrderId {
+        (**self).next_id()
+    }
+
+    fn save(&self, order: &Order) -> Result<(), String> {
+        (**self).save(order)
+    }
+
+    fn find(&self, id: OrderId) -> Option<Order> {
+        (**self).find(id)
+    }
+}
+
+impl<T: PaymentGateway + ?Sized> PaymentGateway for Rc<T> {
+    fn charge(&self, customer_email: &str, amount: Money) -> Result<String, String> {
+        (**self).charge(customer_email, amount)
+    }
+
+    fn refund(&self, reference: &str) -> Result<(), String> {
+        (**self).refund(reference)
+    }
+}
+
+impl<T: Notifier + ?Sized> Notifier for Rc<T> {
+    fn order_placed(&self, order: &Order) {
+        (**self).order_placed(order)
+    }
+}
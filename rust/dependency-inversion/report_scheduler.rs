@@ -0,0 +1,363 @@
+// CORRECT IMPLEMENTATION - FOLLOWS DEPENDENCY INVERSION PRINCIPLE
+// ReportScheduler decides what to send and when. How a message travels is
+// behind the MessageSender trait, implemented by email, Slack and webhook
+// adapters. The scheduler is generic over its sender, so the same code is
+// wired two ways:
+// - statically: ReportScheduler<EmailSender>, resolved and inlined at compile time
+// - dynamically: ReportScheduler<Box<dyn MessageSender>>, chosen at run time
+//   (e.g. from configuration) through the forwarding impl for Box
+
+/// A message ready to be delivered over any channel
+#[derive(Debug, Clone, PartialEq)]
+pub struct Message {
+    /// Channel-specific address: an email address, a Slack channel, ...
+    pub recipient: String,
+    pub subject: String,
+    pub body: String,
+}
+
+/// The abstraction the scheduler depends on
+pub trait MessageSender {
+    /// Human readable channel name, e.g. "email"
+    fn channel(&self) -> &str;
+    fn send(&mut self, message: &Message) -> Result<(), String>;
+}
+
+// A boxed sender is a sender, so ReportScheduler<Box<dyn MessageSender>> works
+impl<S: MessageSender + ?Sized> MessageSender for Box<S> {
+    fn channel(&self) -> &str {
+        (**self).channel()
+    }
+
+    fn send(&mut self, message: &Message) -> Result<(), String> {
+        (**self).send(message)
+    }
+}
+
+/// Low-level adapter: email through an SMTP relay
+#[derive(Debug)]
+pub struct EmailSender {
+    smtp_server: String,
+    from: String,
+}
+
+impl EmailSender {
+    pub fn new(smtp_server: &str, from: &str) -> Self {
+        EmailSender {
+            smtp_server: smtp_server.to_string(),
+            from: from.to_string(),
+        }
+    }
+}
+
+impl MessageSender for EmailSender {
+    fn channel(&self) -> &str {
+        "email"
+    }
+
+    fn send(&mut self, message: &Message) -> Result<(), String> {
+        if !message.recipient.contains('@') {
+            return Err(format!("Not an email address: {}", message.recipient));
+        }
+        println!(
+            "SMTP {} | From: {} | To: {} | Subject: {}",
+            self.smtp_server, self.from, message.recipient, message.subject
+        );
+        Ok(())
+    }
+}
+
+/// Low-level adapter: a Slack channel or user, through a bot token
+#[derive(Debug)]
+pub struct SlackSender {
+    workspace: String,
+}
+
+impl SlackSender {
+    pub fn new(workspace: &str) -> Self {
+        SlackSender {
+            workspace: workspace.to_string(),
+        }
+    }
+}
+
+impl MessageSender for SlackSender {
+    fn channel(&self) -> &str {
+        "slack"
+    }
+
+    fn send(&mut self, message: &Message) -> Result<(), String> {
+        if !message.recipient.starts_with(['#', '@']) {
+            return Err(format!("Slack recipients start with # or @, got {}", message.recipient));
+        }
+        println!(
+            "Slack {} | {} | *{}* {}",
+            self.workspace, message.recipient, message.subject, message.body
+        );
+        Ok(())
+    }
+}
+
+/// Low-level adapter: a JSON POST to an HTTPS endpoint
+#[derive(Debug)]
+pub struct WebhookSender {
+    url: String,
+}
+
+impl WebhookSender {
+    pub fn new(url: &str) -> Result<Self, String> {
+        if !url.starts_with("https://") {
+            return Err(format!("Webhook URL must use https: {}", url));
+        }
+        Ok(WebhookSender { url: url.to_string() })
+    }
+}
+
+impl MessageSender for WebhookSender {
+    fn channel(&self) -> &str {
+        "webhook"
+    }
+
+    fn send(&mut self, message: &Message) -> Result<(), String> {
+        println!(
+            "POST {} | {{\"to\": {:?}, \"subject\": {:?}, \"body\": {:?}}}",
+            self.url, message.recipient, message.subject, message.body
+        );
+        Ok(())
+    }
+}
+
+/// Runtime wiring: a sender picked by name, as a configuration file would
+pub fn sender_from_config(kind: &str) -> Result<Box<dyn MessageSender>, String> {
+    match kind {
+        "email" => Ok(Box::new(EmailSender::new("smtp.company.com", "reports@company.com"))),
+        "slack" => Ok(Box::new(SlackSender::new("company"))),
+        "webhook" => Ok(Box::new(WebhookSender::new("https://hooks.company.com/reports")?)),
+        other => Err(format!("Unknown sender: {} (known: email, slack, webhook)", other)),
+    }
+}
+
+/// A report sent every `every_minutes`, with its body built at send time
+pub struct ScheduledReport {
+    name: String,
+    recipient: String,
+    every_minutes: u64,
+    next_due: u64,
+    body: Box<dyn Fn(u64) -> String>,
+}
+
+/// What one `run_due` call did
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RunSummary {
+    /// Names of the reports sent
+    pub sent: Vec<String>,
+    /// (report name, error) of the reports that failed; they are retried on the next run
+    pub failed: Vec<(String, String)>,
+}
+
+/// High-level policy: which reports are due and what they say. It never
+/// names a concrete sender.
+pub struct ReportScheduler<S: MessageSender> {
+    sender: S,
+    reports: Vec<ScheduledReport>,
+}
+
+impl<S: MessageSender> ReportScheduler<S> {
+    pub fn new(sender: S) -> Self {
+        ReportScheduler {
+            sender,
+            reports: Vec::new(),
+        }
+    }
+
+    /// Adds a report, first due at minute 0. `body` receives the minute the
+    /// report is sent at.
+    pub fn schedule(
+        &mut self,
+        name: &str,
+        recipient: &str,
+        every_minutes: u64,
+        body: impl Fn(u64) -> String + 'static,
+    ) -> Result<(), String> {
+        if every_minutes == 0 {
+            return Err(format!("Report {} needs an interval of at least one minute", name));
+        }
+        self.reports.push(ScheduledReport {
+            name: name.to_string(),
+            recipient: recipient.to_string(),
+            every_minutes,
+            next_due: 0,
+            body: Box::new(body),
+        });
+        Ok(())
+    }
+
+    /// Sends every report due at minute `now`. A report that fell several
+    /// intervals behind is sent once; a failed one stays due.
+    pub fn run_due(&mut self, now: u64) -> RunSummary {
+        let mut summary = RunSummary::default();
+        for report in self.reports.iter_mut().filter(|report| report.next_due <= now) {
+            let message = Message {
+                recipient: report.recipient.clone(),
+                subject: format!("{} (minute {})", report.name, now),
+                body: (report.body)(now),
+            };
+            match self.sender.send(&message) {
+                Ok(()) => {
+                    while report.next_due <= now {
+                        report.next_due += report.every_minutes;
+                    }
+                    summary.sent.push(report.name.clone());
+                }
+                Err(e) => summary.failed.push((report.name.clone(), e)),
+            }
+        }
+        summary
+    }
+
+    pub fn sender(&self) -> &S {
+        &self.sender
+    }
+}
+
+/// Example usage demonstrating DIP compliance
+pub fn demonstrate_dip_compliance() {
+    println!("=== DIP COMPLIANT EXAMPLE (REPORT SCHEDULER) ===");
+    println!("ReportScheduler depends on MessageSender, not on any channel");
+    println!();
+
+    println!("Compile-time wiring: ReportScheduler<EmailSender>");
+    let mut scheduler = ReportScheduler::new(EmailSender::new("smtp.company.com", "reports@company.com"));
+    let scheduled = scheduler
+        .schedule("Hourly sales", "sales@company.com", 60, |minute| format!("{} orders so far", minute * 3))
+        .and_then(|()| scheduler.schedule("Daily summary", "ceo@company.com", 24 * 60, |_| "All systems normal".to_string()));
+    if let Err(e) = scheduled {
+        println!("Error: {}", e);
+    }
+    for now in [0, 30, 60, 125] {
+        let summary = scheduler.run_due(now);
+        println!("  minute {}: sent {:?}", now, summary.sent);
+    }
+    println!();
+
+    println!("Runtime wiring: ReportScheduler<Box<dyn MessageSender>>");
+    for kind in ["email", "slack", "webhook", "fax"] {
+        let sender = match sender_from_config(kind) {
+            Ok(sender) => sender,
+            Err(e) => {
+                println!("  Error: {}", e);
+                continue;
+            }
+        };
+        let mut scheduler = ReportScheduler::new(sender);
+        let recipient = if kind == "slack" { "#ops" } else { "ops@company.com" };
+        if let Err(e) = scheduler.schedule("Error rate", recipient, 15, |_| "0.2% of requests failed".to_string()) {
+            println!("  Error: {}", e);
+        }
+        let summary = scheduler.run_due(0);
+        println!("  via {}: sent {:?}, failed {:?}", scheduler.sender().channel(), summary.sent, summary.failed);
+    }
+
+    println!();
+    println!("Benefits of this approach:");
+    println!("1. New channels implement MessageSender; ReportScheduler never changes");
+    println!("2. The same scheduler is wired statically (generics) or at run time (Box<dyn>)");
+    println!("3. Tests substitute a spy sender - no mail server needed");
+}
+
+fn main() {
+    demonstrate_dip_compliance();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    /// Records every message, and refuses those to `failing_recipient`
+    #[derive(Default)]
+    struct SpySender {
+        sent: Rc<RefCell<Vec<Message>>>,
+        failing_recipient: Option<String>,
+    }
+
+    impl MessageSender for SpySender {
+        fn channel(&self) -> &str {
+            "spy"
+        }
+
+        fn send(&mut self, message: &Message) -> Result<(), String> {
+            if self.failing_recipient.as_deref() == Some(message.recipient.as_str()) {
+                return Err(format!("{} is unreachable", message.recipient));
+            }
+            self.sent.borrow_mut().push(message.clone());
+            Ok(())
+        }
+    }
+
+    fn subjects(sent: &RefCell<Vec<Message>>) -> Vec<String> {
+        sent.borrow().iter().map(|message| message.subject.clone()).collect()
+    }
+
+    #[test]
+    fn static_wiring_sends_each_report_when_due() {
+        let sent = Rc::new(RefCell::new(Vec::new()));
+        let mut scheduler = ReportScheduler::new(SpySender {
+            sent: Rc::clone(&sent),
+            ..SpySender::default()
+        });
+        scheduler.schedule("Hourly", "sales@company.com", 60, |minute| format!("{} orders", minute * 3)).unwrap();
+        scheduler.schedule("Daily", "ceo@company.com", 24 * 60, |_| "All normal".to_string()).unwrap();
+
+        assert_eq!(scheduler.run_due(0).sent, ["Hourly", "Daily"]);
+        assert!(scheduler.run_due(30).sent.is_empty());
+        assert_eq!(scheduler.run_due(60).sent, ["Hourly"]);
+        // Two intervals behind: sent once, then due again at 180
+        assert_eq!(scheduler.run_due(125).sent, ["Hourly"]);
+        assert!(scheduler.run_due(179).sent.is_empty());
+
+        assert_eq!(
+            subjects(&sent),
+            ["Hourly (minute 0)", "Daily (minute 0)", "Hourly (minute 60)", "Hourly (minute 125)"]
+        );
+        let last = sent.borrow().last().cloned().unwrap();
+        assert_eq!((last.recipient.as_str(), last.body.as_str()), ("sales@company.com", "375 orders"));
+        assert_eq!(scheduler.sender().channel(), "spy");
+    }
+
+    #[test]
+    fn boxed_wiring_retries_a_failed_report() {
+        let sent = Rc::new(RefCell::new(Vec::new()));
+        let sender: Box<dyn MessageSender> = Box::new(SpySender {
+            sent: Rc::clone(&sent),
+            failing_recipient: Some("#down".to_string()),
+        });
+        let mut scheduler = ReportScheduler::new(sender);
+        scheduler.schedule("Errors", "#down", 15, |_| "0.2%".to_string()).unwrap();
+        scheduler.schedule("Latency", "#ops", 15, |_| "120ms".to_string()).unwrap();
+
+        let summary = scheduler.run_due(0);
+        assert_eq!(summary.sent, ["Latency"]);
+        assert_eq!(summary.failed, [("Errors".to_string(), "#down is unreachable".to_string())]);
+        // The failed report stays due; the sent one waits for its interval
+        let summary = scheduler.run_due(5);
+        assert!(summary.sent.is_empty());
+        assert_eq!(summary.failed.len(), 1);
+        assert_eq!(subjects(&sent), ["Latency (minute 0)"]);
+        assert_eq!(scheduler.sender().channel(), "spy");
+    }
+
+    #[test]
+    fn zero_interval_and_unknown_senders_are_refused() {
+        let mut scheduler = ReportScheduler::new(SpySender::default());
+        assert_eq!(
+            scheduler.schedule("Spam", "a@b.c", 0, |_| String::new()),
+            Err("Report Spam needs an interval of at least one minute".to_string())
+        );
+        assert!(scheduler.run_due(0).sent.is_empty());
+
+        assert_eq!(sender_from_config("slack").unwrap().channel(), "slack");
+        assert!(sender_from_config("fax").is_err());
+    }
+}
@@ -0,0 +1,116 @@
+// VIOLATION OF DEPENDENCY INVERSION PRINCIPLE
+// This code violates DIP because:
+// 1. ReportSchedulerViolation constructs its SmtpClient itself, in new()
+// 2. The high-level scheduling policy depends directly on a low-level SMTP detail
+// 3. Sending to Slack or a webhook, or testing without a mail server, means editing the scheduler
+
+/// Low-level detail: a concrete SMTP client
+#[derive(Debug)]
+pub struct SmtpClient {
+    host: String,
+    port: u16,
+}
+
+impl SmtpClient {
+    pub fn new(host: &str, port: u16) -> Self {
+        SmtpClient {
+            host: host.to_string(),
+            port,
+        }
+    }
+
+    pub fn send_mail(&self, from: &str, to: &str, subject: &str, body: &str) -> Result<(), String> {
+        println!("SMTP {}:{} | From: {} | To: {} | Subject: {} | {}", self.host, self.port, from, to, subject, body);
+        Ok(())
+    }
+}
+
+/// A report sent every `every_minutes`
+pub struct ScheduledReportViolation {
+    pub name: String,
+    pub recipient: String,
+    pub every_minutes: u64,
+    pub next_due: u64,
+    pub body: fn(u64) -> String,
+}
+
+/// Report scheduler that violates DIP
+pub struct ReportSchedulerViolation {
+    // Violates DIP: a concrete type, not an abstraction
+    smtp: SmtpClient,
+    reports: Vec<ScheduledReportViolation>,
+}
+
+impl ReportSchedulerViolation {
+    /// Violates DIP: the scheduler picks and builds its own dependency, with
+    /// the server address hard-coded
+    pub fn new() -> Self {
+        ReportSchedulerViolation {
+            smtp: SmtpClient::new("smtp.company.com", 25),
+            reports: Vec::new(),
+        }
+    }
+
+    pub fn schedule(&mut self, name: &str, recipient: &str, every_minutes: u64, body: fn(u64) -> String) {
+        self.reports.push(ScheduledReportViolation {
+            name: name.to_string(),
+            recipient: recipient.to_string(),
+            every_minutes,
+            next_due: 0,
+            body,
+        });
+    }
+
+    /// Sends every report due at minute `now` - always by email
+    pub fn run_due(&mut self, now: u64) -> Vec<String> {
+        let mut sent = Vec::new();
+        for report in self.reports.iter_mut().filter(|report| report.next_due <= now) {
+            let subject = format!("{} (minute {})", report.name, now);
+            // Email formatting and delivery details leak into the policy
+            if self
+                .smtp
+                .send_mail("reports@company.com", &report.recipient, &subject, &(report.body)(now))
+                .is_ok()
+            {
+                while report.next_due <= now {
+                    report.next_due += report.every_minutes;
+                }
+                sent.push(report.name.clone());
+            }
+        }
+        sent
+    }
+}
+
+impl Default for ReportSchedulerViolation {
+    fn default() -> Self {
+        ReportSchedulerViolation::new()
+    }
+}
+
+/// Example usage demonstrating DIP violation
+pub fn demonstrate_dip_violation() {
+    println!("=== DIP VIOLATION EXAMPLE (REPORT SCHEDULER) ===");
+    println!("This code violates the Dependency Inversion Principle");
+    println!("The scheduler creates and depends on a concrete SMTP client");
+    println!();
+
+    let mut scheduler = ReportSchedulerViolation::new();
+    scheduler.schedule("Hourly sales", "sales@company.com", 60, |minute| format!("{} orders so far", minute * 3));
+    // There is no way to send this one to Slack without changing the scheduler
+    scheduler.schedule("Error rate", "#ops", 15, |_| "0.2% of requests failed".to_string());
+    for now in [0, 30, 60] {
+        println!("minute {}: sent {:?}", now, scheduler.run_due(now));
+    }
+
+    println!();
+    println!("Problems with this approach:");
+    println!("1. Slack or webhook delivery requires editing ReportSchedulerViolation");
+    println!("2. The SMTP host is hard-coded in the scheduler's constructor");
+    println!("3. Tests cannot run the scheduler without sending real email");
+    println!("4. \"#ops\" is handed to the SMTP client as an email address");
+}
+
+fn main() {
+    demonstrate_dip_violation();
+}
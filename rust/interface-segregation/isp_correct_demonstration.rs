@@ -9,7 +9,31 @@
  */
 
 use std::fmt;
-use std::collections::HashMap;
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, VecDeque};
+use std::rc::Rc;
+use std::sync::mpsc::{self, Sender};
+use std::thread::{self, JoinHandle};
+
+// Observer pattern over the robots below: telemetry published to a dashboard,
+// an alert monitor and a metrics recorder
+#[path = "patterns/observer.rs"]
+mod observer;
+
+// State pattern for IndustrialRobot's lifecycle. With the `lifecycle` feature it
+// decides which capabilities the robot may use; without it, a bool still does.
+#[cfg_attr(not(feature = "lifecycle"), allow(dead_code))]
+#[path = "patterns/state.rs"]
+mod state;
+
+use state::Activity;
+
+// Factory and abstract factory: Persons and robots configured from
+// specifications, and kits of an operator with a robot that belong together
+#[path = "patterns/factory.rs"]
+mod factory;
+
+use factory::EntityFactory;
 
 // CORRECT: Segregated traits - clients only depend on what they need
 
@@ -33,705 +57,3744 @@ trait Cognitive {
     fn learn(&mut self, skill: &str);
     fn solve_problem(&self, problem: &str) -> String;
     fn get_intelligence_level(&self) -> i32;
+    fn get_skills(&self) -> &SkillGraph;
 }
 
 // Social capabilities trait
 trait Social {
     fn socialize(&mut self);
     fn communicate(&self, message: &str) -> String;
-    fn form_relationships(&mut self) -> Result<(), String>;
-}
-
-// Physical capabilities trait
-trait Physical {
-    fn exercise(&mut self) -> Result<(), String>;
-    fn feel_emotions(&mut self) -> Result<(), String>;
-    fn reproduce(&mut self) -> Result<(), String>;
-    fn get_physical_condition(&self) -> String;
-}
-
-// Mechanical operations trait (for robots and machines)
-trait Mechanical {
-    fn power_down(&mut self);
-    fn run_diagnostics(&self) -> String;
-    fn perform_maintenance(&mut self) -> Result<(), String>;
-    fn get_mechanical_status(&self) -> String;
-}
-
-// Power management trait (for electronic devices)
-trait Rechargeable {
-    fn recharge(&mut self);
-    fn get_battery_level(&self) -> i32;
-    fn is_charging(&self) -> bool;
-}
-
-// Programmable behavior trait
-trait Programmable {
-    fn execute_program(&self, program: &str) -> Result<(), String>;
-    fn install_software(&mut self, software: &str) -> Result<(), String>;
-    fn get_installed_programs(&self) -> Vec<String>;
+    fn form_relationships(&mut self, other: &str, kind: RelationshipKind) -> Result<(), String>;
+    fn get_relationships(&self) -> &RelationshipGraph;
+    fn get_social_name(&self) -> String;
 }
 
-// Network connectivity trait
-trait NetworkEnabled {
-    fn connect_to_network(&mut self, network: &str) -> Result<(), String>;
-    fn send_data(&self, data: &str) -> Result<(), String>;
-    fn receive_data(&mut self) -> Result<String, String>;
-    fn get_network_status(&self) -> String;
-}
-
-// Human implementation - only implements relevant traits
-#[derive(Debug)]
-struct Person {
+// A skill with its proficiency (0-100) and the skills it builds on
+#[derive(Debug, Clone, PartialEq)]
+struct Skill {
     name: String,
-    energy: i32,
-    is_awake: bool,
-    skills: Vec<String>,
-    intelligence: i32,
-    health: String,
-    relationships: Vec<String>,
+    proficiency: u8,
+    prerequisites: Vec<String>,
 }
 
-impl Person {
-    fn new(name: String) -> Self {
-        Person {
-            name,
-            energy: 100,
-            is_awake: true,
-            skills: vec!["Basic Communication".to_string()],
-            intelligence: 100,
-            health: "Healthy".to_string(),
-            relationships: vec![],
-        }
-    }
+// Skills known by a Cognitive entity; prerequisite edges always point at known skills
+#[derive(Debug, Clone, Default)]
+struct SkillGraph {
+    skills: HashMap<String, Skill>,
 }
 
-impl Workable for Person {
-    fn work(&mut self) {
-        if !self.is_awake {
-            println!("{} cannot work while sleeping!", self.name);
-            return;
-        }
-        println!("{} is working with human creativity", self.name);
-        self.energy -= 20;
-    }
+impl SkillGraph {
+    // Proficiency gap at which a partner's skill is worth learning from them
+    const MENTORING_GAP: u8 = 20;
     
-    fn get_work_efficiency(&self) -> f32 {
-        if self.is_awake { self.energy as f32 / 100.0 } else { 0.0 }
-    }
-}
-
-impl Biological for Person {
-    fn eat(&mut self) -> Result<(), String> {
-        println!("{} is eating delicious food", self.name);
-        self.energy = (self.energy + 30).min(100);
-        Ok(())
+    fn new() -> Self {
+        SkillGraph::default()
     }
     
-    fn sleep(&mut self) -> Result<(), String> {
-        println!("{} is sleeping peacefully", self.name);
-        self.is_awake = false;
-        self.energy = 100;
-        Ok(())
+    fn with_skill(mut self, name: &str, proficiency: u8, prerequisites: &[&str]) -> Self {
+        self.add_skill(name, proficiency, prerequisites)
+            .expect("skill prerequisites must be added first");
+        self
     }
     
-    fn breathe(&mut self) -> Result<(), String> {
-        println!("{} is breathing fresh air", self.name);
+    fn add_skill(&mut self, name: &str, proficiency: u8, prerequisites: &[&str]) -> Result<(), String> {
+        if let Some(missing) = prerequisites.iter().find(|p| !self.skills.contains_key(**p)) {
+            return Err(format!("Cannot add {}: missing prerequisite {}", name, missing));
+        }
+        self.skills.insert(name.to_string(), Skill {
+            name: name.to_string(),
+            proficiency: proficiency.min(100),
+            prerequisites: prerequisites.iter().map(|p| p.to_string()).collect(),
+        });
         Ok(())
     }
     
-    fn get_health_status(&self) -> String {
-        self.health.clone()
-    }
-}
-
-impl Cognitive for Person {
-    fn think(&mut self) {
-        println!("{} is thinking creatively and emotionally", self.name);
-        self.energy -= 5;
+    // Practising an unknown skill adds it without prerequisites
+    fn practice(&mut self, name: &str, amount: u8) -> u8 {
+        let skill = self.skills.entry(name.to_string()).or_insert_with(|| Skill {
+            name: name.to_string(),
+            proficiency: 0,
+            prerequisites: vec![],
+        });
+        skill.proficiency = skill.proficiency.saturating_add(amount).min(100);
+        skill.proficiency
     }
     
-    fn learn(&mut self, skill: &str) {
-        println!("{} is learning: {}", self.name, skill);
-        self.skills.push(skill.to_string());
-        self.intelligence += 2;
+    fn proficiency(&self, name: &str) -> u8 {
+        self.skills.get(name).map(|skill| skill.proficiency).unwrap_or(0)
     }
     
-    fn solve_problem(&self, problem: &str) -> String {
-        format!("{} is solving '{}' using human intuition and experience", self.name, problem)
+    fn has_skill(&self, name: &str) -> bool {
+        self.skills.contains_key(name)
     }
     
-    fn get_intelligence_level(&self) -> i32 {
-        self.intelligence
+    // Prerequisites first (depth-first), ending with the skill itself
+    fn learning_path(&self, name: &str) -> Vec<String> {
+        let mut path = Vec::new();
+        self.collect_path(name, &mut path);
+        path
     }
-}
-
-impl Social for Person {
-    fn socialize(&mut self) {
-        println!("{} is socializing and building relationships", self.name);
-        self.energy -= 10;
+    
+    fn collect_path(&self, name: &str, path: &mut Vec<String>) {
+        if path.iter().any(|known| known == name) {
+            return;
+        }
+        if let Some(skill) = self.skills.get(name) {
+            for prerequisite in &skill.prerequisites {
+                self.collect_path(prerequisite, path);
+            }
+        }
+        path.push(name.to_string());
     }
     
-    fn communicate(&self, message: &str) -> String {
-        format!("{} says: '{}'", self.name, message)
+    // Skills where the partner is clearly stronger than us
+    fn complementary_skills(&self, partner: &SkillGraph) -> Vec<String> {
+        let mut skills: Vec<String> = partner.skills.values()
+            .filter(|skill| skill.proficiency >= self.proficiency(&skill.name).saturating_add(Self::MENTORING_GAP))
+            .map(|skill| skill.name.clone())
+            .collect();
+        skills.sort();
+        skills
     }
     
-    fn form_relationships(&mut self) -> Result<(), String> {
-        println!("{} is forming meaningful relationships", self.name);
-        self.relationships.push("New Friend".to_string());
-        Ok(())
+    fn skill_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.skills.keys().cloned().collect();
+        names.sort();
+        names
     }
 }
 
-impl Physical for Person {
-    fn exercise(&mut self) -> Result<(), String> {
-        println!("{} is doing physical exercise", self.name);
-        self.energy -= 15;
-        self.health = "Excellent".to_string();
-        Ok(())
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RelationshipKind {
+    Friend,
+    Colleague,
+    Mentor,
+    Mentee,
+}
+
+// Directed, typed edge between two social entities
+#[derive(Debug, Clone, PartialEq)]
+struct Relationship {
+    from: String,
+    to: String,
+    kind: RelationshipKind,
+}
+
+#[derive(Debug, Clone, Default)]
+struct RelationshipGraph {
+    edges: Vec<Relationship>,
+}
+
+impl RelationshipGraph {
+    fn new() -> Self {
+        RelationshipGraph::default()
     }
     
-    fn feel_emotions(&mut self) -> Result<(), String> {
-        println!("{} is experiencing complex human emotions", self.name);
+    fn connect(&mut self, from: &str, to: &str, kind: RelationshipKind) -> Result<(), String> {
+        if from == to {
+            return Err(format!("{} cannot form a relationship with itself", from));
+        }
+        if self.kinds_between(from, to).contains(&kind) {
+            return Err(format!("{} is already a {} of {}", to, kind, from));
+        }
+        self.edges.push(Relationship { from: from.to_string(), to: to.to_string(), kind });
         Ok(())
     }
     
-    fn reproduce(&mut self) -> Result<(), String> {
-        println!("{} can participate in human reproduction", self.name);
-        Ok(())
+    fn relationships_of(&self, name: &str) -> Vec<&Relationship> {
+        self.edges.iter().filter(|edge| edge.from == name).collect()
     }
     
-    fn get_physical_condition(&self) -> String {
-        format!("Energy: {}, Health: {}", self.energy, self.health)
+    fn kinds_between(&self, from: &str, to: &str) -> Vec<RelationshipKind> {
+        self.edges.iter()
+            .filter(|edge| edge.from == from && edge.to == to)
+            .map(|edge| edge.kind)
+            .collect()
+    }
+    
+    fn len(&self) -> usize {
+        self.edges.len()
     }
 }
 
-// Robot implementation - only implements relevant traits
-#[derive(Debug)]
-struct IndustrialRobot {
-    model: String,
-    battery_level: i32,
-    is_operational: bool,
-    programs: Vec<String>,
-    network_status: String,
-    is_charging: bool,
+impl fmt::Display for RelationshipKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let label = match self {
+            RelationshipKind::Friend => "friend",
+            RelationshipKind::Colleague => "colleague",
+            RelationshipKind::Mentor => "mentor",
+            RelationshipKind::Mentee => "mentee",
+        };
+        write!(f, "{}", label)
+    }
 }
 
-impl IndustrialRobot {
-    fn new(model: String) -> Self {
-        IndustrialRobot {
-            model,
-            battery_level: 100,
-            is_operational: true,
-            programs: vec!["Basic Operations".to_string()],
-            network_status: "Disconnected".to_string(),
-            is_charging: false,
-        }
-    }
+// Physical capabilities trait
+trait Physical {
+    fn exercise(&mut self) -> Result<(), String>;
+    fn feel_emotions(&mut self) -> Result<(), String>;
+    fn reproduce(&mut self) -> Result<(), String>;
+    fn get_physical_condition(&self) -> String;
+    fn get_emotional_state(&self) -> &EmotionalState;
 }
 
-impl Workable for IndustrialRobot {
-    fn work(&mut self) {
-        if !self.is_operational {
-            println!("{} is not operational!", self.model);
-            return;
+// Mood (0-100) plus the stressors that pushed it down
+#[derive(Debug, Clone)]
+struct EmotionalState {
+    mood: i32,
+    stressors: Vec<String>,
+}
+
+impl EmotionalState {
+    // Below these moods work efficiency drops
+    const STRESSED_MOOD: i32 = 40;
+    const BURNED_OUT_MOOD: i32 = 20;
+    
+    fn new() -> Self {
+        EmotionalState {
+            mood: 70,
+            stressors: Vec::new(),
         }
-        println!("{} robot is performing precise industrial work", self.model);
-        self.battery_level -= 10;
     }
     
-    fn get_work_efficiency(&self) -> f32 {
-        if self.is_operational { 
-            (self.battery_level as f32 / 100.0) * 1.2  // Robots are more efficient
-        } else { 
-            0.0 
-        }
+    fn add_stressor(&mut self, stressor: &str, impact: i32) {
+        self.mood = (self.mood - impact).max(0);
+        self.stressors.push(stressor.to_string());
     }
-}
-
-impl Cognitive for IndustrialRobot {
-    fn think(&mut self) {
-        println!("{} is processing data and running algorithms", self.model);
-        self.battery_level -= 2;
+    
+    // Raises mood and lets go of the oldest stressor
+    fn recover(&mut self, amount: i32) {
+        self.mood = (self.mood + amount).min(100);
+        if !self.stressors.is_empty() {
+            self.stressors.remove(0);
+        }
     }
     
-    fn learn(&mut self, skill: &str) {
-        println!("{} is updating algorithms for: {}", self.model, skill);
-        self.programs.push(format!("Algorithm: {}", skill));
+    fn get_mood(&self) -> i32 {
+        self.mood
     }
     
-    fn solve_problem(&self, problem: &str) -> String {
-        format!("{} is solving '{}' using computational analysis", self.model, problem)
+    fn get_stressors(&self) -> &[String] {
+        &self.stressors
     }
     
-    fn get_intelligence_level(&self) -> i32 {
-        self.programs.len() as i32 * 25  // Based on installed programs
+    fn efficiency_multiplier(&self) -> f32 {
+        if self.mood < Self::BURNED_OUT_MOOD {
+            0.5
+        } else if self.mood < Self::STRESSED_MOOD {
+            0.75
+        } else {
+            1.0
+        }
     }
 }
 
-impl Mechanical for IndustrialRobot {
-    fn power_down(&mut self) {
-        println!("{} is safely powering down all systems", self.model);
-        self.is_operational = false;
+impl fmt::Display for EmotionalState {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let label = if self.mood < Self::BURNED_OUT_MOOD {
+            "burned out"
+        } else if self.mood < Self::STRESSED_MOOD {
+            "stressed"
+        } else {
+            "content"
+        };
+        write!(f, "Mood {} ({}), stressors: {}", self.mood, label, self.stressors.len())
+    }
+}
+
+// Mechanical operations trait (for robots and machines)
+trait Mechanical {
+    fn power_down(&mut self);
+    fn run_diagnostics(&self) -> String;
+    fn perform_maintenance(&mut self) -> Result<(), String>;
+    fn get_mechanical_status(&self) -> String;
+    fn get_device_id(&self) -> String;
+    fn get_wear_level(&self) -> f32;  // 0.0 = freshly serviced, 1.0 = service overdue
+}
+
+// Point-in-time diagnostics captured by technical support (timestamps are operating hours)
+#[derive(Debug, Clone)]
+struct DiagnosticSnapshot {
+    timestamp: u64,
+    report: String,
+    wear: f32,
+}
+
+// Service history of one Mechanical device
+#[derive(Debug, Clone)]
+struct MaintenanceLog {
+    device_id: String,
+    service_interval: u64,
+    snapshots: Vec<DiagnosticSnapshot>,
+    services: Vec<u64>,
+}
+
+impl MaintenanceLog {
+    fn new(device_id: String, service_interval: u64) -> Self {
+        MaintenanceLog {
+            device_id,
+            service_interval,
+            snapshots: vec![],
+            services: vec![],
+        }
     }
     
-    fn run_diagnostics(&self) -> String {
-        let status = if self.is_operational { "Operational" } else { "Offline" };
-        format!("Robot: {}, Battery: {}%, Status: {}, Programs: {}", 
-                self.model, self.battery_level, status, self.programs.len())
+    fn record_snapshot(&mut self, timestamp: u64, report: String, wear: f32) {
+        self.snapshots.push(DiagnosticSnapshot { timestamp, report, wear });
     }
     
-    fn perform_maintenance(&mut self) -> Result<(), String> {
-        println!("{} is performing self-maintenance", self.model);
-        self.battery_level = (self.battery_level + 10).min(100);
-        Ok(())
+    fn record_service(&mut self, timestamp: u64) {
+        self.services.push(timestamp);
     }
     
-    fn get_mechanical_status(&self) -> String {
-        if self.is_operational { "All systems operational".to_string() } 
-        else { "Systems offline".to_string() }
+    fn last_service(&self) -> Option<u64> {
+        self.services.last().copied()
     }
-}
-
-impl Rechargeable for IndustrialRobot {
-    fn recharge(&mut self) {
-        println!("{} is recharging battery", self.model);
-        self.is_charging = true;
-        self.battery_level = 100;
-        self.is_charging = false;
+    
+    fn snapshots_between(&self, from: u64, to: u64) -> Vec<&DiagnosticSnapshot> {
+        self.snapshots.iter()
+            .filter(|snapshot| snapshot.timestamp >= from && snapshot.timestamp <= to)
+            .collect()
     }
     
-    fn get_battery_level(&self) -> i32 {
-        self.battery_level
+    fn latest_wear(&self) -> f32 {
+        self.snapshots.last().map(|snapshot| snapshot.wear).unwrap_or(0.0)
     }
     
-    fn is_charging(&self) -> bool {
-        self.is_charging
+    // Wear accumulated per operating hour since the last service (which resets wear to zero)
+    fn wear_rate(&self) -> Option<f32> {
+        let since = self.last_service().unwrap_or(0);
+        let last = self.snapshots.last().filter(|snapshot| snapshot.timestamp > since)?;
+        if last.wear <= 0.0 {
+            return None;
+        }
+        Some(last.wear / (last.timestamp - since) as f32)
     }
-}
-
-impl Programmable for IndustrialRobot {
-    fn execute_program(&self, program: &str) -> Result<(), String> {
-        if !self.is_operational {
-            return Err("Robot is not operational".to_string());
+    
+    // Whichever comes first: the scheduled interval or wear reaching 100%
+    fn predict_next_service(&self) -> u64 {
+        let scheduled = self.last_service().unwrap_or(0) + self.service_interval;
+        let Some(last) = self.snapshots.last() else {
+            return scheduled;
+        };
+        match self.wear_rate() {
+            Some(rate) => {
+                let hours_left = ((1.0 - last.wear).max(0.0) / rate).floor() as u64;
+                scheduled.min(last.timestamp + hours_left)
+            }
+            None => scheduled,
         }
-        println!("{} is executing program: {}", self.model, program);
-        Ok(())
     }
+}
+
+// Power management trait (for electronic devices)
+trait Rechargeable {
+    fn start_charging(&mut self, rate: i32) -> Result<(), String>;
+    fn tick_charge(&mut self, minutes: u32) -> ChargingState;
+    fn stop_charging(&mut self) -> ChargingState;
+    fn get_battery_level(&self) -> i32;
+    fn get_charging_state(&self) -> ChargingState;
     
-    fn install_software(&mut self, software: &str) -> Result<(), String> {
-        println!("{} is installing software: {}", self.model, software);
-        self.programs.push(software.to_string());
-        Ok(())
+    fn is_charging(&self) -> bool {
+        matches!(self.get_charging_state(), ChargingState::Charging { .. })
     }
     
-    fn get_installed_programs(&self) -> Vec<String> {
-        self.programs.clone()
+    // Convenience: charge at the standard rate until the battery is full
+    fn recharge(&mut self) {
+        if self.start_charging(STANDARD_CHARGE_RATE).is_ok() {
+            while self.is_charging() {
+                self.tick_charge(1);
+            }
+        }
     }
 }
 
-impl NetworkEnabled for IndustrialRobot {
-    fn connect_to_network(&mut self, network: &str) -> Result<(), String> {
-        println!("{} is connecting to network: {}", self.model, network);
-        self.network_status = format!("Connected to {}", network);
-        Ok(())
+// Percent of battery capacity added per minute on a standard charger
+const STANDARD_CHARGE_RATE: i32 = 5;
+
+// Charging state machine shared by every Rechargeable device
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ChargingState {
+    Idle,
+    Charging { rate: i32 },
+    Full,
+}
+
+impl ChargingState {
+    // Idle and Full follow the battery level unless a charge is in progress
+    fn settle(self, battery_level: i32) -> Self {
+        match self {
+            ChargingState::Charging { .. } => self,
+            _ if battery_level >= 100 => ChargingState::Full,
+            _ => ChargingState::Idle,
+        }
     }
     
-    fn send_data(&self, data: &str) -> Result<(), String> {
-        if self.network_status == "Disconnected" {
-            return Err("Not connected to network".to_string());
+    fn start(self, battery_level: i32, rate: i32) -> Result<Self, String> {
+        if rate <= 0 {
+            return Err(format!("Invalid charge rate: {}%/min", rate));
+        }
+        match self.settle(battery_level) {
+            ChargingState::Idle => Ok(ChargingState::Charging { rate }),
+            ChargingState::Charging { rate: current } => Err(format!("Already charging at {}%/min", current)),
+            ChargingState::Full => Err("Battery is already full".to_string()),
         }
-        println!("{} is sending data: {}", self.model, data);
-        Ok(())
     }
     
-    fn receive_data(&mut self) -> Result<String, String> {
-        if self.network_status == "Disconnected" {
-            return Err("Not connected to network".to_string());
+    // Returns the new state and battery level after charging for the given minutes.
+    // Computed in i64 so a long tick at a high rate cannot overflow.
+    fn tick(self, battery_level: i32, minutes: u32) -> (Self, i32) {
+        match self {
+            ChargingState::Charging { rate } => {
+                let charged = i64::from(battery_level) + i64::from(rate) * i64::from(minutes);
+                let level = charged.clamp(0, 100) as i32;
+                let state = if level >= 100 { ChargingState::Full } else { self };
+                (state, level)
+            }
+            _ => (self.settle(battery_level), battery_level),
         }
-        let data = "Sensor data received";
-        println!("{} received: {}", self.model, data);
-        Ok(data.to_string())
     }
     
-    fn get_network_status(&self) -> String {
-        self.network_status.clone()
+    fn stop(self, battery_level: i32) -> Self {
+        ChargingState::Idle.settle(battery_level)
     }
 }
 
-// Humanoid Robot - extends Robot concept with additional human-like capabilities
-#[derive(Debug)]
-struct HumanoidRobot {
-    model: String,
-    battery_level: i32,
-    is_operational: bool,
-    programs: Vec<String>,
-    network_status: String,
-    is_charging: bool,
-    social_protocols: Vec<String>,
+impl fmt::Display for ChargingState {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ChargingState::Idle => write!(f, "Idle"),
+            ChargingState::Charging { rate } => write!(f, "Charging at {}%/min", rate),
+            ChargingState::Full => write!(f, "Full"),
+        }
+    }
 }
 
-impl HumanoidRobot {
-    fn new(model: String) -> Self {
-        HumanoidRobot {
+// Programmable behavior trait
+trait Programmable {
+    fn execute_program(&self, program: &str) -> Result<(), String>;
+    fn install_software(&mut self, package: SoftwarePackage) -> Result<(), String>;
+    fn rollback_software(&mut self, name: &str) -> Result<SoftwarePackage, String>;
+    fn get_installed_packages(&self) -> Vec<SoftwarePackage>;
+    fn get_installed_programs(&self) -> Vec<String>;
+    fn get_device_profile(&self) -> DeviceProfile;
+}
+
+// Semantic version of a software package
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+struct Version {
+    major: u32,
+    minor: u32,
+    patch: u32,
+}
+
+impl Version {
+    fn new(major: u32, minor: u32, patch: u32) -> Self {
+        Version { major, minor, patch }
+    }
+    
+    // Caret-style compatibility: same major version, at least the required release
+    fn is_compatible_with(&self, required: &Version) -> bool {
+        self.major == required.major && self >= required
+    }
+}
+
+// A dependency on another package, satisfied by any compatible version
+#[derive(Debug, Clone, PartialEq)]
+struct Dependency {
+    name: String,
+    min_version: Version,
+}
+
+impl Dependency {
+    fn is_satisfied_by(&self, package: &SoftwarePackage) -> bool {
+        package.name == self.name && package.version.is_compatible_with(&self.min_version)
+    }
+}
+
+// Installable unit of software with its declared dependencies
+#[derive(Debug, Clone, PartialEq)]
+struct SoftwarePackage {
+    name: String,
+    version: Version,
+    dependencies: Vec<Dependency>,
+    requirements: Vec<Requirement>,
+}
+
+impl SoftwarePackage {
+    fn new(name: &str, version: Version) -> Self {
+        SoftwarePackage {
+            name: name.to_string(),
+            version,
+            dependencies: vec![],
+            requirements: vec![],
+        }
+    }
+    
+    fn with_dependency(mut self, name: &str, min_version: Version) -> Self {
+        self.dependencies.push(Dependency { name: name.to_string(), min_version });
+        self
+    }
+    
+    fn requires(mut self, requirement: Requirement) -> Self {
+        self.requirements.push(requirement);
+        self
+    }
+}
+
+// Condition a target device must meet before a package may be deployed to it
+#[derive(Debug, Clone, PartialEq)]
+enum Requirement {
+    Capability(&'static str),
+    MinBattery(i32),
+}
+
+impl fmt::Display for Requirement {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Requirement::Capability(name) => write!(f, "{}", name),
+            Requirement::MinBattery(level) => write!(f, "battery >= {}%", level),
+        }
+    }
+}
+
+// What a Programmable device reports about itself for deployment policy checks.
+// Capabilities are trait names, so the check never needs the other traits themselves.
+#[derive(Debug, Clone)]
+struct DeviceProfile {
+    capabilities: Vec<&'static str>,
+    battery_level: Option<i32>,  // None for devices without a battery
+}
+
+impl DeviceProfile {
+    fn satisfies(&self, requirement: &Requirement) -> bool {
+        match requirement {
+            Requirement::Capability(name) => self.capabilities.contains(name),
+            Requirement::MinBattery(level) => self.battery_level.is_some_and(|battery| battery >= *level),
+        }
+    }
+    
+    fn unmet(&self, package: &SoftwarePackage) -> Vec<Requirement> {
+        package.requirements.iter().filter(|r| !self.satisfies(r)).cloned().collect()
+    }
+}
+
+// Why ITManager refused or failed a deployment
+#[derive(Debug, Clone, PartialEq)]
+enum DeploymentError {
+    Rejected { package: String, unmet: Vec<Requirement> },
+    Failed(String),
+}
+
+impl fmt::Display for DeploymentError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DeploymentError::Rejected { package, unmet } => {
+                let unmet: Vec<String> = unmet.iter().map(|r| r.to_string()).collect();
+                write!(f, "{} rejected: target lacks {}", package, unmet.join(", "))
+            }
+            DeploymentError::Failed(reason) => write!(f, "{}", reason),
+        }
+    }
+}
+
+impl From<String> for DeploymentError {
+    fn from(reason: String) -> Self {
+        DeploymentError::Failed(reason)
+    }
+}
+
+// Installed packages of a single device plus the versions they replaced,
+// so upgrades can be rolled back without breaking dependent packages
+#[derive(Debug, Default)]
+struct SoftwareInventory {
+    installed: HashMap<String, SoftwarePackage>,
+    history: HashMap<String, Vec<SoftwarePackage>>,
+}
+
+impl SoftwareInventory {
+    fn install(&mut self, package: SoftwarePackage) -> Result<(), String> {
+        if self.installed.get(&package.name) == Some(&package) {
+            return Err(format!("{} is already installed", package));
+        }
+        for dependency in &package.dependencies {
+            match self.installed.get(&dependency.name) {
+                Some(installed) if dependency.is_satisfied_by(installed) => {}
+                Some(installed) => {
+                    return Err(format!("Conflict: {} requires {} but {} is installed",
+                                       package, dependency, installed));
+                }
+                None => return Err(format!("Missing dependency: {} requires {}", package, dependency)),
+            }
+        }
+        self.check_dependents(&package)?;
+        
+        if let Some(previous) = self.installed.insert(package.name.clone(), package) {
+            self.history.entry(previous.name.clone()).or_default().push(previous);
+        }
+        Ok(())
+    }
+    
+    fn rollback(&mut self, name: &str) -> Result<SoftwarePackage, String> {
+        let previous = self.history.get(name)
+            .and_then(|versions| versions.last())
+            .ok_or_else(|| format!("No previous version of {} to roll back to", name))?;
+        self.check_dependents(previous)?;
+        
+        let previous = self.history.get_mut(name).and_then(|versions| versions.pop()).unwrap();
+        self.installed.insert(name.to_string(), previous.clone());
+        Ok(previous)
+    }
+    
+    // Replacing a package must not break anything that already depends on it
+    fn check_dependents(&self, replacement: &SoftwarePackage) -> Result<(), String> {
+        for installed in self.installed.values() {
+            for dependency in &installed.dependencies {
+                if dependency.name == replacement.name && !dependency.is_satisfied_by(replacement) {
+                    return Err(format!("Conflict: {} requires {}, which {} would break",
+                                       installed, dependency, replacement));
+                }
+            }
+        }
+        Ok(())
+    }
+    
+    fn packages(&self) -> Vec<SoftwarePackage> {
+        let mut packages: Vec<SoftwarePackage> = self.installed.values().cloned().collect();
+        packages.sort_by(|a, b| a.name.cmp(&b.name));
+        packages
+    }
+}
+
+// Published packages that IT can deploy, in every released version
+#[derive(Debug, Default)]
+struct SoftwareCatalog {
+    packages: HashMap<String, Vec<SoftwarePackage>>,
+}
+
+impl SoftwareCatalog {
+    fn new() -> Self {
+        SoftwareCatalog::default()
+    }
+    
+    fn publish(&mut self, package: SoftwarePackage) {
+        let versions = self.packages.entry(package.name.clone()).or_default();
+        versions.push(package);
+        versions.sort_by_key(|p| p.version);
+    }
+    
+    fn find(&self, name: &str, version: Version) -> Option<&SoftwarePackage> {
+        self.packages.get(name)?.iter().find(|p| p.version == version)
+    }
+    
+    fn latest(&self, name: &str) -> Option<&SoftwarePackage> {
+        self.packages.get(name)?.last()
+    }
+    
+    fn best_match(&self, dependency: &Dependency) -> Option<&SoftwarePackage> {
+        self.packages.get(&dependency.name)?
+            .iter()
+            .rev()
+            .find(|p| dependency.is_satisfied_by(p))
+    }
+    
+    // Returns the packages to install, dependencies first, skipping anything
+    // the device already has in a compatible version
+    fn resolve(&self, package: &SoftwarePackage, installed: &[SoftwarePackage]) -> Result<Vec<SoftwarePackage>, String> {
+        let mut plan = Vec::new();
+        let mut visiting = Vec::new();
+        self.resolve_into(package, installed, &mut plan, &mut visiting)?;
+        Ok(plan)
+    }
+    
+    fn resolve_into(
+        &self,
+        package: &SoftwarePackage,
+        installed: &[SoftwarePackage],
+        plan: &mut Vec<SoftwarePackage>,
+        visiting: &mut Vec<String>,
+    ) -> Result<(), String> {
+        if visiting.contains(&package.name) {
+            return Err(format!("Circular dependency detected at {}", package.name));
+        }
+        visiting.push(package.name.clone());
+        
+        for dependency in &package.dependencies {
+            if let Some(planned) = plan.iter().find(|p| p.name == dependency.name) {
+                if !dependency.is_satisfied_by(planned) {
+                    return Err(format!("Conflict: {} requires {} but {} is already planned",
+                                       package, dependency, planned));
+                }
+                continue;
+            }
+            if installed.iter().any(|p| dependency.is_satisfied_by(p)) {
+                continue;
+            }
+            let candidate = self.best_match(dependency)
+                .ok_or_else(|| format!("No published version satisfies {} (needed by {})", dependency, package))?;
+            self.resolve_into(candidate, installed, plan, visiting)?;
+        }
+        
+        visiting.pop();
+        plan.push(package.clone());
+        Ok(())
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+impl fmt::Display for Dependency {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} ^{}", self.name, self.min_version)
+    }
+}
+
+impl fmt::Display for SoftwarePackage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} v{}", self.name, self.version)
+    }
+}
+
+// Message travelling through the virtual network
+#[derive(Debug, Clone)]
+struct Packet {
+    from: String,
+    to: String,
+    payload: String,
+    deliver_at: u64,
+}
+
+// Shared state of one virtual network: who is attached, what is in flight,
+// and which faults are currently injected
+#[derive(Debug, Default)]
+struct NetworkHub {
+    mailboxes: HashMap<String, VecDeque<Packet>>,
+    in_flight: Vec<Packet>,
+    failing_links: Vec<String>,
+    latency_ticks: u64,
+    now: u64,
+}
+
+// Cloneable handle to an in-memory network that NetworkEnabled devices register with
+#[derive(Debug, Clone)]
+struct VirtualNetwork {
+    name: String,
+    hub: Rc<RefCell<NetworkHub>>,
+}
+
+impl VirtualNetwork {
+    fn new(name: &str) -> Self {
+        VirtualNetwork {
+            name: name.to_string(),
+            hub: Rc::new(RefCell::new(NetworkHub::default())),
+        }
+    }
+    
+    fn name(&self) -> &str {
+        &self.name
+    }
+    
+    fn register(&self, address: &str) -> Result<(), String> {
+        let mut hub = self.hub.borrow_mut();
+        if hub.mailboxes.contains_key(address) {
+            return Err(format!("Address {} is already registered on {}", address, self.name));
+        }
+        hub.mailboxes.insert(address.to_string(), VecDeque::new());
+        Ok(())
+    }
+    
+    // Detaching drops the device's mailbox and anything still travelling to it
+    fn unregister(&self, address: &str) -> Result<(), String> {
+        let mut hub = self.hub.borrow_mut();
+        hub.mailboxes.remove(address)
+            .ok_or_else(|| format!("Address {} is not registered on {}", address, self.name))?;
+        hub.in_flight.retain(|packet| packet.to != address);
+        Ok(())
+    }
+    
+    fn peers(&self, address: &str) -> Vec<String> {
+        let mut peers: Vec<String> = self.hub.borrow().mailboxes.keys()
+            .filter(|peer| peer.as_str() != address)
+            .cloned()
+            .collect();
+        peers.sort();
+        peers
+    }
+    
+    fn route(&self, from: &str, to: &str, payload: &str) -> Result<(), String> {
+        let mut hub = self.hub.borrow_mut();
+        if !hub.mailboxes.contains_key(from) {
+            return Err(format!("Sender {} is not registered on {}", from, self.name));
+        }
+        if !hub.mailboxes.contains_key(to) {
+            return Err(format!("Destination {} is unreachable on {}", to, self.name));
+        }
+        if hub.failing_links.iter().any(|address| address == from || address == to) {
+            return Err(format!("Delivery from {} to {} failed: link down", from, to));
+        }
+        
+        let packet = Packet {
+            from: from.to_string(),
+            to: to.to_string(),
+            payload: payload.to_string(),
+            deliver_at: hub.now + hub.latency_ticks,
+        };
+        hub.in_flight.push(packet);
+        Self::deliver_due(&mut hub);
+        Ok(())
+    }
+    
+    fn receive(&self, address: &str) -> Result<Packet, String> {
+        let mut hub = self.hub.borrow_mut();
+        let mailbox = hub.mailboxes.get_mut(address)
+            .ok_or_else(|| format!("Address {} is not registered on {}", address, self.name))?;
+        mailbox.pop_front().ok_or_else(|| "No data available".to_string())
+    }
+    
+    // Latency is measured in simulation ticks; packets arrive once the clock catches up
+    fn set_latency(&self, ticks: u64) {
+        self.hub.borrow_mut().latency_ticks = ticks;
+    }
+    
+    fn advance(&self, ticks: u64) {
+        let mut hub = self.hub.borrow_mut();
+        hub.now += ticks;
+        Self::deliver_due(&mut hub);
+    }
+    
+    fn inject_failure(&self, address: &str) {
+        self.hub.borrow_mut().failing_links.push(address.to_string());
+    }
+    
+    fn restore_link(&self, address: &str) {
+        self.hub.borrow_mut().failing_links.retain(|failing| failing != address);
+    }
+    
+    fn deliver_due(hub: &mut NetworkHub) {
+        let now = hub.now;
+        let (due, pending): (Vec<Packet>, Vec<Packet>) = hub.in_flight.drain(..)
+            .partition(|packet| packet.deliver_at <= now);
+        hub.in_flight = pending;
+        for packet in due {
+            if let Some(mailbox) = hub.mailboxes.get_mut(&packet.to) {
+                mailbox.push_back(packet);
+            }
+        }
+    }
+}
+
+// Network connectivity trait
+trait NetworkEnabled {
+    fn connect_to_network(&mut self, network: &VirtualNetwork) -> Result<(), String>;
+    fn disconnect_from_network(&mut self) -> Result<(), String>;
+    fn send_data(&self, recipient: &str, data: &str) -> Result<(), String>;
+    fn receive_data(&mut self) -> Result<String, String>;
+    fn get_network_address(&self) -> String;
+    fn get_network_status(&self) -> String;
+}
+
+// Human implementation - only implements relevant traits
+#[derive(Debug)]
+struct Person {
+    name: String,
+    energy: i32,
+    is_awake: bool,
+    skills: SkillGraph,
+    intelligence: i32,
+    health: String,
+    relationships: RelationshipGraph,
+    emotions: EmotionalState,
+}
+
+impl Person {
+    fn new(name: String) -> Self {
+        Person {
+            name,
+            energy: 100,
+            is_awake: true,
+            skills: SkillGraph::new()
+                .with_skill("Basic Communication", 60, &[])
+                .with_skill("Empathy", 80, &["Basic Communication"]),
+            intelligence: 100,
+            health: "Healthy".to_string(),
+            relationships: RelationshipGraph::new(),
+            emotions: EmotionalState::new(),
+        }
+    }
+}
+
+impl Workable for Person {
+    fn work(&mut self) {
+        if !self.is_awake {
+            println!("{} cannot work while sleeping!", self.name);
+            return;
+        }
+        println!("{} is working with human creativity", self.name);
+        self.energy -= 20;
+        self.emotions.add_stressor("Workload", 15);
+    }
+    
+    fn get_work_efficiency(&self) -> f32 {
+        if self.is_awake { self.energy as f32 / 100.0 * self.emotions.efficiency_multiplier() } else { 0.0 }
+    }
+}
+
+impl Biological for Person {
+    fn eat(&mut self) -> Result<(), String> {
+        println!("{} is eating delicious food", self.name);
+        self.energy = (self.energy + 30).min(100);
+        Ok(())
+    }
+    
+    fn sleep(&mut self) -> Result<(), String> {
+        println!("{} is sleeping peacefully", self.name);
+        self.is_awake = false;
+        self.energy = 100;
+        self.emotions.recover(10);
+        Ok(())
+    }
+    
+    fn breathe(&mut self) -> Result<(), String> {
+        println!("{} is breathing fresh air", self.name);
+        Ok(())
+    }
+    
+    fn get_health_status(&self) -> String {
+        self.health.clone()
+    }
+}
+
+impl Cognitive for Person {
+    fn think(&mut self) {
+        println!("{} is thinking creatively and emotionally", self.name);
+        self.energy -= 5;
+    }
+    
+    fn learn(&mut self, skill: &str) {
+        println!("{} is learning: {}", self.name, skill);
+        self.skills.practice(skill, 10);
+        self.intelligence += 2;
+    }
+    
+    fn solve_problem(&self, problem: &str) -> String {
+        format!("{} is solving '{}' using human intuition and experience", self.name, problem)
+    }
+    
+    fn get_intelligence_level(&self) -> i32 {
+        self.intelligence
+    }
+    
+    fn get_skills(&self) -> &SkillGraph {
+        &self.skills
+    }
+}
+
+impl Social for Person {
+    fn socialize(&mut self) {
+        println!("{} is socializing and building relationships", self.name);
+        self.energy -= 10;
+        self.emotions.recover(10);
+    }
+    
+    fn communicate(&self, message: &str) -> String {
+        format!("{} says: '{}'", self.name, message)
+    }
+    
+    fn form_relationships(&mut self, other: &str, kind: RelationshipKind) -> Result<(), String> {
+        println!("{} is forming a meaningful relationship: {} of {}", self.name, kind, other);
+        self.relationships.connect(&self.name, other, kind)
+    }
+    
+    fn get_relationships(&self) -> &RelationshipGraph {
+        &self.relationships
+    }
+    
+    fn get_social_name(&self) -> String {
+        self.name.clone()
+    }
+}
+
+impl Physical for Person {
+    fn exercise(&mut self) -> Result<(), String> {
+        println!("{} is doing physical exercise", self.name);
+        self.energy -= 15;
+        self.health = "Excellent".to_string();
+        self.emotions.recover(20);
+        Ok(())
+    }
+    
+    fn feel_emotions(&mut self) -> Result<(), String> {
+        println!("{} is experiencing complex human emotions: {}", self.name, self.emotions);
+        if !self.emotions.get_stressors().is_empty() {
+            println!("Weighing on {}: {}", self.name, self.emotions.get_stressors().join(", "));
+        }
+        Ok(())
+    }
+    
+    fn reproduce(&mut self) -> Result<(), String> {
+        println!("{} can participate in human reproduction", self.name);
+        Ok(())
+    }
+    
+    fn get_physical_condition(&self) -> String {
+        format!("Energy: {}, Health: {}, {}", self.energy, self.health, self.emotions)
+    }
+    
+    fn get_emotional_state(&self) -> &EmotionalState {
+        &self.emotions
+    }
+}
+
+// Robot implementation - only implements relevant traits. Crate-visible, like
+// BiologicalCareProvider, so the compile-fail case in ui/ can name it.
+#[derive(Debug)]
+pub(crate) struct IndustrialRobot {
+    model: String,
+    battery_level: i32,
+    #[cfg(not(feature = "lifecycle"))]
+    is_operational: bool,
+    #[cfg(feature = "lifecycle")]
+    lifecycle: state::Lifecycle,
+    programs: Vec<String>,
+    software: SoftwareInventory,
+    network: Option<VirtualNetwork>,
+    wear: f32,
+    charging: ChargingState,
+    skills: SkillGraph,
+}
+
+impl IndustrialRobot {
+    pub(crate) fn new(model: String) -> Self {
+        IndustrialRobot {
             model,
             battery_level: 100,
+            #[cfg(not(feature = "lifecycle"))]
             is_operational: true,
-            programs: vec!["Basic Operations".to_string(), "Social Interaction".to_string()],
-            network_status: "Disconnected".to_string(),
-            is_charging: false,
-            social_protocols: vec!["Polite Communication".to_string()],
+            #[cfg(feature = "lifecycle")]
+            lifecycle: state::Lifecycle::booted(),
+            programs: vec!["Basic Operations".to_string()],
+            software: SoftwareInventory::default(),
+            network: None,
+            wear: 0.0,
+            charging: ChargingState::Full,
+            skills: SkillGraph::new()
+                .with_skill("Precision Assembly", 90, &[])
+                .with_skill("Statistics", 85, &[])
+                .with_skill("Data Analysis", 75, &["Statistics"]),
+        }
+    }
+}
+
+// Without the `lifecycle` feature: operational or powered down, and only work
+// and programs check which
+#[cfg(not(feature = "lifecycle"))]
+impl IndustrialRobot {
+    fn permit(&self, activity: Activity) -> Result<(), String> {
+        match activity {
+            Activity::Work | Activity::ExecuteProgram if !self.is_operational => Err("Robot is not operational".to_string()),
+            _ => Ok(()),
+        }
+    }
+    
+    fn status(&self) -> String {
+        if self.is_operational { "Operational".to_string() } else { "Offline".to_string() }
+    }
+    
+    fn shut_down(&mut self) {
+        self.is_operational = false;
+    }
+    
+    fn maintain(&mut self, service: impl FnOnce(&mut Self)) -> Result<(), String> {
+        service(self);
+        Ok(())
+    }
+}
+
+// With the `lifecycle` feature: the state machine in patterns/state.rs decides
+#[cfg(feature = "lifecycle")]
+impl IndustrialRobot {
+    fn permit(&self, activity: Activity) -> Result<(), String> {
+        self.lifecycle.permit(activity)
+    }
+    
+    fn status(&self) -> String {
+        self.lifecycle.state().to_string()
+    }
+    
+    fn shut_down(&mut self) {
+        self.lifecycle.handle(state::LifecycleEvent::PowerOff).ok();
+    }
+    
+    // Goes through Maintenance and back to Operational, which also clears a fault
+    fn maintain(&mut self, service: impl FnOnce(&mut Self)) -> Result<(), String> {
+        self.lifecycle.handle(state::LifecycleEvent::StartMaintenance)?;
+        service(self);
+        self.lifecycle.handle(state::LifecycleEvent::FinishMaintenance)?;
+        Ok(())
+    }
+    
+    fn lifecycle_state(&self) -> state::RobotState {
+        self.lifecycle.state()
+    }
+    
+    fn handle_event(&mut self, event: state::LifecycleEvent) -> Result<state::RobotState, String> {
+        let next = self.lifecycle.handle(event)?;
+        println!("{} -> {}", self.model, next);
+        Ok(next)
+    }
+}
+
+impl Workable for IndustrialRobot {
+    fn work(&mut self) {
+        if let Err(e) = self.permit(Activity::Work) {
+            println!("{} cannot work: {}", self.model, e);
+            return;
+        }
+        println!("{} robot is performing precise industrial work", self.model);
+        self.battery_level -= 10;
+        self.wear = (self.wear + 0.05).min(1.0);
+    }
+    
+    fn get_work_efficiency(&self) -> f32 {
+        if self.permit(Activity::Work).is_ok() {
+            (self.battery_level as f32 / 100.0) * 1.2  // Robots are more efficient
+        } else { 
+            0.0 
+        }
+    }
+}
+
+impl Cognitive for IndustrialRobot {
+    fn think(&mut self) {
+        if let Err(e) = self.permit(Activity::Think) {
+            println!("{} cannot think: {}", self.model, e);
+            return;
+        }
+        println!("{} is processing data and running algorithms", self.model);
+        self.battery_level -= 2;
+    }
+    
+    fn learn(&mut self, skill: &str) {
+        if let Err(e) = self.permit(Activity::Think) {
+            println!("{} cannot learn {}: {}", self.model, skill, e);
+            return;
+        }
+        println!("{} is updating algorithms for: {}", self.model, skill);
+        self.programs.push(format!("Algorithm: {}", skill));
+        self.skills.practice(skill, 15);
+    }
+    
+    fn solve_problem(&self, problem: &str) -> String {
+        format!("{} is solving '{}' using computational analysis", self.model, problem)
+    }
+    
+    fn get_intelligence_level(&self) -> i32 {
+        (self.programs.len() + self.software.packages().len()) as i32 * 25  // Based on installed programs
+    }
+    
+    fn get_skills(&self) -> &SkillGraph {
+        &self.skills
+    }
+}
+
+impl Mechanical for IndustrialRobot {
+    fn power_down(&mut self) {
+        println!("{} is safely powering down all systems", self.model);
+        self.shut_down();
+    }
+    
+    fn run_diagnostics(&self) -> String {
+        format!("Robot: {}, Battery: {}%, Status: {}, Programs: {}", 
+                self.model, self.battery_level, self.status(), self.programs.len())
+    }
+    
+    fn perform_maintenance(&mut self) -> Result<(), String> {
+        self.maintain(|robot| {
+            println!("{} is performing self-maintenance", robot.model);
+            robot.battery_level = (robot.battery_level + 10).min(100);
+            robot.wear = 0.0;
+        })
+    }
+    
+    fn get_mechanical_status(&self) -> String {
+        if self.permit(Activity::Work).is_ok() { "All systems operational".to_string() } 
+        else { "Systems offline".to_string() }
+    }
+    
+    fn get_device_id(&self) -> String {
+        self.model.clone()
+    }
+    
+    fn get_wear_level(&self) -> f32 {
+        self.wear
+    }
+}
+
+impl Rechargeable for IndustrialRobot {
+    fn start_charging(&mut self, rate: i32) -> Result<(), String> {
+        self.charging = self.charging.start(self.battery_level, rate)?;
+        println!("{} is recharging battery at {}%/min", self.model, rate);
+        Ok(())
+    }
+    
+    fn tick_charge(&mut self, minutes: u32) -> ChargingState {
+        let was_charging = self.is_charging();
+        let (state, level) = self.charging.tick(self.battery_level, minutes);
+        self.charging = state;
+        self.battery_level = level;
+        if was_charging && state == ChargingState::Full {
+            println!("{} battery fully charged", self.model);
+        }
+        state
+    }
+    
+    fn stop_charging(&mut self) -> ChargingState {
+        self.charging = self.charging.stop(self.battery_level);
+        self.charging
+    }
+    
+    fn get_battery_level(&self) -> i32 {
+        self.battery_level
+    }
+    
+    fn get_charging_state(&self) -> ChargingState {
+        self.charging.settle(self.battery_level)
+    }
+}
+
+impl Programmable for IndustrialRobot {
+    fn execute_program(&self, program: &str) -> Result<(), String> {
+        self.permit(Activity::ExecuteProgram)?;
+        println!("{} is executing program: {}", self.model, program);
+        Ok(())
+    }
+    
+    fn install_software(&mut self, package: SoftwarePackage) -> Result<(), String> {
+        self.permit(Activity::InstallSoftware)?;
+        println!("{} is installing software: {}", self.model, package);
+        self.software.install(package)
+    }
+    
+    fn rollback_software(&mut self, name: &str) -> Result<SoftwarePackage, String> {
+        let restored = self.software.rollback(name)?;
+        println!("{} rolled back to: {}", self.model, restored);
+        Ok(restored)
+    }
+    
+    fn get_installed_packages(&self) -> Vec<SoftwarePackage> {
+        self.software.packages()
+    }
+    
+    fn get_installed_programs(&self) -> Vec<String> {
+        let mut programs = self.programs.clone();
+        programs.extend(self.software.packages().iter().map(|p| p.to_string()));
+        programs
+    }
+    
+    fn get_device_profile(&self) -> DeviceProfile {
+        DeviceProfile {
+            capabilities: vec!["Workable", "Cognitive", "Mechanical", "Rechargeable", "Programmable", "NetworkEnabled"],
+            battery_level: Some(self.battery_level),
+        }
+    }
+}
+
+impl NetworkEnabled for IndustrialRobot {
+    fn connect_to_network(&mut self, network: &VirtualNetwork) -> Result<(), String> {
+        self.permit(Activity::Network)?;
+        println!("{} is connecting to network: {}", self.model, network.name());
+        self.disconnect_from_network().ok();
+        network.register(&self.model)?;
+        self.network = Some(network.clone());
+        Ok(())
+    }
+    
+    fn disconnect_from_network(&mut self) -> Result<(), String> {
+        let network = self.network.take().ok_or_else(|| "Not connected to network".to_string())?;
+        println!("{} is disconnecting from network: {}", self.model, network.name());
+        network.unregister(&self.model)
+    }
+    
+    fn send_data(&self, recipient: &str, data: &str) -> Result<(), String> {
+        self.permit(Activity::Network)?;
+        let network = self.network.as_ref().ok_or_else(|| "Not connected to network".to_string())?;
+        println!("{} is sending data to {}: {}", self.model, recipient, data);
+        network.route(&self.model, recipient, data)
+    }
+    
+    fn receive_data(&mut self) -> Result<String, String> {
+        self.permit(Activity::Network)?;
+        let network = self.network.as_ref().ok_or_else(|| "Not connected to network".to_string())?;
+        let packet = network.receive(&self.model)?;
+        println!("{} received from {}: {}", self.model, packet.from, packet.payload);
+        Ok(packet.payload)
+    }
+    
+    fn get_network_address(&self) -> String {
+        self.model.clone()
+    }
+    
+    fn get_network_status(&self) -> String {
+        match &self.network {
+            Some(network) => format!("Connected to {}", network.name()),
+            None => "Disconnected".to_string(),
+        }
+    }
+}
+
+// Reusable robot components: each one implements a single capability trait on top
+// of vitals shared through RobotCore, so new robots are assembled rather than rewritten
+
+// Vitals shared by every component of a composed robot
+#[derive(Debug)]
+struct RobotCore {
+    model: String,
+    battery_level: Cell<i32>,
+    is_operational: Cell<bool>,
+    wear: Cell<f32>,
+    charging: Cell<ChargingState>,
+    roles: RefCell<Vec<&'static str>>,
+}
+
+impl RobotCore {
+    fn new(model: String) -> Rc<Self> {
+        Rc::new(RobotCore {
+            model,
+            battery_level: Cell::new(100),
+            is_operational: Cell::new(true),
+            wear: Cell::new(0.0),
+            charging: Cell::new(ChargingState::Full),
+            roles: RefCell::new(Vec::new()),
+        })
+    }
+    
+    fn drain(&self, amount: i32) {
+        self.battery_level.set(self.battery_level.get() - amount);
+    }
+    
+    // Each component announces the trait it provides, so the assembled
+    // robot can describe itself without knowing its own composition
+    fn register_role(&self, role: &'static str) {
+        self.roles.borrow_mut().push(role);
+    }
+    
+    fn profile(&self) -> DeviceProfile {
+        let roles = self.roles.borrow().clone();
+        let battery_level = if roles.contains(&"Rechargeable") { Some(self.battery_level.get()) } else { None };
+        DeviceProfile { capabilities: roles, battery_level }
+    }
+}
+
+#[derive(Debug)]
+struct WorkUnit {
+    core: Rc<RobotCore>,
+    task: &'static str,
+    battery_cost: i32,
+    wear_per_task: f32,
+    efficiency_factor: f32,
+}
+
+impl WorkUnit {
+    fn new(core: Rc<RobotCore>, task: &'static str, battery_cost: i32, wear_per_task: f32, efficiency_factor: f32) -> Self {
+        core.register_role("Workable");
+        WorkUnit { core, task, battery_cost, wear_per_task, efficiency_factor }
+    }
+}
+
+impl Workable for WorkUnit {
+    fn work(&mut self) {
+        if !self.core.is_operational.get() {
+            println!("{} is not operational!", self.core.model);
+            return;
+        }
+        println!("{} {}", self.core.model, self.task);
+        self.core.drain(self.battery_cost);
+        self.core.wear.set((self.core.wear.get() + self.wear_per_task).min(1.0));
+    }
+    
+    fn get_work_efficiency(&self) -> f32 {
+        if self.core.is_operational.get() {
+            (self.core.battery_level.get() as f32 / 100.0) * self.efficiency_factor
+        } else {
+            0.0
+        }
+    }
+}
+
+#[derive(Debug)]
+struct AiCore {
+    core: Rc<RobotCore>,
+    algorithms: Vec<String>,
+    skills: SkillGraph,
+    think_cost: i32,
+    intelligence_per_algorithm: i32,
+}
+
+impl AiCore {
+    fn new(core: Rc<RobotCore>, algorithms: &[&str], skills: SkillGraph, think_cost: i32, intelligence_per_algorithm: i32) -> Self {
+        core.register_role("Cognitive");
+        AiCore {
+            core,
+            algorithms: algorithms.iter().map(|a| a.to_string()).collect(),
+            skills,
+            think_cost,
+            intelligence_per_algorithm,
+        }
+    }
+}
+
+impl Cognitive for AiCore {
+    fn think(&mut self) {
+        println!("{} is processing with advanced AI and learning algorithms", self.core.model);
+        self.core.drain(self.think_cost);
+    }
+    
+    fn learn(&mut self, skill: &str) {
+        println!("{} is learning skill: {}", self.core.model, skill);
+        self.algorithms.push(format!("Algorithm: {}", skill));
+        self.skills.practice(skill, 12);
+    }
+    
+    fn solve_problem(&self, problem: &str) -> String {
+        format!("{} is solving '{}' using AI with human-like reasoning", self.core.model, problem)
+    }
+    
+    fn get_intelligence_level(&self) -> i32 {
+        self.algorithms.len() as i32 * self.intelligence_per_algorithm
+    }
+    
+    fn get_skills(&self) -> &SkillGraph {
+        &self.skills
+    }
+}
+
+#[derive(Debug)]
+struct SocialModule {
+    core: Rc<RobotCore>,
+    protocols: Vec<String>,
+    relationships: RelationshipGraph,
+    socialize_cost: i32,
+}
+
+impl SocialModule {
+    fn new(core: Rc<RobotCore>, protocols: &[&str], socialize_cost: i32) -> Self {
+        core.register_role("Social");
+        SocialModule {
+            core,
+            protocols: protocols.iter().map(|p| p.to_string()).collect(),
+            relationships: RelationshipGraph::new(),
+            socialize_cost,
+        }
+    }
+}
+
+impl Social for SocialModule {
+    fn socialize(&mut self) {
+        println!("{} is engaging in programmed social interactions", self.core.model);
+        self.core.drain(self.socialize_cost);
+    }
+    
+    fn communicate(&self, message: &str) -> String {
+        format!("{} communicates: '{}'", self.core.model, message)
+    }
+    
+    fn form_relationships(&mut self, other: &str, kind: RelationshipKind) -> Result<(), String> {
+        println!("{} is forming programmed social bonds: {} of {}", self.core.model, kind, other);
+        self.relationships.connect(&self.core.model, other, kind)?;
+        self.protocols.push(format!("{} Protocol: {}", kind, other));
+        Ok(())
+    }
+    
+    fn get_relationships(&self) -> &RelationshipGraph {
+        &self.relationships
+    }
+    
+    fn get_social_name(&self) -> String {
+        self.core.model.clone()
+    }
+}
+
+#[derive(Debug)]
+struct MechanicalFrame {
+    core: Rc<RobotCore>,
+    label: &'static str,
+    maintenance_boost: i32,
+}
+
+impl MechanicalFrame {
+    fn new(core: Rc<RobotCore>, label: &'static str, maintenance_boost: i32) -> Self {
+        core.register_role("Mechanical");
+        MechanicalFrame { core, label, maintenance_boost }
+    }
+}
+
+impl Mechanical for MechanicalFrame {
+    fn power_down(&mut self) {
+        println!("{} is safely powering down all systems", self.core.model);
+        self.core.is_operational.set(false);
+    }
+    
+    fn run_diagnostics(&self) -> String {
+        let status = if self.core.is_operational.get() { "Operational" } else { "Offline" };
+        format!("{}: {}, Battery: {}%, Status: {}, Wear: {:.0}%",
+                self.label, self.core.model, self.core.battery_level.get(), status, self.core.wear.get() * 100.0)
+    }
+    
+    fn perform_maintenance(&mut self) -> Result<(), String> {
+        println!("{} is performing maintenance on {} systems", self.core.model, self.label.to_lowercase());
+        self.core.battery_level.set((self.core.battery_level.get() + self.maintenance_boost).min(100));
+        self.core.wear.set(0.0);
+        Ok(())
+    }
+    
+    fn get_mechanical_status(&self) -> String {
+        if self.core.is_operational.get() { format!("All {} systems operational", self.label.to_lowercase()) }
+        else { "Systems offline".to_string() }
+    }
+    
+    fn get_device_id(&self) -> String {
+        self.core.model.clone()
+    }
+    
+    fn get_wear_level(&self) -> f32 {
+        self.core.wear.get()
+    }
+}
+
+#[derive(Debug)]
+struct PowerCell {
+    core: Rc<RobotCore>,
+}
+
+impl PowerCell {
+    fn new(core: Rc<RobotCore>) -> Self {
+        core.register_role("Rechargeable");
+        PowerCell { core }
+    }
+}
+
+impl Rechargeable for PowerCell {
+    fn start_charging(&mut self, rate: i32) -> Result<(), String> {
+        let state = self.core.charging.get().start(self.core.battery_level.get(), rate)?;
+        self.core.charging.set(state);
+        println!("{} is recharging with energy-efficient protocols at {}%/min", self.core.model, rate);
+        Ok(())
+    }
+    
+    fn tick_charge(&mut self, minutes: u32) -> ChargingState {
+        let was_charging = self.is_charging();
+        let (state, level) = self.core.charging.get().tick(self.core.battery_level.get(), minutes);
+        self.core.charging.set(state);
+        self.core.battery_level.set(level);
+        if was_charging && state == ChargingState::Full {
+            println!("{} battery fully charged", self.core.model);
+        }
+        state
+    }
+    
+    fn stop_charging(&mut self) -> ChargingState {
+        let state = self.core.charging.get().stop(self.core.battery_level.get());
+        self.core.charging.set(state);
+        state
+    }
+    
+    fn get_battery_level(&self) -> i32 {
+        self.core.battery_level.get()
+    }
+    
+    fn get_charging_state(&self) -> ChargingState {
+        self.core.charging.get().settle(self.core.battery_level.get())
+    }
+}
+
+#[derive(Debug)]
+struct SoftwareStack {
+    core: Rc<RobotCore>,
+    inventory: SoftwareInventory,
+}
+
+impl SoftwareStack {
+    fn new(core: Rc<RobotCore>) -> Self {
+        core.register_role("Programmable");
+        SoftwareStack { core, inventory: SoftwareInventory::default() }
+    }
+}
+
+impl Programmable for SoftwareStack {
+    fn execute_program(&self, program: &str) -> Result<(), String> {
+        if !self.core.is_operational.get() {
+            return Err(format!("{} is not operational", self.core.model));
+        }
+        println!("{} is executing program: {}", self.core.model, program);
+        Ok(())
+    }
+    
+    fn install_software(&mut self, package: SoftwarePackage) -> Result<(), String> {
+        println!("{} is installing software: {}", self.core.model, package);
+        self.inventory.install(package)
+    }
+    
+    fn rollback_software(&mut self, name: &str) -> Result<SoftwarePackage, String> {
+        let restored = self.inventory.rollback(name)?;
+        println!("{} rolled back to: {}", self.core.model, restored);
+        Ok(restored)
+    }
+    
+    fn get_installed_packages(&self) -> Vec<SoftwarePackage> {
+        self.inventory.packages()
+    }
+    
+    fn get_installed_programs(&self) -> Vec<String> {
+        self.inventory.packages().iter().map(|p| p.to_string()).collect()
+    }
+    
+    fn get_device_profile(&self) -> DeviceProfile {
+        self.core.profile()
+    }
+}
+
+#[derive(Debug)]
+struct NetworkInterface {
+    core: Rc<RobotCore>,
+    network: Option<VirtualNetwork>,
+}
+
+impl NetworkInterface {
+    fn new(core: Rc<RobotCore>) -> Self {
+        core.register_role("NetworkEnabled");
+        NetworkInterface { core, network: None }
+    }
+}
+
+impl NetworkEnabled for NetworkInterface {
+    fn connect_to_network(&mut self, network: &VirtualNetwork) -> Result<(), String> {
+        println!("{} is connecting to network: {}", self.core.model, network.name());
+        self.disconnect_from_network().ok();
+        network.register(&self.core.model)?;
+        self.network = Some(network.clone());
+        Ok(())
+    }
+    
+    fn disconnect_from_network(&mut self) -> Result<(), String> {
+        let network = self.network.take().ok_or_else(|| "Not connected to network".to_string())?;
+        println!("{} is disconnecting from network: {}", self.core.model, network.name());
+        network.unregister(&self.core.model)
+    }
+    
+    fn send_data(&self, recipient: &str, data: &str) -> Result<(), String> {
+        let network = self.network.as_ref().ok_or_else(|| "Not connected to network".to_string())?;
+        println!("{} is sending data to {}: {}", self.core.model, recipient, data);
+        network.route(&self.core.model, recipient, data)
+    }
+    
+    fn receive_data(&mut self) -> Result<String, String> {
+        let network = self.network.as_ref().ok_or_else(|| "Not connected to network".to_string())?;
+        let packet = network.receive(&self.core.model)?;
+        println!("{} received from {}: {}", self.core.model, packet.from, packet.payload);
+        Ok(packet.payload)
+    }
+    
+    fn get_network_address(&self) -> String {
+        self.core.model.clone()
+    }
+    
+    fn get_network_status(&self) -> String {
+        match &self.network {
+            Some(network) => format!("Connected to {}", network.name()),
+            None => "Disconnected".to_string(),
+        }
+    }
+}
+
+// Generates a delegating impl of one capability trait, forwarding to a component field
+macro_rules! delegate_role {
+    (Workable, $entity:ty, $field:ident) => {
+        impl Workable for $entity {
+            fn work(&mut self) { self.$field.work() }
+            fn get_work_efficiency(&self) -> f32 { self.$field.get_work_efficiency() }
+        }
+    };
+    (Cognitive, $entity:ty, $field:ident) => {
+        impl Cognitive for $entity {
+            fn think(&mut self) { self.$field.think() }
+            fn learn(&mut self, skill: &str) { self.$field.learn(skill) }
+            fn solve_problem(&self, problem: &str) -> String { self.$field.solve_problem(problem) }
+            fn get_intelligence_level(&self) -> i32 { self.$field.get_intelligence_level() }
+            fn get_skills(&self) -> &SkillGraph { self.$field.get_skills() }
+        }
+    };
+    (Social, $entity:ty, $field:ident) => {
+        impl Social for $entity {
+            fn socialize(&mut self) { self.$field.socialize() }
+            fn communicate(&self, message: &str) -> String { self.$field.communicate(message) }
+            fn form_relationships(&mut self, other: &str, kind: RelationshipKind) -> Result<(), String> {
+                self.$field.form_relationships(other, kind)
+            }
+            fn get_relationships(&self) -> &RelationshipGraph { self.$field.get_relationships() }
+            fn get_social_name(&self) -> String { self.$field.get_social_name() }
+        }
+    };
+    (Mechanical, $entity:ty, $field:ident) => {
+        impl Mechanical for $entity {
+            fn power_down(&mut self) { self.$field.power_down() }
+            fn run_diagnostics(&self) -> String { self.$field.run_diagnostics() }
+            fn perform_maintenance(&mut self) -> Result<(), String> { self.$field.perform_maintenance() }
+            fn get_mechanical_status(&self) -> String { self.$field.get_mechanical_status() }
+            fn get_device_id(&self) -> String { self.$field.get_device_id() }
+            fn get_wear_level(&self) -> f32 { self.$field.get_wear_level() }
+        }
+    };
+    (Rechargeable, $entity:ty, $field:ident) => {
+        impl Rechargeable for $entity {
+            fn start_charging(&mut self, rate: i32) -> Result<(), String> { self.$field.start_charging(rate) }
+            fn tick_charge(&mut self, minutes: u32) -> ChargingState { self.$field.tick_charge(minutes) }
+            fn stop_charging(&mut self) -> ChargingState { self.$field.stop_charging() }
+            fn get_battery_level(&self) -> i32 { self.$field.get_battery_level() }
+            fn get_charging_state(&self) -> ChargingState { self.$field.get_charging_state() }
+        }
+    };
+    (Programmable, $entity:ty, $field:ident) => {
+        impl Programmable for $entity {
+            fn execute_program(&self, program: &str) -> Result<(), String> { self.$field.execute_program(program) }
+            fn install_software(&mut self, package: SoftwarePackage) -> Result<(), String> {
+                self.$field.install_software(package)
+            }
+            fn rollback_software(&mut self, name: &str) -> Result<SoftwarePackage, String> {
+                self.$field.rollback_software(name)
+            }
+            fn get_installed_packages(&self) -> Vec<SoftwarePackage> { self.$field.get_installed_packages() }
+            fn get_installed_programs(&self) -> Vec<String> { self.$field.get_installed_programs() }
+            fn get_device_profile(&self) -> DeviceProfile { self.$field.get_device_profile() }
+        }
+    };
+    (NetworkEnabled, $entity:ty, $field:ident) => {
+        impl NetworkEnabled for $entity {
+            fn connect_to_network(&mut self, network: &VirtualNetwork) -> Result<(), String> {
+                self.$field.connect_to_network(network)
+            }
+            fn disconnect_from_network(&mut self) -> Result<(), String> { self.$field.disconnect_from_network() }
+            fn send_data(&self, recipient: &str, data: &str) -> Result<(), String> {
+                self.$field.send_data(recipient, data)
+            }
+            fn receive_data(&mut self) -> Result<String, String> { self.$field.receive_data() }
+            fn get_network_address(&self) -> String { self.$field.get_network_address() }
+            fn get_network_status(&self) -> String { self.$field.get_network_status() }
+        }
+    };
+}
+
+// Assembles an entity from components: `compose_entity!(Type { Trait => field, ... })`
+macro_rules! compose_entity {
+    ($entity:ty { $($role:ident => $field:ident),+ $(,)? }) => {
+        $( delegate_role!($role, $entity, $field); )+
+    };
+}
+
+// Humanoid Robot - extends Robot concept with additional human-like capabilities,
+// assembled from the reusable robot components
+#[derive(Debug)]
+struct HumanoidRobot {
+    core: Rc<RobotCore>,
+    body: WorkUnit,
+    mind: AiCore,
+    social: SocialModule,
+    frame: MechanicalFrame,
+    power: PowerCell,
+    software: SoftwareStack,
+    network: NetworkInterface,
+}
+
+impl HumanoidRobot {
+    fn new(model: String) -> Self {
+        let core = RobotCore::new(model);
+        let skills = SkillGraph::new()
+            .with_skill("Basic Communication", 70, &[])
+            .with_skill("Statistics", 90, &[])
+            .with_skill("Data Analysis", 85, &["Statistics"]);
+        HumanoidRobot {
+            body: WorkUnit::new(core.clone(), "humanoid robot is performing human-like work tasks", 8, 0.03, 1.1),
+            mind: AiCore::new(core.clone(), &["Basic Operations", "Social Interaction"], skills, 3, 30),
+            social: SocialModule::new(core.clone(), &["Polite Communication"], 5),
+            frame: MechanicalFrame::new(core.clone(), "Humanoid", 15),
+            power: PowerCell::new(core.clone()),
+            software: SoftwareStack::new(core.clone()),
+            network: NetworkInterface::new(core.clone()),
+            core,
+        }
+    }
+}
+
+compose_entity!(HumanoidRobot {
+    Workable => body,
+    Cognitive => mind,
+    Social => social,
+    Mechanical => frame,
+    Rechargeable => power,
+    Programmable => software,
+    NetworkEnabled => network,
+});
+
+// Runtime composition: one enum variant per capability component
+#[derive(Debug)]
+enum Capability {
+    Work(WorkUnit),
+    Cognition(AiCore),
+    Social(SocialModule),
+    Mechanics(MechanicalFrame),
+    Power(PowerCell),
+    Software(SoftwareStack),
+    Network(NetworkInterface),
+}
+
+impl Capability {
+    fn trait_name(&self) -> &'static str {
+        match self {
+            Capability::Work(_) => "Workable",
+            Capability::Cognition(_) => "Cognitive",
+            Capability::Social(_) => "Social",
+            Capability::Mechanics(_) => "Mechanical",
+            Capability::Power(_) => "Rechargeable",
+            Capability::Software(_) => "Programmable",
+            Capability::Network(_) => "NetworkEnabled",
+        }
+    }
+}
+
+// Type-erased entity assembled by EntityBuilder. It only hands out the capability
+// traits it was built with - asking for anything else yields None, never a failing stub.
+#[derive(Debug)]
+struct ComposedEntity {
+    core: Rc<RobotCore>,
+    capabilities: Vec<Capability>,
+}
+
+impl ComposedEntity {
+    fn name(&self) -> &str {
+        &self.core.model
+    }
+    
+    fn capabilities(&self) -> Vec<&'static str> {
+        self.capabilities.iter().map(|c| c.trait_name()).collect()
+    }
+    
+    fn workable(&mut self) -> Option<&mut dyn Workable> {
+        self.capabilities.iter_mut().find_map(|c| match c {
+            Capability::Work(unit) => Some(unit as &mut dyn Workable),
+            _ => None,
+        })
+    }
+    
+    fn cognitive(&mut self) -> Option<&mut dyn Cognitive> {
+        self.capabilities.iter_mut().find_map(|c| match c {
+            Capability::Cognition(ai) => Some(ai as &mut dyn Cognitive),
+            _ => None,
+        })
+    }
+    
+    fn social(&mut self) -> Option<&mut dyn Social> {
+        self.capabilities.iter_mut().find_map(|c| match c {
+            Capability::Social(module) => Some(module as &mut dyn Social),
+            _ => None,
+        })
+    }
+    
+    fn mechanical(&mut self) -> Option<&mut dyn Mechanical> {
+        self.capabilities.iter_mut().find_map(|c| match c {
+            Capability::Mechanics(frame) => Some(frame as &mut dyn Mechanical),
+            _ => None,
+        })
+    }
+    
+    fn rechargeable(&mut self) -> Option<&mut dyn Rechargeable> {
+        self.capabilities.iter_mut().find_map(|c| match c {
+            Capability::Power(cell) => Some(cell as &mut dyn Rechargeable),
+            _ => None,
+        })
+    }
+    
+    fn programmable(&mut self) -> Option<&mut dyn Programmable> {
+        self.capabilities.iter_mut().find_map(|c| match c {
+            Capability::Software(stack) => Some(stack as &mut dyn Programmable),
+            _ => None,
+        })
+    }
+    
+    fn network_enabled(&mut self) -> Option<&mut dyn NetworkEnabled> {
+        self.capabilities.iter_mut().find_map(|c| match c {
+            Capability::Network(interface) => Some(interface as &mut dyn NetworkEnabled),
+            _ => None,
+        })
+    }
+}
+
+// Fluent builder for ComposedEntity; adding a capability twice replaces the first one
+struct EntityBuilder {
+    core: Rc<RobotCore>,
+    capabilities: Vec<Capability>,
+}
+
+impl EntityBuilder {
+    fn new(name: &str) -> Self {
+        EntityBuilder {
+            core: RobotCore::new(name.to_string()),
+            capabilities: vec![],
+        }
+    }
+    
+    fn with_work(self, task: &'static str, battery_cost: i32, wear_per_task: f32, efficiency_factor: f32) -> Self {
+        let unit = WorkUnit::new(self.core.clone(), task, battery_cost, wear_per_task, efficiency_factor);
+        self.with(Capability::Work(unit))
+    }
+    
+    fn with_cognition(self, algorithms: &[&str], skills: SkillGraph, think_cost: i32) -> Self {
+        let ai = AiCore::new(self.core.clone(), algorithms, skills, think_cost, 25);
+        self.with(Capability::Cognition(ai))
+    }
+    
+    fn with_social(self, protocols: &[&str], socialize_cost: i32) -> Self {
+        let module = SocialModule::new(self.core.clone(), protocols, socialize_cost);
+        self.with(Capability::Social(module))
+    }
+    
+    fn with_mechanics(self, label: &'static str, maintenance_boost: i32) -> Self {
+        let frame = MechanicalFrame::new(self.core.clone(), label, maintenance_boost);
+        self.with(Capability::Mechanics(frame))
+    }
+    
+    fn with_power(self) -> Self {
+        let cell = PowerCell::new(self.core.clone());
+        self.with(Capability::Power(cell))
+    }
+    
+    fn with_software(self) -> Self {
+        let stack = SoftwareStack::new(self.core.clone());
+        self.with(Capability::Software(stack))
+    }
+    
+    fn with_network(self) -> Self {
+        let interface = NetworkInterface::new(self.core.clone());
+        self.with(Capability::Network(interface))
+    }
+    
+    fn with(mut self, capability: Capability) -> Self {
+        self.capabilities.retain(|existing| existing.trait_name() != capability.trait_name());
+        self.capabilities.push(capability);
+        self
+    }
+    
+    fn build(self) -> ComposedEntity {
+        ComposedEntity {
+            core: self.core,
+            capabilities: self.capabilities,
+        }
+    }
+}
+
+impl fmt::Display for Person {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Person({})", self.name)
+    }
+}
+
+impl fmt::Display for IndustrialRobot {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "IndustrialRobot({})", self.model)
+    }
+}
+
+impl fmt::Display for HumanoidRobot {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "HumanoidRobot({})", self.core.model)
+    }
+}
+
+// CORRECT: Segregated managers that depend only on needed traits
+
+struct WorkManager;
+
+impl WorkManager {
+    // Only depends on Workable trait
+    fn assign_work(worker: &mut dyn Workable, task: &str) {
+        println!("Assigning work task: {}", task);
+        worker.work();
+        println!("Work efficiency: {:.2}", worker.get_work_efficiency());
+    }
+    
+    // Can work with any Workable + Cognitive combination
+    fn assign_complex_work(worker: &mut (impl Workable + Cognitive), task: &str) {
+        println!("Assigning complex task: {}", task);
+        worker.think();
+        println!("Intelligence level: {}", worker.get_intelligence_level());
+        let solution = worker.solve_problem(task);
+        println!("Solution approach: {}", solution);
+        worker.work();
+    }
+}
+
+// Aggregates work efficiency across any mix of Workable entities
+struct EfficiencyReport {
+    entries: Vec<(String, f32)>,
+}
+
+impl EfficiencyReport {
+    fn new() -> Self {
+        EfficiencyReport { entries: Vec::new() }
+    }
+    
+    // Only depends on Workable trait
+    fn record(&mut self, name: &str, worker: &dyn Workable) {
+        self.entries.push((name.to_string(), worker.get_work_efficiency()));
+    }
+    
+    fn mean(&self) -> Option<f32> {
+        if self.entries.is_empty() {
+            return None;
+        }
+        let total: f32 = self.entries.iter().map(|(_, efficiency)| efficiency).sum();
+        Some(total / self.entries.len() as f32)
+    }
+    
+    fn min(&self) -> Option<(&str, f32)> {
+        self.ranking().last().copied()
+    }
+    
+    fn max(&self) -> Option<(&str, f32)> {
+        self.ranking().first().copied()
+    }
+    
+    // Most efficient first; ties keep recording order
+    fn ranking(&self) -> Vec<(&str, f32)> {
+        let mut ranked: Vec<(&str, f32)> = self.entries.iter().map(|(name, efficiency)| (name.as_str(), *efficiency)).collect();
+        ranked.sort_by(|a, b| b.1.total_cmp(&a.1));
+        ranked
+    }
+}
+
+impl fmt::Display for EfficiencyReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Efficiency report ({} workers):", self.entries.len())?;
+        for (rank, (name, efficiency)) in self.ranking().iter().enumerate() {
+            writeln!(f, "  {}. {:<16} {:.2}", rank + 1, name, efficiency)?;
+        }
+        match (self.mean(), self.min(), self.max()) {
+            (Some(mean), Some((low, min)), Some((high, max))) => {
+                write!(f, "  mean {:.2}, min {:.2} ({}), max {:.2} ({})", mean, min, low, max, high)
+            }
+            _ => write!(f, "  no workers recorded"),
+        }
+    }
+}
+
+pub(crate) struct BiologicalCareProvider;
+
+impl BiologicalCareProvider {
+    // Only depends on Biological trait
+    pub(crate) fn provide_care(being: &mut dyn Biological) -> Result<(), String> {
+        println!("Providing biological care...");
+        being.eat()?;
+        being.sleep()?;
+        being.breathe()?;
+        println!("Health status: {}", being.get_health_status());
+        Ok(())
+    }
+    
+    // Combines biological and physical care
+    // Returns how much the being's mood improved
+    fn provide_wellness_care(being: &mut (impl Biological + Physical)) -> Result<i32, String> {
+        println!("Providing comprehensive wellness care...");
+        let mood_before = being.get_emotional_state().get_mood();
+        being.feel_emotions()?;
+        being.eat()?;
+        being.exercise()?;
+        println!("Physical condition: {}", being.get_physical_condition());
+        Ok(being.get_emotional_state().get_mood() - mood_before)
+    }
+}
+
+// Keeps a maintenance log per device and the operating-hours clock the logs are stamped with
+struct TechnicalSupportManager {
+    service_interval: u64,
+    current_hour: u64,
+    logs: HashMap<String, MaintenanceLog>,
+}
+
+impl TechnicalSupportManager {
+    fn new(service_interval: u64) -> Self {
+        TechnicalSupportManager {
+            service_interval,
+            current_hour: 0,
+            logs: HashMap::new(),
+        }
+    }
+    
+    fn advance_hours(&mut self, hours: u64) {
+        self.current_hour += hours;
+    }
+    
+    // Only depends on Mechanical trait
+    fn record_diagnostics(&mut self, device: &dyn Mechanical) -> String {
+        let report = device.run_diagnostics();
+        let timestamp = self.current_hour;
+        self.log_for(device).record_snapshot(timestamp, report.clone(), device.get_wear_level());
+        report
+    }
+    
+    fn perform_maintenance(&mut self, device: &mut dyn Mechanical) -> Result<(), String> {
+        println!("Performing technical maintenance...");
+        println!("Diagnostics: {}", self.record_diagnostics(device));
+        device.perform_maintenance()?;
+        let timestamp = self.current_hour;
+        self.log_for(device).record_service(timestamp);
+        println!("Status: {}", device.get_mechanical_status());
+        Ok(())
+    }
+    
+    // Percent per minute delivered by the service bay's fast charger
+    const SERVICE_CHARGE_RATE: i32 = 15;
+    
+    // Depends on both Mechanical and Rechargeable
+    fn service_electronic_device(&mut self, device: &mut (impl Mechanical + Rechargeable)) -> Result<(), String> {
+        println!("Servicing electronic device...");
+        println!("Battery level: {}% ({})", device.get_battery_level(), device.get_charging_state());
+        if device.get_charging_state() != ChargingState::Full {
+            device.start_charging(Self::SERVICE_CHARGE_RATE)?;
+            let mut minutes = 0;
+            while device.is_charging() {
+                device.tick_charge(1);
+                minutes += 1;
+            }
+            println!("Charged to {}% in {} minutes", device.get_battery_level(), minutes);
+        }
+        self.perform_maintenance(device)?;
+        Ok(())
+    }
+    
+    fn maintenance_log(&self, device_id: &str) -> Option<&MaintenanceLog> {
+        self.logs.get(device_id)
+    }
+    
+    fn predict_next_service(&self, device_id: &str) -> Option<u64> {
+        self.logs.get(device_id).map(|log| log.predict_next_service())
+    }
+    
+    fn log_for(&mut self, device: &dyn Mechanical) -> &mut MaintenanceLog {
+        let device_id = device.get_device_id();
+        let interval = self.service_interval;
+        self.logs.entry(device_id.clone())
+            .or_insert_with(|| MaintenanceLog::new(device_id, interval))
+    }
+}
+
+struct SocialCoordinator;
+
+impl SocialCoordinator {
+    // Only depends on Social trait
+    fn facilitate_interaction(participant: &mut dyn Social) {
+        println!("Facilitating social interaction...");
+        participant.socialize();
+        let response = participant.communicate("Hello, how are you?");
+        println!("Response: {}", response);
+    }
+    
+    // Combines social and cognitive capabilities: each participant learns what the
+    // other is clearly better at, and the pairing is recorded in both relationship graphs
+    fn organize_collaborative_work(
+        first: &mut (impl Social + Cognitive),
+        second: &mut (impl Social + Cognitive),
+    ) -> Result<(), String> {
+        println!("Organizing collaborative work session: {} + {}",
+                 first.get_social_name(), second.get_social_name());
+        first.think();
+        second.think();
+        first.socialize();
+        second.socialize();
+        
+        let first_learns = Self::lessons_for(first.get_skills(), second.get_skills());
+        let second_learns = Self::lessons_for(second.get_skills(), first.get_skills());
+        for skill in &first_learns {
+            first.learn(skill);
+        }
+        for skill in &second_learns {
+            second.learn(skill);
+        }
+        
+        let (first_role, second_role) = match (first_learns.is_empty(), second_learns.is_empty()) {
+            (false, true) => (RelationshipKind::Mentee, RelationshipKind::Mentor),
+            (true, false) => (RelationshipKind::Mentor, RelationshipKind::Mentee),
+            _ => (RelationshipKind::Colleague, RelationshipKind::Colleague),
+        };
+        let (first_name, second_name) = (first.get_social_name(), second.get_social_name());
+        // Pairing the same entities twice keeps the existing edge
+        first.form_relationships(&second_name, second_role).ok();
+        second.form_relationships(&first_name, first_role).ok();
+        
+        first.learn("Team Collaboration");
+        second.learn("Team Collaboration");
+        Ok(())
+    }
+    
+    // Complementary skills the learner can pick up, with any missing prerequisites first
+    fn lessons_for(learner: &SkillGraph, teacher: &SkillGraph) -> Vec<String> {
+        let mut lessons = Vec::new();
+        for skill in learner.complementary_skills(teacher) {
+            for step in teacher.learning_path(&skill) {
+                if !lessons.contains(&step) && (step == skill || !learner.has_skill(&step)) {
+                    lessons.push(step);
+                }
+            }
+        }
+        lessons
+    }
+    
+    // Greedily pairs the candidates whose skills complement each other the most
+    fn pair_by_complementary_skills(candidates: &[(String, &SkillGraph)]) -> Vec<(String, String)> {
+        let mut scored = Vec::new();
+        for (i, (first_name, first_skills)) in candidates.iter().enumerate() {
+            for (second_name, second_skills) in &candidates[i + 1..] {
+                let score = first_skills.complementary_skills(second_skills).len()
+                    + second_skills.complementary_skills(first_skills).len();
+                if score > 0 {
+                    scored.push((score, first_name.clone(), second_name.clone()));
+                }
+            }
+        }
+        scored.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.cmp(&b.1)).then_with(|| a.2.cmp(&b.2)));
+        
+        let mut paired: Vec<String> = Vec::new();
+        let mut pairs = Vec::new();
+        for (_, first, second) in scored {
+            if !paired.contains(&first) && !paired.contains(&second) {
+                paired.push(first.clone());
+                paired.push(second.clone());
+                pairs.push((first, second));
+            }
+        }
+        pairs
+    }
+}
+
+struct ITManager;
+
+impl ITManager {
+    // Only depends on Programmable trait
+    fn deploy_software(device: &mut dyn Programmable, catalog: &SoftwareCatalog, name: &str) -> Result<(), DeploymentError> {
+        let package = catalog.latest(name)
+            .ok_or_else(|| format!("{} is not in the software catalog", name))?;
+        Self::deploy_package(device, catalog, package)
+    }
+    
+    // Deploys an exact version, upgrading or downgrading whatever is installed
+    fn deploy_version(device: &mut dyn Programmable, catalog: &SoftwareCatalog, name: &str, version: Version) -> Result<(), DeploymentError> {
+        let package = catalog.find(name, version)
+            .ok_or_else(|| format!("{} v{} is not in the software catalog", name, version))?;
+        Self::deploy_package(device, catalog, package)
+    }
+    
+    fn deploy_package(device: &mut dyn Programmable, catalog: &SoftwareCatalog, package: &SoftwarePackage) -> Result<(), DeploymentError> {
+        println!("Deploying software: {}", package);
+        let plan = catalog.resolve(package, &device.get_installed_packages())?;
+        // Check every package in the plan before installing anything
+        let profile = device.get_device_profile();
+        for step in &plan {
+            let unmet = profile.unmet(step);
+            if !unmet.is_empty() {
+                return Err(DeploymentError::Rejected { package: step.to_string(), unmet });
+            }
+        }
+        for step in plan {
+            device.install_software(step)?;
+        }
+        device.execute_program(&package.name)?;
+        println!("Installed programs: {:?}", device.get_installed_programs());
+        Ok(())
+    }
+    
+    fn rollback_software(device: &mut dyn Programmable, name: &str) -> Result<(), String> {
+        println!("Rolling back software: {}", name);
+        let restored = device.rollback_software(name)?;
+        println!("Active version: {}", restored);
+        Ok(())
+    }
+    
+    // Combines programmable and network capabilities
+    fn setup_networked_system(device: &mut (impl Programmable + NetworkEnabled), network: &VirtualNetwork) -> Result<(), String> {
+        println!("Setting up networked system...");
+        device.connect_to_network(network)?;
+        device.install_software(SoftwarePackage::new("Network Manager", Version::new(1, 0, 0)))?;
+        // Announce the new device to everything already on the network
+        for peer in network.peers(&device.get_network_address()) {
+            device.send_data(&peer, "System ready")?;
+        }
+        println!("Network status: {}", device.get_network_status());
+        Ok(())
+    }
+    
+    // Only depends on NetworkEnabled - sender and receiver can be any devices
+    fn exchange_data(sender: &dyn NetworkEnabled, receiver: &mut dyn NetworkEnabled, data: &str) -> Result<String, String> {
+        sender.send_data(&receiver.get_network_address(), data)?;
+        receiver.receive_data()
+    }
+}
+
+// Records which trait methods each client actually calls, to measure how much
+// of the interface it depends on without using
+struct UsageTracker {
+    clients: RefCell<Vec<ClientUsage>>,
+}
+
+struct ClientUsage {
+    client: &'static str,
+    exposed: Vec<&'static str>,
+    used: Vec<&'static str>,
+}
+
+const WORKABLE_METHODS: &[&str] = &["work", "get_work_efficiency"];
+const COGNITIVE_METHODS: &[&str] = &["think", "learn", "solve_problem", "get_intelligence_level", "get_skills"];
+const BIOLOGICAL_METHODS: &[&str] = &["eat", "sleep", "breathe", "get_health_status"];
+
+impl UsageTracker {
+    fn new() -> Self {
+        UsageTracker { clients: RefCell::new(Vec::new()) }
+    }
+    
+    fn record(&self, client: &'static str, exposed: &[&'static str], method: &'static str) {
+        let mut clients = self.clients.borrow_mut();
+        let index = match clients.iter().position(|c| c.client == client) {
+            Some(index) => index,
+            None => {
+                clients.push(ClientUsage { client, exposed: exposed.to_vec(), used: Vec::new() });
+                clients.len() - 1
+            }
+        };
+        if !clients[index].used.contains(&method) {
+            clients[index].used.push(method);
+        }
+    }
+    
+    // Percentage of all exposed methods, summed over clients, that went unused
+    fn pollution_score(&self) -> f32 {
+        let clients = self.clients.borrow();
+        let exposed: usize = clients.iter().map(|c| c.exposed.len()).sum();
+        let used: usize = clients.iter().map(|c| c.used.len()).sum();
+        if exposed == 0 { 0.0 } else { (exposed - used) as f32 * 100.0 / exposed as f32 }
+    }
+}
+
+impl fmt::Display for UsageTracker {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for usage in self.clients.borrow().iter() {
+            let unused: Vec<&str> = usage.exposed.iter().filter(|m| !usage.used.contains(m)).copied().collect();
+            writeln!(f, "   {}: used {}/{} methods, {:.0}% unused {:?}",
+                     usage.client, usage.used.len(), usage.exposed.len(),
+                     unused.len() as f32 * 100.0 / usage.exposed.len() as f32, unused)?;
+        }
+        write!(f, "   Interface pollution score: {:.0}% of exposed methods unused", self.pollution_score())
+    }
+}
+
+// Instrumentation decorator: forwards calls for whichever segregated traits the
+// wrapped entity implements, recording them against the surface the client sees
+struct Tracked<'a, T: ?Sized> {
+    inner: &'a mut T,
+    client: &'static str,
+    exposed: Vec<&'static str>,
+    tracker: &'a UsageTracker,
+}
+
+impl<'a, T: ?Sized> Tracked<'a, T> {
+    fn new(inner: &'a mut T, client: &'static str, surface: &[&[&'static str]], tracker: &'a UsageTracker) -> Self {
+        Tracked { inner, client, exposed: surface.concat(), tracker }
+    }
+    
+    fn record(&self, method: &'static str) {
+        self.tracker.record(self.client, &self.exposed, method);
+    }
+}
+
+impl<T: Workable + ?Sized> Workable for Tracked<'_, T> {
+    fn work(&mut self) { self.record("work"); self.inner.work() }
+    fn get_work_efficiency(&self) -> f32 { self.record("get_work_efficiency"); self.inner.get_work_efficiency() }
+}
+
+impl<T: Cognitive + ?Sized> Cognitive for Tracked<'_, T> {
+    fn think(&mut self) { self.record("think"); self.inner.think() }
+    fn learn(&mut self, skill: &str) { self.record("learn"); self.inner.learn(skill) }
+    fn solve_problem(&self, problem: &str) -> String { self.record("solve_problem"); self.inner.solve_problem(problem) }
+    fn get_intelligence_level(&self) -> i32 { self.record("get_intelligence_level"); self.inner.get_intelligence_level() }
+    fn get_skills(&self) -> &SkillGraph { self.record("get_skills"); self.inner.get_skills() }
+}
+
+impl<T: Biological + ?Sized> Biological for Tracked<'_, T> {
+    fn eat(&mut self) -> Result<(), String> { self.record("eat"); self.inner.eat() }
+    fn sleep(&mut self) -> Result<(), String> { self.record("sleep"); self.inner.sleep() }
+    fn breathe(&mut self) -> Result<(), String> { self.record("breathe"); self.inner.breathe() }
+    fn get_health_status(&self) -> String { self.record("get_health_status"); self.inner.get_health_status() }
+}
+
+// Actor runtime: each entity lives on its own thread behind a mailbox.
+// Every segregated trait maps onto one message protocol, and an actor is
+// spawned with the handler for the protocol its entity can actually serve.
+
+enum Envelope<M> {
+    Deliver(M),
+    Shutdown,
+}
+
+struct Actor<M: Send + 'static> {
+    name: String,
+    mailbox: Sender<Envelope<M>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl<M: Send + 'static> Actor<M> {
+    // The entity is built on the actor's own thread, so types holding Rc
+    // handles (robots on a VirtualNetwork) never have to be Send
+    fn spawn<E, F, H>(name: &str, factory: F, handler: H) -> Self
+    where
+        F: FnOnce() -> E + Send + 'static,
+        H: Fn(&mut E, M) + Send + 'static,
+    {
+        let (mailbox, inbox) = mpsc::channel();
+        let thread = thread::spawn(move || {
+            let mut entity = factory();
+            // Ends on Shutdown, or when every sender has been dropped
+            while let Ok(Envelope::Deliver(message)) = inbox.recv() {
+                handler(&mut entity, message);
+            }
+        });
+        Actor {
+            name: name.to_string(),
+            mailbox,
+            thread: Some(thread),
+        }
+    }
+    
+    fn send(&self, message: M) -> Result<(), String> {
+        self.mailbox.send(Envelope::Deliver(message))
+            .map_err(|_| format!("{} has stopped", self.name))
+    }
+    
+    // Messages already queued are handled before the thread exits
+    fn shutdown(&mut self) -> Result<(), String> {
+        let Some(thread) = self.thread.take() else {
+            return Ok(());
+        };
+        // A send error means the thread already exited; join still reports a panic
+        let _ = self.mailbox.send(Envelope::Shutdown);
+        thread.join().map_err(|_| format!("{} panicked", self.name))
+    }
+}
+
+impl<M: Send + 'static> Drop for Actor<M> {
+    fn drop(&mut self) {
+        let _ = self.shutdown();
+    }
+}
+
+// Workable protocol
+enum WorkMessage {
+    Assign { task: String, reply: Sender<f32> },
+}
+
+fn handle_work(worker: &mut dyn Workable, message: WorkMessage) {
+    match message {
+        WorkMessage::Assign { task, reply } => {
+            println!("[actor] assigned: {}", task);
+            worker.work();
+            let _ = reply.send(worker.get_work_efficiency());
+        }
+    }
+}
+
+// Biological protocol
+enum CareMessage {
+    Care { reply: Sender<Result<String, String>> },
+}
+
+fn handle_care(being: &mut dyn Biological, message: CareMessage) {
+    match message {
+        CareMessage::Care { reply } => {
+            let result = BiologicalCareProvider::provide_care(being).map(|()| being.get_health_status());
+            let _ = reply.send(result);
+        }
+    }
+}
+
+// Mechanical protocol
+enum MaintenanceMessage {
+    Service { reply: Sender<Result<String, String>> },
+}
+
+fn handle_maintenance(device: &mut dyn Mechanical, message: MaintenanceMessage) {
+    match message {
+        MaintenanceMessage::Service { reply } => {
+            let result = device.perform_maintenance().map(|()| device.get_mechanical_status());
+            let _ = reply.send(result);
+        }
+    }
+}
+
+fn main() {
+    println!("=== Interface Segregation Principle - CORRECT (Rust) ===");
+    println!();
+    
+    println!("1. Creating entities with segregated traits:");
+    let mut person = Person::new("Alice".to_string());
+    let mut robot = IndustrialRobot::new("R2D2-Industrial".to_string());
+    let mut humanoid = HumanoidRobot::new("ASIMO-Advanced".to_string());
+    
+    println!("\n2. Work management (only needs Workable trait):");
+    WorkManager::assign_work(&mut person, "Write documentation");
+    WorkManager::assign_work(&mut robot, "Assemble components");
+    WorkManager::assign_work(&mut humanoid, "Assist customers");
+    
+    println!("\n3. Complex work (needs Workable + Cognitive):");
+    WorkManager::assign_complex_work(&mut person, "Design new product");
+    WorkManager::assign_complex_work(&mut robot, "Optimize assembly line");
+    WorkManager::assign_complex_work(&mut humanoid, "Develop social protocols");
+    
+    println!("\n3a. Efficiency report across a mixed fleet (only needs Workable):");
+    let mut report = EfficiencyReport::new();
+    report.record("Alice", &person);
+    report.record("R2D2-Industrial", &robot);
+    report.record("ASIMO-Advanced", &humanoid);
+    println!("{}", report);
+    
+    println!("\n4. Biological care (only for entities with Biological trait):");
+    // Only Person implements Biological - no errors!
+    if let Ok(()) = BiologicalCareProvider::provide_care(&mut person) {
+        println!("Biological care provided successfully");
+    }
+    
+    println!("\n4a. Wellbeing (needs Biological + Physical):");
+    let mut colleague = Person::new("Bob".to_string());
+    for _ in 0..3 {
+        colleague.work();
+    }
+    println!("{} after a long shift: {}, efficiency {:.2}", colleague, colleague.get_emotional_state(), colleague.get_work_efficiency());
+    match BiologicalCareProvider::provide_wellness_care(&mut colleague) {
+        Ok(improvement) => println!("Wellness care improved mood by {}, efficiency now {:.2}", improvement, colleague.get_work_efficiency()),
+        Err(e) => println!("Error: {}", e),
+    }
+    
+    println!("\n5. Technical support (only for entities with Mechanical trait):");
+    // Only robots implement Mechanical
+    let mut support = TechnicalSupportManager::new(500);
+    if let Ok(()) = support.perform_maintenance(&mut robot) {
+        println!("Robot maintenance completed");
+    }
+    if let Ok(()) = support.perform_maintenance(&mut humanoid) {
+        println!("Humanoid maintenance completed");
+    }
+    
+    println!("\n6. Electronic device servicing (needs Mechanical + Rechargeable):");
+    if let Ok(()) = support.service_electronic_device(&mut robot) {
+        println!("Robot servicing completed");
+    }
+    if let Ok(()) = support.service_electronic_device(&mut humanoid) {
+        println!("Humanoid servicing completed");
+    }
+    
+    println!("\n6a. Charging state machine (start, tick, interrupt):");
+    robot.work();
+    robot.work();
+    println!("Robot battery {}%, state: {}", robot.get_battery_level(), robot.get_charging_state());
+    if let Err(e) = robot.start_charging(0) {
+        println!("Charger refused: {}", e);
+    }
+    if let Ok(()) = robot.start_charging(2) {
+        let state = robot.tick_charge(4);
+        println!("After 4 minutes: {}% ({})", robot.get_battery_level(), state);
+        if let Err(e) = robot.start_charging(10) {
+            println!("Charger refused: {}", e);
+        }
+        let state = robot.stop_charging();
+        println!("Unplugged at {}%, state: {}, charging: {}", robot.get_battery_level(), state, robot.is_charging());
+    }
+    robot.recharge();
+    println!("After standard recharge: {}% ({})", robot.get_battery_level(), robot.get_charging_state());
+    if let Err(e) = robot.start_charging(STANDARD_CHARGE_RATE) {
+        println!("Charger refused: {}", e);
+    }
+    
+    println!("\n6b. Maintenance history and service prediction:");
+    for _ in 0..3 {
+        support.advance_hours(40);
+        for _ in 0..4 {
+            robot.work();
+            robot.recharge();
+        }
+        support.record_diagnostics(&robot);
+    }
+    let robot_id = robot.get_device_id();
+    if let Some(log) = support.maintenance_log(&robot_id) {
+        println!("{}: {} snapshots, {} services, wear {:.0}%",
+                 log.device_id, log.snapshots.len(), log.services.len(), log.latest_wear() * 100.0);
+        for snapshot in log.snapshots_between(40, 120) {
+            println!("  [hour {}] {}", snapshot.timestamp, snapshot.report);
+        }
+    }
+    if let Some(hour) = support.predict_next_service(&robot_id) {
+        println!("Next service for {} predicted at hour {}", robot_id, hour);
+    }
+    if let Some(hour) = support.predict_next_service(&humanoid.get_device_id()) {
+        println!("Next service for {} scheduled at hour {}", humanoid.get_device_id(), hour);
+    }
+    
+    println!("\n7. Social interaction (only for entities with Social trait):");
+    SocialCoordinator::facilitate_interaction(&mut person);
+    SocialCoordinator::facilitate_interaction(&mut humanoid);  // Humanoid has social capabilities
+    
+    println!("\n7a. Collaborative work paired by complementary skills:");
+    let mut colleague = Person::new("Bob".to_string());
+    colleague.learn("Statistics");
+    if let Err(e) = person.form_relationships(&colleague.get_social_name(), RelationshipKind::Friend)
+        .and_then(|_| person.form_relationships(&colleague.get_social_name(), RelationshipKind::Friend)) {
+        println!("Relationship not added: {}", e);
+    }
+    let candidates = vec![
+        (person.get_social_name(), person.get_skills()),
+        (colleague.get_social_name(), colleague.get_skills()),
+        (humanoid.get_social_name(), humanoid.get_skills()),
+    ];
+    let pairs = SocialCoordinator::pair_by_complementary_skills(&candidates);
+    println!("Suggested pairs: {:?}", pairs);
+    if let Ok(()) = SocialCoordinator::organize_collaborative_work(&mut person, &mut humanoid) {
+        println!("{} skills: {:?}", person.get_social_name(), person.get_skills().skill_names());
+        println!("{} now knows Data Analysis at {}%", person.get_social_name(),
+                 person.get_skills().proficiency("Data Analysis"));
+        for relationship in person.get_relationships().relationships_of(&person.get_social_name()) {
+            println!("{} -> {}: {}", relationship.from, relationship.to, relationship.kind);
+        }
+        println!("{} has {} relationships", humanoid.get_social_name(), humanoid.get_relationships().len());
+    }
+    
+    println!("\n8. IT management (for programmable devices):");
+    let mut catalog = SoftwareCatalog::new();
+    catalog.publish(SoftwarePackage::new("Robot Runtime", Version::new(2, 0, 0)));
+    catalog.publish(SoftwarePackage::new("Robot Runtime", Version::new(2, 4, 0)));
+    catalog.publish(SoftwarePackage::new("Robot Runtime", Version::new(3, 0, 0)));
+    catalog.publish(SoftwarePackage::new("Quality Control", Version::new(3, 0, 0))
+        .with_dependency("Robot Runtime", Version::new(2, 1, 0)));
+    catalog.publish(SoftwarePackage::new("Advanced Social AI", Version::new(2, 1, 0))
+        .with_dependency("Robot Runtime", Version::new(2, 0, 0)));
+    
+    if let Ok(()) = ITManager::deploy_software(&mut robot, &catalog, "Quality Control") {
+        println!("Software deployed to robot");
+    }
+    if let Ok(()) = ITManager::deploy_software(&mut humanoid, &catalog, "Advanced Social AI") {
+        println!("Software deployed to humanoid");
+    }
+    
+    println!("\n8a. Versioned upgrades, conflicts and rollback:");
+    // Quality Control needs Robot Runtime ^2.1, so a major upgrade is refused
+    if let Err(e) = ITManager::deploy_version(&mut robot, &catalog, "Robot Runtime", Version::new(3, 0, 0)) {
+        println!("Upgrade refused: {}", e);
+    }
+    // Downgrading below 2.1 would also break Quality Control
+    if let Err(e) = ITManager::deploy_version(&mut robot, &catalog, "Robot Runtime", Version::new(2, 0, 0)) {
+        println!("Downgrade refused: {}", e);
+    }
+    // The humanoid only needs ^2.0, so it can go from 2.4.0 back to 2.0.0 and return
+    if let Ok(()) = ITManager::deploy_version(&mut humanoid, &catalog, "Robot Runtime", Version::new(2, 0, 0)) {
+        println!("Humanoid runtime pinned to 2.0.0");
+    }
+    if let Ok(()) = ITManager::rollback_software(&mut humanoid, "Robot Runtime") {
+        println!("Humanoid runtime rollback completed");
+    }
+    if let Err(e) = ITManager::rollback_software(&mut robot, "Quality Control") {
+        println!("Rollback refused: {}", e);
+    }
+    
+    println!("\n8b. Capability-based deployment policy:");
+    catalog.publish(SoftwarePackage::new("Fleet Telemetry", Version::new(1, 0, 0))
+        .requires(Requirement::Capability("NetworkEnabled"))
+        .requires(Requirement::MinBattery(50)));
+    catalog.publish(SoftwarePackage::new("Heavy Lift Control", Version::new(1, 2, 0))
+        .requires(Requirement::Capability("Mechanical"))
+        .requires(Requirement::MinBattery(95)));
+    println!("Humanoid profile: {:?}", humanoid.get_device_profile());
+    if let Ok(()) = ITManager::deploy_software(&mut robot, &catalog, "Fleet Telemetry") {
+        println!("Telemetry deployed to robot");
+    }
+    humanoid.work();
+    match ITManager::deploy_software(&mut humanoid, &catalog, "Heavy Lift Control") {
+        Ok(()) => println!("Heavy lift control deployed to humanoid"),
+        Err(DeploymentError::Rejected { package, unmet }) => {
+            println!("Policy rejected {} ({} unmet requirement(s)): {:?}", package, unmet.len(), unmet);
+        }
+        Err(e) => println!("Deployment failed: {}", e),
+    }
+    
+    println!("\n9. Network setup (for networked programmable devices):");
+    let network = VirtualNetwork::new("Factory Network");
+    if let Ok(()) = ITManager::setup_networked_system(&mut robot, &network) {
+        println!("Robot network setup completed");
+    }
+    if let Ok(()) = ITManager::setup_networked_system(&mut humanoid, &network) {
+        println!("Humanoid network setup completed");
+    }
+    // The humanoid announced itself to the robot during setup
+    if let Ok(data) = robot.receive_data() {
+        println!("Robot got announcement: {}", data);
+    }
+    
+    println!("\n9a. Data exchange over the virtual network:");
+    if let Ok(data) = ITManager::exchange_data(&robot, &mut humanoid, "Batch 42 assembled") {
+        println!("Humanoid processed: {}", data);
+    }
+    
+    network.set_latency(3);
+    if let Err(e) = ITManager::exchange_data(&humanoid, &mut robot, "Customer feedback ready") {
+        println!("Not delivered yet: {}", e);
+    }
+    network.advance(3);
+    if let Ok(data) = robot.receive_data() {
+        println!("Delivered after latency: {}", data);
+    }
+    network.set_latency(0);
+    
+    network.inject_failure(&humanoid.get_network_address());
+    if let Err(e) = robot.send_data(&humanoid.get_network_address(), "Status ping") {
+        println!("Delivery failure: {}", e);
+    }
+    network.restore_link(&humanoid.get_network_address());
+    
+    if let Ok(()) = humanoid.disconnect_from_network() {
+        println!("Humanoid status: {}", humanoid.get_network_status());
+    }
+    if let Err(e) = robot.send_data(&humanoid.get_network_address(), "Are you there?") {
+        println!("Routing after disconnect: {}", e);
+    }
+    println!("Devices on {}: {:?}", network.name(), network.peers(""));
+    
+    println!("\n10. Assembling a new entity at runtime (EntityBuilder):");
+    let mut drone = EntityBuilder::new("Courier-Drone")
+        .with_work("is delivering parcels by air", 6, 0.02, 1.0)
+        .with_mechanics("Drone", 20)
+        .with_power()
+        .with_network()
+        .build();
+    println!("{} capabilities: {:?}", drone.name(), drone.capabilities());
+    if let Some(worker) = drone.workable() {
+        WorkManager::assign_work(worker, "Deliver spare parts");
+    }
+    if let Some(device) = drone.mechanical() {
+        if let Ok(()) = support.perform_maintenance(device) {
+            println!("Drone maintenance completed");
+        }
+    }
+    if let Some(battery) = drone.rechargeable() {
+        battery.recharge();
+        println!("Drone battery: {}%", battery.get_battery_level());
+    }
+    if let Some(device) = drone.network_enabled() {
+        if let Ok(()) = device.connect_to_network(&network) {
+            if let Ok(()) = device.send_data(&robot.get_network_address(), "Parcel delivered") {
+                robot.receive_data().ok();
+            }
+        }
+    }
+    if drone.social().is_none() && drone.cognitive().is_none() {
+        println!("{} has no Social or Cognitive capability - coordinators simply can't be handed one", drone.name());
+    }
+    
+    let mut tutor = EntityBuilder::new("Tutor-Bot")
+        .with_cognition(&["Lesson Planning"], SkillGraph::new().with_skill("Statistics", 95, &[]), 2)
+        .with_social(&["Classroom Etiquette"], 3)
+        .with_software()
+        .build();
+    println!("{} capabilities: {:?}", tutor.name(), tutor.capabilities());
+    if let Some(participant) = tutor.social() {
+        SocialCoordinator::facilitate_interaction(participant);
+    }
+    if let Some(thinker) = tutor.cognitive() {
+        println!("{}", thinker.solve_problem("Explain standard deviation"));
+    }
+    if let Some(device) = tutor.programmable() {
+        if let Ok(()) = ITManager::deploy_software(device, &catalog, "Robot Runtime") {
+            println!("Software deployed to tutor");
+        }
+        // Tutor-Bot has no NetworkEnabled or Rechargeable component
+        if let Err(e) = ITManager::deploy_software(device, &catalog, "Fleet Telemetry") {
+            println!("Deployment refused: {}", e);
         }
     }
-}
-
-impl Workable for HumanoidRobot {
-    fn work(&mut self) {
-        if !self.is_operational {
-            println!("{} is not operational!", self.model);
-            return;
-        }
-        println!("{} humanoid robot is performing human-like work tasks", self.model);
-        self.battery_level -= 8;
+    
+    println!("\n10a. Factories: configured entities from specifications, and matched kits:");
+    let spec = factory::RobotSpec {
+        model: "Kuka-9".to_string(),
+        battery_level: 60,
+        skills: vec![("Painting".to_string(), 70)],
+        software: vec![("Paint Planner".to_string(), Version::new(2, 0, 0))],
+    };
+    match factory::IndustrialRobotFactory.produce(&spec) {
+        Ok(kuka) => println!("{}: battery {}%, programs {:?}", kuka, kuka.get_battery_level(), kuka.get_installed_programs()),
+        Err(e) => println!("Robot refused: {}", e),
+    }
+    let tired = factory::PersonSpec { name: "Bo".to_string(), energy: 140, skills: vec![] };
+    if let Err(e) = factory::PersonFactory.produce(&tired) {
+        println!("Person refused: {}", e);
+    }
+    let kit_factories: [&dyn factory::KitFactory; 2] = [&factory::IndustrialKit, &factory::DomesticKit];
+    for (serial, (kit_factory, operator)) in (101..).zip(kit_factories.into_iter().zip(["Ines", "Dara"])) {
+        match factory::Kit::assemble(kit_factory, operator, serial) {
+            Ok(mut kit) => {
+                println!("{}: {} operates {}, untrained on {:?}", kit.name, kit.operator, kit.robot.get_device_id(), kit.untrained_software());
+                WorkManager::assign_work(kit.robot.as_mut(), "First shift");
+            }
+            Err(e) => println!("{} refused: {}", kit_factory.kit_name(), e),
+        }
+    }
+    
+    println!("\n11. Demonstrating trait specialization:");
+    println!("Person traits: Workable, Biological, Cognitive, Social, Physical");
+    println!("Robot traits: Workable, Cognitive, Mechanical, Rechargeable, Programmable, NetworkEnabled");
+    println!("Humanoid traits: Workable, Cognitive, Social, Mechanical, Rechargeable, Programmable, NetworkEnabled");
+    println!("Humanoid is assembled with compose_entity! from WorkUnit, AiCore, SocialModule, MechanicalFrame,");
+    println!("PowerCell, SoftwareStack and NetworkInterface components - no hand-written trait impls");
+    
+    println!("\n11a. Measuring interface pollution (which methods each client calls):");
+    let tracker = UsageTracker::new();
+    WorkManager::assign_work(&mut Tracked::new(&mut robot, "WorkManager::assign_work", &[WORKABLE_METHODS], &tracker), "Weld chassis");
+    WorkManager::assign_complex_work(&mut Tracked::new(&mut humanoid, "WorkManager::assign_complex_work", &[WORKABLE_METHODS, COGNITIVE_METHODS], &tracker), "Plan shift rota");
+    let _ = BiologicalCareProvider::provide_care(&mut Tracked::new(&mut person, "BiologicalCareProvider::provide_care", &[BIOLOGICAL_METHODS], &tracker));
+    println!("{}", tracker);
+    
+    println!("\n12. Actor runtime (one mailbox protocol per trait):");
+    let workers = vec![
+        Actor::spawn("Carol", || Person::new("Carol".to_string()), |p: &mut Person, m| handle_work(p, m)),
+        Actor::spawn("T-800", || IndustrialRobot::new("T-800".to_string()), |r: &mut IndustrialRobot, m| handle_work(r, m)),
+        Actor::spawn("Pepper", || HumanoidRobot::new("Pepper".to_string()), |h: &mut HumanoidRobot, m| handle_work(h, m)),
+    ];
+    let (reply, efficiencies) = mpsc::channel();
+    for worker in &workers {
+        if let Err(e) = worker.send(WorkMessage::Assign { task: "Sort incoming mail".to_string(), reply: reply.clone() }) {
+            println!("Error: {}", e);
+        }
+    }
+    drop(reply);
+    // Replies arrive in whatever order the threads finish
+    let mut results: Vec<f32> = efficiencies.iter().collect();
+    results.sort_by(|a, b| b.total_cmp(a));
+    println!("Efficiencies reported by {} worker actors: {:?}", workers.len(), results);
+    
+    let mut carer = Actor::spawn("Carol", || Person::new("Carol".to_string()), |p: &mut Person, m| handle_care(p, m));
+    let mut mechanic = Actor::spawn("T-800", || IndustrialRobot::new("T-800".to_string()), |r: &mut IndustrialRobot, m| handle_maintenance(r, m));
+    // Actor::spawn(.., || IndustrialRobot::new(..), |r: &mut IndustrialRobot, m| handle_care(r, m))
+    // would not compile: IndustrialRobot does not implement Biological
+    let (reply, care) = mpsc::channel();
+    if carer.send(CareMessage::Care { reply }).is_ok() {
+        println!("Care result: {:?}", care.recv());
+    }
+    let (reply, service) = mpsc::channel();
+    if mechanic.send(MaintenanceMessage::Service { reply }).is_ok() {
+        println!("Service result: {:?}", service.recv());
+    }
+    
+    // Explicit shutdown joins each thread; the worker actors are shut down on drop
+    for (name, result) in [("carer", carer.shutdown()), ("mechanic", mechanic.shutdown())] {
+        println!("{} actor stopped: {:?}", name, result);
+    }
+    if let Err(e) = carer.send(CareMessage::Care { reply: mpsc::channel().0 }) {
+        println!("Send after shutdown refused: {}", e);
+    }
+    drop(workers);
+    println!("All actors stopped");
+    
+    println!("\n13. Observer: robot telemetry (the simulation needs Workable + Mechanical + Rechargeable):");
+    let mut simulation = observer::TelemetrySimulation::new();
+    simulation.add_robot(IndustrialRobot::new("Kuka-7".to_string()));
+    simulation.add_robot(HumanoidRobot::new("Atlas-3".to_string()));
+    let dashboard = Rc::new(observer::ConsoleDashboard::new());
+    let alerts = Rc::new(observer::AlertMonitor::new(30, 60.0));
+    let metrics = Rc::new(observer::MetricsRecorder::new());
+    let dashboard_subscription = simulation.hub().subscribe(&dashboard);
+    simulation.hub().subscribe(&alerts);
+    simulation.hub().subscribe(&metrics);
+    simulation.run(9);
+    simulation.power_down("Atlas-3");
+    simulation.run(1);
+    
+    // Unsubscribing is explicit; dropping the last Rc is enough too
+    let raised: Vec<String> = alerts.alerts().iter().map(|alert| format!("{:?} {}@t{}", alert.kind, alert.device_id, alert.tick)).collect();
+    println!("Alerts raised once per crossing: {:?}", raised);
+    simulation.hub().unsubscribe(dashboard_subscription);
+    drop(alerts);
+    simulation.run(3);
+    println!("Observers left: {} (dashboard unsubscribed, alert monitor dropped)", simulation.hub().observer_count());
+    println!("Dashboard lines: {}", dashboard.lines().len());
+    print!("{}", metrics);
+    if let Some(kuka) = metrics.metrics("Kuka-7") {
+        println!("Kuka-7 was charging for {} of {} ticks", kuka.ticks_by_status.get(&observer::RobotStatus::Charging).unwrap_or(&0), kuka.readings);
     }
     
-    fn get_work_efficiency(&self) -> f32 {
-        if self.is_operational { 
-            (self.battery_level as f32 / 100.0) * 1.1  // Efficient but human-like
-        } else { 
-            0.0 
+    // Only with the `lifecycle` feature does IndustrialRobot carry a state machine
+    #[cfg(feature = "lifecycle")]
+    {
+        use state::{LifecycleEvent, RobotState};
+        
+        println!("\n14. State: robot lifecycle (built with the lifecycle feature):");
+        for robot_state in RobotState::ALL {
+            let allowed: Vec<String> = Activity::ALL.iter().filter(|activity| robot_state.allows(**activity)).map(|activity| activity.to_string()).collect();
+            println!("  {:<12} allows: {}", robot_state.to_string(), if allowed.is_empty() { "nothing".to_string() } else { allowed.join(", ") });
+        }
+        let mut fanuc = IndustrialRobot::new("Fanuc-9".to_string());
+        fanuc.work();
+        fanuc.handle_event(LifecycleEvent::Fail).ok();
+        fanuc.work();
+        if let Err(e) = fanuc.execute_program("Weld seam") {
+            println!("Program refused: {}", e);
+        }
+        // Maintenance goes through the Maintenance state and clears the fault
+        fanuc.perform_maintenance().ok();
+        println!("After maintenance: {}", fanuc.lifecycle_state());
+        fanuc.power_down();
+        if let Err(e) = fanuc.perform_maintenance() {
+            println!("Maintenance refused: {}", e);
         }
+        // A failed boot is reset and retried
+        for event in [LifecycleEvent::PowerOn, LifecycleEvent::Fail, LifecycleEvent::BootComplete, LifecycleEvent::Reset, LifecycleEvent::BootComplete] {
+            if let Err(e) = fanuc.handle_event(event) {
+                println!("Event refused: {}", e);
+            }
+        }
+        println!("{}", fanuc.run_diagnostics());
+        println!("{} transitions since delivery", fanuc.lifecycle.history().len());
     }
+    
+    println!("\n=== Benefits of Segregated Traits ===");
+    println!("✓ Each entity only implements traits it actually needs");
+    println!("✓ Clients depend only on the methods they use");
+    println!("✓ Easy to add new entities with different trait combinations");
+    println!("✓ No empty/error implementations required");
+    println!("✓ Compile-time safety ensures trait contracts are met");
+    println!("✓ Clear separation of concerns");
+    println!("✓ Follows Interface Segregation Principle perfectly");
 }
 
-impl Cognitive for HumanoidRobot {
-    fn think(&mut self) {
-        println!("{} is processing with advanced AI and learning algorithms", self.model);
-        self.battery_level -= 3;
+// Tests for SoftwareInventory and SoftwareCatalog: dependency conflicts,
+// dependency resolution and rollback
+#[cfg(test)]
+mod software_tests {
+    use super::*;
+    
+    fn package(name: &str, major: u32, minor: u32) -> SoftwarePackage {
+        SoftwarePackage::new(name, Version::new(major, minor, 0))
     }
     
-    fn learn(&mut self, skill: &str) {
-        println!("{} is learning social skill: {}", self.model, skill);
-        self.programs.push(format!("Social Algorithm: {}", skill));
+    fn names(packages: &[SoftwarePackage]) -> Vec<String> {
+        packages.iter().map(|p| p.to_string()).collect()
     }
     
-    fn solve_problem(&self, problem: &str) -> String {
-        format!("{} is solving '{}' using AI with human-like reasoning", self.model, problem)
+    #[test]
+    fn install_needs_compatible_dependencies() {
+        let mut inventory = SoftwareInventory::default();
+        let app = package("app", 1, 0).with_dependency("runtime", Version::new(2, 1, 0));
+        assert!(inventory.install(app.clone()).unwrap_err().starts_with("Missing dependency"));
+        
+        inventory.install(package("runtime", 2, 0)).unwrap();
+        assert!(inventory.install(app.clone()).unwrap_err().starts_with("Conflict"), "2.0 is older than 2.1");
+        inventory.install(package("runtime", 2, 4)).unwrap();
+        inventory.install(app.clone()).unwrap();
+        assert!(inventory.install(app).unwrap_err().contains("already installed"));
+        assert_eq!(inventory.packages().len(), 2);
     }
     
-    fn get_intelligence_level(&self) -> i32 {
-        (self.programs.len() as i32 * 30) + (self.social_protocols.len() as i32 * 10)
+    #[test]
+    fn upgrade_that_breaks_a_dependent_is_refused() {
+        let mut inventory = SoftwareInventory::default();
+        inventory.install(package("runtime", 2, 0)).unwrap();
+        inventory.install(package("app", 1, 0).with_dependency("runtime", Version::new(2, 0, 0))).unwrap();
+        
+        let error = inventory.install(package("runtime", 3, 0)).unwrap_err();
+        assert!(error.starts_with("Conflict: app v1.0.0 requires runtime ^2.0.0"), "{}", error);
+        assert_eq!(names(&inventory.packages()), vec!["app v1.0.0", "runtime v2.0.0"]);
+        inventory.install(package("runtime", 2, 5)).unwrap();
     }
-}
-
-impl Social for HumanoidRobot {
-    fn socialize(&mut self) {
-        println!("{} is engaging in programmed social interactions", self.model);
-        self.battery_level -= 5;
+    
+    #[test]
+    fn rollback_restores_each_previous_version_in_turn() {
+        let mut inventory = SoftwareInventory::default();
+        for minor in 0..3 {
+            inventory.install(package("runtime", 1, minor)).unwrap();
+        }
+        assert_eq!(inventory.rollback("runtime").unwrap().version, Version::new(1, 1, 0));
+        assert_eq!(inventory.rollback("runtime").unwrap().version, Version::new(1, 0, 0));
+        assert!(inventory.rollback("runtime").unwrap_err().starts_with("No previous version"));
+        assert!(inventory.rollback("editor").is_err());
+        assert_eq!(names(&inventory.packages()), vec!["runtime v1.0.0"]);
     }
     
-    fn communicate(&self, message: &str) -> String {
-        format!("{} communicates: '{}'", self.model, message)
+    #[test]
+    fn rollback_that_breaks_a_dependent_is_refused() {
+        let mut inventory = SoftwareInventory::default();
+        inventory.install(package("runtime", 1, 0)).unwrap();
+        inventory.install(package("runtime", 2, 0)).unwrap();
+        inventory.install(package("app", 1, 0).with_dependency("runtime", Version::new(2, 0, 0))).unwrap();
+        
+        assert!(inventory.rollback("runtime").unwrap_err().starts_with("Conflict"));
+        assert_eq!(names(&inventory.packages()), vec!["app v1.0.0", "runtime v2.0.0"]);
     }
     
-    fn form_relationships(&mut self) -> Result<(), String> {
-        println!("{} is forming programmed social bonds", self.model);
-        self.social_protocols.push("New Social Protocol".to_string());
-        Ok(())
+    #[test]
+    fn resolve_installs_dependencies_first_and_skips_installed_ones() {
+        let mut catalog = SoftwareCatalog::new();
+        catalog.publish(package("runtime", 1, 2));
+        catalog.publish(package("runtime", 1, 5));
+        catalog.publish(package("runtime", 2, 0));
+        catalog.publish(package("codec", 1, 0).with_dependency("runtime", Version::new(1, 1, 0)));
+        let app = package("app", 1, 0)
+            .with_dependency("codec", Version::new(1, 0, 0))
+            .with_dependency("runtime", Version::new(1, 0, 0));
+        catalog.publish(app.clone());
+        
+        // The newest compatible runtime, not the newest one
+        let plan = catalog.resolve(&app, &[]).unwrap();
+        assert_eq!(names(&plan), vec!["runtime v1.5.0", "codec v1.0.0", "app v1.0.0"]);
+        
+        let plan = catalog.resolve(&app, &[package("runtime", 1, 3)]).unwrap();
+        assert_eq!(names(&plan), vec!["codec v1.0.0", "app v1.0.0"]);
+        assert_eq!(catalog.latest("runtime").unwrap().version, Version::new(2, 0, 0));
+        assert!(catalog.find("runtime", Version::new(1, 3, 0)).is_none());
+    }
+    
+    #[test]
+    fn resolve_reports_conflicts_cycles_and_unpublished_dependencies() {
+        let mut catalog = SoftwareCatalog::new();
+        catalog.publish(package("runtime", 1, 0));
+        catalog.publish(package("runtime", 2, 0));
+        catalog.publish(package("codec", 1, 0).with_dependency("runtime", Version::new(2, 0, 0)));
+        let conflicting = package("app", 1, 0)
+            .with_dependency("runtime", Version::new(1, 0, 0))
+            .with_dependency("codec", Version::new(1, 0, 0));
+        assert!(catalog.resolve(&conflicting, &[]).unwrap_err().contains("already planned"));
+        
+        catalog.publish(package("ping", 1, 0).with_dependency("pong", Version::new(1, 0, 0)));
+        catalog.publish(package("pong", 1, 0).with_dependency("ping", Version::new(1, 0, 0)));
+        let ping = catalog.latest("ping").unwrap().clone();
+        assert!(catalog.resolve(&ping, &[]).unwrap_err().starts_with("Circular dependency"));
+        
+        let orphan = package("viewer", 1, 0).with_dependency("gpu-driver", Version::new(1, 0, 0));
+        assert!(catalog.resolve(&orphan, &[]).unwrap_err().starts_with("No published version"));
     }
 }
 
-impl Mechanical for HumanoidRobot {
-    fn power_down(&mut self) {
-        println!("{} is gracefully shutting down with social protocols", self.model);
-        self.is_operational = false;
+// Tests for VirtualNetwork: routing, latency, link failures and disconnects,
+// both on the hub directly and through NetworkEnabled devices
+#[cfg(test)]
+mod virtual_network_tests {
+    use super::*;
+    
+    fn network_with(addresses: &[&str]) -> VirtualNetwork {
+        let network = VirtualNetwork::new("Test LAN");
+        for address in addresses {
+            network.register(address).unwrap();
+        }
+        network
     }
     
-    fn run_diagnostics(&self) -> String {
-        let status = if self.is_operational { "Operational" } else { "Offline" };
-        format!("Humanoid: {}, Battery: {}%, Status: {}, Programs: {}, Social: {}", 
-                self.model, self.battery_level, status, self.programs.len(), self.social_protocols.len())
+    #[test]
+    fn packets_reach_only_their_recipient_in_order() {
+        let network = network_with(&["a", "b", "c"]);
+        network.route("a", "b", "first").unwrap();
+        network.route("c", "b", "second").unwrap();
+        
+        let first = network.receive("b").unwrap();
+        assert_eq!((first.from.as_str(), first.payload.as_str()), ("a", "first"));
+        assert_eq!(network.receive("b").unwrap().payload, "second");
+        assert_eq!(network.receive("b").unwrap_err(), "No data available");
+        assert_eq!(network.receive("c").unwrap_err(), "No data available");
+        assert_eq!(network.peers("a"), vec!["b", "c"]);
     }
     
-    fn perform_maintenance(&mut self) -> Result<(), String> {
-        println!("{} is performing maintenance on social and mechanical systems", self.model);
-        self.battery_level = (self.battery_level + 15).min(100);
-        Ok(())
+    #[test]
+    fn unknown_addresses_are_rejected() {
+        let network = network_with(&["a"]);
+        assert!(network.register("a").unwrap_err().contains("already registered"));
+        assert!(network.route("a", "ghost", "hi").unwrap_err().contains("unreachable"));
+        assert!(network.route("ghost", "a", "hi").unwrap_err().contains("not registered"));
+        assert!(network.receive("ghost").is_err());
+        assert!(network.unregister("ghost").is_err());
     }
     
-    fn get_mechanical_status(&self) -> String {
-        if self.is_operational { "All humanoid systems operational".to_string() } 
-        else { "Systems offline".to_string() }
+    #[test]
+    fn latency_holds_packets_until_the_clock_catches_up() {
+        let network = network_with(&["a", "b"]);
+        network.set_latency(3);
+        network.route("a", "b", "delayed").unwrap();
+        network.advance(2);
+        assert!(network.receive("b").is_err());
+        network.advance(1);
+        assert_eq!(network.receive("b").unwrap().payload, "delayed");
     }
-}
-
-impl Rechargeable for HumanoidRobot {
-    fn recharge(&mut self) {
-        println!("{} is recharging with energy-efficient protocols", self.model);
-        self.is_charging = true;
-        self.battery_level = 100;
-        self.is_charging = false;
+    
+    #[test]
+    fn failing_link_drops_sends_until_restored() {
+        let network = network_with(&["a", "b"]);
+        network.inject_failure("b");
+        assert!(network.route("a", "b", "lost").unwrap_err().contains("link down"));
+        assert!(network.route("b", "a", "lost").is_err(), "both directions go through the link");
+        network.restore_link("b");
+        network.route("a", "b", "found").unwrap();
+        assert_eq!(network.receive("b").unwrap().payload, "found");
     }
     
-    fn get_battery_level(&self) -> i32 {
-        self.battery_level
+    #[test]
+    fn unregistering_drops_packets_still_in_flight() {
+        let network = network_with(&["a", "b"]);
+        network.set_latency(5);
+        network.route("a", "b", "never arrives").unwrap();
+        network.unregister("b").unwrap();
+        network.register("b").unwrap();
+        network.advance(5);
+        assert!(network.receive("b").is_err());
+        assert_eq!(network.peers("a"), vec!["b"]);
     }
     
-    fn is_charging(&self) -> bool {
-        self.is_charging
+    #[test]
+    fn devices_exchange_data_and_disconnect() {
+        let network = VirtualNetwork::new("Factory LAN");
+        let mut robot = IndustrialRobot::new("Kuka-7".to_string());
+        let mut interface = NetworkInterface::new(RobotCore::new("Atlas-3".to_string()));
+        assert!(robot.send_data("Atlas-3", "hello").unwrap_err().contains("Not connected"));
+        robot.connect_to_network(&network).unwrap();
+        interface.connect_to_network(&network).unwrap();
+        assert_eq!(robot.get_network_status(), "Connected to Factory LAN");
+        
+        assert_eq!(ITManager::exchange_data(&robot, &mut interface, "status?").unwrap(), "status?");
+        
+        interface.disconnect_from_network().unwrap();
+        assert_eq!(interface.get_network_status(), "Disconnected");
+        assert!(interface.disconnect_from_network().is_err());
+        assert!(robot.send_data("Atlas-3", "anyone?").unwrap_err().contains("unreachable"));
+        assert!(network.peers("Kuka-7").is_empty());
+    }
+    
+    #[test]
+    fn reconnecting_moves_a_device_to_the_new_network() {
+        let (old, new) = (VirtualNetwork::new("Old"), VirtualNetwork::new("New"));
+        let mut robot = IndustrialRobot::new("Kuka-7".to_string());
+        robot.connect_to_network(&old).unwrap();
+        robot.connect_to_network(&new).unwrap();
+        assert!(old.peers("").is_empty(), "left the old network");
+        assert_eq!(new.peers(""), vec!["Kuka-7"]);
     }
 }
 
-impl Programmable for HumanoidRobot {
-    fn execute_program(&self, program: &str) -> Result<(), String> {
-        if !self.is_operational {
-            return Err("Humanoid robot is not operational".to_string());
-        }
-        println!("{} is executing human-interaction program: {}", self.model, program);
-        Ok(())
-    }
+// Tests for MaintenanceLog predictions and the history TechnicalSupportManager keeps
+#[cfg(test)]
+mod maintenance_tests {
+    use super::*;
     
-    fn install_software(&mut self, software: &str) -> Result<(), String> {
-        println!("{} is installing human-interaction software: {}", self.model, software);
-        self.programs.push(software.to_string());
-        Ok(())
+    #[test]
+    fn prediction_without_snapshots_follows_the_schedule() {
+        let mut log = MaintenanceLog::new("Kuka-7".to_string(), 100);
+        assert_eq!(log.predict_next_service(), 100);
+        log.record_service(30);
+        assert_eq!(log.predict_next_service(), 130);
+        assert_eq!(log.latest_wear(), 0.0);
     }
     
-    fn get_installed_programs(&self) -> Vec<String> {
-        self.programs.clone()
+    #[test]
+    fn fast_wear_brings_the_next_service_forward() {
+        let mut log = MaintenanceLog::new("Kuka-7".to_string(), 100);
+        log.record_snapshot(8, "report".to_string(), 0.5);
+        assert_eq!(log.wear_rate(), Some(0.0625));
+        assert_eq!(log.predict_next_service(), 16, "wear reaches 100% before the interval");
+        
+        // Slow wear leaves the schedule in charge
+        log.record_snapshot(64, "report".to_string(), 0.5);
+        assert_eq!(log.predict_next_service(), 100);
     }
-}
-
-impl NetworkEnabled for HumanoidRobot {
-    fn connect_to_network(&mut self, network: &str) -> Result<(), String> {
-        println!("{} is connecting to social network: {}", self.model, network);
-        self.network_status = format!("Connected to {}", network);
-        Ok(())
+    
+    #[test]
+    fn service_resets_the_wear_rate() {
+        let mut log = MaintenanceLog::new("Kuka-7".to_string(), 100);
+        log.record_snapshot(8, "report".to_string(), 0.5);
+        log.record_service(8);
+        assert_eq!(log.wear_rate(), None, "no snapshot since the service");
+        assert_eq!(log.predict_next_service(), 108);
+        log.record_snapshot(12, "report".to_string(), 0.0);
+        assert_eq!(log.wear_rate(), None, "no wear yet");
     }
     
-    fn send_data(&self, data: &str) -> Result<(), String> {
-        if self.network_status == "Disconnected" {
-            return Err("Not connected to network".to_string());
+    #[test]
+    fn snapshots_between_includes_both_ends() {
+        let mut log = MaintenanceLog::new("Kuka-7".to_string(), 100);
+        for hour in [0, 5, 10, 15] {
+            log.record_snapshot(hour, format!("hour {}", hour), 0.0);
         }
-        println!("{} is sharing social data: {}", self.model, data);
-        Ok(())
+        let reports: Vec<&str> = log.snapshots_between(5, 10).iter().map(|s| s.report.as_str()).collect();
+        assert_eq!(reports, vec!["hour 5", "hour 10"]);
+        assert!(log.snapshots_between(16, 20).is_empty());
     }
     
-    fn receive_data(&mut self) -> Result<String, String> {
-        if self.network_status == "Disconnected" {
-            return Err("Not connected to network".to_string());
+    #[test]
+    fn support_manager_keeps_a_log_per_device() {
+        let mut support = TechnicalSupportManager::new(500);
+        let mut kuka = IndustrialRobot::new("Kuka-7".to_string());
+        let mut atlas = IndustrialRobot::new("Atlas-3".to_string());
+        for _ in 0..4 {
+            kuka.work();
         }
-        let data = "Social interaction data received";
-        println!("{} received: {}", self.model, data);
-        Ok(data.to_string())
+        support.advance_hours(20);
+        support.record_diagnostics(&kuka);
+        support.perform_maintenance(&mut atlas).unwrap();
+        
+        let kuka_log = support.maintenance_log("Kuka-7").unwrap();
+        assert!(kuka_log.latest_wear() > 0.0);
+        assert_eq!(kuka_log.last_service(), None);
+        assert!(support.predict_next_service("Kuka-7").unwrap() < 500, "worn robot is due early");
+        assert_eq!(support.maintenance_log("Atlas-3").unwrap().last_service(), Some(20));
+        assert_eq!(support.predict_next_service("Atlas-3"), Some(520));
+        assert_eq!(support.predict_next_service("Fanuc-9"), None);
     }
     
-    fn get_network_status(&self) -> String {
-        self.network_status.clone()
+    #[test]
+    fn maintenance_clears_wear_and_is_logged() {
+        let mut support = TechnicalSupportManager::new(100);
+        let mut kuka = IndustrialRobot::new("Kuka-7".to_string());
+        kuka.work();
+        support.advance_hours(5);
+        support.perform_maintenance(&mut kuka).unwrap();
+        assert_eq!(kuka.get_wear_level(), 0.0);
+        
+        let log = support.maintenance_log("Kuka-7").unwrap();
+        assert_eq!(log.snapshots.len(), 1, "diagnostics are taken before servicing");
+        assert!(log.latest_wear() > 0.0);
+        assert_eq!(log.last_service(), Some(5));
     }
 }
 
-impl fmt::Display for Person {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "Person({})", self.name)
+// Tests for SkillGraph and RelationshipGraph, and the collaboration built on them
+#[cfg(test)]
+mod graph_tests {
+    use super::*;
+    
+    fn analyst() -> SkillGraph {
+        SkillGraph::new()
+            .with_skill("Statistics", 90, &[])
+            .with_skill("Data Analysis", 70, &["Statistics"])
+            .with_skill("Forecasting", 60, &["Data Analysis", "Statistics"])
     }
-}
-
-impl fmt::Display for IndustrialRobot {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "IndustrialRobot({})", self.model)
+    
+    #[test]
+    fn skills_need_their_prerequisites_first() {
+        let mut skills = SkillGraph::new();
+        assert!(skills.add_skill("Data Analysis", 50, &["Statistics"]).unwrap_err().contains("missing prerequisite Statistics"));
+        assert!(!skills.has_skill("Data Analysis"));
+        skills.add_skill("Statistics", 250, &[]).unwrap();
+        assert_eq!(skills.proficiency("Statistics"), 100, "proficiency is capped");
+        skills.add_skill("Data Analysis", 50, &["Statistics"]).unwrap();
+        assert_eq!(skills.skill_names(), vec!["Data Analysis", "Statistics"]);
     }
-}
-
-impl fmt::Display for HumanoidRobot {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "HumanoidRobot({})", self.model)
+    
+    #[test]
+    fn practice_adds_unknown_skills_and_saturates() {
+        let mut skills = SkillGraph::new().with_skill("Welding", 95, &[]);
+        assert_eq!(skills.practice("Welding", 10), 100);
+        assert_eq!(skills.practice("Painting", 10), 10);
+        assert_eq!(skills.proficiency("Sculpture"), 0);
     }
-}
-
-// CORRECT: Segregated managers that depend only on needed traits
-
-struct WorkManager;
-
-impl WorkManager {
-    // Only depends on Workable trait
-    fn assign_work(worker: &mut dyn Workable, task: &str) {
-        println!("Assigning work task: {}", task);
-        worker.work();
-        println!("Work efficiency: {:.2}", worker.get_work_efficiency());
+    
+    #[test]
+    fn learning_path_lists_each_prerequisite_once_before_the_skill() {
+        let skills = analyst();
+        assert_eq!(skills.learning_path("Forecasting"), vec!["Statistics", "Data Analysis", "Forecasting"]);
+        assert_eq!(skills.learning_path("Statistics"), vec!["Statistics"]);
+        assert_eq!(skills.learning_path("Unknown"), vec!["Unknown"]);
     }
     
-    // Can work with any Workable + Cognitive combination
-    fn assign_complex_work(worker: &mut (dyn Workable + Cognitive), task: &str) {
-        println!("Assigning complex task: {}", task);
-        worker.think();
-        let solution = worker.solve_problem(task);
-        println!("Solution approach: {}", solution);
-        worker.work();
+    #[test]
+    fn complementary_skills_need_the_mentoring_gap() {
+        let learner = SkillGraph::new().with_skill("Statistics", 71, &[]).with_skill("Data Analysis", 50, &[]);
+        assert_eq!(learner.complementary_skills(&analyst()), vec!["Data Analysis", "Forecasting"]);
+        assert!(analyst().complementary_skills(&analyst()).is_empty());
     }
-}
-
-struct BiologicalCareProvider;
-
-impl BiologicalCareProvider {
-    // Only depends on Biological trait
-    fn provide_care(being: &mut dyn Biological) -> Result<(), String> {
-        println!("Providing biological care...");
-        being.eat()?;
-        being.sleep()?;
-        being.breathe()?;
-        println!("Health status: {}", being.get_health_status());
-        Ok(())
+    
+    #[test]
+    fn lessons_include_missing_prerequisites() {
+        let learner = SkillGraph::new().with_skill("Statistics", 75, &[]);
+        assert_eq!(SocialCoordinator::lessons_for(&learner, &analyst()), vec!["Data Analysis", "Forecasting"]);
+        assert_eq!(SocialCoordinator::lessons_for(&SkillGraph::new(), &analyst()),
+                   vec!["Statistics", "Data Analysis", "Forecasting"]);
     }
     
-    // Combines biological and physical care
-    fn provide_wellness_care(being: &mut (dyn Biological + Physical)) -> Result<(), String> {
-        println!("Providing comprehensive wellness care...");
-        being.eat()?;
-        being.exercise()?;
-        println!("Physical condition: {}", being.get_physical_condition());
-        Ok(())
+    #[test]
+    fn relationships_are_directed_and_not_duplicated() {
+        let mut graph = RelationshipGraph::new();
+        graph.connect("Ada", "Grace", RelationshipKind::Friend).unwrap();
+        graph.connect("Ada", "Grace", RelationshipKind::Colleague).unwrap();
+        assert!(graph.connect("Ada", "Grace", RelationshipKind::Friend).unwrap_err().contains("already a friend"));
+        assert!(graph.connect("Ada", "Ada", RelationshipKind::Friend).is_err());
+        graph.connect("Grace", "Ada", RelationshipKind::Mentor).unwrap();
+        
+        assert_eq!(graph.kinds_between("Ada", "Grace"), vec![RelationshipKind::Friend, RelationshipKind::Colleague]);
+        assert_eq!(graph.kinds_between("Grace", "Ada"), vec![RelationshipKind::Mentor]);
+        assert_eq!(graph.relationships_of("Ada").len(), 2);
+        assert_eq!(graph.len(), 3);
     }
-}
-
-struct TechnicalSupportManager;
-
-impl TechnicalSupportManager {
-    // Only depends on Mechanical trait
-    fn perform_maintenance(device: &mut dyn Mechanical) -> Result<(), String> {
-        println!("Performing technical maintenance...");
-        println!("Diagnostics: {}", device.run_diagnostics());
-        device.perform_maintenance()?;
-        println!("Status: {}", device.get_mechanical_status());
-        Ok(())
+    
+    #[test]
+    fn collaboration_teaches_the_weaker_partner_and_records_roles() {
+        let mut ada = Person::new("Ada".to_string());
+        let mut grace = Person::new("Grace".to_string());
+        grace.skills = analyst();
+        SocialCoordinator::organize_collaborative_work(&mut ada, &mut grace).unwrap();
+        
+        assert_eq!(ada.get_skills().proficiency("Statistics"), 10);
+        assert_eq!(ada.get_skills().proficiency("Forecasting"), 10);
+        assert!(grace.get_skills().has_skill("Empathy"), "Ada's empathy was worth learning");
+        assert!(ada.get_skills().has_skill("Team Collaboration"));
+        
+        // Both learned something, so they pair as colleagues; pairing again adds no edge
+        SocialCoordinator::organize_collaborative_work(&mut ada, &mut grace).unwrap();
+        assert_eq!(ada.get_relationships().kinds_between("Ada", "Grace"), vec![RelationshipKind::Colleague]);
+        assert_eq!(grace.get_relationships().kinds_between("Grace", "Ada"), vec![RelationshipKind::Colleague]);
     }
     
-    // Depends on both Mechanical and Rechargeable
-    fn service_electronic_device(device: &mut (dyn Mechanical + Rechargeable)) -> Result<(), String> {
-        println!("Servicing electronic device...");
-        println!("Battery level: {}%", device.get_battery_level());
-        device.recharge();
-        device.perform_maintenance()?;
-        Ok(())
+    #[test]
+    fn one_sided_collaboration_makes_a_mentor() {
+        let mut ada = Person::new("Ada".to_string());
+        let mut grace = Person::new("Grace".to_string());
+        grace.skills = Person::new("Grace".to_string()).skills.with_skill("Statistics", 90, &[]);
+        SocialCoordinator::organize_collaborative_work(&mut ada, &mut grace).unwrap();
+        assert_eq!(ada.get_relationships().kinds_between("Ada", "Grace"), vec![RelationshipKind::Mentor]);
+        assert_eq!(grace.get_relationships().kinds_between("Grace", "Ada"), vec![RelationshipKind::Mentee]);
+    }
+    
+    #[test]
+    fn pairing_prefers_the_most_complementary_partners() {
+        let generalist = SkillGraph::new();
+        let analyst = analyst();
+        let welder = SkillGraph::new().with_skill("Welding", 90, &[]);
+        let candidates = vec![
+            ("Ada".to_string(), &generalist),
+            ("Grace".to_string(), &analyst),
+            ("Kuka".to_string(), &welder),
+        ];
+        // Grace + Kuka scores 4 and is paired first, so Ada (3 with Grace) is left over
+        let pairs = SocialCoordinator::pair_by_complementary_skills(&candidates);
+        assert_eq!(pairs, vec![("Grace".to_string(), "Kuka".to_string())]);
+    }
+    
+    #[test]
+    fn each_learned_algorithm_raises_the_humanoids_intelligence() {
+        let mut humanoid = HumanoidRobot::new("ASIMO".to_string());
+        assert_eq!(humanoid.get_intelligence_level(), 60, "two built-in algorithms at 30 each");
+        humanoid.learn("Forecasting");
+        assert_eq!(humanoid.get_intelligence_level(), 90);
     }
 }
 
-struct SocialCoordinator;
-
-impl SocialCoordinator {
-    // Only depends on Social trait
-    fn facilitate_interaction(participant: &mut dyn Social) {
-        println!("Facilitating social interaction...");
-        participant.socialize();
-        let response = participant.communicate("Hello, how are you?");
-        println!("Response: {}", response);
+// Tests for EmotionalState: work lowers mood and with it efficiency, and care
+// restores both
+#[cfg(test)]
+mod wellbeing_tests {
+    use super::*;
+    
+    fn overworked(shifts: usize) -> Person {
+        let mut person = Person::new("Ada".to_string());
+        for _ in 0..shifts {
+            person.work();
+        }
+        person
     }
     
-    // Combines social and cognitive capabilities
-    fn organize_collaborative_work(participant: &mut (dyn Social + Cognitive)) {
-        println!("Organizing collaborative work session...");
-        participant.think();
-        participant.socialize();
-        participant.learn("Team Collaboration");
+    fn assert_close(actual: f32, expected: f32) {
+        assert!((actual - expected).abs() < 1e-6, "expected {}, got {}", expected, actual);
+    }
+    
+    #[test]
+    fn mood_thresholds_scale_efficiency() {
+        let mut state = EmotionalState::new();
+        assert_close(state.efficiency_multiplier(), 1.0);
+        state.add_stressor("Deadline", 31);
+        assert_eq!(state.get_mood(), 39);
+        assert_close(state.efficiency_multiplier(), 0.75);
+        state.add_stressor("Overtime", 20);
+        assert_close(state.efficiency_multiplier(), 0.5);
+        state.add_stressor("Outage", 100);
+        assert_eq!(state.get_mood(), 0, "mood never drops below zero");
+        assert_eq!(state.get_stressors(), ["Deadline", "Overtime", "Outage"]);
+    }
+    
+    #[test]
+    fn recovery_raises_mood_and_drops_the_oldest_stressor() {
+        let mut state = EmotionalState::new();
+        state.add_stressor("Deadline", 10);
+        state.add_stressor("Overtime", 10);
+        state.recover(15);
+        assert_eq!(state.get_mood(), 65);
+        assert_eq!(state.get_stressors(), ["Overtime"]);
+        state.recover(100);
+        assert_eq!(state.get_mood(), 100, "mood is capped");
+        assert!(state.get_stressors().is_empty());
+    }
+    
+    #[test]
+    fn work_stresses_a_person_and_lowers_efficiency() {
+        let person = overworked(3);
+        assert_eq!(person.get_emotional_state().get_mood(), 25);
+        assert_close(person.get_work_efficiency(), 0.4 * 0.75);
+        let person = overworked(4);
+        assert_eq!(person.get_emotional_state().get_mood(), 10);
+        assert_close(person.get_work_efficiency(), 0.2 * 0.5);
+    }
+    
+    #[test]
+    fn wellness_care_improves_mood_and_efficiency() {
+        let mut person = overworked(3);
+        let mood_before = person.get_emotional_state().get_mood();
+        let efficiency_before = person.get_work_efficiency();
+        
+        let improvement = BiologicalCareProvider::provide_wellness_care(&mut person).unwrap();
+        assert_eq!(improvement, 20);
+        assert_eq!(person.get_emotional_state().get_mood(), mood_before + 20);
+        assert_eq!(person.get_emotional_state().get_stressors().len(), 2, "exercise lets go of one workload stressor");
+        assert_close(person.get_work_efficiency(), 0.55);
+        assert!(person.get_work_efficiency() > efficiency_before);
+    }
+    
+    #[test]
+    fn socializing_also_lifts_mood() {
+        let mut person = overworked(2);
+        let mood_before = person.get_emotional_state().get_mood();
+        person.socialize();
+        assert_eq!(person.get_emotional_state().get_mood(), mood_before + 10);
     }
 }
 
-struct ITManager;
-
-impl ITManager {
-    // Only depends on Programmable trait
-    fn deploy_software(device: &mut dyn Programmable, software: &str) -> Result<(), String> {
-        println!("Deploying software...");
-        device.install_software(software)?;
-        device.execute_program(software)?;
-        println!("Installed programs: {:?}", device.get_installed_programs());
-        Ok(())
+// Tests for the ChargingState machine and the Rechargeable devices built on it
+#[cfg(test)]
+mod charging_tests {
+    use super::*;
+    
+    #[test]
+    fn charging_starts_only_from_idle_with_a_positive_rate() {
+        assert_eq!(ChargingState::Idle.start(40, 10), Ok(ChargingState::Charging { rate: 10 }));
+        assert!(ChargingState::Idle.start(40, 0).unwrap_err().starts_with("Invalid charge rate"));
+        assert!(ChargingState::Idle.start(40, -5).is_err());
+        assert_eq!(ChargingState::Charging { rate: 10 }.start(40, 20), Err("Already charging at 10%/min".to_string()));
+        assert_eq!(ChargingState::Idle.start(100, 10), Err("Battery is already full".to_string()));
+        assert_eq!(ChargingState::Full.start(60, 10), Ok(ChargingState::Charging { rate: 10 }), "Full follows the level");
     }
     
-    // Combines programmable and network capabilities
-    fn setup_networked_system(device: &mut (dyn Programmable + NetworkEnabled), network: &str) -> Result<(), String> {
-        println!("Setting up networked system...");
-        device.connect_to_network(network)?;
-        device.install_software("Network Manager")?;
-        device.send_data("System ready")?;
-        println!("Network status: {}", device.get_network_status());
-        Ok(())
+    #[test]
+    fn tick_charges_until_full() {
+        let charging = ChargingState::Charging { rate: 15 };
+        assert_eq!(charging.tick(40, 2), (charging, 70));
+        assert_eq!(charging.tick(90, 1), (ChargingState::Full, 100));
+        assert_eq!(charging.tick(40, 0), (charging, 40));
+        assert_eq!(ChargingState::Idle.tick(40, 10), (ChargingState::Idle, 40), "idle batteries do not charge");
+    }
+    
+    #[test]
+    fn long_ticks_at_high_rates_do_not_overflow() {
+        let charging = ChargingState::Charging { rate: i32::MAX };
+        assert_eq!(charging.tick(0, u32::MAX), (ChargingState::Full, 100));
+        assert_eq!(ChargingState::Charging { rate: 1 }.tick(i32::MIN, 1), (ChargingState::Charging { rate: 1 }, 0));
+    }
+    
+    #[test]
+    fn stopping_settles_on_the_battery_level() {
+        assert_eq!(ChargingState::Charging { rate: 10 }.stop(70), ChargingState::Idle);
+        assert_eq!(ChargingState::Charging { rate: 10 }.stop(100), ChargingState::Full);
+        assert_eq!(ChargingState::Full.settle(80), ChargingState::Idle);
+    }
+    
+    #[test]
+    fn robot_and_power_cell_charge_the_same_way() {
+        let mut robot = IndustrialRobot::new("Kuka-7".to_string());
+        robot.battery_level = 40;
+        let core = RobotCore::new("Atlas-3".to_string());
+        core.battery_level.set(40);
+        let mut cell = PowerCell::new(core);
+        
+        let devices: [&mut dyn Rechargeable; 2] = [&mut robot, &mut cell];
+        for device in devices {
+            assert_eq!(device.get_charging_state(), ChargingState::Idle);
+            device.start_charging(25).unwrap();
+            assert!(device.is_charging());
+            assert_eq!(device.tick_charge(2), ChargingState::Charging { rate: 25 });
+            assert_eq!(device.get_battery_level(), 90);
+            assert_eq!(device.tick_charge(1), ChargingState::Full);
+            assert_eq!(device.get_battery_level(), 100);
+            assert!(device.start_charging(25).is_err());
+        }
+    }
+    
+    #[test]
+    fn service_bay_charges_before_maintenance() {
+        let mut support = TechnicalSupportManager::new(100);
+        let mut robot = IndustrialRobot::new("Kuka-7".to_string());
+        robot.battery_level = 20;
+        support.service_electronic_device(&mut robot).unwrap();
+        assert_eq!(robot.get_charging_state(), ChargingState::Full);
+        assert_eq!(support.maintenance_log("Kuka-7").unwrap().last_service(), Some(0));
     }
 }
 
-fn main() {
-    println!("=== Interface Segregation Principle - CORRECT (Rust) ===");
-    println!();
+// Tests for ITManager's deployment checks: which devices a package is accepted
+// on, and that a rejected plan installs nothing
+#[cfg(test)]
+mod deployment_tests {
+    use super::*;
     
-    println!("1. Creating entities with segregated traits:");
-    let mut person = Person::new("Alice".to_string());
-    let mut robot = IndustrialRobot::new("R2D2-Industrial".to_string());
-    let mut humanoid = HumanoidRobot::new("ASIMO-Advanced".to_string());
+    fn catalog() -> SoftwareCatalog {
+        let mut catalog = SoftwareCatalog::new();
+        catalog.publish(SoftwarePackage::new("Controller", Version::new(1, 0, 0))
+            .requires(Requirement::Capability("Mechanical"))
+            .requires(Requirement::MinBattery(50)));
+        catalog.publish(SoftwarePackage::new("Controller", Version::new(1, 1, 0))
+            .requires(Requirement::Capability("Mechanical"))
+            .requires(Requirement::MinBattery(50)));
+        catalog.publish(SoftwarePackage::new("Driver", Version::new(1, 0, 0))
+            .requires(Requirement::Capability("NetworkEnabled")));
+        catalog.publish(SoftwarePackage::new("Vision", Version::new(1, 0, 0))
+            .with_dependency("Driver", Version::new(1, 0, 0)));
+        catalog
+    }
     
-    println!("\n2. Work management (only needs Workable trait):");
-    WorkManager::assign_work(&mut person, "Write documentation");
-    WorkManager::assign_work(&mut robot, "Assemble components");
-    WorkManager::assign_work(&mut humanoid, "Assist customers");
+    // A composed device that is only Programmable: no battery, no other capabilities
+    fn bare_stack() -> SoftwareStack {
+        SoftwareStack::new(RobotCore::new("Stack-1".to_string()))
+    }
     
-    println!("\n3. Complex work (needs Workable + Cognitive):");
-    WorkManager::assign_complex_work(&mut person, "Design new product");
-    WorkManager::assign_complex_work(&mut robot, "Optimize assembly line");
-    WorkManager::assign_complex_work(&mut humanoid, "Develop social protocols");
+    fn installed(device: &dyn Programmable) -> Vec<String> {
+        device.get_installed_packages().iter().map(|p| p.to_string()).collect()
+    }
     
-    println!("\n4. Biological care (only for entities with Biological trait):");
-    // Only Person implements Biological - no errors!
-    if let Ok(()) = BiologicalCareProvider::provide_care(&mut person) {
-        println!("Biological care provided successfully");
+    #[test]
+    fn capable_device_accepts_the_latest_version() {
+        let mut robot = IndustrialRobot::new("Kuka-7".to_string());
+        ITManager::deploy_software(&mut robot, &catalog(), "Controller").unwrap();
+        ITManager::deploy_software(&mut robot, &catalog(), "Vision").unwrap();
+        assert_eq!(installed(&robot), vec!["Controller v1.1.0", "Driver v1.0.0", "Vision v1.0.0"]);
     }
     
-    println!("\n5. Technical support (only for entities with Mechanical trait):");
-    // Only robots implement Mechanical
-    if let Ok(()) = TechnicalSupportManager::perform_maintenance(&mut robot) {
-        println!("Robot maintenance completed");
+    #[test]
+    fn low_battery_is_rejected() {
+        let mut robot = IndustrialRobot::new("Kuka-7".to_string());
+        robot.battery_level = 30;
+        let error = ITManager::deploy_software(&mut robot, &catalog(), "Controller").unwrap_err();
+        assert_eq!(error, DeploymentError::Rejected {
+            package: "Controller v1.1.0".to_string(),
+            unmet: vec![Requirement::MinBattery(50)],
+        });
+        assert!(installed(&robot).is_empty());
     }
-    if let Ok(()) = TechnicalSupportManager::perform_maintenance(&mut humanoid) {
-        println!("Humanoid maintenance completed");
+    
+    #[test]
+    fn missing_capabilities_and_battery_are_all_reported() {
+        let mut stack = bare_stack();
+        let error = ITManager::deploy_software(&mut stack, &catalog(), "Controller").unwrap_err();
+        assert_eq!(error.to_string(), "Controller v1.1.0 rejected: target lacks Mechanical, battery >= 50%");
     }
     
-    println!("\n6. Electronic device servicing (needs Mechanical + Rechargeable):");
-    if let Ok(()) = TechnicalSupportManager::service_electronic_device(&mut robot) {
-        println!("Robot servicing completed");
+    #[test]
+    fn unmet_dependency_rejects_the_whole_plan() {
+        let mut stack = bare_stack();
+        let error = ITManager::deploy_software(&mut stack, &catalog(), "Vision").unwrap_err();
+        assert_eq!(error, DeploymentError::Rejected {
+            package: "Driver v1.0.0".to_string(),
+            unmet: vec![Requirement::Capability("NetworkEnabled")],
+        });
+        assert!(installed(&stack).is_empty(), "Vision needs nothing itself but is not installed either");
+        
+        // Packages without requirements are accepted anywhere
+        let mut catalog = catalog();
+        catalog.publish(SoftwarePackage::new("Clock", Version::new(1, 0, 0)));
+        ITManager::deploy_software(&mut stack, &catalog, "Clock").unwrap();
+        assert_eq!(installed(&stack), vec!["Clock v1.0.0"]);
     }
-    if let Ok(()) = TechnicalSupportManager::service_electronic_device(&mut humanoid) {
-        println!("Humanoid servicing completed");
+    
+    #[test]
+    fn unknown_packages_fail_before_any_check() {
+        let mut robot = IndustrialRobot::new("Kuka-7".to_string());
+        assert_eq!(ITManager::deploy_software(&mut robot, &catalog(), "Painter"),
+                   Err(DeploymentError::Failed("Painter is not in the software catalog".to_string())));
+        assert!(matches!(ITManager::deploy_version(&mut robot, &catalog(), "Controller", Version::new(2, 0, 0)),
+                         Err(DeploymentError::Failed(_))));
     }
     
-    println!("\n7. Social interaction (only for entities with Social trait):");
-    SocialCoordinator::facilitate_interaction(&mut person);
-    SocialCoordinator::facilitate_interaction(&mut humanoid);  // Humanoid has social capabilities
+    #[test]
+    fn exact_versions_can_be_deployed_and_rolled_back() {
+        let mut robot = IndustrialRobot::new("Kuka-7".to_string());
+        ITManager::deploy_version(&mut robot, &catalog(), "Controller", Version::new(1, 1, 0)).unwrap();
+        ITManager::deploy_version(&mut robot, &catalog(), "Controller", Version::new(1, 0, 0)).unwrap();
+        assert_eq!(installed(&robot), vec!["Controller v1.0.0"]);
+        ITManager::rollback_software(&mut robot, "Controller").unwrap();
+        assert_eq!(installed(&robot), vec!["Controller v1.1.0"]);
+        assert!(ITManager::rollback_software(&mut robot, "Controller").is_err());
+    }
+}
+
+// Tests for the actor runtime's shutdown: queued messages are drained, and
+// neither shutdown, drop nor a panicking handler can hang the caller
+#[cfg(test)]
+mod actor_tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
     
-    println!("\n8. IT management (for programmable devices):");
-    if let Ok(()) = ITManager::deploy_software(&mut robot, "Quality Control v3.0") {
-        println!("Software deployed to robot");
+    // Runs `test` on its own thread and fails instead of hanging if it deadlocks
+    fn within_deadline<T: Send + 'static>(test: impl FnOnce() -> T + Send + 'static) -> T {
+        let (done, result) = mpsc::channel();
+        thread::spawn(move || {
+            let _ = done.send(test());
+        });
+        result.recv_timeout(Duration::from_secs(5)).expect("actor shutdown deadlocked")
     }
-    if let Ok(()) = ITManager::deploy_software(&mut humanoid, "Advanced Social AI v2.1") {
-        println!("Software deployed to humanoid");
+    
+    fn counting_actor(handled: &Arc<AtomicUsize>) -> Actor<()> {
+        let handled = Arc::clone(handled);
+        Actor::spawn("Counter", || (), move |_: &mut (), ()| {
+            thread::sleep(Duration::from_millis(5));
+            handled.fetch_add(1, Ordering::SeqCst);
+        })
     }
     
-    println!("\n9. Network setup (for networked programmable devices):");
-    if let Ok(()) = ITManager::setup_networked_system(&mut robot, "Factory Network") {
-        println!("Robot network setup completed");
+    #[test]
+    fn queued_messages_are_handled_before_shutdown() {
+        let replies = within_deadline(|| {
+            let (reply, replies) = mpsc::channel();
+            let mut worker = Actor::spawn("T-800", || IndustrialRobot::new("T-800".to_string()),
+                                          |r: &mut IndustrialRobot, m| handle_work(r, m));
+            for task in ["Weld", "Paint", "Inspect"] {
+                worker.send(WorkMessage::Assign { task: task.to_string(), reply: reply.clone() }).unwrap();
+            }
+            worker.shutdown().unwrap();
+            replies.try_iter().count()
+        });
+        assert_eq!(replies, 3);
     }
-    if let Ok(()) = ITManager::setup_networked_system(&mut humanoid, "Social Network") {
-        println!("Humanoid network setup completed");
+    
+    #[test]
+    fn shutdown_is_idempotent_and_later_sends_fail() {
+        let (second, send) = within_deadline(|| {
+            let mut actor = counting_actor(&Arc::new(AtomicUsize::new(0)));
+            actor.shutdown().unwrap();
+            (actor.shutdown(), actor.send(()))
+        });
+        assert_eq!(second, Ok(()));
+        assert_eq!(send, Err("Counter has stopped".to_string()));
     }
     
-    println!("\n10. Demonstrating trait specialization:");
-    println!("Person traits: Workable, Biological, Cognitive, Social, Physical");
-    println!("Robot traits: Workable, Cognitive, Mechanical, Rechargeable, Programmable, NetworkEnabled");
-    println!("Humanoid traits: Workable, Cognitive, Social, Mechanical, Rechargeable, Programmable, NetworkEnabled");
+    #[test]
+    fn dropping_an_actor_joins_its_thread() {
+        let handled = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&handled);
+        within_deadline(move || {
+            let actor = counting_actor(&counter);
+            for _ in 0..10 {
+                actor.send(()).unwrap();
+            }
+        });
+        assert_eq!(handled.load(Ordering::SeqCst), 10, "drop waited for the queue to drain");
+    }
     
-    println!("\n=== Benefits of Segregated Traits ===");
-    println!("✓ Each entity only implements traits it actually needs");
-    println!("✓ Clients depend only on the methods they use");
-    println!("✓ Easy to add new entities with different trait combinations");
-    println!("✓ No empty/error implementations required");
-    println!("✓ Compile-time safety ensures trait contracts are met");
-    println!("✓ Clear separation of concerns");
-    println!("✓ Follows Interface Segregation Principle perfectly");
-}
\ No newline at end of file
+    #[test]
+    fn panicking_handler_is_reported_not_hung() {
+        let result = within_deadline(|| {
+            let mut actor: Actor<u32> = Actor::spawn("Fragile", || (), |_: &mut (), value: u32| {
+                assert!(value < 3, "cannot handle {}", value);
+            });
+            for value in 0..5 {
+                // Sends after the panic may fail; shutdown must still return
+                let _ = actor.send(value);
+            }
+            actor.shutdown()
+        });
+        assert_eq!(result, Err("Fragile panicked".to_string()));
+    }
+    
+    #[test]
+    fn replies_to_a_departed_caller_do_not_block() {
+        let status = within_deadline(|| {
+            let mut mechanic = Actor::spawn("T-800", || IndustrialRobot::new("T-800".to_string()),
+                                            |r: &mut IndustrialRobot, m| handle_maintenance(r, m));
+            let (reply, replies) = mpsc::channel();
+            drop(replies);
+            mechanic.send(MaintenanceMessage::Service { reply }).unwrap();
+            let (reply, replies) = mpsc::channel();
+            mechanic.send(MaintenanceMessage::Service { reply }).unwrap();
+            mechanic.shutdown().unwrap();
+            replies.recv().unwrap()
+        });
+        assert_eq!(status, Ok("All systems operational".to_string()));
+    }
+}
+
+// Tests for IndustrialRobot driven through legal and illegal lifecycle paths
+// (only built with the `lifecycle` feature)
+#[cfg(all(test, feature = "lifecycle"))]
+mod lifecycle_tests {
+    use super::*;
+    use state::{LifecycleEvent, RobotState};
+    
+    #[test]
+    fn faulted_robot_refuses_work_until_maintained() {
+        let mut robot = IndustrialRobot::new("Fanuc-9".to_string());
+        robot.handle_event(LifecycleEvent::Fail).unwrap();
+        assert_eq!(robot.execute_program("Weld"), Err("Cannot execute programs while Fault".to_string()));
+        assert_eq!(robot.get_mechanical_status(), "Systems offline");
+        
+        robot.perform_maintenance().unwrap();
+        assert_eq!(robot.lifecycle_state(), RobotState::Operational);
+        robot.execute_program("Weld").unwrap();
+    }
+    
+    #[test]
+    fn powered_down_robot_refuses_maintenance_and_stays_off() {
+        let mut robot = IndustrialRobot::new("Fanuc-9".to_string());
+        robot.power_down();
+        assert_eq!(robot.perform_maintenance(), Err("Cannot start maintenance while Off".to_string()));
+        assert_eq!(robot.lifecycle_state(), RobotState::Off);
+        assert!(robot.handle_event(LifecycleEvent::BootComplete).is_err());
+        assert!(robot.install_software(SoftwarePackage::new("Clock", Version::new(1, 0, 0))).is_err());
+    }
+    
+    #[test]
+    fn failed_boot_is_reset_and_retried() {
+        let mut robot = IndustrialRobot::new("Fanuc-9".to_string());
+        robot.power_down();
+        let path = [LifecycleEvent::PowerOn, LifecycleEvent::Fail, LifecycleEvent::Reset, LifecycleEvent::BootComplete];
+        for event in path {
+            robot.handle_event(event).unwrap();
+        }
+        assert_eq!(robot.lifecycle_state(), RobotState::Operational);
+        // Booted on delivery, powered down, then the four events above
+        assert_eq!(robot.lifecycle.history().len(), 7);
+    }
+    
+    #[test]
+    fn maintenance_allows_software_but_not_programs() {
+        let mut robot = IndustrialRobot::new("Fanuc-9".to_string());
+        robot.handle_event(LifecycleEvent::StartMaintenance).unwrap();
+        robot.install_software(SoftwarePackage::new("Clock", Version::new(1, 0, 0))).unwrap();
+        assert!(robot.execute_program("Clock").is_err());
+        robot.handle_event(LifecycleEvent::FinishMaintenance).unwrap();
+        robot.execute_program("Clock").unwrap();
+    }
+}
+
+// Compile-fail cases: each file in ui/ is a call the segregated traits must
+// reject, with the compiler error it gives next to it in a .stderr file. They
+// need Cargo and trybuild, so they only run with the `compile-fail` feature.
+#[cfg(all(test, feature = "compile-fail"))]
+mod compile_fail_tests {
+    use std::path::Path;
+    
+    #[test]
+    fn only_biological_entities_receive_biological_care() {
+        let cases = Path::new(file!()).with_file_name("ui").join("*.rs");
+        trybuild::TestCases::new().compile_fail(cases);
+    }
+}
@@ -0,0 +1,449 @@
+/*
+ * Interface Segregation Principle (ISP) - CORRECT Example (Rust)
+ * Payment Gateway Scenario
+ *
+ * This example demonstrates proper interface segregation for payment providers.
+ * Each payment capability lives in its own trait, so a provider implements
+ * only the features it supports and clients depend only on what they call.
+ *
+ * Solution: Chargeable, Refundable, Subscribable, Payoutable and ThreeDSecure
+ * are separate traits. Checkout requires nothing but Chargeable.
+ */
+
+use std::collections::HashMap;
+use std::fmt;
+
+// Amounts are kept in cents to avoid floating point rounding
+#[derive(Debug, Clone)]
+struct Charge {
+    id: String,
+    customer: String,
+    amount_cents: u64,
+}
+
+impl fmt::Display for Charge {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Charge({}, {}, ${:.2})", self.id, self.customer, self.amount_cents as f64 / 100.0)
+    }
+}
+
+// Segregated traits - each focused on a single payment capability
+
+// Taking money from a customer (every provider can do this)
+trait Chargeable {
+    fn charge(&mut self, customer: &str, amount_cents: u64) -> Result<Charge, String>;
+    fn get_name(&self) -> String;
+}
+
+// Returning money for a previous charge
+trait Refundable {
+    fn refund(&mut self, charge_id: &str) -> Result<(), String>;
+}
+
+// Recurring billing
+trait Subscribable {
+    fn create_subscription(&mut self, customer: &str, plan: &str, amount_cents: u64) -> Result<String, String>;
+    fn cancel_subscription(&mut self, subscription_id: &str) -> Result<(), String>;
+}
+
+// Sending collected funds to a merchant
+trait Payoutable {
+    fn payout(&mut self, merchant: &str, amount_cents: u64) -> Result<(), String>;
+}
+
+// Strong customer authentication for card payments
+trait ThreeDSecure {
+    fn verify_3ds(&mut self, customer: &str) -> Result<bool, String>;
+}
+
+// CardProcessor supports every capability
+#[derive(Debug)]
+struct CardProcessor {
+    name: String,
+    charges: HashMap<String, Charge>,
+    subscriptions: HashMap<String, String>,
+    next_id: u32,
+}
+
+impl CardProcessor {
+    fn new(name: String) -> Self {
+        CardProcessor {
+            name,
+            charges: HashMap::new(),
+            subscriptions: HashMap::new(),
+            next_id: 1,
+        }
+    }
+
+    fn next_id(&mut self, prefix: &str) -> String {
+        let id = format!("{}_{}", prefix, self.next_id);
+        self.next_id += 1;
+        id
+    }
+}
+
+impl Chargeable for CardProcessor {
+    fn charge(&mut self, customer: &str, amount_cents: u64) -> Result<Charge, String> {
+        let charge = Charge {
+            id: self.next_id("ch"),
+            customer: customer.to_string(),
+            amount_cents,
+        };
+        println!("{} charged {}", self.name, charge);
+        self.charges.insert(charge.id.clone(), charge.clone());
+        Ok(charge)
+    }
+
+    fn get_name(&self) -> String {
+        self.name.clone()
+    }
+}
+
+impl Refundable for CardProcessor {
+    fn refund(&mut self, charge_id: &str) -> Result<(), String> {
+        let charge = self.charges.remove(charge_id).ok_or(format!("Unknown charge: {}", charge_id))?;
+        println!("{} refunded {}", self.name, charge);
+        Ok(())
+    }
+}
+
+impl Subscribable for CardProcessor {
+    fn create_subscription(&mut self, customer: &str, plan: &str, amount_cents: u64) -> Result<String, String> {
+        let id = self.next_id("sub");
+        println!("{} subscribed {} to '{}' at ${:.2}/month ({})", self.name, customer, plan, amount_cents as f64 / 100.0, id);
+        self.subscriptions.insert(id.clone(), customer.to_string());
+        Ok(id)
+    }
+
+    fn cancel_subscription(&mut self, subscription_id: &str) -> Result<(), String> {
+        self.subscriptions.remove(subscription_id).ok_or(format!("Unknown subscription: {}", subscription_id))?;
+        println!("{} cancelled subscription {}", self.name, subscription_id);
+        Ok(())
+    }
+}
+
+impl Payoutable for CardProcessor {
+    fn payout(&mut self, merchant: &str, amount_cents: u64) -> Result<(), String> {
+        println!("{} paid out ${:.2} to {}", self.name, amount_cents as f64 / 100.0, merchant);
+        Ok(())
+    }
+}
+
+impl ThreeDSecure for CardProcessor {
+    fn verify_3ds(&mut self, customer: &str) -> Result<bool, String> {
+        println!("{} completed 3-D Secure challenge for {}", self.name, customer);
+        Ok(true)
+    }
+}
+
+// GiftCardProvider implements only what gift cards can do
+#[derive(Debug)]
+struct GiftCardProvider {
+    name: String,
+    balances: HashMap<String, u64>,
+    next_id: u32,
+}
+
+impl GiftCardProvider {
+    fn new(name: String) -> Self {
+        GiftCardProvider {
+            name,
+            balances: HashMap::new(),
+            next_id: 1,
+        }
+    }
+
+    fn issue_card(&mut self, customer: &str, amount_cents: u64) {
+        *self.balances.entry(customer.to_string()).or_insert(0) += amount_cents;
+    }
+
+    fn get_balance(&self, customer: &str) -> u64 {
+        self.balances.get(customer).copied().unwrap_or(0)
+    }
+}
+
+impl Chargeable for GiftCardProvider {
+    fn charge(&mut self, customer: &str, amount_cents: u64) -> Result<Charge, String> {
+        let balance = self.balances.get_mut(customer).ok_or(format!("{} has no gift card", customer))?;
+        if *balance < amount_cents {
+            return Err(format!("Insufficient gift card balance for {}", customer));
+        }
+        *balance -= amount_cents;
+        let charge = Charge {
+            id: format!("gc_{}", self.next_id),
+            customer: customer.to_string(),
+            amount_cents,
+        };
+        self.next_id += 1;
+        println!("{} redeemed {}", self.name, charge);
+        Ok(charge)
+    }
+
+    fn get_name(&self) -> String {
+        self.name.clone()
+    }
+}
+
+// Mock provider for exercising clients without a real gateway.
+// Because Checkout needs only Chargeable, the mock is one small impl.
+#[derive(Debug)]
+struct MockChargeable {
+    decline: bool,
+    charges: Vec<Charge>,
+}
+
+impl MockChargeable {
+    fn approving() -> Self {
+        MockChargeable { decline: false, charges: Vec::new() }
+    }
+
+    fn declining() -> Self {
+        MockChargeable { decline: true, charges: Vec::new() }
+    }
+}
+
+impl Chargeable for MockChargeable {
+    fn charge(&mut self, customer: &str, amount_cents: u64) -> Result<Charge, String> {
+        if self.decline {
+            return Err("Card declined".to_string());
+        }
+        let charge = Charge {
+            id: format!("mock_{}", self.charges.len() + 1),
+            customer: customer.to_string(),
+            amount_cents,
+        };
+        self.charges.push(charge.clone());
+        Ok(charge)
+    }
+
+    fn get_name(&self) -> String {
+        "MockGateway".to_string()
+    }
+}
+
+// Mock for the refund capability, recording which charges were refunded
+#[derive(Debug, Default)]
+struct MockRefundable {
+    refunded: Vec<String>,
+}
+
+impl Refundable for MockRefundable {
+    fn refund(&mut self, charge_id: &str) -> Result<(), String> {
+        self.refunded.push(charge_id.to_string());
+        Ok(())
+    }
+}
+
+// Clients depend only on the capabilities they use
+
+// Checkout requires only Chargeable
+struct Checkout;
+
+impl Checkout {
+    fn place_order(provider: &mut dyn Chargeable, customer: &str, amount_cents: u64) -> Result<Charge, String> {
+        println!("Placing order for {} via {}...", customer, provider.get_name());
+        provider.charge(customer, amount_cents)
+    }
+}
+
+// Secure checkout additionally needs ThreeDSecure
+struct SecureCheckout;
+
+impl SecureCheckout {
+    fn place_order(provider: &mut (impl Chargeable + ThreeDSecure), customer: &str, amount_cents: u64) -> Result<Charge, String> {
+        println!("Placing secure order for {} via {}...", customer, provider.get_name());
+        if !provider.verify_3ds(customer)? {
+            return Err(format!("3-D Secure verification failed for {}", customer));
+        }
+        provider.charge(customer, amount_cents)
+    }
+}
+
+// Order cancellation needs only Refundable
+struct OrderDesk;
+
+impl OrderDesk {
+    fn cancel_order(provider: &mut dyn Refundable, charge: &Charge) -> Result<(), String> {
+        println!("Cancelling order {}...", charge.id);
+        provider.refund(&charge.id)
+    }
+}
+
+// Billing and settlement depend on their own capabilities
+struct BillingService;
+
+impl BillingService {
+    fn start_membership(provider: &mut dyn Subscribable, customer: &str) -> Result<String, String> {
+        provider.create_subscription(customer, "Premium", 999)
+    }
+
+    fn settle(provider: &mut dyn Payoutable, merchant: &str, amount_cents: u64) -> Result<(), String> {
+        println!("Settling ${:.2} with {}...", amount_cents as f64 / 100.0, merchant);
+        provider.payout(merchant, amount_cents)
+    }
+}
+
+// Demonstrates correct ISP implementation
+fn main() {
+    println!("=== Interface Segregation Principle - CORRECT: Payment Gateway (Rust) ===");
+    println!();
+
+    println!("1. Creating payment providers:");
+    let mut cards = CardProcessor::new("CardCo".to_string());
+    let mut gift_cards = GiftCardProvider::new("GiftCo".to_string());
+    gift_cards.issue_card("bob", 5_000);
+    println!("Providers: {}, {}", cards.get_name(), gift_cards.get_name());
+
+    println!("\n2. Checkout with any Chargeable provider:");
+    let order = Checkout::place_order(&mut cards, "alice", 2_499);
+    match Checkout::place_order(&mut gift_cards, "bob", 2_499) {
+        Ok(charge) => println!("Order paid: {}, remaining balance ${:.2}", charge, gift_cards.get_balance("bob") as f64 / 100.0),
+        Err(e) => println!("Error: {}", e),
+    }
+    if let Err(e) = Checkout::place_order(&mut gift_cards, "bob", 9_999) {
+        println!("Declined as expected: {}", e);
+    }
+
+    println!("\n3. Secure checkout (needs Chargeable + ThreeDSecure):");
+    if let Err(e) = SecureCheckout::place_order(&mut cards, "carol", 15_000) {
+        println!("Error: {}", e);
+    }
+    // SecureCheckout::place_order(&mut gift_cards, ...) would not compile:
+    // GiftCardProvider does not implement ThreeDSecure
+
+    println!("\n4. Cancelling an order (needs Refundable):");
+    if let Ok(charge) = order {
+        if let Err(e) = OrderDesk::cancel_order(&mut cards, &charge) {
+            println!("Error: {}", e);
+        }
+    }
+
+    println!("\n5. Subscriptions and payouts (card processor only):");
+    match BillingService::start_membership(&mut cards, "alice") {
+        Ok(subscription) => {
+            if let Err(e) = cards.cancel_subscription(&subscription) {
+                println!("Error: {}", e);
+            }
+        }
+        Err(e) => println!("Error: {}", e),
+    }
+    if let Err(e) = BillingService::settle(&mut cards, "acme-store", 10_000) {
+        println!("Error: {}", e);
+    }
+
+    println!("\n6. Exercising clients with mock providers:");
+    let mut approving = MockChargeable::approving();
+    let mut declining = MockChargeable::declining();
+    let mut refunds = MockRefundable::default();
+    let approved = Checkout::place_order(&mut approving, "dave", 1_250);
+    let declined = Checkout::place_order(&mut declining, "erin", 1_250);
+    println!("Approving mock recorded {} charge(s), checkout ok: {}", approving.charges.len(), approved.is_ok());
+    println!("Declining mock recorded {} charge(s), error: {:?}", declining.charges.len(), declined.err());
+    if let Ok(charge) = approved {
+        let _ = OrderDesk::cancel_order(&mut refunds, &charge);
+    }
+    println!("Refund mock recorded: {:?}", refunds.refunded);
+
+    println!("\n7. Benefits of this design:");
+    println!("   - GiftCardProvider implements only Chargeable, with no Err stubs");
+    println!("   - Checkout depends on a single small trait");
+    println!("   - Mocks implement one trait instead of seven methods");
+    println!("   - Unsupported combinations are rejected at compile time");
+
+    println!("\n=== Conclusion ===");
+    println!("Segregated payment traits let each provider implement only the");
+    println!("capabilities it offers, and let Checkout depend on Chargeable alone.");
+}
+
+// Tests for the providers and clients, each client exercised through the one
+// capability it depends on
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Chargeable + ThreeDSecure provider whose challenge the customer fails
+    struct FailedChallenge {
+        inner: MockChargeable,
+    }
+
+    impl Chargeable for FailedChallenge {
+        fn charge(&mut self, customer: &str, amount_cents: u64) -> Result<Charge, String> {
+            self.inner.charge(customer, amount_cents)
+        }
+
+        fn get_name(&self) -> String {
+            "FailedChallenge".to_string()
+        }
+    }
+
+    impl ThreeDSecure for FailedChallenge {
+        fn verify_3ds(&mut self, _customer: &str) -> Result<bool, String> {
+            Ok(false)
+        }
+    }
+
+    #[test]
+    fn card_charges_get_unique_ids_and_can_be_refunded_once() {
+        let mut cards = CardProcessor::new("CardCo".to_string());
+        let first = Checkout::place_order(&mut cards, "alice", 2_499).unwrap();
+        let second = Checkout::place_order(&mut cards, "bob", 100).unwrap();
+        assert_ne!(first.id, second.id);
+        assert_eq!((first.customer.as_str(), first.amount_cents), ("alice", 2_499));
+
+        OrderDesk::cancel_order(&mut cards, &first).unwrap();
+        assert_eq!(OrderDesk::cancel_order(&mut cards, &first), Err(format!("Unknown charge: {}", first.id)));
+        assert!(cards.charges.contains_key(&second.id));
+    }
+
+    #[test]
+    fn gift_cards_spend_down_their_balance() {
+        let mut gift_cards = GiftCardProvider::new("GiftCo".to_string());
+        gift_cards.issue_card("bob", 3_000);
+        gift_cards.issue_card("bob", 2_000);
+        Checkout::place_order(&mut gift_cards, "bob", 4_000).unwrap();
+        assert_eq!(gift_cards.get_balance("bob"), 1_000);
+
+        assert_eq!(Checkout::place_order(&mut gift_cards, "bob", 1_001).unwrap_err(), "Insufficient gift card balance for bob");
+        assert_eq!(gift_cards.get_balance("bob"), 1_000, "a declined charge takes nothing");
+        assert_eq!(Checkout::place_order(&mut gift_cards, "carol", 1).unwrap_err(), "carol has no gift card");
+        Checkout::place_order(&mut gift_cards, "bob", 1_000).unwrap();
+        assert_eq!(gift_cards.get_balance("bob"), 0);
+    }
+
+    #[test]
+    fn secure_checkout_charges_only_after_verification() {
+        let mut cards = CardProcessor::new("CardCo".to_string());
+        let charge = SecureCheckout::place_order(&mut cards, "carol", 15_000).unwrap();
+        assert_eq!(charge.amount_cents, 15_000);
+
+        let mut failing = FailedChallenge { inner: MockChargeable::approving() };
+        let error = SecureCheckout::place_order(&mut failing, "erin", 15_000).unwrap_err();
+        assert_eq!(error, "3-D Secure verification failed for erin");
+        assert!(failing.inner.charges.is_empty());
+    }
+
+    #[test]
+    fn subscriptions_can_be_cancelled_once() {
+        let mut cards = CardProcessor::new("CardCo".to_string());
+        let subscription = BillingService::start_membership(&mut cards, "alice").unwrap();
+        assert_eq!(cards.subscriptions.get(&subscription).map(String::as_str), Some("alice"));
+        cards.cancel_subscription(&subscription).unwrap();
+        assert!(cards.cancel_subscription(&subscription).unwrap_err().starts_with("Unknown subscription"));
+        BillingService::settle(&mut cards, "acme-store", 10_000).unwrap();
+    }
+
+    #[test]
+    fn clients_work_against_single_trait_mocks() {
+        let mut approving = MockChargeable::approving();
+        let mut declining = MockChargeable::declining();
+        let mut refunds = MockRefundable::default();
+
+        let charge = Checkout::place_order(&mut approving, "dave", 1_250).unwrap();
+        assert_eq!(Checkout::place_order(&mut declining, "erin", 1_250).unwrap_err(), "Card declined");
+        assert_eq!(approving.charges.len(), 1);
+        assert!(declining.charges.is_empty());
+
+        OrderDesk::cancel_order(&mut refunds, &charge).unwrap();
+        assert_eq!(refunds.refunded, vec![charge.id]);
+    }
+}
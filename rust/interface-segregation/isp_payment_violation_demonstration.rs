@@ -0,0 +1,260 @@
+/*
+ * Interface Segregation Principle (ISP) - VIOLATION Example (Rust)
+ * Payment Gateway Scenario
+ *
+ * This example demonstrates how a fat PaymentProvider trait forces a simple
+ * gift-card provider to implement refunds, recurring billing, payouts and
+ * 3-D Secure challenges it cannot support.
+ *
+ * Problem: Checkout only needs to charge a customer, but it must depend on
+ * the whole PaymentProvider trait, and half of GiftCardProvider is Err stubs.
+ */
+
+use std::collections::HashMap;
+use std::fmt;
+
+// Amounts are kept in cents to avoid floating point rounding
+#[derive(Debug, Clone)]
+struct Charge {
+    id: String,
+    customer: String,
+    amount_cents: u64,
+}
+
+impl fmt::Display for Charge {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Charge({}, {}, ${:.2})", self.id, self.customer, self.amount_cents as f64 / 100.0)
+    }
+}
+
+// VIOLATION: Fat trait forces every provider to support every payment feature
+trait PaymentProvider {
+    fn charge(&mut self, customer: &str, amount_cents: u64) -> Result<Charge, String>;
+    fn refund(&mut self, charge_id: &str) -> Result<(), String>;
+    fn create_subscription(&mut self, customer: &str, plan: &str, amount_cents: u64) -> Result<String, String>;
+    fn cancel_subscription(&mut self, subscription_id: &str) -> Result<(), String>;
+    fn payout(&mut self, merchant: &str, amount_cents: u64) -> Result<(), String>;
+    fn verify_3ds(&mut self, customer: &str) -> Result<bool, String>;
+    fn get_name(&self) -> String;
+}
+
+// Card processor - naturally implements all trait methods
+#[derive(Debug)]
+struct CardProcessor {
+    name: String,
+    charges: HashMap<String, Charge>,
+    subscriptions: HashMap<String, String>,
+    next_id: u32,
+}
+
+impl CardProcessor {
+    fn new(name: String) -> Self {
+        CardProcessor {
+            name,
+            charges: HashMap::new(),
+            subscriptions: HashMap::new(),
+            next_id: 1,
+        }
+    }
+
+    fn next_id(&mut self, prefix: &str) -> String {
+        let id = format!("{}_{}", prefix, self.next_id);
+        self.next_id += 1;
+        id
+    }
+}
+
+impl PaymentProvider for CardProcessor {
+    fn charge(&mut self, customer: &str, amount_cents: u64) -> Result<Charge, String> {
+        let charge = Charge {
+            id: self.next_id("ch"),
+            customer: customer.to_string(),
+            amount_cents,
+        };
+        println!("{} charged {}", self.name, charge);
+        self.charges.insert(charge.id.clone(), charge.clone());
+        Ok(charge)
+    }
+
+    fn refund(&mut self, charge_id: &str) -> Result<(), String> {
+        let charge = self.charges.remove(charge_id).ok_or(format!("Unknown charge: {}", charge_id))?;
+        println!("{} refunded {}", self.name, charge);
+        Ok(())
+    }
+
+    fn create_subscription(&mut self, customer: &str, plan: &str, amount_cents: u64) -> Result<String, String> {
+        let id = self.next_id("sub");
+        println!("{} subscribed {} to '{}' at ${:.2}/month ({})", self.name, customer, plan, amount_cents as f64 / 100.0, id);
+        self.subscriptions.insert(id.clone(), customer.to_string());
+        Ok(id)
+    }
+
+    fn cancel_subscription(&mut self, subscription_id: &str) -> Result<(), String> {
+        self.subscriptions.remove(subscription_id).ok_or(format!("Unknown subscription: {}", subscription_id))?;
+        println!("{} cancelled subscription {}", self.name, subscription_id);
+        Ok(())
+    }
+
+    fn payout(&mut self, merchant: &str, amount_cents: u64) -> Result<(), String> {
+        println!("{} paid out ${:.2} to {}", self.name, amount_cents as f64 / 100.0, merchant);
+        Ok(())
+    }
+
+    fn verify_3ds(&mut self, customer: &str) -> Result<bool, String> {
+        println!("{} completed 3-D Secure challenge for {}", self.name, customer);
+        Ok(true)
+    }
+
+    fn get_name(&self) -> String {
+        self.name.clone()
+    }
+}
+
+// VIOLATION: GiftCardProvider forced to implement PaymentProvider
+// Gift cards can only be redeemed against a balance
+#[derive(Debug)]
+struct GiftCardProvider {
+    name: String,
+    balances: HashMap<String, u64>,
+    next_id: u32,
+}
+
+impl GiftCardProvider {
+    fn new(name: String) -> Self {
+        GiftCardProvider {
+            name,
+            balances: HashMap::new(),
+            next_id: 1,
+        }
+    }
+
+    fn issue_card(&mut self, customer: &str, amount_cents: u64) {
+        *self.balances.entry(customer.to_string()).or_insert(0) += amount_cents;
+    }
+}
+
+impl PaymentProvider for GiftCardProvider {
+    fn charge(&mut self, customer: &str, amount_cents: u64) -> Result<Charge, String> {
+        let balance = self.balances.get_mut(customer).ok_or(format!("{} has no gift card", customer))?;
+        if *balance < amount_cents {
+            return Err(format!("Insufficient gift card balance for {}", customer));
+        }
+        *balance -= amount_cents;
+        let charge = Charge {
+            id: format!("gc_{}", self.next_id),
+            customer: customer.to_string(),
+            amount_cents,
+        };
+        self.next_id += 1;
+        println!("{} redeemed {}", self.name, charge);
+        Ok(charge)
+    }
+
+    // VIOLATION: Forced to implement irrelevant methods
+    fn refund(&mut self, _charge_id: &str) -> Result<(), String> {
+        // Redemptions are final
+        Err("Gift card redemptions cannot be refunded!".to_string())
+    }
+
+    fn create_subscription(&mut self, _customer: &str, _plan: &str, _amount_cents: u64) -> Result<String, String> {
+        // No stored card to bill every month
+        Err("Gift cards don't support recurring billing!".to_string())
+    }
+
+    fn cancel_subscription(&mut self, _subscription_id: &str) -> Result<(), String> {
+        Err("Gift cards don't support recurring billing!".to_string())
+    }
+
+    fn payout(&mut self, _merchant: &str, _amount_cents: u64) -> Result<(), String> {
+        // Gift card balances are not cash
+        Err("Gift card balances cannot be paid out!".to_string())
+    }
+
+    fn verify_3ds(&mut self, _customer: &str) -> Result<bool, String> {
+        // 3-D Secure is a card network feature
+        Err("Gift cards don't support 3-D Secure!".to_string())
+    }
+
+    fn get_name(&self) -> String {
+        self.name.clone()
+    }
+}
+
+// Client code that expects the PaymentProvider trait
+struct Checkout;
+
+impl Checkout {
+    // Only needs charge(), but the provider must also be able to do everything else
+    fn place_order(provider: &mut dyn PaymentProvider, customer: &str, amount_cents: u64) -> Result<Charge, String> {
+        println!("Placing order for {} via {}...", customer, provider.get_name());
+
+        // Checkout policy runs 3-D Secure on every order, so gift cards fail here
+        provider.verify_3ds(customer).map_err(|e| format!("Verification failed: {}", e))?;
+        provider.charge(customer, amount_cents)
+    }
+
+    // Cancelling an order assumes every provider can refund
+    fn cancel_order(provider: &mut dyn PaymentProvider, charge: &Charge) -> Result<(), String> {
+        println!("Cancelling order {}...", charge.id);
+        provider.refund(&charge.id)
+    }
+}
+
+// Demonstrates the ISP violation
+fn main() {
+    println!("=== Interface Segregation Principle - VIOLATION: Payment Gateway (Rust) ===");
+    println!();
+
+    println!("1. Creating payment providers:");
+    let mut cards = CardProcessor::new("CardCo".to_string());
+    let mut gift_cards = GiftCardProvider::new("GiftCo".to_string());
+    gift_cards.issue_card("bob", 5_000);
+    println!("Providers: {}, {}", cards.get_name(), gift_cards.get_name());
+
+    println!("\n2. Checkout with the card processor (works fine):");
+    match Checkout::place_order(&mut cards, "alice", 2_499) {
+        Ok(charge) => {
+            if let Err(e) = Checkout::cancel_order(&mut cards, &charge) {
+                println!("Error: {}", e);
+            }
+        }
+        Err(e) => println!("Error: {}", e),
+    }
+
+    println!("\n3. Checkout with the gift card provider (fails!):");
+    if let Err(e) = Checkout::place_order(&mut gift_cards, "bob", 2_499) {
+        println!("Error: {}", e);
+    }
+
+    println!("\n4. Gift card provider's forced methods:");
+    if let Err(e) = gift_cards.create_subscription("bob", "Premium", 999) {
+        println!("Error: {}", e);
+    }
+    if let Err(e) = gift_cards.cancel_subscription("sub_1") {
+        println!("Error: {}", e);
+    }
+    if let Err(e) = gift_cards.payout("acme-store", 1_000) {
+        println!("Error: {}", e);
+    }
+    if let Ok(charge) = gift_cards.charge("bob", 1_000) {
+        if let Err(e) = Checkout::cancel_order(&mut gift_cards, &charge) {
+            println!("Error: {}", e);
+        }
+    }
+
+    println!("\n5. Card processor's full feature set:");
+    if let Ok(subscription) = cards.create_subscription("alice", "Premium", 999) {
+        let _ = cards.cancel_subscription(&subscription);
+    }
+    let _ = cards.payout("acme-store", 10_000);
+
+    println!("\n6. Problems with this design:");
+    println!("   - GiftCardProvider forced to implement 5 methods it can't honor");
+    println!("   - Checkout depends on refunds, subscriptions and payouts it never needs");
+    println!("   - Failures surface at runtime, during a customer's checkout");
+    println!("   - Violates ISP: clients forced to depend on methods they don't use");
+
+    println!("\n=== Conclusion ===");
+    println!("The fat PaymentProvider trait forces GiftCardProvider to stub out most");
+    println!("of its methods, violating the Interface Segregation Principle.");
+}
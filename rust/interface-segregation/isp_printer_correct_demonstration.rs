@@ -0,0 +1,273 @@
+/*
+ * Interface Segregation Principle (ISP) - CORRECT Example (Rust)
+ * Office Equipment Scenario
+ *
+ * This example demonstrates proper interface segregation for office devices.
+ * Instead of one MultiFunctionDevice trait, each capability gets its own
+ * small trait and devices implement only what their hardware supports.
+ *
+ * Solution: Print, Scan and Fax are separate traits. A CopyService is
+ * composed of exactly one scanner and one printer, nothing more.
+ */
+
+use std::fmt;
+
+// A document moving through the office
+#[derive(Debug, Clone)]
+struct Document {
+    title: String,
+    pages: u32,
+}
+
+impl Document {
+    fn new(title: &str, pages: u32) -> Self {
+        Document {
+            title: title.to_string(),
+            pages,
+        }
+    }
+}
+
+impl fmt::Display for Document {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "'{}' ({} pages)", self.title, self.pages)
+    }
+}
+
+// Segregated traits - each focused on a single capability
+
+// Printing capability
+trait Print {
+    fn print(&mut self, document: &Document);
+    fn get_pages_printed(&self) -> u32;
+}
+
+// Scanning capability
+trait Scan {
+    fn scan(&mut self, title: &str, pages: u32) -> Document;
+}
+
+// Fax capability
+trait Fax {
+    fn fax(&mut self, document: &Document, number: &str);
+    fn get_fax_number(&self) -> &str;
+}
+
+// Finishing capability (only devices with a finisher unit)
+trait Staple {
+    fn staple(&mut self, document: &Document);
+}
+
+// BasicPrinter implements only what it can actually do
+#[derive(Debug)]
+struct BasicPrinter {
+    model: String,
+    pages_printed: u32,
+}
+
+impl BasicPrinter {
+    fn new(model: String) -> Self {
+        BasicPrinter {
+            model,
+            pages_printed: 0,
+        }
+    }
+}
+
+impl Print for BasicPrinter {
+    fn print(&mut self, document: &Document) {
+        println!("{} is printing {}", self.model, document);
+        self.pages_printed += document.pages;
+    }
+
+    fn get_pages_printed(&self) -> u32 {
+        self.pages_printed
+    }
+}
+
+// A standalone flatbed scanner
+#[derive(Debug)]
+struct FlatbedScanner {
+    model: String,
+}
+
+impl FlatbedScanner {
+    fn new(model: String) -> Self {
+        FlatbedScanner { model }
+    }
+}
+
+impl Scan for FlatbedScanner {
+    fn scan(&mut self, title: &str, pages: u32) -> Document {
+        println!("{} is scanning '{}' page by page", self.model, title);
+        Document::new(title, pages)
+    }
+}
+
+// OfficeMultiFunctionPrinter implements every capability it really has
+#[derive(Debug)]
+struct OfficeMultiFunctionPrinter {
+    model: String,
+    fax_number: String,
+    pages_printed: u32,
+}
+
+impl OfficeMultiFunctionPrinter {
+    fn new(model: String, fax_number: String) -> Self {
+        OfficeMultiFunctionPrinter {
+            model,
+            fax_number,
+            pages_printed: 0,
+        }
+    }
+}
+
+impl Print for OfficeMultiFunctionPrinter {
+    fn print(&mut self, document: &Document) {
+        println!("{} is printing {} in duplex", self.model, document);
+        self.pages_printed += document.pages;
+    }
+
+    fn get_pages_printed(&self) -> u32 {
+        self.pages_printed
+    }
+}
+
+impl Scan for OfficeMultiFunctionPrinter {
+    fn scan(&mut self, title: &str, pages: u32) -> Document {
+        println!("{} is scanning '{}' through the document feeder", self.model, title);
+        Document::new(title, pages)
+    }
+}
+
+impl Fax for OfficeMultiFunctionPrinter {
+    fn fax(&mut self, document: &Document, number: &str) {
+        println!("{} is faxing {} from {} to {}", self.model, document, self.fax_number, number);
+    }
+
+    fn get_fax_number(&self) -> &str {
+        &self.fax_number
+    }
+}
+
+impl Staple for OfficeMultiFunctionPrinter {
+    fn staple(&mut self, document: &Document) {
+        println!("{} is stapling {}", self.model, document);
+    }
+}
+
+impl fmt::Display for BasicPrinter {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "BasicPrinter({}, {} pages printed)", self.model, self.pages_printed)
+    }
+}
+
+impl fmt::Display for FlatbedScanner {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "FlatbedScanner({})", self.model)
+    }
+}
+
+impl fmt::Display for OfficeMultiFunctionPrinter {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "OfficeMultiFunctionPrinter({}, fax {}, {} pages printed)", self.model, self.fax_number, self.pages_printed)
+    }
+}
+
+// Client composed of exactly the capabilities it needs: one scanner, one printer
+struct CopyService {
+    scanner: Box<dyn Scan>,
+    printer: Box<dyn Print>,
+}
+
+impl CopyService {
+    fn new(scanner: Box<dyn Scan>, printer: Box<dyn Print>) -> Self {
+        CopyService { scanner, printer }
+    }
+
+    fn copy(&mut self, title: &str, pages: u32, copies: u32) -> Document {
+        println!("Copying '{}' x{}...", title, copies);
+        let scanned = self.scanner.scan(title, pages);
+        for _ in 0..copies {
+            self.printer.print(&scanned);
+        }
+        scanned
+    }
+
+    fn get_pages_printed(&self) -> u32 {
+        self.printer.get_pages_printed()
+    }
+}
+
+// Other clients depend only on the traits they use
+struct OfficeManager;
+
+impl OfficeManager {
+    // Only needs printing
+    fn print_report(printer: &mut dyn Print, document: &Document) {
+        println!("Printing report {}", document);
+        printer.print(document);
+    }
+
+    // Needs scanning and faxing, nothing else
+    fn scan_and_fax(device: &mut (impl Scan + Fax), title: &str, number: &str) {
+        println!("Sending '{}' by fax from {}...", title, device.get_fax_number());
+        let scanned = device.scan(title, 1);
+        device.fax(&scanned, number);
+    }
+
+    // Needs printing and stapling
+    fn prepare_handout(device: &mut (impl Print + Staple), document: &Document) {
+        println!("Preparing handout...");
+        device.print(document);
+        device.staple(document);
+    }
+}
+
+// Demonstrates correct ISP implementation
+fn main() {
+    println!("=== Interface Segregation Principle - CORRECT: Office Equipment (Rust) ===");
+    println!();
+
+    println!("1. Creating office devices:");
+    let mut basic_printer = BasicPrinter::new("LaserLite-100".to_string());
+    let scanner = FlatbedScanner::new("ScanMaster-20".to_string());
+    let mut office_printer = OfficeMultiFunctionPrinter::new("OfficeJet-9000".to_string(), "+1-555-0199".to_string());
+    println!("{}", basic_printer);
+    println!("{}", scanner);
+    println!("{}", office_printer);
+
+    let report = Document::new("Quarterly Report", 12);
+
+    println!("\n2. Printing a report (only needs Print):");
+    OfficeManager::print_report(&mut basic_printer, &report);
+    OfficeManager::print_report(&mut office_printer, &report);
+
+    println!("\n3. Faxing a signed contract (needs Scan + Fax):");
+    OfficeManager::scan_and_fax(&mut office_printer, "Signed Contract", "+1-555-0100");
+    // OfficeManager::scan_and_fax(&mut basic_printer, ...) would not compile:
+    // BasicPrinter implements neither Scan nor Fax
+
+    println!("\n4. Preparing a handout (needs Print + Staple):");
+    OfficeManager::prepare_handout(&mut office_printer, &report);
+
+    println!("\n5. Assembling a CopyService from a standalone scanner and a basic printer:");
+    let mut copier = CopyService::new(Box::new(scanner), Box::new(basic_printer));
+    let copied = copier.copy("Meeting Notes", 3, 2);
+    println!("Copied {}; pages printed by the copier's printer: {}", copied, copier.get_pages_printed());
+
+    println!("\n6. Benefits of this design:");
+    println!("   - BasicPrinter implements only Print, with no Err stubs");
+    println!("   - Missing capabilities are caught at compile time, not mid-workflow");
+    println!("   - CopyService depends on exactly Scan and Print");
+    println!("   - Any scanner can be paired with any printer");
+    println!("   - New capabilities (e.g. Staple) don't touch existing devices");
+
+    println!("\n7. Device status:");
+    println!("{}", office_printer);
+
+    println!("\n=== Conclusion ===");
+    println!("Segregated Print, Scan, Fax and Staple traits let each device implement");
+    println!("only the features its hardware has, and let clients such as CopyService");
+    println!("depend on exactly the capabilities they use.");
+}
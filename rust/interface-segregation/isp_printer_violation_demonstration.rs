@@ -0,0 +1,217 @@
+/*
+ * Interface Segregation Principle (ISP) - VIOLATION Example (Rust)
+ * Office Equipment Scenario
+ *
+ * This example demonstrates how a monolithic MultiFunctionDevice trait
+ * forces a basic printer to implement scanning, faxing and stapling.
+ *
+ * Problem: BasicPrinter can only print, yet every client sees a device
+ * that claims to scan, fax and staple, so calls fail at runtime.
+ */
+
+use std::fmt;
+
+// A document moving through the office
+#[derive(Debug, Clone)]
+struct Document {
+    title: String,
+    pages: u32,
+}
+
+impl Document {
+    fn new(title: &str, pages: u32) -> Self {
+        Document {
+            title: title.to_string(),
+            pages,
+        }
+    }
+}
+
+impl fmt::Display for Document {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "'{}' ({} pages)", self.title, self.pages)
+    }
+}
+
+// VIOLATION: Monolithic trait forces every device to support every feature
+trait MultiFunctionDevice {
+    fn print(&mut self, document: &Document) -> Result<(), String>;
+    fn scan(&mut self, title: &str) -> Result<Document, String>;
+    fn fax(&mut self, document: &Document, number: &str) -> Result<(), String>;
+    fn staple(&mut self, document: &Document) -> Result<(), String>;
+}
+
+// Office multifunction printer - naturally implements all trait methods
+#[derive(Debug)]
+struct OfficeMultiFunctionPrinter {
+    model: String,
+    pages_printed: u32,
+}
+
+impl OfficeMultiFunctionPrinter {
+    fn new(model: String) -> Self {
+        OfficeMultiFunctionPrinter {
+            model,
+            pages_printed: 0,
+        }
+    }
+}
+
+impl MultiFunctionDevice for OfficeMultiFunctionPrinter {
+    fn print(&mut self, document: &Document) -> Result<(), String> {
+        println!("{} is printing {}", self.model, document);
+        self.pages_printed += document.pages;
+        Ok(())
+    }
+
+    fn scan(&mut self, title: &str) -> Result<Document, String> {
+        println!("{} is scanning '{}'", self.model, title);
+        Ok(Document::new(title, 1))
+    }
+
+    fn fax(&mut self, document: &Document, number: &str) -> Result<(), String> {
+        println!("{} is faxing {} to {}", self.model, document, number);
+        Ok(())
+    }
+
+    fn staple(&mut self, document: &Document) -> Result<(), String> {
+        println!("{} is stapling {}", self.model, document);
+        Ok(())
+    }
+}
+
+// VIOLATION: BasicPrinter forced to implement MultiFunctionDevice
+// This violates ISP because a basic printer only knows how to print
+#[derive(Debug)]
+struct BasicPrinter {
+    model: String,
+    pages_printed: u32,
+}
+
+impl BasicPrinter {
+    fn new(model: String) -> Self {
+        BasicPrinter {
+            model,
+            pages_printed: 0,
+        }
+    }
+}
+
+impl MultiFunctionDevice for BasicPrinter {
+    fn print(&mut self, document: &Document) -> Result<(), String> {
+        println!("{} is printing {}", self.model, document);
+        self.pages_printed += document.pages;
+        Ok(())
+    }
+
+    // VIOLATION: Forced to implement irrelevant methods
+    fn scan(&mut self, _title: &str) -> Result<Document, String> {
+        // No scanner bed!
+        Err(format!("{} has no scanner!", self.model))
+    }
+
+    fn fax(&mut self, _document: &Document, _number: &str) -> Result<(), String> {
+        // No phone line or modem
+        Err(format!("{} cannot send faxes!", self.model))
+    }
+
+    fn staple(&mut self, _document: &Document) -> Result<(), String> {
+        // No finisher unit
+        Err(format!("{} has no stapler!", self.model))
+    }
+}
+
+impl fmt::Display for OfficeMultiFunctionPrinter {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "OfficeMultiFunctionPrinter({}, {} pages printed)", self.model, self.pages_printed)
+    }
+}
+
+impl fmt::Display for BasicPrinter {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "BasicPrinter({}, {} pages printed)", self.model, self.pages_printed)
+    }
+}
+
+// Client code that expects the MultiFunctionDevice trait
+struct CopyService;
+
+impl CopyService {
+    // Copying only needs scan + print, but must accept the whole device trait
+    fn copy(device: &mut dyn MultiFunctionDevice, title: &str) -> Result<Document, String> {
+        println!("Copying '{}'...", title);
+        let scanned = device.scan(title)?;
+        device.print(&scanned)?;
+        Ok(scanned)
+    }
+
+    // This method only needs printing but still depends on fax and staple
+    fn print_report(device: &mut dyn MultiFunctionDevice, document: &Document) -> Result<(), String> {
+        println!("Printing report {}", document);
+        device.print(document)
+    }
+
+    // Expects the full office workflow to be available
+    fn prepare_handout(device: &mut dyn MultiFunctionDevice, document: &Document, fax_to: &str) -> Result<(), String> {
+        println!("Preparing handout...");
+
+        device.print(document).map_err(|e| format!("Failed to print: {}", e))?;
+        device.staple(document).map_err(|e| format!("Failed to staple: {}", e))?;
+        device.fax(document, fax_to).map_err(|e| format!("Failed to fax: {}", e))?;
+
+        Ok(())
+    }
+}
+
+// Demonstrates the ISP violation
+fn main() {
+    println!("=== Interface Segregation Principle - VIOLATION: Office Equipment (Rust) ===");
+    println!();
+
+    println!("1. Creating office devices:");
+    let mut office_printer = OfficeMultiFunctionPrinter::new("OfficeJet-9000".to_string());
+    let mut basic_printer = BasicPrinter::new("LaserLite-100".to_string());
+    println!("{}", office_printer);
+    println!("{}", basic_printer);
+
+    let report = Document::new("Quarterly Report", 12);
+
+    println!("\n2. Printing a report (works on both):");
+    if let Err(e) = CopyService::print_report(&mut office_printer, &report) {
+        println!("Error: {}", e);
+    }
+    if let Err(e) = CopyService::print_report(&mut basic_printer, &report) {
+        println!("Error: {}", e);
+    }
+
+    println!("\n3. Copying a contract on the multifunction printer (works fine):");
+    if let Err(e) = CopyService::copy(&mut office_printer, "Signed Contract") {
+        println!("Error: {}", e);
+    }
+
+    println!("\n4. Copying a contract on the basic printer (fails!):");
+    if let Err(e) = CopyService::copy(&mut basic_printer, "Signed Contract") {
+        println!("Error: {}", e);
+    }
+
+    println!("\n5. Preparing a handout on the basic printer (fails after printing!):");
+    if let Err(e) = CopyService::prepare_handout(&mut basic_printer, &report, "+1-555-0100") {
+        println!("Failed to prepare handout: {}", e);
+    }
+
+    println!("\n6. Problems with this design:");
+    println!("   - BasicPrinter forced to implement scan, fax and staple");
+    println!("   - Three of its four methods only return Err");
+    println!("   - Clients can't tell from the type which operations will work");
+    println!("   - CopyService depends on fax and staple even though it never uses them");
+    println!("   - Violates ISP: clients forced to depend on methods they don't use");
+
+    println!("\n7. Device status:");
+    println!("{}", office_printer);
+    println!("{}", basic_printer);
+
+    println!("\n=== Conclusion ===");
+    println!("The monolithic MultiFunctionDevice trait forces BasicPrinter to implement");
+    println!("features it does not have, violating the Interface Segregation Principle.");
+    println!("Failures only surface at runtime, often halfway through a workflow.");
+}
@@ -9,6 +9,7 @@
  */
 
 use std::fmt;
+use std::cell::RefCell;
 
 // VIOLATION: Monolithic trait forces all implementations to have methods they don't need
 trait Human {
@@ -264,6 +265,93 @@ impl WorkManager {
     }
 }
 
+// Records which trait methods each client actually calls, to measure how much
+// of the interface it was forced to depend on without using
+struct UsageTracker {
+    clients: RefCell<Vec<ClientUsage>>,
+}
+
+struct ClientUsage {
+    client: &'static str,
+    exposed: Vec<&'static str>,
+    used: Vec<&'static str>,
+}
+
+const HUMAN_METHODS: &[&str] = &[
+    "work", "eat", "sleep", "breathe", "think",
+    "reproduce", "exercise", "socialize", "feel_emotions", "dream",
+];
+
+impl UsageTracker {
+    fn new() -> Self {
+        UsageTracker { clients: RefCell::new(Vec::new()) }
+    }
+    
+    fn record(&self, client: &'static str, exposed: &[&'static str], method: &'static str) {
+        let mut clients = self.clients.borrow_mut();
+        let index = match clients.iter().position(|c| c.client == client) {
+            Some(index) => index,
+            None => {
+                clients.push(ClientUsage { client, exposed: exposed.to_vec(), used: Vec::new() });
+                clients.len() - 1
+            }
+        };
+        if !clients[index].used.contains(&method) {
+            clients[index].used.push(method);
+        }
+    }
+    
+    // Percentage of all exposed methods, summed over clients, that went unused
+    fn pollution_score(&self) -> f32 {
+        let clients = self.clients.borrow();
+        let exposed: usize = clients.iter().map(|c| c.exposed.len()).sum();
+        let used: usize = clients.iter().map(|c| c.used.len()).sum();
+        if exposed == 0 { 0.0 } else { (exposed - used) as f32 * 100.0 / exposed as f32 }
+    }
+}
+
+impl fmt::Display for UsageTracker {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for usage in self.clients.borrow().iter() {
+            let unused: Vec<&str> = usage.exposed.iter().filter(|m| !usage.used.contains(m)).copied().collect();
+            writeln!(f, "   {}: used {}/{} methods, {:.0}% unused {:?}",
+                     usage.client, usage.used.len(), usage.exposed.len(),
+                     unused.len() as f32 * 100.0 / usage.exposed.len() as f32, unused)?;
+        }
+        write!(f, "   Interface pollution score: {:.0}% of exposed methods unused", self.pollution_score())
+    }
+}
+
+// Instrumentation decorator: forwards every Human call and records it
+struct TrackedHuman<'a> {
+    inner: &'a mut dyn Human,
+    client: &'static str,
+    tracker: &'a UsageTracker,
+}
+
+impl<'a> TrackedHuman<'a> {
+    fn new(inner: &'a mut dyn Human, client: &'static str, tracker: &'a UsageTracker) -> Self {
+        TrackedHuman { inner, client, tracker }
+    }
+    
+    fn record(&self, method: &'static str) {
+        self.tracker.record(self.client, HUMAN_METHODS, method);
+    }
+}
+
+impl Human for TrackedHuman<'_> {
+    fn work(&mut self) { self.record("work"); self.inner.work() }
+    fn eat(&mut self) -> Result<(), String> { self.record("eat"); self.inner.eat() }
+    fn sleep(&mut self) -> Result<(), String> { self.record("sleep"); self.inner.sleep() }
+    fn breathe(&mut self) -> Result<(), String> { self.record("breathe"); self.inner.breathe() }
+    fn think(&mut self) { self.record("think"); self.inner.think() }
+    fn reproduce(&mut self) -> Result<(), String> { self.record("reproduce"); self.inner.reproduce() }
+    fn exercise(&mut self) -> Result<(), String> { self.record("exercise"); self.inner.exercise() }
+    fn socialize(&mut self) { self.record("socialize"); self.inner.socialize() }
+    fn feel_emotions(&mut self) -> Result<(), String> { self.record("feel_emotions"); self.inner.feel_emotions() }
+    fn dream(&mut self) -> Result<(), String> { self.record("dream"); self.inner.dream() }
+}
+
 // Function that checks if object follows Human trait contract
 fn check_human_compliance() -> bool {
     // In Rust, if a type implements a trait, it automatically follows the contract
@@ -310,6 +398,13 @@ fn main() {
     robot.run_diagnostics();
     robot.execute_program("Industrial Assembly v2.1");
     
+    println!("\n9. Measuring interface pollution (which Human methods each client calls):");
+    let tracker = UsageTracker::new();
+    WorkManager::assign_work(&mut TrackedHuman::new(&mut robot, "WorkManager::assign_work", &tracker), "Weld chassis");
+    WorkManager::manage_worker(&mut TrackedHuman::new(&mut person, "WorkManager::manage_worker", &tracker));
+    let _ = WorkManager::provide_care(&mut TrackedHuman::new(&mut person, "WorkManager::provide_care", &tracker));
+    println!("{}", tracker);
+    
     println!("\n=== Conclusion ===");
     println!("The monolithic Human trait forces Robot to implement");
     println!("irrelevant methods, violating the Interface Segregation Principle.");
@@ -0,0 +1,343 @@
+// Factory: entities created from specifications instead of constructed by hand
+//
+// - EntityFactory turns a specification into a configured entity, checking the
+//   specification first, so a caller never sees a half-configured Person or robot
+// - PersonFactory, IndustrialRobotFactory and HumanoidRobotFactory are the
+//   concrete factories, one per entity type
+// - KitFactory is an abstract factory: each kit produces an operator and a
+//   robot that belong together. IndustrialKit trains its operators for the
+//   software it installs on its robots, and DomesticKit does the same for
+//   household robots, so mixing a person from one kit with a robot from the
+//   other is what the kits exist to prevent.
+//
+// The transistor example in the open-closed folder has a factory module with
+// the same EntityFactory shape for its devices.
+
+use super::{
+    HumanoidRobot, IndustrialRobot, Mechanical, Person, Programmable, Rechargeable, SkillGraph,
+    SoftwarePackage, Version, Workable,
+};
+
+// Turns a specification into a configured product, or explains why it cannot.
+// Crate-visible only, because its products are the demo's private entity types.
+pub(crate) trait EntityFactory {
+    type Spec;
+    type Product;
+    
+    fn produce(&self, spec: &Self::Spec) -> Result<Self::Product, String>;
+}
+
+#[derive(Debug, Clone)]
+pub struct PersonSpec {
+    pub name: String,
+    // 0-100
+    pub energy: i32,
+    // Added to everyone's Basic Communication and Empathy, with proficiency 0-100
+    pub skills: Vec<(String, u8)>,
+}
+
+#[derive(Debug, Clone)]
+pub struct RobotSpec {
+    pub model: String,
+    // 0-100
+    pub battery_level: i32,
+    // Added to the model's built-in skills, with proficiency 0-100
+    pub skills: Vec<(String, u8)>,
+    pub software: Vec<(String, Version)>,
+}
+
+fn check_name(kind: &str, name: &str) -> Result<(), String> {
+    if name.trim().is_empty() {
+        return Err(format!("{} name must not be blank", kind));
+    }
+    Ok(())
+}
+
+fn check_percent(field: &str, value: i32) -> Result<(), String> {
+    if !(0..=100).contains(&value) {
+        return Err(format!("{} must be between 0 and 100, got {}", field, value));
+    }
+    Ok(())
+}
+
+fn check_skills(skills: &[(String, u8)]) -> Result<(), String> {
+    for (index, (name, proficiency)) in skills.iter().enumerate() {
+        if name.trim().is_empty() {
+            return Err(format!("Skill {} has no name", index + 1));
+        }
+        if *proficiency > 100 {
+            return Err(format!("Proficiency in {} must be at most 100, got {}", name, proficiency));
+        }
+        if skills[..index].iter().any(|(earlier, _)| earlier == name) {
+            return Err(format!("Skill {} is listed twice", name));
+        }
+    }
+    Ok(())
+}
+
+fn add_skills(graph: &mut SkillGraph, skills: &[(String, u8)]) -> Result<(), String> {
+    for (name, proficiency) in skills {
+        graph.add_skill(name, *proficiency, &[])?;
+    }
+    Ok(())
+}
+
+fn check_robot(spec: &RobotSpec) -> Result<(), String> {
+    check_name("Robot", &spec.model)?;
+    check_percent("Battery level", spec.battery_level)?;
+    check_skills(&spec.skills)?;
+    for (index, (name, _)) in spec.software.iter().enumerate() {
+        if spec.software[..index].iter().any(|(earlier, _)| earlier == name) {
+            return Err(format!("Package {} is listed twice", name));
+        }
+    }
+    Ok(())
+}
+
+fn install_all(robot: &mut dyn Programmable, software: &[(String, Version)]) -> Result<(), String> {
+    for (name, version) in software {
+        robot.install_software(SoftwarePackage::new(name, *version))?;
+    }
+    Ok(())
+}
+
+pub struct PersonFactory;
+
+impl EntityFactory for PersonFactory {
+    type Spec = PersonSpec;
+    type Product = Person;
+    
+    fn produce(&self, spec: &PersonSpec) -> Result<Person, String> {
+        check_name("Person", &spec.name)?;
+        check_percent("Energy", spec.energy)?;
+        check_skills(&spec.skills)?;
+        let mut person = Person::new(spec.name.clone());
+        person.energy = spec.energy;
+        add_skills(&mut person.skills, &spec.skills)?;
+        Ok(person)
+    }
+}
+
+pub struct IndustrialRobotFactory;
+
+impl EntityFactory for IndustrialRobotFactory {
+    type Spec = RobotSpec;
+    type Product = IndustrialRobot;
+    
+    fn produce(&self, spec: &RobotSpec) -> Result<IndustrialRobot, String> {
+        check_robot(spec)?;
+        let mut robot = IndustrialRobot::new(spec.model.clone());
+        robot.battery_level = spec.battery_level;
+        add_skills(&mut robot.skills, &spec.skills)?;
+        install_all(&mut robot, &spec.software)?;
+        Ok(robot)
+    }
+}
+
+pub struct HumanoidRobotFactory;
+
+impl EntityFactory for HumanoidRobotFactory {
+    type Spec = RobotSpec;
+    type Product = HumanoidRobot;
+    
+    fn produce(&self, spec: &RobotSpec) -> Result<HumanoidRobot, String> {
+        check_robot(spec)?;
+        let mut robot = HumanoidRobot::new(spec.model.clone());
+        robot.core.battery_level.set(spec.battery_level);
+        add_skills(&mut robot.mind.skills, &spec.skills)?;
+        install_all(&mut robot, &spec.software)?;
+        Ok(robot)
+    }
+}
+
+// What every kit's robot can do, whichever concrete robot the kit picks
+pub trait KitRobot: Workable + Mechanical + Rechargeable + Programmable {}
+
+impl<T: Workable + Mechanical + Rechargeable + Programmable> KitRobot for T {}
+
+// Abstract factory: one family of products that are meant to be used together
+pub trait KitFactory {
+    fn kit_name(&self) -> &'static str;
+    fn operator(&self, name: &str) -> Result<Person, String>;
+    fn robot(&self, serial: u32) -> Result<Box<dyn KitRobot>, String>;
+}
+
+// Precision assembly: an IndustrialRobot running the assembly controller, and
+// an operator certified on it
+pub struct IndustrialKit;
+
+impl IndustrialKit {
+    const SOFTWARE: &'static str = "Assembly Controller";
+}
+
+impl KitFactory for IndustrialKit {
+    fn kit_name(&self) -> &'static str {
+        "industrial kit"
+    }
+    
+    fn operator(&self, name: &str) -> Result<Person, String> {
+        PersonFactory.produce(&PersonSpec {
+            name: name.to_string(),
+            energy: 100,
+            skills: vec![(Self::SOFTWARE.to_string(), 80), ("Safety Procedures".to_string(), 90)],
+        })
+    }
+    
+    fn robot(&self, serial: u32) -> Result<Box<dyn KitRobot>, String> {
+        let robot = IndustrialRobotFactory.produce(&RobotSpec {
+            model: format!("IR-{}", serial),
+            battery_level: 100,
+            skills: vec![("Welding".to_string(), 85)],
+            software: vec![(Self::SOFTWARE.to_string(), Version::new(3, 2, 0))],
+        })?;
+        Ok(Box::new(robot))
+    }
+}
+
+// Help at home: a HumanoidRobot running the home assistant, and a carer who
+// knows how to direct it
+pub struct DomesticKit;
+
+impl DomesticKit {
+    const SOFTWARE: &'static str = "Home Assistant";
+}
+
+impl KitFactory for DomesticKit {
+    fn kit_name(&self) -> &'static str {
+        "domestic kit"
+    }
+    
+    fn operator(&self, name: &str) -> Result<Person, String> {
+        PersonFactory.produce(&PersonSpec {
+            name: name.to_string(),
+            energy: 90,
+            skills: vec![(Self::SOFTWARE.to_string(), 60), ("Household Management".to_string(), 75)],
+        })
+    }
+    
+    fn robot(&self, serial: u32) -> Result<Box<dyn KitRobot>, String> {
+        let robot = HumanoidRobotFactory.produce(&RobotSpec {
+            model: format!("Home-{}", serial),
+            battery_level: 80,
+            skills: vec![("Cooking".to_string(), 60)],
+            software: vec![(Self::SOFTWARE.to_string(), Version::new(1, 4, 0))],
+        })?;
+        Ok(Box::new(robot))
+    }
+}
+
+// An operator and a robot from the same kit
+pub struct Kit {
+    pub name: &'static str,
+    pub operator: Person,
+    pub robot: Box<dyn KitRobot>,
+}
+
+impl Kit {
+    pub fn assemble(factory: &dyn KitFactory, operator: &str, serial: u32) -> Result<Kit, String> {
+        Ok(Kit { name: factory.kit_name(), operator: factory.operator(operator)?, robot: factory.robot(serial)? })
+    }
+    
+    // Installed packages the operator is not trained on; empty for any kit a
+    // KitFactory assembled
+    pub fn untrained_software(&self) -> Vec<String> {
+        self.robot.get_installed_packages().into_iter()
+            .map(|package| package.name)
+            .filter(|name| !self.operator.skills.has_skill(name))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn skills(pairs: &[(&str, u8)]) -> Vec<(String, u8)> {
+        pairs.iter().map(|(name, proficiency)| (name.to_string(), *proficiency)).collect()
+    }
+    
+    fn person(name: &str, energy: i32, extra: &[(&str, u8)]) -> PersonSpec {
+        PersonSpec { name: name.to_string(), energy, skills: skills(extra) }
+    }
+    
+    fn robot(model: &str, battery_level: i32, software: &[(&str, u32)]) -> RobotSpec {
+        RobotSpec {
+            model: model.to_string(),
+            battery_level,
+            skills: skills(&[("Welding", 70)]),
+            software: software.iter().map(|(name, major)| (name.to_string(), Version::new(*major, 0, 0))).collect(),
+        }
+    }
+    
+    fn package_names(robot: &dyn Programmable) -> Vec<String> {
+        robot.get_installed_packages().into_iter().map(|package| package.name).collect()
+    }
+    
+    #[test]
+    fn person_is_configured_as_specified() {
+        let ada = PersonFactory.produce(&person("Ada", 70, &[("Welding", 50)])).unwrap();
+        assert_eq!((ada.name.as_str(), ada.energy), ("Ada", 70));
+        assert_eq!(ada.skills.proficiency("Welding"), 50);
+        assert!(ada.skills.has_skill("Empathy"), "keeps the skills every person starts with");
+    }
+    
+    #[test]
+    fn person_spec_is_checked_before_anything_is_built() {
+        for (spec, expected) in [
+            (person(" ", 50, &[]), "Person name must not be blank"),
+            (person("Bo", 140, &[]), "Energy must be between 0 and 100, got 140"),
+            (person("Bo", -1, &[]), "Energy must be between 0 and 100, got -1"),
+            (person("Bo", 50, &[("", 10)]), "Skill 1 has no name"),
+            (person("Bo", 50, &[("Welding", 101)]), "Proficiency in Welding must be at most 100, got 101"),
+            (person("Bo", 50, &[("Welding", 10), ("Welding", 20)]), "Skill Welding is listed twice"),
+        ] {
+            assert_eq!(PersonFactory.produce(&spec).err().unwrap(), expected);
+        }
+    }
+    
+    #[test]
+    fn robots_are_configured_as_specified() {
+        let spec = robot("IR-1", 55, &[("Vision", 2), ("Planner", 1)]);
+        let industrial = IndustrialRobotFactory.produce(&spec).unwrap();
+        assert_eq!((industrial.get_device_id(), industrial.get_battery_level()), ("IR-1".to_string(), 55));
+        assert_eq!(industrial.skills.proficiency("Welding"), 70);
+        assert_eq!(package_names(&industrial), ["Planner", "Vision"]);
+        
+        let humanoid = HumanoidRobotFactory.produce(&RobotSpec { model: "H-1".to_string(), ..spec }).unwrap();
+        assert_eq!((humanoid.get_device_id(), humanoid.get_battery_level()), ("H-1".to_string(), 55));
+        assert_eq!(humanoid.mind.skills.proficiency("Welding"), 70);
+        assert_eq!(package_names(&humanoid), ["Planner", "Vision"]);
+    }
+    
+    #[test]
+    fn robot_spec_is_checked_before_anything_is_built() {
+        for (spec, expected) in [
+            (robot("", 50, &[]), "Robot name must not be blank"),
+            (robot("IR-1", 101, &[]), "Battery level must be between 0 and 100, got 101"),
+            (robot("IR-1", 50, &[("Vision", 1), ("Vision", 2)]), "Package Vision is listed twice"),
+        ] {
+            assert_eq!(IndustrialRobotFactory.produce(&spec).err().unwrap(), expected);
+            assert_eq!(HumanoidRobotFactory.produce(&spec).err().unwrap(), expected);
+        }
+    }
+    
+    #[test]
+    fn each_kit_trains_its_operator_for_its_robot() {
+        let industrial = Kit::assemble(&IndustrialKit, "Ada", 7).unwrap();
+        assert_eq!((industrial.name, industrial.robot.get_device_id()), ("industrial kit", "IR-7".to_string()));
+        assert_eq!(industrial.operator.skills.proficiency(IndustrialKit::SOFTWARE), 80);
+        assert!(industrial.untrained_software().is_empty());
+        
+        let domestic = Kit::assemble(&DomesticKit, "Bo", 7).unwrap();
+        assert_eq!((domestic.robot.get_device_id(), domestic.robot.get_battery_level()), ("Home-7".to_string(), 80));
+        assert_eq!(domestic.operator.energy, 90);
+        assert!(domestic.untrained_software().is_empty());
+    }
+    
+    #[test]
+    fn mixing_kits_leaves_the_operator_untrained() {
+        let mixed = Kit { name: "mixed", operator: DomesticKit.operator("Bo").unwrap(), robot: IndustrialKit.robot(7).unwrap() };
+        assert_eq!(mixed.untrained_software(), [IndustrialKit::SOFTWARE]);
+        assert_eq!(Kit::assemble(&IndustrialKit, " ", 7).err().unwrap(), "Person name must not be blank");
+    }
+}
@@ -0,0 +1,476 @@
+// Observer: robots publish telemetry, and any number of observers react to it
+//
+// TelemetrySimulation steps a fleet of robots (work until the battery runs
+// low, then recharge) and publishes a Telemetry reading for each robot on
+// every tick. The TelemetryHub only holds weak references: an observer that
+// is dropped stops being notified and is cleaned out on the next publish,
+// without anyone calling unsubscribe.
+//
+// The simulation needs only Workable, Mechanical and Rechargeable from a
+// robot, so IndustrialRobot and the composed HumanoidRobot both qualify.
+
+use std::cell::{Cell, RefCell};
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+use std::rc::{Rc, Weak};
+use super::{Mechanical, Rechargeable, Workable, STANDARD_CHARGE_RATE};
+
+// Simulated minutes per tick, spent working or on the charger
+const TICK_MINUTES: u32 = 5;
+const AMBIENT_TEMPERATURE: f32 = 25.0;
+// Robots go to the charger at or below this battery level
+const RECHARGE_AT: i32 = 20;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum RobotStatus {
+    Working,
+    Charging,
+    Offline,
+}
+
+impl fmt::Display for RobotStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let label = match self {
+            RobotStatus::Working => "working",
+            RobotStatus::Charging => "charging",
+            RobotStatus::Offline => "offline",
+        };
+        write!(f, "{}", label)
+    }
+}
+
+// One robot's state at one tick
+#[derive(Debug, Clone, PartialEq)]
+pub struct Telemetry {
+    pub tick: u64,
+    pub device_id: String,
+    pub battery_level: i32,
+    pub temperature: f32,
+    pub status: RobotStatus,
+}
+
+impl fmt::Display for Telemetry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "[t{:02}] {:<16} battery {:>3}%  {:>5.1}°C  {}",
+               self.tick, self.device_id, self.battery_level, self.temperature, self.status)
+    }
+}
+
+// Observer trait - told about every reading; observers keep their own state in cells
+pub trait TelemetryObserver {
+    fn on_telemetry(&self, reading: &Telemetry);
+}
+
+// Handle returned by subscribe, for unsubscribing
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Subscription(u64);
+
+// Subject: delivers readings to the observers still alive, in subscription order
+#[derive(Default)]
+pub struct TelemetryHub {
+    observers: RefCell<Vec<(Subscription, Weak<dyn TelemetryObserver>)>>,
+    next_id: Cell<u64>,
+}
+
+impl TelemetryHub {
+    // The hub keeps a weak reference; the caller's Rc decides how long the observer lives
+    pub fn subscribe<O: TelemetryObserver + 'static>(&self, observer: &Rc<O>) -> Subscription {
+        let subscription = Subscription(self.next_id.get());
+        self.next_id.set(subscription.0 + 1);
+        let observer: Weak<O> = Rc::downgrade(observer);
+        let observer: Weak<dyn TelemetryObserver> = observer;
+        self.observers.borrow_mut().push((subscription, observer));
+        subscription
+    }
+    
+    // False if the subscription was already removed
+    pub fn unsubscribe(&self, subscription: Subscription) -> bool {
+        let mut observers = self.observers.borrow_mut();
+        let before = observers.len();
+        observers.retain(|(id, _)| *id != subscription);
+        observers.len() < before
+    }
+    
+    // Observers still alive, after dropping the dead ones
+    pub fn observer_count(&self) -> usize {
+        self.live_observers().len()
+    }
+    
+    // Returns how many observers were notified
+    pub fn publish(&self, reading: &Telemetry) -> usize {
+        // Upgrade first and release the borrow, so an observer may (un)subscribe while notified
+        let observers = self.live_observers();
+        for observer in &observers {
+            observer.on_telemetry(reading);
+        }
+        observers.len()
+    }
+    
+    fn live_observers(&self) -> Vec<Rc<dyn TelemetryObserver>> {
+        let mut observers = self.observers.borrow_mut();
+        observers.retain(|(_, observer)| observer.strong_count() > 0);
+        observers.iter().filter_map(|(_, observer)| observer.upgrade()).collect()
+    }
+}
+
+// Prints every reading as one dashboard line, and keeps the lines
+#[derive(Default)]
+pub struct ConsoleDashboard {
+    lines: RefCell<Vec<String>>,
+}
+
+impl ConsoleDashboard {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    pub fn lines(&self) -> Vec<String> {
+        self.lines.borrow().clone()
+    }
+}
+
+impl TelemetryObserver for ConsoleDashboard {
+    fn on_telemetry(&self, reading: &Telemetry) {
+        let line = reading.to_string();
+        println!("  {}", line);
+        self.lines.borrow_mut().push(line);
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AlertKind {
+    LowBattery,
+    Overheating,
+    Offline,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Alert {
+    pub tick: u64,
+    pub device_id: String,
+    pub kind: AlertKind,
+}
+
+// Raises an alert when a robot crosses a threshold, once per crossing: the
+// alert re-arms only after the reading is back within limits
+pub struct AlertMonitor {
+    min_battery: i32,
+    max_temperature: f32,
+    active: RefCell<HashSet<(String, AlertKind)>>,
+    alerts: RefCell<Vec<Alert>>,
+}
+
+impl AlertMonitor {
+    pub fn new(min_battery: i32, max_temperature: f32) -> Self {
+        AlertMonitor { min_battery, max_temperature, active: RefCell::new(HashSet::new()), alerts: RefCell::new(Vec::new()) }
+    }
+    
+    pub fn alerts(&self) -> Vec<Alert> {
+        self.alerts.borrow().clone()
+    }
+}
+
+impl TelemetryObserver for AlertMonitor {
+    fn on_telemetry(&self, reading: &Telemetry) {
+        let conditions = [
+            (AlertKind::LowBattery, reading.battery_level < self.min_battery),
+            (AlertKind::Overheating, reading.temperature > self.max_temperature),
+            (AlertKind::Offline, reading.status == RobotStatus::Offline),
+        ];
+        let mut active = self.active.borrow_mut();
+        for (kind, breached) in conditions {
+            let key = (reading.device_id.clone(), kind);
+            if !breached {
+                active.remove(&key);
+            } else if active.insert(key) {
+                println!("  ALERT {:?} on {} at tick {}", kind, reading.device_id, reading.tick);
+                self.alerts.borrow_mut().push(Alert { tick: reading.tick, device_id: reading.device_id.clone(), kind });
+            }
+        }
+    }
+}
+
+// Aggregates per robot, for a report at the end of a run
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeviceMetrics {
+    pub readings: u64,
+    pub min_battery: i32,
+    pub max_temperature: f32,
+    pub ticks_by_status: BTreeMap<RobotStatus, u64>,
+}
+
+#[derive(Default)]
+pub struct MetricsRecorder {
+    devices: RefCell<BTreeMap<String, DeviceMetrics>>,
+}
+
+impl MetricsRecorder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    pub fn metrics(&self, device_id: &str) -> Option<DeviceMetrics> {
+        self.devices.borrow().get(device_id).cloned()
+    }
+}
+
+impl TelemetryObserver for MetricsRecorder {
+    fn on_telemetry(&self, reading: &Telemetry) {
+        let mut devices = self.devices.borrow_mut();
+        let metrics = devices.entry(reading.device_id.clone()).or_insert(DeviceMetrics {
+            readings: 0,
+            min_battery: reading.battery_level,
+            max_temperature: reading.temperature,
+            ticks_by_status: BTreeMap::new(),
+        });
+        metrics.readings += 1;
+        metrics.min_battery = metrics.min_battery.min(reading.battery_level);
+        metrics.max_temperature = metrics.max_temperature.max(reading.temperature);
+        *metrics.ticks_by_status.entry(reading.status).or_insert(0) += 1;
+    }
+}
+
+impl fmt::Display for MetricsRecorder {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (device_id, metrics) in self.devices.borrow().iter() {
+            writeln!(f, "  {:<16} {} readings, battery >= {}%, peak {:.1}°C, {:?}",
+                     device_id, metrics.readings, metrics.min_battery, metrics.max_temperature, metrics.ticks_by_status)?;
+        }
+        Ok(())
+    }
+}
+
+// The capabilities the simulation uses, and no others
+pub trait TelemetrySource: Workable + Mechanical + Rechargeable {}
+
+impl<T: Workable + Mechanical + Rechargeable> TelemetrySource for T {}
+
+struct SimulatedRobot {
+    device: Box<dyn TelemetrySource>,
+    // Robots have no sensor for it, so the simulation models it
+    temperature: f32,
+}
+
+impl SimulatedRobot {
+    fn status(&self) -> RobotStatus {
+        // IndustrialRobot and MechanicalFrame both report exactly this when powered down
+        if self.device.get_mechanical_status() == "Systems offline" {
+            RobotStatus::Offline
+        } else if self.device.is_charging() {
+            RobotStatus::Charging
+        } else {
+            RobotStatus::Working
+        }
+    }
+    
+    // Works until the battery runs low, then charges until full.
+    // Work heats the robot, more so as it wears; charging and downtime cool it.
+    fn advance(&mut self) {
+        let cooled = (self.temperature - 8.0).max(AMBIENT_TEMPERATURE);
+        match self.status() {
+            RobotStatus::Offline => self.temperature = cooled,
+            RobotStatus::Charging => {
+                self.device.tick_charge(TICK_MINUTES);
+                self.temperature = cooled;
+            }
+            RobotStatus::Working if self.device.get_battery_level() <= RECHARGE_AT => {
+                if self.device.start_charging(STANDARD_CHARGE_RATE).is_ok() {
+                    self.device.tick_charge(TICK_MINUTES);
+                }
+                self.temperature = cooled;
+            }
+            RobotStatus::Working => {
+                self.device.work();
+                self.temperature += 4.0 + 10.0 * self.device.get_wear_level();
+            }
+        }
+    }
+}
+
+// A fleet of robots publishing to one hub
+#[derive(Default)]
+pub struct TelemetrySimulation {
+    robots: Vec<SimulatedRobot>,
+    hub: TelemetryHub,
+    tick: u64,
+}
+
+impl TelemetrySimulation {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    pub fn add_robot(&mut self, device: impl TelemetrySource + 'static) {
+        self.robots.push(SimulatedRobot { device: Box::new(device), temperature: AMBIENT_TEMPERATURE });
+    }
+    
+    pub fn hub(&self) -> &TelemetryHub {
+        &self.hub
+    }
+    
+    // Advances every robot one tick and publishes its reading
+    pub fn step(&mut self) {
+        self.tick += 1;
+        for robot in &mut self.robots {
+            robot.advance();
+            let reading = Telemetry {
+                tick: self.tick,
+                device_id: robot.device.get_device_id(),
+                battery_level: robot.device.get_battery_level(),
+                temperature: robot.temperature,
+                status: robot.status(),
+            };
+            self.hub.publish(&reading);
+        }
+    }
+    
+    pub fn run(&mut self, ticks: u64) {
+        for _ in 0..ticks {
+            self.step();
+        }
+    }
+    
+    // False if no robot has that id
+    pub fn power_down(&mut self, device_id: &str) -> bool {
+        match self.robots.iter_mut().find(|robot| robot.device.get_device_id() == device_id) {
+            Some(robot) => {
+                robot.device.power_down();
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::IndustrialRobot;
+    
+    // Keeps "<name>@t<tick>" for every reading, in delivery order, in a log
+    // shared by several observers
+    struct Recorder {
+        name: &'static str,
+        log: Rc<RefCell<Vec<String>>>,
+    }
+    
+    impl TelemetryObserver for Recorder {
+        fn on_telemetry(&self, reading: &Telemetry) {
+            self.log.borrow_mut().push(format!("{}@t{}", self.name, reading.tick));
+        }
+    }
+    
+    fn recorder(name: &'static str, log: &Rc<RefCell<Vec<String>>>) -> Rc<Recorder> {
+        Rc::new(Recorder { name, log: Rc::clone(log) })
+    }
+    
+    fn reading(tick: u64, battery_level: i32, temperature: f32, status: RobotStatus) -> Telemetry {
+        Telemetry { tick, device_id: "Kuka-7".to_string(), battery_level, temperature, status }
+    }
+    
+    fn working(tick: u64) -> Telemetry {
+        reading(tick, 80, 30.0, RobotStatus::Working)
+    }
+    
+    #[test]
+    fn every_observer_gets_each_reading_in_subscription_order() {
+        let log = Rc::new(RefCell::new(Vec::new()));
+        let hub = TelemetryHub::default();
+        let (first, second) = (recorder("first", &log), recorder("second", &log));
+        hub.subscribe(&first);
+        hub.subscribe(&second);
+        
+        assert_eq!(hub.publish(&working(1)), 2);
+        assert_eq!(hub.publish(&working(2)), 2);
+        assert_eq!(*log.borrow(), ["first@t1", "second@t1", "first@t2", "second@t2"]);
+    }
+    
+    #[test]
+    fn unsubscribe_stops_delivery_to_that_observer_only() {
+        let log = Rc::new(RefCell::new(Vec::new()));
+        let hub = TelemetryHub::default();
+        let (first, second) = (recorder("first", &log), recorder("second", &log));
+        let subscription = hub.subscribe(&first);
+        hub.subscribe(&second);
+        
+        assert!(hub.unsubscribe(subscription));
+        assert!(!hub.unsubscribe(subscription), "already removed");
+        assert_eq!(hub.publish(&working(1)), 1);
+        assert_eq!(*log.borrow(), ["second@t1"]);
+        assert_eq!(hub.observer_count(), 1);
+    }
+    
+    #[test]
+    fn dropped_observers_are_cleaned_out() {
+        let log = Rc::new(RefCell::new(Vec::new()));
+        let hub = TelemetryHub::default();
+        let kept = recorder("kept", &log);
+        let dropped = recorder("dropped", &log);
+        hub.subscribe(&kept);
+        hub.subscribe(&dropped);
+        assert_eq!(hub.observer_count(), 2);
+        
+        drop(dropped);
+        assert_eq!(hub.observer_count(), 1);
+        assert_eq!(hub.observers.borrow().len(), 1, "the dead entry is removed, not just skipped");
+        assert_eq!(hub.publish(&working(1)), 1);
+        assert_eq!(*log.borrow(), ["kept@t1"]);
+    }
+    
+    #[test]
+    fn dashboard_keeps_one_line_per_reading() {
+        let dashboard = ConsoleDashboard::new();
+        dashboard.on_telemetry(&working(3));
+        assert_eq!(dashboard.lines(), ["[t03] Kuka-7           battery  80%   30.0°C  working"]);
+    }
+    
+    #[test]
+    fn alerts_fire_once_per_crossing_and_rearm_after_recovery() {
+        let monitor = AlertMonitor::new(20, 60.0);
+        for telemetry in [
+            reading(1, 15, 30.0, RobotStatus::Working),
+            reading(2, 10, 65.0, RobotStatus::Working),
+            reading(3, 50, 30.0, RobotStatus::Charging),
+            reading(4, 12, 30.0, RobotStatus::Offline),
+        ] {
+            monitor.on_telemetry(&telemetry);
+        }
+        let alerts: Vec<(u64, AlertKind)> = monitor.alerts().into_iter().map(|alert| (alert.tick, alert.kind)).collect();
+        assert_eq!(alerts, [
+            (1, AlertKind::LowBattery),
+            (2, AlertKind::Overheating),
+            (4, AlertKind::LowBattery),
+            (4, AlertKind::Offline),
+        ]);
+    }
+    
+    #[test]
+    fn metrics_aggregate_per_device() {
+        let recorder = MetricsRecorder::new();
+        recorder.on_telemetry(&reading(1, 80, 30.0, RobotStatus::Working));
+        recorder.on_telemetry(&reading(2, 40, 52.5, RobotStatus::Working));
+        recorder.on_telemetry(&reading(3, 60, 41.0, RobotStatus::Charging));
+        
+        let metrics = recorder.metrics("Kuka-7").unwrap();
+        assert_eq!((metrics.readings, metrics.min_battery, metrics.max_temperature), (3, 40, 52.5));
+        assert_eq!(metrics.ticks_by_status, BTreeMap::from([(RobotStatus::Working, 2), (RobotStatus::Charging, 1)]));
+        assert_eq!(recorder.metrics("Atlas-3"), None);
+    }
+    
+    #[test]
+    fn simulation_publishes_a_reading_per_robot_per_tick() {
+        let mut simulation = TelemetrySimulation::new();
+        simulation.add_robot(IndustrialRobot::new("Kuka-7".to_string()));
+        let metrics = Rc::new(MetricsRecorder::new());
+        let alerts = Rc::new(AlertMonitor::new(0, 1000.0));
+        simulation.hub().subscribe(&metrics);
+        simulation.hub().subscribe(&alerts);
+        
+        simulation.run(3);
+        assert_eq!(metrics.metrics("Kuka-7").unwrap().ticks_by_status, BTreeMap::from([(RobotStatus::Working, 3)]));
+        assert!(simulation.power_down("Kuka-7"));
+        assert!(!simulation.power_down("Atlas-3"));
+        simulation.step();
+        assert_eq!(alerts.alerts(), [Alert { tick: 4, device_id: "Kuka-7".to_string(), kind: AlertKind::Offline }]);
+    }
+}
@@ -0,0 +1,299 @@
+// State: a robot's operational lifecycle as an explicit state machine
+//
+// Off -> Booting -> Operational <-> Maintenance, with Fault reachable from any
+// powered state. Each state says which capabilities it allows, so a method
+// such as work() asks the lifecycle instead of checking a bool, and a robot
+// that is booting, faulted or in maintenance refuses it with a reason.
+//
+//   event              from                                   to
+//   PowerOn            Off                                    Booting
+//   BootComplete       Booting                                Operational
+//   StartMaintenance   Operational, Fault                     Maintenance
+//   FinishMaintenance  Maintenance                            Operational
+//   Fail               Booting, Operational, Maintenance      Fault
+//   Reset              Fault                                  Booting
+//   PowerOff           any state but Off                      Off
+//
+// Every other pair is illegal and leaves the state unchanged.
+
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RobotState {
+    Off,
+    Booting,
+    Operational,
+    Maintenance,
+    Fault,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LifecycleEvent {
+    PowerOn,
+    BootComplete,
+    StartMaintenance,
+    FinishMaintenance,
+    Fail,
+    Reset,
+    PowerOff,
+}
+
+// What a state can allow; charging and diagnostics are always allowed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Activity {
+    Work,
+    Think,
+    ExecuteProgram,
+    InstallSoftware,
+    Network,
+}
+
+impl RobotState {
+    pub const ALL: [RobotState; 5] = [
+        RobotState::Off,
+        RobotState::Booting,
+        RobotState::Operational,
+        RobotState::Maintenance,
+        RobotState::Fault,
+    ];
+    
+    // The state `event` leads to, or None if it is illegal in this state
+    pub fn on(self, event: LifecycleEvent) -> Option<RobotState> {
+        use LifecycleEvent::*;
+        use RobotState::*;
+        match (self, event) {
+            (Off, PowerOn) => Some(Booting),
+            (Booting, BootComplete) => Some(Operational),
+            (Operational | Fault, StartMaintenance) => Some(Maintenance),
+            (Maintenance, FinishMaintenance) => Some(Operational),
+            (Booting | Operational | Maintenance, Fail) => Some(Fault),
+            (Fault, Reset) => Some(Booting),
+            (Booting | Operational | Maintenance | Fault, PowerOff) => Some(Off),
+            _ => None,
+        }
+    }
+    
+    pub fn allows(self, activity: Activity) -> bool {
+        match activity {
+            Activity::Work | Activity::ExecuteProgram => self == RobotState::Operational,
+            Activity::Think | Activity::InstallSoftware | Activity::Network => {
+                matches!(self, RobotState::Operational | RobotState::Maintenance)
+            }
+        }
+    }
+}
+
+impl Activity {
+    pub const ALL: [Activity; 5] = [
+        Activity::Work,
+        Activity::Think,
+        Activity::ExecuteProgram,
+        Activity::InstallSoftware,
+        Activity::Network,
+    ];
+}
+
+impl fmt::Display for RobotState {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let label = match self {
+            RobotState::Off => "Off",
+            RobotState::Booting => "Booting",
+            RobotState::Operational => "Operational",
+            RobotState::Maintenance => "Maintenance",
+            RobotState::Fault => "Fault",
+        };
+        write!(f, "{}", label)
+    }
+}
+
+impl fmt::Display for LifecycleEvent {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let label = match self {
+            LifecycleEvent::PowerOn => "power on",
+            LifecycleEvent::BootComplete => "complete booting",
+            LifecycleEvent::StartMaintenance => "start maintenance",
+            LifecycleEvent::FinishMaintenance => "finish maintenance",
+            LifecycleEvent::Fail => "fail",
+            LifecycleEvent::Reset => "reset",
+            LifecycleEvent::PowerOff => "power off",
+        };
+        write!(f, "{}", label)
+    }
+}
+
+impl fmt::Display for Activity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let label = match self {
+            Activity::Work => "work",
+            Activity::Think => "think",
+            Activity::ExecuteProgram => "execute programs",
+            Activity::InstallSoftware => "install software",
+            Activity::Network => "use the network",
+        };
+        write!(f, "{}", label)
+    }
+}
+
+// The current state and every transition taken to reach it; starts Off
+#[derive(Debug, Clone)]
+pub struct Lifecycle {
+    state: RobotState,
+    history: Vec<(RobotState, LifecycleEvent, RobotState)>,
+}
+
+impl Default for Lifecycle {
+    fn default() -> Self {
+        Lifecycle { state: RobotState::Off, history: Vec::new() }
+    }
+}
+
+impl Lifecycle {
+    pub fn new() -> Self {
+        Lifecycle::default()
+    }
+    
+    // Powered on and booted, as a robot is delivered
+    pub fn booted() -> Self {
+        let mut lifecycle = Lifecycle::new();
+        for event in [LifecycleEvent::PowerOn, LifecycleEvent::BootComplete] {
+            let _ = lifecycle.handle(event);
+        }
+        lifecycle
+    }
+    
+    pub fn state(&self) -> RobotState {
+        self.state
+    }
+    
+    pub fn history(&self) -> &[(RobotState, LifecycleEvent, RobotState)] {
+        &self.history
+    }
+    
+    pub fn handle(&mut self, event: LifecycleEvent) -> Result<RobotState, String> {
+        let next = self.state.on(event).ok_or_else(|| format!("Cannot {} while {}", event, self.state))?;
+        self.history.push((self.state, event, next));
+        self.state = next;
+        Ok(next)
+    }
+    
+    pub fn permit(&self, activity: Activity) -> Result<(), String> {
+        if self.state.allows(activity) {
+            Ok(())
+        } else {
+            Err(format!("Cannot {} while {}", activity, self.state))
+        }
+    }
+}
+
+// Tests for every (state, event) pair against the table above, and for what
+// each state allows
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use LifecycleEvent::*;
+    use RobotState::*;
+    
+    const EVENTS: [LifecycleEvent; 7] = [PowerOn, BootComplete, StartMaintenance, FinishMaintenance, Fail, Reset, PowerOff];
+    
+    const LEGAL: [(RobotState, LifecycleEvent, RobotState); 13] = [
+        (Off, PowerOn, Booting),
+        (Booting, BootComplete, Operational),
+        (Operational, StartMaintenance, Maintenance),
+        (Fault, StartMaintenance, Maintenance),
+        (Maintenance, FinishMaintenance, Operational),
+        (Booting, Fail, Fault),
+        (Operational, Fail, Fault),
+        (Maintenance, Fail, Fault),
+        (Fault, Reset, Booting),
+        (Booting, PowerOff, Off),
+        (Operational, PowerOff, Off),
+        (Maintenance, PowerOff, Off),
+        (Fault, PowerOff, Off),
+    ];
+    
+    fn expected(from: RobotState, event: LifecycleEvent) -> Option<RobotState> {
+        LEGAL.iter().find(|(f, e, _)| (*f, *e) == (from, event)).map(|(_, _, to)| *to)
+    }
+    
+    // Drives a fresh lifecycle into `state` through legal events only
+    fn lifecycle_in(state: RobotState) -> Lifecycle {
+        let path: &[LifecycleEvent] = match state {
+            Off => &[],
+            Booting => &[PowerOn],
+            Operational => &[PowerOn, BootComplete],
+            Maintenance => &[PowerOn, BootComplete, StartMaintenance],
+            Fault => &[PowerOn, Fail],
+        };
+        let mut lifecycle = Lifecycle::new();
+        for event in path {
+            lifecycle.handle(*event).unwrap();
+        }
+        assert_eq!(lifecycle.state(), state);
+        lifecycle
+    }
+    
+    #[test]
+    fn every_pair_matches_the_table() {
+        for from in RobotState::ALL {
+            for event in EVENTS {
+                assert_eq!(from.on(event), expected(from, event), "{} while {}", event, from);
+            }
+        }
+    }
+    
+    #[test]
+    fn legal_events_move_the_lifecycle_and_are_recorded() {
+        for from in RobotState::ALL {
+            for event in EVENTS {
+                let Some(to) = expected(from, event) else { continue };
+                let mut lifecycle = lifecycle_in(from);
+                let before = lifecycle.history().len();
+                assert_eq!(lifecycle.handle(event), Ok(to));
+                assert_eq!(lifecycle.state(), to);
+                assert_eq!(lifecycle.history().len(), before + 1);
+                assert_eq!(lifecycle.history().last(), Some(&(from, event, to)));
+            }
+        }
+    }
+    
+    #[test]
+    fn illegal_events_are_refused_and_change_nothing() {
+        for from in RobotState::ALL {
+            for event in EVENTS {
+                if expected(from, event).is_some() {
+                    continue;
+                }
+                let mut lifecycle = lifecycle_in(from);
+                let before = lifecycle.history().to_vec();
+                assert_eq!(lifecycle.handle(event), Err(format!("Cannot {} while {}", event, from)));
+                assert_eq!(lifecycle.state(), from);
+                assert_eq!(lifecycle.history(), &before[..]);
+            }
+        }
+    }
+    
+    #[test]
+    fn each_state_allows_its_activities() {
+        for state in RobotState::ALL {
+            for activity in Activity::ALL {
+                let allowed = match activity {
+                    Activity::Work | Activity::ExecuteProgram => state == Operational,
+                    _ => matches!(state, Operational | Maintenance),
+                };
+                assert_eq!(state.allows(activity), allowed, "{} while {}", activity, state);
+                let permit = lifecycle_in(state).permit(activity);
+                assert_eq!(permit.is_ok(), allowed);
+                if !allowed {
+                    assert_eq!(permit, Err(format!("Cannot {} while {}", activity, state)));
+                }
+            }
+        }
+    }
+    
+    #[test]
+    fn booted_lifecycle_is_operational_with_its_boot_recorded() {
+        let lifecycle = Lifecycle::booted();
+        assert_eq!(lifecycle.state(), Operational);
+        assert_eq!(lifecycle.history(), &[(Off, PowerOn, Booting), (Booting, BootComplete, Operational)]);
+    }
+}
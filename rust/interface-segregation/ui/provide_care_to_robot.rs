@@ -0,0 +1,12 @@
+// A robot is not Biological, so it cannot be handed to biological care
+
+#[path = "../isp_correct_demonstration.rs"]
+#[allow(dead_code, private_interfaces)]
+mod correct;
+
+use correct::{BiologicalCareProvider, IndustrialRobot};
+
+fn main() {
+    let mut robot = IndustrialRobot::new("Kuka-7".to_string());
+    let _ = BiologicalCareProvider::provide_care(&mut robot);
+}
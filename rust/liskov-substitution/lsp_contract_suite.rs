@@ -0,0 +1,449 @@
+// CONTRACT SUITES - CHECKING LSP FOR YOUR OWN TRAITS
+//
+// The correct and violation demonstrations check the DataStructure contract
+// by hand. This file turns that idea into reusable machinery: declare a
+// trait's contract once (invariants, preconditions, postconditions and an
+// input generator) with ContractSuiteBuilder, then run the same suite against
+// every implementation and compare the reports.
+//
+// The example contract is for a small Cache trait.
+
+use std::fmt;
+
+/// Deterministic pseudo-random source handed to input generators (xorshift64)
+pub struct CaseRng {
+    state: u64,
+}
+
+impl CaseRng {
+    pub fn new(seed: u64) -> Self {
+        CaseRng { state: seed.max(1) }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 7;
+        self.state ^= self.state << 17;
+        self.state
+    }
+
+    /// Uniform value in `0..bound`
+    pub fn below(&mut self, bound: u64) -> u64 {
+        self.next_u64() % bound.max(1)
+    }
+}
+
+type Generator<I> = Box<dyn Fn(&mut CaseRng) -> I>;
+type Invariant<T> = Box<dyn Fn(&T) -> bool>;
+type Precondition<T, I> = Box<dyn Fn(&T, &I) -> bool>;
+type Postcondition<T, I> = Box<dyn Fn(&mut T, &I) -> Result<(), String>>;
+
+/// A postcondition, optionally guarded by a precondition on the subject and input
+struct Rule<T: ?Sized, I> {
+    name: String,
+    precondition: Option<Precondition<T, I>>,
+    postcondition: Postcondition<T, I>,
+}
+
+/// Declares the behavioral contract of a trait `T` exercised with inputs of type `I`
+pub struct ContractSuiteBuilder<T: ?Sized, I> {
+    name: String,
+    cases: usize,
+    seed: u64,
+    generator: Option<Generator<I>>,
+    invariants: Vec<(String, Invariant<T>)>,
+    rules: Vec<Rule<T, I>>,
+}
+
+impl<T: ?Sized, I: fmt::Debug> ContractSuiteBuilder<T, I> {
+    pub fn new(name: &str) -> Self {
+        ContractSuiteBuilder {
+            name: name.to_string(),
+            cases: 100,
+            seed: 42,
+            generator: None,
+            invariants: Vec::new(),
+            rules: Vec::new(),
+        }
+    }
+
+    /// Number of generated inputs each rule is checked against
+    pub fn cases(mut self, cases: usize) -> Self {
+        self.cases = cases;
+        self
+    }
+
+    /// Seed for the input generator, so failing runs can be reproduced
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    pub fn generator(mut self, generator: impl Fn(&mut CaseRng) -> I + 'static) -> Self {
+        self.generator = Some(Box::new(generator));
+        self
+    }
+
+    /// Must hold for every implementation after every rule
+    pub fn invariant(mut self, name: &str, invariant: impl Fn(&T) -> bool + 'static) -> Self {
+        self.invariants.push((name.to_string(), Box::new(invariant)));
+        self
+    }
+
+    /// Postcondition checked on every generated input
+    pub fn rule(mut self, name: &str, postcondition: impl Fn(&mut T, &I) -> Result<(), String> + 'static) -> Self {
+        self.rules.push(Rule {
+            name: name.to_string(),
+            precondition: None,
+            postcondition: Box::new(postcondition),
+        });
+        self
+    }
+
+    /// Postcondition checked only on inputs where the precondition holds.
+    /// Implementations may not strengthen it: callers only promise the precondition.
+    pub fn rule_when(
+        mut self,
+        name: &str,
+        precondition: impl Fn(&T, &I) -> bool + 'static,
+        postcondition: impl Fn(&mut T, &I) -> Result<(), String> + 'static,
+    ) -> Self {
+        self.rules.push(Rule {
+            name: name.to_string(),
+            precondition: Some(Box::new(precondition)),
+            postcondition: Box::new(postcondition),
+        });
+        self
+    }
+
+    pub fn build(self) -> Result<ContractSuite<T, I>, String> {
+        let Some(generator) = self.generator else {
+            return Err(format!("Contract suite '{}' has no input generator", self.name));
+        };
+        if self.rules.is_empty() && self.invariants.is_empty() {
+            return Err(format!("Contract suite '{}' declares no rules or invariants", self.name));
+        }
+        Ok(ContractSuite {
+            name: self.name,
+            cases: self.cases,
+            seed: self.seed,
+            generator,
+            invariants: self.invariants,
+            rules: self.rules,
+        })
+    }
+}
+
+/// A built contract, runnable against any number of implementations
+pub struct ContractSuite<T: ?Sized, I> {
+    name: String,
+    cases: usize,
+    seed: u64,
+    generator: Generator<I>,
+    invariants: Vec<(String, Invariant<T>)>,
+    rules: Vec<Rule<T, I>>,
+}
+
+impl<T: ?Sized, I: fmt::Debug> ContractSuite<T, I> {
+    /// Runs every rule on a fresh subject per case. Every implementation sees
+    /// the same inputs, so reports for different implementations line up.
+    pub fn run(&self, implementation: &str, factory: impl Fn() -> Box<T>) -> ContractReport {
+        let mut report = ContractReport {
+            suite: self.name.clone(),
+            implementation: implementation.to_string(),
+            checks: 0,
+            skipped: 0,
+            violations: Vec::new(),
+        };
+        let mut rng = CaseRng::new(self.seed);
+
+        for case in 0..self.cases {
+            let input = (self.generator)(&mut rng);
+            for rule in &self.rules {
+                let mut subject = factory();
+                if let Some(precondition) = &rule.precondition {
+                    if !precondition(&subject, &input) {
+                        report.skipped += 1;
+                        continue;
+                    }
+                }
+                report.checks += 1;
+                if let Err(message) = (rule.postcondition)(&mut subject, &input) {
+                    report.record(&rule.name, case, &input, message);
+                }
+                for (name, invariant) in &self.invariants {
+                    if !invariant(&subject) {
+                        report.record(name, case, &input, format!("invariant broken after '{}'", rule.name));
+                    }
+                }
+            }
+        }
+        report
+    }
+}
+
+/// One failed check, with the input that triggered it
+#[derive(Debug, Clone)]
+pub struct Violation {
+    pub rule: String,
+    pub case: usize,
+    pub input: String,
+    pub message: String,
+}
+
+/// Outcome of running one suite against one implementation
+#[derive(Debug)]
+pub struct ContractReport {
+    pub suite: String,
+    pub implementation: String,
+    pub checks: usize,
+    pub skipped: usize,
+    pub violations: Vec<Violation>,
+}
+
+impl ContractReport {
+    // Only the first failure per rule is kept; the rest would repeat it
+    fn record(&mut self, rule: &str, case: usize, input: &impl fmt::Debug, message: String) {
+        if self.violations.iter().all(|v| v.rule != rule) {
+            self.violations.push(Violation {
+                rule: rule.to_string(),
+                case,
+                input: format!("{:?}", input),
+                message,
+            });
+        }
+    }
+
+    pub fn passed(&self) -> bool {
+        self.violations.is_empty()
+    }
+}
+
+impl fmt::Display for ContractReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let verdict = if self.passed() { "SUBSTITUTABLE" } else { "VIOLATES CONTRACT" };
+        write!(f, "{} vs '{}': {} ({} checks, {} skipped by preconditions)",
+               self.implementation, self.suite, verdict, self.checks, self.skipped)?;
+        for violation in &self.violations {
+            write!(f, "\n  - {} (case {}, input {}): {}",
+                   violation.rule, violation.case, violation.input, violation.message)?;
+        }
+        Ok(())
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Example: a contract for a user-defined Cache trait
+// ---------------------------------------------------------------------------
+
+/// A bounded key-value cache
+pub trait Cache {
+    /// Store a value, evicting another entry if the cache is full
+    fn put(&mut self, key: u8, value: i32);
+
+    /// Look up a value without removing it
+    fn get(&mut self, key: u8) -> Option<i32>;
+
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn capacity(&self) -> usize;
+}
+
+/// Least-recently-used cache - honors the contract
+#[derive(Debug)]
+pub struct LruCache {
+    capacity: usize,
+    entries: Vec<(u8, i32)>,  // most recently used last
+}
+
+impl LruCache {
+    pub fn new(capacity: usize) -> Self {
+        LruCache { capacity, entries: Vec::new() }
+    }
+}
+
+impl Cache for LruCache {
+    fn put(&mut self, key: u8, value: i32) {
+        self.entries.retain(|(k, _)| *k != key);
+        if self.entries.len() == self.capacity {
+            self.entries.remove(0);
+        }
+        self.entries.push((key, value));
+    }
+
+    fn get(&mut self, key: u8) -> Option<i32> {
+        let position = self.entries.iter().position(|(k, _)| *k == key)?;
+        let entry = self.entries.remove(position);
+        self.entries.push(entry);
+        Some(entry.1)
+    }
+
+    fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    fn capacity(&self) -> usize {
+        self.capacity
+    }
+}
+
+/// VIOLATION: read-once cache - get() removes the entry
+#[derive(Debug)]
+pub struct ReadOnceCache {
+    inner: LruCache,
+}
+
+impl ReadOnceCache {
+    pub fn new(capacity: usize) -> Self {
+        ReadOnceCache { inner: LruCache::new(capacity) }
+    }
+}
+
+impl Cache for ReadOnceCache {
+    fn put(&mut self, key: u8, value: i32) {
+        self.inner.put(key, value);
+    }
+
+    fn get(&mut self, key: u8) -> Option<i32> {
+        let value = self.inner.get(key)?;
+        self.inner.entries.retain(|(k, _)| *k != key);
+        Some(value)
+    }
+
+    fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    fn capacity(&self) -> usize {
+        self.inner.capacity()
+    }
+}
+
+/// VIOLATION: reports a capacity but never evicts
+#[derive(Debug)]
+pub struct UnboundedCache {
+    capacity: usize,
+    entries: Vec<(u8, i32)>,
+}
+
+impl UnboundedCache {
+    pub fn new(capacity: usize) -> Self {
+        UnboundedCache { capacity, entries: Vec::new() }
+    }
+}
+
+impl Cache for UnboundedCache {
+    fn put(&mut self, key: u8, value: i32) {
+        self.entries.retain(|(k, _)| *k != key);
+        self.entries.push((key, value));
+    }
+
+    fn get(&mut self, key: u8) -> Option<i32> {
+        self.entries.iter().find(|(k, _)| *k == key).map(|(_, v)| *v)
+    }
+
+    fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    fn capacity(&self) -> usize {
+        self.capacity
+    }
+}
+
+/// Generated input: a batch of writes, then a key to probe
+#[derive(Debug)]
+pub struct CacheCase {
+    writes: Vec<(u8, i32)>,
+    probe: u8,
+}
+
+/// The Cache contract, declared once for every implementation
+pub fn cache_contract() -> Result<ContractSuite<dyn Cache, CacheCase>, String> {
+    ContractSuiteBuilder::<dyn Cache, CacheCase>::new("Cache")
+        .cases(200)
+        .seed(7)
+        .generator(|rng| {
+            let count = rng.below(8) as usize;
+            let writes = (0..count).map(|_| (rng.below(6) as u8, rng.below(1000) as i32)).collect();
+            CacheCase { writes, probe: rng.below(6) as u8 }
+        })
+        .invariant("len <= capacity", |cache| cache.len() <= cache.capacity())
+        .rule("get returns the value just put", |cache, case| {
+            cache.put(case.probe, 1);
+            match cache.get(case.probe) {
+                Some(1) => Ok(()),
+                other => Err(format!("expected Some(1), got {:?}", other)),
+            }
+        })
+        .rule("get does not consume the entry", |cache, case| {
+            for (key, value) in &case.writes {
+                cache.put(*key, *value);
+            }
+            let first = cache.get(case.probe);
+            let second = cache.get(case.probe);
+            if first == second { Ok(()) } else { Err(format!("first get {:?}, second get {:?}", first, second)) }
+        })
+        .rule_when(
+            "writes below capacity are all retained",
+            |cache, case| case.writes.len() <= cache.capacity(),
+            |cache, case| {
+                for (key, value) in &case.writes {
+                    cache.put(*key, *value);
+                }
+                for (key, _) in &case.writes {
+                    // Later writes to the same key win
+                    let expected = case.writes.iter().rev().find(|(k, _)| k == key).map(|(_, v)| *v);
+                    let actual = cache.get(*key);
+                    if actual != expected {
+                        return Err(format!("key {}: expected {:?}, got {:?}", key, expected, actual));
+                    }
+                }
+                Ok(())
+            },
+        )
+        .build()
+}
+
+pub fn main() {
+    println!("=== LSP CONTRACT SUITES ===");
+    println!("One declared contract, run against every implementation");
+    println!();
+
+    let suite = match cache_contract() {
+        Ok(suite) => suite,
+        Err(e) => {
+            println!("Invalid contract: {}", e);
+            return;
+        }
+    };
+
+    let reports = [
+        suite.run("LruCache", || Box::new(LruCache::new(4))),
+        suite.run("ReadOnceCache", || Box::new(ReadOnceCache::new(4))),
+        suite.run("UnboundedCache", || Box::new(UnboundedCache::new(4))),
+    ];
+    for report in &reports {
+        println!("{}", report);
+        println!();
+    }
+
+    let substitutable: Vec<&str> = reports.iter()
+        .filter(|r| r.passed())
+        .map(|r| r.implementation.as_str())
+        .collect();
+    println!("Safe to substitute for any Cache: {:?}", substitutable);
+    println!();
+
+    // A suite without a generator is rejected when it is built, not when it runs
+    let incomplete = ContractSuiteBuilder::<dyn Cache, CacheCase>::new("Incomplete")
+        .invariant("len <= capacity", |cache| cache.len() <= cache.capacity())
+        .build();
+    if let Err(e) = incomplete {
+        println!("Builder error: {}", e);
+    }
+}
@@ -0,0 +1,456 @@
+// TEMPLATE METHOD VS HOOKS - WHO OWNS AN ALGORITHM'S SKELETON
+//
+// Every report here is generated the same way: fetch rows, transform them,
+// then render a header, one line per row and a footer. Two shapes let callers
+// vary the steps:
+//
+// 1. ReportTemplate, a trait. Its provided method generate() is the skeleton,
+//    and the other methods are hooks, most with defaults, that implementors
+//    override. Rust has no `final`, so generate() is a default like any other:
+//    an implementor can override it too, and callers holding a
+//    `&dyn ReportTemplate` cannot tell.
+// 2. ReportPipeline, a struct. It owns the skeleton as an inherent method and
+//    takes the steps as closures (strategies). Callers choose the steps but
+//    cannot replace the skeleton, so the checks it makes always run.
+//
+// The skeleton's contract, which every report must keep:
+// - the first line is the header naming the report's title
+// - there is one line per rendered row, and the last line is the footer
+//   counting exactly those rows
+// - transforming never invents rows: at most as many are rendered as were fetched
+//
+// Overridable defaults are where LSP breaks. A subtype that overrides
+// generate() "for speed" can drop the footer, and one that overrides a hook can
+// quietly break what the skeleton assumes about it. Both still compile and
+// still type-check as a ReportTemplate. FastSalesReport below overrides
+// generate() and breaks the contract.
+
+use std::cmp::Reverse;
+use std::fmt;
+
+/// One line of the sales ledger. Refunds have negative amounts.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Row {
+    pub region: String,
+    pub amount: i64,
+}
+
+impl Row {
+    pub fn new(region: &str, amount: i64) -> Self {
+        Row { region: region.to_string(), amount }
+    }
+}
+
+/// Where both report shapes fetch their rows from
+#[derive(Debug, Clone, Default)]
+pub struct SalesLedger {
+    rows: Vec<Row>,
+    offline: bool,
+}
+
+impl SalesLedger {
+    pub fn new(rows: Vec<Row>) -> Self {
+        SalesLedger { rows, offline: false }
+    }
+    
+    pub fn offline() -> Self {
+        SalesLedger { rows: Vec::new(), offline: true }
+    }
+    
+    pub fn fetch(&self) -> Result<Vec<Row>, String> {
+        if self.offline {
+            return Err("Sales ledger is offline".to_string());
+        }
+        Ok(self.rows.clone())
+    }
+}
+
+/// A generated report: its lines, and how many rows were fetched and rendered
+#[derive(Debug, Clone, PartialEq)]
+pub struct Report {
+    pub title: String,
+    pub lines: Vec<String>,
+    pub fetched: usize,
+    pub rendered: usize,
+}
+
+impl Report {
+    /// Checks the skeleton's contract, as every caller of generate() relies on it
+    pub fn check(&self) -> Result<(), String> {
+        if self.lines.first() != Some(&header(&self.title)) {
+            return Err(format!("first line is not the header for '{}'", self.title));
+        }
+        if self.rendered > self.fetched {
+            return Err(format!("{} rows rendered from {} fetched", self.rendered, self.fetched));
+        }
+        if self.lines.len() != self.rendered + 2 {
+            return Err(format!("{} lines for {} rows, expected header, rows and footer", self.lines.len(), self.rendered));
+        }
+        let footer = self.lines.last().map(String::as_str).unwrap_or("");
+        if !footer.starts_with(&format!("{} rows", self.rendered)) {
+            return Err(format!("footer '{}' does not count {} rows", footer, self.rendered));
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.lines.join("\n"))
+    }
+}
+
+fn header(title: &str) -> String {
+    format!("== {} ==", title)
+}
+
+fn footer(rows: &[Row]) -> String {
+    format!("{} rows, total {}", rows.len(), rows.iter().map(|row| row.amount).sum::<i64>())
+}
+
+fn default_row(row: &Row) -> String {
+    format!("{:<8} {:>8}", row.region, row.amount)
+}
+
+// ---------------------------------------------------------------------------
+// Shape 1: a trait whose default method is the skeleton
+// ---------------------------------------------------------------------------
+
+/// Template method: generate() runs the hooks in a fixed order
+pub trait ReportTemplate {
+    fn title(&self) -> String;
+    
+    /// Required: where the rows come from
+    fn fetch(&self) -> Result<Vec<Row>, String>;
+    
+    /// Hook: filter, sort or merge rows. Must not invent rows. Default: unchanged
+    fn transform(&self, rows: Vec<Row>) -> Vec<Row> {
+        rows
+    }
+    
+    /// Hook: one line per row
+    fn render_row(&self, row: &Row) -> String {
+        default_row(row)
+    }
+    
+    /// The skeleton. Meant to be left alone, but nothing stops an override.
+    fn generate(&self) -> Result<Report, String> {
+        let fetched = self.fetch()?;
+        let fetched_count = fetched.len();
+        let rows = self.transform(fetched);
+        let mut lines = vec![header(&self.title())];
+        lines.extend(rows.iter().map(|row| self.render_row(row)));
+        lines.push(footer(&rows));
+        Ok(Report { title: self.title(), lines, fetched: fetched_count, rendered: rows.len() })
+    }
+}
+
+/// Overrides only hooks: drops refunds and sorts the largest sales first
+pub struct RegionalSalesReport {
+    pub ledger: SalesLedger,
+}
+
+impl ReportTemplate for RegionalSalesReport {
+    fn title(&self) -> String {
+        "Regional sales".to_string()
+    }
+    
+    fn fetch(&self) -> Result<Vec<Row>, String> {
+        self.ledger.fetch()
+    }
+    
+    fn transform(&self, mut rows: Vec<Row>) -> Vec<Row> {
+        rows.retain(|row| row.amount > 0);
+        rows.sort_by_key(|row| Reverse(row.amount));
+        rows
+    }
+}
+
+/// Overrides only hooks: one line per region, summed
+pub struct RegionTotalsReport {
+    pub ledger: SalesLedger,
+}
+
+impl ReportTemplate for RegionTotalsReport {
+    fn title(&self) -> String {
+        "Totals by region".to_string()
+    }
+    
+    fn fetch(&self) -> Result<Vec<Row>, String> {
+        self.ledger.fetch()
+    }
+    
+    fn transform(&self, rows: Vec<Row>) -> Vec<Row> {
+        let mut totals: Vec<Row> = Vec::new();
+        for row in rows {
+            match totals.iter_mut().find(|total| total.region == row.region) {
+                Some(total) => total.amount += row.amount,
+                None => totals.push(row),
+            }
+        }
+        totals
+    }
+    
+    fn render_row(&self, row: &Row) -> String {
+        format!("{:<8} {:>8} (net)", row.region, row.amount)
+    }
+}
+
+/// VIOLATION: overrides the skeleton itself to skip the transform, and with it
+/// the refund filter and the footer. It is still a ReportTemplate to the compiler.
+pub struct FastSalesReport {
+    pub ledger: SalesLedger,
+}
+
+impl ReportTemplate for FastSalesReport {
+    fn title(&self) -> String {
+        "Regional sales (fast)".to_string()
+    }
+    
+    fn fetch(&self) -> Result<Vec<Row>, String> {
+        self.ledger.fetch()
+    }
+    
+    fn transform(&self, mut rows: Vec<Row>) -> Vec<Row> {
+        rows.retain(|row| row.amount > 0);
+        rows
+    }
+    
+    fn generate(&self) -> Result<Report, String> {
+        let rows = self.fetch()?;
+        let mut lines = vec![header(&self.title())];
+        lines.extend(rows.iter().map(|row| self.render_row(row)));
+        Ok(Report { title: self.title(), lines, fetched: rows.len(), rendered: rows.len() })
+    }
+}
+
+/// A client that only knows the trait: it trusts the skeleton's contract
+pub fn publish(template: &dyn ReportTemplate) -> Result<Report, String> {
+    let report = template.generate()?;
+    report.check().map_err(|e| format!("{} broke the report contract: {}", template.title(), e))?;
+    Ok(report)
+}
+
+// ---------------------------------------------------------------------------
+// Shape 2: a struct that owns the skeleton, with the steps as closures
+// ---------------------------------------------------------------------------
+
+type Fetch<'a> = Box<dyn Fn() -> Result<Vec<Row>, String> + 'a>;
+type Transform<'a> = Box<dyn Fn(Vec<Row>) -> Vec<Row> + 'a>;
+type RenderRow<'a> = Box<dyn Fn(&Row) -> String + 'a>;
+
+/// The same skeleton as ReportTemplate::generate, but not overridable. Because
+/// the pipeline calls every step itself, it can also check each one's result.
+pub struct ReportPipeline<'a> {
+    title: String,
+    fetch: Fetch<'a>,
+    transform: Transform<'a>,
+    render_row: RenderRow<'a>,
+}
+
+impl<'a> ReportPipeline<'a> {
+    pub fn new(title: &str, fetch: impl Fn() -> Result<Vec<Row>, String> + 'a) -> Self {
+        ReportPipeline {
+            title: title.to_string(),
+            fetch: Box::new(fetch),
+            transform: Box::new(|rows| rows),
+            render_row: Box::new(default_row),
+        }
+    }
+    
+    /// Every report this module builds reads from a ledger
+    pub fn from_ledger(title: &str, ledger: &'a SalesLedger) -> Self {
+        ReportPipeline::new(title, move || ledger.fetch())
+    }
+    
+    pub fn transform(mut self, transform: impl Fn(Vec<Row>) -> Vec<Row> + 'a) -> Self {
+        self.transform = Box::new(transform);
+        self
+    }
+    
+    pub fn render_row(mut self, render_row: impl Fn(&Row) -> String + 'a) -> Self {
+        self.render_row = Box::new(render_row);
+        self
+    }
+    
+    /// Runs fetch -> transform -> render. A transform that invents rows is
+    /// refused here, whoever wrote it.
+    pub fn generate(&self) -> Result<Report, String> {
+        let fetched = (self.fetch)()?;
+        let fetched_count = fetched.len();
+        let rows = (self.transform)(fetched);
+        if rows.len() > fetched_count {
+            return Err(format!("{}: transform returned {} rows from {} fetched", self.title, rows.len(), fetched_count));
+        }
+        let mut lines = vec![header(&self.title)];
+        lines.extend(rows.iter().map(|row| (self.render_row)(row)));
+        lines.push(footer(&rows));
+        Ok(Report { title: self.title.clone(), lines, fetched: fetched_count, rendered: rows.len() })
+    }
+}
+
+fn sample_ledger() -> SalesLedger {
+    SalesLedger::new(vec![
+        Row::new("North", 1200),
+        Row::new("South", 800),
+        Row::new("North", -150),
+        Row::new("East", 2300),
+        Row::new("South", 400),
+    ])
+}
+
+fn demonstrate_template_method() {
+    println!("--- Template method: a trait whose default generate() is the skeleton ---");
+    let ledger = sample_ledger();
+    let templates: Vec<Box<dyn ReportTemplate>> = vec![
+        Box::new(RegionalSalesReport { ledger: ledger.clone() }),
+        Box::new(RegionTotalsReport { ledger: ledger.clone() }),
+        Box::new(FastSalesReport { ledger: ledger.clone() }),
+    ];
+    for template in &templates {
+        match publish(template.as_ref()) {
+            Ok(report) => println!("{}\n", report),
+            Err(e) => println!("Refused: {}\n", e),
+        }
+    }
+    
+    if let Err(e) = publish(&RegionalSalesReport { ledger: SalesLedger::offline() }) {
+        println!("Fetch failed, nothing rendered: {}", e);
+    }
+}
+
+fn demonstrate_pipeline() {
+    println!("--- Pipeline: the skeleton is a struct's method, the steps are closures ---");
+    let ledger = sample_ledger();
+    let regional = ReportPipeline::from_ledger("Regional sales", &ledger).transform(|mut rows| {
+        rows.retain(|row| row.amount > 0);
+        rows.sort_by_key(|row| Reverse(row.amount));
+        rows
+    });
+    let net = ReportPipeline::from_ledger("Net sales", &ledger)
+        .render_row(|row| format!("{:<8} {:>8}{}", row.region, row.amount, if row.amount < 0 { " (refund)" } else { "" }));
+    for pipeline in [&regional, &net] {
+        match pipeline.generate().and_then(|report| report.check().map(|()| report)) {
+            Ok(report) => println!("{}\n", report),
+            Err(e) => println!("Refused: {}\n", e),
+        }
+    }
+    
+    // The closest a pipeline can get to FastSalesReport is a bad step, and the
+    // skeleton still runs and checks it: here, a transform that adds a total row
+    let padded = ReportPipeline::from_ledger("Sales with total", &ledger).transform(|mut rows| {
+        let total = rows.iter().map(|row| row.amount).sum();
+        rows.push(Row::new("TOTAL", total));
+        rows
+    });
+    if let Err(e) = padded.generate() {
+        println!("Refused: {}", e);
+    }
+}
+
+pub fn main() {
+    println!("=== TEMPLATE METHOD VS HOOKS ===");
+    println!("Same skeleton (fetch -> transform -> render), two ways to vary its steps");
+    println!();
+    
+    demonstrate_template_method();
+    println!();
+    demonstrate_pipeline();
+    println!();
+    
+    println!("=== Summary ===");
+    println!("✓ Hook overrides (RegionalSalesReport, RegionTotalsReport) keep the skeleton's contract");
+    println!("✗ FastSalesReport overrides generate() itself: refunds shown, footer gone, same trait");
+    println!("✓ ReportPipeline owns its skeleton, so every report gets the same steps and checks");
+    println!("• Prefer hooks without defaults for steps that carry obligations, and keep the");
+    println!("  skeleton out of reach (a pipeline, or a free function over a steps-only trait)");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn regions(report: &Report) -> Vec<String> {
+        // Skip the header and the footer
+        report.lines[1..report.lines.len() - 1].iter().map(|line| line.split_whitespace().next().unwrap().to_string()).collect()
+    }
+    
+    fn report(title: &str, lines: &[&str], fetched: usize, rendered: usize) -> Report {
+        Report { title: title.to_string(), lines: lines.iter().map(|line| line.to_string()).collect(), fetched, rendered }
+    }
+    
+    #[test]
+    fn hook_overrides_keep_the_contract() {
+        let regional = publish(&RegionalSalesReport { ledger: sample_ledger() }).unwrap();
+        assert_eq!(regional.lines.first().unwrap(), "== Regional sales ==");
+        assert_eq!(regions(&regional), ["East", "North", "South", "South"], "refund dropped, largest first");
+        assert_eq!(regional.lines.last().unwrap(), "4 rows, total 4700");
+        assert_eq!((regional.fetched, regional.rendered), (5, 4));
+        
+        let totals = publish(&RegionTotalsReport { ledger: sample_ledger() }).unwrap();
+        assert_eq!(totals.lines[1..4], [
+            format!("{:<8} {:>8} (net)", "North", 1050),
+            format!("{:<8} {:>8} (net)", "South", 1200),
+            format!("{:<8} {:>8} (net)", "East", 2300),
+        ]);
+        assert_eq!(totals.lines.last().unwrap(), "3 rows, total 4550");
+    }
+    
+    #[test]
+    fn fast_sales_report_breaks_the_contract() {
+        let report = FastSalesReport { ledger: sample_ledger() }.generate().unwrap();
+        assert_eq!(report.lines.len(), 6, "header and all five rows, but no footer");
+        assert_eq!(report.check(), Err("6 lines for 5 rows, expected header, rows and footer".to_string()));
+        assert!(report.lines.contains(&default_row(&Row::new("North", -150))), "the refund its transform would drop is shown");
+        
+        let error = publish(&FastSalesReport { ledger: sample_ledger() }).unwrap_err();
+        assert_eq!(error, "Regional sales (fast) broke the report contract: 6 lines for 5 rows, expected header, rows and footer");
+    }
+    
+    #[test]
+    fn a_failed_fetch_renders_nothing() {
+        let error = publish(&RegionalSalesReport { ledger: SalesLedger::offline() }).unwrap_err();
+        assert_eq!(error, "Sales ledger is offline");
+        assert!(ReportPipeline::from_ledger("Offline", &SalesLedger::offline()).generate().is_err());
+    }
+    
+    #[test]
+    fn check_rejects_each_broken_clause() {
+        let cases = [
+            (report("Sales", &["Sales", "0 rows, total 0"], 0, 0), "first line is not the header for 'Sales'"),
+            (report("Sales", &["== Sales ==", "a", "b", "2 rows, total 0"], 1, 2), "2 rows rendered from 1 fetched"),
+            (report("Sales", &["== Sales ==", "a", "2 rows, total 0"], 2, 1), "footer '2 rows, total 0' does not count 1 rows"),
+        ];
+        for (report, expected) in cases {
+            assert_eq!(report.check(), Err(expected.to_string()));
+        }
+        assert_eq!(report("Sales", &["== Sales ==", "0 rows, total 0"], 3, 0).check(), Ok(()));
+    }
+    
+    #[test]
+    fn pipeline_runs_the_same_skeleton_with_closures() {
+        let ledger = sample_ledger();
+        let pipeline = ReportPipeline::from_ledger("Regional sales", &ledger).transform(|mut rows| {
+            rows.retain(|row| row.amount > 0);
+            rows.sort_by_key(|row| Reverse(row.amount));
+            rows
+        });
+        let from_pipeline = pipeline.generate().unwrap();
+        assert_eq!(from_pipeline.check(), Ok(()));
+        assert_eq!(from_pipeline, publish(&RegionalSalesReport { ledger: ledger.clone() }).unwrap());
+        
+        let net = ReportPipeline::from_ledger("Net sales", &ledger).render_row(|row| format!("{}:{}", row.region, row.amount)).generate().unwrap();
+        assert_eq!(net.lines[1..6], ["North:1200", "South:800", "North:-150", "East:2300", "South:400"]);
+        assert_eq!(net.lines.last().unwrap(), "5 rows, total 4550");
+    }
+    
+    #[test]
+    fn pipeline_refuses_a_transform_that_invents_rows() {
+        let ledger = sample_ledger();
+        let padded = ReportPipeline::from_ledger("Sales with total", &ledger).transform(|mut rows| {
+            rows.push(Row::new("TOTAL", 0));
+            rows
+        });
+        assert_eq!(padded.generate(), Err("Sales with total: transform returned 6 rows from 5 fetched".to_string()));
+    }
+}
@@ -0,0 +1,101 @@
+// DISPATCH BENCHMARKS - WHAT EACH DESIGN COSTS AT RUN TIME
+//
+// Runs amplify_signal over large circuits built three ways:
+// - enum + match: AmplifierCircuitViolation from transistor_ocp_violation.rs
+// - trait objects: Circuit of Box<dyn Transistor> from transistor.rs
+// - generics: StaticAmplifier<BJTTransistor> from transistor.rs, monomorphized
+//   for one transistor type
+// - enum dispatch: StaticAmplifier<AnyTransistor> from transistor.rs, a match
+//   over the built-in types instead of a virtual call
+//
+// The match version is closed to extension but the compiler sees every case.
+// Trait objects pay a virtual call per transistor and cannot be inlined, but
+// accept types written later. Generics inline fully, but a StaticAmplifier
+// holds only one type, so they only appear in the single-type group.
+// AnyTransistor sits in between: it mixes the built-in types with a match
+// and falls back to a virtual call only for its Dyn variant.
+//
+// The violation's transistors are bare formulas, while the correct ones also
+// clamp their output and track temperature, so enum_match does less work per
+// transistor. dyn_trait against generic runs the same transistor code and is
+// the like-for-like comparison of dispatch alone.
+
+#[allow(dead_code)]
+#[path = "../transistor.rs"]
+mod correct;
+#[allow(dead_code, clippy::upper_case_acronyms)]
+#[path = "../transistor_ocp_violation.rs"]
+mod violation;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use std::hint::black_box;
+
+use correct::{AnyTransistor, BJTTransistor, Circuit, FETTransistor, MOSFETTransistor, StaticAmplifier, Transistor};
+use violation::{AmplifierCircuitViolation, TransistorType};
+
+const SIZES: [usize; 3] = [100, 1_000, 10_000];
+const SIGNAL: f64 = 2.0;
+const INPUT: f64 = 5.0;
+
+fn bjt_only(c: &mut Criterion) {
+    let mut group = c.benchmark_group("bjt_only");
+    for size in SIZES {
+        group.throughput(Throughput::Elements(size as u64));
+
+        let mut matched = AmplifierCircuitViolation::new();
+        let mut dynamic = Circuit::new();
+        let mut generic = StaticAmplifier::new();
+        for _ in 0..size {
+            matched.add_transistor(TransistorType::BJT);
+            dynamic.add_transistor(Box::new(BJTTransistor::new()));
+            generic.add_transistor(BJTTransistor::new());
+        }
+
+        group.bench_with_input(BenchmarkId::new("enum_match", size), &size, |b, _| {
+            b.iter(|| matched.amplify_signal(black_box(SIGNAL), black_box(INPUT)))
+        });
+        group.bench_with_input(BenchmarkId::new("dyn_trait", size), &size, |b, _| {
+            b.iter(|| dynamic.amplify_signal(black_box(SIGNAL), black_box(INPUT)))
+        });
+        group.bench_with_input(BenchmarkId::new("generic", size), &size, |b, _| {
+            b.iter(|| generic.amplify_signal(black_box(SIGNAL), black_box(INPUT)))
+        });
+    }
+    group.finish();
+}
+
+/// BJT, FET and MOSFET in turn, so the branch or call target changes every step
+fn mixed(c: &mut Criterion) {
+    let mut group = c.benchmark_group("mixed");
+    for size in SIZES {
+        group.throughput(Throughput::Elements(size as u64));
+
+        let mut matched = AmplifierCircuitViolation::new();
+        let mut dynamic = Circuit::new();
+        let mut enum_dispatch = StaticAmplifier::new();
+        for i in 0..size {
+            let (transistor_type, transistor, any): (TransistorType, Box<dyn Transistor>, AnyTransistor) = match i % 3 {
+                0 => (TransistorType::BJT, Box::new(BJTTransistor::new()), BJTTransistor::new().into()),
+                1 => (TransistorType::FET, Box::new(FETTransistor::new()), FETTransistor::new().into()),
+                _ => (TransistorType::MOSFET, Box::new(MOSFETTransistor::new()), MOSFETTransistor::new().into()),
+            };
+            matched.add_transistor(transistor_type);
+            dynamic.add_transistor(transistor);
+            enum_dispatch.add_transistor(any);
+        }
+
+        group.bench_with_input(BenchmarkId::new("enum_match", size), &size, |b, _| {
+            b.iter(|| matched.amplify_signal(black_box(SIGNAL), black_box(INPUT)))
+        });
+        group.bench_with_input(BenchmarkId::new("dyn_trait", size), &size, |b, _| {
+            b.iter(|| dynamic.amplify_signal(black_box(SIGNAL), black_box(INPUT)))
+        });
+        group.bench_with_input(BenchmarkId::new("any_transistor", size), &size, |b, _| {
+            b.iter(|| enum_dispatch.amplify_signal(black_box(SIGNAL), black_box(INPUT)))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bjt_only, mixed);
+criterion_main!(benches);
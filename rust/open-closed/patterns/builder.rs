@@ -0,0 +1,146 @@
+// BUILDER - A MULTI-STAGE AMPLIFIER ASSEMBLED IN A CHECKED ORDER
+//
+// CircuitBuilder produces the same Circuit as the hand-written examples, using
+// only its public methods. Its type parameter records how far construction
+// has got, so an illegal order is a compile error rather than a runtime check:
+// - NoStages: configuration only; there is nothing to seal or build yet
+// - Staged: at least one stage has been added; more stages, configuration, or seal()
+// - Sealed: validated and frozen; build() is the only thing left to do
+//
+// What types cannot see, such as two stages with the same name, is checked
+// once, by seal().
+
+use std::marker::PhantomData;
+
+use super::{Circuit, CircuitElement, CircuitNode, CircuitObserver};
+
+/// No stage added yet
+#[derive(Debug)]
+pub struct NoStages;
+
+/// At least one stage added
+#[derive(Debug)]
+pub struct Staged;
+
+/// Validated; cannot be changed
+#[derive(Debug)]
+pub struct Sealed;
+
+/// The states in which stages and configuration can still be added
+pub trait Unsealed {}
+
+impl Unsealed for NoStages {}
+impl Unsealed for Staged {}
+
+/// Builds a `Circuit` whose stages run in series, each feeding the next
+pub struct CircuitBuilder<S> {
+    stages: Vec<(String, CircuitNode)>,
+    observers: Vec<Box<dyn CircuitObserver>>,
+    power_threshold: Option<f64>,
+    state: PhantomData<S>,
+}
+
+impl CircuitBuilder<NoStages> {
+    pub fn new() -> Self {
+        CircuitBuilder {
+            stages: Vec::new(),
+            observers: Vec::new(),
+            power_threshold: None,
+            state: PhantomData,
+        }
+    }
+}
+
+impl Default for CircuitBuilder<NoStages> {
+    fn default() -> Self {
+        CircuitBuilder::new()
+    }
+}
+
+impl<S> CircuitBuilder<S> {
+    /// The stage names so far, in signal order
+    pub fn stage_names(&self) -> Vec<&str> {
+        self.stages.iter().map(|(name, _)| name.as_str()).collect()
+    }
+    
+    fn into_state<T>(self) -> CircuitBuilder<T> {
+        CircuitBuilder {
+            stages: self.stages,
+            observers: self.observers,
+            power_threshold: self.power_threshold,
+            state: PhantomData,
+        }
+    }
+}
+
+impl<S: Unsealed> CircuitBuilder<S> {
+    /// Adds a stage of one element, driven by the previous stage's output
+    pub fn stage(self, name: &str, element: Box<dyn CircuitElement>) -> CircuitBuilder<Staged> {
+        self.node_stage(name, CircuitNode::device(element))
+    }
+    
+    /// Adds a stage of elements in parallel: each sees the previous stage's
+    /// output and their outputs add up
+    pub fn bank(self, name: &str, elements: Vec<Box<dyn CircuitElement>>) -> CircuitBuilder<Staged> {
+        self.node_stage(name, CircuitNode::parallel(elements.into_iter().map(CircuitNode::device).collect()))
+    }
+    
+    /// Adds a stage of any topology
+    pub fn node_stage(mut self, name: &str, node: CircuitNode) -> CircuitBuilder<Staged> {
+        self.stages.push((name.to_string(), node));
+        self.into_state()
+    }
+    
+    pub fn observer(mut self, observer: Box<dyn CircuitObserver>) -> Self {
+        self.observers.push(observer);
+        self
+    }
+    
+    /// See `Circuit::set_power_threshold`
+    pub fn power_threshold(mut self, watts: f64) -> Self {
+        self.power_threshold = Some(watts);
+        self
+    }
+}
+
+impl CircuitBuilder<Staged> {
+    /// Checks what the type cannot: stage names are unique and not blank,
+    /// every stage has an element, and the power threshold is positive
+    pub fn seal(self) -> Result<CircuitBuilder<Sealed>, String> {
+        let mut seen = Vec::new();
+        for (name, node) in &self.stages {
+            if name.trim().is_empty() {
+                return Err(format!("Stage {} has no name", seen.len() + 1));
+            }
+            if seen.contains(&name) {
+                return Err(format!("Duplicate stage name '{}'", name));
+            }
+            if node.element_count() == 0 {
+                return Err(format!("Stage '{}' has no elements", name));
+            }
+            seen.push(name);
+        }
+        if let Some(watts) = self.power_threshold {
+            if watts.is_nan() || watts <= 0.0 {
+                return Err(format!("Power threshold must be positive, got {}", watts));
+            }
+        }
+        Ok(self.into_state())
+    }
+}
+
+impl CircuitBuilder<Sealed> {
+    pub fn build(self) -> Circuit {
+        let mut circuit = Circuit::new();
+        circuit.add_node(CircuitNode::series(
+            self.stages.into_iter().map(|(name, node)| CircuitNode::stage(&name, node)).collect(),
+        ));
+        if let Some(watts) = self.power_threshold {
+            circuit.set_power_threshold(watts);
+        }
+        for observer in self.observers {
+            circuit.add_observer(observer);
+        }
+        circuit
+    }
+}
@@ -0,0 +1,187 @@
+// FACTORY - DEVICES FROM SPECIFICATIONS, AND KITS OF DEVICES THAT BELONG TOGETHER
+//
+// EntityFactory has the same shape as the one in the interface-segregation
+// example (patterns/factory.rs there): a specification in, a configured product
+// out, or an error saying what is wrong with the specification.
+// - TransistorRegistry is the concrete factory for transistors: a TransistorSpec
+//   names a registered type and its parameters
+// - DeviceKit is an abstract factory for a switching output stage. A kit makes
+//   the driver, the switch and the load, all rated for the same junction
+//   temperature and power. Asking one kit for every part is how a circuit avoids
+//   an industrial driver next to a domestic switch.
+
+use super::builder::CircuitBuilder;
+use super::{Circuit, CircuitElement, CircuitNode, ParamValues, Resistor, Transistor, TransistorRegistry, TransistorSpec};
+
+/// Turns a specification into a configured product, or explains why it cannot
+pub trait EntityFactory {
+    type Spec;
+    type Product;
+    
+    fn produce(&self, spec: &Self::Spec) -> Result<Self::Product, String>;
+}
+
+impl EntityFactory for TransistorRegistry {
+    type Spec = TransistorSpec;
+    type Product = Box<dyn Transistor>;
+    
+    fn produce(&self, spec: &TransistorSpec) -> Result<Box<dyn Transistor>, String> {
+        self.create_with(&spec.kind, &spec.params)
+    }
+}
+
+/// Abstract factory for a driver -> switch -> load output stage
+pub trait DeviceKit {
+    fn kit_name(&self) -> &'static str;
+    /// Junction temperature in °C that every transistor in the kit is rated for
+    fn rated_temperature(&self) -> f64;
+    /// Power in watts above which the stage's observers are warned
+    fn rated_power(&self) -> f64;
+    fn driver(&self) -> Result<Box<dyn Transistor>, String>;
+    fn switch(&self) -> Result<Box<dyn Transistor>, String>;
+    fn load(&self) -> Box<dyn CircuitElement>;
+    
+    /// The kit's three parts in series, with the kit's power rating as the
+    /// circuit's power threshold
+    fn output_stage(&self) -> Result<Circuit, String> {
+        let sealed = CircuitBuilder::new()
+            .stage("driver", self.driver()?)
+            .stage("switch", self.switch()?)
+            .node_stage("load", CircuitNode::device(self.load()))
+            .power_threshold(self.rated_power())
+            .seal()?;
+        Ok(sealed.build())
+    }
+}
+
+/// A registered transistor type with the given parameters, rated for `max_temperature`
+fn rated_part(kind: &str, params: &[(&str, f64)], max_temperature: f64) -> Result<Box<dyn Transistor>, String> {
+    let mut values: ParamValues = params.iter().map(|(name, value)| (name.to_string(), *value)).collect();
+    values.insert("max_temperature".to_string(), max_temperature);
+    TransistorRegistry::with_builtin_types().produce(&TransistorSpec::new(kind, values))
+}
+
+/// Motor drives: a Darlington driving an IGBT into a low-resistance load
+pub struct IndustrialKit;
+
+impl DeviceKit for IndustrialKit {
+    fn kit_name(&self) -> &'static str {
+        "industrial kit"
+    }
+    
+    fn rated_temperature(&self) -> f64 {
+        175.0
+    }
+    
+    fn rated_power(&self) -> f64 {
+        50.0
+    }
+    
+    fn driver(&self) -> Result<Box<dyn Transistor>, String> {
+        rated_part("darlington", &[("gain", 0.12)], self.rated_temperature())
+    }
+    
+    fn switch(&self) -> Result<Box<dyn Transistor>, String> {
+        rated_part("igbt", &[("threshold", 4.0), ("saturation_voltage", 1.5)], self.rated_temperature())
+    }
+    
+    fn load(&self) -> Box<dyn CircuitElement> {
+        Box::new(Resistor::new(10.0).with_load(40.0))
+    }
+}
+
+/// Appliances: a BJT driving a logic-level MOSFET into a light load
+pub struct DomesticKit;
+
+impl DeviceKit for DomesticKit {
+    fn kit_name(&self) -> &'static str {
+        "domestic kit"
+    }
+    
+    fn rated_temperature(&self) -> f64 {
+        125.0
+    }
+    
+    fn rated_power(&self) -> f64 {
+        0.5
+    }
+    
+    fn driver(&self) -> Result<Box<dyn Transistor>, String> {
+        rated_part("bjt", &[], self.rated_temperature())
+    }
+    
+    fn switch(&self) -> Result<Box<dyn Transistor>, String> {
+        rated_part("mosfet", &[("threshold", 0.4)], self.rated_temperature())
+    }
+    
+    fn load(&self) -> Box<dyn CircuitElement> {
+        Box::new(Resistor::new(1.0e3).with_load(4.0e3))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn part_names(circuit: &Circuit) -> Vec<String> {
+        circuit.inventory().into_iter().map(|info| info.name).collect()
+    }
+    
+    // Shutdown temperature of every transistor in the circuit
+    fn ratings(circuit: &Circuit) -> Vec<f64> {
+        circuit.elements().iter().filter_map(|element| element.thermal_limit(25.0)).map(|(_, max)| max).collect()
+    }
+    
+    #[test]
+    fn registry_produces_configured_transistors_from_specs() {
+        let registry = TransistorRegistry::with_builtin_types();
+        let spec = TransistorSpec::new("jfet", ParamValues::from([("threshold".to_string(), -2.5)]));
+        let jfet = registry.produce(&spec).unwrap();
+        assert_eq!(jfet.describe().name, "JFET");
+        assert_eq!(jfet.to_spec(), Some(spec));
+        
+        let error = registry.produce(&TransistorSpec::new("jfet", ParamValues::from([("threshold".to_string(), 1.0)]))).err().unwrap();
+        assert_eq!(error, "Invalid jfet parameters: threshold (the pinch-off voltage) must be negative, got 1");
+        assert!(registry.produce(&TransistorSpec::new("triac", ParamValues::new())).err().unwrap().starts_with("Unknown transistor type: triac"));
+    }
+    
+    #[test]
+    fn industrial_kit_builds_a_matched_output_stage() {
+        let circuit = IndustrialKit.output_stage().unwrap();
+        assert_eq!(part_names(&circuit), ["Darlington (BJT + BJT)", "IGBT", "Resistor 10 Ω"]);
+        let stages: Vec<&str> = circuit.stage_outputs().into_iter().map(|(name, _)| name).collect();
+        assert_eq!(stages, ["driver", "switch", "load"]);
+        assert_eq!(ratings(&circuit), [175.0, 175.0], "both transistors rated for the kit's temperature");
+        assert_eq!(circuit.power_threshold, Some(50.0));
+    }
+    
+    #[test]
+    fn domestic_kit_builds_a_matched_output_stage() {
+        let circuit = DomesticKit.output_stage().unwrap();
+        assert_eq!(part_names(&circuit), ["BJT", "MOSFET", "Resistor 1000 Ω"]);
+        assert_eq!(ratings(&circuit), [125.0, 125.0]);
+        assert_eq!(circuit.power_threshold, Some(0.5));
+    }
+    
+    #[test]
+    fn kit_parts_keep_their_configuration() {
+        let switch = IndustrialKit.switch().unwrap().to_spec().unwrap();
+        assert_eq!(switch.kind, "igbt");
+        assert_eq!(switch.params["threshold"], 4.0);
+        assert_eq!(switch.params["saturation_voltage"], 1.5);
+        assert_eq!(switch.params["max_temperature"], 175.0);
+        assert_eq!(DomesticKit.switch().unwrap().to_spec().unwrap().params["threshold"], 0.4);
+    }
+    
+    #[test]
+    fn the_same_drive_fits_one_kit_and_overloads_the_other() {
+        let mut industrial = IndustrialKit.output_stage().unwrap();
+        industrial.amplify_signal(2.0, 12.0);
+        assert!(industrial.total_power() <= IndustrialKit.rated_power());
+        assert_eq!(industrial.validate(2.0, 12.0, 25.0), []);
+        
+        let mut domestic = DomesticKit.output_stage().unwrap();
+        domestic.amplify_signal(2.0, 12.0);
+        assert!(domestic.total_power() > DomesticKit.rated_power());
+    }
+}
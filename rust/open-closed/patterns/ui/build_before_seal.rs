@@ -0,0 +1,12 @@
+// Staged builders must be sealed, and so validated, before they are built
+
+#[path = "../../transistor.rs"]
+#[allow(dead_code)]
+mod correct;
+
+use correct::builder::CircuitBuilder;
+use correct::BJTTransistor;
+
+fn main() {
+    let _ = CircuitBuilder::new().stage("preamp", Box::new(BJTTransistor::new())).build();
+}
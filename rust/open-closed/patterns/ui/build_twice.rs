@@ -0,0 +1,14 @@
+// build() consumes the sealed builder, so it cannot build a second circuit
+
+#[path = "../../transistor.rs"]
+#[allow(dead_code)]
+mod correct;
+
+use correct::builder::CircuitBuilder;
+use correct::BJTTransistor;
+
+fn main() {
+    let sealed = CircuitBuilder::new().stage("preamp", Box::new(BJTTransistor::new())).seal().unwrap();
+    let _first = sealed.build();
+    let _second = sealed.build();
+}
@@ -0,0 +1,11 @@
+// A builder with no stage cannot be built
+
+#[path = "../../transistor.rs"]
+#[allow(dead_code)]
+mod correct;
+
+use correct::builder::CircuitBuilder;
+
+fn main() {
+    let _ = CircuitBuilder::new().build();
+}
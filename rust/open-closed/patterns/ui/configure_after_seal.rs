@@ -0,0 +1,13 @@
+// A sealed builder cannot be reconfigured
+
+#[path = "../../transistor.rs"]
+#[allow(dead_code)]
+mod correct;
+
+use correct::builder::CircuitBuilder;
+use correct::{BJTTransistor, MetricsObserver};
+
+fn main() {
+    let sealed = CircuitBuilder::new().stage("preamp", Box::new(BJTTransistor::new())).seal().unwrap();
+    let _ = sealed.observer(Box::new(MetricsObserver::new()));
+}
@@ -0,0 +1,13 @@
+// Sealing is done once; a sealed builder has nothing left to check
+
+#[path = "../../transistor.rs"]
+#[allow(dead_code)]
+mod correct;
+
+use correct::builder::CircuitBuilder;
+use correct::BJTTransistor;
+
+fn main() {
+    let sealed = CircuitBuilder::new().stage("preamp", Box::new(BJTTransistor::new())).seal().unwrap();
+    let _ = sealed.seal();
+}
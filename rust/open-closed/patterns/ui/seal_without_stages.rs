@@ -0,0 +1,11 @@
+// A builder with no stage has nothing to seal
+
+#[path = "../../transistor.rs"]
+#[allow(dead_code)]
+mod correct;
+
+use correct::builder::CircuitBuilder;
+
+fn main() {
+    let _ = CircuitBuilder::new().power_threshold(0.5).seal();
+}
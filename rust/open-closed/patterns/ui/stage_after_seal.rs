@@ -0,0 +1,13 @@
+// A sealed builder cannot take more stages
+
+#[path = "../../transistor.rs"]
+#[allow(dead_code)]
+mod correct;
+
+use correct::builder::CircuitBuilder;
+use correct::BJTTransistor;
+
+fn main() {
+    let sealed = CircuitBuilder::new().stage("preamp", Box::new(BJTTransistor::new())).seal().unwrap();
+    let _ = sealed.stage("driver", Box::new(BJTTransistor::new()));
+}
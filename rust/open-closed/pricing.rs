@@ -0,0 +1,461 @@
+// CORRECT IMPLEMENTATION - FOLLOWS OPEN-CLOSED PRINCIPLE
+// Order pricing: each discount is a DiscountRule, and the PricingEngine applies
+// whatever rules it is given, in order. New promotions are new rules; the
+// engine never changes.
+
+use std::fmt::{self, Debug};
+use std::ops::RangeInclusive;
+
+/// Rounds a money amount to whole cents
+fn round_cents(amount: f64) -> f64 {
+    (amount * 100.0).round() / 100.0
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct OrderLine {
+    pub sku: String,
+    pub unit_price: f64,
+    pub quantity: u32,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Order {
+    customer_tier: String,
+    lines: Vec<OrderLine>,
+    coupon: Option<String>,
+    month: u32,
+}
+
+impl Order {
+    /// New empty order for a customer tier (e.g. "regular", "premium"),
+    /// placed in `month` (1-12)
+    pub fn new(customer_tier: &str, month: u32) -> Self {
+        Order {
+            customer_tier: customer_tier.to_string(),
+            lines: Vec::new(),
+            coupon: None,
+            month,
+        }
+    }
+
+    pub fn with_line(mut self, sku: &str, unit_price: f64, quantity: u32) -> Self {
+        self.lines.push(OrderLine {
+            sku: sku.to_string(),
+            unit_price,
+            quantity,
+        });
+        self
+    }
+
+    pub fn with_coupon(mut self, code: &str) -> Self {
+        self.coupon = Some(code.to_string());
+        self
+    }
+
+    pub fn customer_tier(&self) -> &str {
+        &self.customer_tier
+    }
+
+    pub fn lines(&self) -> &[OrderLine] {
+        &self.lines
+    }
+
+    pub fn coupon(&self) -> Option<&str> {
+        self.coupon.as_deref()
+    }
+
+    pub fn month(&self) -> u32 {
+        self.month
+    }
+
+    pub fn subtotal(&self) -> f64 {
+        self.lines
+            .iter()
+            .fold(0.0, |total, line| total + line.unit_price * line.quantity as f64)
+    }
+
+    pub fn item_count(&self) -> u32 {
+        self.lines.iter().map(|line| line.quantity).sum()
+    }
+}
+
+/// Trait defining the interface for all discounts
+pub trait DiscountRule: Debug {
+    /// Label shown on the quote
+    fn name(&self) -> String;
+    /// Amount to take off `running_total`, the price after the rules before
+    /// this one. Zero when the rule does not apply to the order.
+    fn discount(&self, order: &Order, running_total: f64) -> f64;
+}
+
+/// Percentage off the running total, optionally only for one customer tier
+#[derive(Debug, Clone, PartialEq)]
+pub struct PercentageDiscount {
+    percent: f64,
+    tier: Option<String>,
+}
+
+impl PercentageDiscount {
+    pub fn new(percent: f64) -> Self {
+        PercentageDiscount { percent, tier: None }
+    }
+
+    /// Restricts the discount to orders from `tier`
+    pub fn for_tier(mut self, tier: &str) -> Self {
+        self.tier = Some(tier.to_string());
+        self
+    }
+}
+
+impl DiscountRule for PercentageDiscount {
+    fn name(&self) -> String {
+        match &self.tier {
+            Some(tier) => format!("{} customer {}%", tier, self.percent),
+            None => format!("{}% off", self.percent),
+        }
+    }
+
+    fn discount(&self, order: &Order, running_total: f64) -> f64 {
+        match &self.tier {
+            Some(tier) if tier != order.customer_tier() => 0.0,
+            _ => running_total * self.percent / 100.0,
+        }
+    }
+}
+
+/// Percentage off when the order has at least `min_items` items
+#[derive(Debug, Clone, PartialEq)]
+pub struct BulkDiscount {
+    min_items: u32,
+    percent: f64,
+}
+
+impl BulkDiscount {
+    pub fn new(min_items: u32, percent: f64) -> Self {
+        BulkDiscount { min_items, percent }
+    }
+}
+
+impl DiscountRule for BulkDiscount {
+    fn name(&self) -> String {
+        format!("Bulk {}+ items {}%", self.min_items, self.percent)
+    }
+
+    fn discount(&self, order: &Order, running_total: f64) -> f64 {
+        if order.item_count() >= self.min_items {
+            running_total * self.percent / 100.0
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Percentage off for orders placed in the given months
+#[derive(Debug, Clone, PartialEq)]
+pub struct SeasonalDiscount {
+    label: String,
+    months: RangeInclusive<u32>,
+    percent: f64,
+}
+
+impl SeasonalDiscount {
+    pub fn new(label: &str, months: RangeInclusive<u32>, percent: f64) -> Self {
+        SeasonalDiscount {
+            label: label.to_string(),
+            months,
+            percent,
+        }
+    }
+}
+
+impl DiscountRule for SeasonalDiscount {
+    fn name(&self) -> String {
+        format!("{} {}%", self.label, self.percent)
+    }
+
+    fn discount(&self, order: &Order, running_total: f64) -> f64 {
+        if self.months.contains(&order.month()) {
+            running_total * self.percent / 100.0
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Fixed amount off when the order carries the matching coupon code
+#[derive(Debug, Clone, PartialEq)]
+pub struct CouponDiscount {
+    code: String,
+    amount: f64,
+}
+
+impl CouponDiscount {
+    pub fn new(code: &str, amount: f64) -> Self {
+        CouponDiscount {
+            code: code.to_string(),
+            amount,
+        }
+    }
+}
+
+impl DiscountRule for CouponDiscount {
+    fn name(&self) -> String {
+        format!("Coupon {}", self.code)
+    }
+
+    fn discount(&self, order: &Order, _running_total: f64) -> f64 {
+        if order.coupon() == Some(self.code.as_str()) {
+            self.amount
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Composite rule: applies only the largest of its rules. Being a rule
+/// itself, it stacks with the others like any other discount.
+#[derive(Debug)]
+pub struct BestOf {
+    rules: Vec<Box<dyn DiscountRule>>,
+}
+
+impl BestOf {
+    pub fn new(rules: Vec<Box<dyn DiscountRule>>) -> Self {
+        BestOf { rules }
+    }
+}
+
+impl DiscountRule for BestOf {
+    fn name(&self) -> String {
+        let names: Vec<String> = self.rules.iter().map(|rule| rule.name()).collect();
+        format!("Best of ({})", names.join(", "))
+    }
+
+    fn discount(&self, order: &Order, running_total: f64) -> f64 {
+        self.rules
+            .iter()
+            .map(|rule| rule.discount(order, running_total))
+            .fold(0.0, f64::max)
+    }
+}
+
+/// A discount the engine applied to an order
+#[derive(Debug, Clone, PartialEq)]
+pub struct Adjustment {
+    pub rule: String,
+    pub amount: f64,
+}
+
+/// Priced order: the subtotal, each discount applied, in order, and the total
+#[derive(Debug, Clone, PartialEq)]
+pub struct Quote {
+    pub subtotal: f64,
+    pub adjustments: Vec<Adjustment>,
+    pub total: f64,
+}
+
+impl fmt::Display for Quote {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // Label column fits the longest rule name, indented by two
+        let width = self
+            .adjustments
+            .iter()
+            .map(|adjustment| adjustment.rule.len() + 2)
+            .fold(28, usize::max);
+        writeln!(f, "{:<width$} {:>9}", "Subtotal", format!("${:.2}", self.subtotal))?;
+        for adjustment in &self.adjustments {
+            let label = format!("  {}", adjustment.rule);
+            writeln!(f, "{:<width$} {:>9}", label, format!("-${:.2}", adjustment.amount))?;
+        }
+        write!(f, "{:<width$} {:>9}", "Total", format!("${:.2}", self.total))
+    }
+}
+
+/// Prices orders by applying its rules in the order they were added - closed
+/// for modification
+#[derive(Debug, Default)]
+pub struct PricingEngine {
+    rules: Vec<Box<dyn DiscountRule>>,
+}
+
+impl PricingEngine {
+    pub fn new() -> Self {
+        PricingEngine { rules: Vec::new() }
+    }
+
+    /// Adds a rule after the existing ones. Percentage rules see the total
+    /// left by earlier rules, so the order they are added in matters.
+    pub fn add_rule(&mut self, rule: Box<dyn DiscountRule>) {
+        self.rules.push(rule);
+    }
+
+    /// Applies each rule to the running total. Amounts are rounded to cents
+    /// and never take the total below zero; rules that do not apply are
+    /// left off the quote.
+    pub fn price(&self, order: &Order) -> Quote {
+        let subtotal = round_cents(order.subtotal());
+        let mut total = subtotal;
+        let mut adjustments = Vec::new();
+
+        for rule in &self.rules {
+            let amount = round_cents(rule.discount(order, total)).min(total);
+            if amount > 0.0 {
+                total = round_cents(total - amount);
+                adjustments.push(Adjustment {
+                    rule: rule.name(),
+                    amount,
+                });
+            }
+        }
+
+        Quote {
+            subtotal,
+            adjustments,
+            total,
+        }
+    }
+}
+
+/// Example usage demonstrating OCP compliance
+pub fn demonstrate_ocp_compliance() {
+    println!("=== OCP COMPLIANT EXAMPLE (PRICING) ===");
+    println!("PricingEngine applies any DiscountRule, in the order given");
+    println!();
+
+    let order = Order::new("premium", 12)
+        .with_line("cable", 5.0, 8)
+        .with_line("router", 80.0, 2)
+        .with_coupon("SAVE20");
+
+    let mut engine = PricingEngine::new();
+    engine.add_rule(Box::new(PercentageDiscount::new(10.0).for_tier("premium")));
+    engine.add_rule(Box::new(BulkDiscount::new(10, 5.0)));
+    engine.add_rule(Box::new(SeasonalDiscount::new("Holiday sale", 12..=12, 15.0)));
+    engine.add_rule(Box::new(CouponDiscount::new("SAVE20", 20.0)));
+    println!("Percentages first, coupon last:");
+    println!("{}", engine.price(&order));
+
+    println!();
+    let mut coupon_first = PricingEngine::new();
+    coupon_first.add_rule(Box::new(CouponDiscount::new("SAVE20", 20.0)));
+    coupon_first.add_rule(Box::new(SeasonalDiscount::new("Holiday sale", 12..=12, 15.0)));
+    coupon_first.add_rule(Box::new(BulkDiscount::new(10, 5.0)));
+    coupon_first.add_rule(Box::new(PercentageDiscount::new(10.0).for_tier("premium")));
+    println!("Same rules, coupon first:");
+    println!("{}", coupon_first.price(&order));
+
+    println!();
+    // New promotions are new rules - the engine is untouched
+    let mut best_of = PricingEngine::new();
+    best_of.add_rule(Box::new(BestOf::new(vec![
+        Box::new(PercentageDiscount::new(10.0).for_tier("premium")),
+        Box::new(SeasonalDiscount::new("Holiday sale", 12..=12, 15.0)),
+    ])));
+    best_of.add_rule(Box::new(CouponDiscount::new("SAVE20", 20.0)));
+    println!("Best of tier and seasonal, then coupon:");
+    println!("{}", best_of.price(&order));
+
+    println!();
+    println!("Benefits of this approach:");
+    println!("1. New promotions implement DiscountRule; PricingEngine never changes");
+    println!("2. Stacking order is configuration, not code");
+    println!("3. Rules compose: BestOf is itself a DiscountRule");
+    println!("4. The quote shows every discount applied");
+}
+
+fn main() {
+    demonstrate_ocp_compliance();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn order() -> Order {
+        Order::new("premium", 12)
+            .with_line("cable", 5.0, 8)
+            .with_line("router", 80.0, 2)
+            .with_coupon("SAVE20")
+    }
+
+    fn engine(rules: Vec<Box<dyn DiscountRule>>) -> PricingEngine {
+        let mut engine = PricingEngine::new();
+        for rule in rules {
+            engine.add_rule(rule);
+        }
+        engine
+    }
+
+    fn all_rules() -> Vec<Box<dyn DiscountRule>> {
+        vec![
+            Box::new(PercentageDiscount::new(10.0).for_tier("premium")),
+            Box::new(BulkDiscount::new(10, 5.0)),
+            Box::new(SeasonalDiscount::new("Holiday sale", 12..=12, 15.0)),
+            Box::new(CouponDiscount::new("SAVE20", 20.0)),
+        ]
+    }
+
+    #[test]
+    fn golden_percentages_then_coupon() {
+        let quote = engine(all_rules()).price(&order());
+        assert_eq!(
+            quote.to_string(),
+            "\
+Subtotal                       $200.00
+  premium customer 10%         -$20.00
+  Bulk 10+ items 5%             -$9.00
+  Holiday sale 15%             -$25.65
+  Coupon SAVE20                -$20.00
+Total                          $125.35"
+        );
+    }
+
+    #[test]
+    fn golden_coupon_then_percentages() {
+        let mut rules = all_rules();
+        rules.reverse();
+        let quote = engine(rules).price(&order());
+        assert_eq!(
+            quote.to_string(),
+            "\
+Subtotal                       $200.00
+  Coupon SAVE20                -$20.00
+  Holiday sale 15%             -$27.00
+  Bulk 10+ items 5%             -$7.65
+  premium customer 10%         -$14.54
+Total                          $130.81"
+        );
+    }
+
+    #[test]
+    fn rules_that_do_not_apply_are_left_off_the_quote() {
+        let order = Order::new("regular", 6).with_line("router", 80.0, 2);
+        let quote = engine(all_rules()).price(&order);
+        assert!(quote.adjustments.is_empty());
+        assert_eq!(quote.total, 160.0);
+    }
+
+    #[test]
+    fn best_of_applies_only_the_largest_discount() {
+        let quote = engine(vec![
+            Box::new(BestOf::new(vec![
+                Box::new(PercentageDiscount::new(10.0).for_tier("premium")),
+                Box::new(SeasonalDiscount::new("Holiday sale", 12..=12, 15.0)),
+            ])),
+            Box::new(CouponDiscount::new("SAVE20", 20.0)),
+        ])
+        .price(&order());
+
+        assert_eq!(quote.adjustments.len(), 2);
+        assert_eq!(quote.adjustments[0].amount, 30.0);
+        assert_eq!(quote.total, 150.0);
+    }
+
+    #[test]
+    fn discounts_never_take_the_total_below_zero() {
+        let order = Order::new("regular", 1).with_line("cable", 5.0, 1).with_coupon("BIG");
+        let quote = engine(vec![Box::new(CouponDiscount::new("BIG", 50.0))]).price(&order);
+        assert_eq!(quote.adjustments[0].amount, 5.0);
+        assert_eq!(quote.total, 0.0);
+    }
+}
@@ -0,0 +1,151 @@
+// VIOLATION OF OPEN-CLOSED PRINCIPLE
+// This code violates OCP because:
+// 1. Customer-type discounts are hard-coded in a match statement
+// 2. Bulk, seasonal and coupon discounts are fixed if-blocks inside the calculator
+// 3. Adding a customer type, promotion or coupon requires modifying existing code
+
+use std::fmt;
+
+/// Hard-coded customer types - violates OCP
+#[derive(Debug, Clone, PartialEq)]
+pub enum CustomerType {
+    Regular,
+    Premium,
+    Vip,
+}
+
+impl fmt::Display for CustomerType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CustomerType::Regular => write!(f, "Regular"),
+            CustomerType::Premium => write!(f, "Premium"),
+            CustomerType::Vip => write!(f, "VIP"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct OrderViolation {
+    pub customer_type: CustomerType,
+    /// (sku, unit price, quantity)
+    pub lines: Vec<(String, f64, u32)>,
+    pub coupon: Option<String>,
+    /// Month the order is placed, 1-12
+    pub month: u32,
+}
+
+impl OrderViolation {
+    pub fn subtotal(&self) -> f64 {
+        self.lines
+            .iter()
+            .fold(0.0, |total, (_, price, quantity)| total + price * *quantity as f64)
+    }
+
+    pub fn item_count(&self) -> u32 {
+        self.lines.iter().map(|(_, _, quantity)| quantity).sum()
+    }
+}
+
+/// Pricing calculator that violates OCP
+pub struct PricingViolation;
+
+impl PricingViolation {
+    /// Violates OCP: every new discount needs another branch here, and the
+    /// order the discounts stack in is buried in the method body
+    pub fn calculate_total(order: &OrderViolation) -> f64 {
+        let mut total = order.subtotal();
+
+        // This match statement violates OCP - must be modified for each new customer type
+        let customer_percent = match order.customer_type {
+            CustomerType::Regular => 0.0,
+            CustomerType::Premium => 10.0,
+            CustomerType::Vip => 20.0,
+        };
+        total -= total * customer_percent / 100.0;
+
+        // Hard-coded bulk discount
+        if order.item_count() >= 10 {
+            total -= total * 0.05;
+        }
+
+        // Hard-coded seasonal sale - changing the season means editing this method
+        if order.month == 12 {
+            total -= total * 0.15;
+        }
+
+        // Hard-coded coupons - every new campaign means editing this match
+        let coupon_amount = match order.coupon.as_deref() {
+            Some("SAVE20") => 20.0,
+            Some("WELCOME5") => 5.0,
+            _ => 0.0,
+        };
+        total -= coupon_amount;
+
+        (total.max(0.0) * 100.0).round() / 100.0
+    }
+}
+
+/// Example usage demonstrating OCP violation
+pub fn demonstrate_ocp_violation() {
+    println!("=== OCP VIOLATION EXAMPLE (PRICING) ===");
+    println!("This code violates the Open-Closed Principle");
+    println!("Adding new discounts requires modifying existing code");
+    println!();
+
+    let order = OrderViolation {
+        customer_type: CustomerType::Premium,
+        lines: vec![
+            ("cable".to_string(), 5.0, 8),
+            ("router".to_string(), 80.0, 2),
+        ],
+        coupon: Some("SAVE20".to_string()),
+        month: 12,
+    };
+
+    println!("Customer: {}", order.customer_type);
+    println!("Items: {}", order.item_count());
+    println!("Subtotal: ${:.2}", order.subtotal());
+    println!("Total: ${:.2}", PricingViolation::calculate_total(&order));
+
+    println!();
+    println!("Problems with this approach:");
+    println!("1. A new customer type requires editing the CustomerType enum and the match");
+    println!("2. Bulk and seasonal thresholds are hard-coded in calculate_total");
+    println!("3. Each coupon campaign requires a code change");
+    println!("4. The order discounts stack in cannot be changed without editing the method");
+    println!("5. No way to see which discounts were applied");
+}
+
+fn main() {
+    demonstrate_ocp_violation();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn order(customer_type: CustomerType, coupon: Option<&str>, month: u32) -> OrderViolation {
+        OrderViolation {
+            customer_type,
+            lines: vec![
+                ("cable".to_string(), 5.0, 8),
+                ("router".to_string(), 80.0, 2),
+            ],
+            coupon: coupon.map(str::to_string),
+            month,
+        }
+    }
+
+    #[test]
+    fn regular_customer_out_of_season_pays_bulk_price() {
+        // 200.00 - 5% bulk
+        assert_eq!(PricingViolation::calculate_total(&order(CustomerType::Regular, None, 6)), 190.0);
+    }
+
+    #[test]
+    fn discounts_stack_in_the_hard_coded_order() {
+        // 200.00 -10% = 180.00, -5% = 171.00, -15% = 145.35, -20.00 = 125.35
+        let total = PricingViolation::calculate_total(&order(CustomerType::Premium, Some("SAVE20"), 12));
+        assert_eq!(total, 125.35);
+    }
+}
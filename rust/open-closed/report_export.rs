@@ -0,0 +1,454 @@
+// CORRECT IMPLEMENTATION - FOLLOWS OPEN-CLOSED PRINCIPLE
+// Report export: each output format is a ReportSink, looked up by file
+// extension in a FormatRegistry. New formats are registered, not coded into
+// the exporter.
+
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+use std::path::Path;
+
+/// Tabular report with a title, column headings and rows of cells
+#[derive(Debug, Clone, PartialEq)]
+pub struct Report {
+    title: String,
+    columns: Vec<String>,
+    rows: Vec<Vec<String>>,
+}
+
+impl Report {
+    pub fn new(title: &str, columns: &[&str]) -> Self {
+        Report {
+            title: title.to_string(),
+            columns: columns.iter().map(|column| column.to_string()).collect(),
+            rows: Vec::new(),
+        }
+    }
+
+    /// Appends a row; it must have one cell per column
+    pub fn add_row(&mut self, cells: &[&str]) -> Result<(), String> {
+        if cells.len() != self.columns.len() {
+            return Err(format!(
+                "Row has {} cells but the report has {} columns",
+                cells.len(),
+                self.columns.len()
+            ));
+        }
+        self.rows.push(cells.iter().map(|cell| cell.to_string()).collect());
+        Ok(())
+    }
+
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    pub fn columns(&self) -> &[String] {
+        &self.columns
+    }
+
+    pub fn rows(&self) -> &[Vec<String>] {
+        &self.rows
+    }
+}
+
+/// Trait defining the interface for all export formats
+pub trait ReportSink {
+    /// Human readable format name, e.g. "CSV"
+    fn name(&self) -> &str;
+    /// MIME type of the output
+    fn content_type(&self) -> &str;
+    /// Writes the whole report to `out`
+    fn write(&self, report: &Report, out: &mut dyn Write) -> io::Result<()>;
+}
+
+/// Comma separated values (RFC 4180 quoting)
+pub struct CsvSink;
+
+impl CsvSink {
+    fn field(cell: &str) -> String {
+        if cell.contains([',', '"', '\n', '\r']) {
+            format!("\"{}\"", cell.replace('"', "\"\""))
+        } else {
+            cell.to_string()
+        }
+    }
+
+    fn line(cells: &[String], out: &mut dyn Write) -> io::Result<()> {
+        let fields: Vec<String> = cells.iter().map(|cell| Self::field(cell)).collect();
+        writeln!(out, "{}", fields.join(","))
+    }
+}
+
+impl ReportSink for CsvSink {
+    fn name(&self) -> &str {
+        "CSV"
+    }
+
+    fn content_type(&self) -> &str {
+        "text/csv"
+    }
+
+    fn write(&self, report: &Report, out: &mut dyn Write) -> io::Result<()> {
+        Self::line(report.columns(), out)?;
+        for row in report.rows() {
+            Self::line(row, out)?;
+        }
+        Ok(())
+    }
+}
+
+/// JSON object with the title and one object per row, keyed by column
+pub struct JsonSink;
+
+impl JsonSink {
+    fn string(value: &str) -> String {
+        let mut escaped = String::from("\"");
+        for c in value.chars() {
+            match c {
+                '"' => escaped.push_str("\\\""),
+                '\\' => escaped.push_str("\\\\"),
+                '\n' => escaped.push_str("\\n"),
+                '\r' => escaped.push_str("\\r"),
+                '\t' => escaped.push_str("\\t"),
+                c if (c as u32) < 0x20 => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+                c => escaped.push(c),
+            }
+        }
+        escaped.push('"');
+        escaped
+    }
+}
+
+impl ReportSink for JsonSink {
+    fn name(&self) -> &str {
+        "JSON"
+    }
+
+    fn content_type(&self) -> &str {
+        "application/json"
+    }
+
+    fn write(&self, report: &Report, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "{{")?;
+        writeln!(out, "  \"title\": {},", Self::string(report.title()))?;
+        writeln!(out, "  \"rows\": [")?;
+        for (i, row) in report.rows().iter().enumerate() {
+            let fields: Vec<String> = report
+                .columns()
+                .iter()
+                .zip(row)
+                .map(|(column, cell)| format!("{}: {}", Self::string(column), Self::string(cell)))
+                .collect();
+            let separator = if i + 1 < report.rows().len() { "," } else { "" };
+            writeln!(out, "    {{{}}}{}", fields.join(", "), separator)?;
+        }
+        writeln!(out, "  ]")?;
+        writeln!(out, "}}")
+    }
+}
+
+/// GitHub-flavored Markdown table under a heading
+pub struct MarkdownSink;
+
+impl MarkdownSink {
+    fn line(cells: &[String], out: &mut dyn Write) -> io::Result<()> {
+        let cells: Vec<String> = cells.iter().map(|cell| cell.replace('|', "\\|")).collect();
+        writeln!(out, "| {} |", cells.join(" | "))
+    }
+}
+
+impl ReportSink for MarkdownSink {
+    fn name(&self) -> &str {
+        "Markdown"
+    }
+
+    fn content_type(&self) -> &str {
+        "text/markdown"
+    }
+
+    fn write(&self, report: &Report, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "# {}", report.title())?;
+        writeln!(out)?;
+        Self::line(report.columns(), out)?;
+        writeln!(out, "|{}", "---|".repeat(report.columns().len()))?;
+        for row in report.rows() {
+            Self::line(row, out)?;
+        }
+        Ok(())
+    }
+}
+
+/// HTML fragment: a heading and a table
+pub struct HtmlSink;
+
+impl HtmlSink {
+    fn escape(text: &str) -> String {
+        text.replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+            .replace('"', "&quot;")
+    }
+
+    fn line(tag: &str, cells: &[String], out: &mut dyn Write) -> io::Result<()> {
+        let cells: String = cells
+            .iter()
+            .map(|cell| format!("<{tag}>{}</{tag}>", Self::escape(cell)))
+            .collect();
+        writeln!(out, "  <tr>{}</tr>", cells)
+    }
+}
+
+impl ReportSink for HtmlSink {
+    fn name(&self) -> &str {
+        "HTML"
+    }
+
+    fn content_type(&self) -> &str {
+        "text/html"
+    }
+
+    fn write(&self, report: &Report, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "<h1>{}</h1>", Self::escape(report.title()))?;
+        writeln!(out, "<table>")?;
+        Self::line("th", report.columns(), out)?;
+        for row in report.rows() {
+            Self::line("td", row, out)?;
+        }
+        writeln!(out, "</table>")
+    }
+}
+
+/// Export formats keyed by file extension - closed for modification
+#[derive(Default)]
+pub struct FormatRegistry {
+    sinks: BTreeMap<String, Box<dyn ReportSink>>,
+}
+
+impl FormatRegistry {
+    pub fn new() -> Self {
+        FormatRegistry {
+            sinks: BTreeMap::new(),
+        }
+    }
+
+    /// A registry with the built-in formats "csv", "json", "md" and "html"
+    pub fn with_builtin_formats() -> Self {
+        let mut registry = FormatRegistry::new();
+        for (extension, sink) in [
+            ("csv", Box::new(CsvSink) as Box<dyn ReportSink>),
+            ("json", Box::new(JsonSink)),
+            ("md", Box::new(MarkdownSink)),
+            ("html", Box::new(HtmlSink)),
+        ] {
+            registry.sinks.insert(extension.to_string(), sink);
+        }
+        registry
+    }
+
+    /// Adds a format for `extension` (case-insensitive, with or without the
+    /// leading dot). Registering an extension twice is an error, so one
+    /// format cannot silently replace another.
+    pub fn register(&mut self, extension: &str, sink: Box<dyn ReportSink>) -> Result<(), String> {
+        let extension = extension.trim_start_matches('.').to_lowercase();
+        if extension.is_empty() {
+            return Err("Extension must not be empty".to_string());
+        }
+        if self.sinks.contains_key(&extension) {
+            return Err(format!("Export format already registered: .{}", extension));
+        }
+        self.sinks.insert(extension, sink);
+        Ok(())
+    }
+
+    pub fn is_registered(&self, extension: &str) -> bool {
+        self.sinks.contains_key(&extension.trim_start_matches('.').to_lowercase())
+    }
+
+    /// Registered extensions in alphabetical order
+    pub fn extensions(&self) -> Vec<&str> {
+        self.sinks.keys().map(|extension| extension.as_str()).collect()
+    }
+
+    /// The format registered for the extension of `path`
+    pub fn sink_for_path(&self, path: &str) -> Result<&dyn ReportSink, String> {
+        let extension = Path::new(path)
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .ok_or_else(|| format!("Cannot tell the export format of {}: no extension", path))?;
+        self.sinks
+            .get(&extension.to_lowercase())
+            .map(|sink| sink.as_ref())
+            .ok_or_else(|| format!("Unsupported export format: .{} (known: {})", extension, self.extensions().join(", ")))
+    }
+
+    /// Writes `report` to `out` in the format chosen by the extension of `path`
+    pub fn export(&self, report: &Report, path: &str, out: &mut dyn Write) -> Result<(), String> {
+        let sink = self.sink_for_path(path)?;
+        sink.write(report, out)
+            .map_err(|e| format!("Failed to write {} as {}: {}", path, sink.name(), e))
+    }
+}
+
+/// Example usage demonstrating OCP compliance
+pub fn demonstrate_ocp_compliance() {
+    println!("=== OCP COMPLIANT EXAMPLE (REPORT EXPORT) ===");
+    println!("FormatRegistry exports to any registered ReportSink");
+    println!();
+
+    let mut report = Report::new("Quarterly sales", &["region", "revenue"]);
+    for row in [["North", "1200"], ["South", "950"], ["East, coastal", "1430"]] {
+        if let Err(e) = report.add_row(&row) {
+            println!("Error: {}", e);
+        }
+    }
+
+    // A format supplied by the user - no existing code changes
+    struct TsvSink;
+
+    impl ReportSink for TsvSink {
+        fn name(&self) -> &str {
+            "TSV"
+        }
+
+        fn content_type(&self) -> &str {
+            "text/tab-separated-values"
+        }
+
+        fn write(&self, report: &Report, out: &mut dyn Write) -> io::Result<()> {
+            writeln!(out, "{}", report.columns().join("\t"))?;
+            for row in report.rows() {
+                writeln!(out, "{}", row.join("\t"))?;
+            }
+            Ok(())
+        }
+    }
+
+    let mut registry = FormatRegistry::with_builtin_formats();
+    if let Err(e) = registry.register("tsv", Box::new(TsvSink)) {
+        println!("Error: {}", e);
+    }
+    if let Err(e) = registry.register(".CSV", Box::new(CsvSink)) {
+        println!("Rejected duplicate: {}", e);
+    }
+    println!("Registered formats: {}", registry.extensions().join(", "));
+    println!();
+
+    for path in ["sales.csv", "sales.json", "sales.md", "sales.html", "sales.tsv", "sales.xml"] {
+        let mut buffer = Vec::new();
+        match registry.export(&report, path, &mut buffer) {
+            Ok(()) => {
+                let content_type = registry.sink_for_path(path).map(|sink| sink.content_type()).unwrap_or("");
+                println!("--- {} ({}) ---", path, content_type);
+                print!("{}", String::from_utf8_lossy(&buffer));
+            }
+            Err(e) => println!("Error: {}", e),
+        }
+    }
+
+    println!();
+    println!("Benefits of this approach:");
+    println!("1. New formats implement ReportSink and are registered by extension");
+    println!("2. FormatRegistry::export never changes");
+    println!("3. Each format owns its own escaping rules");
+    println!("4. Sinks write to any io::Write - files, sockets or in-memory buffers");
+}
+
+fn main() {
+    demonstrate_ocp_compliance();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report() -> Report {
+        let mut report = Report::new("Sales & \"returns\"", &["region", "note"]);
+        report.add_row(&["North", "a, b"]).unwrap();
+        report.add_row(&["South", "<x|y>"]).unwrap();
+        report
+    }
+
+    fn export(path: &str) -> String {
+        let mut buffer = Vec::new();
+        FormatRegistry::with_builtin_formats()
+            .export(&report(), path, &mut buffer)
+            .unwrap();
+        String::from_utf8(buffer).unwrap()
+    }
+
+    #[test]
+    fn csv_quotes_cells_with_commas() {
+        assert_eq!(export("out.csv"), "region,note\nNorth,\"a, b\"\nSouth,<x|y>\n");
+    }
+
+    #[test]
+    fn json_escapes_strings_and_keys_rows_by_column() {
+        assert_eq!(
+            export("out.json"),
+            "\
+{
+  \"title\": \"Sales & \\\"returns\\\"\",
+  \"rows\": [
+    {\"region\": \"North\", \"note\": \"a, b\"},
+    {\"region\": \"South\", \"note\": \"<x|y>\"}
+  ]
+}
+"
+        );
+    }
+
+    #[test]
+    fn markdown_escapes_pipes() {
+        assert_eq!(
+            export("out.md"),
+            "\
+# Sales & \"returns\"
+
+| region | note |
+|---|---|
+| North | a, b |
+| South | <x\\|y> |
+"
+        );
+    }
+
+    #[test]
+    fn html_escapes_markup() {
+        assert_eq!(
+            export("REPORT.HTML"),
+            "\
+<h1>Sales &amp; &quot;returns&quot;</h1>
+<table>
+  <tr><th>region</th><th>note</th></tr>
+  <tr><td>North</td><td>a, b</td></tr>
+  <tr><td>South</td><td>&lt;x|y&gt;</td></tr>
+</table>
+"
+        );
+    }
+
+    #[test]
+    fn unknown_and_missing_extensions_are_errors() {
+        let registry = FormatRegistry::with_builtin_formats();
+        let mut buffer = Vec::new();
+        assert_eq!(
+            registry.export(&report(), "out.xml", &mut buffer),
+            Err("Unsupported export format: .xml (known: csv, html, json, md)".to_string())
+        );
+        assert!(registry.export(&report(), "out", &mut buffer).is_err());
+        assert!(buffer.is_empty());
+    }
+
+    #[test]
+    fn registering_an_extension_twice_is_an_error() {
+        let mut registry = FormatRegistry::with_builtin_formats();
+        assert!(registry.register(".CSV", Box::new(CsvSink)).is_err());
+        assert!(registry.register("txt", Box::new(CsvSink)).is_ok());
+        assert!(registry.is_registered(".TXT"));
+    }
+
+    #[test]
+    fn rows_must_match_the_columns() {
+        let mut report = Report::new("t", &["a", "b"]);
+        assert!(report.add_row(&["only one"]).is_err());
+    }
+}
@@ -0,0 +1,157 @@
+// VIOLATION OF OPEN-CLOSED PRINCIPLE
+// This code violates OCP because:
+// 1. Every output format is a variant of the ExportFormat enum
+// 2. The Exporter matches on the format in several places, one arm per format
+// 3. Adding a format (e.g. TSV or XML) requires modifying the enum and every match
+
+/// Tabular report with a title, column headings and rows of cells
+#[derive(Debug, Clone, PartialEq)]
+pub struct Report {
+    pub title: String,
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+}
+
+/// Hard-coded output formats - violates OCP
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExportFormat {
+    Csv,
+    Json,
+    Markdown,
+    Html,
+}
+
+/// Report exporter that violates OCP
+pub struct Exporter;
+
+impl Exporter {
+    /// Violates OCP: every new format needs another extension here
+    pub fn format_for_path(path: &str) -> Result<ExportFormat, String> {
+        let extension = path.rsplit_once('.').map(|(_, extension)| extension.to_lowercase());
+        match extension.as_deref() {
+            Some("csv") => Ok(ExportFormat::Csv),
+            Some("json") => Ok(ExportFormat::Json),
+            Some("md") => Ok(ExportFormat::Markdown),
+            Some("html") => Ok(ExportFormat::Html),
+            _ => Err(format!("Unsupported export format: {}", path)),
+        }
+    }
+
+    /// Violates OCP: one giant match with every format's rendering inline
+    pub fn export(report: &Report, format: &ExportFormat) -> String {
+        let mut out = String::new();
+        match format {
+            ExportFormat::Csv => {
+                out.push_str(&report.columns.join(","));
+                out.push('\n');
+                for row in &report.rows {
+                    // No quoting - a cell containing a comma breaks the file
+                    out.push_str(&row.join(","));
+                    out.push('\n');
+                }
+            }
+            ExportFormat::Json => {
+                out.push_str(&format!("{{\"title\":\"{}\",\"rows\":[", report.title));
+                for (i, row) in report.rows.iter().enumerate() {
+                    if i > 0 {
+                        out.push(',');
+                    }
+                    let fields: Vec<String> = report
+                        .columns
+                        .iter()
+                        .zip(row)
+                        .map(|(column, cell)| format!("\"{}\":\"{}\"", column, cell))
+                        .collect();
+                    out.push_str(&format!("{{{}}}", fields.join(",")));
+                }
+                out.push_str("]}\n");
+            }
+            ExportFormat::Markdown => {
+                out.push_str(&format!("# {}\n\n", report.title));
+                out.push_str(&format!("| {} |\n", report.columns.join(" | ")));
+                out.push_str(&format!("|{}\n", "---|".repeat(report.columns.len())));
+                for row in &report.rows {
+                    out.push_str(&format!("| {} |\n", row.join(" | ")));
+                }
+            }
+            ExportFormat::Html => {
+                out.push_str(&format!("<h1>{}</h1>\n<table>\n", report.title));
+                out.push_str(&format!("<tr><th>{}</th></tr>\n", report.columns.join("</th><th>")));
+                for row in &report.rows {
+                    out.push_str(&format!("<tr><td>{}</td></tr>\n", row.join("</td><td>")));
+                }
+                out.push_str("</table>\n");
+            }
+        }
+        out
+    }
+
+    /// Yet another match that must be extended for each new format
+    pub fn content_type(format: &ExportFormat) -> &'static str {
+        match format {
+            ExportFormat::Csv => "text/csv",
+            ExportFormat::Json => "application/json",
+            ExportFormat::Markdown => "text/markdown",
+            ExportFormat::Html => "text/html",
+        }
+    }
+}
+
+/// Example usage demonstrating OCP violation
+pub fn demonstrate_ocp_violation() {
+    println!("=== OCP VIOLATION EXAMPLE (REPORT EXPORT) ===");
+    println!("This code violates the Open-Closed Principle");
+    println!("Adding new export formats requires modifying existing code");
+    println!();
+
+    let report = Report {
+        title: "Quarterly sales".to_string(),
+        columns: vec!["region".to_string(), "revenue".to_string()],
+        rows: vec![
+            vec!["North".to_string(), "1200".to_string()],
+            vec!["South".to_string(), "950".to_string()],
+        ],
+    };
+
+    for path in ["sales.csv", "sales.json", "sales.md", "sales.html", "sales.tsv"] {
+        match Exporter::format_for_path(path) {
+            Ok(format) => {
+                println!("--- {} ({}) ---", path, Exporter::content_type(&format));
+                print!("{}", Exporter::export(&report, &format));
+            }
+            Err(e) => println!("Error: {}", e),
+        }
+    }
+
+    println!();
+    println!("Problems with this approach:");
+    println!("1. A TSV export requires editing ExportFormat, format_for_path, export and content_type");
+    println!("2. All formats live in one method, so a change to one risks the others");
+    println!("3. Users of the module cannot add formats of their own");
+}
+
+fn main() {
+    demonstrate_ocp_violation();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format_is_chosen_by_extension() {
+        assert_eq!(Exporter::format_for_path("out.CSV"), Ok(ExportFormat::Csv));
+        assert_eq!(Exporter::format_for_path("out.md"), Ok(ExportFormat::Markdown));
+        assert!(Exporter::format_for_path("out.tsv").is_err());
+    }
+
+    #[test]
+    fn csv_export_lists_columns_then_rows() {
+        let report = Report {
+            title: "t".to_string(),
+            columns: vec!["a".to_string(), "b".to_string()],
+            rows: vec![vec!["1".to_string(), "2".to_string()]],
+        };
+        assert_eq!(Exporter::export(&report, &ExportFormat::Csv), "a,b\n1,2\n");
+    }
+}
@@ -0,0 +1,236 @@
+// CORRECT IMPLEMENTATION - FOLLOWS OPEN-CLOSED PRINCIPLE
+// The classic shapes example: the AreaCalculator works with any type that
+// implements Shape, so new shapes are added without modifying it.
+
+use std::f64::consts::PI;
+use std::fmt::Debug;
+
+/// Trait defining the interface for all shapes
+pub trait Shape: Debug {
+    /// Short description including the dimensions, used in reports
+    fn name(&self) -> String;
+    /// Area in square units
+    fn area(&self) -> f64;
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Circle {
+    radius: f64,
+}
+
+impl Circle {
+    pub fn new(radius: f64) -> Self {
+        Circle { radius }
+    }
+}
+
+impl Shape for Circle {
+    fn name(&self) -> String {
+        format!("Circle (r = {:.2})", self.radius)
+    }
+
+    fn area(&self) -> f64 {
+        PI * self.radius * self.radius
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rectangle {
+    width: f64,
+    height: f64,
+}
+
+impl Rectangle {
+    pub fn new(width: f64, height: f64) -> Self {
+        Rectangle { width, height }
+    }
+}
+
+impl Shape for Rectangle {
+    fn name(&self) -> String {
+        format!("Rectangle ({:.2} x {:.2})", self.width, self.height)
+    }
+
+    fn area(&self) -> f64 {
+        self.width * self.height
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Triangle {
+    base: f64,
+    height: f64,
+}
+
+impl Triangle {
+    pub fn new(base: f64, height: f64) -> Self {
+        Triangle { base, height }
+    }
+}
+
+impl Shape for Triangle {
+    fn name(&self) -> String {
+        format!("Triangle (base {:.2}, height {:.2})", self.base, self.height)
+    }
+
+    fn area(&self) -> f64 {
+        0.5 * self.base * self.height
+    }
+}
+
+/// Sums the areas of any shapes - closed for modification
+#[derive(Debug, Default)]
+pub struct AreaCalculator {
+    shapes: Vec<Box<dyn Shape>>,
+}
+
+impl AreaCalculator {
+    pub fn new() -> Self {
+        AreaCalculator { shapes: Vec::new() }
+    }
+
+    /// Accepts any shape, including ones defined outside this module
+    pub fn add_shape(&mut self, shape: Box<dyn Shape>) {
+        self.shapes.push(shape);
+    }
+
+    pub fn shapes(&self) -> &[Box<dyn Shape>] {
+        &self.shapes
+    }
+
+    pub fn total_area(&self) -> f64 {
+        self.shapes.iter().fold(0.0, |total, shape| total + shape.area())
+    }
+}
+
+/// Client that prints each shape's area and the total. It depends only on the
+/// Shape trait, so it never changes when shapes are added.
+pub fn report_total_area(calculator: &AreaCalculator) -> String {
+    let mut report = String::new();
+    for (i, shape) in calculator.shapes().iter().enumerate() {
+        report.push_str(&format!("{}. {}: {:.2}\n", i + 1, shape.name(), shape.area()));
+    }
+    report.push_str(&format!("Total area: {:.2}", calculator.total_area()));
+    report
+}
+
+// ---------------------------------------------------------------------------
+// Extension: a shape supplied by a user of the module. Nothing above knows
+// about it, and it could just as well live in another crate.
+// ---------------------------------------------------------------------------
+
+/// Regular polygon with `sides` equal sides of length `side_length`
+#[derive(Debug, Clone, PartialEq)]
+pub struct RegularPolygon {
+    sides: u32,
+    side_length: f64,
+}
+
+impl RegularPolygon {
+    pub fn new(sides: u32, side_length: f64) -> Result<Self, String> {
+        if sides < 3 {
+            return Err(format!("A regular polygon needs at least 3 sides, got {}", sides));
+        }
+        if side_length.is_nan() || side_length <= 0.0 {
+            return Err(format!("Side length must be positive, got {}", side_length));
+        }
+        Ok(RegularPolygon { sides, side_length })
+    }
+}
+
+impl Shape for RegularPolygon {
+    fn name(&self) -> String {
+        format!("Regular {}-gon (side {:.2})", self.sides, self.side_length)
+    }
+
+    fn area(&self) -> f64 {
+        let n = self.sides as f64;
+        n * self.side_length * self.side_length / (4.0 * (PI / n).tan())
+    }
+}
+
+/// Example usage demonstrating OCP compliance
+pub fn demonstrate_ocp_compliance() {
+    println!("=== OCP COMPLIANT EXAMPLE (SHAPES) ===");
+    println!("AreaCalculator works with any Shape, including user-supplied ones");
+    println!();
+
+    let mut calculator = AreaCalculator::new();
+    calculator.add_shape(Box::new(Circle::new(1.0)));
+    calculator.add_shape(Box::new(Rectangle::new(3.0, 4.0)));
+    calculator.add_shape(Box::new(Triangle::new(6.0, 2.0)));
+
+    // Added without touching AreaCalculator or report_total_area
+    match RegularPolygon::new(6, 2.0) {
+        Ok(hexagon) => calculator.add_shape(Box::new(hexagon)),
+        Err(e) => println!("Error: {}", e),
+    }
+
+    println!("{}", report_total_area(&calculator));
+
+    println!();
+    println!("Benefits of this approach:");
+    println!("1. New shapes only implement the Shape trait");
+    println!("2. AreaCalculator and report_total_area never change");
+    println!("3. Each shape owns its own area formula");
+}
+
+fn main() {
+    demonstrate_ocp_compliance();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!((actual - expected).abs() < 1e-9, "{} != {}", actual, expected);
+    }
+
+    #[test]
+    fn built_in_shapes_compute_their_area() {
+        assert_close(Circle::new(2.0).area(), 4.0 * PI);
+        assert_close(Rectangle::new(3.0, 4.0).area(), 12.0);
+        assert_close(Triangle::new(6.0, 2.0).area(), 6.0);
+    }
+
+    #[test]
+    fn regular_polygon_matches_known_areas() {
+        // A square and a hexagon made of six equilateral triangles
+        assert_close(RegularPolygon::new(4, 3.0).unwrap().area(), 9.0);
+        let triangle = Triangle::new(2.0, 3.0_f64.sqrt());
+        assert_close(RegularPolygon::new(6, 2.0).unwrap().area(), 6.0 * triangle.area());
+    }
+
+    #[test]
+    fn regular_polygon_rejects_invalid_dimensions() {
+        assert!(RegularPolygon::new(2, 1.0).is_err());
+        assert!(RegularPolygon::new(5, 0.0).is_err());
+        assert!(RegularPolygon::new(5, f64::NAN).is_err());
+    }
+
+    #[test]
+    fn calculator_sums_user_supplied_shapes() {
+        let mut calculator = AreaCalculator::new();
+        calculator.add_shape(Box::new(Rectangle::new(3.0, 4.0)));
+        calculator.add_shape(Box::new(RegularPolygon::new(4, 3.0).unwrap()));
+        assert_close(calculator.total_area(), 21.0);
+    }
+
+    #[test]
+    fn report_lists_each_shape_and_the_total() {
+        let mut calculator = AreaCalculator::new();
+        calculator.add_shape(Box::new(Triangle::new(6.0, 2.0)));
+        calculator.add_shape(Box::new(RegularPolygon::new(4, 3.0).unwrap()));
+
+        let report = report_total_area(&calculator);
+        assert!(report.contains("1. Triangle (base 6.00, height 2.00): 6.00"));
+        assert!(report.contains("2. Regular 4-gon (side 3.00): 9.00"));
+        assert!(report.ends_with("Total area: 15.00"));
+    }
+
+    #[test]
+    fn empty_calculator_reports_zero() {
+        assert_eq!(report_total_area(&AreaCalculator::new()), "Total area: 0.00");
+    }
+}
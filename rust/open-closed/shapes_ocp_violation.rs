@@ -0,0 +1,124 @@
+// VIOLATION OF OPEN-CLOSED PRINCIPLE
+// This code violates OCP because:
+// 1. Adding a new shape requires modifying the Shape enum
+// 2. Every method of the AreaCalculator matches on the enum and must grow a new arm
+// 3. Users of the module cannot add shapes of their own
+
+use std::f64::consts::PI;
+
+/// Hard-coded shape kinds - violates OCP
+#[derive(Debug, Clone, PartialEq)]
+pub enum Shape {
+    Circle { radius: f64 },
+    Rectangle { width: f64, height: f64 },
+    Triangle { base: f64, height: f64 },
+}
+
+/// Area calculator that violates OCP
+#[derive(Default)]
+pub struct AreaCalculatorViolation {
+    shapes: Vec<Shape>,
+}
+
+impl AreaCalculatorViolation {
+    pub fn new() -> Self {
+        AreaCalculatorViolation { shapes: Vec::new() }
+    }
+
+    pub fn add_shape(&mut self, shape: Shape) {
+        self.shapes.push(shape);
+    }
+
+    /// Violates OCP: every new shape needs a new formula here
+    pub fn area(shape: &Shape) -> f64 {
+        match shape {
+            Shape::Circle { radius } => PI * radius * radius,
+            Shape::Rectangle { width, height } => width * height,
+            Shape::Triangle { base, height } => 0.5 * base * height,
+        }
+    }
+
+    /// Violates OCP: every new shape needs a new description here
+    pub fn describe(shape: &Shape) -> String {
+        match shape {
+            Shape::Circle { radius } => format!("Circle (r = {:.2})", radius),
+            Shape::Rectangle { width, height } => {
+                format!("Rectangle ({:.2} x {:.2})", width, height)
+            }
+            Shape::Triangle { base, height } => {
+                format!("Triangle (base {:.2}, height {:.2})", base, height)
+            }
+        }
+    }
+
+    pub fn total_area(&self) -> f64 {
+        self.shapes.iter().fold(0.0, |total, shape| total + Self::area(shape))
+    }
+}
+
+/// Client that prints each shape's area and the total. It only works for the
+/// shapes the enum already knows about.
+pub fn report_total_area(calculator: &AreaCalculatorViolation) -> String {
+    let mut report = String::new();
+    for (i, shape) in calculator.shapes.iter().enumerate() {
+        report.push_str(&format!(
+            "{}. {}: {:.2}\n",
+            i + 1,
+            AreaCalculatorViolation::describe(shape),
+            AreaCalculatorViolation::area(shape)
+        ));
+    }
+    report.push_str(&format!("Total area: {:.2}", calculator.total_area()));
+    report
+}
+
+/// Example usage demonstrating OCP violation
+pub fn demonstrate_ocp_violation() {
+    println!("=== OCP VIOLATION EXAMPLE (SHAPES) ===");
+    println!("This code violates the Open-Closed Principle");
+    println!("Adding new shapes requires modifying existing code");
+    println!();
+
+    let mut calculator = AreaCalculatorViolation::new();
+    calculator.add_shape(Shape::Circle { radius: 1.0 });
+    calculator.add_shape(Shape::Rectangle { width: 3.0, height: 4.0 });
+    calculator.add_shape(Shape::Triangle { base: 6.0, height: 2.0 });
+
+    println!("{}", report_total_area(&calculator));
+
+    println!();
+    println!("Problems with this approach:");
+    println!("1. A regular polygon cannot be added without editing the Shape enum");
+    println!("2. area() must grow a new match arm for each new shape");
+    println!("3. describe() must grow a new match arm for each new shape");
+    println!("4. Users of the module cannot supply shapes of their own");
+}
+
+fn main() {
+    demonstrate_ocp_violation();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn areas_follow_the_hard_coded_formulas() {
+        let circle = Shape::Circle { radius: 2.0 };
+        assert!((AreaCalculatorViolation::area(&circle) - 4.0 * PI).abs() < 1e-9);
+        assert_eq!(AreaCalculatorViolation::area(&Shape::Rectangle { width: 3.0, height: 4.0 }), 12.0);
+        assert_eq!(AreaCalculatorViolation::area(&Shape::Triangle { base: 6.0, height: 2.0 }), 6.0);
+    }
+
+    #[test]
+    fn report_lists_each_shape_and_the_total() {
+        let mut calculator = AreaCalculatorViolation::new();
+        calculator.add_shape(Shape::Rectangle { width: 3.0, height: 4.0 });
+        calculator.add_shape(Shape::Triangle { base: 6.0, height: 2.0 });
+
+        let report = report_total_area(&calculator);
+        assert!(report.contains("1. Rectangle (3.00 x 4.00): 12.00"));
+        assert!(report.contains("2. Triangle (base 6.00, height 2.00): 6.00"));
+        assert!(report.ends_with("Total area: 18.00"));
+    }
+}
@@ -1,16 +1,266 @@
 // CORRECT IMPLEMENTATION - FOLLOWS OPEN-CLOSED PRINCIPLE
 // This approach uses traits to allow extension without modification
 
-use std::fmt::Debug;
+use std::cell::RefCell;
+use std::collections::{BTreeMap, BTreeSet};
+use std::f64::consts::PI;
+use std::fmt::{self, Debug};
+use std::ops::RangeInclusive;
+use std::path::Path;
+use std::rc::Rc;
+use serde::Deserialize;
+
+// Builder pattern over Circuit: stages added in an order the compiler checks.
+// Public so the compile-fail cases in patterns/ui can name it.
+#[path = "patterns/builder.rs"]
+pub mod builder;
+
+// Factory and abstract factory: transistors from specifications, and kits of
+// parts rated to work together
+#[path = "patterns/factory.rs"]
+mod factory;
+
+use builder::CircuitBuilder;
+use factory::{DeviceKit, DomesticKit, EntityFactory, IndustrialKit};
+
+/// Trait shared by everything a circuit can contain. Each element receives the
+/// signal from the stage before it and the shared supply, and reports its own
+/// output, so circuits work with any element without knowing its kind.
+pub trait CircuitElement: Debug {
+    /// Applies this step's inputs: the signal from the previous stage and the shared supply
+    fn drive(&mut self, signal: f64, supply: f64);
+    /// Advances internal state by `dt` seconds; `f64::INFINITY` settles it to
+    /// its steady state. Elements without memory ignore it.
+    fn tick(&mut self, _dt: f64) {}
+    /// Measured output (Collector - Emitter for a transistor)
+    fn output(&self) -> f64;
+    /// Power dissipated at the current operating point, in watts
+    fn power_consumption(&self) -> f64;
+    /// What this device is, for inventories and reports
+    fn describe(&self) -> DeviceInfo;
+    /// Gain at `freq_hz` relative to the gain at DC, between 0 and 1.
+    /// Ideal (flat) unless a type knows its bandwidth.
+    fn frequency_response(&self, _freq_hz: f64) -> f64 {
+        1.0
+    }
+    /// The registry type and parameters that recreate this transistor, used for
+    /// netlist export. `None` when it has no registry name.
+    fn to_spec(&self) -> Option<TransistorSpec> {
+        None
+    }
+    /// Advances the element's thermal model by `dt` seconds at `ambient` °C.
+    /// Elements that do not heat up ignore it.
+    fn heat(&mut self, _dt: f64, _ambient: f64) {}
+    /// Junction temperature in °C; `None` for elements without a thermal model
+    fn junction_temperature(&self) -> Option<f64> {
+        None
+    }
+    /// True while the element is above its maximum temperature and has shut down
+    fn is_shut_down(&self) -> bool {
+        false
+    }
+    /// The junction temperature the element would settle at if held at its
+    /// current operating point in `ambient` °C, and the temperature at which
+    /// it shuts down; `None` for elements without a thermal model
+    fn thermal_limit(&self, _ambient: f64) -> Option<(f64, f64)> {
+        None
+    }
+    /// True when the drive at the current operating point is below the
+    /// element's conduction threshold, so it passes no signal
+    fn is_cut_off(&self) -> bool {
+        false
+    }
+    /// True when the output is held at one of the element's saturation limits
+    fn is_clipping(&self) -> bool {
+        false
+    }
+}
 
 /// Trait defining the interface for all transistor types
-pub trait Transistor: Debug {
+pub trait Transistor: CircuitElement {
     /// Control input
     fn base(&mut self, signal: f64);
     /// Main input
     fn collector(&mut self, input: f64);
-    /// Measured output (Collector - Emitter)
-    fn output(&self) -> f64;
+}
+
+/// Name and description of a transistor, as reported by `Transistor::describe`
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeviceInfo {
+    pub name: String,
+    pub description: String,
+}
+
+impl DeviceInfo {
+    pub fn new(name: &str, description: &str) -> Self {
+        DeviceInfo {
+            name: name.to_string(),
+            description: description.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for DeviceInfo {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {}", self.name, self.description)
+    }
+}
+
+/// Temperature the nominal gains are specified at, in °C
+pub const REFERENCE_TEMPERATURE: f64 = 25.0;
+
+/// Electrical characteristics of a transistor model. Each type reads the
+/// fields that apply to it. `Default` is neutral (unit gain, no threshold, no
+/// output limits); `bjt()`, `fet()` and so on are the presets each type starts from.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TransistorParams {
+    pub gain: f64,
+    /// Control voltage where conduction starts (pinch-off for a JFET)
+    pub threshold: f64,
+    /// Saturation limits: the output is clamped to this range
+    pub min_output: f64,
+    pub max_output: f64,
+    /// Fractional change in gain per °C away from REFERENCE_TEMPERATURE
+    pub temperature_coefficient: f64,
+    /// Operating (junction) temperature in °C; the thermal simulation updates it
+    pub temperature: f64,
+    /// Frequency in Hz where the gain has dropped by 3 dB (single-pole roll-off)
+    pub cutoff_frequency: f64,
+    /// Junction temperature in °C above which the transistor shuts down
+    pub max_temperature: f64,
+    /// Junction-to-ambient thermal resistance in °C/W
+    pub thermal_resistance: f64,
+    /// Heat capacity of the junction in J/°C
+    pub thermal_capacitance: f64,
+}
+
+impl Default for TransistorParams {
+    fn default() -> Self {
+        TransistorParams {
+            gain: 1.0,
+            threshold: 0.0,
+            min_output: f64::NEG_INFINITY,
+            max_output: f64::INFINITY,
+            temperature_coefficient: 0.0,
+            temperature: REFERENCE_TEMPERATURE,
+            cutoff_frequency: f64::INFINITY,
+            max_temperature: 150.0,
+            thermal_resistance: 50.0,
+            thermal_capacitance: 0.02,
+        }
+    }
+}
+
+impl TransistorParams {
+    pub fn bjt() -> Self {
+        TransistorParams { gain: 0.1, cutoff_frequency: 1.0e6, ..Self::default() }
+    }
+    
+    pub fn fet() -> Self {
+        TransistorParams { gain: 0.15, cutoff_frequency: 1.0e7, ..Self::default() }
+    }
+    
+    pub fn mosfet() -> Self {
+        TransistorParams { gain: 0.2, threshold: 0.7, cutoff_frequency: 1.0e8, ..Self::default() }
+    }
+    
+    /// Gain scales I_DSS; the threshold is the pinch-off voltage V_P
+    pub fn jfet() -> Self {
+        TransistorParams { threshold: -4.0, cutoff_frequency: 1.0e7, ..Self::default() }
+    }
+    
+    /// Gain is the transconductance above the gate threshold
+    pub fn igbt() -> Self {
+        TransistorParams { gain: 4.0, threshold: 5.0, cutoff_frequency: 5.0e4, ..Self::default() }
+    }
+    
+    /// Gain corrected for the operating temperature; never negative, and 0 once shut down
+    pub fn effective_gain(&self) -> f64 {
+        if self.is_shut_down() {
+            return 0.0;
+        }
+        let drift = self.temperature_coefficient * (self.temperature - REFERENCE_TEMPERATURE);
+        (self.gain * (1.0 + drift)).max(0.0)
+    }
+    
+    pub fn is_shut_down(&self) -> bool {
+        self.temperature > self.max_temperature
+    }
+    
+    /// Junction temperature reached after dissipating `power` watts for long
+    /// enough at `ambient` °C
+    pub fn steady_state_temperature(&self, power: f64, ambient: f64) -> f64 {
+        ambient + power * self.thermal_resistance
+    }
+    
+    /// Advances the junction temperature by `dt` seconds while dissipating
+    /// `power` watts: first-order heating towards ambient + power × thermal
+    /// resistance, with time constant thermal resistance × capacitance
+    pub fn heat(&mut self, power: f64, dt: f64, ambient: f64) {
+        let steady = self.steady_state_temperature(power, ambient);
+        let time_constant = self.thermal_resistance * self.thermal_capacitance;
+        self.temperature += (steady - self.temperature) * (1.0 - (-dt / time_constant).exp());
+    }
+    
+    /// Applies the saturation limits
+    pub fn clamp(&self, output: f64) -> f64 {
+        output.max(self.min_output).min(self.max_output)
+    }
+    
+    /// True when a clamped `output` sits at a saturation limit
+    pub fn is_clipping(&self, output: f64) -> bool {
+        output <= self.min_output || output >= self.max_output
+    }
+    
+    /// Single-pole low-pass roll-off: 1 at DC, 1/√2 (-3 dB) at the cutoff
+    pub fn frequency_response(&self, freq_hz: f64) -> f64 {
+        1.0 / (1.0 + (freq_hz / self.cutoff_frequency).powi(2)).sqrt()
+    }
+    
+    /// Checks the parameters describe a usable device. Infinite saturation
+    /// limits and cutoff frequency mean "none"; every other field must be finite,
+    /// and no field may be NaN.
+    pub fn validate(&self) -> Result<(), String> {
+        let finite = [
+            ("gain", self.gain),
+            ("threshold", self.threshold),
+            ("temperature_coefficient", self.temperature_coefficient),
+            ("temperature", self.temperature),
+            ("max_temperature", self.max_temperature),
+            ("thermal_resistance", self.thermal_resistance),
+            ("thermal_capacitance", self.thermal_capacitance),
+        ];
+        if let Some((name, value)) = finite.iter().find(|(_, value)| !value.is_finite()) {
+            return Err(format!("{} must be finite, got {}", name, value));
+        }
+        let unbounded = [
+            ("min_output", self.min_output),
+            ("max_output", self.max_output),
+            ("cutoff_frequency", self.cutoff_frequency),
+        ];
+        if let Some((name, _)) = unbounded.iter().find(|(_, value)| value.is_nan()) {
+            return Err(format!("{} must be a number, got NaN", name));
+        }
+        if self.min_output == f64::INFINITY || self.max_output == f64::NEG_INFINITY {
+            return Err(format!("saturation limits {}..{} leave no finite output", self.min_output, self.max_output));
+        }
+        if self.min_output > self.max_output {
+            return Err(format!("min_output {} is above max_output {}", self.min_output, self.max_output));
+        }
+        if self.gain < 0.0 {
+            return Err(format!("gain must not be negative, got {}", self.gain));
+        }
+        if self.cutoff_frequency <= 0.0 {
+            return Err(format!("cutoff_frequency must be positive, got {}", self.cutoff_frequency));
+        }
+        if self.thermal_resistance <= 0.0 || self.thermal_capacitance <= 0.0 {
+            return Err(format!(
+                "thermal_resistance and thermal_capacitance must be positive, got {} and {}",
+                self.thermal_resistance, self.thermal_capacitance
+            ));
+        }
+        Ok(())
+    }
 }
 
 /// BJT Transistor implementation
@@ -18,17 +268,81 @@ pub trait Transistor: Debug {
 pub struct BJTTransistor {
     base_signal: f64,
     collector_input: f64,
+    params: TransistorParams,
 }
 
 impl BJTTransistor {
     pub fn new() -> Self {
+        BJTTransistor::default()
+    }
+    
+    pub fn with_params(params: TransistorParams) -> Self {
         BJTTransistor {
             base_signal: 0.0,
             collector_input: 0.0,
+            params,
         }
     }
 }
 
+impl Default for BJTTransistor {
+    fn default() -> Self {
+        BJTTransistor::with_params(TransistorParams::bjt())
+    }
+}
+
+impl CircuitElement for BJTTransistor {
+    fn drive(&mut self, signal: f64, supply: f64) {
+        self.base(signal);
+        self.collector(supply);
+    }
+    
+    fn output(&self) -> f64 {
+        // Simulated gain: collector output depends on base signal
+        let output = self.collector_input * (self.base_signal * self.params.effective_gain()); // crude amplifier model
+        self.params.clamp(output)
+    }
+    
+    // Dissipation follows the collector current, so it rises and falls with the gain
+    fn power_consumption(&self) -> f64 {
+        self.base_signal * self.collector_input * 0.5 * self.params.effective_gain()
+    }
+    
+    fn describe(&self) -> DeviceInfo {
+        DeviceInfo::new("BJT", "Bipolar Junction Transistor - Current controlled")
+    }
+    
+    fn frequency_response(&self, freq_hz: f64) -> f64 {
+        self.params.frequency_response(freq_hz)
+    }
+    
+    fn to_spec(&self) -> Option<TransistorSpec> {
+        Some(TransistorSpec::new("bjt", changed_params(&self.params, &TransistorParams::bjt())))
+    }
+    
+    fn heat(&mut self, dt: f64, ambient: f64) {
+        let power = self.power_consumption();
+        self.params.heat(power, dt, ambient);
+    }
+    
+    fn junction_temperature(&self) -> Option<f64> {
+        Some(self.params.temperature)
+    }
+    
+    fn is_shut_down(&self) -> bool {
+        self.params.is_shut_down()
+    }
+    
+    fn thermal_limit(&self, ambient: f64) -> Option<(f64, f64)> {
+        let power = self.power_consumption();
+        Some((self.params.steady_state_temperature(power, ambient), self.params.max_temperature))
+    }
+    
+    fn is_clipping(&self) -> bool {
+        self.params.is_clipping(self.output())
+    }
+}
+
 impl Transistor for BJTTransistor {
     fn base(&mut self, signal: f64) {
         self.base_signal = signal;
@@ -37,11 +351,6 @@ impl Transistor for BJTTransistor {
     fn collector(&mut self, input: f64) {
         self.collector_input = input;
     }
-    
-    fn output(&self) -> f64 {
-        // Simulated gain: collector output depends on base signal
-        self.collector_input * (self.base_signal * 0.1) // crude amplifier model
-    }
 }
 
 /// FET Transistor implementation - extends without modifying existing code
@@ -49,17 +358,79 @@ impl Transistor for BJTTransistor {
 pub struct FETTransistor {
     gate_voltage: f64,
     drain_current: f64,
+    params: TransistorParams,
 }
 
 impl FETTransistor {
     pub fn new() -> Self {
+        FETTransistor::default()
+    }
+    
+    pub fn with_params(params: TransistorParams) -> Self {
         FETTransistor {
             gate_voltage: 0.0,
             drain_current: 0.0,
+            params,
         }
     }
 }
 
+impl Default for FETTransistor {
+    fn default() -> Self {
+        FETTransistor::with_params(TransistorParams::fet())
+    }
+}
+
+impl CircuitElement for FETTransistor {
+    fn drive(&mut self, signal: f64, supply: f64) {
+        self.base(signal);
+        self.collector(supply);
+    }
+    
+    fn output(&self) -> f64 {
+        // FET specific behavior - different from BJT
+        self.params.clamp(self.drain_current * (self.gate_voltage * self.params.effective_gain()))
+    }
+    
+    fn power_consumption(&self) -> f64 {
+        self.gate_voltage * self.drain_current * 0.2 * self.params.effective_gain()
+    }
+    
+    fn describe(&self) -> DeviceInfo {
+        DeviceInfo::new("FET", "Field Effect Transistor - Voltage controlled")
+    }
+    
+    fn frequency_response(&self, freq_hz: f64) -> f64 {
+        self.params.frequency_response(freq_hz)
+    }
+    
+    fn to_spec(&self) -> Option<TransistorSpec> {
+        Some(TransistorSpec::new("fet", changed_params(&self.params, &TransistorParams::fet())))
+    }
+    
+    fn heat(&mut self, dt: f64, ambient: f64) {
+        let power = self.power_consumption();
+        self.params.heat(power, dt, ambient);
+    }
+    
+    fn junction_temperature(&self) -> Option<f64> {
+        Some(self.params.temperature)
+    }
+    
+    fn is_shut_down(&self) -> bool {
+        self.params.is_shut_down()
+    }
+    
+    fn thermal_limit(&self, ambient: f64) -> Option<(f64, f64)> {
+        let power = self.power_consumption();
+        Some((self.params.steady_state_temperature(power, ambient), self.params.max_temperature))
+    }
+    
+    fn is_clipping(&self) -> bool {
+        self.params.is_clipping(self.output())
+    }
+}
+
 impl Transistor for FETTransistor {
     fn base(&mut self, signal: f64) {
         self.gate_voltage = signal;
@@ -68,11 +439,6 @@ impl Transistor for FETTransistor {
     fn collector(&mut self, input: f64) {
         self.drain_current = input;
     }
-    
-    fn output(&self) -> f64 {
-        // FET specific behavior - different from BJT
-        self.drain_current * (self.gate_voltage * 0.15)
-    }
 }
 
 /// MOSFET Transistor implementation - another extension without modifying existing code
@@ -80,17 +446,91 @@ impl Transistor for FETTransistor {
 pub struct MOSFETTransistor {
     gate_voltage: f64,
     drain_current: f64,
-    threshold: f64,
+    params: TransistorParams,
 }
 
 impl MOSFETTransistor {
     pub fn new() -> Self {
+        MOSFETTransistor::default()
+    }
+    
+    pub fn with_params(params: TransistorParams) -> Self {
         MOSFETTransistor {
             gate_voltage: 0.0,
             drain_current: 0.0,
-            threshold: 0.7,
+            params,
+        }
+    }
+}
+
+impl Default for MOSFETTransistor {
+    fn default() -> Self {
+        MOSFETTransistor::with_params(TransistorParams::mosfet())
+    }
+}
+
+impl CircuitElement for MOSFETTransistor {
+    fn drive(&mut self, signal: f64, supply: f64) {
+        self.base(signal);
+        self.collector(supply);
+    }
+    
+    fn output(&self) -> f64 {
+        // MOSFET specific behavior with threshold
+        if self.gate_voltage > self.params.threshold {
+            let overdrive = self.gate_voltage - self.params.threshold;
+            self.params.clamp(self.drain_current * overdrive * self.params.effective_gain())
+        } else {
+            0.0
+        }
+    }
+    
+    fn power_consumption(&self) -> f64 {
+        // No channel, no dissipation below the threshold
+        if self.gate_voltage > self.params.threshold {
+            self.gate_voltage * self.drain_current * 0.1 * self.params.effective_gain()
+        } else {
+            0.0
         }
     }
+    
+    fn describe(&self) -> DeviceInfo {
+        DeviceInfo::new("MOSFET", "Metal Oxide Semiconductor FET - Enhanced mode")
+    }
+    
+    fn frequency_response(&self, freq_hz: f64) -> f64 {
+        self.params.frequency_response(freq_hz)
+    }
+    
+    fn to_spec(&self) -> Option<TransistorSpec> {
+        Some(TransistorSpec::new("mosfet", changed_params(&self.params, &TransistorParams::mosfet())))
+    }
+    
+    fn heat(&mut self, dt: f64, ambient: f64) {
+        let power = self.power_consumption();
+        self.params.heat(power, dt, ambient);
+    }
+    
+    fn junction_temperature(&self) -> Option<f64> {
+        Some(self.params.temperature)
+    }
+    
+    fn is_shut_down(&self) -> bool {
+        self.params.is_shut_down()
+    }
+    
+    fn thermal_limit(&self, ambient: f64) -> Option<(f64, f64)> {
+        let power = self.power_consumption();
+        Some((self.params.steady_state_temperature(power, ambient), self.params.max_temperature))
+    }
+    
+    fn is_cut_off(&self) -> bool {
+        self.gate_voltage <= self.params.threshold
+    }
+    
+    fn is_clipping(&self) -> bool {
+        self.params.is_clipping(self.output())
+    }
 }
 
 impl Transistor for MOSFETTransistor {
@@ -101,80 +541,4138 @@ impl Transistor for MOSFETTransistor {
     fn collector(&mut self, input: f64) {
         self.drain_current = input;
     }
+}
+
+/// N-channel JFET - drain current follows the Shockley equation
+/// `I_D = I_DSS * (1 - V_GS / V_P)^2`, with the collector input taken as I_DSS.
+/// The channel is fully open at V_GS = 0 and pinched off at V_GS <= V_P,
+/// where V_P is the (negative) threshold.
+#[derive(Debug)]
+pub struct JFETTransistor {
+    gate_voltage: f64,
+    saturation_current: f64,
+    params: TransistorParams,
+}
+
+impl JFETTransistor {
+    pub fn new() -> Self {
+        JFETTransistor::default()
+    }
     
-    fn output(&self) -> f64 {
-        // MOSFET specific behavior with threshold
-        if self.gate_voltage > self.threshold {
-            self.drain_current * (self.gate_voltage - self.threshold) * 0.2
-        } else {
-            0.0
+    pub fn with_params(params: TransistorParams) -> Self {
+        JFETTransistor {
+            gate_voltage: 0.0,
+            saturation_current: 0.0,
+            params,
         }
     }
 }
 
-/// Amplifier circuit that follows OCP
-pub struct AmplifierCircuit {
-    transistors: Vec<Box<dyn Transistor>>,
+impl Default for JFETTransistor {
+    fn default() -> Self {
+        JFETTransistor::with_params(TransistorParams::jfet())
+    }
 }
 
-impl AmplifierCircuit {
-    pub fn new() -> Self {
-        AmplifierCircuit {
-            transistors: Vec::new(),
+impl CircuitElement for JFETTransistor {
+    fn drive(&mut self, signal: f64, supply: f64) {
+        self.base(signal);
+        self.collector(supply);
+    }
+    
+    fn output(&self) -> f64 {
+        let pinch_off = self.params.threshold;
+        if self.gate_voltage <= pinch_off {
+            return 0.0;
         }
+        // A forward-biased gate does not open the channel any further than V_GS = 0
+        let vgs = self.gate_voltage.min(0.0);
+        let drain_current = self.saturation_current * (1.0 - vgs / pinch_off).powi(2);
+        self.params.clamp(drain_current * self.params.effective_gain())
     }
     
-    /// This method doesn't need to change when new transistor types are added
-    pub fn add_transistor(&mut self, transistor: Box<dyn Transistor>) {
-        self.transistors.push(transistor);
+    fn power_consumption(&self) -> f64 {
+        // Dissipation follows the drain current, so a pinched-off channel draws nothing
+        self.output().abs() * 0.03
     }
     
-    /// This method works with any Transistor implementation
-    pub fn amplify_signal(&mut self, signal: f64, input: f64) -> f64 {
-        let mut total_output = 0.0;
-        
-        for transistor in &mut self.transistors {
-            transistor.base(signal);
-            transistor.collector(input);
-            total_output += transistor.output();
-        }
-        
-        total_output
+    fn describe(&self) -> DeviceInfo {
+        DeviceInfo::new("JFET", "Junction FET - Depletion mode, on until pinched off")
     }
-}
-
-/// Example usage demonstrating OCP compliance
-pub fn demonstrate_correct_ocp() {
-    println!("=== CORRECT OCP IMPLEMENTATION ===");
-    println!("This code follows the Open-Closed Principle");
-    println!("New transistor types can be added without modifying existing code");
-    println!();
     
-    // Create amplifier circuit
-    let mut amplifier = AmplifierCircuit::new();
+    fn frequency_response(&self, freq_hz: f64) -> f64 {
+        self.params.frequency_response(freq_hz)
+    }
     
-    // Add different transistor types
-    amplifier.add_transistor(Box::new(BJTTransistor::new()));
-    amplifier.add_transistor(Box::new(FETTransistor::new()));
-    amplifier.add_transistor(Box::new(MOSFETTransistor::new()));
+    fn to_spec(&self) -> Option<TransistorSpec> {
+        Some(TransistorSpec::new("jfet", changed_params(&self.params, &TransistorParams::jfet())))
+    }
     
-    // Test the amplifier
-    let signal = 2.0;
-    let input = 5.0;
+    fn heat(&mut self, dt: f64, ambient: f64) {
+        let power = self.power_consumption();
+        self.params.heat(power, dt, ambient);
+    }
     
-    let output = amplifier.amplify_signal(signal, input);
-    println!("Input Signal: {:.2}", signal);
-    println!("Input Current: {:.2}", input);
-    println!("Amplified Output: {:.2}", output);
+    fn junction_temperature(&self) -> Option<f64> {
+        Some(self.params.temperature)
+    }
     
-    println!();
-    println!("Benefits of this approach:");
-    println!("1. New transistor types can be added without modifying existing code");
-    println!("2. Each transistor type is responsible for its own behavior");
-    println!("3. Traits enable flexible design");
-    println!("4. Follows Single Responsibility Principle");
+    fn is_shut_down(&self) -> bool {
+        self.params.is_shut_down()
+    }
+    
+    fn thermal_limit(&self, ambient: f64) -> Option<(f64, f64)> {
+        let power = self.power_consumption();
+        Some((self.params.steady_state_temperature(power, ambient), self.params.max_temperature))
+    }
+    
+    // Pinched off at or below V_P
+    fn is_cut_off(&self) -> bool {
+        self.gate_voltage <= self.params.threshold
+    }
+    
+    fn is_clipping(&self) -> bool {
+        self.params.is_clipping(self.output())
+    }
 }
 
-fn main() {
-    demonstrate_correct_ocp();
-}
\ No newline at end of file
+impl Transistor for JFETTransistor {
+    fn base(&mut self, signal: f64) {
+        self.gate_voltage = signal;
+    }
+    
+    fn collector(&mut self, input: f64) {
+        self.saturation_current = input;
+    }
+}
+
+/// IGBT - a MOSFET gate driving a bipolar output stage. It is off below the
+/// gate threshold; above it the output rises with transconductance (the gain)
+/// until it saturates at the collector input minus the on-state voltage drop V_CE(sat).
+#[derive(Debug)]
+pub struct IGBTTransistor {
+    gate_voltage: f64,
+    collector_input: f64,
+    params: TransistorParams,
+    saturation_voltage: f64,
+}
+
+impl IGBTTransistor {
+    pub const DEFAULT_SATURATION_VOLTAGE: f64 = 1.7;
+    
+    pub fn new() -> Self {
+        IGBTTransistor::default()
+    }
+    
+    pub fn with_params(params: TransistorParams, saturation_voltage: f64) -> Self {
+        IGBTTransistor {
+            gate_voltage: 0.0,
+            collector_input: 0.0,
+            params,
+            saturation_voltage,
+        }
+    }
+}
+
+impl Default for IGBTTransistor {
+    fn default() -> Self {
+        IGBTTransistor::with_params(TransistorParams::igbt(), Self::DEFAULT_SATURATION_VOLTAGE)
+    }
+}
+
+impl CircuitElement for IGBTTransistor {
+    fn drive(&mut self, signal: f64, supply: f64) {
+        self.base(signal);
+        self.collector(supply);
+    }
+    
+    fn output(&self) -> f64 {
+        if self.gate_voltage <= self.params.threshold {
+            return 0.0;
+        }
+        let active = self.params.effective_gain() * (self.gate_voltage - self.params.threshold);
+        let saturated = (self.collector_input - self.saturation_voltage).max(0.0);
+        self.params.clamp(active.min(saturated))
+    }
+    
+    fn power_consumption(&self) -> f64 {
+        // Conduction loss: the output current across the on-state voltage drop
+        self.output().abs() * self.saturation_voltage
+    }
+    
+    fn describe(&self) -> DeviceInfo {
+        DeviceInfo::new("IGBT", "Insulated Gate Bipolar Transistor - MOSFET gate, bipolar output")
+    }
+    
+    fn frequency_response(&self, freq_hz: f64) -> f64 {
+        self.params.frequency_response(freq_hz)
+    }
+    
+    fn to_spec(&self) -> Option<TransistorSpec> {
+        let mut params = changed_params(&self.params, &TransistorParams::igbt());
+        if self.saturation_voltage != Self::DEFAULT_SATURATION_VOLTAGE {
+            params.insert("saturation_voltage".to_string(), self.saturation_voltage);
+        }
+        Some(TransistorSpec::new("igbt", params))
+    }
+    
+    fn heat(&mut self, dt: f64, ambient: f64) {
+        let power = self.power_consumption();
+        self.params.heat(power, dt, ambient);
+    }
+    
+    fn junction_temperature(&self) -> Option<f64> {
+        Some(self.params.temperature)
+    }
+    
+    fn is_shut_down(&self) -> bool {
+        self.params.is_shut_down()
+    }
+    
+    fn thermal_limit(&self, ambient: f64) -> Option<(f64, f64)> {
+        let power = self.power_consumption();
+        Some((self.params.steady_state_temperature(power, ambient), self.params.max_temperature))
+    }
+    
+    fn is_cut_off(&self) -> bool {
+        self.gate_voltage <= self.params.threshold
+    }
+    
+    fn is_clipping(&self) -> bool {
+        self.params.is_clipping(self.output())
+    }
+}
+
+impl Transistor for IGBTTransistor {
+    fn base(&mut self, signal: f64) {
+        self.gate_voltage = signal;
+    }
+    
+    fn collector(&mut self, input: f64) {
+        self.collector_input = input;
+    }
+}
+
+/// Darlington pair - two transistors where the first one's output drives the
+/// second one's base, and both share the collector input. It is built purely
+/// from the Transistor trait, so any two implementations can be chained and
+/// their gains multiply.
+#[derive(Debug)]
+pub struct DarlingtonPair {
+    first: Box<dyn Transistor>,
+    second: Box<dyn Transistor>,
+}
+
+impl DarlingtonPair {
+    pub fn new(first: Box<dyn Transistor>, second: Box<dyn Transistor>) -> Self {
+        DarlingtonPair { first, second }
+    }
+    
+    /// The classic pair: two BJTs with the same characteristics
+    pub fn bjt(params: TransistorParams) -> Self {
+        DarlingtonPair::new(Box::new(BJTTransistor::with_params(params)), Box::new(BJTTransistor::with_params(params)))
+    }
+    
+    // The second stage's base follows the first stage's output
+    fn propagate(&mut self) {
+        let drive = self.first.output();
+        self.second.base(drive);
+    }
+}
+
+impl CircuitElement for DarlingtonPair {
+    fn drive(&mut self, signal: f64, supply: f64) {
+        self.base(signal);
+        self.collector(supply);
+    }
+    
+    fn output(&self) -> f64 {
+        self.second.output()
+    }
+    
+    fn power_consumption(&self) -> f64 {
+        self.first.power_consumption() + self.second.power_consumption()
+    }
+    
+    fn describe(&self) -> DeviceInfo {
+        DeviceInfo {
+            name: format!("Darlington ({} + {})", self.first.describe().name, self.second.describe().name),
+            description: "Two cascaded transistors - gains multiply".to_string(),
+        }
+    }
+    
+    // Both stages roll off, so their responses multiply like their gains
+    fn frequency_response(&self, freq_hz: f64) -> f64 {
+        self.first.frequency_response(freq_hz) * self.second.frequency_response(freq_hz)
+    }
+    
+    // Only the classic pair of identical BJTs has a registry name
+    fn to_spec(&self) -> Option<TransistorSpec> {
+        let (first, second) = (self.first.to_spec()?, self.second.to_spec()?);
+        if first.kind == "bjt" && first == second {
+            Some(TransistorSpec::new("darlington", first.params))
+        } else {
+            None
+        }
+    }
+    
+    fn heat(&mut self, dt: f64, ambient: f64) {
+        self.first.heat(dt, ambient);
+        self.second.heat(dt, ambient);
+    }
+    
+    // The hotter of the two junctions
+    fn junction_temperature(&self) -> Option<f64> {
+        match (self.first.junction_temperature(), self.second.junction_temperature()) {
+            (Some(first), Some(second)) => Some(first.max(second)),
+            (first, second) => first.or(second),
+        }
+    }
+    
+    fn is_shut_down(&self) -> bool {
+        self.first.is_shut_down() || self.second.is_shut_down()
+    }
+    
+    // The stage closest to its limit
+    fn thermal_limit(&self, ambient: f64) -> Option<(f64, f64)> {
+        let headroom = |(temperature, max): (f64, f64)| max - temperature;
+        match (self.first.thermal_limit(ambient), self.second.thermal_limit(ambient)) {
+            (Some(first), Some(second)) => Some(if headroom(second) < headroom(first) { second } else { first }),
+            (first, second) => first.or(second),
+        }
+    }
+    
+    fn is_cut_off(&self) -> bool {
+        self.first.is_cut_off() || self.second.is_cut_off()
+    }
+    
+    fn is_clipping(&self) -> bool {
+        self.first.is_clipping() || self.second.is_clipping()
+    }
+}
+
+impl Transistor for DarlingtonPair {
+    fn base(&mut self, signal: f64) {
+        self.first.base(signal);
+        self.propagate();
+    }
+    
+    fn collector(&mut self, input: f64) {
+        self.first.collector(input);
+        self.second.collector(input);
+        self.propagate();
+    }
+}
+
+/// Decorator adding seeded noise to another transistor's output. A new
+/// noise sample is drawn each time the base is driven, so the output is
+/// stable between inputs and the same seed always gives the same sequence.
+#[derive(Debug)]
+pub struct NoisyTransistor {
+    inner: Box<dyn Transistor>,
+    noise: NoiseSource,
+    current_noise: f64,
+}
+
+impl NoisyTransistor {
+    pub fn new(inner: Box<dyn Transistor>, amplitude: f64, seed: u64) -> Self {
+        NoisyTransistor {
+            inner,
+            noise: NoiseSource::new(amplitude, 1.0, seed),
+            current_noise: 0.0,
+        }
+    }
+}
+
+impl CircuitElement for NoisyTransistor {
+    fn drive(&mut self, signal: f64, supply: f64) {
+        self.base(signal);
+        self.collector(supply);
+    }
+    
+    fn output(&self) -> f64 {
+        self.inner.output() + self.current_noise
+    }
+    
+    fn power_consumption(&self) -> f64 {
+        self.inner.power_consumption()
+    }
+    
+    fn frequency_response(&self, freq_hz: f64) -> f64 {
+        self.inner.frequency_response(freq_hz)
+    }
+    
+    fn heat(&mut self, dt: f64, ambient: f64) {
+        self.inner.heat(dt, ambient);
+    }
+    
+    fn junction_temperature(&self) -> Option<f64> {
+        self.inner.junction_temperature()
+    }
+    
+    fn is_shut_down(&self) -> bool {
+        self.inner.is_shut_down()
+    }
+    
+    fn thermal_limit(&self, ambient: f64) -> Option<(f64, f64)> {
+        self.inner.thermal_limit(ambient)
+    }
+    
+    fn is_cut_off(&self) -> bool {
+        self.inner.is_cut_off()
+    }
+    
+    fn is_clipping(&self) -> bool {
+        self.inner.is_clipping()
+    }
+    
+    fn describe(&self) -> DeviceInfo {
+        let inner = self.inner.describe();
+        DeviceInfo {
+            name: format!("Noisy {}", inner.name),
+            description: format!("{}, plus noise of ±{}", inner.description, self.noise.amplitude),
+        }
+    }
+}
+
+impl Transistor for NoisyTransistor {
+    fn base(&mut self, signal: f64) {
+        self.inner.base(signal);
+        self.current_noise = self.noise.next_sample();
+    }
+    
+    fn collector(&mut self, input: f64) {
+        self.inner.collector(input);
+    }
+}
+
+/// One call seen by a LoggedTransistor
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TransistorCall {
+    Base(f64),
+    Collector(f64),
+    /// The value `output()` returned
+    Output(f64),
+}
+
+/// Decorator recording every base, collector and output call on another
+/// transistor. The log is shared, so it can still be read after the
+/// transistor has been boxed into a circuit.
+#[derive(Debug)]
+pub struct LoggedTransistor {
+    inner: Box<dyn Transistor>,
+    calls: Rc<RefCell<Vec<TransistorCall>>>,
+}
+
+impl LoggedTransistor {
+    pub fn new(inner: Box<dyn Transistor>) -> Self {
+        LoggedTransistor {
+            inner,
+            calls: Rc::new(RefCell::new(Vec::new())),
+        }
+    }
+    
+    /// A handle to the log that stays valid after the transistor is moved
+    pub fn call_log(&self) -> Rc<RefCell<Vec<TransistorCall>>> {
+        Rc::clone(&self.calls)
+    }
+    
+    pub fn calls(&self) -> Vec<TransistorCall> {
+        self.calls.borrow().clone()
+    }
+}
+
+impl CircuitElement for LoggedTransistor {
+    fn drive(&mut self, signal: f64, supply: f64) {
+        self.base(signal);
+        self.collector(supply);
+    }
+    
+    fn output(&self) -> f64 {
+        let output = self.inner.output();
+        self.calls.borrow_mut().push(TransistorCall::Output(output));
+        output
+    }
+    
+    fn power_consumption(&self) -> f64 {
+        self.inner.power_consumption()
+    }
+    
+    fn frequency_response(&self, freq_hz: f64) -> f64 {
+        self.inner.frequency_response(freq_hz)
+    }
+    
+    fn heat(&mut self, dt: f64, ambient: f64) {
+        self.inner.heat(dt, ambient);
+    }
+    
+    fn junction_temperature(&self) -> Option<f64> {
+        self.inner.junction_temperature()
+    }
+    
+    fn is_shut_down(&self) -> bool {
+        self.inner.is_shut_down()
+    }
+    
+    fn thermal_limit(&self, ambient: f64) -> Option<(f64, f64)> {
+        self.inner.thermal_limit(ambient)
+    }
+    
+    fn is_cut_off(&self) -> bool {
+        self.inner.is_cut_off()
+    }
+    
+    fn is_clipping(&self) -> bool {
+        self.inner.is_clipping()
+    }
+    
+    fn describe(&self) -> DeviceInfo {
+        let inner = self.inner.describe();
+        DeviceInfo {
+            name: format!("Logged {}", inner.name),
+            description: format!("{}, with every call recorded", inner.description),
+        }
+    }
+}
+
+impl Transistor for LoggedTransistor {
+    fn base(&mut self, signal: f64) {
+        self.calls.borrow_mut().push(TransistorCall::Base(signal));
+        self.inner.base(signal);
+    }
+    
+    fn collector(&mut self, input: f64) {
+        self.calls.borrow_mut().push(TransistorCall::Collector(input));
+        self.inner.collector(input);
+    }
+}
+
+/// Decorator limiting another transistor's output to a range, like a
+/// protection stage in front of the next part of the circuit
+#[derive(Debug)]
+pub struct ClippedTransistor {
+    inner: Box<dyn Transistor>,
+    min_output: f64,
+    max_output: f64,
+}
+
+impl ClippedTransistor {
+    pub fn new(inner: Box<dyn Transistor>, min_output: f64, max_output: f64) -> Self {
+        ClippedTransistor {
+            inner,
+            min_output,
+            max_output,
+        }
+    }
+}
+
+impl CircuitElement for ClippedTransistor {
+    fn drive(&mut self, signal: f64, supply: f64) {
+        self.base(signal);
+        self.collector(supply);
+    }
+    
+    fn output(&self) -> f64 {
+        self.inner.output().max(self.min_output).min(self.max_output)
+    }
+    
+    fn power_consumption(&self) -> f64 {
+        self.inner.power_consumption()
+    }
+    
+    fn frequency_response(&self, freq_hz: f64) -> f64 {
+        self.inner.frequency_response(freq_hz)
+    }
+    
+    fn heat(&mut self, dt: f64, ambient: f64) {
+        self.inner.heat(dt, ambient);
+    }
+    
+    fn junction_temperature(&self) -> Option<f64> {
+        self.inner.junction_temperature()
+    }
+    
+    fn is_shut_down(&self) -> bool {
+        self.inner.is_shut_down()
+    }
+    
+    fn thermal_limit(&self, ambient: f64) -> Option<(f64, f64)> {
+        self.inner.thermal_limit(ambient)
+    }
+    
+    fn is_cut_off(&self) -> bool {
+        self.inner.is_cut_off()
+    }
+    
+    // Clipping here, or already clipping inside
+    fn is_clipping(&self) -> bool {
+        let output = self.inner.output();
+        output <= self.min_output || output >= self.max_output || self.inner.is_clipping()
+    }
+    
+    fn describe(&self) -> DeviceInfo {
+        let inner = self.inner.describe();
+        DeviceInfo {
+            name: format!("Clipped {}", inner.name),
+            description: format!("{}, clipped to {}..{}", inner.description, self.min_output, self.max_output),
+        }
+    }
+}
+
+impl Transistor for ClippedTransistor {
+    fn base(&mut self, signal: f64) {
+        self.inner.base(signal);
+    }
+    
+    fn collector(&mut self, input: f64) {
+        self.inner.collector(input);
+    }
+}
+
+/// Resistor in series with a load: the output is the voltage divided across
+/// the load. With no load (the default) no current flows and the signal passes through.
+#[derive(Debug)]
+pub struct Resistor {
+    pub resistance: f64,
+    pub load: f64,
+    signal: f64,
+}
+
+impl Resistor {
+    pub fn new(resistance: f64) -> Self {
+        Resistor {
+            resistance,
+            load: f64::INFINITY,
+            signal: 0.0,
+        }
+    }
+    
+    pub fn with_load(mut self, load: f64) -> Self {
+        self.load = load;
+        self
+    }
+    
+    fn current(&self) -> f64 {
+        if self.load.is_infinite() {
+            0.0
+        } else {
+            self.signal / (self.resistance + self.load)
+        }
+    }
+}
+
+impl CircuitElement for Resistor {
+    fn drive(&mut self, signal: f64, _supply: f64) {
+        self.signal = signal;
+    }
+    
+    fn output(&self) -> f64 {
+        self.signal - self.current() * self.resistance
+    }
+    
+    // I²R in the resistor and the load
+    fn power_consumption(&self) -> f64 {
+        let current = self.current();
+        current * current * self.resistance + current * self.output()
+    }
+    
+    fn describe(&self) -> DeviceInfo {
+        DeviceInfo {
+            name: format!("Resistor {} Ω", self.resistance),
+            description: "Passive - Voltage divider against its load".to_string(),
+        }
+    }
+}
+
+/// Capacitor to ground charged through a series resistance: an RC low-pass
+/// stage with time constant τ = RC. Its voltage follows the signal with an
+/// exponential lag in tick simulation and settles to it at steady state.
+#[derive(Debug)]
+pub struct Capacitor {
+    pub capacitance: f64,
+    pub resistance: f64,
+    signal: f64,
+    voltage: f64,
+}
+
+impl Capacitor {
+    pub fn new(capacitance: f64, resistance: f64) -> Self {
+        Capacitor {
+            capacitance,
+            resistance,
+            signal: 0.0,
+            voltage: 0.0,
+        }
+    }
+    
+    /// τ = RC, in seconds
+    pub fn time_constant(&self) -> f64 {
+        self.resistance * self.capacitance
+    }
+    
+    /// Frequency where the response is 3 dB down: 1 / (2πRC)
+    pub fn cutoff_frequency(&self) -> f64 {
+        1.0 / (2.0 * PI * self.time_constant())
+    }
+}
+
+impl CircuitElement for Capacitor {
+    fn drive(&mut self, signal: f64, _supply: f64) {
+        self.signal = signal;
+    }
+    
+    fn tick(&mut self, dt: f64) {
+        if dt <= 0.0 {
+            return;
+        }
+        // Exact solution of dV/dt = (signal - V) / τ over one step; an
+        // infinite step (or τ = 0) lands on the signal
+        let settled = 1.0 - (-dt / self.time_constant()).exp();
+        self.voltage += (self.signal - self.voltage) * settled;
+    }
+    
+    fn output(&self) -> f64 {
+        self.voltage
+    }
+    
+    // Charging current through the series resistance
+    fn power_consumption(&self) -> f64 {
+        (self.signal - self.voltage).powi(2) / self.resistance
+    }
+    
+    fn describe(&self) -> DeviceInfo {
+        DeviceInfo {
+            name: format!("Capacitor {} F", self.capacitance),
+            description: format!("Passive - RC low-pass, τ = {} s", self.time_constant()),
+        }
+    }
+    
+    fn frequency_response(&self, freq_hz: f64) -> f64 {
+        1.0 / (1.0 + (2.0 * PI * freq_hz * self.time_constant()).powi(2)).sqrt()
+    }
+}
+
+/// Diode with a fixed forward voltage drop: it conducts only when the signal
+/// exceeds the drop, so it half-wave rectifies
+#[derive(Debug)]
+pub struct Diode {
+    pub forward_voltage: f64,
+    signal: f64,
+}
+
+impl Diode {
+    pub const SILICON_FORWARD_VOLTAGE: f64 = 0.7;
+    
+    pub fn new() -> Self {
+        Diode::default()
+    }
+    
+    pub fn with_forward_voltage(forward_voltage: f64) -> Self {
+        Diode {
+            forward_voltage,
+            signal: 0.0,
+        }
+    }
+}
+
+impl Default for Diode {
+    fn default() -> Self {
+        Diode::with_forward_voltage(Self::SILICON_FORWARD_VOLTAGE)
+    }
+}
+
+impl CircuitElement for Diode {
+    fn drive(&mut self, signal: f64, _supply: f64) {
+        self.signal = signal;
+    }
+    
+    fn output(&self) -> f64 {
+        (self.signal - self.forward_voltage).max(0.0)
+    }
+    
+    // The forward drop times the conducted signal, taken as the current into a unit load
+    fn power_consumption(&self) -> f64 {
+        self.forward_voltage * self.output()
+    }
+    
+    fn describe(&self) -> DeviceInfo {
+        DeviceInfo::new("Diode", "Passive - Conducts above its forward voltage")
+    }
+    
+    fn is_cut_off(&self) -> bool {
+        self.signal <= self.forward_voltage
+    }
+}
+
+/// One element of a circuit's topology. Nodes nest, so a multi-stage
+/// amplifier is a series of stages, each of which can be a single element
+/// or a parallel bank.
+#[derive(Debug)]
+pub enum CircuitNode {
+    /// A single element: a transistor, resistor, capacitor, diode, ...
+    Device(Box<dyn CircuitElement>),
+    /// Each node's output drives the next node's input; all share the supply
+    Series(Vec<CircuitNode>),
+    /// Every branch sees the same signal and their outputs add up
+    Parallel(Vec<CircuitNode>),
+    /// A labelled sub-circuit, so its output can be read back by name
+    Stage { name: String, node: Box<CircuitNode> },
+}
+
+impl CircuitNode {
+    pub fn device(element: Box<dyn CircuitElement>) -> Self {
+        CircuitNode::Device(element)
+    }
+    
+    pub fn series(nodes: Vec<CircuitNode>) -> Self {
+        CircuitNode::Series(nodes)
+    }
+    
+    pub fn parallel(nodes: Vec<CircuitNode>) -> Self {
+        CircuitNode::Parallel(nodes)
+    }
+    
+    pub fn stage(name: &str, node: CircuitNode) -> Self {
+        CircuitNode::Stage {
+            name: name.to_string(),
+            node: Box::new(node),
+        }
+    }
+    
+    /// Drives the node with a signal and supply and returns its steady-state
+    /// output. An empty series or parallel node outputs nothing.
+    pub fn amplify(&mut self, signal: f64, input: f64) -> f64 {
+        self.evaluate(signal, input, 0.0, f64::INFINITY)
+    }
+    
+    /// Like `amplify`, for a signal at `freq_hz`: each device's output is scaled
+    /// by its frequency response before it drives the next stage
+    pub fn amplify_at(&mut self, signal: f64, input: f64, freq_hz: f64) -> f64 {
+        self.evaluate(signal, input, freq_hz, f64::INFINITY)
+    }
+    
+    /// Like `amplify`, but elements with memory (capacitors) only advance by
+    /// `dt` seconds instead of settling
+    pub fn step(&mut self, signal: f64, input: f64, dt: f64) -> f64 {
+        self.evaluate(signal, input, 0.0, dt)
+    }
+    
+    fn evaluate(&mut self, signal: f64, input: f64, freq_hz: f64, dt: f64) -> f64 {
+        match self {
+            CircuitNode::Device(element) => {
+                element.drive(signal, input);
+                element.tick(dt);
+                element.output() * element.frequency_response(freq_hz)
+            }
+            CircuitNode::Series(nodes) => {
+                let mut drive = signal;
+                let mut output = 0.0;
+                for node in nodes {
+                    output = node.evaluate(drive, input, freq_hz, dt);
+                    drive = output;
+                }
+                output
+            }
+            CircuitNode::Parallel(nodes) => nodes.iter_mut().map(|node| node.evaluate(signal, input, freq_hz, dt)).sum(),
+            CircuitNode::Stage { node, .. } => node.evaluate(signal, input, freq_hz, dt),
+        }
+    }
+    
+    /// Output at the last operating point, without driving the node again
+    pub fn output(&self) -> f64 {
+        match self {
+            CircuitNode::Device(element) => element.output(),
+            CircuitNode::Series(nodes) => nodes.last().map_or(0.0, |node| node.output()),
+            CircuitNode::Parallel(nodes) => nodes.iter().map(|node| node.output()).sum(),
+            CircuitNode::Stage { node, .. } => node.output(),
+        }
+    }
+    
+    pub fn power_consumption(&self) -> f64 {
+        match self {
+            CircuitNode::Device(element) => element.power_consumption(),
+            CircuitNode::Series(nodes) | CircuitNode::Parallel(nodes) => nodes.iter().map(|node| node.power_consumption()).sum(),
+            CircuitNode::Stage { node, .. } => node.power_consumption(),
+        }
+    }
+    
+    /// The devices under this node, in traversal order
+    pub fn inventory(&self) -> Vec<DeviceInfo> {
+        match self {
+            CircuitNode::Device(element) => vec![element.describe()],
+            CircuitNode::Series(nodes) | CircuitNode::Parallel(nodes) => nodes.iter().flat_map(|node| node.inventory()).collect(),
+            CircuitNode::Stage { node, .. } => node.inventory(),
+        }
+    }
+    
+    pub fn element_count(&self) -> usize {
+        match self {
+            CircuitNode::Device(_) => 1,
+            CircuitNode::Series(nodes) | CircuitNode::Parallel(nodes) => nodes.iter().map(|node| node.element_count()).sum(),
+            CircuitNode::Stage { node, .. } => node.element_count(),
+        }
+    }
+    
+    /// The elements under this node, in traversal order
+    fn elements(&self) -> Vec<&dyn CircuitElement> {
+        match self {
+            CircuitNode::Device(element) => vec![element.as_ref()],
+            CircuitNode::Series(nodes) | CircuitNode::Parallel(nodes) => nodes.iter().flat_map(|node| node.elements()).collect(),
+            CircuitNode::Stage { node, .. } => node.elements(),
+        }
+    }
+    
+    fn elements_mut(&mut self) -> Vec<&mut Box<dyn CircuitElement>> {
+        match self {
+            CircuitNode::Device(element) => vec![element],
+            CircuitNode::Series(nodes) | CircuitNode::Parallel(nodes) => nodes.iter_mut().flat_map(|node| node.elements_mut()).collect(),
+            CircuitNode::Stage { node, .. } => node.elements_mut(),
+        }
+    }
+    
+    /// Each named stage's output at the last operating point, outermost first
+    pub fn stage_outputs(&self) -> Vec<(&str, f64)> {
+        match self {
+            CircuitNode::Device(_) => Vec::new(),
+            CircuitNode::Series(nodes) | CircuitNode::Parallel(nodes) => nodes.iter().flat_map(|node| node.stage_outputs()).collect(),
+            CircuitNode::Stage { name, node } => {
+                let mut outputs = vec![(name.as_str(), node.output())];
+                outputs.extend(node.stage_outputs());
+                outputs
+            }
+        }
+    }
+    
+    /// Checks this node at the last operating point, given the signal that
+    /// drove it. `stages` holds the named stages around the node and
+    /// `element` the number of elements visited so far.
+    fn diagnose(
+        &self,
+        drive: f64,
+        ambient: f64,
+        stages: &mut Vec<String>,
+        element: &mut usize,
+        diagnostics: &mut Vec<CircuitDiagnostic>,
+    ) {
+        match self {
+            CircuitNode::Device(device) => {
+                *element += 1;
+                let (element, name, stage) = (*element, device.describe().name, stage_path(stages));
+                if let Some((temperature, max_temperature)) = device.thermal_limit(ambient) {
+                    if temperature > max_temperature {
+                        diagnostics.push(CircuitDiagnostic::ThermalLimit {
+                            element,
+                            name: name.clone(),
+                            stage: stage.clone(),
+                            temperature,
+                            max_temperature,
+                        });
+                    }
+                }
+                if device.is_cut_off() {
+                    diagnostics.push(CircuitDiagnostic::BelowThreshold { element, name, stage, drive });
+                } else if drive != 0.0 && device.output() == 0.0 {
+                    diagnostics.push(CircuitDiagnostic::ZeroGain { element, name, stage, drive });
+                }
+            }
+            CircuitNode::Series(nodes) | CircuitNode::Parallel(nodes) if nodes.is_empty() => {
+                diagnostics.push(CircuitDiagnostic::EmptyNode { stage: stage_path(stages) });
+            }
+            CircuitNode::Series(nodes) => {
+                // Each node is driven by the output of the one before it
+                let mut drive = drive;
+                for node in nodes {
+                    node.diagnose(drive, ambient, stages, element, diagnostics);
+                    drive = node.output();
+                }
+            }
+            CircuitNode::Parallel(nodes) => {
+                for node in nodes {
+                    node.diagnose(drive, ambient, stages, element, diagnostics);
+                }
+            }
+            CircuitNode::Stage { name, node } => {
+                stages.push(name.clone());
+                node.diagnose(drive, ambient, stages, element, diagnostics);
+                stages.pop();
+            }
+        }
+    }
+}
+
+/// Circuit gain at one frequency
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FrequencyPoint {
+    pub frequency_hz: f64,
+    /// Output divided by input signal
+    pub gain: f64,
+}
+
+impl FrequencyPoint {
+    pub fn gain_db(&self) -> f64 {
+        20.0 * self.gain.abs().log10()
+    }
+}
+
+/// Result of `Circuit::ac_sweep`. Displays as a Bode-style table.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AcSweep {
+    pub points: Vec<FrequencyPoint>,
+}
+
+impl AcSweep {
+    /// The first swept frequency where the gain is 3 dB or more below the
+    /// lowest-frequency point, if the sweep reaches it
+    pub fn cutoff_frequency(&self) -> Option<f64> {
+        let reference = self.points.first()?.gain_db();
+        self.points.iter().find(|p| p.gain_db() <= reference - 3.0).map(|p| p.frequency_hz)
+    }
+    
+    /// The sweep as CSV, for plotting elsewhere
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("frequency_hz,gain,gain_db\n");
+        for p in &self.points {
+            csv.push_str(&format!("{},{},{}\n", p.frequency_hz, p.gain, p.gain_db()));
+        }
+        csv
+    }
+}
+
+impl fmt::Display for AcSweep {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{:>14}  {:>10}  {:>9}", "Frequency (Hz)", "Gain", "Gain (dB)")?;
+        for p in &self.points {
+            writeln!(f, "{:>14.0}  {:>10.4}  {:>9.2}", p.frequency_hz, p.gain, p.gain_db())?;
+        }
+        Ok(())
+    }
+}
+
+/// Junction temperatures of one element over a thermal simulation
+#[derive(Debug, Clone, PartialEq)]
+pub struct JunctionReport {
+    /// Position in the circuit's inventory, from 1
+    pub element: usize,
+    pub name: String,
+    pub peak_temperature: f64,
+    pub final_temperature: f64,
+}
+
+/// Result of `Circuit::simulate` when no junction overheated
+#[derive(Debug, Clone, PartialEq)]
+pub struct ThermalReport {
+    pub duration: f64,
+    pub ambient: f64,
+    pub initial_output: f64,
+    pub final_output: f64,
+    pub junctions: Vec<JunctionReport>,
+}
+
+impl fmt::Display for ThermalReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "{:.1} s at {:.1} °C ambient: output {:.2} -> {:.2}",
+            self.duration, self.ambient, self.initial_output, self.final_output
+        )?;
+        for junction in &self.junctions {
+            writeln!(
+                f,
+                "  {}. {}: peak {:.1} °C, final {:.1} °C",
+                junction.element, junction.name, junction.peak_temperature, junction.final_temperature
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// How serious a `CircuitDiagnostic` is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    /// The circuit runs, but part of it does nothing useful
+    Warning,
+    /// Simulating the circuit would fail or give meaningless results
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Severity::Warning => write!(f, "warning"),
+            Severity::Error => write!(f, "error"),
+        }
+    }
+}
+
+/// A configuration problem found by `Circuit::validate`. Elements are
+/// numbered from 1 in traversal order, as in a `ThermalReport`; `stage` is
+/// the path of named stages around the problem, such as `preamp/driver`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CircuitDiagnostic {
+    /// The circuit has no elements at all
+    EmptyCircuit,
+    /// A series or parallel node has no elements, so it outputs nothing
+    EmptyNode { stage: Option<String> },
+    /// An element driven with a non-zero signal outputs nothing, so
+    /// anything after it in series sees no signal
+    ZeroGain { element: usize, name: String, stage: Option<String>, drive: f64 },
+    /// An element is biased below its conduction threshold
+    BelowThreshold { element: usize, name: String, stage: Option<String>, drive: f64 },
+    /// An element would settle above its maximum temperature and shut down
+    ThermalLimit { element: usize, name: String, stage: Option<String>, temperature: f64, max_temperature: f64 },
+}
+
+impl CircuitDiagnostic {
+    pub fn severity(&self) -> Severity {
+        match self {
+            CircuitDiagnostic::EmptyCircuit | CircuitDiagnostic::ThermalLimit { .. } => Severity::Error,
+            CircuitDiagnostic::EmptyNode { .. } | CircuitDiagnostic::ZeroGain { .. } | CircuitDiagnostic::BelowThreshold { .. } => {
+                Severity::Warning
+            }
+        }
+    }
+}
+
+// "preamp/driver", or None outside any named stage
+fn stage_path(stages: &[String]) -> Option<String> {
+    if stages.is_empty() {
+        None
+    } else {
+        Some(stages.join("/"))
+    }
+}
+
+// "BJT (element 2, stage preamp)"
+fn element_location(name: &str, element: usize, stage: &Option<String>) -> String {
+    match stage {
+        Some(stage) => format!("{} (element {}, stage {})", name, element, stage),
+        None => format!("{} (element {})", name, element),
+    }
+}
+
+impl fmt::Display for CircuitDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: ", self.severity())?;
+        match self {
+            CircuitDiagnostic::EmptyCircuit => write!(f, "Circuit has no elements"),
+            CircuitDiagnostic::EmptyNode { stage: Some(stage) } => {
+                write!(f, "Empty series or parallel node in stage {} outputs nothing", stage)
+            }
+            CircuitDiagnostic::EmptyNode { stage: None } => write!(f, "Empty series or parallel node outputs nothing"),
+            CircuitDiagnostic::ZeroGain { element, name, stage, drive } => write!(
+                f,
+                "{} outputs nothing when driven with {:.2}",
+                element_location(name, *element, stage),
+                drive
+            ),
+            CircuitDiagnostic::BelowThreshold { element, name, stage, drive } => write!(
+                f,
+                "{} is biased below its threshold by a drive of {:.2} and passes no signal",
+                element_location(name, *element, stage),
+                drive
+            ),
+            CircuitDiagnostic::ThermalLimit { element, name, stage, temperature, max_temperature } => write!(
+                f,
+                "{} would settle at {:.1} °C, above its {:.1} °C limit, and shut down",
+                element_location(name, *element, stage),
+                temperature,
+                max_temperature
+            ),
+        }
+    }
+}
+
+/// Hooks a circuit calls after every drive. Each callback has an empty
+/// default, so an observer overrides only the events it cares about, and
+/// new observers plug in without any change to Circuit.
+pub trait CircuitObserver: Debug {
+    /// A named stage produced `output`; stages are reported outermost first
+    fn on_stage_output(&mut self, _stage: &str, _output: f64) {}
+    /// Element `element` (numbered from 1 in circuit order), described as
+    /// `name`, is held at a saturation limit with output `output`
+    fn on_clipping(&mut self, _element: usize, _name: &str, _output: f64) {}
+    /// The circuit draws `power` watts, above the threshold set with
+    /// `Circuit::set_power_threshold`
+    fn on_power_threshold_exceeded(&mut self, _power: f64, _threshold: f64) {}
+}
+
+/// Observer printing every event, each line prefixed with its label
+#[derive(Debug)]
+pub struct LoggingObserver {
+    label: String,
+}
+
+impl LoggingObserver {
+    pub fn new(label: &str) -> Self {
+        LoggingObserver { label: label.to_string() }
+    }
+}
+
+impl CircuitObserver for LoggingObserver {
+    fn on_stage_output(&mut self, stage: &str, output: f64) {
+        println!("[{}] stage '{}' output {:.2}", self.label, stage, output);
+    }
+    
+    fn on_clipping(&mut self, element: usize, name: &str, output: f64) {
+        println!("[{}] {} (element {}) clipping at {:.2}", self.label, name, element, output);
+    }
+    
+    fn on_power_threshold_exceeded(&mut self, power: f64, threshold: f64) {
+        println!("[{}] power {:.2} W above the {:.2} W threshold", self.label, power, threshold);
+    }
+}
+
+/// Totals collected by a MetricsObserver
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CircuitMetrics {
+    /// Largest output magnitude seen from each named stage
+    pub stage_peaks: BTreeMap<String, f64>,
+    pub clipping_events: usize,
+    /// Elements seen clipping at least once, numbered from 1
+    pub clipped_elements: BTreeSet<usize>,
+    pub power_threshold_events: usize,
+    /// Highest power reported above the threshold; zero if it never was
+    pub peak_power: f64,
+}
+
+/// Observer counting events into CircuitMetrics. The metrics are shared,
+/// so they can still be read after the observer has been boxed into a circuit.
+#[derive(Debug, Default)]
+pub struct MetricsObserver {
+    metrics: Rc<RefCell<CircuitMetrics>>,
+}
+
+impl MetricsObserver {
+    pub fn new() -> Self {
+        MetricsObserver::default()
+    }
+    
+    /// A handle to the metrics that stays valid after the observer is moved
+    pub fn metrics(&self) -> Rc<RefCell<CircuitMetrics>> {
+        Rc::clone(&self.metrics)
+    }
+}
+
+impl CircuitObserver for MetricsObserver {
+    fn on_stage_output(&mut self, stage: &str, output: f64) {
+        let mut metrics = self.metrics.borrow_mut();
+        let peak = metrics.stage_peaks.entry(stage.to_string()).or_insert(0.0);
+        *peak = peak.max(output.abs());
+    }
+    
+    fn on_clipping(&mut self, element: usize, _name: &str, _output: f64) {
+        let mut metrics = self.metrics.borrow_mut();
+        metrics.clipping_events += 1;
+        metrics.clipped_elements.insert(element);
+    }
+    
+    fn on_power_threshold_exceeded(&mut self, power: f64, _threshold: f64) {
+        let mut metrics = self.metrics.borrow_mut();
+        metrics.power_threshold_events += 1;
+        metrics.peak_power = metrics.peak_power.max(power);
+    }
+}
+
+/// Circuit of any elements that follows OCP. Its top-level nodes are in
+/// parallel: each one sees the input signal and their outputs add up.
+pub struct Circuit {
+    nodes: Vec<CircuitNode>,
+    /// The signal and supply the circuit was last driven with
+    operating_point: Option<(f64, f64)>,
+    observers: Vec<Box<dyn CircuitObserver>>,
+    /// Power in watts above which observers are notified
+    power_threshold: Option<f64>,
+}
+
+impl Default for Circuit {
+    fn default() -> Self {
+        Circuit::new()
+    }
+}
+
+impl Circuit {
+    /// Step of the thermal simulation, in seconds
+    pub const THERMAL_TIME_STEP: f64 = 0.01;
+    
+    pub fn new() -> Self {
+        Circuit::with_nodes(Vec::new())
+    }
+    
+    fn with_nodes(nodes: Vec<CircuitNode>) -> Self {
+        Circuit {
+            nodes,
+            operating_point: None,
+            observers: Vec::new(),
+            power_threshold: None,
+        }
+    }
+    
+    /// This method doesn't need to change when new element types are added
+    pub fn add_element(&mut self, element: Box<dyn CircuitElement>) {
+        self.nodes.push(CircuitNode::Device(element));
+    }
+    
+    pub fn add_transistor(&mut self, transistor: Box<dyn Transistor>) {
+        self.add_element(transistor);
+    }
+    
+    /// Adds a sub-circuit, such as a series of stages
+    pub fn add_node(&mut self, node: CircuitNode) {
+        self.nodes.push(node);
+    }
+    
+    /// Registers an observer. Observers are called, in the order they were
+    /// added, after every `amplify_signal` and `step` - including the drives
+    /// made by `simulate`, `validate` and `ac_sweep`.
+    pub fn add_observer(&mut self, observer: Box<dyn CircuitObserver>) {
+        self.observers.push(observer);
+    }
+    
+    /// Power in watts above which observers get `on_power_threshold_exceeded`
+    pub fn set_power_threshold(&mut self, watts: f64) {
+        self.power_threshold = Some(watts);
+    }
+    
+    /// This method works with any CircuitElement implementation and any topology.
+    /// Elements with memory settle to their steady state.
+    pub fn amplify_signal(&mut self, signal: f64, input: f64) -> f64 {
+        self.operating_point = Some((signal, input));
+        let mut total_output = 0.0;
+        
+        for node in &mut self.nodes {
+            total_output += node.amplify(signal, input);
+        }
+        
+        self.notify_observers();
+        total_output
+    }
+    
+    // Reports the state after a drive: stage outputs, clipping elements and
+    // the power threshold, in that order
+    fn notify_observers(&mut self) {
+        if self.observers.is_empty() {
+            return;
+        }
+        let stages: Vec<(&str, f64)> = self.nodes.iter().flat_map(|node| node.stage_outputs()).collect();
+        let clipping: Vec<(usize, String, f64)> = self
+            .nodes
+            .iter()
+            .flat_map(|node| node.elements())
+            .enumerate()
+            .filter(|(_, element)| element.is_clipping())
+            .map(|(index, element)| (index + 1, element.describe().name, element.output()))
+            .collect();
+        let power: f64 = self.nodes.iter().map(|node| node.power_consumption()).sum();
+        let exceeded = self.power_threshold.filter(|threshold| power > *threshold);
+        
+        for observer in &mut self.observers {
+            for (stage, output) in &stages {
+                observer.on_stage_output(stage, *output);
+            }
+            for (element, name, output) in &clipping {
+                observer.on_clipping(*element, name, *output);
+            }
+            if let Some(threshold) = exceeded {
+                observer.on_power_threshold_exceeded(power, threshold);
+            }
+        }
+    }
+    
+    /// Builds the circuit a config describes, using the built-in transistor types;
+    /// use `TransistorRegistry::circuit_from` when plugins are registered
+    pub fn from_config(config: &CircuitConfig) -> Result<Self, String> {
+        TransistorRegistry::with_builtin_types().circuit_from(config)
+    }
+    
+    /// Writes the circuit as a minimal SPICE-style netlist, one `Q` line per
+    /// transistor; see `TransistorRegistry::circuit_from_netlist` for the format.
+    /// Fails for elements without a registry type and for empty nodes.
+    pub fn to_netlist(&self) -> Result<String, String> {
+        let mut writer = NetlistWriter::default();
+        for node in &self.nodes {
+            writer.write(node, NET_IN, NET_OUT, &mut Vec::new())?;
+        }
+        let mut netlist = String::from("* OCP amplifier circuit\n");
+        for line in writer.lines {
+            netlist.push_str(&line);
+            netlist.push('\n');
+        }
+        netlist.push_str(".end\n");
+        Ok(netlist)
+    }
+    
+    /// Rebuilds a circuit from a netlist, using the built-in transistor types;
+    /// use `TransistorRegistry::circuit_from_netlist` when plugins are registered
+    pub fn from_netlist(text: &str) -> Result<Self, String> {
+        TransistorRegistry::with_builtin_types().circuit_from_netlist(text)
+    }
+    
+    fn with_transistors(transistors: Vec<Box<dyn Transistor>>) -> Self {
+        Circuit::with_nodes(transistors.into_iter().map(|transistor| CircuitNode::Device(transistor)).collect())
+    }
+    
+    pub fn element_count(&self) -> usize {
+        self.nodes.iter().map(|node| node.element_count()).sum()
+    }
+    
+    /// Power drawn by every transistor at its last operating point.
+    /// Each type computes its own, so this never changes for new types.
+    pub fn total_power(&self) -> f64 {
+        self.nodes.iter().map(|node| node.power_consumption()).sum()
+    }
+    
+    /// The devices in the circuit, in order
+    pub fn inventory(&self) -> Vec<DeviceInfo> {
+        self.nodes.iter().flat_map(|node| node.inventory()).collect()
+    }
+    
+    /// Each named stage's output at the last operating point
+    pub fn stage_outputs(&self) -> Vec<(&str, f64)> {
+        self.nodes.iter().flat_map(|node| node.stage_outputs()).collect()
+    }
+    
+    /// Feeds `samples` samples from a source into the circuit, one time step
+    /// of 1 / sample rate per sample, and returns the output waveform at the same rate
+    pub fn process_waveform(&mut self, source: &mut dyn SignalSource, samples: usize, input: f64) -> Waveform {
+        let dt = 1.0 / source.sample_rate();
+        Waveform {
+            sample_rate: source.sample_rate(),
+            samples: (0..samples).map(|_| self.step(source.next_sample(), input, dt)).collect(),
+        }
+    }
+    
+    /// Advances the whole circuit by one time step of `dt` seconds and returns its output
+    pub fn step(&mut self, signal: f64, input: f64, dt: f64) -> f64 {
+        self.operating_point = Some((signal, input));
+        let output = self.nodes.iter_mut().map(|node| node.step(signal, input, dt)).sum();
+        self.notify_observers();
+        output
+    }
+    
+    /// Runs the thermal model for `duration` seconds at `ambient` °C, holding
+    /// the circuit at the operating point it was last driven with. Junctions
+    /// heat from their own dissipation, starting from their current temperature,
+    /// and the circuit is re-driven after every step so gains track the heat.
+    /// A junction passing its maximum temperature is reported as thermal runaway.
+    pub fn simulate(&mut self, duration: f64, ambient: f64) -> Result<ThermalReport, String> {
+        let (signal, input) = self
+            .operating_point
+            .ok_or("Drive the circuit with amplify_signal before simulating it")?;
+        if duration.is_nan() || duration <= 0.0 {
+            return Err(format!("Simulation duration must be positive, got {}", duration));
+        }
+        
+        let initial_output = self.amplify_signal(signal, input);
+        let mut junctions: Vec<JunctionReport> = self
+            .elements()
+            .into_iter()
+            .enumerate()
+            .filter_map(|(index, element)| {
+                element.junction_temperature().map(|temperature| JunctionReport {
+                    element: index + 1,
+                    name: element.describe().name,
+                    peak_temperature: temperature,
+                    final_temperature: temperature,
+                })
+            })
+            .collect();
+        
+        let steps = (duration / Self::THERMAL_TIME_STEP).ceil() as usize;
+        let dt = duration / steps as f64;
+        for step in 1..=steps {
+            for element in self.elements_mut() {
+                element.heat(dt, ambient);
+            }
+            let output = self.amplify_signal(signal, input);
+            
+            let elements = self.elements();
+            for junction in &mut junctions {
+                let element = elements[junction.element - 1];
+                let temperature = element.junction_temperature().unwrap_or(junction.final_temperature);
+                junction.final_temperature = temperature;
+                junction.peak_temperature = junction.peak_temperature.max(temperature);
+                if element.is_shut_down() {
+                    return Err(format!(
+                        "Thermal runaway: {} (element {}) reached {:.1} °C at t = {:.2} s and shut down; output fell from {:.2} to {:.2}",
+                        junction.name,
+                        junction.element,
+                        temperature,
+                        step as f64 * dt,
+                        initial_output,
+                        output
+                    ));
+                }
+            }
+        }
+        
+        Ok(ThermalReport {
+            duration,
+            ambient,
+            initial_output,
+            final_output: self.amplify_signal(signal, input),
+            junctions,
+        })
+    }
+    
+    /// Checks for configuration problems before a simulation: drives the
+    /// circuit at `signal` and `input` (as `amplify_signal` does) and reports,
+    /// in circuit order, empty circuits and nodes, elements that output
+    /// nothing, elements biased below their threshold, and junctions
+    /// that would settle above their limit at `ambient` °C. Junction
+    /// temperatures are not advanced. A healthy circuit gives an empty list.
+    pub fn validate(&mut self, signal: f64, input: f64, ambient: f64) -> Vec<CircuitDiagnostic> {
+        if self.element_count() == 0 {
+            return vec![CircuitDiagnostic::EmptyCircuit];
+        }
+        
+        self.amplify_signal(signal, input);
+        let mut diagnostics = Vec::new();
+        let mut element = 0;
+        for node in &self.nodes {
+            node.diagnose(signal, ambient, &mut Vec::new(), &mut element, &mut diagnostics);
+        }
+        diagnostics
+    }
+    
+    fn elements(&self) -> Vec<&dyn CircuitElement> {
+        self.nodes.iter().flat_map(|node| node.elements()).collect()
+    }
+    
+    fn elements_mut(&mut self) -> Vec<&mut Box<dyn CircuitElement>> {
+        self.nodes.iter_mut().flat_map(|node| node.elements_mut()).collect()
+    }
+    
+    /// Gain (output / signal) at `points` frequencies spaced logarithmically
+    /// across `range`, as on a Bode plot. The circuit is left at the DC operating point.
+    pub fn ac_sweep(&mut self, signal: f64, input: f64, range: RangeInclusive<f64>, points: usize) -> Result<AcSweep, String> {
+        let (start, end) = (*range.start(), *range.end());
+        if !(start > 0.0 && start < end) {
+            return Err(format!("Sweep range must be positive and increasing, got {}..={}", start, end));
+        }
+        if points < 2 {
+            return Err(format!("A sweep needs at least 2 points, got {}", points));
+        }
+        if signal == 0.0 {
+            return Err("Cannot measure gain with a zero input signal".to_string());
+        }
+        
+        let step = (end / start).ln() / (points - 1) as f64;
+        let mut sweep = Vec::with_capacity(points);
+        for i in 0..points {
+            let frequency_hz = start * (step * i as f64).exp();
+            let output: f64 = self.nodes.iter_mut().map(|node| node.amplify_at(signal, input, frequency_hz)).sum();
+            sweep.push(FrequencyPoint { frequency_hz, gain: output / signal });
+        }
+        self.amplify_signal(signal, input);
+        Ok(AcSweep { points: sweep })
+    }
+}
+
+/// Amplifier of a single transistor type, using static dispatch. Each call
+/// to `amplify_signal` is monomorphized for `T` and can be inlined, at the
+/// cost of every transistor having the same type; `Circuit` is the
+/// heterogeneous, dynamically dispatched counterpart.
+pub struct StaticAmplifier<T: Transistor> {
+    transistors: Vec<T>,
+}
+
+impl<T: Transistor> StaticAmplifier<T> {
+    pub fn new() -> Self {
+        StaticAmplifier {
+            transistors: Vec::new(),
+        }
+    }
+    
+    pub fn add_transistor(&mut self, transistor: T) {
+        self.transistors.push(transistor);
+    }
+    
+    /// Same result as `Circuit::amplify_signal` with these transistors in parallel
+    pub fn amplify_signal(&mut self, signal: f64, input: f64) -> f64 {
+        let mut total_output = 0.0;
+        
+        for transistor in &mut self.transistors {
+            transistor.drive(signal, input);
+            transistor.tick(f64::INFINITY);
+            total_output += transistor.output();
+        }
+        
+        total_output
+    }
+}
+
+impl<T: Transistor> Default for StaticAmplifier<T> {
+    fn default() -> Self {
+        StaticAmplifier::new()
+    }
+}
+
+// Writes out `AnyTransistor` and its trait impls: every method becomes a
+// `match` that forwards to the wrapped transistor, as the enum_dispatch crate
+// would generate. Adding a built-in type is one line in the invocation below.
+macro_rules! any_transistor {
+    ($($variant:ident($ty:ty)),+ $(,)?) => {
+        /// Closed set of the built-in transistor types, dispatched with a `match`
+        /// instead of a virtual call, plus `Dyn` for any other transistor. A
+        /// `StaticAmplifier<AnyTransistor>` mixes types like a `Circuit` while
+        /// the built-in ones can still be inlined.
+        #[derive(Debug)]
+        pub enum AnyTransistor {
+            $($variant($ty),)+
+            /// Any other transistor (plugins, decorators), dispatched dynamically
+            Dyn(Box<dyn Transistor>),
+        }
+        
+        macro_rules! dispatch {
+            ($self_:expr, $t:ident => $call:expr) => {
+                match $self_ {
+                    $(AnyTransistor::$variant($t) => $call,)+
+                    AnyTransistor::Dyn($t) => $call,
+                }
+            };
+        }
+        
+        $(impl From<$ty> for AnyTransistor {
+            fn from(transistor: $ty) -> Self {
+                AnyTransistor::$variant(transistor)
+            }
+        })+
+    };
+}
+
+any_transistor! {
+    Bjt(BJTTransistor),
+    Fet(FETTransistor),
+    Mosfet(MOSFETTransistor),
+    Jfet(JFETTransistor),
+    Igbt(IGBTTransistor),
+    Darlington(DarlingtonPair),
+}
+
+impl AnyTransistor {
+    /// True for the `Dyn` fallback, which pays for a virtual call
+    pub fn is_dynamic(&self) -> bool {
+        matches!(self, AnyTransistor::Dyn(_))
+    }
+    
+    /// Converts back to a trait object, unwrapping `Dyn` instead of boxing it again
+    pub fn into_boxed(self) -> Box<dyn Transistor> {
+        match self {
+            AnyTransistor::Dyn(transistor) => transistor,
+            other => Box::new(other),
+        }
+    }
+}
+
+/// Trait objects (from the registry, decorators or plugins) become `Dyn`;
+/// there is no downcasting back to a built-in variant
+impl From<Box<dyn Transistor>> for AnyTransistor {
+    fn from(transistor: Box<dyn Transistor>) -> Self {
+        AnyTransistor::Dyn(transistor)
+    }
+}
+
+impl CircuitElement for AnyTransistor {
+    fn drive(&mut self, signal: f64, supply: f64) {
+        dispatch!(self, t => t.drive(signal, supply))
+    }
+    
+    fn tick(&mut self, dt: f64) {
+        dispatch!(self, t => t.tick(dt))
+    }
+    
+    fn output(&self) -> f64 {
+        dispatch!(self, t => t.output())
+    }
+    
+    fn power_consumption(&self) -> f64 {
+        dispatch!(self, t => t.power_consumption())
+    }
+    
+    fn describe(&self) -> DeviceInfo {
+        dispatch!(self, t => t.describe())
+    }
+    
+    fn frequency_response(&self, freq_hz: f64) -> f64 {
+        dispatch!(self, t => t.frequency_response(freq_hz))
+    }
+    
+    fn to_spec(&self) -> Option<TransistorSpec> {
+        dispatch!(self, t => t.to_spec())
+    }
+    
+    fn heat(&mut self, dt: f64, ambient: f64) {
+        dispatch!(self, t => t.heat(dt, ambient))
+    }
+    
+    fn junction_temperature(&self) -> Option<f64> {
+        dispatch!(self, t => t.junction_temperature())
+    }
+    
+    fn is_shut_down(&self) -> bool {
+        dispatch!(self, t => t.is_shut_down())
+    }
+    
+    fn thermal_limit(&self, ambient: f64) -> Option<(f64, f64)> {
+        dispatch!(self, t => t.thermal_limit(ambient))
+    }
+    
+    fn is_cut_off(&self) -> bool {
+        dispatch!(self, t => t.is_cut_off())
+    }
+    
+    fn is_clipping(&self) -> bool {
+        dispatch!(self, t => t.is_clipping())
+    }
+}
+
+impl Transistor for AnyTransistor {
+    fn base(&mut self, signal: f64) {
+        dispatch!(self, t => t.base(signal))
+    }
+    
+    fn collector(&mut self, input: f64) {
+        dispatch!(self, t => t.collector(input))
+    }
+}
+
+/// A sampled input signal. Sources produce one sample at a time at a fixed
+/// sample rate, so the circuit never needs to know what kind of waveform it is fed.
+pub trait SignalSource: Debug {
+    /// Samples per second
+    fn sample_rate(&self) -> f64;
+    /// The next sample
+    fn next_sample(&mut self) -> f64;
+}
+
+/// `offset + amplitude * sin(2π f t + phase)`
+#[derive(Debug)]
+pub struct SineWave {
+    pub amplitude: f64,
+    pub frequency_hz: f64,
+    /// DC bias added to every sample
+    pub offset: f64,
+    /// Phase at t = 0, in radians
+    pub phase: f64,
+    sample_rate: f64,
+    index: u64,
+}
+
+impl SineWave {
+    pub fn new(amplitude: f64, frequency_hz: f64, sample_rate: f64) -> Self {
+        SineWave {
+            amplitude,
+            frequency_hz,
+            offset: 0.0,
+            phase: 0.0,
+            sample_rate,
+            index: 0,
+        }
+    }
+    
+    pub fn with_offset(mut self, offset: f64) -> Self {
+        self.offset = offset;
+        self
+    }
+}
+
+impl SignalSource for SineWave {
+    fn sample_rate(&self) -> f64 {
+        self.sample_rate
+    }
+    
+    fn next_sample(&mut self) -> f64 {
+        let t = self.index as f64 / self.sample_rate;
+        self.index += 1;
+        self.offset + self.amplitude * (2.0 * PI * self.frequency_hz * t + self.phase).sin()
+    }
+}
+
+/// Alternates between `offset + amplitude` and `offset - amplitude`; the high
+/// part of each period is `duty_cycle` (0.5 for a symmetric square wave)
+#[derive(Debug)]
+pub struct SquareWave {
+    pub amplitude: f64,
+    pub frequency_hz: f64,
+    pub offset: f64,
+    pub duty_cycle: f64,
+    sample_rate: f64,
+    index: u64,
+}
+
+impl SquareWave {
+    pub fn new(amplitude: f64, frequency_hz: f64, sample_rate: f64) -> Self {
+        SquareWave {
+            amplitude,
+            frequency_hz,
+            offset: 0.0,
+            duty_cycle: 0.5,
+            sample_rate,
+            index: 0,
+        }
+    }
+    
+    pub fn with_offset(mut self, offset: f64) -> Self {
+        self.offset = offset;
+        self
+    }
+}
+
+impl SignalSource for SquareWave {
+    fn sample_rate(&self) -> f64 {
+        self.sample_rate
+    }
+    
+    fn next_sample(&mut self) -> f64 {
+        let t = self.index as f64 / self.sample_rate;
+        self.index += 1;
+        let position = (self.frequency_hz * t).fract();
+        if position < self.duty_cycle {
+            self.offset + self.amplitude
+        } else {
+            self.offset - self.amplitude
+        }
+    }
+}
+
+/// Uniform white noise in `offset ± amplitude`. The same seed always gives the
+/// same samples (xorshift64*), so runs are reproducible.
+#[derive(Debug)]
+pub struct NoiseSource {
+    pub amplitude: f64,
+    pub offset: f64,
+    sample_rate: f64,
+    state: u64,
+}
+
+impl NoiseSource {
+    pub fn new(amplitude: f64, sample_rate: f64, seed: u64) -> Self {
+        NoiseSource {
+            amplitude,
+            offset: 0.0,
+            sample_rate,
+            // xorshift never leaves the all-zero state
+            state: if seed == 0 { 0x9E37_79B9_7F4A_7C15 } else { seed },
+        }
+    }
+    
+    pub fn with_offset(mut self, offset: f64) -> Self {
+        self.offset = offset;
+        self
+    }
+}
+
+impl SignalSource for NoiseSource {
+    fn sample_rate(&self) -> f64 {
+        self.sample_rate
+    }
+    
+    fn next_sample(&mut self) -> f64 {
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        let bits = self.state.wrapping_mul(0x2545_F491_4F6C_DD1D) >> 11;
+        let unit = bits as f64 / (1u64 << 53) as f64; // [0, 1)
+        self.offset + self.amplitude * (2.0 * unit - 1.0)
+    }
+}
+
+/// A buffer of samples taken at a fixed rate, with the usual measurements
+#[derive(Debug, Clone, PartialEq)]
+pub struct Waveform {
+    pub sample_rate: f64,
+    pub samples: Vec<f64>,
+}
+
+impl Waveform {
+    /// Takes `samples` samples from a source
+    pub fn from_source(source: &mut dyn SignalSource, samples: usize) -> Self {
+        Waveform {
+            sample_rate: source.sample_rate(),
+            samples: (0..samples).map(|_| source.next_sample()).collect(),
+        }
+    }
+    
+    pub fn mean(&self) -> f64 {
+        if self.samples.is_empty() {
+            return 0.0;
+        }
+        self.samples.iter().sum::<f64>() / self.samples.len() as f64
+    }
+    
+    /// Root mean square, including any DC component
+    pub fn rms(&self) -> f64 {
+        if self.samples.is_empty() {
+            return 0.0;
+        }
+        (self.samples.iter().map(|x| x * x).sum::<f64>() / self.samples.len() as f64).sqrt()
+    }
+    
+    /// Largest absolute sample
+    pub fn peak(&self) -> f64 {
+        self.samples.iter().fold(0.0, |peak: f64, x| peak.max(x.abs()))
+    }
+    
+    /// Amplitude of the component at `frequency_hz`, from a single-bin DFT.
+    /// Exact when the buffer holds a whole number of periods.
+    pub fn amplitude_at(&self, frequency_hz: f64) -> f64 {
+        if self.samples.is_empty() {
+            return 0.0;
+        }
+        let (mut re, mut im) = (0.0, 0.0);
+        for (n, x) in self.samples.iter().enumerate() {
+            let angle = 2.0 * PI * frequency_hz * n as f64 / self.sample_rate;
+            re += x * angle.cos();
+            im -= x * angle.sin();
+        }
+        2.0 * (re * re + im * im).sqrt() / self.samples.len() as f64
+    }
+    
+    /// Total harmonic distortion: the combined amplitude of harmonics 2 to
+    /// `harmonics` relative to the fundamental, as a ratio (0.01 is 1%).
+    /// Harmonics at or above the Nyquist frequency are skipped.
+    pub fn thd(&self, fundamental_hz: f64, harmonics: usize) -> f64 {
+        let fundamental = self.amplitude_at(fundamental_hz);
+        if fundamental == 0.0 {
+            return 0.0;
+        }
+        let nyquist = self.sample_rate / 2.0;
+        let distortion: f64 = (2..=harmonics)
+            .map(|k| fundamental_hz * k as f64)
+            .take_while(|&f| f < nyquist)
+            .map(|f| self.amplitude_at(f).powi(2))
+            .sum();
+        distortion.sqrt() / fundamental
+    }
+}
+
+/// Parameter values by name, as written in a circuit file, such as `gain` or `threshold`
+pub type ParamValues = BTreeMap<String, f64>;
+
+/// Builds a fresh transistor of one registered type from its parameters
+pub type TransistorFactory = Box<dyn Fn(&ParamValues) -> Result<Box<dyn Transistor>, String>>;
+
+/// Transistor types registered by name, so circuits can be assembled from
+/// identifiers (for example read from a config file) at runtime.
+/// New types are added by registering a factory; the registry itself never changes.
+pub struct TransistorRegistry {
+    factories: BTreeMap<String, TransistorFactory>,
+}
+
+impl Default for TransistorRegistry {
+    fn default() -> Self {
+        TransistorRegistry::new()
+    }
+}
+
+impl TransistorRegistry {
+    pub fn new() -> Self {
+        TransistorRegistry {
+            factories: BTreeMap::new(),
+        }
+    }
+    
+    /// A registry with the built-in types "bjt", "fet", "mosfet", "jfet", "igbt" and
+    /// "darlington". Each accepts the TransistorParams fields by name; "igbt" also
+    /// accepts `saturation_voltage`.
+    pub fn with_builtin_types() -> Self {
+        let mut registry = TransistorRegistry::new();
+        for (name, factory) in [
+            ("bjt", Box::new(|values: &ParamValues| -> Result<Box<dyn Transistor>, String> {
+                Ok(Box::new(BJTTransistor::with_params(apply_params(TransistorParams::bjt(), values, &[])?)))
+            }) as TransistorFactory),
+            ("fet", Box::new(|values: &ParamValues| -> Result<Box<dyn Transistor>, String> {
+                Ok(Box::new(FETTransistor::with_params(apply_params(TransistorParams::fet(), values, &[])?)))
+            })),
+            ("mosfet", Box::new(|values: &ParamValues| -> Result<Box<dyn Transistor>, String> {
+                Ok(Box::new(MOSFETTransistor::with_params(apply_params(TransistorParams::mosfet(), values, &[])?)))
+            })),
+            ("jfet", Box::new(|values: &ParamValues| -> Result<Box<dyn Transistor>, String> {
+                let params = apply_params(TransistorParams::jfet(), values, &[])?;
+                if params.threshold >= 0.0 {
+                    return Err(format!("threshold (the pinch-off voltage) must be negative, got {}", params.threshold));
+                }
+                Ok(Box::new(JFETTransistor::with_params(params)))
+            })),
+            ("igbt", Box::new(|values: &ParamValues| -> Result<Box<dyn Transistor>, String> {
+                let params = apply_params(TransistorParams::igbt(), values, &["saturation_voltage"])?;
+                let saturation_voltage = values.get("saturation_voltage").copied().unwrap_or(IGBTTransistor::DEFAULT_SATURATION_VOLTAGE);
+                if !saturation_voltage.is_finite() {
+                    return Err(format!("saturation_voltage must be finite, got {}", saturation_voltage));
+                }
+                Ok(Box::new(IGBTTransistor::with_params(params, saturation_voltage)))
+            })),
+            ("darlington", Box::new(|values: &ParamValues| -> Result<Box<dyn Transistor>, String> {
+                Ok(Box::new(DarlingtonPair::bjt(apply_params(TransistorParams::bjt(), values, &[])?)))
+            })),
+        ] {
+            registry.factories.insert(name.to_string(), factory);
+        }
+        registry
+    }
+    
+    /// Adds a transistor type that takes no parameters. Registering a name
+    /// twice is an error, so one plugin cannot silently replace another.
+    pub fn register<F>(&mut self, name: &str, factory: F) -> Result<(), String>
+    where
+        F: Fn() -> Box<dyn Transistor> + 'static,
+    {
+        self.register_configurable(name, move |values| {
+            if let Some(key) = values.keys().next() {
+                return Err(format!("unexpected parameter {} (takes none)", key));
+            }
+            Ok(factory())
+        })
+    }
+    
+    /// Adds a transistor type built from parameters; the factory rejects any it does not accept
+    pub fn register_configurable<F>(&mut self, name: &str, factory: F) -> Result<(), String>
+    where
+        F: Fn(&ParamValues) -> Result<Box<dyn Transistor>, String> + 'static,
+    {
+        if self.factories.contains_key(name) {
+            return Err(format!("Transistor type already registered: {}", name));
+        }
+        self.factories.insert(name.to_string(), Box::new(factory));
+        Ok(())
+    }
+    
+    pub fn is_registered(&self, name: &str) -> bool {
+        self.factories.contains_key(name)
+    }
+    
+    /// Registered type names in alphabetical order
+    pub fn names(&self) -> Vec<&str> {
+        self.factories.keys().map(|name| name.as_str()).collect()
+    }
+    
+    pub fn create(&self, name: &str) -> Result<Box<dyn Transistor>, String> {
+        self.create_with(name, &ParamValues::new())
+    }
+    
+    pub fn create_with(&self, name: &str, values: &ParamValues) -> Result<Box<dyn Transistor>, String> {
+        let factory = self.factories.get(name).ok_or_else(|| {
+            format!("Unknown transistor type: {} (registered: {})", name, self.names().join(", "))
+        })?;
+        factory(values).map_err(|e| format!("Invalid {} parameters: {}", name, e))
+    }
+    
+    /// Builds a circuit with one transistor per identifier, in order.
+    /// Nothing is built if any identifier is unknown.
+    pub fn build_circuit<S: AsRef<str>>(&self, names: &[S]) -> Result<Circuit, String> {
+        let transistors = names
+            .iter()
+            .map(|name| self.create(name.as_ref()))
+            .collect::<Result<Vec<_>, String>>()?;
+        Ok(Circuit::with_transistors(transistors))
+    }
+    
+    /// Builds a circuit from a comma-separated list such as "bjt, mosfet, mosfet"
+    pub fn circuit_from_config(&self, config: &str) -> Result<Circuit, String> {
+        let names: Vec<&str> = config.split(',').map(str::trim).collect();
+        if names.iter().any(|name| name.is_empty()) {
+            return Err(format!("Empty transistor type in circuit config: {:?}", config));
+        }
+        self.build_circuit(&names)
+    }
+    
+    /// Builds the circuit a CircuitConfig describes. Errors name the position of
+    /// the offending transistor, counting from 1, and nothing is built.
+    pub fn circuit_from(&self, config: &CircuitConfig) -> Result<Circuit, String> {
+        if config.transistors.is_empty() {
+            return Err("Circuit config lists no transistors".to_string());
+        }
+        let transistors = config
+            .transistors
+            .iter()
+            .enumerate()
+            .map(|(index, spec)| {
+                self.create_with(&spec.kind, &spec.params)
+                    .map_err(|e| format!("Transistor {}: {}", index + 1, e))
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        Ok(Circuit::with_transistors(transistors))
+    }
+    
+    /// Builds the circuit a netlist describes. Each element line is
+    /// `Q<name> vcc <base net> <emitter net> <type> [key=value ...] [stage=outer/inner]`:
+    /// every collector is on the shared supply `vcc`, the circuit is driven on `in`
+    /// and read on `out`, and outputs meeting on a net add up. `*` lines are
+    /// comments and `.end` ends the netlist. Other elements and directives are rejected.
+    pub fn circuit_from_netlist(&self, text: &str) -> Result<Circuit, String> {
+        let mut edges = Vec::new();
+        let mut elements = BTreeSet::new();
+        for (index, line) in text.lines().enumerate() {
+            let at = |e: String| format!("Line {}: {}", index + 1, e);
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('*') {
+                continue;
+            }
+            if line.eq_ignore_ascii_case(".end") {
+                break;
+            }
+            let mut fields = line.split_whitespace();
+            let element = fields.next().unwrap_or_default();
+            if element.starts_with('.') {
+                return Err(at(format!("unsupported directive {}", element)));
+            }
+            if !element.starts_with(['Q', 'q']) {
+                return Err(at(format!("unsupported element {} (only transistors, Q..., are supported)", element)));
+            }
+            if !elements.insert(element.to_ascii_uppercase()) {
+                return Err(at(format!("duplicate element {}", element)));
+            }
+            let (collector, base, emitter, kind) = match (fields.next(), fields.next(), fields.next(), fields.next()) {
+                (Some(collector), Some(base), Some(emitter), Some(kind)) => (collector, base, emitter, kind),
+                _ => return Err(at(format!("{} needs collector, base and emitter nets and a type", element))),
+            };
+            if collector != NET_SUPPLY {
+                return Err(at(format!("{}: collector must be on {}, got {}", element, NET_SUPPLY, collector)));
+            }
+            if emitter == NET_IN || base == NET_OUT || base == emitter {
+                return Err(at(format!("{}: cannot drive {} from {}", element, emitter, base)));
+            }
+            
+            let mut params = ParamValues::new();
+            let mut stages = Vec::new();
+            for field in fields {
+                let (key, value) = field.split_once('=').ok_or_else(|| at(format!("expected key=value, got {}", field)))?;
+                if key == "stage" {
+                    stages = value.split('/').map(String::from).collect();
+                    continue;
+                }
+                let value = value.parse::<f64>().map_err(|_| at(format!("{} is not a number: {}", key, value)))?;
+                params.insert(key.to_string(), value);
+            }
+            let transistor = self.create_with(kind, &params).map_err(at)?;
+            edges.push(NetlistEdge {
+                from: base.to_string(),
+                to: emitter.to_string(),
+                node: CircuitNode::Device(transistor),
+                stages,
+            });
+        }
+        Ok(Circuit::with_nodes(reduce_netlist(edges)?))
+    }
+}
+
+/// The fields of `params` that differ from a type's preset; the inverse of `apply_params`
+fn changed_params(params: &TransistorParams, preset: &TransistorParams) -> ParamValues {
+    let fields = |p: &TransistorParams| {
+        [
+            p.gain,
+            p.threshold,
+            p.min_output,
+            p.max_output,
+            p.temperature_coefficient,
+            p.temperature,
+            p.cutoff_frequency,
+            p.max_temperature,
+            p.thermal_resistance,
+            p.thermal_capacitance,
+        ]
+    };
+    PARAM_NAMES
+        .iter()
+        .zip(fields(params).into_iter().zip(fields(preset)))
+        .filter(|(_, (value, default))| value != default)
+        .map(|(name, (value, _))| (name.to_string(), value))
+        .collect()
+}
+
+/// The TransistorParams fields a circuit file can set, by name
+const PARAM_NAMES: [&str; 10] = [
+    "gain",
+    "threshold",
+    "min_output",
+    "max_output",
+    "temperature_coefficient",
+    "temperature",
+    "cutoff_frequency",
+    "max_temperature",
+    "thermal_resistance",
+    "thermal_capacitance",
+];
+
+/// Starts from a type's preset and overrides the fields named in `values`.
+/// `extra` lists type-specific keys the caller reads itself; any other key is an error.
+fn apply_params(mut params: TransistorParams, values: &ParamValues, extra: &[&str]) -> Result<TransistorParams, String> {
+    for (key, value) in values {
+        match key.as_str() {
+            "gain" => params.gain = *value,
+            "threshold" => params.threshold = *value,
+            "min_output" => params.min_output = *value,
+            "max_output" => params.max_output = *value,
+            "temperature_coefficient" => params.temperature_coefficient = *value,
+            "temperature" => params.temperature = *value,
+            "cutoff_frequency" => params.cutoff_frequency = *value,
+            "max_temperature" => params.max_temperature = *value,
+            "thermal_resistance" => params.thermal_resistance = *value,
+            "thermal_capacitance" => params.thermal_capacitance = *value,
+            other if extra.contains(&other) => {}
+            other => {
+                let expected: Vec<&str> = PARAM_NAMES.iter().chain(extra).copied().collect();
+                return Err(format!("unknown parameter {} (expected {})", other, expected.join(", ")));
+            }
+        }
+    }
+    params.validate()?;
+    Ok(params)
+}
+
+/// One transistor in a circuit file: its registered type and optional parameters
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TransistorSpec {
+    #[serde(rename = "type")]
+    pub kind: String,
+    #[serde(default)]
+    pub params: ParamValues,
+}
+
+impl TransistorSpec {
+    pub fn new(kind: &str, params: ParamValues) -> Self {
+        TransistorSpec {
+            kind: kind.to_string(),
+            params,
+        }
+    }
+}
+
+/// A circuit described in TOML or JSON, so new circuits need no code changes:
+///
+/// ```toml
+/// name = "two-stage"
+///
+/// [[transistors]]
+/// type = "bjt"
+/// params = { gain = 0.12 }
+/// ```
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CircuitConfig {
+    #[serde(default)]
+    pub name: Option<String>,
+    pub transistors: Vec<TransistorSpec>,
+}
+
+impl CircuitConfig {
+    pub fn from_toml(text: &str) -> Result<Self, String> {
+        toml::from_str(text).map_err(|e| format!("Invalid circuit TOML: {}", e.message()))
+    }
+    
+    pub fn from_json(text: &str) -> Result<Self, String> {
+        serde_json::from_str(text).map_err(|e| format!("Invalid circuit JSON: {}", e))
+    }
+    
+    /// Reads a `.toml` or `.json` file, chosen by extension
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path).map_err(|e| format!("Cannot read {}: {}", path.display(), e))?;
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("toml") => Self::from_toml(&text),
+            Some("json") => Self::from_json(&text),
+            _ => Err(format!("Unsupported circuit config format: {} (use .toml or .json)", path.display())),
+        }
+    }
+}
+
+/// Nets with a fixed meaning in a netlist: the signal input, the circuit
+/// output and the collector supply every transistor shares
+const NET_IN: &str = "in";
+const NET_OUT: &str = "out";
+const NET_SUPPLY: &str = "vcc";
+
+/// Flattens a topology into netlist lines. A series gets a fresh net between
+/// each pair of nodes, parallel branches share their nets, and stage names
+/// are written on each device as a path.
+#[derive(Default)]
+struct NetlistWriter {
+    lines: Vec<String>,
+    nets: usize,
+}
+
+impl NetlistWriter {
+    fn write(&mut self, node: &CircuitNode, base: &str, emitter: &str, stages: &mut Vec<String>) -> Result<(), String> {
+        match node {
+            CircuitNode::Device(element) => {
+                let spec = element
+                    .to_spec()
+                    .ok_or_else(|| format!("Cannot export {}: it has no registry type", element.describe().name))?;
+                let mut line = format!("Q{} {} {} {} {}", self.lines.len() + 1, NET_SUPPLY, base, emitter, spec.kind);
+                for (key, value) in &spec.params {
+                    line.push_str(&format!(" {}={}", key, value));
+                }
+                if !stages.is_empty() {
+                    line.push_str(&format!(" stage={}", stages.join("/")));
+                }
+                self.lines.push(line);
+            }
+            CircuitNode::Series(nodes) | CircuitNode::Parallel(nodes) if nodes.is_empty() => {
+                return Err("Cannot export an empty series or parallel node".to_string());
+            }
+            CircuitNode::Series(nodes) => {
+                let mut from = base.to_string();
+                for (index, node) in nodes.iter().enumerate() {
+                    let to = if index + 1 == nodes.len() {
+                        emitter.to_string()
+                    } else {
+                        self.nets += 1;
+                        format!("n{}", self.nets)
+                    };
+                    self.write(node, &from, &to, stages)?;
+                    from = to;
+                }
+            }
+            CircuitNode::Parallel(nodes) => {
+                for node in nodes {
+                    self.write(node, base, emitter, stages)?;
+                }
+            }
+            CircuitNode::Stage { name, node } => {
+                if name.is_empty() || name.contains(|c: char| c.is_whitespace() || c == '/' || c == '=') {
+                    return Err(format!("Cannot export stage {:?}: names need at least one character and no spaces, '/' or '='", name));
+                }
+                stages.push(name.clone());
+                self.write(node, base, emitter, stages)?;
+                stages.pop();
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A sub-circuit between two nets while a netlist is being rebuilt
+struct NetlistEdge {
+    from: String,
+    to: String,
+    node: CircuitNode,
+    stages: Vec<String>,
+}
+
+/// Rebuilds the topology by series-parallel reduction. Edges between the
+/// same two nets merge into a Parallel node, and a net with exactly one edge
+/// in and one out merges them into a Series. Merges inside the deepest shared
+/// stage go first, so stage boundaries come back where they were written.
+fn reduce_netlist(mut edges: Vec<NetlistEdge>) -> Result<Vec<CircuitNode>, String> {
+    if edges.is_empty() {
+        return Ok(Vec::new());
+    }
+    loop {
+        let mut best: Option<(usize, usize, bool, usize)> = None;
+        for (i, a) in edges.iter().enumerate() {
+            for (j, b) in edges.iter().enumerate() {
+                let parallel = i < j && a.from == b.from && a.to == b.to;
+                let series = i != j
+                    && a.to == b.from
+                    && a.to != NET_OUT
+                    && edges.iter().filter(|e| e.to == a.to).count() == 1
+                    && edges.iter().filter(|e| e.from == a.to).count() == 1;
+                if parallel || series {
+                    let shared = a.stages.iter().zip(&b.stages).take_while(|(x, y)| x == y).count();
+                    if best.is_none_or(|(.., best_shared)| shared > best_shared) {
+                        best = Some((i, j, series, shared));
+                    }
+                }
+            }
+        }
+        let Some((i, j, series, shared)) = best else {
+            break;
+        };
+        
+        let later = edges.remove(i.max(j));
+        let earlier = edges.remove(i.min(j));
+        let (a, b) = if i < j { (earlier, later) } else { (later, earlier) };
+        let to = if series { b.to } else { a.to };
+        let left = wrap_stages(a.node, &a.stages[shared..]);
+        let right = wrap_stages(b.node, &b.stages[shared..]);
+        let mut nodes = Vec::new();
+        for node in [left, right] {
+            match node {
+                CircuitNode::Series(inner) if series => nodes.extend(inner),
+                CircuitNode::Parallel(inner) if !series => nodes.extend(inner),
+                other => nodes.push(other),
+            }
+        }
+        let node = if series { CircuitNode::Series(nodes) } else { CircuitNode::Parallel(nodes) };
+        let mut stages = a.stages;
+        stages.truncate(shared);
+        edges.insert(i.min(j), NetlistEdge { from: a.from, to, node, stages });
+    }
+    
+    match edges.pop() {
+        Some(edge) if edges.is_empty() && edge.from == NET_IN && edge.to == NET_OUT => {
+            match wrap_stages(edge.node, &edge.stages) {
+                CircuitNode::Parallel(nodes) => Ok(nodes),
+                node => Ok(vec![node]),
+            }
+        }
+        _ => Err(format!("Netlist does not form a series-parallel circuit from {} to {}", NET_IN, NET_OUT)),
+    }
+}
+
+fn wrap_stages(node: CircuitNode, stages: &[String]) -> CircuitNode {
+    stages.iter().rev().fold(node, |node, name| CircuitNode::stage(name, node))
+}
+
+/// Example usage demonstrating OCP compliance
+pub fn demonstrate_correct_ocp() {
+    println!("=== CORRECT OCP IMPLEMENTATION ===");
+    println!("This code follows the Open-Closed Principle");
+    println!("New transistor types can be added without modifying existing code");
+    println!();
+    
+    // Create amplifier circuit
+    let mut amplifier = Circuit::new();
+    
+    // Add different transistor types
+    amplifier.add_transistor(Box::new(BJTTransistor::new()));
+    amplifier.add_transistor(Box::new(FETTransistor::new()));
+    amplifier.add_transistor(Box::new(MOSFETTransistor::new()));
+    
+    // Test the amplifier
+    let signal = 2.0;
+    let input = 5.0;
+    
+    let output = amplifier.amplify_signal(signal, input);
+    println!("Input Signal: {:.2}", signal);
+    println!("Input Current: {:.2}", input);
+    println!("Amplified Output: {:.2}", output);
+    
+    // Power and device info come from the transistors themselves
+    let power = amplifier.total_power();
+    println!("Power Consumption: {:.2}W", power);
+    
+    println!();
+    println!("Transistor Information:");
+    for (i, info) in amplifier.inventory().iter().enumerate() {
+        println!("{}. {}", i + 1, info);
+    }
+    
+    println!();
+    println!("Benefits of this approach:");
+    println!("1. New transistor types can be added without modifying existing code");
+    println!("2. Each transistor type is responsible for its own behavior");
+    println!("3. Traits enable flexible design");
+    println!("4. Follows Single Responsibility Principle");
+}
+
+/// Example usage of the registry: circuits assembled from type names, and a
+/// new type added at runtime without touching the registry or the circuit
+pub fn demonstrate_plugin_registry() {
+    println!("=== TRANSISTOR PLUGIN REGISTRY ===");
+    
+    let mut registry = TransistorRegistry::with_builtin_types();
+    
+    // A "plugin": a type the registry has never seen, added by name.
+    // The base signal is the light falling on it.
+    #[derive(Debug)]
+    struct Phototransistor {
+        light: f64,
+        collector_input: f64,
+    }
+    
+    impl CircuitElement for Phototransistor {
+        fn drive(&mut self, signal: f64, supply: f64) {
+            self.base(signal);
+            self.collector(supply);
+        }
+        
+        fn output(&self) -> f64 {
+            self.collector_input * (self.light * 0.05)
+        }
+        
+        fn power_consumption(&self) -> f64 {
+            self.light * self.collector_input * 0.01
+        }
+        
+        fn describe(&self) -> DeviceInfo {
+            DeviceInfo::new("Phototransistor", "Light controlled BJT - Light replaces base current")
+        }
+    }
+    
+    impl Transistor for Phototransistor {
+        fn base(&mut self, signal: f64) {
+            self.light = signal.max(0.0);
+        }
+        
+        fn collector(&mut self, input: f64) {
+            self.collector_input = input;
+        }
+    }
+    
+    let phototransistor = || Box::new(Phototransistor { light: 0.0, collector_input: 0.0 }) as Box<dyn Transistor>;
+    if let Err(e) = registry.register("phototransistor", phototransistor) {
+        println!("Registration failed: {}", e);
+    }
+    println!("Registered types: {}", registry.names().join(", "));
+    
+    // Config-driven circuit
+    let config = "bjt, phototransistor, mosfet";
+    match registry.circuit_from_config(config) {
+        Ok(mut circuit) => println!(
+            "Circuit \"{}\" has {} transistors, output {:.2}",
+            config,
+            circuit.element_count(),
+            circuit.amplify_signal(2.0, 5.0)
+        ),
+        Err(e) => println!("Cannot build circuit: {}", e),
+    }
+    
+    // Errors: an unknown identifier, and a second registration under a taken name
+    if let Err(e) = registry.circuit_from_config("bjt, thyristor") {
+        println!("Cannot build circuit: {}", e);
+    }
+    if let Err(e) = registry.register("bjt", || Box::new(FETTransistor::new())) {
+        println!("Registration failed: {}", e);
+    }
+}
+
+/// Example usage of circuit files: new circuits are data, not code
+pub fn demonstrate_config_circuits() {
+    println!("=== CIRCUITS FROM CONFIG FILES ===");
+    
+    for config in [
+        CircuitConfig::from_toml(include_str!("circuits/two_stage.toml")),
+        CircuitConfig::from_json(include_str!("circuits/fet_bank.json")),
+    ] {
+        match config.and_then(|config| Ok((Circuit::from_config(&config)?, config))) {
+            Ok((mut circuit, config)) => println!(
+                "{}: {} transistors, output {:.2}",
+                config.name.as_deref().unwrap_or("unnamed"),
+                circuit.element_count(),
+                circuit.amplify_signal(2.0, 5.0)
+            ),
+            Err(e) => println!("Cannot build circuit: {}", e),
+        }
+    }
+    
+    // Mistakes are reported, not guessed around
+    for broken in [
+        "[[transistors]]\ntype = \"bjt\"\nparams = { gain = \"high\" }",
+        "[[transistors]]\ntype = \"thyristor\"",
+        "[[transistors]]\ntype = \"mosfet\"\nparams = { treshold = 0.5 }",
+        "transistors = []",
+    ] {
+        if let Err(e) = CircuitConfig::from_toml(broken).and_then(|config| Circuit::from_config(&config)) {
+            println!("Rejected: {}", e);
+        }
+    }
+}
+
+/// Example usage of the newer transistor types at known operating points.
+/// None of them needed a change to the trait, the circuit or the other types.
+pub fn demonstrate_transistor_models() {
+    println!("=== MORE TRANSISTOR TYPES ===");
+    
+    let unit_gain = TransistorParams { gain: 1.0, ..TransistorParams::bjt() };
+    let mut operating_points: Vec<(&str, Box<dyn Transistor>, f64, f64)> = vec![
+        ("JFET, V_GS = 0 (channel open)", Box::new(JFETTransistor::new()), 0.0, 10.0),
+        ("JFET, V_GS = V_P / 2", Box::new(JFETTransistor::new()), -2.0, 10.0),
+        ("JFET, V_GS = V_P (pinched off)", Box::new(JFETTransistor::new()), -4.0, 10.0),
+        ("IGBT, below threshold", Box::new(IGBTTransistor::new()), 4.0, 24.0),
+        ("IGBT, active region", Box::new(IGBTTransistor::new()), 6.0, 24.0),
+        ("IGBT, saturated", Box::new(IGBTTransistor::new()), 15.0, 24.0),
+        ("Single BJT, gain 1", Box::new(BJTTransistor::with_params(unit_gain)), 2.0, 5.0),
+        ("Darlington pair, gain 1 each", Box::new(DarlingtonPair::bjt(unit_gain)), 2.0, 5.0),
+    ];
+    for (label, transistor, signal, input) in &mut operating_points {
+        transistor.base(*signal);
+        transistor.collector(*input);
+        println!("{:<32} signal {:>5.1}, input {:>5.1} -> output {:.2}", label, signal, input, transistor.output());
+    }
+    
+    // Composition works with any pair, not just BJTs
+    let mut hybrid = DarlingtonPair::new(Box::new(MOSFETTransistor::new()), Box::new(IGBTTransistor::new()));
+    hybrid.base(3.0);
+    hybrid.collector(24.0);
+    println!("{}: output {:.2}, power {:.2}W", hybrid.describe().name, hybrid.output(), hybrid.power_consumption());
+}
+
+/// Example usage of TransistorParams at the edges of the operating range:
+/// saturation clamping, conduction exactly at the threshold and temperature drift.
+pub fn demonstrate_transistor_params() {
+    println!("=== TRANSISTOR PARAMETERS ===");
+    
+    let limited = TransistorParams { max_output: 1.0, min_output: -1.0, ..TransistorParams::bjt() };
+    let mut operating_points: Vec<(&str, Box<dyn Transistor>, f64, f64)> = vec![
+        ("BJT, below max_output", Box::new(BJTTransistor::with_params(limited)), 2.0, 4.0),
+        ("BJT, exactly at max_output", Box::new(BJTTransistor::with_params(limited)), 2.0, 5.0),
+        ("BJT, clamped to max_output", Box::new(BJTTransistor::with_params(limited)), 2.0, 50.0),
+        ("BJT, clamped to min_output", Box::new(BJTTransistor::with_params(limited)), -2.0, 50.0),
+        ("MOSFET, exactly at threshold", Box::new(MOSFETTransistor::new()), 0.7, 5.0),
+        ("MOSFET, just above threshold", Box::new(MOSFETTransistor::new()), 0.8, 5.0),
+    ];
+    for (label, transistor, signal, input) in &mut operating_points {
+        transistor.base(*signal);
+        transistor.collector(*input);
+        println!("{:<32} signal {:>5.1}, input {:>5.1} -> output {:.2}", label, signal, input, transistor.output());
+    }
+    
+    for temperature in [-25.0, REFERENCE_TEMPERATURE, 75.0] {
+        let params = TransistorParams { temperature_coefficient: 0.005, temperature, ..TransistorParams::bjt() };
+        let mut bjt = BJTTransistor::with_params(params);
+        bjt.base(2.0);
+        bjt.collector(5.0);
+        println!("BJT at {:>5.1} °C: effective gain {:.3}, output {:.2}", temperature, params.effective_gain(), bjt.output());
+    }
+    
+    let inverted = TransistorParams { min_output: 2.0, max_output: 1.0, ..TransistorParams::default() };
+    if let Err(e) = inverted.validate() {
+        println!("Rejected: {}", e);
+    }
+}
+
+/// Example usage of circuit topologies: a two-stage amplifier where a BJT
+/// preamp drives a parallel pair of FETs
+pub fn demonstrate_circuit_topology() {
+    println!("=== MULTI-STAGE AMPLIFIER ===");
+    
+    let preamp = TransistorParams { gain: 1.0, ..TransistorParams::bjt() };
+    let mut amplifier = Circuit::new();
+    amplifier.add_node(CircuitNode::series(vec![
+        CircuitNode::stage("preamp", CircuitNode::device(Box::new(BJTTransistor::with_params(preamp)))),
+        CircuitNode::stage(
+            "output",
+            CircuitNode::parallel(vec![
+                CircuitNode::device(Box::new(FETTransistor::new())),
+                CircuitNode::device(Box::new(FETTransistor::new())),
+            ]),
+        ),
+    ]));
+    
+    // preamp: 5.0 * 0.2 * 1.0 = 1.0; output: 2 * (5.0 * 1.0 * 0.15) = 1.5
+    let signal = 0.2;
+    let input = 5.0;
+    let output = amplifier.amplify_signal(signal, input);
+    println!("Input Signal: {:.2}, Input Current: {:.2}", signal, input);
+    for (stage, stage_output) in amplifier.stage_outputs() {
+        println!("Stage {:<8} output {:.2}", stage, stage_output);
+    }
+    println!("Amplified Output: {:.2} (overall gain {:.1})", output, output / signal);
+    println!("Transistors: {}, Power Consumption: {:.2}W", amplifier.element_count(), amplifier.total_power());
+}
+
+/// Example usage of an AC sweep: the two-stage BJT amplifier rolls off
+/// sooner than either stage alone, since both poles sit at the same frequency
+pub fn demonstrate_ac_sweep() {
+    println!("=== AC SWEEP ===");
+    
+    let stage = TransistorParams { gain: 1.0, ..TransistorParams::bjt() };
+    let mut amplifier = Circuit::new();
+    amplifier.add_node(CircuitNode::series(vec![
+        CircuitNode::device(Box::new(BJTTransistor::with_params(stage))),
+        CircuitNode::device(Box::new(BJTTransistor::with_params(stage))),
+    ]));
+    
+    match amplifier.ac_sweep(0.1, 5.0, 1.0e3..=1.0e8, 11) {
+        Ok(sweep) => {
+            print!("{}", sweep);
+            match sweep.cutoff_frequency() {
+                Some(cutoff) => println!("-3 dB reached by {:.0} Hz (each stage: {:.0} Hz)", cutoff, stage.cutoff_frequency),
+                None => println!("No roll-off within the sweep"),
+            }
+        }
+        Err(e) => println!("Sweep failed: {}", e),
+    }
+    
+    if let Err(e) = amplifier.ac_sweep(0.1, 5.0, 0.0..=1.0e6, 11) {
+        println!("Rejected: {}", e);
+    }
+}
+
+/// Example usage of waveform processing: the same sine through a linear BJT,
+/// a MOSFET biased near its threshold (clipping) and a JFET (square law),
+/// then a square wave and seeded noise
+pub fn demonstrate_waveforms() {
+    println!("=== WAVEFORM PROCESSING ===");
+    
+    let sample_rate = 48_000.0;
+    let tone = 1_000.0;
+    let samples = 480; // ten whole periods
+    
+    let mut amplifiers: Vec<(&str, Box<dyn Transistor>, f64)> = vec![
+        ("BJT", Box::new(BJTTransistor::new()), 1.0),
+        ("MOSFET", Box::new(MOSFETTransistor::new()), 1.0),
+        ("JFET", Box::new(JFETTransistor::new()), -2.0),
+    ];
+    for (label, transistor, bias) in amplifiers.drain(..) {
+        let mut circuit = Circuit::new();
+        circuit.add_transistor(transistor);
+        let mut source = SineWave::new(0.5, tone, sample_rate).with_offset(bias);
+        let output = circuit.process_waveform(&mut source, samples, 5.0);
+        println!(
+            "{:<7} sine in, bias {:>4.1}: RMS {:.3}, peak {:.3}, THD {:.2}%",
+            label,
+            bias,
+            output.rms(),
+            output.peak(),
+            output.thd(tone, 10) * 100.0
+        );
+    }
+    
+    let mut bjt = Circuit::new();
+    bjt.add_transistor(Box::new(BJTTransistor::new()));
+    let square = bjt.process_waveform(&mut SquareWave::new(1.0, tone, sample_rate), samples, 5.0);
+    println!("BJT     square in:          RMS {:.3}, peak {:.3}, THD {:.2}%", square.rms(), square.peak(), square.thd(tone, 10) * 100.0);
+    
+    let noise = bjt.process_waveform(&mut NoiseSource::new(1.0, sample_rate, 42), samples, 5.0);
+    println!("BJT     noise in (seed 42): RMS {:.3}, peak {:.3}, mean {:.3}", noise.rms(), noise.peak(), noise.mean());
+}
+
+/// Example usage of netlists: the two-stage amplifier written out, read back
+/// and checked against the original, then the errors for unsupported input
+pub fn demonstrate_netlist() {
+    println!("=== SPICE-STYLE NETLIST ===");
+    
+    let preamp = TransistorParams { gain: 1.0, ..TransistorParams::bjt() };
+    let mut amplifier = Circuit::new();
+    amplifier.add_node(CircuitNode::series(vec![
+        CircuitNode::stage("preamp", CircuitNode::device(Box::new(BJTTransistor::with_params(preamp)))),
+        CircuitNode::stage(
+            "output",
+            CircuitNode::parallel(vec![
+                CircuitNode::device(Box::new(FETTransistor::new())),
+                CircuitNode::device(Box::new(MOSFETTransistor::new())),
+            ]),
+        ),
+    ]));
+    
+    let netlist = match amplifier.to_netlist() {
+        Ok(netlist) => netlist,
+        Err(e) => {
+            println!("Export failed: {}", e);
+            return;
+        }
+    };
+    print!("{}", netlist);
+    
+    match Circuit::from_netlist(&netlist) {
+        Ok(mut rebuilt) => {
+            let (original, copy) = (amplifier.amplify_signal(0.2, 5.0), rebuilt.amplify_signal(0.2, 5.0));
+            println!("Round trip: output {:.2} -> {:.2}, stages {:?}", original, copy, rebuilt.stage_outputs());
+        }
+        Err(e) => println!("Import failed: {}", e),
+    }
+    
+    // Errors: elements other than transistors, and a transistor with no registry type
+    if let Err(e) = Circuit::from_netlist("* divider\nR1 in out 1k\n.end\n") {
+        println!("Rejected: {}", e);
+    }
+    let mut hybrid = Circuit::new();
+    hybrid.add_transistor(Box::new(DarlingtonPair::new(Box::new(MOSFETTransistor::new()), Box::new(IGBTTransistor::new()))));
+    if let Err(e) = hybrid.to_netlist() {
+        println!("Rejected: {}", e);
+    }
+}
+
+/// Example usage of non-transistor elements: an RC low-pass stage's step
+/// response and roll-off, and a diode and RC filter after a BJT stage.
+/// The circuit and topology code did not change to support them.
+pub fn demonstrate_circuit_elements() {
+    println!("=== RESISTORS, CAPACITORS AND DIODES ===");
+    
+    // 1 kΩ and 1 µF: τ = 1 ms
+    let mut low_pass = CircuitNode::device(Box::new(Capacitor::new(1.0e-6, 1.0e3)));
+    let dt = 1.0e-4;
+    for step in 1..=50 {
+        let output = low_pass.step(1.0, 0.0, dt);
+        if step % 10 == 0 {
+            println!("RC step response at t = {:.0} τ: {:.3}", step as f64 * dt / 1.0e-3, output);
+        }
+    }
+    
+    let mut filter = Circuit::new();
+    filter.add_element(Box::new(Capacitor::new(1.0e-6, 1.0e3)));
+    if let Ok(sweep) = filter.ac_sweep(1.0, 0.0, 10.0..=10_000.0, 7) {
+        print!("{}", sweep);
+    }
+    
+    let mut chain = Circuit::new();
+    chain.add_node(CircuitNode::series(vec![
+        CircuitNode::device(Box::new(BJTTransistor::new())),
+        CircuitNode::device(Box::new(Resistor::new(1.0e3).with_load(1.0e3))),
+        CircuitNode::device(Box::new(Diode::new())),
+    ]));
+    let output = chain.amplify_signal(4.0, 5.0);
+    println!("BJT -> 1 kΩ divider -> diode: output {:.2}", output);
+    for (i, info) in chain.inventory().iter().enumerate() {
+        println!("{}. {}", i + 1, info);
+    }
+}
+
+/// Example usage of the thermal model: a BJT whose gain rises with temperature
+/// settles at moderate power, runs away at higher power, and a MOSFET whose
+/// gain falls with temperature limits itself
+pub fn demonstrate_thermal() {
+    println!("=== THERMAL SIMULATION ===");
+    
+    let hot_bjt = TransistorParams { temperature_coefficient: 0.01, ..TransistorParams::bjt() };
+    let cases: Vec<(&str, Box<dyn Transistor>, f64, f64)> = vec![
+        ("BJT at 1 W", Box::new(BJTTransistor::with_params(hot_bjt)), 2.0, 10.0),
+        ("BJT at 2 W", Box::new(BJTTransistor::with_params(hot_bjt)), 2.0, 20.0),
+        (
+            "MOSFET, negative tempco",
+            Box::new(MOSFETTransistor::with_params(TransistorParams { temperature_coefficient: -0.004, ..TransistorParams::mosfet() })),
+            5.0,
+            6.0,
+        ),
+    ];
+    for (label, transistor, signal, input) in cases {
+        let mut circuit = Circuit::new();
+        circuit.add_transistor(transistor);
+        circuit.amplify_signal(signal, input);
+        match circuit.simulate(10.0, 25.0) {
+            Ok(report) => print!("{}: {}", label, report),
+            Err(e) => println!("{}: {}", label, e),
+        }
+    }
+}
+
+/// Example usage of decorators: behaviour added by wrapping a transistor,
+/// composed in any order, without changing BJTTransistor or the circuit
+pub fn demonstrate_decorators() {
+    println!("=== DECORATED TRANSISTORS ===");
+    
+    // Innermost first: noise is added, then the result is clipped, then every call is logged
+    let decorated = LoggedTransistor::new(Box::new(ClippedTransistor::new(
+        Box::new(NoisyTransistor::new(Box::new(BJTTransistor::new()), 0.2, 7)),
+        0.0,
+        2.0,
+    )));
+    let log = decorated.call_log();
+    println!("{}", decorated.describe());
+    
+    let mut circuit = Circuit::new();
+    circuit.add_transistor(Box::new(decorated));
+    for signal in [1.0, 3.0, 5.0, 7.0] {
+        let mut plain = BJTTransistor::new();
+        plain.drive(signal, 5.0);
+        println!("signal {:.1}: plain BJT {:.2}, decorated {:.2}", signal, plain.output(), circuit.amplify_signal(signal, 5.0));
+    }
+    
+    let calls = log.borrow();
+    println!("Logged {} calls, first three: {:?}", calls.len(), &calls[..3]);
+}
+
+/// Demonstrates AnyTransistor: enum dispatch for the built-in types, with a
+/// dynamic fallback so the set stays open
+pub fn demonstrate_enum_dispatch() {
+    println!("=== ENUM DISPATCH WITH ANYTRANSISTOR ===");
+    
+    let devices = || -> Vec<AnyTransistor> {
+        vec![
+            BJTTransistor::new().into(),
+            FETTransistor::new().into(),
+            MOSFETTransistor::new().into(),
+            // Not a built-in variant: dispatched through the Dyn fallback
+            AnyTransistor::from(Box::new(ClippedTransistor::new(Box::new(JFETTransistor::new()), 0.0, 1.0)) as Box<dyn Transistor>),
+        ]
+    };
+    
+    let mut amplifier = StaticAmplifier::new();
+    for transistor in devices() {
+        let dispatch = if transistor.is_dynamic() { "dynamic" } else { "enum" };
+        println!("{} ({})", transistor.describe().name, dispatch);
+        amplifier.add_transistor(transistor);
+    }
+    
+    // The same devices as trait objects, for comparison
+    let mut circuit = Circuit::new();
+    for transistor in devices() {
+        circuit.add_transistor(transistor.into_boxed());
+    }
+    
+    println!(
+        "StaticAmplifier<AnyTransistor>: {:.2}, Circuit: {:.2}",
+        amplifier.amplify_signal(2.0, 5.0),
+        circuit.amplify_signal(2.0, 5.0)
+    );
+    
+    println!();
+    println!("When to use each:");
+    println!("- Box<dyn Transistor> / Circuit: open set, plugins and config files, any topology");
+    println!("- StaticAmplifier<T>: one known type, fastest, fully inlined");
+    println!("- AnyTransistor: a mix of built-in types in a hot loop; other types still fit via Dyn");
+    println!("  (each new built-in type is one line in the any_transistor! invocation)");
+}
+
+/// Example of checking circuits for configuration problems before simulating them
+pub fn demonstrate_validation() {
+    println!("=== CIRCUIT VALIDATION ===");
+    
+    let (signal, input, ambient) = (0.5, 24.0, 25.0);
+    let mut circuit = Circuit::new();
+    circuit.add_node(CircuitNode::stage(
+        "preamp",
+        CircuitNode::series(vec![
+            // A gain of zero silences everything after it in the chain
+            CircuitNode::device(Box::new(BJTTransistor::with_params(TransistorParams { gain: 0.0, ..TransistorParams::bjt() }))),
+            CircuitNode::device(Box::new(FETTransistor::new())),
+        ]),
+    ));
+    // 0.5 on the gate is below the MOSFET's 0.7 threshold
+    circuit.add_transistor(Box::new(MOSFETTransistor::new()));
+    // No heatsink: 400 °C/W instead of 50
+    circuit.add_transistor(Box::new(FETTransistor::with_params(TransistorParams { thermal_resistance: 400.0, ..TransistorParams::fet() })));
+    circuit.add_node(CircuitNode::parallel(Vec::new()));
+    
+    println!("Signal {:.1}, supply {:.1}, ambient {:.0} °C:", signal, input, ambient);
+    let diagnostics = circuit.validate(signal, input, ambient);
+    for diagnostic in &diagnostics {
+        println!("  {}", diagnostic);
+    }
+    let errors = diagnostics.iter().filter(|diagnostic| diagnostic.severity() == Severity::Error).count();
+    println!("{} of {} problems are errors", errors, diagnostics.len());
+    
+    for diagnostic in Circuit::new().validate(signal, input, ambient) {
+        println!("Empty circuit: {}", diagnostic);
+    }
+    
+    let mut healthy = Circuit::new();
+    healthy.add_transistor(Box::new(BJTTransistor::new()));
+    println!("Healthy circuit: {} problems", healthy.validate(2.0, 5.0, ambient).len());
+}
+
+/// Demonstrates observers: built-in logging and metrics observers plus a
+/// user-defined one, all hooked into a circuit that is not modified
+pub fn demonstrate_observers() {
+    println!("=== CIRCUIT OBSERVERS ===");
+    
+    // A user-supplied observer - only the hook it needs is implemented
+    #[derive(Debug)]
+    struct OverloadAlarm;
+    
+    impl CircuitObserver for OverloadAlarm {
+        fn on_power_threshold_exceeded(&mut self, power: f64, threshold: f64) {
+            println!("  ALARM: {:.1} W is {:.0}% over budget", power, (power / threshold - 1.0) * 100.0);
+        }
+    }
+    
+    let mut circuit = Circuit::new();
+    circuit.add_node(CircuitNode::stage("preamp", CircuitNode::device(Box::new(BJTTransistor::new()))));
+    circuit.add_node(CircuitNode::stage(
+        "limiter",
+        CircuitNode::device(Box::new(ClippedTransistor::new(Box::new(FETTransistor::new()), -1.0, 1.0))),
+    ));
+    circuit.set_power_threshold(0.5);
+    
+    let metrics = MetricsObserver::new();
+    let totals = metrics.metrics();
+    circuit.add_observer(Box::new(LoggingObserver::new("log")));
+    circuit.add_observer(Box::new(metrics));
+    circuit.add_observer(Box::new(OverloadAlarm));
+    
+    for signal in [0.1, 0.5, 2.0] {
+        println!("Signal {:.1}:", signal);
+        let output = circuit.amplify_signal(signal, 5.0);
+        println!("  Output {:.2}", output);
+    }
+    
+    let totals = totals.borrow();
+    println!("Metrics:");
+    for (stage, peak) in &totals.stage_peaks {
+        println!("  Peak output of '{}': {:.2}", stage, peak);
+    }
+    println!("  Clipping events: {} (elements {:?})", totals.clipping_events, totals.clipped_elements);
+    println!("  Power threshold exceeded {} times, peak {:.2} W", totals.power_threshold_events, totals.peak_power);
+}
+
+pub fn demonstrate_circuit_builder() {
+    println!("=== CIRCUIT BUILDER ===");
+    
+    let metrics = MetricsObserver::new();
+    let totals = metrics.metrics();
+    let builder = CircuitBuilder::new()
+        .power_threshold(0.5)
+        .stage("preamp", Box::new(BJTTransistor::new()))
+        .bank("driver", vec![Box::new(FETTransistor::new()), Box::new(MOSFETTransistor::new())])
+        .node_stage("load", CircuitNode::device(Box::new(Resistor::new(1.0e3).with_load(4.0e3))))
+        .observer(Box::new(metrics));
+    println!("Stages: {}", builder.stage_names().join(" -> "));
+    
+    // Only a builder with a stage can be sealed, and only a sealed one built.
+    // CircuitBuilder::new().seal(), .build() before .seal() and .stage(..)
+    // after it are compile errors.
+    match builder.seal() {
+        Ok(sealed) => {
+            let mut circuit = sealed.build();
+            println!("Built {} elements, output {:.3}", circuit.element_count(), circuit.amplify_signal(2.0, 20.0));
+            for (stage, output) in circuit.stage_outputs() {
+                println!("  {:<8} {:.3}", stage, output);
+            }
+            println!("  Power threshold exceeded {} times", totals.borrow().power_threshold_events);
+        }
+        Err(e) => println!("Error: {}", e),
+    }
+    
+    // What the types cannot rule out is reported by seal()
+    let rejected = [
+        CircuitBuilder::default().stage("gain", Box::new(BJTTransistor::new())).stage("gain", Box::new(FETTransistor::new())),
+        CircuitBuilder::default().bank("empty", Vec::new()),
+        CircuitBuilder::default().stage("gain", Box::new(BJTTransistor::new())).power_threshold(0.0),
+    ];
+    for builder in rejected {
+        if let Err(e) = builder.seal() {
+            println!("Rejected: {}", e);
+        }
+    }
+}
+
+pub fn demonstrate_factories() {
+    println!("=== FACTORIES ===");
+    
+    // The registry is the factory for a single transistor: a spec in, a configured device out
+    let registry = TransistorRegistry::with_builtin_types();
+    let specs = [
+        TransistorSpec::new("jfet", ParamValues::from([("threshold".to_string(), -2.5)])),
+        TransistorSpec::new("jfet", ParamValues::from([("threshold".to_string(), 1.0)])),
+        TransistorSpec::new("triac", ParamValues::new()),
+    ];
+    for spec in &specs {
+        match registry.produce(spec) {
+            Ok(transistor) => println!("Produced {}", transistor.describe()),
+            Err(e) => println!("Rejected: {}", e),
+        }
+    }
+    
+    // Each kit makes every part of an output stage, so the parts always match.
+    // The same 12 V drive is well within the industrial rating, not the domestic one.
+    let kits: [&dyn DeviceKit; 2] = [&IndustrialKit, &DomesticKit];
+    for kit in kits {
+        match kit.output_stage() {
+            Ok(mut circuit) => {
+                let output = circuit.amplify_signal(2.0, 12.0);
+                let parts: Vec<String> = circuit.inventory().iter().map(|info| info.name.clone()).collect();
+                let power = circuit.total_power();
+                println!("{} ({} °C, {} W): {}", kit.kit_name(), kit.rated_temperature(), kit.rated_power(), parts.join(" -> "));
+                println!(
+                    "  output {:.3}, dissipating {:.3} W, {}",
+                    output,
+                    power,
+                    if power > kit.rated_power() { "over its rating" } else { "within its rating" }
+                );
+            }
+            Err(e) => println!("{}: {}", kit.kit_name(), e),
+        }
+    }
+}
+
+fn main() {
+    demonstrate_correct_ocp();
+    println!();
+    demonstrate_plugin_registry();
+    println!();
+    demonstrate_config_circuits();
+    println!();
+    demonstrate_transistor_models();
+    println!();
+    demonstrate_transistor_params();
+    println!();
+    demonstrate_circuit_topology();
+    println!();
+    demonstrate_ac_sweep();
+    println!();
+    demonstrate_waveforms();
+    println!();
+    demonstrate_netlist();
+    println!();
+    demonstrate_circuit_elements();
+    println!();
+    demonstrate_thermal();
+    println!();
+    demonstrate_decorators();
+    println!();
+    demonstrate_enum_dispatch();
+    println!();
+    demonstrate_validation();
+    println!();
+    demonstrate_observers();
+    println!();
+    demonstrate_circuit_builder();
+    println!();
+    demonstrate_factories();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn assert_close(actual: f64, expected: f64) {
+        assert!((actual - expected).abs() < 1e-9, "{} != {}", actual, expected);
+    }
+    
+    fn values(pairs: &[(&str, f64)]) -> ParamValues {
+        pairs.iter().map(|(key, value)| (key.to_string(), *value)).collect()
+    }
+    
+    fn output_of(transistor: &mut dyn Transistor, signal: f64, input: f64) -> f64 {
+        transistor.base(signal);
+        transistor.collector(input);
+        transistor.output()
+    }
+    
+    // TransistorParams at the edges of their ranges
+    
+    #[test]
+    fn presets_and_neutral_defaults_are_valid() {
+        for params in [
+            TransistorParams::default(),
+            TransistorParams::bjt(),
+            TransistorParams::fet(),
+            TransistorParams::mosfet(),
+            TransistorParams::jfet(),
+            TransistorParams::igbt(),
+        ] {
+            assert_eq!(params.validate(), Ok(()), "{:?}", params);
+        }
+    }
+    
+    #[test]
+    fn limits_at_their_boundaries_are_accepted() {
+        let pinned = TransistorParams { min_output: 1.0, max_output: 1.0, gain: 0.0, ..TransistorParams::bjt() };
+        assert_eq!(pinned.validate(), Ok(()));
+        assert_eq!(pinned.clamp(-5.0), 1.0);
+        assert!(pinned.is_clipping(1.0));
+        
+        assert!(TransistorParams { min_output: 1.0, max_output: 0.999, ..TransistorParams::bjt() }.validate().is_err());
+        assert!(TransistorParams { gain: -0.001, ..TransistorParams::bjt() }.validate().is_err());
+        assert!(TransistorParams { cutoff_frequency: 0.0, ..TransistorParams::bjt() }.validate().is_err());
+        assert!(TransistorParams { thermal_capacitance: 0.0, ..TransistorParams::bjt() }.validate().is_err());
+    }
+    
+    #[test]
+    fn nan_is_rejected_in_every_field() {
+        for name in PARAM_NAMES {
+            let error = apply_params(TransistorParams::bjt(), &values(&[(name, f64::NAN)]), &[]).unwrap_err();
+            assert!(error.starts_with(name), "{}: {}", name, error);
+        }
+    }
+    
+    #[test]
+    fn only_limits_and_cutoff_may_be_infinite() {
+        for name in ["gain", "threshold", "temperature_coefficient", "temperature", "max_temperature", "thermal_resistance", "thermal_capacitance"] {
+            let error = apply_params(TransistorParams::bjt(), &values(&[(name, f64::INFINITY)]), &[]).unwrap_err();
+            assert_eq!(error, format!("{} must be finite, got inf", name));
+        }
+        let unbounded = values(&[("min_output", f64::NEG_INFINITY), ("max_output", f64::INFINITY), ("cutoff_frequency", f64::INFINITY)]);
+        assert!(apply_params(TransistorParams::bjt(), &unbounded, &[]).is_ok());
+        assert!(apply_params(TransistorParams::bjt(), &values(&[("min_output", f64::INFINITY), ("max_output", f64::INFINITY)]), &[]).is_err());
+        assert!(apply_params(TransistorParams::bjt(), &values(&[("max_output", f64::NEG_INFINITY), ("min_output", f64::NEG_INFINITY)]), &[]).is_err());
+    }
+    
+    #[test]
+    fn registry_rejects_non_finite_values_from_files() {
+        let registry = TransistorRegistry::with_builtin_types();
+        let error = registry.create_with("mosfet", &values(&[("gain", f64::NAN)])).unwrap_err();
+        assert_eq!(error, "Invalid mosfet parameters: gain must be finite, got NaN");
+        assert!(registry.create_with("igbt", &values(&[("saturation_voltage", f64::NAN)])).is_err());
+        assert!(registry.circuit_from_netlist("Q1 vcc in out bjt gain=nan").is_err());
+    }
+    
+    #[test]
+    fn output_is_clamped_exactly_at_the_saturation_limits() {
+        let limited = TransistorParams { max_output: 1.0, min_output: -1.0, ..TransistorParams::bjt() };
+        let mut bjt = BJTTransistor::with_params(limited);
+        assert_close(output_of(&mut bjt, 2.0, 4.0), 0.8);
+        assert!(!bjt.is_clipping());
+        assert_close(output_of(&mut bjt, 2.0, 5.0), 1.0);
+        assert!(bjt.is_clipping(), "exactly at the limit counts as clipping");
+        assert_close(output_of(&mut bjt, 2.0, 50.0), 1.0);
+        assert_close(output_of(&mut bjt, -2.0, 50.0), -1.0);
+    }
+    
+    #[test]
+    fn conduction_starts_just_above_the_threshold() {
+        let mut mosfet = MOSFETTransistor::new();
+        assert_eq!(output_of(&mut mosfet, 0.7, 5.0), 0.0);
+        assert!(mosfet.is_cut_off());
+        assert_close(output_of(&mut mosfet, 0.8, 5.0), 5.0 * 0.1 * 0.2);
+        assert!(!mosfet.is_cut_off());
+    }
+    
+    #[test]
+    fn gain_drifts_with_temperature_but_never_goes_negative() {
+        let at = |temperature| TransistorParams { temperature_coefficient: 0.005, temperature, ..TransistorParams::bjt() };
+        assert_close(at(REFERENCE_TEMPERATURE).effective_gain(), 0.1);
+        assert_close(at(75.0).effective_gain(), 0.125);
+        assert_close(at(-25.0).effective_gain(), 0.075);
+        assert_eq!(at(-200.0).effective_gain(), 0.0);
+        assert_eq!(TransistorParams { temperature: 151.0, ..TransistorParams::bjt() }.effective_gain(), 0.0, "shut down");
+    }
+    
+    // TransistorRegistry: unknown identifiers and duplicate registrations
+    
+    fn names_of(circuit: &Circuit) -> Vec<String> {
+        circuit.inventory().into_iter().map(|info| info.name).collect()
+    }
+    
+    #[test]
+    fn builtin_types_are_registered_in_alphabetical_order() {
+        let registry = TransistorRegistry::with_builtin_types();
+        assert_eq!(registry.names(), ["bjt", "darlington", "fet", "igbt", "jfet", "mosfet"]);
+        assert!(registry.is_registered("mosfet"));
+        assert!(!registry.is_registered("MOSFET"), "names are case-sensitive");
+        assert!(TransistorRegistry::default().names().is_empty());
+    }
+    
+    #[test]
+    fn unknown_type_lists_the_registered_ones() {
+        let registry = TransistorRegistry::with_builtin_types();
+        let error = registry.create("thyristor").err().unwrap();
+        assert_eq!(error, "Unknown transistor type: thyristor (registered: bjt, darlington, fet, igbt, jfet, mosfet)");
+    }
+    
+    #[test]
+    fn duplicate_registration_keeps_the_original_factory() {
+        let mut registry = TransistorRegistry::with_builtin_types();
+        let error = registry.register("bjt", || Box::new(FETTransistor::new())).unwrap_err();
+        assert_eq!(error, "Transistor type already registered: bjt");
+        assert_eq!(registry.create("bjt").unwrap().describe().name, "BJT");
+        
+        let error = registry.register_configurable("mosfet", |_| Ok(Box::new(FETTransistor::new()))).unwrap_err();
+        assert_eq!(error, "Transistor type already registered: mosfet");
+    }
+    
+    #[test]
+    fn registered_plugin_is_built_by_name_and_takes_no_parameters() {
+        let mut registry = TransistorRegistry::new();
+        registry.register("fet", || Box::new(FETTransistor::new())).unwrap();
+        assert_eq!(registry.create("fet").unwrap().describe().name, "FET");
+        
+        let error = registry.create_with("fet", &values(&[("gain", 0.2)])).err().unwrap();
+        assert_eq!(error, "Invalid fet parameters: unexpected parameter gain (takes none)");
+    }
+    
+    #[test]
+    fn circuits_are_built_in_the_listed_order() {
+        let registry = TransistorRegistry::with_builtin_types();
+        let circuit = registry.circuit_from_config(" bjt,mosfet , bjt ").unwrap();
+        assert_eq!(names_of(&circuit), ["BJT", "MOSFET", "BJT"]);
+        assert_eq!(registry.build_circuit::<&str>(&[]).unwrap().element_count(), 0);
+    }
+    
+    #[test]
+    fn one_bad_identifier_builds_nothing() {
+        let registry = TransistorRegistry::with_builtin_types();
+        let error = registry.build_circuit(&["bjt", "thyristor", "mosfet"]).err().unwrap();
+        assert!(error.starts_with("Unknown transistor type: thyristor"), "{}", error);
+        
+        for config in ["", "bjt,", "bjt,,mosfet", " , "] {
+            let error = registry.circuit_from_config(config).err().unwrap();
+            assert!(error.starts_with("Empty transistor type in circuit config"), "{:?}: {}", config, error);
+        }
+    }
+    
+    // CircuitConfig: parse errors and invalid circuit files
+    
+    #[test]
+    fn shipped_circuit_files_parse_and_build() {
+        let config = CircuitConfig::from_toml(include_str!("circuits/two_stage.toml")).unwrap();
+        assert_eq!(config.name.as_deref(), Some("two-stage"));
+        assert_eq!(config.transistors[1], TransistorSpec::new("mosfet", values(&[("threshold", 0.5), ("gain", 0.25)])));
+        assert_eq!(names_of(&Circuit::from_config(&config).unwrap()), ["BJT", "MOSFET"]);
+        
+        let config = CircuitConfig::from_json(include_str!("circuits/fet_bank.json")).unwrap();
+        assert_eq!(config.transistors[0].params, ParamValues::new());
+        assert_eq!(Circuit::from_config(&config).unwrap().element_count(), 3);
+    }
+    
+    #[test]
+    fn malformed_files_are_rejected_by_the_parser() {
+        for toml in [
+            "[[transistors]]\ntype = \"bjt\"\nparams = { gain = \"high\" }",
+            "[[transistors]]\nkind = \"bjt\"",
+            "name = \"missing transistors\"",
+            "transistors = []\ncolour = \"red\"",
+            "[[transistors]\ntype = \"bjt\"",
+        ] {
+            let error = CircuitConfig::from_toml(toml).unwrap_err();
+            assert!(error.starts_with("Invalid circuit TOML: "), "{:?}: {}", toml, error);
+        }
+        for json in [r#"{"transistors": [{"type": 3}]}"#, r#"{"transistors": "bjt"}"#, "{", ""] {
+            let error = CircuitConfig::from_json(json).unwrap_err();
+            assert!(error.starts_with("Invalid circuit JSON: "), "{:?}: {}", json, error);
+        }
+    }
+    
+    #[test]
+    fn config_errors_name_the_offending_transistor() {
+        let registry = TransistorRegistry::with_builtin_types();
+        let config = CircuitConfig::from_toml("[[transistors]]\ntype = \"bjt\"\n\n[[transistors]]\ntype = \"mosfet\"\nparams = { treshold = 0.5 }").unwrap();
+        let error = registry.circuit_from(&config).err().unwrap();
+        assert!(error.starts_with("Transistor 2: Invalid mosfet parameters: unknown parameter treshold (expected gain, "), "{}", error);
+        
+        let config = CircuitConfig::from_json(r#"{"transistors": [{"type": "thyristor"}]}"#).unwrap();
+        assert!(registry.circuit_from(&config).err().unwrap().starts_with("Transistor 1: Unknown transistor type: thyristor"));
+        
+        let config = CircuitConfig::from_toml("[[transistors]]\ntype = \"jfet\"\nparams = { threshold = 1.0 }").unwrap();
+        assert_eq!(
+            registry.circuit_from(&config).err().unwrap(),
+            "Transistor 1: Invalid jfet parameters: threshold (the pinch-off voltage) must be negative, got 1"
+        );
+    }
+    
+    #[test]
+    fn config_without_transistors_is_rejected() {
+        let config = CircuitConfig::from_toml("transistors = []").unwrap();
+        assert_eq!(Circuit::from_config(&config).err().unwrap(), "Circuit config lists no transistors");
+    }
+    
+    #[test]
+    fn load_chooses_the_format_by_extension() {
+        let dir = std::env::temp_dir().join(format!("transistor-config-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let json = dir.join("bank.json");
+        std::fs::write(&json, include_str!("circuits/fet_bank.json")).unwrap();
+        let yaml = dir.join("bank.yaml");
+        std::fs::write(&yaml, "transistors: []").unwrap();
+        
+        assert_eq!(CircuitConfig::load(&json).unwrap().name.as_deref(), Some("fet-bank"));
+        assert!(CircuitConfig::load(&yaml).unwrap_err().starts_with("Unsupported circuit config format"));
+        assert!(CircuitConfig::load(&dir.join("missing.toml")).unwrap_err().starts_with("Cannot read"));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+    
+    // JFET, IGBT and Darlington pair at known operating points
+    
+    #[test]
+    fn jfet_follows_the_shockley_equation() {
+        let mut jfet = JFETTransistor::new();
+        assert_close(output_of(&mut jfet, 0.0, 10.0), 10.0);
+        assert_close(output_of(&mut jfet, -2.0, 10.0), 2.5);
+        assert_close(output_of(&mut jfet, -3.0, 10.0), 0.625);
+        assert!(!jfet.is_cut_off());
+        assert_close(output_of(&mut jfet, 1.0, 10.0), 10.0);
+    }
+    
+    #[test]
+    fn jfet_is_pinched_off_at_and_below_the_pinch_off_voltage() {
+        let mut jfet = JFETTransistor::new();
+        for gate in [-4.0, -6.0] {
+            assert_eq!(output_of(&mut jfet, gate, 10.0), 0.0);
+            assert!(jfet.is_cut_off());
+            assert_eq!(jfet.power_consumption(), 0.0);
+        }
+    }
+    
+    #[test]
+    fn igbt_is_linear_above_the_threshold_until_it_saturates() {
+        let mut igbt = IGBTTransistor::new();
+        assert_eq!(output_of(&mut igbt, 5.0, 10.0), 0.0);
+        assert!(igbt.is_cut_off());
+        assert_close(output_of(&mut igbt, 6.0, 10.0), 4.0);
+        assert!(!igbt.is_cut_off());
+        assert_close(igbt.power_consumption(), 4.0 * IGBTTransistor::DEFAULT_SATURATION_VOLTAGE);
+        assert_close(output_of(&mut igbt, 8.0, 10.0), 10.0 - IGBTTransistor::DEFAULT_SATURATION_VOLTAGE);
+        assert_eq!(output_of(&mut igbt, 8.0, 1.0), 0.0, "a supply below V_CE(sat) gives nothing");
+    }
+    
+    #[test]
+    fn igbt_saturation_voltage_is_configurable() {
+        let registry = TransistorRegistry::with_builtin_types();
+        let mut igbt = registry.create_with("igbt", &values(&[("saturation_voltage", 2.0)])).unwrap();
+        assert_close(output_of(igbt.as_mut(), 8.0, 10.0), 8.0);
+        assert_eq!(igbt.to_spec().unwrap().params, values(&[("saturation_voltage", 2.0)]));
+    }
+    
+    #[test]
+    fn darlington_gains_multiply() {
+        let mut pair = DarlingtonPair::bjt(TransistorParams::bjt());
+        let mut single = BJTTransistor::new();
+        let first = output_of(&mut single, 2.0, 5.0);
+        assert_close(output_of(&mut pair, 2.0, 5.0), output_of(&mut single, first, 5.0));
+        assert_close(pair.output(), 0.5);
+        assert_eq!(pair.describe().name, "Darlington (BJT + BJT)");
+        assert_eq!(pair.to_spec(), Some(TransistorSpec::new("darlington", ParamValues::new())));
+    }
+    
+    #[test]
+    fn darlington_of_mixed_types_has_no_registry_name() {
+        let mut pair = DarlingtonPair::new(Box::new(BJTTransistor::new()), Box::new(MOSFETTransistor::new()));
+        assert_eq!(output_of(&mut pair, 1.0, 5.0), 0.0, "0.5 V from the BJT is below the MOSFET threshold");
+        assert!(pair.is_cut_off());
+        assert_close(output_of(&mut pair, 2.0, 5.0), 5.0 * 0.3 * 0.2);
+        assert!(!pair.is_cut_off());
+        assert_eq!(pair.describe().name, "Darlington (BJT + MOSFET)");
+        assert_eq!(pair.to_spec(), None);
+    }
+    
+    // CircuitNode topologies: series, parallel and named stages
+    
+    fn bjt() -> CircuitNode {
+        CircuitNode::device(Box::new(BJTTransistor::new()))
+    }
+    
+    #[test]
+    fn series_feeds_each_output_into_the_next_base() {
+        let mut chain = CircuitNode::series(vec![bjt(), bjt(), bjt()]);
+        assert_close(chain.amplify(2.0, 5.0), 0.25);
+        assert_close(chain.output(), 0.25);
+        assert_close(chain.power_consumption(), 0.5 * (2.0 + 1.0 + 0.5) * 5.0 * 0.1);
+        assert_eq!(chain.element_count(), 3);
+    }
+    
+    #[test]
+    fn parallel_branches_share_the_signal_and_add_up() {
+        let mut bank = CircuitNode::parallel(vec![bjt(), CircuitNode::device(Box::new(MOSFETTransistor::new())), bjt()]);
+        assert_close(bank.amplify(2.0, 5.0), 1.0 + 1.3 + 1.0);
+        assert_eq!(bank.inventory().into_iter().map(|info| info.name).collect::<Vec<_>>(), ["BJT", "MOSFET", "BJT"]);
+    }
+    
+    #[test]
+    fn empty_nodes_output_nothing() {
+        for mut node in [CircuitNode::series(Vec::new()), CircuitNode::parallel(Vec::new())] {
+            assert_eq!(node.amplify(2.0, 5.0), 0.0);
+            assert_eq!(node.element_count(), 0);
+        }
+        assert_eq!(Circuit::with_nodes(Vec::new()).amplify_signal(2.0, 5.0), 0.0);
+    }
+    
+    #[test]
+    fn nested_stages_report_their_outputs_outermost_first() {
+        let mut circuit = Circuit::with_nodes(vec![CircuitNode::stage(
+            "amplifier",
+            CircuitNode::series(vec![
+                CircuitNode::stage("pre", bjt()),
+                CircuitNode::stage("bank", CircuitNode::parallel(vec![bjt(), bjt()])),
+            ]),
+        )]);
+        assert_close(circuit.amplify_signal(2.0, 5.0), 1.0);
+        let stages = circuit.stage_outputs();
+        assert_eq!(stages.iter().map(|(name, _)| *name).collect::<Vec<_>>(), ["amplifier", "pre", "bank"]);
+        for ((_, actual), expected) in stages.iter().zip([1.0, 1.0, 1.0]) {
+            assert_close(*actual, expected);
+        }
+        assert_eq!(circuit.element_count(), 3);
+    }
+    
+    #[test]
+    fn top_level_nodes_are_independent_branches() {
+        let mut circuit = Circuit::with_nodes(vec![CircuitNode::series(vec![bjt(), bjt()]), bjt()]);
+        assert_close(circuit.amplify_signal(2.0, 5.0), 0.5 + 1.0);
+        assert_close(circuit.total_power(), 0.5 * (2.0 + 1.0) * 5.0 * 0.1 + 0.5);
+    }
+    
+    // Frequency response: unity at DC, -3 dB at the cutoff, and AC sweeps
+    
+    #[test]
+    fn single_pole_response_is_unity_at_dc_and_minus_3_db_at_cutoff() {
+        let params = TransistorParams::bjt();
+        assert_eq!(params.frequency_response(0.0), 1.0);
+        assert_close(params.frequency_response(1.0e6), std::f64::consts::FRAC_1_SQRT_2);
+        assert_close(params.frequency_response(1.0e7), 1.0 / 101.0_f64.sqrt());
+        assert_eq!(TransistorParams::default().frequency_response(1.0e12), 1.0, "no cutoff, no roll-off");
+        assert_close(DarlingtonPair::bjt(params).frequency_response(1.0e6), 0.5);
+    }
+    
+    #[test]
+    fn sweep_finds_the_cutoff_of_a_single_transistor() {
+        let mut circuit = Circuit::with_transistors(vec![Box::new(BJTTransistor::new())]);
+        let sweep = circuit.ac_sweep(2.0, 5.0, 1.0e3..=1.0e9, 7).unwrap();
+        assert_eq!(sweep.points.len(), 7);
+        assert_close(sweep.points[0].frequency_hz, 1.0e3);
+        assert!((sweep.points[6].frequency_hz - 1.0e9).abs() < 1.0e-3);
+        assert!((sweep.points[0].gain - 0.5).abs() < 1.0e-6, "flat well below the cutoff");
+        let cutoff = sweep.cutoff_frequency().unwrap();
+        assert!((cutoff / 1.0e6 - 1.0).abs() < 1.0e-9, "{}", cutoff);
+        assert!(sweep.points.windows(2).all(|pair| pair[1].gain < pair[0].gain));
+    }
+    
+    #[test]
+    fn sweep_below_the_cutoff_has_no_cutoff() {
+        let mut circuit = Circuit::with_transistors(vec![Box::new(MOSFETTransistor::new())]);
+        let sweep = circuit.ac_sweep(2.0, 5.0, 1.0..=1.0e4, 5).unwrap();
+        assert_eq!(sweep.cutoff_frequency(), None);
+        assert!(sweep.to_csv().starts_with("frequency_hz,gain,gain_db\n1,0.65,"));
+        assert_eq!(sweep.to_csv().lines().count(), 6);
+    }
+    
+    #[test]
+    fn sweep_leaves_the_circuit_at_its_dc_operating_point() {
+        let mut circuit = Circuit::with_transistors(vec![Box::new(BJTTransistor::new())]);
+        circuit.ac_sweep(2.0, 5.0, 1.0e5..=1.0e8, 4).unwrap();
+        assert_close(circuit.nodes[0].output(), 1.0);
+    }
+    
+    #[test]
+    fn sweep_rejects_unusable_ranges() {
+        let mut circuit = Circuit::with_transistors(vec![Box::new(BJTTransistor::new())]);
+        assert!(circuit.ac_sweep(2.0, 5.0, 0.0..=1.0e6, 10).unwrap_err().starts_with("Sweep range must be positive"));
+        assert!(circuit.ac_sweep(2.0, 5.0, 1.0e6..=1.0e6, 10).is_err());
+        assert!(circuit.ac_sweep(2.0, 5.0, 1.0e6..=1.0e3, 10).is_err());
+        assert_eq!(circuit.ac_sweep(2.0, 5.0, 1.0..=1.0e6, 1).unwrap_err(), "A sweep needs at least 2 points, got 1");
+        assert_eq!(circuit.ac_sweep(0.0, 5.0, 1.0..=1.0e6, 10).unwrap_err(), "Cannot measure gain with a zero input signal");
+    }
+    
+    // Signal sources, waveform measurements and distortion
+    
+    fn assert_near(actual: f64, expected: f64, tolerance: f64) {
+        assert!((actual - expected).abs() < tolerance, "{} != {} ± {}", actual, expected, tolerance);
+    }
+    
+    #[test]
+    fn sine_wave_measures_as_expected() {
+        let wave = Waveform::from_source(&mut SineWave::new(2.0, 100.0, 10_000.0).with_offset(1.0), 1000);
+        assert_eq!(wave.samples.len(), 1000);
+        assert_near(wave.mean(), 1.0, 1.0e-9);
+        assert_near(wave.rms(), (1.0 + 2.0_f64).sqrt(), 1.0e-9);
+        assert_near(wave.peak(), 3.0, 1.0e-9);
+        assert_near(wave.amplitude_at(100.0), 2.0, 1.0e-9);
+        assert_near(wave.amplitude_at(300.0), 0.0, 1.0e-9);
+        assert!(wave.thd(100.0, 10) < 1.0e-9);
+    }
+    
+    #[test]
+    fn square_wave_has_odd_harmonics() {
+        let wave = Waveform::from_source(&mut SquareWave::new(1.0, 100.0, 10_000.0), 1000);
+        assert_eq!(wave.mean(), 0.0);
+        assert_eq!(wave.rms(), 1.0);
+        assert_near(wave.amplitude_at(100.0), 4.0 / PI, 0.01);
+        assert_near(wave.amplitude_at(200.0), 0.0, 1.0e-9);
+        assert_near(wave.amplitude_at(300.0), 4.0 / (3.0 * PI), 0.01);
+        // Harmonics 3, 5, 7 and 9 of an ideal square wave
+        let expected = [3.0_f64, 5.0, 7.0, 9.0].iter().map(|k| k.powi(-2)).sum::<f64>().sqrt();
+        assert_near(wave.thd(100.0, 9), expected, 0.01);
+    }
+    
+    #[test]
+    fn noise_is_reproducible_and_bounded() {
+        let first = Waveform::from_source(&mut NoiseSource::new(0.5, 1000.0, 42).with_offset(2.0), 2000);
+        let second = Waveform::from_source(&mut NoiseSource::new(0.5, 1000.0, 42).with_offset(2.0), 2000);
+        assert_eq!(first, second);
+        assert!(first.samples.iter().all(|x| (1.5..2.5).contains(x)));
+        assert_near(first.mean(), 2.0, 0.05);
+        assert_ne!(first, Waveform::from_source(&mut NoiseSource::new(0.5, 1000.0, 43).with_offset(2.0), 2000));
+        
+        let zero_seed = Waveform::from_source(&mut NoiseSource::new(1.0, 1000.0, 0), 10);
+        assert!(zero_seed.samples.iter().any(|x| *x != zero_seed.samples[0]), "seed 0 must not get stuck");
+    }
+    
+    #[test]
+    fn empty_waveform_measures_zero() {
+        let wave = Waveform { sample_rate: 1000.0, samples: Vec::new() };
+        assert_eq!((wave.mean(), wave.rms(), wave.peak(), wave.amplitude_at(50.0), wave.thd(50.0, 5)), (0.0, 0.0, 0.0, 0.0, 0.0));
+    }
+    
+    #[test]
+    fn linear_circuit_scales_without_distortion() {
+        let mut circuit = Circuit::with_transistors(vec![Box::new(BJTTransistor::new())]);
+        let output = circuit.process_waveform(&mut SineWave::new(1.0, 100.0, 10_000.0), 1000, 5.0);
+        assert_eq!(output.sample_rate, 10_000.0);
+        assert_near(output.amplitude_at(100.0), 0.5, 1.0e-9);
+        assert!(output.thd(100.0, 10) < 1.0e-9);
+    }
+    
+    #[test]
+    fn clipping_circuit_distorts() {
+        let limited = TransistorParams { min_output: -0.25, max_output: 0.25, ..TransistorParams::bjt() };
+        let mut circuit = Circuit::with_transistors(vec![Box::new(BJTTransistor::with_params(limited))]);
+        let output = circuit.process_waveform(&mut SineWave::new(1.0, 100.0, 10_000.0), 1000, 5.0);
+        assert_near(output.peak(), 0.25, 1.0e-12);
+        assert_near(output.amplitude_at(200.0), 0.0, 1.0e-9);
+        assert!(output.thd(100.0, 10) > 0.1, "thd {}", output.thd(100.0, 10));
+    }
+    
+    // Netlist export and import
+    
+    fn two_stage_amplifier() -> Circuit {
+        let preamp = TransistorParams { gain: 1.0, ..TransistorParams::bjt() };
+        let mut amplifier = Circuit::new();
+        amplifier.add_node(CircuitNode::series(vec![
+            CircuitNode::stage("preamp", CircuitNode::device(Box::new(BJTTransistor::with_params(preamp)))),
+            CircuitNode::stage(
+                "output",
+                CircuitNode::parallel(vec![
+                    CircuitNode::device(Box::new(FETTransistor::new())),
+                    CircuitNode::device(Box::new(MOSFETTransistor::new())),
+                ]),
+            ),
+        ]));
+        amplifier
+    }
+    
+    #[test]
+    fn netlist_lists_one_line_per_transistor() {
+        let netlist = two_stage_amplifier().to_netlist().unwrap();
+        assert_eq!(
+            netlist,
+            "* OCP amplifier circuit\n\
+             Q1 vcc in n1 bjt gain=1 stage=preamp\n\
+             Q2 vcc n1 out fet stage=output\n\
+             Q3 vcc n1 out mosfet stage=output\n\
+             .end\n"
+        );
+    }
+    
+    #[test]
+    fn netlist_round_trip_keeps_topology_stages_and_output() {
+        let mut original = two_stage_amplifier();
+        let netlist = original.to_netlist().unwrap();
+        let mut rebuilt = Circuit::from_netlist(&netlist).unwrap();
+        assert_eq!(rebuilt.to_netlist().unwrap(), netlist);
+        assert_eq!(rebuilt.inventory(), original.inventory());
+        assert_close(rebuilt.amplify_signal(0.2, 5.0), original.amplify_signal(0.2, 5.0));
+        assert_eq!(rebuilt.stage_outputs(), original.stage_outputs());
+    }
+    
+    #[test]
+    fn top_level_transistors_come_back_as_separate_nodes() {
+        let netlist = "* bank\nq1 vcc in out bjt\n\n  Q2 vcc in out jfet threshold=-2  \n.END\nQ3 this is ignored\n";
+        let circuit = Circuit::from_netlist(netlist).unwrap();
+        assert_eq!(circuit.nodes.len(), 2);
+        assert_eq!(names_of(&circuit), ["BJT", "JFET"]);
+        assert!(circuit.to_netlist().unwrap().contains("Q2 vcc in out jfet threshold=-2\n"));
+        assert_eq!(Circuit::from_netlist("* nothing\n.end").unwrap().element_count(), 0);
+    }
+    
+    #[test]
+    fn malformed_netlist_lines_are_rejected_with_their_line_number() {
+        for (netlist, expected) in [
+            ("R1 in out 1k", "Line 1: unsupported element R1 (only transistors, Q..., are supported)"),
+            ("* sim\n.tran 1ms", "Line 2: unsupported directive .tran"),
+            ("Q1 vcc in out bjt\nq1 vcc in out bjt", "Line 2: duplicate element q1"),
+            ("Q1 vcc in out", "Line 1: Q1 needs collector, base and emitter nets and a type"),
+            ("Q1 gnd in out bjt", "Line 1: Q1: collector must be on vcc, got gnd"),
+            ("Q1 vcc out in bjt", "Line 1: Q1: cannot drive in from out"),
+            ("Q1 vcc n1 n1 bjt", "Line 1: Q1: cannot drive n1 from n1"),
+            ("Q1 vcc in out bjt gain", "Line 1: expected key=value, got gain"),
+            ("Q1 vcc in out bjt gain=high", "Line 1: gain is not a number: high"),
+            ("Q1 vcc in out triac", "Line 1: Unknown transistor type: triac (registered: bjt, darlington, fet, igbt, jfet, mosfet)"),
+        ] {
+            assert_eq!(Circuit::from_netlist(netlist).err().unwrap(), expected, "{:?}", netlist);
+        }
+    }
+    
+    #[test]
+    fn netlist_must_connect_in_to_out() {
+        for netlist in ["Q1 vcc in n1 bjt", "Q1 vcc n1 out bjt", "Q1 vcc in n1 bjt\nQ2 vcc n1 out bjt\nQ3 vcc n1 n2 bjt"] {
+            let error = Circuit::from_netlist(netlist).err().unwrap();
+            assert_eq!(error, "Netlist does not form a series-parallel circuit from in to out", "{:?}", netlist);
+        }
+    }
+    
+    #[test]
+    fn circuits_without_a_netlist_form_are_not_exported() {
+        let mut hybrid = Circuit::new();
+        hybrid.add_transistor(Box::new(DarlingtonPair::new(Box::new(MOSFETTransistor::new()), Box::new(IGBTTransistor::new()))));
+        assert_eq!(hybrid.to_netlist().unwrap_err(), "Cannot export Darlington (MOSFET + IGBT): it has no registry type");
+        
+        let mut empty = Circuit::new();
+        empty.add_node(CircuitNode::parallel(Vec::new()));
+        assert_eq!(empty.to_netlist().unwrap_err(), "Cannot export an empty series or parallel node");
+        
+        let mut spaced = Circuit::new();
+        spaced.add_node(CircuitNode::stage("pre amp", bjt()));
+        assert!(spaced.to_netlist().unwrap_err().starts_with("Cannot export stage \"pre amp\""));
+    }
+    
+    // Passive elements: RC low-pass, resistor divider and diode
+    
+    // 1 kΩ and 1 mF: τ = 1 s
+    fn low_pass() -> Capacitor {
+        Capacitor::new(1.0e-3, 1.0e3)
+    }
+    
+    #[test]
+    fn rc_step_response_follows_the_time_constant() {
+        assert_close(low_pass().time_constant(), 1.0);
+        let mut rc = CircuitNode::device(Box::new(low_pass()));
+        assert_close(rc.step(1.0, 0.0, 1.0), 1.0 - (-1.0_f64).exp());
+        for _ in 0..4 {
+            rc.step(1.0, 0.0, 1.0);
+        }
+        assert_close(rc.output(), 1.0 - (-5.0_f64).exp());
+        
+        // The update is exact, so the step size does not change the result
+        let mut fine = CircuitNode::device(Box::new(low_pass()));
+        for _ in 0..10 {
+            fine.step(1.0, 0.0, 0.1);
+        }
+        assert_close(fine.output(), 1.0 - (-1.0_f64).exp());
+    }
+    
+    #[test]
+    fn rc_settles_at_steady_state_and_ignores_empty_steps() {
+        let mut rc = CircuitNode::device(Box::new(low_pass()));
+        assert_eq!(rc.step(1.0, 0.0, 0.0), 0.0);
+        assert_eq!(rc.amplify(2.5, 0.0), 2.5);
+        assert_eq!(rc.power_consumption(), 0.0, "no charging current once settled");
+    }
+    
+    #[test]
+    fn rc_is_3_db_down_at_its_cutoff() {
+        let rc = low_pass();
+        assert_close(rc.cutoff_frequency(), 1.0 / (2.0 * PI));
+        assert_close(rc.frequency_response(rc.cutoff_frequency()), std::f64::consts::FRAC_1_SQRT_2);
+        assert_eq!(rc.frequency_response(0.0), 1.0);
+        
+        let mut filter = Circuit::new();
+        filter.add_element(Box::new(low_pass()));
+        let sweep = filter.ac_sweep(1.0, 0.0, 0.01..=100.0, 9).unwrap();
+        assert_near(sweep.cutoff_frequency().unwrap(), 0.1_f64.sqrt(), 1.0e-9); // the first point past 1/(2π) Hz
+    }
+    
+    #[test]
+    fn rc_attenuates_a_sine_at_its_cutoff_in_the_time_domain() {
+        let cutoff = low_pass().cutoff_frequency();
+        let mut filter = Circuit::new();
+        filter.add_element(Box::new(low_pass()));
+        let mut sine = SineWave::new(1.0, cutoff, cutoff * 1000.0);
+        // Let the start-up transient die away before measuring ten periods
+        filter.process_waveform(&mut sine, 20_000, 0.0);
+        let settled = filter.process_waveform(&mut sine, 10_000, 0.0);
+        assert_near(settled.amplitude_at(cutoff), std::f64::consts::FRAC_1_SQRT_2, 0.01);
+    }
+    
+    #[test]
+    fn resistor_divides_against_its_load() {
+        let mut open = Resistor::new(1.0e3);
+        open.drive(3.0, 0.0);
+        assert_eq!((open.output(), open.power_consumption()), (3.0, 0.0));
+        
+        let mut divider = Resistor::new(1.0e3).with_load(2.0e3);
+        divider.drive(3.0, 0.0);
+        assert_close(divider.output(), 2.0);
+        assert_close(divider.power_consumption(), 3.0 * 1.0e-3);
+    }
+    
+    #[test]
+    fn diode_conducts_above_its_forward_voltage() {
+        let mut diode = Diode::new();
+        diode.drive(2.0, 0.0);
+        assert_close(diode.output(), 1.3);
+        assert_close(diode.power_consumption(), 0.7 * 1.3);
+        for signal in [0.7, 0.2, -3.0] {
+            diode.drive(signal, 0.0);
+            assert_eq!(diode.output(), 0.0);
+            assert!(diode.is_cut_off());
+        }
+        let mut schottky = Diode::with_forward_voltage(0.3);
+        schottky.drive(0.5, 0.0);
+        assert_close(schottky.output(), 0.2);
+    }
+    
+    #[test]
+    fn passive_elements_chain_after_a_transistor() {
+        let mut chain = Circuit::new();
+        chain.add_node(CircuitNode::series(vec![
+            bjt(),
+            CircuitNode::device(Box::new(Resistor::new(1.0e3).with_load(1.0e3))),
+            CircuitNode::device(Box::new(Diode::new())),
+        ]));
+        assert_close(chain.amplify_signal(4.0, 5.0), 0.3);
+        assert_eq!(names_of(&chain), ["BJT", "Resistor 1000 Ω", "Diode"]);
+    }
+    
+    // Junction heating, thermal runaway and shutdown
+    
+    fn hot_bjt() -> TransistorParams {
+        TransistorParams { temperature_coefficient: 0.01, ..TransistorParams::bjt() }
+    }
+    
+    fn driven(transistor: Box<dyn Transistor>, signal: f64, input: f64) -> Circuit {
+        let mut circuit = Circuit::default();
+        circuit.add_transistor(transistor);
+        circuit.amplify_signal(signal, input);
+        circuit
+    }
+    
+    #[test]
+    fn junction_heats_exponentially_towards_steady_state() {
+        // 50 °C/W and 0.02 J/°C: τ = 1 s, and 1 W settles 50 °C above ambient
+        let mut params = TransistorParams::bjt();
+        assert_eq!(params.steady_state_temperature(1.0, 25.0), 75.0);
+        params.heat(1.0, 1.0, 25.0);
+        assert_close(params.temperature, 25.0 + 50.0 * (1.0 - (-1.0_f64).exp()));
+        params.heat(0.0, 1000.0, 25.0);
+        assert_close(params.temperature, 25.0);
+    }
+    
+    #[test]
+    fn shut_down_junction_has_no_gain() {
+        let mut bjt = BJTTransistor::with_params(TransistorParams { temperature: 150.5, ..TransistorParams::bjt() });
+        assert!(bjt.is_shut_down());
+        assert_eq!(output_of(&mut bjt, 2.0, 10.0), 0.0);
+        assert_eq!(bjt.power_consumption(), 0.0);
+        assert!(!BJTTransistor::with_params(TransistorParams { temperature: 150.0, ..TransistorParams::bjt() }).is_shut_down());
+    }
+    
+    #[test]
+    fn moderate_power_settles_below_the_limit() {
+        let mut circuit = driven(Box::new(BJTTransistor::with_params(hot_bjt())), 2.0, 10.0);
+        let report = circuit.simulate(10.0, 25.0).unwrap();
+        assert_close(report.initial_output, 2.0);
+        assert!(report.final_output > 3.9 && report.final_output < 4.0, "gain rose with temperature: {}", report.final_output);
+        let junction = &report.junctions[0];
+        assert_eq!((junction.element, junction.name.as_str()), (1, "BJT"));
+        assert!(junction.final_temperature > 120.0 && junction.final_temperature < 150.0, "{}", junction.final_temperature);
+        assert_eq!(junction.peak_temperature, junction.final_temperature);
+    }
+    
+    #[test]
+    fn higher_power_runs_away_and_shuts_down() {
+        let mut circuit = driven(Box::new(BJTTransistor::with_params(hot_bjt())), 2.0, 20.0);
+        let error = circuit.simulate(10.0, 25.0).unwrap_err();
+        assert!(error.starts_with("Thermal runaway: BJT (element 1) reached 150."), "{}", error);
+        assert!(error.ends_with("output fell from 4.00 to 0.00"), "{}", error);
+        assert_eq!(circuit.amplify_signal(2.0, 20.0), 0.0, "stays shut down");
+    }
+    
+    #[test]
+    fn negative_temperature_coefficient_limits_itself() {
+        let cool = TransistorParams { temperature_coefficient: -0.004, ..TransistorParams::mosfet() };
+        let mut circuit = driven(Box::new(MOSFETTransistor::with_params(cool)), 5.0, 6.0);
+        let report = circuit.simulate(10.0, 25.0).unwrap();
+        assert!(report.final_output < report.initial_output);
+        assert!(report.junctions[0].final_temperature < 60.0);
+    }
+    
+    #[test]
+    fn only_elements_with_a_junction_are_reported() {
+        let mut circuit = Circuit::new();
+        circuit.add_element(Box::new(Diode::new()));
+        circuit.add_transistor(Box::new(DarlingtonPair::bjt(TransistorParams::bjt())));
+        circuit.amplify_signal(2.0, 5.0);
+        let report = circuit.simulate(1.0, 25.0).unwrap();
+        assert_eq!(report.junctions.len(), 1);
+        assert_eq!(report.junctions[0].element, 2);
+        assert!(report.to_string().contains("2. Darlington (BJT + BJT): peak "));
+    }
+    
+    #[test]
+    fn simulation_needs_a_drive_and_a_positive_duration() {
+        let mut idle = Circuit::new();
+        idle.add_transistor(Box::new(BJTTransistor::new()));
+        assert_eq!(idle.simulate(1.0, 25.0).unwrap_err(), "Drive the circuit with amplify_signal before simulating it");
+        
+        let mut circuit = driven(Box::new(BJTTransistor::new()), 2.0, 5.0);
+        for duration in [0.0, -1.0, f64::NAN] {
+            assert!(circuit.simulate(duration, 25.0).unwrap_err().starts_with("Simulation duration must be positive"));
+        }
+    }
+    
+    // Decorators: noise, call logging and clipping, alone and composed
+    
+    #[test]
+    fn noise_is_seeded_and_drawn_once_per_base_drive() {
+        let mut expected = NoiseSource::new(0.2, 1.0, 7);
+        let mut noisy = NoisyTransistor::new(Box::new(BJTTransistor::new()), 0.2, 7);
+        for signal in [1.0, 2.0, 3.0] {
+            let output = output_of(&mut noisy, signal, 5.0);
+            assert_close(output, signal * 0.5 + expected.next_sample());
+            assert_eq!(noisy.output(), output, "stable until the base is driven again");
+        }
+        assert_eq!(noisy.describe().name, "Noisy BJT");
+        assert!(noisy.describe().description.ends_with(", plus noise of ±0.2"));
+    }
+    
+    #[test]
+    fn logged_transistor_records_calls_in_order() {
+        let logged = LoggedTransistor::new(Box::new(BJTTransistor::new()));
+        let log = logged.call_log();
+        let mut circuit = Circuit::new();
+        circuit.add_transistor(Box::new(logged));
+        circuit.amplify_signal(2.0, 5.0);
+        assert_eq!(*log.borrow(), [TransistorCall::Base(2.0), TransistorCall::Collector(5.0), TransistorCall::Output(1.0)]);
+        
+        let mut direct = LoggedTransistor::new(Box::new(MOSFETTransistor::new()));
+        direct.base(0.5);
+        assert_eq!(direct.output(), 0.0);
+        assert_eq!(direct.calls(), [TransistorCall::Base(0.5), TransistorCall::Output(0.0)]);
+    }
+    
+    #[test]
+    fn clipped_transistor_limits_the_output_range() {
+        let mut clipped = ClippedTransistor::new(Box::new(BJTTransistor::new()), 0.0, 2.0);
+        assert_close(output_of(&mut clipped, 3.0, 5.0), 1.5);
+        assert!(!clipped.is_clipping());
+        assert_eq!(output_of(&mut clipped, 7.0, 5.0), 2.0);
+        assert!(clipped.is_clipping());
+        assert_eq!(output_of(&mut clipped, -1.0, 5.0), 0.0);
+        assert!(clipped.describe().description.ends_with(", clipped to 0..2"));
+    }
+    
+    #[test]
+    fn composition_order_decides_what_is_clipped() {
+        let mut noise_then_clip = ClippedTransistor::new(Box::new(NoisyTransistor::new(Box::new(BJTTransistor::new()), 0.5, 3)), 0.0, 2.0);
+        let mut clip_then_noise = NoisyTransistor::new(Box::new(ClippedTransistor::new(Box::new(BJTTransistor::new()), 0.0, 2.0)), 0.5, 3);
+        let mut escaped = false;
+        for step in 0..50 {
+            let signal = 4.0 + step as f64 * 0.01;
+            assert!(output_of(&mut noise_then_clip, signal, 5.0) <= 2.0);
+            escaped |= output_of(&mut clip_then_noise, signal, 5.0) > 2.0;
+        }
+        assert!(escaped, "noise added after the clip can exceed it");
+        assert_eq!(clip_then_noise.describe().name, "Noisy Clipped BJT");
+    }
+    
+    #[test]
+    fn decorators_pass_the_thermal_model_through() {
+        let params = TransistorParams { temperature_coefficient: 0.01, ..TransistorParams::bjt() };
+        let decorated = LoggedTransistor::new(Box::new(ClippedTransistor::new(
+            Box::new(NoisyTransistor::new(Box::new(BJTTransistor::with_params(params)), 0.0, 1)),
+            0.0,
+            100.0,
+        )));
+        let mut plain = driven(Box::new(BJTTransistor::with_params(params)), 2.0, 10.0);
+        let mut wrapped = driven(Box::new(decorated), 2.0, 10.0);
+        let (plain, wrapped) = (plain.simulate(5.0, 25.0).unwrap(), wrapped.simulate(5.0, 25.0).unwrap());
+        assert_close(wrapped.final_output, plain.final_output);
+        assert_close(wrapped.junctions[0].final_temperature, plain.junctions[0].final_temperature);
+        assert_eq!(wrapped.junctions[0].name, "Logged Clipped Noisy BJT");
+    }
+    
+    #[test]
+    fn decorated_transistors_have_no_registry_type() {
+        let noisy = NoisyTransistor::new(Box::new(BJTTransistor::new()), 0.1, 1);
+        assert_eq!(noisy.to_spec(), None);
+        let mut circuit = Circuit::new();
+        circuit.add_transistor(Box::new(noisy));
+        assert_eq!(circuit.to_netlist().unwrap_err(), "Cannot export Noisy BJT: it has no registry type");
+    }
+    
+    // Circuit::validate diagnostics
+    
+    #[test]
+    fn healthy_circuit_has_no_diagnostics() {
+        let mut circuit = Circuit::with_nodes(vec![CircuitNode::series(vec![bjt(), bjt()])]);
+        assert_eq!(circuit.validate(2.0, 5.0, 25.0), []);
+    }
+    
+    #[test]
+    fn empty_circuit_is_an_error() {
+        let diagnostics = Circuit::new().validate(2.0, 5.0, 25.0);
+        assert_eq!(diagnostics, [CircuitDiagnostic::EmptyCircuit]);
+        assert_eq!(diagnostics[0].severity(), Severity::Error);
+        assert_eq!(diagnostics[0].to_string(), "error: Circuit has no elements");
+    }
+    
+    #[test]
+    fn element_below_threshold_is_located_by_stage() {
+        let mut circuit = Circuit::with_nodes(vec![CircuitNode::stage(
+            "amp",
+            CircuitNode::series(vec![bjt(), CircuitNode::stage("driver", CircuitNode::device(Box::new(MOSFETTransistor::new())))]),
+        )]);
+        let diagnostics = circuit.validate(1.0, 5.0, 25.0);
+        assert_eq!(
+            diagnostics,
+            [CircuitDiagnostic::BelowThreshold { element: 2, name: "MOSFET".to_string(), stage: Some("amp/driver".to_string()), drive: 0.5 }]
+        );
+        assert_eq!(
+            diagnostics[0].to_string(),
+            "warning: MOSFET (element 2, stage amp/driver) is biased below its threshold by a drive of 0.50 and passes no signal"
+        );
+        assert_eq!(circuit.validate(2.0, 5.0, 25.0), [], "1 V from the BJT turns the MOSFET on");
+    }
+    
+    #[test]
+    fn element_without_gain_is_a_warning() {
+        let mut circuit = Circuit::new();
+        circuit.add_transistor(Box::new(BJTTransistor::with_params(TransistorParams { gain: 0.0, ..TransistorParams::bjt() })));
+        let diagnostics = circuit.validate(2.0, 5.0, 25.0);
+        assert_eq!(diagnostics, [CircuitDiagnostic::ZeroGain { element: 1, name: "BJT".to_string(), stage: None, drive: 2.0 }]);
+        assert_eq!(diagnostics[0].to_string(), "warning: BJT (element 1) outputs nothing when driven with 2.00");
+        assert_eq!(circuit.validate(0.0, 5.0, 25.0), [], "no drive, nothing expected");
+    }
+    
+    #[test]
+    fn empty_nodes_are_reported_where_they_are() {
+        let mut circuit = Circuit::with_nodes(vec![bjt(), CircuitNode::stage("bank", CircuitNode::parallel(Vec::new()))]);
+        let diagnostics = circuit.validate(2.0, 5.0, 25.0);
+        assert_eq!(diagnostics, [CircuitDiagnostic::EmptyNode { stage: Some("bank".to_string()) }]);
+        assert_eq!(diagnostics[0].to_string(), "warning: Empty series or parallel node in stage bank outputs nothing");
+    }
+    
+    #[test]
+    fn junction_that_would_overheat_is_an_error_and_is_not_heated() {
+        // 4 W into 50 °C/W settles at 225 °C
+        let mut circuit = Circuit::new();
+        circuit.add_transistor(Box::new(BJTTransistor::new()));
+        circuit.add_transistor(Box::new(BJTTransistor::with_params(TransistorParams { gain: 0.05, ..TransistorParams::bjt() })));
+        let diagnostics = circuit.validate(2.0, 40.0, 25.0);
+        assert_eq!(
+            diagnostics,
+            [CircuitDiagnostic::ThermalLimit { element: 1, name: "BJT".to_string(), stage: None, temperature: 225.0, max_temperature: 150.0 }]
+        );
+        assert_eq!(diagnostics[0].severity(), Severity::Error);
+        assert_eq!(diagnostics[0].to_string(), "error: BJT (element 1) would settle at 225.0 °C, above its 150.0 °C limit, and shut down");
+        assert_eq!(circuit.elements()[0].junction_temperature(), Some(REFERENCE_TEMPERATURE));
+    }
+    
+    #[test]
+    fn errors_outrank_warnings() {
+        assert!(Severity::Error > Severity::Warning);
+        assert_eq!(CircuitDiagnostic::EmptyNode { stage: None }.severity(), Severity::Warning);
+    }
+    
+    // Circuit observers: event order, fan-out and metrics
+    
+    #[derive(Debug, Clone, PartialEq)]
+    enum Event {
+        Stage(String, f64),
+        Clipping(usize, String, f64),
+        Power(f64, f64),
+    }
+    
+    // Events tagged with the label of the observer that saw them
+    type EventLog = Rc<RefCell<Vec<(&'static str, Event)>>>;
+    
+    // Records every event into a log shared with the test
+    #[derive(Debug)]
+    struct RecordingObserver {
+        label: &'static str,
+        log: EventLog,
+    }
+    
+    impl CircuitObserver for RecordingObserver {
+        fn on_stage_output(&mut self, stage: &str, output: f64) {
+            self.log.borrow_mut().push((self.label, Event::Stage(stage.to_string(), output)));
+        }
+        
+        fn on_clipping(&mut self, element: usize, name: &str, output: f64) {
+            self.log.borrow_mut().push((self.label, Event::Clipping(element, name.to_string(), output)));
+        }
+        
+        fn on_power_threshold_exceeded(&mut self, power: f64, threshold: f64) {
+            self.log.borrow_mut().push((self.label, Event::Power(power, threshold)));
+        }
+    }
+    
+    // A BJT preamp and a FET limited to ±1 beside it, with a 0.5 W budget
+    fn observed_circuit() -> (Circuit, EventLog) {
+        let mut circuit = Circuit::new();
+        circuit.add_node(CircuitNode::stage("preamp", bjt()));
+        circuit.add_node(CircuitNode::stage(
+            "limiter",
+            CircuitNode::device(Box::new(ClippedTransistor::new(Box::new(FETTransistor::new()), -1.0, 1.0))),
+        ));
+        circuit.set_power_threshold(0.5);
+        let log = Rc::new(RefCell::new(Vec::new()));
+        for label in ["first", "second"] {
+            circuit.add_observer(Box::new(RecordingObserver { label, log: Rc::clone(&log) }));
+        }
+        (circuit, log)
+    }
+    
+    #[test]
+    fn observers_see_stages_then_clipping_then_power_in_the_order_added() {
+        let (mut circuit, log) = observed_circuit();
+        circuit.amplify_signal(2.0, 5.0);
+        let expected = |label| {
+            [
+                (label, Event::Stage("preamp".to_string(), 1.0)),
+                (label, Event::Stage("limiter".to_string(), 1.0)),
+                (label, Event::Clipping(2, "Clipped FET".to_string(), 1.0)),
+                (label, Event::Power(0.5 + 0.3, 0.5)),
+            ]
+        };
+        let log = log.borrow();
+        assert_eq!(log.len(), 8);
+        for (actual, expected) in log.iter().zip(expected("first").into_iter().chain(expected("second"))) {
+            match (&actual.1, &expected.1) {
+                (Event::Power(power, threshold), Event::Power(expected_power, expected_threshold)) => {
+                    assert_eq!(actual.0, expected.0);
+                    assert_close(*power, *expected_power);
+                    assert_eq!(threshold, expected_threshold);
+                }
+                _ => assert_eq!(*actual, expected),
+            }
+        }
+    }
+    
+    #[test]
+    fn quiet_drive_reports_only_stage_outputs() {
+        let (mut circuit, log) = observed_circuit();
+        circuit.amplify_signal(0.1, 5.0);
+        assert!(log.borrow().iter().all(|(_, event)| matches!(event, Event::Stage(..))));
+        assert_eq!(log.borrow().len(), 4);
+    }
+    
+    #[test]
+    fn every_kind_of_drive_notifies() {
+        let (mut circuit, log) = observed_circuit();
+        // Each drive reports the preamp stage once to each observer
+        let drives = |log: &EventLog| {
+            log.borrow()
+                .iter()
+                .filter(|(label, event)| *label == "first" && matches!(event, Event::Stage(stage, _) if stage == "preamp"))
+                .count()
+        };
+        circuit.step(0.1, 5.0, 0.01);
+        assert_eq!(drives(&log), 1);
+        circuit.validate(0.1, 5.0, 25.0);
+        assert_eq!(drives(&log), 2);
+        circuit.ac_sweep(0.1, 5.0, 1.0..=1.0e3, 4).unwrap();
+        assert_eq!(drives(&log), 3, "the sweep reports its final DC drive only");
+        circuit.simulate(0.02, 25.0).unwrap();
+        assert_eq!(drives(&log), 3 + 4, "before the first step, after both steps and for the report");
+    }
+    
+    #[test]
+    fn metrics_observer_totals_events() {
+        let (mut circuit, _) = observed_circuit();
+        let metrics = MetricsObserver::new();
+        let totals = metrics.metrics();
+        circuit.add_observer(Box::new(metrics));
+        for signal in [0.1, 0.5, 2.0, 3.0] {
+            circuit.amplify_signal(signal, 5.0);
+        }
+        let totals = totals.borrow();
+        assert_eq!(totals.stage_peaks.keys().collect::<Vec<_>>(), ["limiter", "preamp"]);
+        assert_close(totals.stage_peaks["preamp"], 1.5);
+        assert_eq!(totals.stage_peaks["limiter"], 1.0);
+        assert_eq!((totals.clipping_events, totals.clipped_elements.iter().copied().collect::<Vec<_>>()), (2, vec![2]));
+        assert_eq!(totals.power_threshold_events, 2);
+        assert_close(totals.peak_power, 3.0 * 5.0 * (0.5 * 0.1 + 0.2 * 0.15));
+    }
+    
+    // CircuitBuilder: circuits it produces, seal() errors, and illegal orders
+    
+    fn staged() -> CircuitBuilder<builder::Staged> {
+        CircuitBuilder::new().stage("preamp", Box::new(BJTTransistor::new()))
+    }
+    
+    #[test]
+    fn builder_chains_its_stages_in_series() {
+        let builder = staged()
+            .bank("driver", vec![Box::new(BJTTransistor::new()), Box::new(BJTTransistor::new())])
+            .node_stage("output", CircuitNode::series(vec![bjt(), bjt()]));
+        assert_eq!(builder.stage_names(), ["preamp", "driver", "output"]);
+        let mut circuit = builder.seal().unwrap().build();
+        assert_eq!(circuit.element_count(), 5);
+        assert_close(circuit.amplify_signal(2.0, 5.0), 0.25);
+        let stages: Vec<&str> = circuit.stage_outputs().into_iter().map(|(name, _)| name).collect();
+        assert_eq!(stages, ["preamp", "driver", "output"]);
+        assert_eq!(circuit.validate(2.0, 5.0, 25.0), []);
+    }
+    
+    #[test]
+    fn builder_hands_over_observers_and_the_power_threshold() {
+        let metrics = MetricsObserver::new();
+        let totals = metrics.metrics();
+        let mut circuit = CircuitBuilder::default().observer(Box::new(metrics)).power_threshold(0.4).stage("preamp", Box::new(BJTTransistor::new())).seal().unwrap().build();
+        circuit.amplify_signal(1.0, 5.0);
+        assert_eq!(totals.borrow().power_threshold_events, 0);
+        circuit.amplify_signal(2.0, 5.0);
+        assert_eq!(totals.borrow().power_threshold_events, 1);
+        assert_close(totals.borrow().stage_peaks["preamp"], 1.0);
+    }
+    
+    #[test]
+    fn seal_rejects_what_the_types_cannot() {
+        let error = |builder: CircuitBuilder<builder::Staged>| builder.seal().err().unwrap();
+        assert_eq!(error(staged().stage(" ", Box::new(BJTTransistor::new()))), "Stage 2 has no name");
+        assert_eq!(error(staged().stage("preamp", Box::new(BJTTransistor::new()))), "Duplicate stage name 'preamp'");
+        assert_eq!(error(staged().bank("bank", Vec::new())), "Stage 'bank' has no elements");
+        assert_eq!(error(staged().power_threshold(0.0)), "Power threshold must be positive, got 0");
+        assert_eq!(error(staged().power_threshold(f64::NAN)), "Power threshold must be positive, got NaN");
+    }
+    
+    // Each file in patterns/ui is an illegal build order that must not compile,
+    // with the compiler error it gives next to it in a .stderr file
+    #[test]
+    fn illegal_build_orders_do_not_compile() {
+        let cases = Path::new(file!()).with_file_name("patterns").join("ui").join("*.rs");
+        trybuild::TestCases::new().compile_fail(cases);
+    }
+}
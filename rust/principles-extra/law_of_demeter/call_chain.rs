@@ -0,0 +1,212 @@
+// CALL CHAIN ANALYZER - a rough gauge of how far code reaches
+//
+// Finds chained expressions such as `order.customer().wallet().balance()` in
+// Rust source and counts their hops: every `.field` or `.method(..)` after the
+// receiver. A hop from `self` to one of its own fields is free, so
+// `self.wallet.debit(amount)` is one hop. The Law of Demeter allows one.
+//
+// It reads text, not types. Comments and literals are skipped, but iterator
+// adapters and builders chain on purpose and are counted like any other hops.
+
+/// One chained expression
+#[derive(Debug, Clone, PartialEq)]
+pub struct Chain {
+    /// 1-based line of the receiver
+    pub line: usize,
+    /// The chain with arguments elided, e.g. `order.customer().name()`
+    pub expression: String,
+    pub depth: usize,
+}
+
+/// Every chain in a source file, measured against a depth limit
+#[derive(Debug, Clone, PartialEq)]
+pub struct DepthReport {
+    pub chains: usize,
+    pub deepest: usize,
+    pub total_depth: usize,
+    /// Chains deeper than the limit, in source order
+    pub over_limit: Vec<Chain>,
+}
+
+impl DepthReport {
+    pub fn average_depth(&self) -> f64 {
+        if self.chains == 0 {
+            0.0
+        } else {
+            self.total_depth as f64 / self.chains as f64
+        }
+    }
+}
+
+pub fn report(source: &str, limit: usize) -> DepthReport {
+    let found = chains(source);
+    DepthReport {
+        chains: found.len(),
+        deepest: found.iter().map(|chain| chain.depth).max().unwrap_or(0),
+        total_depth: found.iter().map(|chain| chain.depth).sum(),
+        over_limit: found.into_iter().filter(|chain| chain.depth > limit).collect(),
+    }
+}
+
+/// Every chain of at least one hop, in source order
+pub fn chains(source: &str) -> Vec<Chain> {
+    let code: Vec<char> = blank_out_comments_and_literals(source).chars().collect();
+    let mut found = Vec::new();
+    let mut i = 0;
+    while i < code.len() {
+        let starts_identifier = is_identifier_start(code[i])
+            && (i == 0 || !(is_identifier_char(code[i - 1]) || code[i - 1] == '.' || code[i - 1] == ':'));
+        if !starts_identifier {
+            i += 1;
+            continue;
+        }
+        let receiver_end = scan_identifier(&code, i);
+        if let Some(chain) = scan_chain(&code, i, receiver_end) {
+            found.push(chain);
+        }
+        // Arguments may hold chains of their own, so resume right after the receiver
+        i = receiver_end;
+    }
+    found
+}
+
+fn scan_chain(code: &[char], start: usize, receiver_end: usize) -> Option<Chain> {
+    let receiver: String = code[start..receiver_end].iter().collect();
+    let mut expression = receiver.clone();
+    let mut depth = 0;
+    let mut free_hop = receiver == "self";
+    let mut i = receiver_end;
+    loop {
+        let mut next = skip_whitespace(code, i);
+        while code.get(next) == Some(&'?') {
+            expression.push('?');
+            next = skip_whitespace(code, next + 1);
+        }
+        // `.name` or `.0`, but not a `..` range
+        if code.get(next) != Some(&'.') || code.get(next + 1) == Some(&'.') {
+            break;
+        }
+        let segment_start = skip_whitespace(code, next + 1);
+        let segment_end = match code.get(segment_start) {
+            Some(&c) if is_identifier_start(c) => scan_identifier(code, segment_start),
+            Some(c) if c.is_ascii_digit() => scan_digits(code, segment_start),
+            _ => break,
+        };
+        expression.push('.');
+        expression.extend(&code[segment_start..segment_end]);
+        i = segment_end;
+
+        let mut is_call = false;
+        let after = skip_whitespace(code, i);
+        if code.get(after) == Some(&'(') {
+            let close = matching_paren(code, after)?;
+            expression.push_str(if code[after + 1..close].iter().all(|c| c.is_whitespace()) { "()" } else { "(..)" });
+            i = close + 1;
+            is_call = true;
+        }
+
+        if free_hop && !is_call {
+            // self.field: the object's own part, not a hop
+            free_hop = false;
+        } else {
+            free_hop = false;
+            depth += 1;
+        }
+    }
+    (depth > 0).then(|| Chain {
+        line: code[..start].iter().filter(|&&c| c == '\n').count() + 1,
+        expression,
+        depth,
+    })
+}
+
+/// Replaces comments and string and char literals with spaces, keeping newlines
+fn blank_out_comments_and_literals(source: &str) -> String {
+    let chars: Vec<char> = source.chars().collect();
+    let mut out = String::with_capacity(source.len());
+    let blank = |c: char| if c == '\n' { '\n' } else { ' ' };
+    let mut i = 0;
+    while i < chars.len() {
+        match (chars[i], chars.get(i + 1).copied()) {
+            ('/', Some('/')) => {
+                while i < chars.len() && chars[i] != '\n' {
+                    out.push(' ');
+                    i += 1;
+                }
+            }
+            ('/', Some('*')) => {
+                while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+                    out.push(blank(chars[i]));
+                    i += 1;
+                }
+                out.push_str("  ");
+                i += 2;
+            }
+            ('"', _) => {
+                out.push(' ');
+                i += 1;
+                while i < chars.len() && chars[i] != '"' {
+                    if chars[i] == '\\' {
+                        out.push(' ');
+                        i += 1;
+                    }
+                    if let Some(&c) = chars.get(i) {
+                        out.push(blank(c));
+                    }
+                    i += 1;
+                }
+                out.push(' ');
+                i += 1;
+            }
+            // A char literal ('x' or '\n'); anything else after a quote is a lifetime
+            ('\'', Some(next)) if next == '\\' || chars.get(i + 2) == Some(&'\'') => {
+                let close = (i + 2..chars.len()).find(|&j| chars[j] == '\'').unwrap_or(chars.len() - 1);
+                out.extend((i..=close).map(|j| blank(chars[j])));
+                i = close + 1;
+            }
+            (c, _) => {
+                out.push(c);
+                i += 1;
+            }
+        }
+    }
+    out
+}
+
+fn is_identifier_start(c: char) -> bool {
+    c.is_alphabetic() || c == '_'
+}
+
+fn is_identifier_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+fn scan_identifier(code: &[char], start: usize) -> usize {
+    (start..code.len()).find(|&i| !is_identifier_char(code[i])).unwrap_or(code.len())
+}
+
+fn scan_digits(code: &[char], start: usize) -> usize {
+    (start..code.len()).find(|&i| !code[i].is_ascii_digit()).unwrap_or(code.len())
+}
+
+fn skip_whitespace(code: &[char], start: usize) -> usize {
+    (start..code.len()).find(|&i| !code[i].is_whitespace()).unwrap_or(code.len())
+}
+
+/// Index of the `)` closing the `(` at `open`
+fn matching_paren(code: &[char], open: usize) -> Option<usize> {
+    let mut depth = 0;
+    for (i, &c) in code.iter().enumerate().skip(open) {
+        match c {
+            '(' => depth += 1,
+            ')' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
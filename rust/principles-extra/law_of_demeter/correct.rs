@@ -0,0 +1,136 @@
+// CORRECT IMPLEMENTATION - FOLLOWS THE LAW OF DEMETER
+// Each object talks only to its immediate friends: its own fields, its
+// arguments and what it creates. Checkout tells the order to pay; the order
+// tells its customer to pay; the customer tells its wallet to debit. Every
+// method reveals an intention ("pay", "debit") instead of exposing the state
+// needed to carry it out.
+
+use std::fmt;
+
+/// An amount of money, in cents
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Money(pub u64);
+
+impl fmt::Display for Money {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "${}.{:02}", self.0 / 100, self.0 % 100)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PaymentError {
+    AlreadyPaid { order: u32 },
+    InsufficientFunds { customer: String, available: Money, needed: Money },
+}
+
+impl fmt::Display for PaymentError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PaymentError::AlreadyPaid { order } => write!(f, "Order {} is already paid", order),
+            PaymentError::InsufficientFunds { customer, available, needed } => {
+                write!(f, "{} has {} but needs {}", customer, available, needed)
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Wallet {
+    balance: Money,
+}
+
+impl Wallet {
+    pub fn new(balance: Money) -> Self {
+        Wallet { balance }
+    }
+
+    /// Takes `amount` out and returns what is left, or returns what is available
+    pub fn debit(&mut self, amount: Money) -> Result<Money, Money> {
+        if amount > self.balance {
+            return Err(self.balance);
+        }
+        self.balance = Money(self.balance.0 - amount.0);
+        Ok(self.balance)
+    }
+}
+
+#[derive(Debug)]
+pub struct Customer {
+    name: String,
+    wallet: Wallet,
+}
+
+impl Customer {
+    pub fn new(name: &str, wallet: Wallet) -> Self {
+        Customer { name: name.to_string(), wallet }
+    }
+
+    /// Pays `amount` from the customer's own money; returns what is left
+    pub fn pay(&mut self, amount: Money) -> Result<Money, PaymentError> {
+        match self.wallet.debit(amount) {
+            Ok(remaining) => Ok(remaining),
+            Err(available) => Err(PaymentError::InsufficientFunds {
+                customer: self.name.clone(),
+                available,
+                needed: amount,
+            }),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+/// What a successful payment reports back
+#[derive(Debug, Clone, PartialEq)]
+pub struct Receipt {
+    pub order: u32,
+    pub customer: String,
+    pub paid: Money,
+    pub remaining: Money,
+}
+
+impl fmt::Display for Receipt {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Order {}: {} paid {}, {} left", self.order, self.customer, self.paid, self.remaining)
+    }
+}
+
+#[derive(Debug)]
+pub struct Order {
+    id: u32,
+    customer: Customer,
+    total: Money,
+    paid: bool,
+}
+
+impl Order {
+    pub fn new(id: u32, customer: Customer, total: Money) -> Self {
+        Order { id, customer, total, paid: false }
+    }
+
+    /// Charges the customer for this order, once
+    pub fn pay(&mut self) -> Result<Receipt, PaymentError> {
+        if self.paid {
+            return Err(PaymentError::AlreadyPaid { order: self.id });
+        }
+        let remaining = self.customer.pay(self.total)?;
+        self.paid = true;
+        Ok(Receipt {
+            order: self.id,
+            customer: String::from(self.customer.name()),
+            paid: self.total,
+            remaining,
+        })
+    }
+}
+
+/// The client: takes payment for an order
+pub struct Checkout;
+
+impl Checkout {
+    pub fn pay(&self, order: &mut Order) -> Result<Receipt, PaymentError> {
+        order.pay()
+    }
+}
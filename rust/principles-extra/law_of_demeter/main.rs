@@ -0,0 +1,109 @@
+// LAW OF DEMETER - "only talk to your immediate friends"
+//
+// A method should only call methods on itself, its fields, its arguments and
+// the objects it creates, not on objects those return.
+// - violation.rs: CheckoutViolation reads order.customer().wallet().balance()
+//   and sets the new balance itself
+// - correct.rs: Checkout calls order.pay(); each object passes the request on
+//   to the next through an intention-revealing method
+// - call_chain.rs: measures how deep the chains in each file go
+//
+// The demo runs the same checkout through both versions, then points the
+// analyzer at their source.
+
+mod call_chain;
+mod correct;
+mod violation;
+
+/// Deepest chain the law allows: a call on a friend, not on a friend's friend
+const DEPTH_LIMIT: usize = 1;
+
+const SOURCES: [(&str, &str); 2] = [
+    ("violation.rs", include_str!("violation.rs")),
+    ("correct.rs", include_str!("correct.rs")),
+];
+
+fn run_violation() {
+    use violation::{CheckoutViolation, Customer, Money, Order, Wallet};
+
+    let checkout = CheckoutViolation;
+    let mut orders = [
+        Order::new(1, Customer::new("Ada", Wallet::new(Money(5000))), Money(1999)),
+        Order::new(2, Customer::new("Grace", Wallet::new(Money(1000))), Money(4500)),
+    ];
+    for order in orders.iter_mut() {
+        match checkout.pay(order) {
+            Ok(receipt) => println!("  {}", receipt),
+            Err(e) => println!("  Declined: {}", e),
+        }
+    }
+    if let Err(e) = checkout.pay(&mut orders[0]) {
+        println!("  Declined: {}", e);
+    }
+}
+
+fn run_correct() {
+    use correct::{Checkout, Customer, Money, Order, Wallet};
+
+    let checkout = Checkout;
+    let mut orders = [
+        Order::new(1, Customer::new("Ada", Wallet::new(Money(5000))), Money(1999)),
+        Order::new(2, Customer::new("Grace", Wallet::new(Money(1000))), Money(4500)),
+    ];
+    for order in orders.iter_mut() {
+        match checkout.pay(order) {
+            Ok(receipt) => println!("  {}", receipt),
+            Err(e) => println!("  Declined: {}", e),
+        }
+    }
+    if let Err(e) = checkout.pay(&mut orders[0]) {
+        println!("  Declined: {}", e);
+    }
+}
+
+fn print_depths() {
+    println!("Call chain depth (limit {}):", DEPTH_LIMIT);
+    println!("  {:<14} {:>6} {:>8} {:>8} {:>10}", "file", "chains", "deepest", "average", "over limit");
+    for (file, source) in SOURCES {
+        let report = call_chain::report(source, DEPTH_LIMIT);
+        println!(
+            "  {:<14} {:>6} {:>8} {:>8.2} {:>10}",
+            file,
+            report.chains,
+            report.deepest,
+            report.average_depth(),
+            report.over_limit.len()
+        );
+    }
+    for (file, source) in SOURCES {
+        for chain in call_chain::report(source, DEPTH_LIMIT).over_limit {
+            println!("  {}:{} depth {}: {}", file, chain.line, chain.depth, chain.expression);
+        }
+    }
+}
+
+/// Example usage: the same payments, reaching through objects and asking them
+pub fn demonstrate_law_of_demeter() {
+    println!("=== LAW OF DEMETER ===");
+    println!();
+
+    println!("Violation: the checkout reaches through order -> customer -> wallet");
+    run_violation();
+    println!();
+
+    println!("Correct: the checkout asks the order to pay");
+    run_correct();
+    println!();
+
+    print_depths();
+
+    println!();
+    println!("Benefits of the correct version:");
+    println!("1. Checkout depends on Order alone; Customer and Wallet can change freely");
+    println!("2. The funds check and the debit happen in one place, so they cannot be split");
+    println!("3. Methods say what they do (pay, debit) instead of exposing state");
+}
+
+fn main() {
+    demonstrate_law_of_demeter();
+}
@@ -0,0 +1,135 @@
+// VIOLATION OF THE LAW OF DEMETER
+// This code violates the Law of Demeter ("only talk to your immediate friends") because:
+// 1. CheckoutViolation reaches through Order into Customer and then Wallet:
+//    order.customer().wallet().balance()
+// 2. The checkout knows how customers keep their money, so a change to Wallet
+//    (credit limits, several wallets, ...) ripples into every client
+// 3. The balance check and the debit are separate steps done by the client,
+//    so nothing stops another client from debiting without checking
+
+use std::fmt;
+
+/// An amount of money, in cents
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Money(pub u64);
+
+impl fmt::Display for Money {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "${}.{:02}", self.0 / 100, self.0 % 100)
+    }
+}
+
+#[derive(Debug)]
+pub struct Wallet {
+    balance: Money,
+}
+
+impl Wallet {
+    pub fn new(balance: Money) -> Self {
+        Wallet { balance }
+    }
+
+    pub fn balance(&self) -> Money {
+        self.balance
+    }
+
+    pub fn set_balance(&mut self, balance: Money) {
+        self.balance = balance;
+    }
+}
+
+#[derive(Debug)]
+pub struct Customer {
+    name: String,
+    wallet: Wallet,
+}
+
+impl Customer {
+    pub fn new(name: &str, wallet: Wallet) -> Self {
+        Customer { name: name.to_string(), wallet }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    // Violates LoD: hands out its internals for others to work on
+    pub fn wallet(&self) -> &Wallet {
+        &self.wallet
+    }
+
+    pub fn wallet_mut(&mut self) -> &mut Wallet {
+        &mut self.wallet
+    }
+}
+
+#[derive(Debug)]
+pub struct Order {
+    id: u32,
+    customer: Customer,
+    total: Money,
+    paid: bool,
+}
+
+impl Order {
+    pub fn new(id: u32, customer: Customer, total: Money) -> Self {
+        Order { id, customer, total, paid: false }
+    }
+
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+
+    pub fn total(&self) -> Money {
+        self.total
+    }
+
+    pub fn is_paid(&self) -> bool {
+        self.paid
+    }
+
+    pub fn mark_paid(&mut self) {
+        self.paid = true;
+    }
+
+    pub fn customer(&self) -> &Customer {
+        &self.customer
+    }
+
+    pub fn customer_mut(&mut self) -> &mut Customer {
+        &mut self.customer
+    }
+}
+
+/// The client: takes payment for an order
+pub struct CheckoutViolation;
+
+impl CheckoutViolation {
+    pub fn pay(&self, order: &mut Order) -> Result<String, String> {
+        if order.is_paid() {
+            return Err(format!("Order {} is already paid", order.id()));
+        }
+        // Violates LoD: three hops to read a balance
+        let balance = order.customer().wallet().balance();
+        if balance < order.total() {
+            return Err(format!(
+                "{} has {} but order {} costs {}",
+                order.customer().name(),
+                balance,
+                order.id(),
+                order.total()
+            ));
+        }
+        // Violates LoD: the client does the wallet's arithmetic itself
+        let remaining = Money(balance.0 - order.total().0);
+        order.customer_mut().wallet_mut().set_balance(remaining);
+        order.mark_paid();
+        Ok(format!(
+            "Order {}: {} paid {}, {} left",
+            order.id(),
+            order.customer().name(),
+            order.total(),
+            order.customer().wallet().balance()
+        ))
+    }
+}
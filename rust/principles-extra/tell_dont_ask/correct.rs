@@ -0,0 +1,220 @@
+// CORRECT IMPLEMENTATION - TELL, DON'T ASK WITH A RICH DOMAIN MODEL
+// Order owns its rules. Callers tell it what to do (add a line, place, pay,
+// ship) and it either does it or says why not. Invalid states cannot be
+// built at all:
+// - the fields are private, so only Order's methods change them
+// - the total is computed from the lines, so it cannot drift
+// - the status is a type parameter (a typestate): ship() exists only on
+//   Order<Paid>, so shipping an unpaid order is a compile error
+// - each state carries its own data: a tracking number exists only once
+//   shipped, a payment only once paid
+// - an order is created with its first line, so an empty order never exists
+
+use std::fmt;
+
+/// An amount of money, in cents
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Money(pub u64);
+
+impl fmt::Display for Money {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "${}.{:02}", self.0 / 100, self.0 % 100)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct OrderLine {
+    sku: String,
+    unit_price: Money,
+    quantity: u32,
+}
+
+impl OrderLine {
+    /// Quantity is at least 1
+    pub fn new(sku: &str, unit_price: Money, quantity: u32) -> Result<Self, OrderError> {
+        if quantity == 0 {
+            return Err(OrderError::ZeroQuantity { sku: sku.to_string() });
+        }
+        Ok(OrderLine { sku: sku.to_string(), unit_price, quantity })
+    }
+
+    pub fn total(&self) -> Money {
+        Money(self.unit_price.0 * self.quantity as u64)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum OrderError {
+    ZeroQuantity { sku: String },
+    Underpaid { due: Money, offered: Money },
+    NoTrackingNumber,
+}
+
+impl fmt::Display for OrderError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            OrderError::ZeroQuantity { sku } => write!(f, "Quantity of {} must be at least 1", sku),
+            OrderError::Underpaid { due, offered } => write!(f, "{} is due, {} offered", due, offered),
+            OrderError::NoTrackingNumber => write!(f, "A shipment needs a tracking number"),
+        }
+    }
+}
+
+// The states. Each holds what only exists from that point on.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Draft;
+#[derive(Debug, Clone, PartialEq)]
+pub struct Placed;
+#[derive(Debug, Clone, PartialEq)]
+pub struct Paid {
+    amount: Money,
+}
+#[derive(Debug, Clone, PartialEq)]
+pub struct Shipped {
+    amount: Money,
+    tracking_number: String,
+}
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cancelled;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Order<S> {
+    id: u32,
+    lines: Vec<OrderLine>,
+    state: S,
+}
+
+/// A refused payment hands the order back, still placed
+pub type PaymentResult = Result<Order<Paid>, (Order<Placed>, OrderError)>;
+
+// Available in every state
+impl<S> Order<S> {
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+
+    pub fn total(&self) -> Money {
+        Money(self.lines.iter().map(|line| line.total().0).sum())
+    }
+
+    pub fn line_count(&self) -> usize {
+        self.lines.len()
+    }
+
+    fn into_state<T>(self, state: T) -> Order<T> {
+        Order { id: self.id, lines: self.lines, state }
+    }
+}
+
+impl Order<Draft> {
+    pub fn new(id: u32, first_line: OrderLine) -> Self {
+        Order { id, lines: vec![first_line], state: Draft }
+    }
+
+    pub fn add_line(&mut self, line: OrderLine) {
+        self.lines.push(line);
+    }
+
+    pub fn place(self) -> Order<Placed> {
+        self.into_state(Placed)
+    }
+
+    pub fn cancel(self) -> Order<Cancelled> {
+        self.into_state(Cancelled)
+    }
+}
+
+impl Order<Placed> {
+    pub fn pay(self, amount: Money) -> PaymentResult {
+        let due = self.total();
+        if amount < due {
+            return Err((self, OrderError::Underpaid { due, offered: amount }));
+        }
+        Ok(self.into_state(Paid { amount }))
+    }
+
+    pub fn cancel(self) -> Order<Cancelled> {
+        self.into_state(Cancelled)
+    }
+}
+
+impl Order<Paid> {
+    pub fn amount_paid(&self) -> Money {
+        self.state.amount
+    }
+
+    pub fn ship(self, tracking_number: &str) -> Result<Order<Shipped>, (Self, OrderError)> {
+        if tracking_number.trim().is_empty() {
+            return Err((self, OrderError::NoTrackingNumber));
+        }
+        let amount = self.state.amount;
+        Ok(self.into_state(Shipped { amount, tracking_number: tracking_number.to_string() }))
+    }
+}
+
+impl Order<Shipped> {
+    pub fn amount_paid(&self) -> Money {
+        self.state.amount
+    }
+
+    pub fn tracking_number(&self) -> &str {
+        &self.state.tracking_number
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(sku: &str, cents: u64, quantity: u32) -> OrderLine {
+        OrderLine::new(sku, Money(cents), quantity).unwrap()
+    }
+
+    fn placed_order() -> Order<Placed> {
+        let mut draft = Order::new(1, line("BOOK-1", 2500, 2));
+        draft.add_line(line("PEN-7", 300, 1));
+        draft.place()
+    }
+
+    #[test]
+    fn total_is_computed_from_the_lines() {
+        let mut draft = Order::new(1, line("BOOK-1", 2500, 2));
+        assert_eq!(draft.total(), Money(5000));
+        draft.add_line(line("PEN-7", 300, 1));
+        assert_eq!((draft.line_count(), draft.total()), (2, Money(5300)));
+        assert_eq!(draft.total().to_string(), "$53.00");
+    }
+
+    #[test]
+    fn zero_quantity_lines_are_refused() {
+        assert_eq!(OrderLine::new("PEN-7", Money(300), 0), Err(OrderError::ZeroQuantity { sku: "PEN-7".into() }));
+    }
+
+    #[test]
+    fn underpayment_hands_back_the_placed_order() {
+        let (order, error) = placed_order().pay(Money(1000)).unwrap_err();
+        assert_eq!(error, OrderError::Underpaid { due: Money(5300), offered: Money(1000) });
+        assert_eq!(error.to_string(), "$53.00 is due, $10.00 offered");
+        assert_eq!(order, placed_order());
+
+        let paid = order.pay(Money(5300)).unwrap();
+        assert_eq!(paid.amount_paid(), Money(5300));
+    }
+
+    #[test]
+    fn blank_tracking_number_hands_back_the_paid_order() {
+        let paid = placed_order().pay(Money(6000)).unwrap();
+        let (paid, error) = paid.ship("  ").unwrap_err();
+        assert_eq!(error, OrderError::NoTrackingNumber);
+
+        let shipped = paid.ship("TRACK-001").unwrap();
+        assert_eq!(shipped.tracking_number(), "TRACK-001");
+        assert_eq!((shipped.amount_paid(), shipped.total()), (Money(6000), Money(5300)));
+    }
+
+    #[test]
+    fn drafts_and_placed_orders_can_be_cancelled() {
+        assert_eq!(Order::new(2, line("LAMP-3", 4000, 1)).cancel().id(), 2);
+        assert_eq!(placed_order().cancel().total(), Money(5300));
+    }
+}
@@ -0,0 +1,148 @@
+// TELL, DON'T ASK - ANEMIC VERSUS RICH DOMAIN MODELS
+//
+// Tell objects what to do; don't ask them for their data, decide for them,
+// and write the result back.
+// - violation.rs: an anemic Order with public fields, driven by an
+//   OrderService that holds every rule
+// - correct.rs: a rich Order that owns its invariants. Its total is
+//   computed, and its status is a type, so each transition exists only on
+//   the states it is valid from
+//
+// This complements SRP and OCP: those say where behaviour goes between
+// types; this says behaviour goes with the data it guards.
+
+mod correct;
+mod violation;
+
+fn run_violation() {
+    use violation::{invariant_violations, Money, Order, OrderService, OrderStatus};
+
+    let service = OrderService;
+    let report = |label: &str, order: &Order| {
+        let problems = invariant_violations(order);
+        if problems.is_empty() {
+            println!("  {}: {:?}, total {}, consistent", label, order.status, order.total);
+        } else {
+            println!("  {}: {:?}, total {}, BROKEN: {}", label, order.status, order.total, problems.join("; "));
+        }
+    };
+
+    // Through the service, the happy path holds
+    let mut order = Order { id: 1, ..Order::default() };
+    let _ = service.add_line(&mut order, "BOOK-1", Money(2500), 2);
+    let _ = service.add_line(&mut order, "PEN-7", Money(300), 1);
+    let _ = service.place(&mut order);
+    if let Err(e) = service.pay(&mut order, Money(1000)) {
+        println!("  Declined: {}", e);
+    }
+    let _ = service.pay(&mut order, Money(5300));
+    service.ship(&mut order, "TRACK-001");
+    report("Order 1 via the service", &order);
+
+    // The service forgot to check the status before shipping
+    let mut unpaid = Order { id: 2, ..Order::default() };
+    let _ = service.add_line(&mut unpaid, "LAMP-3", Money(4000), 1);
+    service.ship(&mut unpaid, "TRACK-002");
+    report("Order 2 shipped unpaid", &unpaid);
+
+    // Any code can go round the service
+    let mut edited = Order { id: 3, ..Order::default() };
+    let _ = service.add_line(&mut edited, "MUG-2", Money(1200), 1);
+    let _ = service.place(&mut edited);
+    edited.lines.push(violation::OrderLine { sku: "MUG-2".into(), unit_price: Money(1200), quantity: 3 });
+    report("Order 3 line pushed directly", &edited);
+
+    let mut forged = Order { id: 4, status: OrderStatus::Paid, ..Order::default() };
+    forged.tracking_number = Some("TRACK-004".into());
+    report("Order 4 fields assigned", &forged);
+}
+
+fn run_correct() {
+    use correct::{Money, Order, OrderLine};
+
+    let line = |sku: &str, cents: u64, quantity: u32| OrderLine::new(sku, Money(cents), quantity);
+
+    let mut draft = match line("BOOK-1", 2500, 2) {
+        Ok(first) => Order::new(1, first),
+        Err(e) => return println!("  Rejected: {}", e),
+    };
+    if let Ok(pen) = line("PEN-7", 300, 1) {
+        draft.add_line(pen);
+    }
+    if let Err(e) = line("PEN-7", 300, 0) {
+        println!("  Rejected line: {}", e);
+    }
+
+    // Tell, don't ask: each step is a method on the order
+    let placed = draft.place();
+    println!("  Order {} placed, {} lines, total {}", placed.id(), placed.line_count(), placed.total());
+    let placed = match placed.pay(Money(1000)) {
+        Ok(_) => unreachable!("$10.00 does not cover $53.00"),
+        Err((order, e)) => {
+            println!("  Declined: {} (order {} is still placed)", e, order.id());
+            order
+        }
+    };
+    let paid = match placed.pay(Money(5300)) {
+        Ok(paid) => paid,
+        Err((_, e)) => return println!("  Declined: {}", e),
+    };
+    println!("  Order {} paid {}", paid.id(), paid.amount_paid());
+    let paid = match paid.ship("  ") {
+        Ok(_) => unreachable!("a blank tracking number is refused"),
+        Err((order, e)) => {
+            println!("  Not shipped: {}", e);
+            order
+        }
+    };
+    match paid.ship("TRACK-001") {
+        Ok(shipped) => println!(
+            "  Order {} shipped as {}, {} paid of {}",
+            shipped.id(),
+            shipped.tracking_number(),
+            shipped.amount_paid(),
+            shipped.total()
+        ),
+        Err((_, e)) => println!("  Not shipped: {}", e),
+    }
+
+    if let Ok(first) = line("LAMP-3", 4000, 1) {
+        let cancelled = Order::new(2, first).place().cancel();
+        println!("  Order {} cancelled before payment", cancelled.id());
+    }
+    if let Ok(first) = line("MUG-2", 1200, 1) {
+        let cancelled = Order::new(3, first).cancel();
+        println!("  Order {} cancelled as a draft", cancelled.id());
+    }
+
+    // The anemic failures above do not compile here:
+    //   Order::new(2, lamp).place().ship("TRACK-002")  // no method `ship` on Order<Placed>
+    //   order.lines.push(line)                         // field `lines` is private
+    //   order.total = Money(0)                         // no field `total`; it is computed
+    //   Order { id: 4, lines: vec![], state: Paid .. } // fields are private outside correct.rs
+    println!("  Shipping unpaid, editing lines after placing, or forging a state: compile errors");
+}
+
+/// Example usage: the same orders through an anemic and a rich model
+pub fn demonstrate_tell_dont_ask() {
+    println!("=== TELL, DON'T ASK ===");
+    println!();
+
+    println!("Violation: an anemic Order, with the rules in OrderService");
+    run_violation();
+    println!();
+
+    println!("Correct: a rich Order that owns its invariants");
+    run_correct();
+
+    println!();
+    println!("Benefits of the correct version:");
+    println!("1. The rules live with the data, so no caller can skip them");
+    println!("2. The total is computed from the lines and cannot go stale");
+    println!("3. Invalid transitions are compile errors, not runtime checks someone forgot");
+    println!("4. A failed step returns the order unchanged, in the state it was in");
+}
+
+fn main() {
+    demonstrate_tell_dont_ask();
+}
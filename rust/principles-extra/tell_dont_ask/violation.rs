@@ -0,0 +1,157 @@
+// VIOLATION OF TELL, DON'T ASK - AN ANEMIC DOMAIN MODEL
+// This code violates Tell, Don't Ask because:
+// 1. Order is a bag of public fields with no behaviour; OrderService asks for
+//    its data, decides, and writes the result back
+// 2. The rules of an order (its total, which status follows which) live in
+//    the service, so any other code that touches the fields can skip them
+// 3. Nothing keeps `total` in step with `lines`, or `tracking_number` with
+//    `status`: invalid combinations are one assignment away
+
+use std::fmt;
+
+/// An amount of money, in cents
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Money(pub u64);
+
+impl fmt::Display for Money {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "${}.{:02}", self.0 / 100, self.0 % 100)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OrderStatus {
+    #[default]
+    Draft,
+    Placed,
+    Paid,
+    Shipped,
+    Cancelled,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct OrderLine {
+    pub sku: String,
+    pub unit_price: Money,
+    pub quantity: u32,
+}
+
+/// Anemic: data only, every field public
+#[derive(Debug, Clone, Default)]
+pub struct Order {
+    pub id: u32,
+    pub lines: Vec<OrderLine>,
+    pub status: OrderStatus,
+    /// Kept up to date by whoever remembers to
+    pub total: Money,
+    pub paid: Money,
+    pub tracking_number: Option<String>,
+}
+
+/// All the behaviour, outside the data it works on
+pub struct OrderService;
+
+impl OrderService {
+    pub fn add_line(&self, order: &mut Order, sku: &str, unit_price: Money, quantity: u32) -> Result<(), String> {
+        if order.status != OrderStatus::Draft {
+            return Err(format!("Order {} is {:?}; lines can only be added to drafts", order.id, order.status));
+        }
+        order.lines.push(OrderLine { sku: sku.to_string(), unit_price, quantity });
+        order.total = Money(order.total.0 + unit_price.0 * quantity as u64);
+        Ok(())
+    }
+
+    pub fn place(&self, order: &mut Order) -> Result<(), String> {
+        if order.lines.is_empty() {
+            return Err(format!("Order {} has no lines", order.id));
+        }
+        order.status = OrderStatus::Placed;
+        Ok(())
+    }
+
+    pub fn pay(&self, order: &mut Order, amount: Money) -> Result<(), String> {
+        if order.status != OrderStatus::Placed {
+            return Err(format!("Order {} is {:?}, not placed", order.id, order.status));
+        }
+        if amount < order.total {
+            return Err(format!("Order {} costs {}, got {}", order.id, order.total, amount));
+        }
+        order.paid = amount;
+        order.status = OrderStatus::Paid;
+        Ok(())
+    }
+
+    // Bug: no status check. With the rules outside the data, every
+    // operation has to repeat them, and this one forgot.
+    pub fn ship(&self, order: &mut Order, tracking_number: &str) {
+        order.tracking_number = Some(tracking_number.to_string());
+        order.status = OrderStatus::Shipped;
+    }
+}
+
+/// What must hold for any order. The model cannot enforce it, so it can
+/// only be checked after the fact.
+pub fn invariant_violations(order: &Order) -> Vec<String> {
+    let mut problems = Vec::new();
+    let sum = order.lines.iter().map(|line| line.unit_price.0 * line.quantity as u64).sum::<u64>();
+    if order.total.0 != sum {
+        problems.push(format!("total is {} but the lines add up to {}", order.total, Money(sum)));
+    }
+    if order.status != OrderStatus::Draft && order.status != OrderStatus::Cancelled && order.lines.is_empty() {
+        problems.push(format!("{:?} with no lines", order.status));
+    }
+    if matches!(order.status, OrderStatus::Paid | OrderStatus::Shipped) && order.paid < order.total {
+        problems.push(format!("{:?} but only {} of {} paid", order.status, order.paid, order.total));
+    }
+    if (order.status == OrderStatus::Shipped) != order.tracking_number.is_some() {
+        problems.push(format!("{:?} with tracking number {:?}", order.status, order.tracking_number));
+    }
+    problems
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn order_with_line(id: u32) -> Order {
+        let mut order = Order { id, ..Order::default() };
+        OrderService.add_line(&mut order, "LAMP-3", Money(4000), 1).unwrap();
+        order
+    }
+
+    #[test]
+    fn the_service_happy_path_is_consistent() {
+        let mut order = order_with_line(1);
+        OrderService.place(&mut order).unwrap();
+        assert_eq!(OrderService.pay(&mut order, Money(1000)), Err("Order 1 costs $40.00, got $10.00".to_string()));
+        OrderService.pay(&mut order, Money(4000)).unwrap();
+        OrderService.ship(&mut order, "TRACK-001");
+        assert!(invariant_violations(&order).is_empty());
+    }
+
+    #[test]
+    fn the_anemic_model_ships_what_the_rich_model_refuses() {
+        // Unpaid, through the service itself
+        let mut unpaid = order_with_line(2);
+        OrderService.ship(&mut unpaid, "TRACK-002");
+        assert_eq!(unpaid.status, OrderStatus::Shipped);
+        assert_eq!(invariant_violations(&unpaid), ["Shipped but only $0.00 of $40.00 paid"]);
+
+        // The rich model has no ship() before payment, and refuses the payment
+        use crate::correct;
+        let placed = correct::Order::new(2, correct::OrderLine::new("LAMP-3", correct::Money(4000), 1).unwrap()).place();
+        let (_, error) = placed.pay(correct::Money(0)).unwrap_err();
+        assert_eq!(error, correct::OrderError::Underpaid { due: correct::Money(4000), offered: correct::Money(0) });
+    }
+
+    #[test]
+    fn fields_written_round_the_service_break_the_invariants() {
+        let mut edited = order_with_line(3);
+        OrderService.place(&mut edited).unwrap();
+        edited.lines.push(OrderLine { sku: "MUG-2".into(), unit_price: Money(1200), quantity: 3 });
+        assert_eq!(invariant_violations(&edited), ["total is $40.00 but the lines add up to $76.00"]);
+
+        let forged = Order { id: 4, status: OrderStatus::Paid, tracking_number: Some("TRACK-004".into()), ..Order::default() };
+        assert_eq!(invariant_violations(&forged).len(), 2, "{:?}", invariant_violations(&forged));
+    }
+}
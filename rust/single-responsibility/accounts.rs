@@ -0,0 +1,308 @@
+// Passwords, stored credentials and sessions
+
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use argon2::Argon2;
+use argon2::password_hash::{self, PasswordHash, PasswordVerifier, SaltString};
+use argon2::password_hash::rand_core::OsRng;
+use chrono::{DateTime, Duration, Utc};
+use crate::{Clock, IdGenerator, SystemClock, SystemIdGenerator, UserId};
+
+// Password hasher trait - defines how passwords are turned into stored hashes
+pub trait PasswordHasher {
+    fn hash_password(&self, password: &str) -> Result<String, String>;
+    fn verify_password(&self, password: &str, hash: &str) -> Result<bool, String>;
+}
+
+// Concrete implementation of PasswordHasher using Argon2id with a random salt.
+// Hashes are PHC strings, so the parameters travel with the hash.
+pub struct Argon2PasswordHasher {
+    argon2: Argon2<'static>,
+}
+
+impl Argon2PasswordHasher {
+    pub fn new() -> Self {
+        Argon2PasswordHasher {
+            argon2: Argon2::default(),
+        }
+    }
+}
+
+impl Default for Argon2PasswordHasher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PasswordHasher for Argon2PasswordHasher {
+    fn hash_password(&self, password: &str) -> Result<String, String> {
+        let salt = SaltString::generate(&mut OsRng);
+        password_hash::PasswordHasher::hash_password(&self.argon2, password.as_bytes(), &salt)
+            .map(|hash| hash.to_string())
+            .map_err(|e| format!("Password hashing failed: {}", e))
+    }
+    
+    fn verify_password(&self, password: &str, hash: &str) -> Result<bool, String> {
+        let parsed = PasswordHash::new(hash).map_err(|e| format!("Invalid password hash: {}", e))?;
+        match self.argon2.verify_password(password.as_bytes(), &parsed) {
+            Ok(()) => Ok(true),
+            Err(password_hash::Error::Password) => Ok(false),
+            Err(e) => Err(format!("Password verification failed: {}", e)),
+        }
+    }
+}
+
+// Fast, deterministic PasswordHasher for tests and demos. Not for real passwords.
+pub struct FakePasswordHasher;
+
+impl FakePasswordHasher {
+    pub fn new() -> Self {
+        FakePasswordHasher
+    }
+}
+
+impl Default for FakePasswordHasher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PasswordHasher for FakePasswordHasher {
+    fn hash_password(&self, password: &str) -> Result<String, String> {
+        Ok(format!("fake${}", password.chars().rev().collect::<String>()))
+    }
+    
+    fn verify_password(&self, password: &str, hash: &str) -> Result<bool, String> {
+        Ok(self.hash_password(password)? == hash)
+    }
+}
+
+#[cfg(test)]
+mod password_hasher_contract {
+    use super::*;
+    
+    crate::password_hasher_contract_tests!(argon2, Argon2PasswordHasher::new());
+    crate::password_hasher_contract_tests!(fake, FakePasswordHasher::new());
+}
+
+// Credential store trait - defines where password hashes are kept
+pub trait CredentialStore {
+    fn set_password_hash(&self, user_id: UserId, hash: String) -> Result<(), String>;
+    fn password_hash(&self, user_id: UserId) -> Result<Option<String>, String>;
+    fn remove_credentials(&self, user_id: UserId) -> Result<bool, String>;
+}
+
+// In-memory CredentialStore keyed by user id, kept apart from the user records
+pub struct InMemoryCredentialStore {
+    hashes: RwLock<HashMap<UserId, String>>,
+}
+
+impl InMemoryCredentialStore {
+    pub fn new() -> Self {
+        InMemoryCredentialStore {
+            hashes: RwLock::new(HashMap::new()),
+        }
+    }
+}
+
+impl Default for InMemoryCredentialStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CredentialStore for InMemoryCredentialStore {
+    fn set_password_hash(&self, user_id: UserId, hash: String) -> Result<(), String> {
+        let mut hashes = self.hashes.write().map_err(|e| e.to_string())?;
+        hashes.insert(user_id, hash);
+        Ok(())
+    }
+    
+    fn password_hash(&self, user_id: UserId) -> Result<Option<String>, String> {
+        let hashes = self.hashes.read().map_err(|e| e.to_string())?;
+        Ok(hashes.get(&user_id).cloned())
+    }
+    
+    fn remove_credentials(&self, user_id: UserId) -> Result<bool, String> {
+        let mut hashes = self.hashes.write().map_err(|e| e.to_string())?;
+        Ok(hashes.remove(&user_id).is_some())
+    }
+}
+
+// Session service trait - defines how logged-in sessions are issued and checked
+pub trait SessionService {
+    fn create_session(&self, user_id: UserId) -> Result<String, String>;
+    fn validate_token(&self, token: &str) -> Result<Option<UserId>, String>;
+    fn revoke(&self, token: &str) -> Result<bool, String>;
+}
+
+#[derive(Debug, Clone)]
+struct Session {
+    user_id: UserId,
+    expires_at: DateTime<Utc>,
+}
+
+// In-memory SessionService issuing random opaque tokens that expire after a fixed time
+pub struct InMemorySessionService {
+    sessions: RwLock<HashMap<String, Session>>,
+    ttl: Duration,
+    clock: Arc<dyn Clock>,
+    ids: Arc<dyn IdGenerator>,
+}
+
+impl InMemorySessionService {
+    pub fn new(ttl: Duration) -> Self {
+        InMemorySessionService {
+            sessions: RwLock::new(HashMap::new()),
+            ttl,
+            clock: Arc::new(SystemClock),
+            ids: Arc::new(SystemIdGenerator),
+        }
+    }
+    
+    // The clock sessions expire by; SystemClock by default
+    pub fn with_clock(mut self, clock: impl Clock + 'static) -> Self {
+        self.clock = Arc::new(clock);
+        self
+    }
+    
+    // Where tokens come from; SystemIdGenerator by default
+    pub fn with_id_generator(mut self, ids: impl IdGenerator + 'static) -> Self {
+        self.ids = Arc::new(ids);
+        self
+    }
+    
+    fn generate_token(&self) -> String {
+        self.ids.token(32)
+    }
+}
+
+impl Default for InMemorySessionService {
+    fn default() -> Self {
+        InMemorySessionService::new(Duration::hours(12))
+    }
+}
+
+impl SessionService for InMemorySessionService {
+    fn create_session(&self, user_id: UserId) -> Result<String, String> {
+        let token = self.generate_token();
+        let session = Session {
+            user_id,
+            expires_at: self.clock.now() + self.ttl,
+        };
+        let mut sessions = self.sessions.write().map_err(|e| e.to_string())?;
+        sessions.insert(token.clone(), session);
+        Ok(token)
+    }
+    
+    // Expired sessions are dropped on lookup
+    fn validate_token(&self, token: &str) -> Result<Option<UserId>, String> {
+        let mut sessions = self.sessions.write().map_err(|e| e.to_string())?;
+        match sessions.get(token) {
+            Some(session) if session.expires_at > self.clock.now() => Ok(Some(session.user_id)),
+            Some(_) => {
+                sessions.remove(token);
+                Ok(None)
+            }
+            None => Ok(None),
+        }
+    }
+    
+    fn revoke(&self, token: &str) -> Result<bool, String> {
+        let mut sessions = self.sessions.write().map_err(|e| e.to_string())?;
+        Ok(sessions.remove(token).is_some())
+    }
+}
+
+// Tests for InMemorySessionService, with time driven by a ManualClock
+#[cfg(test)]
+mod session_service_tests {
+    use super::*;
+    use crate::{ManualClock, SequentialIdGenerator};
+    use crate::testing::test_epoch;
+    
+    fn sessions(clock: &ManualClock) -> InMemorySessionService {
+        InMemorySessionService::new(Duration::minutes(30)).with_clock(clock.clone())
+    }
+    
+    #[test]
+    fn token_resolves_to_the_user_it_was_issued_to() {
+        let sessions = sessions(&ManualClock::new(test_epoch()));
+        let (ada, grace) = (UserId::generate(), UserId::generate());
+        let first = sessions.create_session(ada).unwrap();
+        let second = sessions.create_session(grace).unwrap();
+        assert_ne!(first, second);
+        assert_eq!(sessions.validate_token(&first), Ok(Some(ada)));
+        assert_eq!(sessions.validate_token(&second), Ok(Some(grace)));
+        assert_eq!(sessions.validate_token("not-a-token"), Ok(None));
+    }
+    
+    #[test]
+    fn sessions_expire_after_their_time_to_live() {
+        let clock = ManualClock::new(test_epoch());
+        let sessions = sessions(&clock);
+        let token = sessions.create_session(UserId::generate()).unwrap();
+        clock.advance(Duration::minutes(30) - Duration::seconds(1));
+        assert!(sessions.validate_token(&token).unwrap().is_some());
+        clock.advance(Duration::seconds(1));
+        assert_eq!(sessions.validate_token(&token), Ok(None));
+        // The expired session was dropped, so there is nothing left to revoke
+        assert_eq!(sessions.revoke(&token), Ok(false));
+    }
+    
+    #[test]
+    fn revoked_token_stops_working_and_cannot_be_revoked_twice() {
+        let sessions = sessions(&ManualClock::new(test_epoch()));
+        let token = sessions.create_session(UserId::generate()).unwrap();
+        assert_eq!(sessions.revoke(&token), Ok(true));
+        assert_eq!(sessions.validate_token(&token), Ok(None));
+        assert_eq!(sessions.revoke(&token), Ok(false));
+    }
+    
+    #[test]
+    fn tokens_come_from_the_id_generator() {
+        let sessions = InMemorySessionService::default().with_id_generator(SequentialIdGenerator::new());
+        let first = sessions.create_session(UserId::generate()).unwrap();
+        let second = sessions.create_session(UserId::generate()).unwrap();
+        assert_eq!(first, format!("{:064x}", 1));
+        assert_eq!(second, format!("{:064x}", 2));
+    }
+}
+
+// Contract tests every PasswordHasher must pass, in the same style as
+// repository_contract_tests!:
+//
+//     password_hasher_contract_tests!(argon2_contract, Argon2PasswordHasher::new());
+//     password_hasher_contract_tests!(fake_contract, FakePasswordHasher::new());
+#[macro_export]
+macro_rules! password_hasher_contract_tests {
+    ($name:ident, $make_hasher:expr) => {
+        #[cfg(test)]
+        mod $name {
+            use super::*;
+            use $crate::PasswordHasher;
+            
+            #[test]
+            fn hash_does_not_contain_the_password() {
+                let hasher = $make_hasher;
+                let hash = hasher.hash_password("Secret123").unwrap();
+                assert!(!hash.contains("Secret123"));
+            }
+            
+            #[test]
+            fn correct_password_verifies() {
+                let hasher = $make_hasher;
+                let hash = hasher.hash_password("Secret123").unwrap();
+                assert_eq!(hasher.verify_password("Secret123", &hash), Ok(true));
+            }
+            
+            #[test]
+            fn wrong_password_is_rejected() {
+                let hasher = $make_hasher;
+                let hash = hasher.hash_password("Secret123").unwrap();
+                assert_eq!(hasher.verify_password("Secret124", &hash), Ok(false));
+                assert_eq!(hasher.verify_password("", &hash), Ok(false));
+            }
+        }
+    };
+}
@@ -0,0 +1,110 @@
+// Analytics: the AnalyticsService trait and a buffering decorator
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use crate::{Clock, SystemClock};
+
+// Analytics service trait - defines analytics operations
+pub trait AnalyticsService {
+    fn track_user_event(&self, username: &str, event_name: &str, metadata: Option<HashMap<String, String>>);
+    fn track_user_registration(&self, username: &str);
+    fn track_user_update(&self, username: &str);
+}
+
+// Concrete implementation of AnalyticsService
+pub struct GoogleAnalyticsService {
+    tracking_id: String,
+    clock: Arc<dyn Clock>,
+}
+
+impl GoogleAnalyticsService {
+    pub fn new(tracking_id: String) -> Self {
+        GoogleAnalyticsService { tracking_id, clock: Arc::new(SystemClock) }
+    }
+    
+    // Where event timestamps come from; SystemClock by default
+    pub fn with_clock(mut self, clock: impl Clock + 'static) -> Self {
+        self.clock = Arc::new(clock);
+        self
+    }
+    
+    pub fn track_user_login(&self, username: &str, ip_address: &str) {
+        let mut metadata = HashMap::new();
+        metadata.insert("ip_address".to_string(), ip_address.to_string());
+        self.track_user_event(username, "user_login", Some(metadata));
+    }
+}
+
+impl AnalyticsService for GoogleAnalyticsService {
+    fn track_user_event(&self, username: &str, event_name: &str, metadata: Option<HashMap<String, String>>) {
+        let timestamp = self.clock.now().timestamp();
+        println!("[ANALYTICS {}] {}: {} (timestamp: {})", self.tracking_id, username, event_name, timestamp);
+        if let Some(meta) = metadata {
+            println!("  Metadata: {:?}", meta);
+        }
+        // Google Analytics tracking logic
+    }
+    
+    fn track_user_registration(&self, username: &str) {
+        self.track_user_event(username, "user_registered", None);
+    }
+    
+    fn track_user_update(&self, username: &str) {
+        self.track_user_event(username, "user_updated", None);
+    }
+}
+
+// One tracked event: username, event name and optional properties
+type TrackedEvent = (String, String, Option<HashMap<String, String>>);
+
+// Analytics decorator that holds events in memory until `flush` forwards them
+// to the wrapped service in one batch. Clones share the same buffer.
+pub struct BufferedAnalyticsService<A: AnalyticsService> {
+    inner: Arc<A>,
+    buffer: Arc<Mutex<Vec<TrackedEvent>>>,
+}
+
+impl<A: AnalyticsService> BufferedAnalyticsService<A> {
+    pub fn new(inner: A) -> Self {
+        BufferedAnalyticsService { inner: Arc::new(inner), buffer: Arc::new(Mutex::new(Vec::new())) }
+    }
+    
+    pub fn pending_len(&self) -> usize {
+        self.buffer.lock().map(|buffer| buffer.len()).unwrap_or(0)
+    }
+    
+    // Forwards every buffered event in the order it was tracked; returns how many
+    pub fn flush(&self) -> Result<usize, String> {
+        let events = std::mem::take(&mut *self.buffer.lock().map_err(|e| e.to_string())?);
+        for (username, event, metadata) in &events {
+            self.inner.track_user_event(username, event, metadata.clone());
+        }
+        Ok(events.len())
+    }
+    
+    fn push(&self, username: &str, event: &str, metadata: Option<HashMap<String, String>>) {
+        if let Ok(mut buffer) = self.buffer.lock() {
+            buffer.push((username.to_string(), event.to_string(), metadata));
+        }
+    }
+}
+
+impl<A: AnalyticsService> Clone for BufferedAnalyticsService<A> {
+    fn clone(&self) -> Self {
+        BufferedAnalyticsService { inner: self.inner.clone(), buffer: self.buffer.clone() }
+    }
+}
+
+impl<A: AnalyticsService> AnalyticsService for BufferedAnalyticsService<A> {
+    fn track_user_event(&self, username: &str, event_name: &str, metadata: Option<HashMap<String, String>>) {
+        self.push(username, event_name, metadata);
+    }
+    
+    fn track_user_registration(&self, username: &str) {
+        self.push(username, "user_registered", None);
+    }
+    
+    fn track_user_update(&self, username: &str) {
+        self.push(username, "user_updated", None);
+    }
+}
@@ -0,0 +1,238 @@
+// API payloads: the inbound UserDto, versioned responses and the UserFormatter
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use crate::{PhoneNumber, User, UserId, UserStatus};
+
+// Inbound API payload for creating or updating a user
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UserDto {
+    pub username: String,
+    pub email: String,
+}
+
+impl UserDto {
+    pub fn to_json(&self) -> Result<String, String> {
+        serde_json::to_string(self).map_err(|e| e.to_string())
+    }
+    
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        serde_json::from_str(json).map_err(|e| format!("Invalid user payload: {}", e))
+    }
+}
+
+// API versions the formatter can produce
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ApiVersion {
+    V1,
+    V2,
+}
+
+impl ApiVersion {
+    pub const LATEST: ApiVersion = ApiVersion::V2;
+}
+
+// v1: the original response shape
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UserResponseV1 {
+    pub id: UserId,
+    pub username: String,
+    pub email: String,
+    pub display_name: String,
+}
+
+// v2: adds lifecycle status and timestamps
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UserResponseV2 {
+    pub id: UserId,
+    pub username: String,
+    pub email: String,
+    pub display_name: String,
+    pub status: UserStatus,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub phone: Option<PhoneNumber>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub locale: Option<String>,
+}
+
+// Outbound API representation, tagged with its version so clients and
+// stored payloads can tell the shapes apart
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "version")]
+pub enum UserResponse {
+    #[serde(rename = "1")]
+    V1(UserResponseV1),
+    #[serde(rename = "2")]
+    V2(UserResponseV2),
+}
+
+impl UserResponse {
+    pub fn version(&self) -> ApiVersion {
+        match self {
+            UserResponse::V1(_) => ApiVersion::V1,
+            UserResponse::V2(_) => ApiVersion::V2,
+        }
+    }
+    
+    pub fn to_json(&self) -> Result<String, String> {
+        serde_json::to_string(self).map_err(|e| e.to_string())
+    }
+    
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        serde_json::from_str(json).map_err(|e| format!("Invalid user response: {}", e))
+    }
+}
+
+// Formatter trait - defines formatting operations
+pub trait UserFormatter {
+    fn format_user_for_display(&self, user: &User) -> String;
+    fn format_user_for_api(&self, user: &User, version: ApiVersion) -> UserResponse;
+}
+
+// Concrete implementation of UserFormatter
+pub struct DefaultUserFormatter;
+
+impl DefaultUserFormatter {
+    pub fn new() -> Self {
+        DefaultUserFormatter
+    }
+    
+    pub fn format_user_for_csv(&self, user: &User) -> String {
+        format!("{},{}", user.username(), user.email())
+    }
+}
+
+impl Default for DefaultUserFormatter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DefaultUserFormatter {
+    // When the user joined, worded and dated for their locale's language (and,
+    // for English, region). Unknown languages get English with an ISO date.
+    fn member_since(locale: &str, joined: DateTime<Utc>) -> String {
+        let language = locale.split('-').next().unwrap_or(locale);
+        match (language, locale) {
+            (_, "en-US") => format!("member since {}", joined.format("%m/%d/%Y")),
+            ("en", _) => format!("member since {}", joined.format("%d/%m/%Y")),
+            ("de", _) => format!("Mitglied seit {}", joined.format("%d.%m.%Y")),
+            ("fr", _) => format!("membre depuis le {}", joined.format("%d/%m/%Y")),
+            ("ja", _) => format!("{}から利用", joined.format("%Y年%m月%d日")),
+            _ => format!("member since {}", joined.format("%Y-%m-%d")),
+        }
+    }
+}
+
+impl UserFormatter for DefaultUserFormatter {
+    // The display name when there is one; users with a locale also get their join date
+    fn format_user_for_display(&self, user: &User) -> String {
+        let name = user.display_name().unwrap_or(user.username());
+        match user.locale() {
+            Some(locale) => format!("{} ({}), {}", name, user.email(), Self::member_since(locale, user.created_at())),
+            None => format!("{} ({})", name, user.email()),
+        }
+    }
+    
+    fn format_user_for_api(&self, user: &User, version: ApiVersion) -> UserResponse {
+        match version {
+            ApiVersion::V1 => UserResponse::V1(UserResponseV1 {
+                id: user.id(),
+                username: user.username().to_string(),
+                email: user.email().to_string(),
+                display_name: self.format_user_for_display(user),
+            }),
+            ApiVersion::V2 => UserResponse::V2(UserResponseV2 {
+                id: user.id(),
+                username: user.username().to_string(),
+                email: user.email().to_string(),
+                display_name: self.format_user_for_display(user),
+                status: user.status(),
+                created_at: user.created_at(),
+                updated_at: user.updated_at(),
+                phone: user.phone().cloned(),
+                locale: user.locale().map(str::to_string),
+            }),
+        }
+    }
+}
+
+// Tests for the API payloads: every shape survives a JSON round trip
+#[cfg(test)]
+mod api_payload_tests {
+    use super::*;
+    use chrono::Duration;
+    use crate::testing::test_epoch;
+    
+    fn ada() -> User {
+        User::restore(UserId::generate(), "ada".to_string(), "ada@example.com".to_string(),
+                      UserStatus::Active, test_epoch(), test_epoch() + Duration::days(1))
+    }
+    
+    fn round_trip(response: &UserResponse) -> UserResponse {
+        UserResponse::from_json(&response.to_json().unwrap()).unwrap()
+    }
+    
+    #[test]
+    fn user_dto_round_trips() {
+        let dto = UserDto { username: "ada".to_string(), email: "ada@example.com".to_string() };
+        assert_eq!(UserDto::from_json(&dto.to_json().unwrap()).unwrap(), dto);
+        assert_eq!(
+            UserDto::from_json(r#"{"username":"ada","email":"ada@example.com"}"#).unwrap(),
+            dto
+        );
+    }
+    
+    #[test]
+    fn user_dto_rejects_incomplete_payloads() {
+        let error = UserDto::from_json(r#"{"username":"ada"}"#).unwrap_err();
+        assert!(error.starts_with("Invalid user payload: missing field `email`"), "{}", error);
+        assert!(UserDto::from_json("not json").is_err());
+    }
+    
+    #[test]
+    fn every_version_round_trips_and_keeps_its_tag() {
+        let formatter = DefaultUserFormatter::new();
+        let user = ada();
+        for version in [ApiVersion::V1, ApiVersion::V2] {
+            let response = formatter.format_user_for_api(&user, version);
+            let back = round_trip(&response);
+            assert_eq!(back, response);
+            assert_eq!(back.version(), version);
+        }
+        assert!(formatter.format_user_for_api(&user, ApiVersion::V1).to_json().unwrap().contains(r#""version":"1""#));
+        assert!(formatter.format_user_for_api(&user, ApiVersion::V2).to_json().unwrap().contains(r#""version":"2""#));
+    }
+    
+    #[test]
+    fn v2_omits_an_empty_profile_and_round_trips_a_full_one() {
+        let formatter = DefaultUserFormatter::new();
+        let bare = formatter.format_user_for_api(&ada(), ApiVersion::V2).to_json().unwrap();
+        assert!(!bare.contains("phone") && !bare.contains("locale"), "{}", bare);
+        
+        let profiled = ada().with_profile(PhoneNumber::parse("+44 20 7946 0958").ok(), Some("Ada Lovelace".to_string()), Some("en-GB".to_string()));
+        let response = formatter.format_user_for_api(&profiled, ApiVersion::V2);
+        let UserResponse::V2(v2) = round_trip(&response) else {
+            panic!("expected a v2 response");
+        };
+        assert_eq!(v2.phone.as_ref().map(PhoneNumber::as_str), Some("+442079460958"));
+        assert_eq!(v2.locale.as_deref(), Some("en-GB"));
+        assert_eq!((v2.status, v2.created_at, v2.updated_at), (UserStatus::Active, test_epoch(), test_epoch() + Duration::days(1)));
+    }
+    
+    #[test]
+    fn unknown_versions_and_invalid_fields_are_rejected() {
+        let json = DefaultUserFormatter::new().format_user_for_api(&ada(), ApiVersion::V1).to_json().unwrap();
+        assert!(UserResponse::from_json(&json.replace(r#""version":"1""#, r#""version":"3""#)).is_err());
+        assert!(UserResponse::from_json(&json.replace(r#""version":"1","#, "")).is_err());
+        
+        let v2 = DefaultUserFormatter::new()
+            .format_user_for_api(&ada().with_profile(PhoneNumber::parse("+442079460958").ok(), None, None), ApiVersion::V2)
+            .to_json()
+            .unwrap();
+        let error = UserResponse::from_json(&v2.replace("+442079460958", "12345")).unwrap_err();
+        assert!(error.starts_with("Invalid user response"), "{}", error);
+    }
+}
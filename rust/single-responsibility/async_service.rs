@@ -0,0 +1,408 @@
+// Async counterparts of the I/O collaborators and AsyncUserService (enable the
+// `async` feature)
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Arc;
+use crate::{default_log_path, ActivityLogger, AnalyticsService, Clock, DefaultUserValidator, EmailMessage, EmailService, EmailTemplateRenderer, FileActivityLogger, FirebaseNotificationService, GoogleAnalyticsService, IdGenerator, InMemoryUserRepository, NotificationService, PhoneNumber, PlaceholderTemplateRenderer, SMTPEmailService, SystemClock, SystemIdGenerator, User, UserId, UserRepository, UserValidator};
+
+// Async collaborator traits (enable the `async` feature). They mirror the
+// synchronous traits one for one; validation, logging and templating stay
+// synchronous because they never wait on I/O in these examples.
+pub trait AsyncUserRepository {
+    fn save(&self, user: &User) -> impl Future<Output = Result<bool, String>> + Send;
+    fn update(&self, user: &User) -> impl Future<Output = Result<bool, String>> + Send;
+    fn find_by_id(&self, user_id: &str) -> impl Future<Output = Result<Option<User>, String>> + Send;
+    fn find_all(&self) -> impl Future<Output = Result<Vec<User>, String>> + Send;
+    fn delete(&self, user: &User) -> impl Future<Output = Result<bool, String>> + Send;
+}
+
+pub trait AsyncEmailService {
+    fn send(&self, message: &EmailMessage) -> impl Future<Output = Result<bool, String>> + Send;
+}
+
+pub trait AsyncNotificationService {
+    fn send_push_notification(&self, username: &str, message: &str) -> impl Future<Output = Result<bool, String>> + Send;
+    fn send_sms_notification(&self, phone: &PhoneNumber, message: &str) -> impl Future<Output = Result<bool, String>> + Send;
+}
+
+pub trait AsyncAnalyticsService {
+    fn track_user_event(&self, username: &str, event_name: &str, metadata: Option<HashMap<String, String>>) -> impl Future<Output = ()> + Send;
+    fn track_user_registration(&self, username: &str) -> impl Future<Output = ()> + Send;
+    fn track_user_update(&self, username: &str) -> impl Future<Output = ()> + Send;
+}
+
+// Adapts a synchronous collaborator to its async trait, waiting a fixed
+// latency before each call to stand in for a network round trip
+pub struct SimulatedLatency<T> {
+    inner: T,
+    latency: std::time::Duration,
+}
+
+impl<T> SimulatedLatency<T> {
+    pub fn new(inner: T, latency: std::time::Duration) -> Self {
+        SimulatedLatency { inner, latency }
+    }
+}
+
+impl<T: UserRepository + Sync> AsyncUserRepository for SimulatedLatency<T> {
+    async fn save(&self, user: &User) -> Result<bool, String> {
+        tokio::time::sleep(self.latency).await;
+        self.inner.save(user)
+    }
+    
+    async fn update(&self, user: &User) -> Result<bool, String> {
+        tokio::time::sleep(self.latency).await;
+        self.inner.update(user)
+    }
+    
+    async fn find_by_id(&self, user_id: &str) -> Result<Option<User>, String> {
+        tokio::time::sleep(self.latency).await;
+        self.inner.find_by_id(user_id)
+    }
+    
+    async fn find_all(&self) -> Result<Vec<User>, String> {
+        tokio::time::sleep(self.latency).await;
+        self.inner.find_all()
+    }
+    
+    async fn delete(&self, user: &User) -> Result<bool, String> {
+        tokio::time::sleep(self.latency).await;
+        self.inner.delete(user)
+    }
+}
+
+impl<T: EmailService + Sync> AsyncEmailService for SimulatedLatency<T> {
+    async fn send(&self, message: &EmailMessage) -> Result<bool, String> {
+        tokio::time::sleep(self.latency).await;
+        self.inner.send(message)
+    }
+}
+
+impl<T: NotificationService + Sync> AsyncNotificationService for SimulatedLatency<T> {
+    async fn send_push_notification(&self, username: &str, message: &str) -> Result<bool, String> {
+        tokio::time::sleep(self.latency).await;
+        self.inner.send_push_notification(username, message)
+    }
+    
+    async fn send_sms_notification(&self, phone: &PhoneNumber, message: &str) -> Result<bool, String> {
+        tokio::time::sleep(self.latency).await;
+        self.inner.send_sms_notification(phone, message)
+    }
+}
+
+impl<T: AnalyticsService + Sync> AsyncAnalyticsService for SimulatedLatency<T> {
+    async fn track_user_event(&self, username: &str, event_name: &str, metadata: Option<HashMap<String, String>>) {
+        tokio::time::sleep(self.latency).await;
+        self.inner.track_user_event(username, event_name, metadata)
+    }
+    
+    async fn track_user_registration(&self, username: &str) {
+        tokio::time::sleep(self.latency).await;
+        self.inner.track_user_registration(username)
+    }
+    
+    async fn track_user_update(&self, username: &str) {
+        tokio::time::sleep(self.latency).await;
+        self.inner.track_user_update(username)
+    }
+}
+
+// Async counterpart of CorrectUserService. The orchestration is the same;
+// side effects that don't depend on each other are awaited together.
+pub struct AsyncUserService<R, E, N, A, L, V, T>
+where
+    R: AsyncUserRepository,
+    E: AsyncEmailService,
+    N: AsyncNotificationService,
+    A: AsyncAnalyticsService,
+    L: ActivityLogger,
+    V: UserValidator,
+    T: EmailTemplateRenderer,
+{
+    user_repo: R,
+    email_service: E,
+    notification_service: Option<N>,
+    analytics: A,
+    logger: L,
+    validator: V,
+    templates: T,
+    clock: Arc<dyn Clock>,
+    ids: Arc<dyn IdGenerator>,
+}
+
+impl<R, E, N, A, L, V, T> AsyncUserService<R, E, N, A, L, V, T>
+where
+    R: AsyncUserRepository,
+    E: AsyncEmailService,
+    N: AsyncNotificationService,
+    A: AsyncAnalyticsService,
+    L: ActivityLogger,
+    V: UserValidator,
+    T: EmailTemplateRenderer,
+{
+    pub fn new(
+        user_repo: R,
+        email_service: E,
+        notification_service: Option<N>,
+        analytics: A,
+        logger: L,
+        validator: V,
+        templates: T,
+    ) -> Self {
+        AsyncUserService {
+            user_repo,
+            email_service,
+            notification_service,
+            analytics,
+            logger,
+            validator,
+            templates,
+            clock: Arc::new(SystemClock),
+            ids: Arc::new(SystemIdGenerator),
+        }
+    }
+    
+    pub fn with_clock(mut self, clock: impl Clock + 'static) -> Self {
+        self.clock = Arc::new(clock);
+        self
+    }
+    
+    pub fn with_id_generator(mut self, ids: impl IdGenerator + 'static) -> Self {
+        self.ids = Arc::new(ids);
+        self
+    }
+    
+    pub async fn create_user(&self, username: String, email: String) -> Result<User, String> {
+        let username = self.validator.normalize_username(&username);
+        let user = User::create(self.ids.next_user_id(), username.clone(), email.clone(), self.clock.now());
+        let report = self.validator.validate_user(&user);
+        if !report.is_valid() {
+            let error_msg = format!("Invalid user data: {}", report);
+            self.logger.log_error(&error_msg, "User creation failed");
+            return Err(error_msg);
+        }
+        
+        self.user_repo.save(&user).await.inspect_err(|e| self.logger.log_error(e, "Failed to save user"))?;
+        self.logger.log_user_activity(&username, "User created");
+        
+        // Email, push notification and analytics are independent, so they run concurrently
+        let welcome_email = self.send_email("welcome", &user);
+        let push = async {
+            match self.notification_service {
+                Some(ref notification_service) => {
+                    notification_service.send_push_notification(&username, "Welcome! Your account has been created.").await
+                }
+                None => Ok(false),
+            }
+        };
+        let (email_result, push_result, ()) = tokio::join!(
+            welcome_email,
+            push,
+            self.analytics.track_user_registration(&username),
+        );
+        if let Err(e) = email_result {
+            self.logger.log_error(&e, "Failed to send welcome email");
+        }
+        if let Err(e) = push_result {
+            self.logger.log_error(&e, "Failed to send push notification");
+        }
+        
+        Ok(user)
+    }
+    
+    pub async fn update_user(&self, user: &mut User, new_username: String, new_email: String) -> Result<(), String> {
+        let new_username = self.validator.normalize_username(&new_username);
+        let mut updated = user.clone();
+        updated.set_username(new_username.clone());
+        updated.set_email(new_email.clone());
+        updated.touch(self.clock.now());
+        let report = self.validator.validate_user(&updated);
+        if !report.is_valid() {
+            let error_msg = format!("Invalid user data: {}", report);
+            self.logger.log_error(&error_msg, "User update failed");
+            return Err(error_msg);
+        }
+        
+        if !self.user_repo.update(&updated).await? {
+            let error_msg = format!("User not found: {}", user.id());
+            self.logger.log_error(&error_msg, "User update failed");
+            return Err(error_msg);
+        }
+        *user = updated;
+        
+        self.logger.log_user_activity(&new_username, "User updated");
+        self.analytics.track_user_update(&new_username).await;
+        Ok(())
+    }
+    
+    pub async fn find_user(&self, user_id: UserId) -> Result<Option<User>, String> {
+        self.user_repo.find_by_id(&user_id.to_string()).await
+    }
+    
+    async fn send_email(&self, template: &str, user: &User) -> Result<bool, String> {
+        let values = HashMap::from([("username".to_string(), user.username().to_string())]);
+        let message = self.templates.render(template, user.email(), &values)?;
+        self.email_service.send(&message).await
+    }
+}
+
+// The service returned by create_async_user_service()
+pub type SimulatedAsyncUserService = AsyncUserService<
+    SimulatedLatency<InMemoryUserRepository>,
+    SimulatedLatency<SMTPEmailService>,
+    SimulatedLatency<FirebaseNotificationService>,
+    SimulatedLatency<GoogleAnalyticsService>,
+    FileActivityLogger,
+    DefaultUserValidator,
+    PlaceholderTemplateRenderer,
+>;
+
+// Factory function for an async service whose remote collaborators each take `latency`
+pub fn create_async_user_service(latency: std::time::Duration) -> SimulatedAsyncUserService {
+    AsyncUserService::new(
+        SimulatedLatency::new(InMemoryUserRepository::new(), latency),
+        SimulatedLatency::new(SMTPEmailService::new("smtp.example.com".to_string()), latency),
+        Some(SimulatedLatency::new(FirebaseNotificationService::new("firebase-api-key".to_string()), latency)),
+        SimulatedLatency::new(GoogleAnalyticsService::new("GA-XXXXX-X".to_string()), latency),
+        FileActivityLogger::new(default_log_path()),
+        DefaultUserValidator::new(),
+        PlaceholderTemplateRenderer::with_defaults(),
+    )
+}
+
+// Tests for AsyncUserService on a paused tokio clock, so SimulatedLatency
+// delays are measured exactly and cost no real time
+#[cfg(test)]
+mod async_user_service_tests {
+    use super::*;
+    use crate::{AnalyticsFlushJob, BufferedAnalyticsService, JobScheduler, ManualClock};
+    use crate::testing::*;
+    use tokio::time::Instant;
+    use chrono::Duration;
+    
+    const LATENCY: std::time::Duration = std::time::Duration::from_millis(100);
+    
+    type TestAsyncUserService = AsyncUserService<
+        SimulatedLatency<MockUserRepository>,
+        SimulatedLatency<MockEmailService>,
+        SimulatedLatency<MockNotificationService>,
+        SimulatedLatency<MockAnalyticsService>,
+        MockActivityLogger,
+        MockUserValidator,
+        MockTemplateRenderer,
+    >;
+    
+    fn service() -> (TestAsyncUserService, Mocks) {
+        let (_, mocks) = mock_user_service();
+        let service = AsyncUserService::new(
+            SimulatedLatency::new(mocks.repository.clone(), LATENCY),
+            SimulatedLatency::new(mocks.email.clone(), LATENCY),
+            Some(SimulatedLatency::new(mocks.notifications.clone(), LATENCY)),
+            SimulatedLatency::new(mocks.analytics.clone(), LATENCY),
+            mocks.logger.clone(),
+            mocks.validator.clone(),
+            mocks.templates.clone(),
+        )
+        .with_clock(mocks.clock.clone())
+        .with_id_generator(mocks.ids.clone());
+        (service, mocks)
+    }
+    
+    #[tokio::test(start_paused = true)]
+    async fn side_effects_after_the_save_are_awaited_together() {
+        let (service, _) = service();
+        let start = Instant::now();
+        service.create_user("ada".to_string(), "ada@example.com".to_string()).await.unwrap();
+        // One round trip to save, one for email, push and analytics together
+        assert_eq!(start.elapsed(), LATENCY * 2);
+    }
+    
+    #[tokio::test(start_paused = true)]
+    async fn create_user_saves_and_runs_every_side_effect() {
+        let (service, mocks) = service();
+        let user = service.create_user("ada".to_string(), "ada@example.com".to_string()).await.unwrap();
+        
+        assert_eq!(service.find_user(user.id()).await.unwrap().map(|found| found.id()), Some(user.id()));
+        assert_eq!(mocks.email.sent().len(), 1);
+        assert_eq!(mocks.notifications.spy().calls(), vec!["send_push_notification(ada)".to_string()]);
+        assert_eq!(mocks.analytics.spy().calls(), vec!["track_user_registration(ada)".to_string()]);
+        assert_eq!(mocks.logger.activities(), vec![("ada".to_string(), "User created".to_string())]);
+    }
+    
+    #[tokio::test(start_paused = true)]
+    async fn failed_side_effects_are_logged_and_do_not_fail_the_call() {
+        let (service, mocks) = service();
+        mocks.email.spy().fail_all("SMTP server unreachable");
+        mocks.notifications.spy().fail_all("Firebase unavailable");
+        
+        assert!(service.create_user("ada".to_string(), "ada@example.com".to_string()).await.is_ok());
+        assert_eq!(
+            mocks.logger.errors(),
+            vec![
+                ("Failed to send welcome email".to_string(), "SMTP server unreachable".to_string()),
+                ("Failed to send push notification".to_string(), "Firebase unavailable".to_string()),
+            ]
+        );
+        assert_eq!(mocks.analytics.spy().calls_to("track_user_registration"), 1);
+    }
+    
+    #[tokio::test(start_paused = true)]
+    async fn invalid_user_fails_before_any_round_trip() {
+        let (service, mocks) = service();
+        mocks.validator.reject("email", "not an email");
+        let start = Instant::now();
+        
+        assert!(service.create_user("ada".to_string(), "ada".to_string()).await.is_err());
+        assert_eq!(start.elapsed(), std::time::Duration::ZERO);
+        assert!(mocks.repository.spy().calls().is_empty());
+        assert!(mocks.email.spy().calls().is_empty());
+    }
+    
+    #[tokio::test(start_paused = true)]
+    async fn failed_save_skips_the_side_effects() {
+        let (service, mocks) = service();
+        mocks.repository.spy().fail_on("save", "database offline");
+        
+        assert_eq!(service.create_user("ada".to_string(), "ada@example.com".to_string()).await.unwrap_err(), "database offline");
+        assert_eq!(mocks.logger.errors(), vec![("Failed to save user".to_string(), "database offline".to_string())]);
+        assert!(mocks.email.spy().calls().is_empty());
+        assert!(mocks.analytics.spy().calls().is_empty());
+    }
+    
+    #[tokio::test(start_paused = true)]
+    async fn update_user_persists_and_tracks_the_change() {
+        let (service, mocks) = service();
+        let mut user = service.create_user("ada".to_string(), "ada@example.com".to_string()).await.unwrap();
+        service.update_user(&mut user, "ada".to_string(), "ada@lovelace.org".to_string()).await.unwrap();
+        
+        assert_eq!(user.email(), "ada@lovelace.org");
+        assert_eq!(service.find_user(user.id()).await.unwrap().unwrap().email(), "ada@lovelace.org");
+        assert_eq!(mocks.analytics.spy().calls_to("track_user_update"), 1);
+    }
+    
+    #[tokio::test(start_paused = true)]
+    async fn updating_an_unknown_user_is_an_error() {
+        let (service, mocks) = service();
+        let mut stranger = User::create(UserId::generate(), "grace".to_string(), "grace@example.com".to_string(), test_epoch());
+        
+        let error = service.update_user(&mut stranger, "grace".to_string(), "grace@navy.mil".to_string()).await.unwrap_err();
+        assert!(error.starts_with("User not found"));
+        assert_eq!(stranger.email(), "grace@example.com");
+        assert_eq!(mocks.logger.errors().len(), 1);
+    }
+    
+    #[tokio::test(start_paused = true)]
+    async fn scheduler_ticks_until_shutdown() {
+        let analytics = BufferedAnalyticsService::new(MockAnalyticsService::default());
+        // A zero interval keeps the job due on every tick
+        let mut scheduler = JobScheduler::with_clock(ManualClock::new(test_epoch()))
+            .every(Duration::zero(), AnalyticsFlushJob::new(analytics));
+        
+        // Ticks at 0s, 60s and 120s; shutdown comes at 150s
+        let runs = scheduler
+            .run(std::time::Duration::from_secs(60), tokio::time::sleep(std::time::Duration::from_secs(150)))
+            .await;
+        assert_eq!(runs, 3);
+    }
+}
@@ -0,0 +1,182 @@
+// Data-protection records and the tamper-evident audit trail
+
+use std::fmt;
+use std::sync::Mutex;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use crate::{User, UserId};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditAction {
+    Created,
+    Updated,
+    StatusChanged,
+    PasswordChanged,
+    Anonymized,
+    PersonalDataExported,
+    HardDeleted,
+}
+
+impl fmt::Display for AuditAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            AuditAction::Created => "created",
+            AuditAction::Updated => "updated",
+            AuditAction::StatusChanged => "status_changed",
+            AuditAction::PasswordChanged => "password_changed",
+            AuditAction::Anonymized => "anonymized",
+            AuditAction::PersonalDataExported => "personal_data_exported",
+            AuditAction::HardDeleted => "hard_deleted",
+        };
+        f.write_str(name)
+    }
+}
+
+// Record of a data-protection operation, sent to the ActivityLogger. It names
+// the user only by id, so the log itself holds no personal data.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AuditEvent {
+    pub action: AuditAction,
+    pub user_id: UserId,
+    pub at: DateTime<Utc>,
+}
+
+impl fmt::Display for AuditEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "audit {} {} at {}", self.action, self.user_id, self.at.to_rfc3339())
+    }
+}
+
+// One change to record in an AuditTrail: who did what to which user, when, and
+// the user as it was before and after. Creations have no `before`; erasures keep
+// no copy of what they erase.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditRecord {
+    pub actor: String,
+    pub action: AuditAction,
+    pub user_id: UserId,
+    pub at: DateTime<Utc>,
+    pub before: Option<User>,
+    pub after: Option<User>,
+}
+
+impl AuditRecord {
+    // The user as the trail keeps it. The trail is append-only and outlives an
+    // anonymization, so it never holds personal data: username, email and display
+    // name become fingerprints and the phone is dropped. Status, locale and
+    // timestamps are kept as they are.
+    pub fn snapshot(user: &User) -> User {
+        let fingerprint = |value: &str| personal_data_fingerprint(user.id(), value);
+        let mut snapshot = user.clone();
+        snapshot.set_username(fingerprint(user.username()));
+        snapshot.set_email(fingerprint(user.email()));
+        snapshot.set_display_name(user.display_name().map(fingerprint));
+        snapshot.set_phone(None);
+        snapshot.touch(user.updated_at());
+        snapshot
+    }
+}
+
+// Stands in for a personal value in the audit trail. The same value for the same
+// user always gives the same fingerprint, so the trail shows that a field changed
+// without showing what it was.
+pub fn personal_data_fingerprint(user_id: UserId, value: &str) -> String {
+    let digest = format!("{:x}", Sha256::digest(format!("{}:{}", user_id, value)));
+    format!("sha256:{}", &digest[..16])
+}
+
+// A record once it is in the trail. Each entry's hash covers its own content and
+// the previous entry's hash, so altering, dropping or reordering an entry breaks
+// every hash after it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub sequence: u64,
+    pub record: AuditRecord,
+    pub previous_hash: String,
+    pub hash: String,
+}
+
+const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+impl AuditEntry {
+    fn seal(sequence: u64, record: AuditRecord, previous_hash: String) -> Result<Self, String> {
+        let hash = Self::chain_hash(sequence, &record, &previous_hash)?;
+        Ok(AuditEntry { sequence, record, previous_hash, hash })
+    }
+    
+    fn chain_hash(sequence: u64, record: &AuditRecord, previous_hash: &str) -> Result<String, String> {
+        let content = serde_json::to_string(&(sequence, record, previous_hash)).map_err(|e| e.to_string())?;
+        Ok(format!("{:x}", Sha256::digest(content)))
+    }
+}
+
+// Checks a whole trail, from its first entry, returning how many entries were checked
+pub fn verify_audit_chain(entries: &[AuditEntry]) -> Result<usize, String> {
+    let mut previous_hash = GENESIS_HASH.to_string();
+    for (expected, entry) in (0u64..).zip(entries) {
+        if entry.sequence != expected {
+            return Err(format!("Expected audit entry {}, found {}", expected, entry.sequence));
+        }
+        if entry.previous_hash != previous_hash {
+            return Err(format!("Audit entry {} does not link to the entry before it", entry.sequence));
+        }
+        if AuditEntry::chain_hash(entry.sequence, &entry.record, &entry.previous_hash)? != entry.hash {
+            return Err(format!("Audit entry {} has been altered", entry.sequence));
+        }
+        previous_hash = entry.hash.clone();
+    }
+    Ok(entries.len())
+}
+
+// Audit trail trait - keeps an append-only, tamper-evident history of user changes.
+// Separate from ActivityLogger: logs are for operators and may be rotated or
+// dropped; the trail is evidence and is only ever appended to.
+pub trait AuditTrail: Send + Sync {
+    fn append(&self, record: AuditRecord) -> Result<AuditEntry, String>;
+    fn entries(&self) -> Result<Vec<AuditEntry>, String>;
+    
+    fn entries_for_user(&self, user_id: UserId) -> Result<Vec<AuditEntry>, String> {
+        let mut entries = self.entries()?;
+        entries.retain(|entry| entry.record.user_id == user_id);
+        Ok(entries)
+    }
+    
+    // Entries recorded at or after `from` and before `to`
+    fn entries_between(&self, from: DateTime<Utc>, to: DateTime<Utc>) -> Result<Vec<AuditEntry>, String> {
+        let mut entries = self.entries()?;
+        entries.retain(|entry| entry.record.at >= from && entry.record.at < to);
+        Ok(entries)
+    }
+    
+    fn verify(&self) -> Result<usize, String> {
+        verify_audit_chain(&self.entries()?)
+    }
+}
+
+// Concrete implementation of AuditTrail kept in memory
+#[derive(Default)]
+pub struct InMemoryAuditTrail {
+    entries: Mutex<Vec<AuditEntry>>,
+}
+
+impl InMemoryAuditTrail {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl AuditTrail for InMemoryAuditTrail {
+    fn append(&self, record: AuditRecord) -> Result<AuditEntry, String> {
+        let mut entries = self.entries.lock().map_err(|e| e.to_string())?;
+        let previous_hash = entries.last().map_or_else(|| GENESIS_HASH.to_string(), |last| last.hash.clone());
+        let entry = AuditEntry::seal(entries.len() as u64, record, previous_hash)?;
+        entries.push(entry.clone());
+        Ok(entry)
+    }
+    
+    fn entries(&self) -> Result<Vec<AuditEntry>, String> {
+        self.entries.lock().map(|entries| entries.clone()).map_err(|e| e.to_string())
+    }
+}
@@ -0,0 +1,96 @@
+// Time and identity sources, so tests can control both
+
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicU64, Ordering};
+use argon2::password_hash::rand_core::{OsRng, RngCore};
+use chrono::{DateTime, Duration, Utc};
+use crate::UserId;
+
+// `len` random bytes from the OS, hex encoded
+pub(crate) fn random_hex(len: usize) -> String {
+    let mut bytes = vec![0u8; len];
+    OsRng.fill_bytes(&mut bytes);
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+// Source of the current time, so time-based behaviour can be driven by tests
+pub trait Clock: Send + Sync {
+    fn now(&self) -> DateTime<Utc>;
+}
+
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+// A clock that only moves when told to; clones share the same time
+#[derive(Clone)]
+pub struct ManualClock {
+    now: Arc<Mutex<DateTime<Utc>>>,
+}
+
+impl ManualClock {
+    pub fn new(start: DateTime<Utc>) -> Self {
+        ManualClock { now: Arc::new(Mutex::new(start)) }
+    }
+    
+    pub fn advance(&self, by: Duration) {
+        if let Ok(mut now) = self.now.lock() {
+            *now += by;
+        }
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> DateTime<Utc> {
+        self.now.lock().map(|now| *now).unwrap_or_else(|e| *e.into_inner())
+    }
+}
+
+// Source of new user ids and of the random tokens behind sessions and anonymized
+// values, so tests can know in advance what a service will hand out
+pub trait IdGenerator: Send + Sync {
+    fn next_user_id(&self) -> UserId;
+    // `len` bytes, hex encoded
+    fn token(&self, len: usize) -> String;
+}
+
+// The process-wide user id counter and random bytes from the OS
+pub struct SystemIdGenerator;
+
+impl IdGenerator for SystemIdGenerator {
+    fn next_user_id(&self) -> UserId {
+        UserId::generate()
+    }
+    
+    fn token(&self, len: usize) -> String {
+        random_hex(len)
+    }
+}
+
+// User ids counting up from user-1 and tokens that are a zero-padded counter;
+// clones share the counters
+#[derive(Clone, Default)]
+pub struct SequentialIdGenerator {
+    users: Arc<AtomicU64>,
+    tokens: Arc<AtomicU64>,
+}
+
+impl SequentialIdGenerator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl IdGenerator for SequentialIdGenerator {
+    fn next_user_id(&self) -> UserId {
+        UserId(self.users.fetch_add(1, Ordering::Relaxed) + 1)
+    }
+    
+    fn token(&self, len: usize) -> String {
+        format!("{:0width$x}", self.tokens.fetch_add(1, Ordering::Relaxed) + 1, width = len * 2)
+    }
+}
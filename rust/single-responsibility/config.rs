@@ -0,0 +1,638 @@
+// Choosing collaborators at run time: boxed trait objects, ServiceConfig,
+// UserServiceBuilder and the compose! macro
+
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::sync::Arc;
+use serde::{Deserialize, Serialize};
+use crate::{default_log_path, ActivityLogger, AnalyticsService, ApiVersion, Argon2PasswordHasher, AuditEvent, CorrectUserService, CredentialStore, DatabaseUserRepository, DefaultUserFormatter, DefaultUserValidator, EmailMessage, EmailService, EmailTemplateRenderer, FakePasswordHasher, FileActivityLogger, FirebaseNotificationService, GoogleAnalyticsService, InMemoryCredentialStore, InMemorySessionService, InMemoryUserRepository, NotificationService, Page, Pagination, PasswordHasher, PhoneNumber, PlaceholderTemplateRenderer, SMTPEmailService, SessionService, TracingActivityLogger, UnitOfWork, User, UserFormatter, UserId, UserRepository, UserResponse, UserValidator, ValidationReport};
+
+// Boxed collaborators forward to the value inside, so backends can be chosen at runtime
+impl<R: UnitOfWork + ?Sized> UnitOfWork for Box<R> {
+    fn begin(&self) -> Result<(), String> {
+        (**self).begin()
+    }
+    
+    fn commit(&self) -> Result<(), String> {
+        (**self).commit()
+    }
+    
+    fn rollback(&self) -> Result<(), String> {
+        (**self).rollback()
+    }
+}
+
+impl<R: UserRepository + ?Sized> UserRepository for Box<R> {
+    fn save(&self, user: &User) -> Result<bool, String> {
+        (**self).save(user)
+    }
+    
+    fn update(&self, user: &User) -> Result<bool, String> {
+        (**self).update(user)
+    }
+    
+    fn find_by_id(&self, user_id: &str) -> Result<Option<User>, String> {
+        (**self).find_by_id(user_id)
+    }
+    
+    fn find_by_email(&self, email: &str) -> Result<Option<User>, String> {
+        (**self).find_by_email(email)
+    }
+    
+    fn find_all(&self) -> Result<Vec<User>, String> {
+        (**self).find_all()
+    }
+    
+    fn search(&self, query: &str, page: Pagination) -> Result<Page<User>, String> {
+        (**self).search(query, page)
+    }
+    
+    fn count(&self) -> Result<usize, String> {
+        (**self).count()
+    }
+    
+    fn delete(&self, user: &User) -> Result<bool, String> {
+        (**self).delete(user)
+    }
+}
+
+impl<E: EmailService + ?Sized> EmailService for Box<E> {
+    fn send(&self, message: &EmailMessage) -> Result<bool, String> {
+        (**self).send(message)
+    }
+}
+
+impl<E: EmailService + ?Sized> EmailService for Rc<E> {
+    fn send(&self, message: &EmailMessage) -> Result<bool, String> {
+        (**self).send(message)
+    }
+}
+
+impl<L: ActivityLogger + ?Sized> ActivityLogger for Box<L> {
+    fn log_user_activity(&self, username: &str, activity: &str) {
+        (**self).log_user_activity(username, activity)
+    }
+    
+    fn log_error(&self, error: &str, context: &str) {
+        (**self).log_error(error, context)
+    }
+    
+    fn log_audit(&self, event: &AuditEvent) {
+        (**self).log_audit(event)
+    }
+}
+
+impl<L: ActivityLogger + ?Sized> ActivityLogger for Arc<L> {
+    fn log_user_activity(&self, username: &str, activity: &str) {
+        (**self).log_user_activity(username, activity)
+    }
+    
+    fn log_error(&self, error: &str, context: &str) {
+        (**self).log_error(error, context)
+    }
+    
+    fn log_audit(&self, event: &AuditEvent) {
+        (**self).log_audit(event)
+    }
+}
+
+impl<L: ActivityLogger + ?Sized> ActivityLogger for Rc<L> {
+    fn log_user_activity(&self, username: &str, activity: &str) {
+        (**self).log_user_activity(username, activity)
+    }
+    
+    fn log_error(&self, error: &str, context: &str) {
+        (**self).log_error(error, context)
+    }
+    
+    fn log_audit(&self, event: &AuditEvent) {
+        (**self).log_audit(event)
+    }
+}
+
+impl<A: AnalyticsService + ?Sized> AnalyticsService for Box<A> {
+    fn track_user_event(&self, username: &str, event_name: &str, metadata: Option<HashMap<String, String>>) {
+        (**self).track_user_event(username, event_name, metadata)
+    }
+    
+    fn track_user_registration(&self, username: &str) {
+        (**self).track_user_registration(username)
+    }
+    
+    fn track_user_update(&self, username: &str) {
+        (**self).track_user_update(username)
+    }
+}
+
+impl<N: NotificationService + ?Sized> NotificationService for Box<N> {
+    fn send_push_notification(&self, username: &str, message: &str) -> Result<bool, String> {
+        (**self).send_push_notification(username, message)
+    }
+    
+    fn send_sms_notification(&self, phone: &PhoneNumber, message: &str) -> Result<bool, String> {
+        (**self).send_sms_notification(phone, message)
+    }
+}
+
+impl<H: PasswordHasher + ?Sized> PasswordHasher for Box<H> {
+    fn hash_password(&self, password: &str) -> Result<String, String> {
+        (**self).hash_password(password)
+    }
+    
+    fn verify_password(&self, password: &str, hash: &str) -> Result<bool, String> {
+        (**self).verify_password(password, hash)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RepositoryBackend {
+    Memory,
+    #[default]
+    Database,
+    // Needs the `sqlite` feature
+    Sqlite,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LoggerBackend {
+    #[default]
+    File,
+    Tracing,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PasswordBackend {
+    #[default]
+    Argon2,
+    Fake,
+}
+
+// Settings for wiring a service at runtime. Missing keys keep the values that
+// create_user_service() uses.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ServiceConfig {
+    pub smtp_host: String,
+    pub log_path: String,
+    pub analytics_id: String,
+    // None turns push and SMS notifications off
+    pub notifications_api_key: Option<String>,
+    pub repository: RepositoryBackend,
+    // Used when `repository` is sqlite
+    pub sqlite_path: String,
+    pub logger: LoggerBackend,
+    pub passwords: PasswordBackend,
+}
+
+impl Default for ServiceConfig {
+    fn default() -> Self {
+        ServiceConfig {
+            smtp_host: "smtp.example.com".to_string(),
+            log_path: default_log_path(),
+            analytics_id: "GA-XXXXX-X".to_string(),
+            notifications_api_key: Some("firebase-api-key".to_string()),
+            repository: RepositoryBackend::default(),
+            sqlite_path: "users.db".to_string(),
+            logger: LoggerBackend::default(),
+            passwords: PasswordBackend::default(),
+        }
+    }
+}
+
+impl ServiceConfig {
+    pub const ENV_PREFIX: &'static str = "USER_SERVICE_";
+    
+    pub fn from_toml(text: &str) -> Result<Self, String> {
+        toml::from_str(text).map_err(|e| format!("Invalid service config: {}", e))
+    }
+    
+    // Applies USER_SERVICE_* environment variables on top of `self`
+    pub fn with_env(self) -> Result<Self, String> {
+        self.with_vars(std::env::vars())
+    }
+    
+    // Same as `with_env`, from any list of variables; others are ignored.
+    // An empty USER_SERVICE_NOTIFICATIONS_API_KEY turns notifications off.
+    pub fn with_vars(mut self, vars: impl IntoIterator<Item = (String, String)>) -> Result<Self, String> {
+        for (name, value) in vars {
+            let Some(key) = name.strip_prefix(Self::ENV_PREFIX) else {
+                continue;
+            };
+            match key {
+                "SMTP_HOST" => self.smtp_host = value,
+                "LOG_PATH" => self.log_path = value,
+                "ANALYTICS_ID" => self.analytics_id = value,
+                "NOTIFICATIONS_API_KEY" => self.notifications_api_key = Some(value).filter(|key| !key.is_empty()),
+                "REPOSITORY" => self.repository = Self::parse_choice(&name, &value)?,
+                "SQLITE_PATH" => self.sqlite_path = value,
+                "LOGGER" => self.logger = Self::parse_choice(&name, &value)?,
+                "PASSWORDS" => self.passwords = Self::parse_choice(&name, &value)?,
+                _ => return Err(format!("Unknown setting {}", name)),
+            }
+        }
+        Ok(self)
+    }
+    
+    // Backend names are parsed exactly as they are in TOML
+    fn parse_choice<T: serde::de::DeserializeOwned>(name: &str, value: &str) -> Result<T, String> {
+        use serde::de::value::{Error, StrDeserializer};
+        
+        T::deserialize(StrDeserializer::<Error>::new(value)).map_err(|e| format!("Invalid {}: {}", name, e))
+    }
+}
+
+// The service type produced by UserServiceBuilder: collaborators with more than
+// one backend are boxed, the rest use the defaults
+pub type ConfiguredUserService = CorrectUserService<
+    Box<dyn UserRepository>,
+    Box<dyn EmailService>,
+    Box<dyn ActivityLogger>,
+    DefaultUserValidator,
+    DefaultUserFormatter,
+    Box<dyn AnalyticsService>,
+    Box<dyn NotificationService>,
+    Box<dyn PasswordHasher>,
+    InMemoryCredentialStore,
+    InMemorySessionService,
+    PlaceholderTemplateRenderer,
+>;
+
+// Builds a service from defaults, a ServiceConfig, explicit overrides, or all three
+// in that order. Anything not set falls back to what create_user_service() uses.
+#[derive(Default)]
+pub struct UserServiceBuilder {
+    repository: Option<Box<dyn UserRepository>>,
+    email_service: Option<Box<dyn EmailService>>,
+    logger: Option<Box<dyn ActivityLogger>>,
+    analytics: Option<Box<dyn AnalyticsService>>,
+    notification_service: Option<Option<Box<dyn NotificationService>>>,
+    password_hasher: Option<Box<dyn PasswordHasher>>,
+}
+
+impl UserServiceBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    pub fn from_config(config: &ServiceConfig) -> Result<Self, String> {
+        let repository: Box<dyn UserRepository> = match config.repository {
+            RepositoryBackend::Memory => Box::new(InMemoryUserRepository::new()),
+            RepositoryBackend::Database => Box::new(DatabaseUserRepository::new()),
+            #[cfg(feature = "sqlite")]
+            RepositoryBackend::Sqlite => Box::new(crate::SqliteUserRepository::open(&config.sqlite_path)?),
+            #[cfg(not(feature = "sqlite"))]
+            RepositoryBackend::Sqlite => return Err("The sqlite repository needs the `sqlite` feature".to_string()),
+        };
+        let logger: Box<dyn ActivityLogger> = match config.logger {
+            LoggerBackend::File => Box::new(FileActivityLogger::new(config.log_path.clone())),
+            LoggerBackend::Tracing => Box::new(TracingActivityLogger::new()),
+        };
+        let password_hasher: Box<dyn PasswordHasher> = match config.passwords {
+            PasswordBackend::Argon2 => Box::new(Argon2PasswordHasher::new()),
+            PasswordBackend::Fake => Box::new(FakePasswordHasher::new()),
+        };
+        let notification_service = config.notifications_api_key.clone().map(|api_key| {
+            Box::new(FirebaseNotificationService::new(api_key)) as Box<dyn NotificationService>
+        });
+        
+        Ok(UserServiceBuilder {
+            repository: Some(repository),
+            email_service: Some(Box::new(SMTPEmailService::new(config.smtp_host.clone()))),
+            logger: Some(logger),
+            analytics: Some(Box::new(GoogleAnalyticsService::new(config.analytics_id.clone()))),
+            notification_service: Some(notification_service),
+            password_hasher: Some(password_hasher),
+        })
+    }
+    
+    pub fn repository(mut self, repository: impl UserRepository + 'static) -> Self {
+        self.repository = Some(Box::new(repository));
+        self
+    }
+    
+    pub fn email_service(mut self, email_service: impl EmailService + 'static) -> Self {
+        self.email_service = Some(Box::new(email_service));
+        self
+    }
+    
+    pub fn logger(mut self, logger: impl ActivityLogger + 'static) -> Self {
+        self.logger = Some(Box::new(logger));
+        self
+    }
+    
+    pub fn analytics(mut self, analytics: impl AnalyticsService + 'static) -> Self {
+        self.analytics = Some(Box::new(analytics));
+        self
+    }
+    
+    pub fn notification_service(mut self, notification_service: impl NotificationService + 'static) -> Self {
+        self.notification_service = Some(Some(Box::new(notification_service)));
+        self
+    }
+    
+    pub fn without_notifications(mut self) -> Self {
+        self.notification_service = Some(None);
+        self
+    }
+    
+    pub fn password_hasher(mut self, password_hasher: impl PasswordHasher + 'static) -> Self {
+        self.password_hasher = Some(Box::new(password_hasher));
+        self
+    }
+    
+    pub fn build(self) -> ConfiguredUserService {
+        let defaults = ServiceConfig::default();
+        CorrectUserService::new(
+            self.repository.unwrap_or_else(|| Box::new(DatabaseUserRepository::new())),
+            self.email_service.unwrap_or_else(|| Box::new(SMTPEmailService::new(defaults.smtp_host))),
+            self.logger.unwrap_or_else(|| Box::new(FileActivityLogger::new(defaults.log_path))),
+            DefaultUserValidator::new(),
+            DefaultUserFormatter::new(),
+            self.analytics.unwrap_or_else(|| Box::new(GoogleAnalyticsService::new(defaults.analytics_id))),
+            self.notification_service.unwrap_or_else(|| {
+                defaults.notifications_api_key.map(|api_key| {
+                    Box::new(FirebaseNotificationService::new(api_key)) as Box<dyn NotificationService>
+                })
+            }),
+        )
+        .with_password_hasher(self.password_hasher.unwrap_or_else(|| Box::new(Argon2PasswordHasher::new())))
+    }
+}
+
+impl<V: UserValidator + ?Sized> UserValidator for Box<V> {
+    fn validate_email(&self, email: &str) -> ValidationReport {
+        (**self).validate_email(email)
+    }
+    
+    fn validate_username(&self, username: &str) -> ValidationReport {
+        (**self).validate_username(username)
+    }
+    
+    fn validate_password(&self, password: &str) -> ValidationReport {
+        (**self).validate_password(password)
+    }
+    
+    fn validate_user(&self, user: &User) -> ValidationReport {
+        (**self).validate_user(user)
+    }
+    
+    fn validate_phone(&self, phone: &str) -> ValidationReport {
+        (**self).validate_phone(phone)
+    }
+    
+    fn normalize_username(&self, username: &str) -> String {
+        (**self).normalize_username(username)
+    }
+}
+
+impl<F: UserFormatter + ?Sized> UserFormatter for Box<F> {
+    fn format_user_for_display(&self, user: &User) -> String {
+        (**self).format_user_for_display(user)
+    }
+    
+    fn format_user_for_api(&self, user: &User, version: ApiVersion) -> UserResponse {
+        (**self).format_user_for_api(user, version)
+    }
+}
+
+impl<C: CredentialStore + ?Sized> CredentialStore for Box<C> {
+    fn set_password_hash(&self, user_id: UserId, hash: String) -> Result<(), String> {
+        (**self).set_password_hash(user_id, hash)
+    }
+    
+    fn password_hash(&self, user_id: UserId) -> Result<Option<String>, String> {
+        (**self).password_hash(user_id)
+    }
+    
+    fn remove_credentials(&self, user_id: UserId) -> Result<bool, String> {
+        (**self).remove_credentials(user_id)
+    }
+}
+
+impl<S: SessionService + ?Sized> SessionService for Box<S> {
+    fn create_session(&self, user_id: UserId) -> Result<String, String> {
+        (**self).create_session(user_id)
+    }
+    
+    fn validate_token(&self, token: &str) -> Result<Option<UserId>, String> {
+        (**self).validate_token(token)
+    }
+    
+    fn revoke(&self, token: &str) -> Result<bool, String> {
+        (**self).revoke(token)
+    }
+}
+
+impl<T: EmailTemplateRenderer + ?Sized> EmailTemplateRenderer for Box<T> {
+    fn render(&self, template: &str, to: &str, values: &HashMap<String, String>) -> Result<EmailMessage, String> {
+        (**self).render(template, to, values)
+    }
+}
+
+// Declarative wiring for demos and tests: starts from `create_user_service()` and
+// replaces only the collaborators that are named, in any order.
+//
+//     let service = compose! {
+//         repository: database,
+//         email: smtp("smtp.internal.example.com"),
+//         notifications: none,
+//     };
+//
+// `repository: in_memory`, `email: recording` and `logger: null` wire a service
+// that touches nothing outside the process; pass `recording(email.clone())` to
+// inspect the sent emails afterwards.
+#[macro_export]
+macro_rules! compose {
+    ($($component:ident : $choice:ident $(($($arg:expr),* $(,)?))?),* $(,)?) => {{
+        let service = $crate::create_user_service();
+        $( let service = $crate::compose!(@set service, $component, $choice $(($($arg),*))?); )*
+        service
+    }};
+    
+    (@set $service:ident, repository, database) => {
+        $service.with_repository($crate::DatabaseUserRepository::new())
+    };
+    (@set $service:ident, repository, in_memory) => {
+        $service.with_repository($crate::InMemoryUserRepository::new())
+    };
+    (@set $service:ident, email, smtp) => {
+        $crate::compose!(@set $service, email, smtp("smtp.example.com"))
+    };
+    (@set $service:ident, email, smtp($server:expr)) => {
+        $service.with_email_service($crate::SMTPEmailService::new(($server).to_string()))
+    };
+    (@set $service:ident, email, recording) => {
+        $crate::compose!(@set $service, email, recording($crate::testing::MockEmailService::default()))
+    };
+    (@set $service:ident, email, recording($email:expr)) => {
+        $service.with_email_service($email)
+    };
+    (@set $service:ident, logger, file) => {
+        $crate::compose!(@set $service, logger, file($crate::default_log_path()))
+    };
+    (@set $service:ident, logger, file($path:expr)) => {
+        $service.with_logger($crate::FileActivityLogger::new(($path).to_string()))
+    };
+    (@set $service:ident, logger, tracing) => {
+        $service.with_logger($crate::TracingActivityLogger::new())
+    };
+    (@set $service:ident, logger, null) => {
+        $service.with_logger($crate::patterns::null_object::NoopLogger)
+    };
+    (@set $service:ident, validator, default) => {
+        $service.with_validator($crate::DefaultUserValidator::new())
+    };
+    (@set $service:ident, formatter, default) => {
+        $service.with_formatter($crate::DefaultUserFormatter::new())
+    };
+    (@set $service:ident, analytics, google) => {
+        $crate::compose!(@set $service, analytics, google("GA-XXXXX-X"))
+    };
+    (@set $service:ident, analytics, google($tracking_id:expr)) => {
+        $service.with_analytics($crate::GoogleAnalyticsService::new(($tracking_id).to_string()))
+    };
+    (@set $service:ident, notifications, firebase) => {
+        $crate::compose!(@set $service, notifications, firebase("firebase-api-key"))
+    };
+    (@set $service:ident, notifications, firebase($api_key:expr)) => {
+        $service.with_notification_service(Some($crate::FirebaseNotificationService::new(($api_key).to_string())))
+    };
+    (@set $service:ident, notifications, none) => {
+        $service.with_notification_service(None::<$crate::FirebaseNotificationService>)
+    };
+    (@set $service:ident, passwords, argon2) => {
+        $service.with_password_hasher($crate::Argon2PasswordHasher::new())
+    };
+    (@set $service:ident, passwords, fake) => {
+        $service.with_password_hasher($crate::FakePasswordHasher::new())
+    };
+    (@set $service:ident, credentials, memory) => {
+        $service.with_credential_store($crate::InMemoryCredentialStore::new())
+    };
+    (@set $service:ident, templates, default) => {
+        $service.with_template_renderer($crate::PlaceholderTemplateRenderer::with_defaults())
+    };
+    (@set $service:ident, sessions, memory) => {
+        $service.with_session_service($crate::InMemorySessionService::default())
+    };
+    (@set $service:ident, sessions, memory($ttl:expr)) => {
+        $service.with_session_service($crate::InMemorySessionService::new($ttl))
+    };
+}
+
+// Tests for ServiceConfig loading and UserServiceBuilder wiring
+#[cfg(test)]
+mod service_builder_tests {
+    use super::*;
+    use crate::InMemoryLayer;
+    use crate::testing::{MockActivityLogger, MockEmailService, MockNotificationService};
+    
+    fn vars(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs.iter().map(|(name, value)| (name.to_string(), value.to_string())).collect()
+    }
+    
+    #[test]
+    fn empty_config_matches_the_defaults() {
+        assert_eq!(ServiceConfig::from_toml("").unwrap(), ServiceConfig::default());
+        assert_eq!(ServiceConfig::default().with_vars(vars(&[("PATH", "/bin")])).unwrap(), ServiceConfig::default());
+    }
+    
+    #[test]
+    fn toml_sets_hosts_and_backends() {
+        let config = ServiceConfig::from_toml(r#"
+            smtp_host = "mail.internal"
+            analytics_id = "GA-12345-6"
+            repository = "memory"
+            logger = "tracing"
+            passwords = "fake"
+        "#).unwrap();
+        assert_eq!(config.smtp_host, "mail.internal");
+        assert_eq!(config.analytics_id, "GA-12345-6");
+        assert_eq!(config.repository, RepositoryBackend::Memory);
+        assert_eq!(config.logger, LoggerBackend::Tracing);
+        assert_eq!(config.passwords, PasswordBackend::Fake);
+        assert_eq!(config.log_path, default_log_path(), "unset keys keep their defaults");
+    }
+    
+    #[test]
+    fn typos_and_unknown_backends_are_errors() {
+        assert!(ServiceConfig::from_toml("smtp_hots = \"mail\"").is_err());
+        assert!(ServiceConfig::from_toml("repository = \"mongo\"").is_err());
+        assert!(ServiceConfig::default().with_vars(vars(&[("USER_SERVICE_LOGGER", "syslog")])).is_err());
+        assert!(ServiceConfig::default().with_vars(vars(&[("USER_SERVICE_SMTP_HOTS", "mail")])).is_err());
+    }
+    
+    #[test]
+    fn environment_overrides_the_file() {
+        let config = ServiceConfig::from_toml("smtp_host = \"mail.internal\"\nrepository = \"database\"")
+            .unwrap()
+            .with_vars(vars(&[
+                ("USER_SERVICE_SMTP_HOST", "mail.staging"),
+                ("USER_SERVICE_REPOSITORY", "memory"),
+                ("USER_SERVICE_NOTIFICATIONS_API_KEY", ""),
+            ]))
+            .unwrap();
+        assert_eq!(config.smtp_host, "mail.staging");
+        assert_eq!(config.repository, RepositoryBackend::Memory);
+        assert_eq!(config.notifications_api_key, None);
+    }
+    
+    #[test]
+    fn default_builder_wires_a_working_service() {
+        let service = UserServiceBuilder::new().build();
+        let user = service.create_user("ada_l".to_string(), "ada@example.com".to_string()).unwrap();
+        assert_eq!(service.format_user(&user), crate::create_user_service().format_user(&user));
+    }
+    
+    #[test]
+    fn overrides_replace_only_what_they_name() {
+        let (email, logger, notifications) = (MockEmailService::default(), MockActivityLogger::default(), MockNotificationService::default());
+        let service = UserServiceBuilder::new()
+            .repository(InMemoryUserRepository::new())
+            .email_service(email.clone())
+            .logger(logger.clone())
+            .notification_service(notifications.clone())
+            .build();
+        service.create_user("ada_l".to_string(), "ada@example.com".to_string()).unwrap();
+        assert_eq!(email.sent()[0].to, "ada@example.com");
+        assert_eq!(logger.activities(), vec![("ada_l".to_string(), "User created".to_string())]);
+        assert_eq!(notifications.spy().calls_to("send_push_notification"), 1);
+    }
+    
+    #[test]
+    fn config_choices_reach_the_service() {
+        let config = ServiceConfig {
+            repository: RepositoryBackend::Memory,
+            logger: LoggerBackend::Tracing,
+            notifications_api_key: None,
+            ..ServiceConfig::default()
+        };
+        let notifications = MockNotificationService::default();
+        let service = UserServiceBuilder::from_config(&config).unwrap().build();
+        let (created, events) = InMemoryLayer::capture(|| {
+            service.create_user("ada_l".to_string(), "ada@example.com".to_string())
+        });
+        assert!(created.is_ok());
+        assert!(events.iter().any(|event| event.field("activity") == Some("User created")), "tracing logger was chosen");
+        
+        // Overrides still apply after from_config
+        let service = UserServiceBuilder::from_config(&config).unwrap().notification_service(notifications.clone()).build();
+        service.create_user("grace_h".to_string(), "grace@example.com".to_string()).unwrap();
+        assert_eq!(notifications.spy().calls_to("send_push_notification"), 1);
+    }
+    
+    #[test]
+    fn compose_wires_in_process_collaborators() {
+        let service = crate::compose! { repository: in_memory, email: recording, logger: null };
+        service.create_user("ada_l".to_string(), "ada@example.com".to_string()).unwrap();
+        assert_eq!(service.list_users(Pagination::first(10)).unwrap().total, 1);
+        
+        let email = MockEmailService::default();
+        let service = crate::compose! { email: recording(email.clone()), repository: in_memory };
+        service.create_user("grace_h".to_string(), "grace@example.com".to_string()).unwrap();
+        assert_eq!(email.sent()[0].to, "grace@example.com");
+    }
+}
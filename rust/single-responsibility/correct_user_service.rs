@@ -1,495 +1,415 @@
 // CORRECT: Each struct/trait has a single responsibility
 // Demonstrates proper separation of concerns in Rust
+//
+// Each responsibility lives in its own module below. Everything is re-exported
+// from here, so `crate::User` and `$crate::compose!` work as before.
+
+pub mod user;
+pub mod repository;
+pub mod export;
+pub mod email;
+pub mod clock;
+pub mod audit;
+pub mod logging;
+pub mod metrics;
+pub mod validation;
+pub mod service;
+pub mod accounts;
+pub mod api;
+pub mod analytics;
+pub mod notifications;
+pub mod outbox;
+pub mod jobs;
+pub mod config;
+pub mod events;
+#[cfg(feature = "async")]
+pub mod async_service;
+
+pub use user::*;
+pub use repository::*;
+pub use export::*;
+pub use email::*;
+pub use clock::*;
+pub use audit::*;
+pub use logging::*;
+pub use metrics::*;
+pub use validation::*;
+pub use service::*;
+pub use accounts::*;
+pub use api::*;
+pub use analytics::*;
+pub use notifications::*;
+pub use outbox::*;
+pub use jobs::*;
+pub use config::*;
+pub use events::*;
+#[cfg(feature = "async")]
+pub use async_service::*;
+
+// Ready-made doubles for every collaborator, for testing code that uses
+// CorrectUserService without hand-writing mocks. Each double records its calls
+// and can be told to fail; clones share state, so a test keeps one clone and
+// hands the other to the service.
+pub mod testing;
+
+// The violation example, compiled unchanged so the walkthrough can drive it
+#[cfg(feature = "walkthrough")]
+#[path = "violation_user_service.rs"]
+#[allow(dead_code, unused_imports, unused_variables)]
+pub mod violation;
+
+// Runs one scripted scenario against ViolationUserService and CorrectUserService
+// (enable the `walkthrough` feature) and reports, step by step, which component
+// handled each part of every action. The correct side runs on the `testing`
+// doubles, so nothing is sent or stored for real.
+#[cfg(feature = "walkthrough")]
+pub mod walkthrough;
+
+// Larger patterns over the same components: CQRS (patterns/cqrs.rs),
+// runtime-swappable strategies for backups and retries (patterns/strategy.rs),
+// stackable email decorators (patterns/decorator.rs), an adapter for a
+// pager client with its own API (patterns/adapter.rs) and do-nothing
+// collaborators that replace Option fields (patterns/null_object.rs)
+#[path = "patterns/mod.rs"]
+pub mod patterns;
 
-use std::collections::HashMap;
-use chrono::{DateTime, Utc};
-use regex::Regex;
-
-// User entity - only handles user data
-#[derive(Debug, Clone)]
-pub struct User {
-    username: String,
-    email: String,
-}
-
-impl User {
-    pub fn new(username: String, email: String) -> Self {
-        User { username, email }
-    }
+// Example usage with dependency injection - uncomment to run
+/*
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Create user service with all dependencies
+    let user_service = create_user_service();
     
-    pub fn username(&self) -> &str {
-        &self.username
-    }
+    // Create a new user
+    let mut user = user_service.create_user("john_doe".to_string(), "john@example.com".to_string())?;
+    println!("User created: {}", user_service.format_user(&user));
     
-    pub fn email(&self) -> &str {
-        &self.email
-    }
+    // Update the user
+    user_service.update_user(&mut user, "john_smith".to_string(), "john.smith@example.com".to_string())?;
+    println!("User updated: {}", user_service.format_user(&user));
+    println!("API v1: {}", user_service.get_user_api_data(&user, ApiVersion::V1).to_json()?);
+    let v2_json = user_service.get_user_api_data(&user, ApiVersion::LATEST).to_json()?;
+    println!("API v2: {}", v2_json);
+    println!("Round trip: {:?}", UserResponse::from_json(&v2_json)?);
+    let grace = user_service.create_user_from_json(r#"{"username": "grace_h", "email": "grace@example.com"}"#)?;
+    println!("Created from JSON: {}", user_service.format_user(&grace));
     
-    pub fn set_username(&mut self, username: String) {
-        self.username = username;
-    }
+    // Profile fields: the phone is parsed to E.164 and display follows the locale
+    user_service.update_profile(&mut user, Some("+1 (415) 555-0132"), Some("John Smith".to_string()), Some("de-DE".to_string()))?;
+    println!("Profile: {} / {:?}", user_service.format_user(&user), user.phone().map(PhoneNumber::as_str));
+    user_service.send_sms(&user, "Your verification code is 123456")?;
     
-    pub fn set_email(&mut self, email: String) {
-        self.email = email;
-    }
-}
-
-// User repository trait - defines user persistence operations
-pub trait UserRepository {
-    fn save(&self, user: &User) -> Result<bool, String>;
-    fn update(&self, user: &User) -> Result<bool, String>;
-    fn find_by_id(&self, user_id: &str) -> Result<Option<User>, String>;
-    fn delete(&self, user: &User) -> Result<bool, String>;
-}
-
-// Concrete implementation of UserRepository
-pub struct DatabaseUserRepository {
-    connection: Option<String>,
-}
-
-impl DatabaseUserRepository {
-    pub fn new() -> Self {
-        DatabaseUserRepository {
-            connection: None,
+    // Validation reports every failed rule, not just "invalid"
+    if let Err(UserServiceError::Invalid(report)) = user_service.create_user("x!".to_string(), "not-an-email".to_string()) {
+        for failure in report.failures() {
+            println!("{} failed {}: {}", failure.field, failure.rule, failure.reason);
         }
     }
     
-    fn connect(&mut self) -> Result<(), String> {
-        if self.connection.is_none() {
-            println!("Connecting to database...");
-            self.connection = Some("database_connection".to_string());
-        }
-        Ok(())
-    }
-}
-
-impl UserRepository for DatabaseUserRepository {
-    fn save(&self, user: &User) -> Result<bool, String> {
-        println!("Saving user to database: {}", user.username());
-        // Database save logic
-        Ok(true)
+    // Export every stored user; the exporters escape awkward field values
+    let archive = InMemoryUserRepository::new();
+    archive.save(&user)?;
+    archive.save(&User::new("smith, \"jr\"\nthe 2nd".to_string(), "<smith>&co@example.com".to_string()))?;
+    let exporters: [(&str, Box<dyn UserExporter>); 3] = [
+        ("CSV", Box::new(CsvExporter::new())),
+        ("JSON", Box::new(JsonExporter::new())),
+        ("XML", Box::new(XmlExporter::new())),
+    ];
+    for (name, exporter) in exporters.iter() {
+        println!("--- {} ---", name);
+        exporter.export_all(&archive, &mut std::io::stdout())?;
     }
     
-    fn update(&self, user: &User) -> Result<bool, String> {
-        println!("Updating user in database: {}", user.username());
-        // Database update logic
-        Ok(true)
-    }
+    // Look users up by email, or page through a search of the same repository
+    println!("By email: {:?}", archive.find_by_email("JOHN.SMITH@example.com")?.map(|user| user.id()));
+    let page = archive.search("smith", Pagination::first(1))?;
+    println!("Page 1 of {} matches: {:?}, next {:?}", page.total, page.items.iter().map(|user| user.username()).collect::<Vec<_>>(), page.next_page());
+    let listing = user_service.list_users(Pagination::default())?;
+    println!("{} users stored", listing.total);
     
-    fn find_by_id(&self, user_id: &str) -> Result<Option<User>, String> {
-        println!("Finding user with ID: {}", user_id);
-        // Database query logic
-        Ok(Some(User::new("john_doe".to_string(), "john@example.com".to_string())))
-    }
+    // Rate limiting and a circuit breaker, added by wrapping the notification service
+    let clock = ManualClock::new(Utc::now());
+    let notifications = ResilientNotificationService::with_clock(FirebaseNotificationService::new("firebase-api-key".to_string()), clock.clone())
+        .rate_limit(2, Duration::seconds(60));
+    for attempt in 1..=3 {
+        println!("Push {}: {:?}", attempt, notifications.send_push_notification("john_smith", "Hello"));
+    }
+    clock.advance(Duration::seconds(60));
+    println!("After a minute: {:?} (circuit {:?})", notifications.send_push_notification("john_smith", "Hello"), notifications.circuit_state());
     
-    fn delete(&self, user: &User) -> Result<bool, String> {
-        println!("Deleting user from database: {}", user.username());
-        // Database delete logic
-        Ok(true)
-    }
-}
-
-// Email service trait - defines email operations
-pub trait EmailService {
-    fn send_welcome_email(&self, email: &str) -> Result<bool, String>;
-    fn send_password_reset_email(&self, email: &str) -> Result<bool, String>;
-    fn send_notification_email(&self, email: &str, subject: &str, message: &str) -> Result<bool, String>;
-}
-
-// Concrete implementation of EmailService
-pub struct SMTPEmailService {
-    smtp_server: String,
-}
-
-impl SMTPEmailService {
-    pub fn new(smtp_server: String) -> Self {
-        SMTPEmailService { smtp_server }
-    }
-}
-
-impl EmailService for SMTPEmailService {
-    fn send_welcome_email(&self, email: &str) -> Result<bool, String> {
-        println!("Sending welcome email via SMTP ({}) to: {}", self.smtp_server, email);
-        // SMTP email sending logic
-        Ok(true)
-    }
+    // A pager vendor's client with its own API, used as a NotificationService through an adapter
+    let pager = patterns::adapter::LegacyPagerClient::new("acme-ops");
+    pager.register_pager(4711);
+    let paged_service = create_user_service()
+        .with_notification_service(Some(patterns::adapter::PagerNotificationAdapter::new(pager.clone()).with_pager("ken_t", 4711)));
+    paged_service.create_user("ken_t".to_string(), "ken@example.com".to_string())?;
+    println!("Pages sent: {:?}", pager.pages());
     
-    fn send_password_reset_email(&self, email: &str) -> Result<bool, String> {
-        println!("Sending password reset email via SMTP ({}) to: {}", self.smtp_server, email);
-        // SMTP email sending logic
-        Ok(true)
-    }
+    // Null objects: a collaborator that is always there and may do nothing,
+    // instead of an Option checked before every use
+    let announcer = patterns::null_object::UserAnnouncer::new().with_logger(TracingActivityLogger::new());
+    let mut grace = User::new("grace_h".to_string(), "grace@example.com".to_string());
+    grace.set_phone(Some(PhoneNumber::parse("+44 20 7946 0958")?));
+    announcer.announce(&grace);
+    println!("SMS without a gateway: {:?}", announcer.send_sms(&grace, "Hello"));
+    let announcer = announcer.with_notifications(FirebaseNotificationService::new("firebase-api-key".to_string()));
+    println!("SMS through Firebase: {:?}", announcer.send_sms(&grace, "Hello"));
     
-    fn send_notification_email(&self, email: &str, subject: &str, message: &str) -> Result<bool, String> {
-        println!("Sending notification via SMTP ({}) to {}: {}", self.smtp_server, email, subject);
-        // SMTP email sending logic
-        Ok(true)
-    }
-}
-
-// Logger trait - defines logging operations
-pub trait ActivityLogger {
-    fn log_user_activity(&self, username: &str, activity: &str);
-    fn log_error(&self, error: &str, context: &str);
-}
-
-// Concrete implementation of ActivityLogger
-pub struct FileActivityLogger {
-    log_file: String,
-}
-
-impl FileActivityLogger {
-    pub fn new(log_file: String) -> Self {
-        FileActivityLogger { log_file }
-    }
+    // Runtime wiring: backends chosen by a config file, then one explicit override
+    let config = ServiceConfig::from_toml("repository = \"memory\"\nlogger = \"tracing\"")?.with_env()?;
+    let configured = UserServiceBuilder::from_config(&config)?.without_notifications().build();
+    configured.create_user("hopper_g".to_string(), "grace.hopper@example.com".to_string())?;
     
-    fn write_to_file(&self, message: &str) -> Result<(), String> {
-        // File writing logic would go here
-        println!("Writing to {}: {}", self.log_file, message);
-        Ok(())
-    }
-}
-
-impl ActivityLogger for FileActivityLogger {
-    fn log_user_activity(&self, username: &str, activity: &str) {
-        let timestamp = Utc::now();
-        let log_message = format!("[LOG {}] User {} performed: {}", 
-                                timestamp.format("%Y-%m-%d %H:%M:%S"), username, activity);
-        println!("{}", log_message);
-        self.write_to_file(&log_message).unwrap_or_else(|e| {
-            eprintln!("Failed to write to log file: {}", e);
-        });
+    // Type erasure: differently wired services share one type and can be rewired at runtime
+    let mut services: Vec<DynUserService> = vec![
+        DynUserService::from(create_user_service()),
+        DynUserService::from(compose! { logger: tracing, notifications: none }),
+    ];
+    services[1].replace_repository(InMemoryUserRepository::new());
+    for (i, service) in services.iter().enumerate() {
+        service.create_user(format!("erased_{}", i), format!("erased{}@example.com", i))?;
     }
     
-    fn log_error(&self, error: &str, context: &str) {
-        let timestamp = Utc::now();
-        let log_message = format!("[ERROR {}] {}: {}", 
-                                timestamp.format("%Y-%m-%d %H:%M:%S"), context, error);
-        println!("{}", log_message);
-        self.write_to_file(&log_message).unwrap_or_else(|e| {
-            eprintln!("Failed to write to log file: {}", e);
-        });
-    }
-}
-
-// Validator trait - defines validation operations
-pub trait UserValidator {
-    fn validate_email(&self, email: &str) -> bool;
-    fn validate_username(&self, username: &str) -> bool;
-    fn validate_user(&self, user: &User) -> bool;
-}
-
-// Concrete implementation of UserValidator
-pub struct DefaultUserValidator {
-    email_regex: Regex,
-}
-
-impl DefaultUserValidator {
-    pub fn new() -> Self {
-        DefaultUserValidator {
-            email_regex: Regex::new(r"^[^\s@]+@[^\s@]+\.[^\s@]+$").unwrap(),
-        }
-    }
+    // Static vs dynamic dispatch over the same quiet test doubles
+    let iterations = 10_000;
+    let (static_service, _) = testing::mock_user_service();
+    let dyn_service = DynUserService::from(testing::mock_user_service().0);
+    let started = std::time::Instant::now();
+    for i in 0..iterations {
+        let user = static_service.create_user(format!("static_{}", i), format!("static{}@example.com", i))?;
+        static_service.format_user(&user);
+    }
+    let static_elapsed = started.elapsed();
+    let started = std::time::Instant::now();
+    for i in 0..iterations {
+        let user = dyn_service.create_user(format!("dyn_{}", i), format!("dyn{}@example.com", i))?;
+        dyn_service.format_user(&user);
+    }
+    println!("{} calls: static {:?}, dyn {:?}", iterations, static_elapsed, started.elapsed());
     
-    pub fn validate_password(&self, password: &str) -> bool {
-        password.len() >= 8 &&
-        password.chars().any(|c| c.is_uppercase()) &&
-        password.chars().any(|c| c.is_lowercase()) &&
-        password.chars().any(|c| c.is_numeric())
-    }
-}
-
-impl UserValidator for DefaultUserValidator {
-    fn validate_email(&self, email: &str) -> bool {
-        self.email_regex.is_match(email)
+    // Structured logging: the same flow, captured as tracing events with one correlation id
+    let traced_service = compose! { logger: tracing };
+    let (_, events) = InMemoryLayer::capture(|| traced_service.create_user("ada_l".to_string(), "ada@example.com".to_string()));
+    for event in &events {
+        println!("[{}] {} {:?}", event.correlation_id.as_deref().unwrap_or("-"), event.level, event.fields);
     }
     
-    fn validate_username(&self, username: &str) -> bool {
-        username.len() >= 3 && username.len() <= 20 && 
-        username.chars().all(|c| c.is_alphanumeric() || c == '_')
-    }
+    // Data lifecycle: export a subject's data, anonymize them, then remove the record
+    let mut subject = user_service.create_user("grace_h".to_string(), "grace@example.com".to_string())?;
+    println!("{}", user_service.export_personal_data(&subject, &JsonExporter::new())?.to_json()?);
+    user_service.anonymize_user(&mut subject)?;
+    println!("Anonymized as {} <{}>", subject.username(), subject.email());
+    user_service.hard_delete_with_audit(subject)?;
     
-    fn validate_user(&self, user: &User) -> bool {
-        self.validate_email(user.email()) && self.validate_username(user.username())
-    }
-}
-
-// Formatter trait - defines formatting operations
-pub trait UserFormatter {
-    fn format_user_for_display(&self, user: &User) -> String;
-    fn format_user_for_api(&self, user: &User) -> HashMap<String, String>;
-}
-
-// Concrete implementation of UserFormatter
-pub struct DefaultUserFormatter;
-
-impl DefaultUserFormatter {
-    pub fn new() -> Self {
-        DefaultUserFormatter
-    }
+    // Audit trail: a hash-chained record of who changed what, separate from the activity log
+    let trail = Arc::new(InMemoryAuditTrail::new());
+    let audited_service = create_user_service().with_audit_trail(trail.clone(), "admin@example.com");
+    let mut linus = audited_service.create_user("linus_t".to_string(), "linus@example.com".to_string())?;
+    audited_service.verify_user(&mut linus)?;
+    for entry in trail.entries_for_user(linus.id())? {
+        println!("#{} {} by {} ({}...)", entry.sequence, entry.record.action, entry.record.actor, &entry.hash[..12]);
+    }
+    println!("Audit chain intact: {} entries", trail.verify()?);
     
-    pub fn format_user_for_csv(&self, user: &User) -> String {
-        format!("{},{}", user.username(), user.email())
-    }
-}
-
-impl UserFormatter for DefaultUserFormatter {
-    fn format_user_for_display(&self, user: &User) -> String {
-        format!("{} ({})", user.username(), user.email())
-    }
+    // Back up the same repository, check the archive, and restore it somewhere else
+    let backups = GzipBackupService::new();
+    let backup_path = std::env::temp_dir().join("users-backup.json.gz");
+    let manifest = backups.backup(&archive, &backup_path)?;
+    println!("Backed up {} users, checksum {}", manifest.user_count, manifest.checksum);
+    println!("Verified: {:?}", backups.verify(&backup_path)?.created_at);
+    let replica = InMemoryUserRepository::new();
+    println!("Restored {} users", backups.restore(&backup_path, &replica)?);
     
-    fn format_user_for_api(&self, user: &User) -> HashMap<String, String> {
-        let mut map = HashMap::new();
-        map.insert("username".to_string(), user.username().to_string());
-        map.insert("email".to_string(), user.email().to_string());
-        map.insert("display_name".to_string(), self.format_user_for_display(user));
-        map
-    }
-}
-
-// Analytics service trait - defines analytics operations
-pub trait AnalyticsService {
-    fn track_user_event(&self, username: &str, event_name: &str, metadata: Option<HashMap<String, String>>);
-    fn track_user_registration(&self, username: &str);
-    fn track_user_update(&self, username: &str);
-}
-
-// Concrete implementation of AnalyticsService
-pub struct GoogleAnalyticsService {
-    tracking_id: String,
-}
-
-impl GoogleAnalyticsService {
-    pub fn new(tracking_id: String) -> Self {
-        GoogleAnalyticsService { tracking_id }
+    // Username policy: Unicode-aware lengths, allowed scripts and reserved names
+    let policy = UsernamePolicy::default().allow_script(Script::Han).reserve("moderator");
+    let validator = DefaultUserValidator::with_username_policy(policy);
+    for name in ["José", "Jose\u{301}", "李小龙", "rocket🚀", "pаypal", "Аdmin", "moderator"] {
+        let report = validator.validate_username(name);
+        println!("{:<12} -> {} as {:?}", name, report, validator.normalize_username(name));
     }
     
-    pub fn track_user_login(&self, username: &str, ip_address: &str) {
-        let mut metadata = HashMap::new();
-        metadata.insert("ip_address".to_string(), ip_address.to_string());
-        self.track_user_event(username, "user_login", Some(metadata));
-    }
-}
-
-impl AnalyticsService for GoogleAnalyticsService {
-    fn track_user_event(&self, username: &str, event_name: &str, metadata: Option<HashMap<String, String>>) {
-        let timestamp = Utc::now().timestamp();
-        println!("[ANALYTICS {}] {}: {} (timestamp: {})", self.tracking_id, username, event_name, timestamp);
-        if let Some(meta) = metadata {
-            println!("  Metadata: {:?}", meta);
-        }
-        // Google Analytics tracking logic
-    }
+    // Same service, rewired declaratively for a demo without push notifications
+    let demo_service = compose! {
+        email: smtp("smtp.demo.example.com"),
+        logger: file("demo.log"),
+        notifications: none,
+    };
+    demo_service.create_user("jane_doe".to_string(), "jane@example.com".to_string())?;
     
-    fn track_user_registration(&self, username: &str) {
-        self.track_user_event(username, "user_registered", None);
+    // With the `sqlite` feature, swap in a real database and read the user back
+    #[cfg(feature = "sqlite")]
+    {
+        let path = std::env::temp_dir().join(format!("srp_demo_{}.db", std::process::id()));
+        let path = path.to_string_lossy();
+        let sqlite_service = create_user_service().with_repository(SqliteUserRepository::open(&path)?);
+        let ada = sqlite_service.create_user("ada".to_string(), "ada@example.com".to_string())?;
+        println!("Stored in SQLite: {:?}", SqliteUserRepository::open(&path)?.find_by_id(&ada.id().to_string())?);
     }
     
-    fn track_user_update(&self, username: &str) {
-        self.track_user_event(username, "user_updated", None);
-    }
-}
-
-// Notification service trait - defines notification operations
-pub trait NotificationService {
-    fn send_push_notification(&self, username: &str, message: &str) -> Result<bool, String>;
-    fn send_sms_notification(&self, phone: &str, message: &str) -> Result<bool, String>;
-}
-
-// Concrete implementation of NotificationService
-pub struct FirebaseNotificationService {
-    api_key: String,
-}
-
-impl FirebaseNotificationService {
-    pub fn new(api_key: String) -> Self {
-        FirebaseNotificationService { api_key }
-    }
-}
-
-impl NotificationService for FirebaseNotificationService {
-    fn send_push_notification(&self, username: &str, message: &str) -> Result<bool, String> {
-        println!("Sending Firebase push notification (API: {}) to {}: {}", self.api_key, username, message);
-        // Firebase push notification logic
-        Ok(true)
-    }
+    // Email content is rendered from templates, separately from delivery
+    let renderer = PlaceholderTemplateRenderer::with_defaults()
+        .register("digest", "{count} new messages", "Hi {username}, you have {count} unread messages.");
+    let values = HashMap::from([
+        ("username".to_string(), "john_smith".to_string()),
+        ("count".to_string(), "3".to_string()),
+    ]);
+    let digest = renderer.render("digest", user.email(), &values)?;
+    println!("Rendered: {:?}", digest);
+    SMTPEmailService::new("smtp.example.com".to_string()).send(&digest)?;
     
-    fn send_sms_notification(&self, phone: &str, message: &str) -> Result<bool, String> {
-        println!("Sending SMS via Firebase (API: {}) to {}: {}", self.api_key, phone, message);
-        // SMS sending logic
-        Ok(true)
-    }
-}
-
-// User service - orchestrates operations using other services
-pub struct CorrectUserService<R, E, L, V, F, A, N>
-where
-    R: UserRepository,
-    E: EmailService,
-    L: ActivityLogger,
-    V: UserValidator,
-    F: UserFormatter,
-    A: AnalyticsService,
-    N: NotificationService,
-{
-    user_repo: R,
-    email_service: E,
-    logger: L,
-    validator: V,
-    formatter: F,
-    analytics: A,
-    notification_service: Option<N>,
-}
-
-impl<R, E, L, V, F, A, N> CorrectUserService<R, E, L, V, F, A, N>
-where
-    R: UserRepository,
-    E: EmailService,
-    L: ActivityLogger,
-    V: UserValidator,
-    F: UserFormatter,
-    A: AnalyticsService,
-    N: NotificationService,
-{
-    pub fn new(
-        user_repo: R,
-        email_service: E,
-        logger: L,
-        validator: V,
-        formatter: F,
-        analytics: A,
-        notification_service: Option<N>,
-    ) -> Self {
-        CorrectUserService {
-            user_repo,
-            email_service,
-            logger,
-            validator,
-            formatter,
-            analytics,
-            notification_service,
-        }
+    // Retry, rate limit and audit as decorators around any EmailService; the
+    // order they are stacked in changes what gets delivered and what gets recorded
+    let log = patterns::decorator::EmailAuditLog::new();
+    let guarded = patterns::decorator::AuditingEmailService::new(
+        patterns::decorator::RateLimitedEmailService::new(
+            patterns::decorator::RetryingEmailService::new(SMTPEmailService::new("smtp.example.com".to_string()), 3),
+            10,
+            Duration::seconds(6),
+        ),
+        log.clone(),
+    );
+    guarded.send(&digest)?;
+    println!("Audited: {:?}", log.records());
+    patterns::decorator::demonstrate_decorator_stacks();
+    
+    // The same collaborators, reacting to domain events instead of being called directly
+    let event_service = create_event_driven_user_service();
+    let mut alan = event_service.create_user("alan".to_string(), "alan@example.com".to_string())?;
+    event_service.update_user(&mut alan, "alan_t".to_string(), "alan@example.com".to_string())?;
+    event_service.delete_user(&mut alan)?;
+    
+    // With the `async` feature: the save takes one round trip, then email, push and
+    // analytics run concurrently, so create_user takes about two round trips, not four
+    #[cfg(feature = "async")]
+    {
+        let runtime = tokio::runtime::Runtime::new()?;
+        let async_service = create_async_user_service(std::time::Duration::from_millis(100));
+        let started = std::time::Instant::now();
+        let barbara = runtime.block_on(async_service.create_user("barbara".to_string(), "barbara@example.com".to_string()))?;
+        println!("Async create_user took {:?}", started.elapsed());
+        println!("Found: {:?}", runtime.block_on(async_service.find_user(barbara.id()))?);
     }
     
-    pub fn create_user(&self, username: String, email: String) -> Result<User, String> {
-        // Create user object
-        let user = User::new(username.clone(), email.clone());
-        
-        // Validate user data
-        if !self.validator.validate_user(&user) {
-            let error_msg = format!("Invalid user data: username={}, email={}", username, email);
-            self.logger.log_error(&error_msg, "User creation failed");
-            return Err(error_msg);
-        }
-        
-        // Save user
-        self.user_repo.save(&user).map_err(|e| {
-            self.logger.log_error(&e, "Failed to save user");
-            e
-        })?;
-        
-        // Send welcome email
-        if let Err(e) = self.email_service.send_welcome_email(&email) {
-            self.logger.log_error(&e, "Failed to send welcome email");
-            // Don't fail user creation if email fails
-        }
-        
-        // Log activity
-        self.logger.log_user_activity(&username, "User created");
-        
-        // Track analytics
-        self.analytics.track_user_registration(&username);
-        
-        // Send push notification if service is available
-        if let Some(ref notification_service) = self.notification_service {
-            if let Err(e) = notification_service.send_push_notification(&username, "Welcome! Your account has been created.") {
-                self.logger.log_error(&e, "Failed to send push notification");
+    // Reliable delivery: side effects go to an outbox and are retried with backoff.
+    // This email service fails twice before succeeding.
+    struct FlakyEmailService {
+        failures_left: std::cell::Cell<u32>,
+    }
+    impl EmailService for FlakyEmailService {
+        fn send(&self, message: &EmailMessage) -> Result<bool, String> {
+            if self.failures_left.get() == 0 {
+                println!("Delivered '{}' to {}", message.subject, message.to);
+                return Ok(true);
             }
+            self.failures_left.set(self.failures_left.get() - 1);
+            Err("SMTP server unavailable".to_string())
         }
-        
-        Ok(user)
     }
     
-    pub fn update_user(&self, user: &mut User, new_username: String, new_email: String) -> Result<(), String> {
-        // Validate new data
-        let temp_user = User::new(new_username.clone(), new_email.clone());
-        if !self.validator.validate_user(&temp_user) {
-            let error_msg = format!("Invalid user data: username={}, email={}", new_username, new_email);
-            self.logger.log_error(&error_msg, "User update failed");
-            return Err(error_msg);
-        }
-        
-        // Update user
-        user.set_username(new_username.clone());
-        user.set_email(new_email);
-        
-        self.user_repo.update(user).map_err(|e| {
-            self.logger.log_error(&e, "Failed to update user");
-            e
-        })?;
-        
-        // Log activity
-        self.logger.log_user_activity(&new_username, "User updated");
-        
-        // Track analytics
-        self.analytics.track_user_update(&new_username);
-        
-        Ok(())
-    }
+    let outbox = Arc::new(OutboxQueue::new());
+    let queued_service = create_user_service().with_outbox(outbox.clone());
+    queued_service.create_user("linus".to_string(), "linus@example.com".to_string())?;
+    println!("Queued side effects: {}", outbox.pending_len());
     
-    pub fn format_user(&self, user: &User) -> String {
-        self.formatter.format_user_for_display(user)
+    let processor = OutboxProcessor::new(
+        FlakyEmailService { failures_left: std::cell::Cell::new(2) },
+        GoogleAnalyticsService::new("GA-XXXXX-X".to_string()),
+        None::<FirebaseNotificationService>,
+        ExponentialBackoff::new(5, Duration::seconds(1), Duration::seconds(30)),
+    );
+    let mut now = Utc::now();
+    while outbox.pending_len() > 0 {
+        println!("Outbox pass: {}", processor.process_due(&outbox, now)?);
+        now = now + Duration::seconds(30);
     }
     
-    pub fn get_user_api_data(&self, user: &User) -> HashMap<String, String> {
-        self.formatter.format_user_for_api(user)
+    // A server that never recovers exhausts the retries; the push notification
+    // has no service to go to and is dead-lettered straight away
+    queued_service.create_user("ken".to_string(), "ken@example.com".to_string())?;
+    let impatient = OutboxProcessor::new(
+        FlakyEmailService { failures_left: std::cell::Cell::new(u32::MAX) },
+        GoogleAnalyticsService::new("GA-XXXXX-X".to_string()),
+        None::<FirebaseNotificationService>,
+        FixedBackoff::new(3, Duration::seconds(10)),
+    );
+    while outbox.pending_len() > 0 {
+        println!("Outbox pass: {}", impatient.process_due(&outbox, now)?);
+        now = now + Duration::seconds(10);
+    }
+    for entry in outbox.dead_letters() {
+        println!("Dead letter #{} after {} attempt(s): {:?}", entry.id, entry.attempts, entry.last_error);
     }
-}
-
-// Factory function to create a fully configured user service
-pub fn create_user_service() -> CorrectUserService<
-    DatabaseUserRepository,
-    SMTPEmailService,
-    FileActivityLogger,
-    DefaultUserValidator,
-    DefaultUserFormatter,
-    GoogleAnalyticsService,
-    FirebaseNotificationService,
-> {
-    let user_repo = DatabaseUserRepository::new();
-    let email_service = SMTPEmailService::new("smtp.example.com".to_string());
-    let logger = FileActivityLogger::new("activity.log".to_string());
-    let validator = DefaultUserValidator::new();
-    let formatter = DefaultUserFormatter::new();
-    let analytics = GoogleAnalyticsService::new("GA-XXXXX-X".to_string());
-    let notification_service = FirebaseNotificationService::new("firebase-api-key".to_string());
-    
-    CorrectUserService::new(
-        user_repo,
-        email_service,
-        logger,
-        validator,
-        formatter,
-        analytics,
-        Some(notification_service),
-    )
-}
-
-// Example usage with dependency injection - uncomment to run
-/*
-fn main() -> Result<(), Box<dyn std::error::Error>> {
-    // Create user service with all dependencies
-    let user_service = create_user_service();
     
-    // Create a new user
-    let mut user = user_service.create_user("john_doe".to_string(), "john@example.com".to_string())?;
-    println!("User created: {}", user_service.format_user(&user));
+    // Periodic maintenance lives in jobs on a JobScheduler, not in the orchestrator.
+    // A ManualClock stands in for a month passing.
+    let members = Arc::new(InMemoryUserRepository::new());
+    let mut dormant = User::new("dormant_dan".to_string(), "dan@example.com".to_string());
+    dormant.transition_to(UserStatus::Active)?;
+    members.save(&dormant)?;
+    let buffered_analytics = BufferedAnalyticsService::new(GoogleAnalyticsService::new("GA-XXXXX-X".to_string()));
+    buffered_analytics.track_user_registration("dormant_dan");
+    let activity_log = Arc::new(FileActivityLogger::new(default_log_path()));
+    let calendar = ManualClock::new(Utc::now());
+    let mut scheduler = JobScheduler::with_clock(calendar.clone())
+        .every(Duration::days(1), InactiveUserReminderJob::new(
+            members.clone(),
+            SMTPEmailService::new("smtp.example.com".to_string()),
+            PlaceholderTemplateRenderer::with_defaults(),
+            Duration::days(30),
+        ))
+        .every(Duration::days(7), LogRotationJob::new(activity_log.clone()))
+        .every(Duration::hours(1), AnalyticsFlushJob::new(buffered_analytics.clone()));
+    println!("After start: {}", scheduler.tick());
+    calendar.advance(Duration::days(31));
+    println!("A month later:\n{}", scheduler.tick());
     
-    // Update the user
-    user_service.update_user(&mut user, "john_smith".to_string(), "john.smith@example.com".to_string())?;
-    println!("User updated: {}", user_service.format_user(&user));
-    println!("API data: {:?}", user_service.get_user_api_data(&user));
+    // Metrics: the service times its own operations; the repository and email
+    // service are wrapped in decorators that time theirs
+    let metrics: Arc<dyn Metrics> = Arc::new(InMemoryMetrics::new());
+    let metered_service = create_user_service()
+        .with_repository(MeteredUserRepository::new(InMemoryUserRepository::new(), metrics.clone()))
+        .with_email_service(MeteredEmailService::new(SMTPEmailService::new("smtp.example.com".to_string()), metrics.clone()))
+        .with_metrics(metrics.clone());
+    metered_service.create_user("alan_t".to_string(), "alan@example.com".to_string())?;
+    let _ = metered_service.create_user("x".to_string(), "not-an-email".to_string());
+    metered_service.list_users(Pagination::default())?;
+    print!("{}", PrometheusExporter::new().render(&metered_service.metrics_snapshot()));
+    
+    // Password flows: a fast fake hasher keeps demos quick; the default is Argon2
+    let auth_service = compose! { passwords: fake, notifications: none };
+    let grace = auth_service.register_with_password("grace".to_string(), "grace@example.com".to_string(), "Compiler1952")?;
+    println!("Login with correct password: {}", auth_service.verify_login(&grace, "Compiler1952")?);
+    println!("Login with wrong password: {}", auth_service.verify_login(&grace, "compiler1952")?);
+    if let Err(e) = auth_service.change_password(&grace, "Compiler1952", "weak") {
+        println!("Password change rejected: {}", e);
+    }
+    auth_service.change_password(&grace, "Compiler1952", "Cobol1959")?;
+    println!("Old password still works: {}", auth_service.verify_login(&grace, "Compiler1952")?);
+    
+    // Sessions are issued on login and revoked on logout
+    let token = auth_service.login(&grace, "Cobol1959")?;
+    println!("Session belongs to: {:?}", auth_service.authenticate(&token)?);
+    auth_service.logout(&grace, &token)?;
+    println!("After logout: {:?}", auth_service.authenticate(&token)?);
+    
+    // Walk the user through its lifecycle; invalid transitions are rejected
+    user_service.verify_user(&mut user)?;
+    user_service.suspend_user(&mut user)?;
+    user_service.reactivate_user(&mut user)?;
+    println!("User {} is {} (updated {})", user.id(), user.status(), user.updated_at());
+    user_service.delete_user(&mut user)?;
+    if let Err(e) = user_service.reactivate_user(&mut user) {
+        println!("Cannot reactivate deleted user: {}", e);
+    }
+    
+    // Before and after: one scenario through ViolationUserService and this service,
+    // showing which component handled each part of every step
+    #[cfg(feature = "walkthrough")]
+    {
+        let report = walkthrough::run()?;
+        println!("{}", report);
+        std::fs::write("walkthrough.json", report.to_json()?)?;
+    }
     
     Ok(())
 }
-*/
\ No newline at end of file
+*/
@@ -0,0 +1,214 @@
+// What an email says and how it is delivered: templates and EmailService
+
+use std::collections::HashMap;
+use regex::Regex;
+
+// A fully rendered email, ready for delivery
+#[derive(Debug, Clone, PartialEq)]
+pub struct EmailMessage {
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+}
+
+// Email template renderer trait - defines how email content is produced
+pub trait EmailTemplateRenderer {
+    fn render(&self, template: &str, to: &str, values: &HashMap<String, String>) -> Result<EmailMessage, String>;
+}
+
+#[derive(Debug, Clone)]
+struct EmailTemplate {
+    subject: String,
+    body: String,
+}
+
+// Concrete implementation of EmailTemplateRenderer: a registry of named
+// templates whose subject and body may contain {placeholder} markers
+pub struct PlaceholderTemplateRenderer {
+    templates: HashMap<String, EmailTemplate>,
+    placeholder_regex: Regex,
+}
+
+impl PlaceholderTemplateRenderer {
+    pub fn new() -> Self {
+        PlaceholderTemplateRenderer {
+            templates: HashMap::new(),
+            placeholder_regex: Regex::new(r"\{(\w+)\}").unwrap(),
+        }
+    }
+    
+    // The templates CorrectUserService sends
+    pub fn with_defaults() -> Self {
+        PlaceholderTemplateRenderer::new()
+            .register("welcome", "Welcome, {username}!", "Hi {username}, your account has been created.")
+            .register("password_reset", "Password reset", "Hi {username}, follow {reset_link} to reset your password.")
+            .register("password_changed", "Password changed", "Hi {username}, your password was changed.")
+            .register("inactive_reminder", "We miss you, {username}", "Hi {username}, we haven't seen you since {last_active}.")
+    }
+    
+    pub fn register(mut self, name: &str, subject: &str, body: &str) -> Self {
+        self.templates.insert(name.to_string(), EmailTemplate {
+            subject: subject.to_string(),
+            body: body.to_string(),
+        });
+        self
+    }
+    
+    fn fill(&self, template: &str, text: &str, values: &HashMap<String, String>) -> Result<String, String> {
+        let mut rendered = String::with_capacity(text.len());
+        let mut last = 0;
+        for captures in self.placeholder_regex.captures_iter(text) {
+            let marker = captures.get(0).unwrap();
+            let key = &captures[1];
+            let value = values.get(key).ok_or(format!("Missing value for {{{}}} in template {}", key, template))?;
+            rendered.push_str(&text[last..marker.start()]);
+            rendered.push_str(value);
+            last = marker.end();
+        }
+        rendered.push_str(&text[last..]);
+        Ok(rendered)
+    }
+}
+
+impl Default for PlaceholderTemplateRenderer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EmailTemplateRenderer for PlaceholderTemplateRenderer {
+    fn render(&self, template: &str, to: &str, values: &HashMap<String, String>) -> Result<EmailMessage, String> {
+        let source = self.templates.get(template).ok_or(format!("Unknown email template: {}", template))?;
+        Ok(EmailMessage {
+            to: to.to_string(),
+            subject: self.fill(template, &source.subject, values)?,
+            body: self.fill(template, &source.body, values)?,
+        })
+    }
+}
+
+// Email service trait - defines email delivery; content comes from the renderer
+pub trait EmailService {
+    fn send(&self, message: &EmailMessage) -> Result<bool, String>;
+}
+
+// Concrete implementation of EmailService
+pub struct SMTPEmailService {
+    smtp_server: String,
+}
+
+impl SMTPEmailService {
+    pub fn new(smtp_server: String) -> Self {
+        SMTPEmailService { smtp_server }
+    }
+}
+
+impl EmailService for SMTPEmailService {
+    fn send(&self, message: &EmailMessage) -> Result<bool, String> {
+        println!("Sending email via SMTP ({}) to {}: {}", self.smtp_server, message.to, message.subject);
+        // SMTP email sending logic
+        Ok(true)
+    }
+}
+
+// EmailService that delivers through a real SMTP server with lettre (enable the `smtp` feature)
+#[cfg(feature = "smtp")]
+pub struct LettreEmailService {
+    transport: lettre::SmtpTransport,
+    from: lettre::message::Mailbox,
+}
+
+#[cfg(feature = "smtp")]
+impl LettreEmailService {
+    // STARTTLS relay with credentials, e.g. a production mail provider
+    pub fn relay(host: &str, username: &str, password: &str, from: &str) -> Result<Self, String> {
+        let transport = lettre::SmtpTransport::starttls_relay(host)
+            .map_err(|e| e.to_string())?
+            .credentials(lettre::transport::smtp::authentication::Credentials::new(
+                username.to_string(),
+                password.to_string(),
+            ))
+            .build();
+        Self::with_transport(transport, from)
+    }
+    
+    // Unencrypted connection, for local catchers such as MailHog on port 1025
+    pub fn local(host: &str, port: u16, from: &str) -> Result<Self, String> {
+        let transport = lettre::SmtpTransport::builder_dangerous(host).port(port).build();
+        Self::with_transport(transport, from)
+    }
+    
+    fn with_transport(transport: lettre::SmtpTransport, from: &str) -> Result<Self, String> {
+        let from = from.parse().map_err(|e| format!("Invalid sender address {}: {}", from, e))?;
+        Ok(LettreEmailService { transport, from })
+    }
+
+}
+
+#[cfg(feature = "smtp")]
+impl EmailService for LettreEmailService {
+    fn send(&self, message: &EmailMessage) -> Result<bool, String> {
+        use lettre::Transport;
+        
+        let to = message.to.parse().map_err(|e| format!("Invalid recipient address {}: {}", message.to, e))?;
+        let email = lettre::Message::builder()
+            .from(self.from.clone())
+            .to(to)
+            .subject(message.subject.as_str())
+            .body(message.body.clone())
+            .map_err(|e| e.to_string())?;
+        self.transport
+            .send(&email)
+            .map(|response| response.is_positive())
+            .map_err(|e| format!("SMTP delivery to {} failed: {}", message.to, e))
+    }
+}
+
+// Contract tests for EmailTemplateRenderer implementations. The renderer must
+// provide a "welcome" template that uses {username}, as CorrectUserService expects:
+//
+//     template_renderer_contract_tests!(placeholder_contract, PlaceholderTemplateRenderer::with_defaults());
+#[macro_export]
+macro_rules! template_renderer_contract_tests {
+    ($name:ident, $make_renderer:expr) => {
+        #[cfg(test)]
+        mod $name {
+            use super::*;
+            use std::collections::HashMap;
+            use $crate::EmailTemplateRenderer;
+            
+            fn values(username: &str) -> HashMap<String, String> {
+                HashMap::from([("username".to_string(), username.to_string())])
+            }
+            
+            #[test]
+            fn welcome_is_addressed_and_personalised() {
+                let renderer = $make_renderer;
+                let message = renderer.render("welcome", "ada@example.com", &values("ada")).unwrap();
+                assert_eq!(message.to, "ada@example.com");
+                assert!(message.body.contains("ada"));
+                assert!(!message.subject.contains("{username}"));
+                assert!(!message.body.contains("{username}"));
+            }
+            
+            #[test]
+            fn unknown_template_is_an_error() {
+                let renderer = $make_renderer;
+                assert!(renderer.render("no_such_template", "ada@example.com", &values("ada")).is_err());
+            }
+            
+            #[test]
+            fn missing_value_is_an_error() {
+                let renderer = $make_renderer;
+                assert!(renderer.render("welcome", "ada@example.com", &HashMap::new()).is_err());
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod template_renderer_contract {
+    use super::*;
+    
+    crate::template_renderer_contract_tests!(placeholder, PlaceholderTemplateRenderer::with_defaults());
+}
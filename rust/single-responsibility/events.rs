@@ -0,0 +1,432 @@
+// Domain events: the EventBus, subscribers wrapping the existing collaborators,
+// and EventDrivenUserService
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use crate::{default_log_path, ActivityLogger, AnalyticsService, Clock, DatabaseUserRepository, DefaultUserValidator, EmailService, EmailTemplateRenderer, FileActivityLogger, FirebaseNotificationService, GoogleAnalyticsService, IdGenerator, NotificationService, PlaceholderTemplateRenderer, SMTPEmailService, SystemClock, SystemIdGenerator, User, UserId, UserRepository, UserStatus, UserValidator};
+
+// Something that happened to a user, published after it has been persisted
+#[derive(Debug, Clone, PartialEq)]
+pub enum DomainEvent {
+    UserCreated { user_id: UserId, username: String, email: String },
+    UserUpdated { user_id: UserId, username: String, email: String },
+    UserDeleted { user_id: UserId, username: String },
+}
+
+impl DomainEvent {
+    pub fn username(&self) -> &str {
+        match self {
+            DomainEvent::UserCreated { username, .. }
+            | DomainEvent::UserUpdated { username, .. }
+            | DomainEvent::UserDeleted { username, .. } => username,
+        }
+    }
+    
+    pub fn user_id(&self) -> UserId {
+        match self {
+            DomainEvent::UserCreated { user_id, .. }
+            | DomainEvent::UserUpdated { user_id, .. }
+            | DomainEvent::UserDeleted { user_id, .. } => *user_id,
+        }
+    }
+}
+
+// Event subscriber trait - reacts to domain events
+pub trait EventSubscriber {
+    fn handle(&self, event: &DomainEvent) -> Result<(), String>;
+}
+
+// Lets a subscriber be shared, e.g. a projection that is also queried
+impl<S: EventSubscriber + ?Sized> EventSubscriber for Arc<S> {
+    fn handle(&self, event: &DomainEvent) -> Result<(), String> {
+        (**self).handle(event)
+    }
+}
+
+// Event bus - delivers each published event to every registered subscriber
+pub struct EventBus {
+    subscribers: Vec<Box<dyn EventSubscriber>>,
+}
+
+impl EventBus {
+    pub fn new() -> Self {
+        EventBus { subscribers: Vec::new() }
+    }
+    
+    pub fn subscribe<S: EventSubscriber + 'static>(&mut self, subscriber: S) {
+        self.subscribers.push(Box::new(subscriber));
+    }
+    
+    pub fn subscriber_count(&self) -> usize {
+        self.subscribers.len()
+    }
+    
+    // A failing subscriber does not stop the others; their errors are returned
+    pub fn publish(&self, event: &DomainEvent) -> Vec<String> {
+        self.subscribers
+            .iter()
+            .filter_map(|subscriber| subscriber.handle(event).err())
+            .collect()
+    }
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Subscribers adapting the existing collaborators to the event bus
+
+pub struct ActivityLogSubscriber<L: ActivityLogger> {
+    logger: L,
+}
+
+impl<L: ActivityLogger> ActivityLogSubscriber<L> {
+    pub fn new(logger: L) -> Self {
+        ActivityLogSubscriber { logger }
+    }
+}
+
+impl<L: ActivityLogger> EventSubscriber for ActivityLogSubscriber<L> {
+    fn handle(&self, event: &DomainEvent) -> Result<(), String> {
+        let activity = match event {
+            DomainEvent::UserCreated { .. } => "User created",
+            DomainEvent::UserUpdated { .. } => "User updated",
+            DomainEvent::UserDeleted { .. } => "User deleted",
+        };
+        self.logger.log_user_activity(event.username(), activity);
+        Ok(())
+    }
+}
+
+pub struct AnalyticsSubscriber<A: AnalyticsService> {
+    analytics: A,
+}
+
+impl<A: AnalyticsService> AnalyticsSubscriber<A> {
+    pub fn new(analytics: A) -> Self {
+        AnalyticsSubscriber { analytics }
+    }
+}
+
+impl<A: AnalyticsService> EventSubscriber for AnalyticsSubscriber<A> {
+    fn handle(&self, event: &DomainEvent) -> Result<(), String> {
+        match event {
+            DomainEvent::UserCreated { username, .. } => self.analytics.track_user_registration(username),
+            DomainEvent::UserUpdated { username, .. } => self.analytics.track_user_update(username),
+            DomainEvent::UserDeleted { username, .. } => self.analytics.track_user_event(username, "user_deleted", None),
+        }
+        Ok(())
+    }
+}
+
+// Sends the welcome push notification for new users; other events are ignored
+pub struct NotificationSubscriber<N: NotificationService> {
+    notification_service: N,
+}
+
+impl<N: NotificationService> NotificationSubscriber<N> {
+    pub fn new(notification_service: N) -> Self {
+        NotificationSubscriber { notification_service }
+    }
+}
+
+impl<N: NotificationService> EventSubscriber for NotificationSubscriber<N> {
+    fn handle(&self, event: &DomainEvent) -> Result<(), String> {
+        if let DomainEvent::UserCreated { username, .. } = event {
+            self.notification_service.send_push_notification(username, "Welcome! Your account has been created.")?;
+        }
+        Ok(())
+    }
+}
+
+// Sends the welcome email for new users; other events are ignored
+pub struct WelcomeEmailSubscriber<E: EmailService, T: EmailTemplateRenderer> {
+    email_service: E,
+    templates: T,
+}
+
+impl<E: EmailService, T: EmailTemplateRenderer> WelcomeEmailSubscriber<E, T> {
+    pub fn new(email_service: E, templates: T) -> Self {
+        WelcomeEmailSubscriber { email_service, templates }
+    }
+}
+
+impl<E: EmailService, T: EmailTemplateRenderer> EventSubscriber for WelcomeEmailSubscriber<E, T> {
+    fn handle(&self, event: &DomainEvent) -> Result<(), String> {
+        if let DomainEvent::UserCreated { username, email, .. } = event {
+            let values = HashMap::from([("username".to_string(), username.clone())]);
+            let message = self.templates.render("welcome", email, &values)?;
+            self.email_service.send(&message)?;
+        }
+        Ok(())
+    }
+}
+
+// Event-driven counterpart of CorrectUserService: it validates, persists and
+// publishes, and knows nothing about who reacts to the events
+pub struct EventDrivenUserService<R, V>
+where
+    R: UserRepository,
+    V: UserValidator,
+{
+    user_repo: R,
+    validator: V,
+    events: EventBus,
+    clock: Arc<dyn Clock>,
+    ids: Arc<dyn IdGenerator>,
+}
+
+impl<R, V> EventDrivenUserService<R, V>
+where
+    R: UserRepository,
+    V: UserValidator,
+{
+    pub fn new(user_repo: R, validator: V, events: EventBus) -> Self {
+        EventDrivenUserService {
+            user_repo,
+            validator,
+            events,
+            clock: Arc::new(SystemClock),
+            ids: Arc::new(SystemIdGenerator),
+        }
+    }
+    
+    pub fn with_clock(mut self, clock: impl Clock + 'static) -> Self {
+        self.clock = Arc::new(clock);
+        self
+    }
+    
+    pub fn with_id_generator(mut self, ids: impl IdGenerator + 'static) -> Self {
+        self.ids = Arc::new(ids);
+        self
+    }
+    
+    pub fn create_user(&self, username: String, email: String) -> Result<User, String> {
+        let username = self.validator.normalize_username(&username);
+        let user = User::create(self.ids.next_user_id(), username.clone(), email.clone(), self.clock.now());
+        let report = self.validator.validate_user(&user);
+        if !report.is_valid() {
+            return Err(format!("Invalid user data: {}", report));
+        }
+        self.user_repo.save(&user)?;
+        
+        self.publish(DomainEvent::UserCreated { user_id: user.id(), username, email });
+        Ok(user)
+    }
+    
+    pub fn update_user(&self, user: &mut User, new_username: String, new_email: String) -> Result<(), String> {
+        let new_username = self.validator.normalize_username(&new_username);
+        let mut updated = user.clone();
+        updated.set_username(new_username.clone());
+        updated.set_email(new_email.clone());
+        updated.touch(self.clock.now());
+        let report = self.validator.validate_user(&updated);
+        if !report.is_valid() {
+            return Err(format!("Invalid user data: {}", report));
+        }
+        if !self.user_repo.update(&updated)? {
+            return Err(format!("User not found: {}", user.id()));
+        }
+        *user = updated;
+        
+        self.publish(DomainEvent::UserUpdated { user_id: user.id(), username: new_username, email: new_email });
+        Ok(())
+    }
+    
+    // Soft delete, as in CorrectUserService::delete_user
+    pub fn delete_user(&self, user: &mut User) -> Result<(), String> {
+        let mut deleted = user.clone();
+        deleted.transition_to(UserStatus::Deleted)?;
+        deleted.touch(self.clock.now());
+        if !self.user_repo.update(&deleted)? {
+            return Err(format!("User not found: {}", user.id()));
+        }
+        *user = deleted;
+        
+        self.publish(DomainEvent::UserDeleted { user_id: user.id(), username: user.username().to_string() });
+        Ok(())
+    }
+    
+    // Side effects are best effort, as in the direct-call service
+    fn publish(&self, event: DomainEvent) {
+        for error in self.events.publish(&event) {
+            eprintln!("Subscriber failed for {:?}: {}", event, error);
+        }
+    }
+}
+
+// Factory function wiring the same collaborators as create_user_service(),
+// but as event subscribers
+pub fn create_event_driven_user_service() -> EventDrivenUserService<DatabaseUserRepository, DefaultUserValidator> {
+    let mut events = EventBus::new();
+    events.subscribe(ActivityLogSubscriber::new(FileActivityLogger::new(default_log_path())));
+    events.subscribe(AnalyticsSubscriber::new(GoogleAnalyticsService::new("GA-XXXXX-X".to_string())));
+    events.subscribe(WelcomeEmailSubscriber::new(
+        SMTPEmailService::new("smtp.example.com".to_string()),
+        PlaceholderTemplateRenderer::with_defaults(),
+    ));
+    events.subscribe(NotificationSubscriber::new(FirebaseNotificationService::new("firebase-api-key".to_string())));
+    
+    EventDrivenUserService::new(DatabaseUserRepository::new(), DefaultUserValidator::new(), events)
+}
+
+// Tests for EventBus and EventDrivenUserService, using the mocks as subscribers
+#[cfg(test)]
+mod event_bus_tests {
+    use super::*;
+    use std::sync::Mutex;
+    use crate::{ManualClock, SequentialIdGenerator};
+    use crate::testing::*;
+    
+    // Keeps every event it sees, or fails them all when told to
+    #[derive(Default)]
+    struct RecordingSubscriber {
+        events: Mutex<Vec<DomainEvent>>,
+        error: Option<String>,
+    }
+    
+    impl RecordingSubscriber {
+        fn failing(error: &str) -> Self {
+            RecordingSubscriber { events: Mutex::new(Vec::new()), error: Some(error.to_string()) }
+        }
+        
+        fn events(&self) -> Vec<DomainEvent> {
+            self.events.lock().unwrap().clone()
+        }
+    }
+    
+    impl EventSubscriber for RecordingSubscriber {
+        fn handle(&self, event: &DomainEvent) -> Result<(), String> {
+            self.events.lock().unwrap().push(event.clone());
+            match self.error {
+                Some(ref error) => Err(error.clone()),
+                None => Ok(()),
+            }
+        }
+    }
+    
+    struct Subscribers {
+        recorder: Arc<RecordingSubscriber>,
+        logger: MockActivityLogger,
+        analytics: MockAnalyticsService,
+        notifications: MockNotificationService,
+        email: MockEmailService,
+    }
+    
+    fn service() -> (EventDrivenUserService<MockUserRepository, MockUserValidator>, MockUserRepository, MockUserValidator, Subscribers) {
+        let subscribers = Subscribers {
+            recorder: Arc::new(RecordingSubscriber::default()),
+            logger: MockActivityLogger::default(),
+            analytics: MockAnalyticsService::default(),
+            notifications: MockNotificationService::default(),
+            email: MockEmailService::default(),
+        };
+        let mut events = EventBus::new();
+        events.subscribe(subscribers.recorder.clone());
+        events.subscribe(ActivityLogSubscriber::new(subscribers.logger.clone()));
+        events.subscribe(AnalyticsSubscriber::new(subscribers.analytics.clone()));
+        events.subscribe(NotificationSubscriber::new(subscribers.notifications.clone()));
+        events.subscribe(WelcomeEmailSubscriber::new(subscribers.email.clone(), MockTemplateRenderer::default()));
+        let (repository, validator) = (MockUserRepository::default(), MockUserValidator::default());
+        let service = EventDrivenUserService::new(repository.clone(), validator.clone(), events)
+            .with_clock(ManualClock::new(test_epoch()))
+            .with_id_generator(SequentialIdGenerator::new());
+        (service, repository, validator, subscribers)
+    }
+    
+    #[test]
+    fn publish_reaches_every_subscriber_and_collects_their_errors() {
+        let (first, broken, last) = (
+            Arc::new(RecordingSubscriber::default()),
+            Arc::new(RecordingSubscriber::failing("disk full")),
+            Arc::new(RecordingSubscriber::default()),
+        );
+        let mut events = EventBus::new();
+        events.subscribe(first.clone());
+        events.subscribe(broken.clone());
+        events.subscribe(last.clone());
+        assert_eq!(events.subscriber_count(), 3);
+        
+        let event = DomainEvent::UserDeleted { user_id: UserId::generate(), username: "ada".to_string() };
+        assert_eq!(events.publish(&event), vec!["disk full".to_string()]);
+        assert_eq!(first.events(), vec![event.clone()]);
+        assert_eq!(broken.events(), vec![event.clone()]);
+        assert_eq!(last.events(), vec![event]);
+    }
+    
+    #[test]
+    fn publishing_without_subscribers_is_a_no_op() {
+        let event = DomainEvent::UserDeleted { user_id: UserId::generate(), username: "ada".to_string() };
+        assert!(EventBus::new().publish(&event).is_empty());
+    }
+    
+    #[test]
+    fn create_user_publishes_user_created_to_every_reaction() {
+        let (service, repository, _, subscribers) = service();
+        let user = service.create_user("ada".to_string(), "ada@example.com".to_string()).unwrap();
+        
+        assert_eq!(repository.stored().iter().map(User::id).collect::<Vec<_>>(), vec![user.id()]);
+        assert_eq!(
+            subscribers.recorder.events(),
+            vec![DomainEvent::UserCreated { user_id: user.id(), username: "ada".to_string(), email: "ada@example.com".to_string() }]
+        );
+        assert_eq!(subscribers.logger.activities(), vec![("ada".to_string(), "User created".to_string())]);
+        assert_eq!(subscribers.analytics.spy().calls(), vec!["track_user_registration(ada)".to_string()]);
+        assert_eq!(subscribers.notifications.spy().calls(), vec!["send_push_notification(ada)".to_string()]);
+        let sent = subscribers.email.sent();
+        assert_eq!(sent.len(), 1);
+        assert_eq!((sent[0].to.as_str(), sent[0].subject.as_str()), ("ada@example.com", "welcome"));
+    }
+    
+    #[test]
+    fn update_and_delete_publish_their_events_and_skip_the_welcome_reactions() {
+        let (service, _, _, subscribers) = service();
+        let mut user = service.create_user("ada".to_string(), "ada@example.com".to_string()).unwrap();
+        service.update_user(&mut user, "ada".to_string(), "ada@lovelace.org".to_string()).unwrap();
+        service.delete_user(&mut user).unwrap();
+        
+        let events = subscribers.recorder.events();
+        assert_eq!(
+            events[1..],
+            [
+                DomainEvent::UserUpdated { user_id: user.id(), username: "ada".to_string(), email: "ada@lovelace.org".to_string() },
+                DomainEvent::UserDeleted { user_id: user.id(), username: "ada".to_string() },
+            ]
+        );
+        assert_eq!(user.status(), UserStatus::Deleted);
+        assert_eq!(subscribers.analytics.spy().calls_to("track_user_update"), 1);
+        assert_eq!(subscribers.analytics.spy().calls_to("track_user_event"), 1);
+        assert_eq!(subscribers.notifications.spy().calls_to("send_push_notification"), 1);
+        assert_eq!(subscribers.email.sent().len(), 1);
+    }
+    
+    #[test]
+    fn invalid_user_is_neither_saved_nor_published() {
+        let (service, repository, validator, subscribers) = service();
+        validator.reject("email", "not an email");
+        assert!(service.create_user("ada".to_string(), "ada".to_string()).is_err());
+        assert_eq!(repository.spy().calls_to("save"), 0);
+        assert!(subscribers.recorder.events().is_empty());
+    }
+    
+    #[test]
+    fn failed_save_is_not_published() {
+        let (service, repository, _, subscribers) = service();
+        repository.spy().fail_on("save", "database offline");
+        assert_eq!(service.create_user("ada".to_string(), "ada@example.com".to_string()).unwrap_err(), "database offline");
+        assert!(subscribers.recorder.events().is_empty());
+        assert!(subscribers.email.sent().is_empty());
+    }
+    
+    #[test]
+    fn failing_subscriber_does_not_fail_the_command() {
+        let (service, repository, _, subscribers) = service();
+        subscribers.notifications.spy().fail_all("Firebase unavailable");
+        let user = service.create_user("ada".to_string(), "ada@example.com".to_string()).unwrap();
+        assert_eq!(repository.stored().iter().map(User::id).collect::<Vec<_>>(), vec![user.id()]);
+        assert_eq!(subscribers.email.sent().len(), 1);
+        assert_eq!(subscribers.logger.activities().len(), 1);
+    }
+}
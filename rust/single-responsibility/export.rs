@@ -0,0 +1,508 @@
+// Getting users out: file exporters, subject access bundles and backups
+
+use std::io::{Read, Write};
+use std::path::Path;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use crate::{PhoneNumber, User, UserId, UserRepository};
+
+// Exporter trait - writes users out in one file format.
+// `export` streams record by record, so large exports never build one big string.
+pub trait UserExporter {
+    fn export(&self, users: &mut dyn Iterator<Item = User>, out: &mut dyn Write) -> Result<usize, String>;
+    fn content_type(&self) -> &'static str;
+    
+    // Every user in the repository, returning how many were written
+    fn export_all(&self, repo: &dyn UserRepository, out: &mut dyn Write) -> Result<usize, String> {
+        self.export(&mut repo.find_all()?.into_iter(), out)
+    }
+    
+    fn export_to_string(&self, users: &[User]) -> Result<String, String> {
+        let mut buffer = Vec::new();
+        self.export(&mut users.iter().cloned(), &mut buffer)?;
+        String::from_utf8(buffer).map_err(|e| e.to_string())
+    }
+}
+
+const EXPORT_COLUMNS: [&str; 9] = ["id", "username", "email", "status", "created_at", "updated_at", "phone", "display_name", "locale"];
+
+// CSV with a header row; the csv crate quotes commas, quotes and newlines
+pub struct CsvExporter;
+
+impl CsvExporter {
+    pub fn new() -> Self {
+        CsvExporter
+    }
+}
+
+impl Default for CsvExporter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl UserExporter for CsvExporter {
+    fn content_type(&self) -> &'static str {
+        "text/csv"
+    }
+    
+    fn export(&self, users: &mut dyn Iterator<Item = User>, out: &mut dyn Write) -> Result<usize, String> {
+        let mut writer = csv::Writer::from_writer(out);
+        writer.write_record(EXPORT_COLUMNS).map_err(|e| e.to_string())?;
+        let mut count = 0;
+        for user in users {
+            writer
+                .write_record([
+                    user.id().to_string(),
+                    user.username().to_string(),
+                    user.email().to_string(),
+                    user.status().to_string(),
+                    user.created_at().to_rfc3339(),
+                    user.updated_at().to_rfc3339(),
+                    user.phone().map(PhoneNumber::to_string).unwrap_or_default(),
+                    user.display_name().unwrap_or_default().to_string(),
+                    user.locale().unwrap_or_default().to_string(),
+                ])
+                .map_err(|e| e.to_string())?;
+            count += 1;
+        }
+        writer.flush().map_err(|e| e.to_string())?;
+        Ok(count)
+    }
+}
+
+// A JSON array of users, in the same shape serde gives User
+pub struct JsonExporter;
+
+impl JsonExporter {
+    pub fn new() -> Self {
+        JsonExporter
+    }
+}
+
+impl Default for JsonExporter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl UserExporter for JsonExporter {
+    fn content_type(&self) -> &'static str {
+        "application/json"
+    }
+    
+    fn export(&self, users: &mut dyn Iterator<Item = User>, out: &mut dyn Write) -> Result<usize, String> {
+        let mut count = 0;
+        out.write_all(b"[").map_err(|e| e.to_string())?;
+        for user in users {
+            if count > 0 {
+                out.write_all(b",").map_err(|e| e.to_string())?;
+            }
+            out.write_all(b"\n  ").map_err(|e| e.to_string())?;
+            serde_json::to_writer(&mut *out, &user).map_err(|e| e.to_string())?;
+            count += 1;
+        }
+        out.write_all(if count > 0 { b"\n]\n" } else { b"]\n" }).map_err(|e| e.to_string())?;
+        Ok(count)
+    }
+}
+
+// <users><user id="...">...</user></users>, with text and attributes escaped
+pub struct XmlExporter;
+
+impl XmlExporter {
+    pub fn new() -> Self {
+        XmlExporter
+    }
+}
+
+impl Default for XmlExporter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl UserExporter for XmlExporter {
+    fn content_type(&self) -> &'static str {
+        "application/xml"
+    }
+    
+    fn export(&self, users: &mut dyn Iterator<Item = User>, out: &mut dyn Write) -> Result<usize, String> {
+        use quick_xml::escape::escape;
+        
+        let mut count = 0;
+        writeln!(out, "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<users>").map_err(|e| e.to_string())?;
+        for user in users {
+            writeln!(out, "  <user id=\"{}\" status=\"{}\">", user.id(), user.status()).map_err(|e| e.to_string())?;
+            writeln!(out, "    <username>{}</username>", escape(user.username())).map_err(|e| e.to_string())?;
+            writeln!(out, "    <email>{}</email>", escape(user.email())).map_err(|e| e.to_string())?;
+            writeln!(out, "    <created_at>{}</created_at>", user.created_at().to_rfc3339()).map_err(|e| e.to_string())?;
+            writeln!(out, "    <updated_at>{}</updated_at>", user.updated_at().to_rfc3339()).map_err(|e| e.to_string())?;
+            let profile = [("phone", user.phone().map(PhoneNumber::as_str)), ("display_name", user.display_name()), ("locale", user.locale())];
+            for (element, value) in profile {
+                if let Some(value) = value {
+                    writeln!(out, "    <{0}>{1}</{0}>", element, escape(value)).map_err(|e| e.to_string())?;
+                }
+            }
+            writeln!(out, "  </user>").map_err(|e| e.to_string())?;
+            count += 1;
+        }
+        writeln!(out, "</users>").map_err(|e| e.to_string())?;
+        Ok(count)
+    }
+}
+
+// Each exporter read back by a real reader for its format
+#[cfg(test)]
+mod user_exporter_contract {
+    use super::*;
+    
+    crate::user_exporter_contract_tests!(csv_exporter, CsvExporter::new(), |text: &str| -> Vec<String> {
+        csv::Reader::from_reader(text.as_bytes()).records().map(|record| record.unwrap()[1].to_string()).collect()
+    });
+    crate::user_exporter_contract_tests!(json_exporter, JsonExporter::new(), |text: &str| -> Vec<String> {
+        serde_json::from_str::<Vec<User>>(text).unwrap().iter().map(|user| user.username().to_string()).collect()
+    });
+    crate::user_exporter_contract_tests!(xml_exporter, XmlExporter::new(), |text: &str| -> Vec<String> {
+        let mut reader = quick_xml::Reader::from_str(text);
+        let (mut names, mut inside) = (Vec::new(), false);
+        loop {
+            match reader.read_event().unwrap() {
+                quick_xml::events::Event::Start(e) => inside = e.name().as_ref() == b"username",
+                quick_xml::events::Event::Text(t) if inside => names.push(t.unescape().unwrap().into_owned()),
+                quick_xml::events::Event::End(_) => inside = false,
+                quick_xml::events::Event::Eof => break,
+                _ => {}
+            }
+        }
+        names
+    });
+}
+
+// Everything held about one user, for a subject access request. The profile is
+// written by whichever exporter the caller chose.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PersonalDataBundle {
+    pub user_id: UserId,
+    pub generated_at: DateTime<Utc>,
+    pub content_type: String,
+    pub profile: String,
+    pub has_password: bool,
+}
+
+impl PersonalDataBundle {
+    pub fn to_json(&self) -> Result<String, String> {
+        serde_json::to_string_pretty(self).map_err(|e| e.to_string())
+    }
+}
+
+// Describes one backup archive; stored as its first line
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BackupManifest {
+    pub format_version: u32,
+    pub created_at: DateTime<Utc>,
+    pub user_count: usize,
+    // SHA-256 of the user payload, hex encoded
+    pub checksum: String,
+}
+
+// Backup trait - snapshots a repository to a file and restores it into any repository
+pub trait BackupService {
+    fn backup(&self, repo: &dyn UserRepository, path: &Path) -> Result<BackupManifest, String>;
+    // Checks the archive without touching any repository
+    fn verify(&self, path: &Path) -> Result<BackupManifest, String>;
+    // Verifies first; users that already exist are overwritten with the backed-up copy
+    fn restore(&self, path: &Path, repo: &dyn UserRepository) -> Result<usize, String>;
+}
+
+const BACKUP_FORMAT_VERSION: u32 = 1;
+
+// The uncompressed archive body: a manifest line followed by the users as JSON
+pub(crate) fn encode_backup(users: &[User]) -> Result<(BackupManifest, Vec<u8>), String> {
+    let payload = serde_json::to_vec(users).map_err(|e| e.to_string())?;
+    let manifest = BackupManifest {
+        format_version: BACKUP_FORMAT_VERSION,
+        created_at: Utc::now(),
+        user_count: users.len(),
+        checksum: format!("{:x}", Sha256::digest(&payload)),
+    };
+    let mut contents = serde_json::to_vec(&manifest).map_err(|e| e.to_string())?;
+    contents.push(b'\n');
+    contents.extend_from_slice(&payload);
+    Ok((manifest, contents))
+}
+
+pub(crate) fn decode_backup(contents: &[u8]) -> Result<(BackupManifest, Vec<User>), String> {
+    let split = contents.iter().position(|&b| b == b'\n').ok_or("Backup has no manifest")?;
+    let (header, payload) = (&contents[..split], &contents[split + 1..]);
+    let manifest: BackupManifest = serde_json::from_slice(header).map_err(|e| format!("Invalid backup manifest: {}", e))?;
+    if manifest.format_version != BACKUP_FORMAT_VERSION {
+        return Err(format!("Unsupported backup format version {}", manifest.format_version));
+    }
+    if format!("{:x}", Sha256::digest(payload)) != manifest.checksum {
+        return Err("Backup checksum mismatch".to_string());
+    }
+    let users: Vec<User> = serde_json::from_slice(payload).map_err(|e| format!("Invalid backup payload: {}", e))?;
+    if users.len() != manifest.user_count {
+        return Err(format!("Backup lists {} users but contains {}", manifest.user_count, users.len()));
+    }
+    Ok((manifest, users))
+}
+
+// Write beside the target and rename, so a failed backup never replaces a good one
+pub(crate) fn write_backup(path: &Path, write: impl FnOnce(std::fs::File) -> std::io::Result<()>) -> Result<(), String> {
+    let partial = path.with_extension("partial");
+    let written = std::fs::File::create(&partial).and_then(write).and_then(|_| std::fs::rename(&partial, path));
+    written.map_err(|e| {
+        let _ = std::fs::remove_file(&partial);
+        format!("Cannot write backup {}: {}", path.display(), e)
+    })
+}
+
+// All or nothing: a write that fails rolls back the users already restored
+pub(crate) fn restore_users(users: &[User], repo: &dyn UserRepository) -> Result<usize, String> {
+    repo.begin()?;
+    let restored = users.iter().try_for_each(|user| {
+        if repo.find_by_id(&user.id().to_string())?.is_some() {
+            repo.update(user)?;
+        } else {
+            repo.save(user)?;
+        }
+        Ok::<(), String>(())
+    });
+    match restored {
+        Ok(()) => {
+            repo.commit()?;
+            Ok(users.len())
+        }
+        Err(e) => match repo.rollback() {
+            Ok(()) => Err(e),
+            Err(rollback) => Err(format!("{}; rollback also failed: {}", e, rollback)),
+        },
+    }
+}
+
+// Gzip-compressed archive: a manifest line followed by the users as JSON.
+// Gzip's own CRC catches damaged files; the manifest checksum catches edited payloads.
+pub struct GzipBackupService;
+
+impl GzipBackupService {
+    pub fn new() -> Self {
+        GzipBackupService
+    }
+    
+    fn read_archive(path: &Path) -> Result<(BackupManifest, Vec<User>), String> {
+        let file = std::fs::File::open(path).map_err(|e| format!("Cannot open backup {}: {}", path.display(), e))?;
+        let mut contents = Vec::new();
+        flate2::read::GzDecoder::new(file)
+            .read_to_end(&mut contents)
+            .map_err(|e| format!("Backup {} is corrupt: {}", path.display(), e))?;
+        decode_backup(&contents)
+    }
+}
+
+impl Default for GzipBackupService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BackupService for GzipBackupService {
+    fn backup(&self, repo: &dyn UserRepository, path: &Path) -> Result<BackupManifest, String> {
+        let (manifest, contents) = encode_backup(&repo.find_all()?)?;
+        write_backup(path, |file| {
+            let mut encoder = flate2::write::GzEncoder::new(file, flate2::Compression::default());
+            encoder.write_all(&contents)?;
+            encoder.finish()?.sync_all()
+        })?;
+        Ok(manifest)
+    }
+    
+    fn verify(&self, path: &Path) -> Result<BackupManifest, String> {
+        Self::read_archive(path).map(|(manifest, _)| manifest)
+    }
+    
+    fn restore(&self, path: &Path, repo: &dyn UserRepository) -> Result<usize, String> {
+        let (_, users) = Self::read_archive(path)?;
+        restore_users(&users, repo)
+    }
+}
+
+#[cfg(test)]
+mod backup_service_contract {
+    use super::*;
+    
+    crate::backup_service_contract_tests!(gzip, GzipBackupService::new());
+}
+
+// Contract tests for `UserExporter` implementations. `$read_usernames` parses an
+// export back into usernames, so escaping is checked by a real reader for the format.
+//
+//     user_exporter_contract_tests!(csv_exporter, CsvExporter::new(), |text: &str| {
+//         csv::Reader::from_reader(text.as_bytes()).records().map(|r| r.unwrap()[1].to_string()).collect()
+//     });
+#[macro_export]
+macro_rules! user_exporter_contract_tests {
+    ($name:ident, $make_exporter:expr, $read_usernames:expr) => {
+        #[cfg(test)]
+        mod $name {
+            use super::*;
+            use $crate::{InMemoryUserRepository, User, UserExporter, UserRepository};
+            
+            fn read_usernames(text: &str) -> Vec<String> {
+                ($read_usernames)(text)
+            }
+            
+            #[test]
+            fn awkward_fields_survive_a_round_trip() {
+                let exporter = $make_exporter;
+                let names = ["plain", "smith, jr", "say \"hi\"", "two\nlines", "<tag> & 'quote'"];
+                let users: Vec<User> = names
+                    .iter()
+                    .map(|name| User::new(name.to_string(), "someone@example.com".to_string()))
+                    .collect();
+                let text = exporter.export_to_string(&users).unwrap();
+                assert_eq!(read_usernames(&text), names.map(String::from).to_vec());
+            }
+            
+            #[test]
+            fn export_all_writes_every_stored_user() {
+                let exporter = $make_exporter;
+                let repository = InMemoryUserRepository::new();
+                repository.save(&User::new("ada".to_string(), "ada@example.com".to_string())).unwrap();
+                repository.save(&User::new("grace".to_string(), "grace@example.com".to_string())).unwrap();
+                let mut out = Vec::new();
+                assert_eq!(exporter.export_all(&repository, &mut out).unwrap(), 2);
+                assert_eq!(read_usernames(&String::from_utf8(out).unwrap()), vec!["ada", "grace"]);
+            }
+            
+            #[test]
+            fn empty_export_is_still_well_formed() {
+                let exporter = $make_exporter;
+                let text = exporter.export_to_string(&[]).unwrap();
+                assert!(read_usernames(&text).is_empty());
+            }
+        }
+    };
+}
+
+// Contract tests for `BackupService` implementations: round trips, and damaged
+// archives that must be rejected before any user is restored.
+#[macro_export]
+macro_rules! backup_service_contract_tests {
+    ($name:ident, $make_backup_service:expr) => {
+        #[cfg(test)]
+        mod $name {
+            use super::*;
+            use std::path::PathBuf;
+            use $crate::{BackupService, InMemoryUserRepository, User, UserRepository};
+            
+            fn archive_path(test: &str) -> PathBuf {
+                let module = module_path!().replace("::", "-");
+                std::env::temp_dir().join(format!("{}-{}-{}.backup", module, test, std::process::id()))
+            }
+            
+            fn populated_repository() -> InMemoryUserRepository {
+                let repository = InMemoryUserRepository::new();
+                repository.save(&User::new("ada".to_string(), "ada@example.com".to_string())).unwrap();
+                repository.save(&User::new("grace".to_string(), "grace@example.com".to_string())).unwrap();
+                repository
+            }
+            
+            #[test]
+            fn restore_reproduces_the_backed_up_users() {
+                let backups = $make_backup_service;
+                let path = archive_path("round_trip");
+                let source = populated_repository();
+                let manifest = backups.backup(&source, &path).unwrap();
+                assert_eq!(manifest.user_count, 2);
+                assert_eq!(backups.verify(&path).unwrap(), manifest);
+                
+                let target = InMemoryUserRepository::new();
+                assert_eq!(backups.restore(&path, &target).unwrap(), 2);
+                let summary = |repository: &InMemoryUserRepository| -> Vec<(String, String, String)> {
+                    repository.find_all().unwrap().iter()
+                        .map(|user| (user.id().to_string(), user.username().to_string(), user.created_at().to_rfc3339()))
+                        .collect()
+                };
+                assert_eq!(summary(&target), summary(&source));
+                std::fs::remove_file(&path).unwrap();
+            }
+            
+            #[test]
+            fn restore_overwrites_users_that_already_exist() {
+                let backups = $make_backup_service;
+                let path = archive_path("overwrite");
+                let source = populated_repository();
+                backups.backup(&source, &path).unwrap();
+                
+                let mut changed = source.find_all().unwrap().remove(0);
+                changed.set_email("changed@example.com".to_string());
+                source.update(&changed).unwrap();
+                backups.restore(&path, &source).unwrap();
+                let restored = source.find_by_id(&changed.id().to_string()).unwrap().unwrap();
+                assert_eq!(restored.email(), "ada@example.com");
+                std::fs::remove_file(&path).unwrap();
+            }
+            
+            #[test]
+            fn damaged_archive_is_rejected_without_restoring() {
+                let backups = $make_backup_service;
+                let path = archive_path("damaged");
+                backups.backup(&populated_repository(), &path).unwrap();
+                let mut bytes = std::fs::read(&path).unwrap();
+                let middle = bytes.len() / 2;
+                bytes[middle] ^= 0xff;
+                std::fs::write(&path, &bytes).unwrap();
+                
+                assert!(backups.verify(&path).is_err());
+                let target = InMemoryUserRepository::new();
+                assert!(backups.restore(&path, &target).is_err());
+                assert!(target.find_all().unwrap().is_empty());
+                std::fs::remove_file(&path).unwrap();
+            }
+            
+            #[test]
+            fn failed_restore_rolls_back_the_users_already_written() {
+                let backups = $make_backup_service;
+                let path = archive_path("partial");
+                let source = populated_repository();
+                backups.backup(&source, &path).unwrap();
+                
+                // The first user in the archive exists and is overwritten; saving the second fails
+                let target = $crate::testing::MockUserRepository::default();
+                let mut first = source.find_all().unwrap().remove(0);
+                first.set_email("changed@example.com".to_string());
+                target.save(&first).unwrap();
+                target.spy().fail_on("save", "disk full");
+                
+                assert_eq!(backups.restore(&path, &target).unwrap_err(), "disk full");
+                let stored = target.stored();
+                assert_eq!(stored.len(), 1);
+                assert_eq!(stored[0].email(), "changed@example.com");
+                assert_eq!(target.spy().calls_to("update"), 1, "the first user was overwritten before the failure");
+                assert_eq!(target.spy().calls_to("rollback"), 1);
+                assert_eq!(target.spy().calls_to("commit"), 0);
+                std::fs::remove_file(&path).unwrap();
+            }
+            
+            #[test]
+            fn truncated_archive_is_rejected() {
+                let backups = $make_backup_service;
+                let path = archive_path("truncated");
+                backups.backup(&populated_repository(), &path).unwrap();
+                let bytes = std::fs::read(&path).unwrap();
+                std::fs::write(&path, &bytes[..bytes.len() - 8]).unwrap();
+                assert!(backups.verify(&path).is_err());
+                std::fs::remove_file(&path).unwrap();
+            }
+            
+            #[test]
+            fn missing_archive_is_an_error() {
+                let backups = $make_backup_service;
+                assert!(backups.verify(&archive_path("missing")).is_err());
+            }
+        }
+    };
+}
@@ -0,0 +1,430 @@
+// Periodic maintenance jobs and the scheduler that runs them
+
+use std::collections::HashMap;
+use std::fmt;
+#[cfg(feature = "async")]
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use chrono::{DateTime, Duration, Utc};
+use crate::{AnalyticsService, BufferedAnalyticsService, Clock, EmailService, EmailTemplateRenderer, FileActivityLogger, SystemClock, User, UserId, UserRepository, UserStatus};
+
+// Scheduled job trait - one unit of periodic work. When it runs is up to
+// JobScheduler; the job only gets told what time it is.
+pub trait ScheduledJob {
+    fn name(&self) -> &str;
+    // Does one round of work; the Ok message summarises it for the report
+    fn run(&self, now: DateTime<Utc>) -> Result<String, String>;
+}
+
+// Emails active users who have not changed anything for `inactive_for`.
+// Each user is reminded once per period of inactivity, not on every run.
+pub struct InactiveUserReminderJob<R, E, T>
+where
+    R: UserRepository,
+    E: EmailService,
+    T: EmailTemplateRenderer,
+{
+    user_repo: R,
+    email_service: E,
+    templates: T,
+    inactive_for: Duration,
+    // The `updated_at` each user was last reminded about
+    reminded: Mutex<HashMap<UserId, DateTime<Utc>>>,
+}
+
+impl<R, E, T> InactiveUserReminderJob<R, E, T>
+where
+    R: UserRepository,
+    E: EmailService,
+    T: EmailTemplateRenderer,
+{
+    pub fn new(user_repo: R, email_service: E, templates: T, inactive_for: Duration) -> Self {
+        InactiveUserReminderJob {
+            user_repo,
+            email_service,
+            templates,
+            inactive_for,
+            reminded: Mutex::new(HashMap::new()),
+        }
+    }
+    
+    fn remind(&self, user: &User) -> Result<(), String> {
+        let mut values = HashMap::new();
+        values.insert("username".to_string(), user.username().to_string());
+        values.insert("last_active".to_string(), user.updated_at().format("%Y-%m-%d").to_string());
+        let message = self.templates.render("inactive_reminder", user.email(), &values)?;
+        match self.email_service.send(&message)? {
+            true => Ok(()),
+            false => Err(format!("Email to {} was not accepted", message.to)),
+        }
+    }
+}
+
+impl<R, E, T> ScheduledJob for InactiveUserReminderJob<R, E, T>
+where
+    R: UserRepository,
+    E: EmailService,
+    T: EmailTemplateRenderer,
+{
+    fn name(&self) -> &str {
+        "inactive_user_reminders"
+    }
+    
+    fn run(&self, now: DateTime<Utc>) -> Result<String, String> {
+        let cutoff = now - self.inactive_for;
+        let mut reminded = self.reminded.lock().map_err(|e| e.to_string())?;
+        let mut sent = 0;
+        let mut failed = Vec::new();
+        for user in self.user_repo.find_all()? {
+            if user.status() != UserStatus::Active
+                || user.updated_at() > cutoff
+                || reminded.get(&user.id()) == Some(&user.updated_at())
+            {
+                continue;
+            }
+            match self.remind(&user) {
+                Ok(()) => {
+                    reminded.insert(user.id(), user.updated_at());
+                    sent += 1;
+                }
+                Err(e) => failed.push(format!("{}: {}", user.username(), e)),
+            }
+        }
+        
+        if failed.is_empty() {
+            Ok(format!("sent {} reminder(s)", sent))
+        } else {
+            Err(format!("sent {} reminder(s), {} failed: {}", sent, failed.len(), failed.join("; ")))
+        }
+    }
+}
+
+// Rotates the activity log on a schedule, on top of the size and day limits
+// FileActivityLogger already applies when it writes
+pub struct LogRotationJob {
+    logger: Arc<FileActivityLogger>,
+}
+
+impl LogRotationJob {
+    pub fn new(logger: Arc<FileActivityLogger>) -> Self {
+        LogRotationJob { logger }
+    }
+}
+
+impl ScheduledJob for LogRotationJob {
+    fn name(&self) -> &str {
+        "log_rotation"
+    }
+    
+    fn run(&self, _now: DateTime<Utc>) -> Result<String, String> {
+        match self.logger.rotate_now()? {
+            true => Ok("rotated the activity log".to_string()),
+            false => Ok("nothing to rotate".to_string()),
+        }
+    }
+}
+
+// Forwards the events a BufferedAnalyticsService has collected since the last run
+pub struct AnalyticsFlushJob<A: AnalyticsService> {
+    analytics: BufferedAnalyticsService<A>,
+}
+
+impl<A: AnalyticsService> AnalyticsFlushJob<A> {
+    pub fn new(analytics: BufferedAnalyticsService<A>) -> Self {
+        AnalyticsFlushJob { analytics }
+    }
+}
+
+impl<A: AnalyticsService> ScheduledJob for AnalyticsFlushJob<A> {
+    fn name(&self) -> &str {
+        "analytics_flush"
+    }
+    
+    fn run(&self, _now: DateTime<Utc>) -> Result<String, String> {
+        Ok(format!("flushed {} event(s)", self.analytics.flush()?))
+    }
+}
+
+// One job run within a tick
+#[derive(Debug, Clone, PartialEq)]
+pub struct JobRun {
+    pub job: String,
+    pub at: DateTime<Utc>,
+    pub outcome: Result<String, String>,
+}
+
+// Every job run during one tick, in registration order
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct JobReport {
+    pub runs: Vec<JobRun>,
+}
+
+impl JobReport {
+    pub fn ran(&self, job: &str) -> bool {
+        self.runs.iter().any(|run| run.job == job)
+    }
+    
+    pub fn failures(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.runs.iter().filter_map(|run| run.outcome.as_ref().err().map(|e| (run.job.as_str(), e.as_str())))
+    }
+}
+
+impl fmt::Display for JobReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.runs.is_empty() {
+            return write!(f, "no jobs due");
+        }
+        for (index, run) in self.runs.iter().enumerate() {
+            if index > 0 {
+                writeln!(f)?;
+            }
+            match &run.outcome {
+                Ok(summary) => write!(f, "{}: {}", run.job, summary)?,
+                Err(e) => write!(f, "{} failed: {}", run.job, e)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+struct ScheduledEntry {
+    job: Box<dyn ScheduledJob + Send>,
+    every: Duration,
+    next_run_at: DateTime<Utc>,
+}
+
+// Job scheduler - decides when periodic jobs run. Time comes from the Clock,
+// so tests call `tick` after advancing a ManualClock instead of waiting.
+pub struct JobScheduler<K: Clock = SystemClock> {
+    clock: K,
+    jobs: Vec<ScheduledEntry>,
+}
+
+impl JobScheduler<SystemClock> {
+    pub fn new() -> Self {
+        Self::with_clock(SystemClock)
+    }
+}
+
+impl Default for JobScheduler<SystemClock> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Clock> JobScheduler<K> {
+    pub fn with_clock(clock: K) -> Self {
+        JobScheduler { clock, jobs: Vec::new() }
+    }
+    
+    // Runs `job` every `every`, the first time one interval from now
+    pub fn every(mut self, every: Duration, job: impl ScheduledJob + Send + 'static) -> Self {
+        let next_run_at = self.clock.now() + every;
+        self.jobs.push(ScheduledEntry { job: Box::new(job), every, next_run_at });
+        self
+    }
+    
+    pub fn next_run_at(&self, job: &str) -> Option<DateTime<Utc>> {
+        self.jobs.iter().find(|entry| entry.job.name() == job).map(|entry| entry.next_run_at)
+    }
+    
+    // Runs every job that is due. A failed job is reported and still rescheduled;
+    // a job that fell several intervals behind runs once, not once per missed interval.
+    pub fn tick(&mut self) -> JobReport {
+        let now = self.clock.now();
+        let mut report = JobReport::default();
+        for entry in self.jobs.iter_mut().filter(|entry| entry.next_run_at <= now) {
+            report.runs.push(JobRun {
+                job: entry.job.name().to_string(),
+                at: now,
+                outcome: entry.job.run(now),
+            });
+            entry.next_run_at = now + entry.every;
+        }
+        report
+    }
+}
+
+#[cfg(feature = "async")]
+impl<K: Clock> JobScheduler<K> {
+    // Ticks every `poll_every` on the tokio runtime until `shutdown` completes and
+    // returns how many job runs there were. Failures are logged, not fatal.
+    pub async fn run(&mut self, poll_every: std::time::Duration, shutdown: impl Future<Output = ()>) -> usize {
+        let mut interval = tokio::time::interval(poll_every);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        tokio::pin!(shutdown);
+        let mut runs = 0;
+        loop {
+            tokio::select! {
+                _ = &mut shutdown => return runs,
+                _ = interval.tick() => {
+                    let report = self.tick();
+                    for (job, error) in report.failures() {
+                        tracing::warn!(target: "scheduler", job, error, "scheduled job failed");
+                    }
+                    runs += report.runs.len();
+                }
+            }
+        }
+    }
+}
+
+// Tests for JobScheduler and the maintenance jobs, driven by a ManualClock
+#[cfg(test)]
+mod job_scheduler_tests {
+    use super::*;
+    use crate::ManualClock;
+    use std::sync::Arc;
+    use chrono::{DateTime, Duration, TimeZone, Utc};
+    use crate::testing::{MockAnalyticsService, MockEmailService, MockTemplateRenderer, MockUserRepository};
+    
+    fn start() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 6, 1, 9, 0, 0).unwrap()
+    }
+    
+    fn stored_user(repository: &MockUserRepository, name: &str, status: UserStatus, updated_at: DateTime<Utc>) -> User {
+        let user = User::restore(UserId::generate(), name.to_string(), format!("{}@example.com", name),
+                                 status, updated_at, updated_at);
+        repository.save(&user).unwrap();
+        user
+    }
+    
+    fn reminders(repository: &MockUserRepository, email: &MockEmailService) -> InactiveUserReminderJob<MockUserRepository, MockEmailService, MockTemplateRenderer> {
+        InactiveUserReminderJob::new(repository.clone(), email.clone(), MockTemplateRenderer::default(), Duration::days(30))
+    }
+    
+    struct Failing;
+    
+    impl ScheduledJob for Failing {
+        fn name(&self) -> &str {
+            "failing"
+        }
+        
+        fn run(&self, _now: DateTime<Utc>) -> Result<String, String> {
+            Err("disk full".to_string())
+        }
+    }
+    
+    #[test]
+    fn jobs_run_only_once_their_interval_has_passed() {
+        let clock = ManualClock::new(start());
+        let analytics = BufferedAnalyticsService::new(MockAnalyticsService::default());
+        let mut scheduler = JobScheduler::with_clock(clock.clone())
+            .every(Duration::hours(1), AnalyticsFlushJob::new(analytics));
+        assert!(scheduler.tick().runs.is_empty());
+        clock.advance(Duration::minutes(59));
+        assert!(scheduler.tick().runs.is_empty());
+        clock.advance(Duration::minutes(1));
+        assert!(scheduler.tick().ran("analytics_flush"));
+        assert_eq!(scheduler.next_run_at("analytics_flush"), Some(start() + Duration::hours(2)));
+    }
+    
+    #[test]
+    fn a_job_that_fell_behind_runs_once() {
+        let clock = ManualClock::new(start());
+        let analytics = BufferedAnalyticsService::new(MockAnalyticsService::default());
+        let mut scheduler = JobScheduler::with_clock(clock.clone())
+            .every(Duration::hours(1), AnalyticsFlushJob::new(analytics));
+        clock.advance(Duration::hours(5));
+        assert_eq!(scheduler.tick().runs.len(), 1);
+        assert!(scheduler.tick().runs.is_empty());
+        assert_eq!(scheduler.next_run_at("analytics_flush"), Some(start() + Duration::hours(6)));
+    }
+    
+    #[test]
+    fn a_failing_job_is_reported_and_does_not_stop_the_others() {
+        let clock = ManualClock::new(start());
+        let analytics = BufferedAnalyticsService::new(MockAnalyticsService::default());
+        let mut scheduler = JobScheduler::with_clock(clock.clone())
+            .every(Duration::hours(1), Failing)
+            .every(Duration::hours(1), AnalyticsFlushJob::new(analytics));
+        clock.advance(Duration::hours(1));
+        let report = scheduler.tick();
+        assert_eq!(report.failures().collect::<Vec<_>>(), vec![("failing", "disk full")]);
+        assert!(report.ran("analytics_flush"));
+        assert_eq!(scheduler.next_run_at("failing"), Some(start() + Duration::hours(2)));
+    }
+    
+    #[test]
+    fn inactive_active_users_are_reminded_once() {
+        let repository = MockUserRepository::default();
+        let email = MockEmailService::default();
+        stored_user(&repository, "idle_ada", UserStatus::Active, start() - Duration::days(45));
+        stored_user(&repository, "busy_grace", UserStatus::Active, start() - Duration::days(2));
+        stored_user(&repository, "idle_pending", UserStatus::PendingVerification, start() - Duration::days(45));
+        stored_user(&repository, "idle_suspended", UserStatus::Suspended, start() - Duration::days(45));
+        let job = reminders(&repository, &email);
+        
+        assert_eq!(job.run(start()).unwrap(), "sent 1 reminder(s)");
+        let sent = email.sent();
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].to, "idle_ada@example.com");
+        assert_eq!(sent[0].subject, "inactive_reminder");
+        assert!(sent[0].body.contains("last_active=2024-04-17"));
+        
+        assert_eq!(job.run(start() + Duration::days(1)).unwrap(), "sent 0 reminder(s)");
+        assert_eq!(email.sent().len(), 1);
+    }
+    
+    #[test]
+    fn a_user_who_comes_back_and_goes_quiet_again_is_reminded_again() {
+        let repository = MockUserRepository::default();
+        let email = MockEmailService::default();
+        let mut user = stored_user(&repository, "idle_ada", UserStatus::Active, start() - Duration::days(45));
+        let job = reminders(&repository, &email);
+        job.run(start()).unwrap();
+        
+        user.set_display_name(Some("Ada".to_string()));
+        repository.update(&user).unwrap();
+        job.run(user.updated_at() + Duration::days(31)).unwrap();
+        assert_eq!(email.sent().len(), 2);
+    }
+    
+    #[test]
+    fn failed_reminders_are_reported_and_retried_on_the_next_run() {
+        let repository = MockUserRepository::default();
+        let email = MockEmailService::default();
+        stored_user(&repository, "idle_ada", UserStatus::Active, start() - Duration::days(45));
+        let job = reminders(&repository, &email);
+        email.spy().fail_on("send", "SMTP unavailable");
+        assert_eq!(job.run(start()).unwrap_err(), "sent 0 reminder(s), 1 failed: idle_ada: SMTP unavailable");
+        
+        email.spy().succeed();
+        assert_eq!(job.run(start()).unwrap(), "sent 1 reminder(s)");
+    }
+    
+    #[test]
+    fn buffered_analytics_are_forwarded_only_when_flushed() {
+        let inner = MockAnalyticsService::default();
+        let analytics = BufferedAnalyticsService::new(inner.clone());
+        analytics.track_user_registration("ada_l");
+        analytics.track_user_update("ada_l");
+        assert_eq!(inner.spy().calls(), Vec::<String>::new());
+        assert_eq!(analytics.pending_len(), 2);
+        
+        let job = AnalyticsFlushJob::new(analytics.clone());
+        assert_eq!(job.run(start()).unwrap(), "flushed 2 event(s)");
+        assert_eq!(inner.spy().calls(), vec![
+            "track_user_event(ada_l, user_registered)",
+            "track_user_event(ada_l, user_updated)",
+        ]);
+        assert_eq!(analytics.pending_len(), 0);
+    }
+    
+    #[test]
+    fn log_rotation_moves_the_current_log_aside() {
+        let dir = std::env::temp_dir().join(format!("{}-rotation-{}", module_path!().replace("::", "-"), std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let logger = Arc::new(FileActivityLogger::new(dir.join("activity.log").display().to_string()).compress_rotated(false));
+        let job = LogRotationJob::new(logger.clone());
+        assert_eq!(job.run(start()).unwrap(), "nothing to rotate");
+        
+        logger.append("first", start()).unwrap();
+        assert_eq!(job.run(start()).unwrap(), "rotated the activity log");
+        assert_eq!(std::fs::read_to_string(logger.rotated_path(1)).unwrap(), "first\n");
+        logger.append("second", start()).unwrap();
+        assert_eq!(std::fs::read_to_string(dir.join("activity.log")).unwrap(), "second\n");
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
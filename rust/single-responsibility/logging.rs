@@ -0,0 +1,540 @@
+// Activity logging: the ActivityLogger trait, tracing integration and the
+// rotating file logger
+
+use std::collections::HashMap;
+use std::fmt;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use chrono::{DateTime, NaiveDate, Utc};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id};
+use tracing::{Event, Subscriber};
+use tracing_subscriber::layer::{Context, Layer};
+use tracing_subscriber::registry::LookupSpan;
+use crate::{random_hex, AuditEvent, Clock, SystemClock};
+
+// Logger trait - defines logging operations
+pub trait ActivityLogger {
+    fn log_user_activity(&self, username: &str, activity: &str);
+    fn log_error(&self, error: &str, context: &str);
+    
+    // Loggers without a separate audit channel record audits as activity
+    fn log_audit(&self, event: &AuditEvent) {
+        self.log_user_activity(&event.user_id.to_string(), &event.to_string());
+    }
+}
+
+// Identifies one service call across every event it emits
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CorrelationId(String);
+
+impl CorrelationId {
+    pub fn new() -> Self {
+        CorrelationId(random_hex(8))
+    }
+    
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Default for CorrelationId {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Display for CorrelationId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+// Span wrapping one service operation; events logged inside it carry its correlation id
+pub fn user_flow_span(operation: &str) -> tracing::Span {
+    tracing::info_span!("user_flow", operation, correlation_id = %CorrelationId::new())
+}
+
+// ActivityLogger that emits `tracing` events. Where they end up (console, JSON file,
+// test capture) is decided by the layers installed on the subscriber.
+pub struct TracingActivityLogger;
+
+impl TracingActivityLogger {
+    pub fn new() -> Self {
+        TracingActivityLogger
+    }
+}
+
+impl Default for TracingActivityLogger {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ActivityLogger for TracingActivityLogger {
+    fn log_user_activity(&self, username: &str, activity: &str) {
+        tracing::info!(target: "user_activity", username, activity, "user activity");
+    }
+    
+    fn log_error(&self, error: &str, context: &str) {
+        tracing::error!(target: "user_activity", error, context, "operation failed");
+    }
+    
+    fn log_audit(&self, event: &AuditEvent) {
+        tracing::info!(target: "audit", action = %event.action, user_id = %event.user_id, at = %event.at.to_rfc3339(), "audit");
+    }
+}
+
+// Human-readable output for local runs
+pub fn console_layer<S>() -> impl Layer<S>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    tracing_subscriber::fmt::layer().with_target(false)
+}
+
+// One JSON object per line, including the enclosing spans and their correlation ids
+pub fn json_file_layer<S>(path: &Path) -> Result<impl Layer<S>, String>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    let file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|e| format!("Cannot open log file {}: {}", path.display(), e))?;
+    Ok(tracing_subscriber::fmt::layer()
+        .json()
+        .with_current_span(true)
+        .with_span_list(true)
+        .with_writer(Mutex::new(file)))
+}
+
+// An event seen by `InMemoryLayer`, with its fields rendered as strings
+#[derive(Debug, Clone, PartialEq)]
+pub struct CapturedEvent {
+    pub level: tracing::Level,
+    pub target: String,
+    pub fields: HashMap<String, String>,
+    // From the outermost `user_flow` span, so nested flows share one id
+    pub correlation_id: Option<String>,
+}
+
+impl CapturedEvent {
+    pub fn field(&self, name: &str) -> Option<&str> {
+        self.fields.get(name).map(String::as_str)
+    }
+}
+
+#[derive(Default)]
+struct FieldCollector(HashMap<String, String>);
+
+impl Visit for FieldCollector {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.insert(field.name().to_string(), value.to_string());
+    }
+    
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.0.insert(field.name().to_string(), format!("{:?}", value));
+    }
+}
+
+// Keeps events in memory so tests and demos can assert on what was logged
+#[derive(Clone, Default)]
+pub struct InMemoryLayer {
+    events: Arc<Mutex<Vec<CapturedEvent>>>,
+}
+
+impl InMemoryLayer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    pub fn events(&self) -> Vec<CapturedEvent> {
+        self.events.lock().map(|events| events.clone()).unwrap_or_default()
+    }
+    
+    // Runs `f` with only a fresh capture layer installed, returning what it logged
+    pub fn capture<T>(f: impl FnOnce() -> T) -> (T, Vec<CapturedEvent>) {
+        use tracing_subscriber::layer::SubscriberExt;
+        
+        let layer = InMemoryLayer::new();
+        let result = tracing::subscriber::with_default(tracing_subscriber::registry().with(layer.clone()), f);
+        (result, layer.events())
+    }
+}
+
+impl<S> Layer<S> for InMemoryLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let mut fields = FieldCollector::default();
+        attrs.record(&mut fields);
+        if let (Some(correlation_id), Some(span)) = (fields.0.remove("correlation_id"), ctx.span(id)) {
+            span.extensions_mut().insert(CorrelationId(correlation_id));
+        }
+    }
+    
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        let mut fields = FieldCollector::default();
+        event.record(&mut fields);
+        let correlation_id = ctx.event_scope(event).and_then(|scope| {
+            scope
+                .from_root()
+                .find_map(|span| span.extensions().get::<CorrelationId>().map(|id| id.0.clone()))
+        });
+        if let Ok(mut events) = self.events.lock() {
+            events.push(CapturedEvent {
+                level: *event.metadata().level(),
+                target: event.metadata().target().to_string(),
+                fields: fields.0,
+                correlation_id,
+            });
+        }
+    }
+}
+
+// Tests for `TracingActivityLogger` and the correlation ids of service flows,
+// asserting on the events captured by `InMemoryLayer`.
+#[cfg(test)]
+mod tracing_logger_tests {
+    use super::*;
+    
+    fn activities(events: &[CapturedEvent]) -> Vec<&str> {
+        events.iter().filter_map(|event| event.field("activity")).collect()
+    }
+    
+    #[test]
+    fn activity_is_an_info_event_with_fields() {
+        let (_, events) = InMemoryLayer::capture(|| TracingActivityLogger::new().log_user_activity("ada", "Logged in"));
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].level, tracing::Level::INFO);
+        assert_eq!(events[0].target, "user_activity");
+        assert_eq!(events[0].field("username"), Some("ada"));
+        assert_eq!(events[0].field("activity"), Some("Logged in"));
+        assert_eq!(events[0].correlation_id, None);
+    }
+    
+    #[test]
+    fn error_is_an_error_event_with_context() {
+        let (_, events) = InMemoryLayer::capture(|| TracingActivityLogger::new().log_error("disk full", "Failed to save user"));
+        assert_eq!(events[0].level, tracing::Level::ERROR);
+        assert_eq!(events[0].field("error"), Some("disk full"));
+        assert_eq!(events[0].field("context"), Some("Failed to save user"));
+    }
+    
+    #[test]
+    fn create_user_events_share_one_correlation_id() {
+        let service = crate::compose! { logger: tracing };
+        let (user, events) = InMemoryLayer::capture(|| {
+            service.create_user("ada_l".to_string(), "ada@example.com".to_string())
+        });
+        assert!(user.is_ok());
+        assert!(activities(&events).contains(&"User created"));
+        let id = events[0].correlation_id.clone();
+        assert!(id.is_some());
+        assert!(events.iter().all(|event| event.correlation_id == id));
+    }
+    
+    #[test]
+    fn separate_flows_get_separate_ids() {
+        let service = crate::compose! { logger: tracing };
+        let (_, events) = InMemoryLayer::capture(|| {
+            service.create_user("ada_l".to_string(), "ada@example.com".to_string()).unwrap();
+            service.create_user("grace_h".to_string(), "grace@example.com".to_string()).unwrap();
+        });
+        let ids: std::collections::HashSet<_> = events.iter().map(|event| event.correlation_id.clone()).collect();
+        assert_eq!(ids.len(), 2);
+    }
+    
+    #[test]
+    fn nested_flow_keeps_the_outer_id() {
+        let service = crate::compose! { logger: tracing, passwords: fake };
+        let (_, events) = InMemoryLayer::capture(|| {
+            let _batch = crate::user_flow_span("import").entered();
+            service.create_user("ada_l".to_string(), "ada@example.com".to_string()).unwrap();
+            service.create_user("grace_h".to_string(), "grace@example.com".to_string()).unwrap();
+        });
+        assert_eq!(activities(&events), vec!["User created", "User created"]);
+        assert!(events.iter().all(|event| event.correlation_id.is_some() && event.correlation_id == events[0].correlation_id));
+    }
+    
+    #[test]
+    fn calls_outside_a_flow_have_no_id() {
+        let service = crate::compose! { logger: tracing, passwords: fake };
+        let user = service.register_with_password("ada_l".to_string(), "ada@example.com".to_string(), "Correct-Horse-42").unwrap();
+        // verify_login is not a flow of its own
+        let (_, events) = InMemoryLayer::capture(|| service.verify_login(&user, "wrong password").unwrap());
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].correlation_id, None);
+    }
+}
+
+// Where the factories log unless told otherwise: the system temp directory, so
+// running the demo or the tests never writes into the current directory
+pub fn default_log_path() -> String {
+    std::env::temp_dir().join("activity.log").display().to_string()
+}
+
+// Concrete implementation of ActivityLogger.
+// Appends to `log_file` and rotates it by size and by day: `activity.log` moves to
+// `activity.log.1.gz`, older files shift up, and anything past the retention count is deleted.
+pub struct FileActivityLogger {
+    log_file: PathBuf,
+    max_bytes: u64,
+    rotate_daily: bool,
+    retain: usize,
+    compress: bool,
+    current: Mutex<Option<OpenLogFile>>,
+    clock: Arc<dyn Clock>,
+}
+
+struct OpenLogFile {
+    file: std::fs::File,
+    size: u64,
+    // Day of the last entry; a write on a later day starts a new file
+    day: NaiveDate,
+}
+
+impl FileActivityLogger {
+    pub fn new(log_file: String) -> Self {
+        FileActivityLogger {
+            log_file: PathBuf::from(log_file),
+            max_bytes: 10 * 1024 * 1024,
+            rotate_daily: true,
+            retain: 5,
+            compress: true,
+            current: Mutex::new(None),
+            clock: Arc::new(SystemClock),
+        }
+    }
+    
+    // Where entry timestamps come from; SystemClock by default
+    pub fn with_clock(mut self, clock: impl Clock + 'static) -> Self {
+        self.clock = Arc::new(clock);
+        self
+    }
+    
+    pub fn max_size(mut self, bytes: u64) -> Self {
+        self.max_bytes = bytes;
+        self
+    }
+    
+    pub fn rotate_daily(mut self, enabled: bool) -> Self {
+        self.rotate_daily = enabled;
+        self
+    }
+    
+    // How many rotated files to keep; 0 deletes the log instead of rotating it
+    pub fn retain(mut self, count: usize) -> Self {
+        self.retain = count;
+        self
+    }
+    
+    pub fn compress_rotated(mut self, enabled: bool) -> Self {
+        self.compress = enabled;
+        self
+    }
+    
+    // Path of the `index`th most recent rotated file, starting at 1
+    pub fn rotated_path(&self, index: usize) -> PathBuf {
+        let suffix = if self.compress { ".gz" } else { "" };
+        PathBuf::from(format!("{}.{}{}", self.log_file.display(), index, suffix))
+    }
+    
+    // Appends one line, rotating first if it would overflow the file or `now` is a new day
+    pub fn append(&self, message: &str, now: DateTime<Utc>) -> Result<(), String> {
+        let failed = |e: std::io::Error| format!("Cannot write to {}: {}", self.log_file.display(), e);
+        let mut current = self.current.lock().map_err(|e| e.to_string())?;
+        if current.is_none() {
+            *current = Some(self.open(now).map_err(failed)?);
+        }
+        
+        let line_len = message.len() as u64 + 1;
+        let needs_rotation = current.as_ref().is_some_and(|log| {
+            log.size > 0
+                && (log.size + line_len > self.max_bytes || (self.rotate_daily && log.day != now.date_naive()))
+        });
+        if needs_rotation {
+            *current = None;
+            self.rotate().map_err(failed)?;
+            *current = Some(self.open(now).map_err(failed)?);
+        }
+        
+        let log = current.as_mut().ok_or("Log file is not open")?;
+        writeln!(log.file, "{}", message).map_err(failed)?;
+        log.size += line_len;
+        log.day = now.date_naive();
+        Ok(())
+    }
+    
+    fn open(&self, now: DateTime<Utc>) -> std::io::Result<OpenLogFile> {
+        let file = std::fs::OpenOptions::new().create(true).append(true).open(&self.log_file)?;
+        let metadata = file.metadata()?;
+        let day = match metadata.modified() {
+            Ok(modified) if metadata.len() > 0 => DateTime::<Utc>::from(modified).date_naive(),
+            _ => now.date_naive(),
+        };
+        Ok(OpenLogFile { file, size: metadata.len(), day })
+    }
+    
+    fn rotate(&self) -> std::io::Result<()> {
+        if self.retain == 0 {
+            return remove_if_exists(&self.log_file);
+        }
+        remove_if_exists(&self.rotated_path(self.retain))?;
+        for index in (1..self.retain).rev() {
+            let from = self.rotated_path(index);
+            if from.exists() {
+                std::fs::rename(&from, self.rotated_path(index + 1))?;
+            }
+        }
+        
+        if self.compress {
+            let mut source = std::fs::File::open(&self.log_file)?;
+            let target = std::fs::File::create(self.rotated_path(1))?;
+            let mut encoder = flate2::write::GzEncoder::new(target, flate2::Compression::default());
+            std::io::copy(&mut source, &mut encoder)?;
+            encoder.finish()?;
+            std::fs::remove_file(&self.log_file)
+        } else {
+            std::fs::rename(&self.log_file, self.rotated_path(1))
+        }
+    }
+    
+    // Rotates now, regardless of size or day; false if the log is missing or empty
+    pub fn rotate_now(&self) -> Result<bool, String> {
+        let failed = |e: std::io::Error| format!("Cannot rotate {}: {}", self.log_file.display(), e);
+        let mut current = self.current.lock().map_err(|e| e.to_string())?;
+        let size = match std::fs::metadata(&self.log_file) {
+            Ok(metadata) => metadata.len(),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => 0,
+            Err(e) => return Err(failed(e)),
+        };
+        if size == 0 {
+            return Ok(false);
+        }
+        *current = None;
+        self.rotate().map_err(failed)?;
+        Ok(true)
+    }
+    
+    fn write_entry(&self, message: &str, now: DateTime<Utc>) {
+        println!("{}", message);
+        self.append(message, now).unwrap_or_else(|e| {
+            eprintln!("Failed to write to log file: {}", e);
+        });
+    }
+}
+
+fn remove_if_exists(path: &Path) -> std::io::Result<()> {
+    match std::fs::remove_file(path) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
+
+impl ActivityLogger for FileActivityLogger {
+    fn log_user_activity(&self, username: &str, activity: &str) {
+        let timestamp = self.clock.now();
+        let log_message = format!("[LOG {}] User {} performed: {}", 
+                                timestamp.format("%Y-%m-%d %H:%M:%S"), username, activity);
+        self.write_entry(&log_message, timestamp);
+    }
+    
+    fn log_error(&self, error: &str, context: &str) {
+        let timestamp = self.clock.now();
+        let log_message = format!("[ERROR {}] {}: {}", 
+                                timestamp.format("%Y-%m-%d %H:%M:%S"), context, error);
+        self.write_entry(&log_message, timestamp);
+    }
+}
+
+// Tests for `FileActivityLogger` writes, rotation and retention, each in its own temp dir
+#[cfg(test)]
+mod file_activity_logger_tests {
+    use super::*;
+    use crate::ManualClock;
+    use std::path::PathBuf;
+    use chrono::{TimeZone, Utc};
+    
+    fn temp_dir(test: &str) -> PathBuf {
+        let module = module_path!().replace("::", "-");
+        let dir = std::env::temp_dir().join(format!("{}-{}-{}", module, test, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+    
+    fn logger(dir: &Path) -> FileActivityLogger {
+        FileActivityLogger::new(dir.join("activity.log").display().to_string())
+    }
+    
+    #[test]
+    fn default_log_path_is_outside_the_working_directory() {
+        let path = PathBuf::from(default_log_path());
+        assert_eq!(path.parent(), Some(std::env::temp_dir().as_path()));
+    }
+    
+    fn noon(day: u32) -> chrono::DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 3, day, 12, 0, 0).unwrap()
+    }
+    
+    #[test]
+    fn entries_are_appended_to_the_file() {
+        let dir = temp_dir("append");
+        // The file's mtime is today, not the clock's day, so keep daily rotation out of it
+        let at_noon = || logger(&dir).rotate_daily(false).with_clock(ManualClock::new(noon(1)));
+        at_noon().log_user_activity("ada", "Logged in");
+        let second = at_noon();
+        second.log_error("disk full", "Failed to save user");
+        let text = std::fs::read_to_string(dir.join("activity.log")).unwrap();
+        let lines: Vec<_> = text.lines().collect();
+        assert_eq!(lines, vec![
+            "[LOG 2024-03-01 12:00:00] User ada performed: Logged in",
+            "[ERROR 2024-03-01 12:00:00] Failed to save user: disk full",
+        ]);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+    
+    #[test]
+    fn large_files_rotate_and_old_ones_are_dropped() {
+        let dir = temp_dir("size");
+        let logger = logger(&dir).max_size(64).retain(2).rotate_daily(false);
+        for entry in 0..10 {
+            logger.append(&format!("entry {:02} padded to about forty bytes", entry), noon(1)).unwrap();
+        }
+        assert!(std::fs::metadata(dir.join("activity.log")).unwrap().len() <= 64);
+        assert!(logger.rotated_path(1).exists());
+        assert!(logger.rotated_path(2).exists());
+        assert!(!logger.rotated_path(3).exists());
+        let rotated = std::fs::read(logger.rotated_path(1)).unwrap();
+        assert_eq!(&rotated[..2], &[0x1f, 0x8b], "rotated files are gzip");
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+    
+    #[test]
+    fn a_new_day_starts_a_new_file() {
+        let dir = temp_dir("daily");
+        let logger = logger(&dir).compress_rotated(false);
+        logger.append("first day", noon(1)).unwrap();
+        logger.append("still the first day", noon(1)).unwrap();
+        logger.append("second day", noon(2)).unwrap();
+        assert_eq!(std::fs::read_to_string(dir.join("activity.log")).unwrap(), "second day\n");
+        assert_eq!(std::fs::read_to_string(logger.rotated_path(1)).unwrap(), "first day\nstill the first day\n");
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+    
+    #[test]
+    fn zero_retention_keeps_no_rotated_files() {
+        let dir = temp_dir("no_retention");
+        let logger = logger(&dir).max_size(16).retain(0);
+        logger.append("first entry here", noon(1)).unwrap();
+        logger.append("second entry here", noon(1)).unwrap();
+        let names: Vec<_> = std::fs::read_dir(&dir).unwrap().map(|entry| entry.unwrap().file_name()).collect();
+        assert_eq!(names, vec![std::ffi::OsString::from("activity.log")]);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,432 @@
+// Counters and latency histograms, a Prometheus exporter and metered decorators
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use crate::{EmailMessage, EmailService, Page, Pagination, UnitOfWork, User, UserRepository};
+
+// Metrics trait - records counters, gauges and histograms. Labels are
+// (name, value) pairs; every distinct set of labels is a separate series.
+pub trait Metrics: Send + Sync {
+    fn increment_counter(&self, name: &str, labels: &[(&str, &str)]);
+    fn set_gauge(&self, name: &str, labels: &[(&str, &str)], value: f64);
+    fn observe_histogram(&self, name: &str, labels: &[(&str, &str)], value: f64);
+    fn snapshot(&self) -> MetricsSnapshot;
+}
+
+// Upper bounds of the default latency buckets, in seconds
+pub const LATENCY_BUCKETS: [f64; 11] = [0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5];
+
+// A metric name and its labels, sorted by label name
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SeriesKey {
+    pub name: String,
+    pub labels: Vec<(String, String)>,
+}
+
+impl SeriesKey {
+    pub fn new(name: &str, labels: &[(&str, &str)]) -> Self {
+        let mut labels: Vec<(String, String)> = labels.iter().map(|(key, value)| (key.to_string(), value.to_string())).collect();
+        labels.sort();
+        SeriesKey { name: name.to_string(), labels }
+    }
+    
+    // `{key="value",...}` in Prometheus syntax, with `extra` last; empty when there are no labels
+    fn label_set(&self, extra: Option<(&str, &str)>) -> String {
+        let pairs: Vec<String> = self.labels.iter()
+            .map(|(key, value)| (key.as_str(), value.as_str()))
+            .chain(extra)
+            .map(|(key, value)| format!("{}=\"{}\"", key, value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")))
+            .collect();
+        if pairs.is_empty() {
+            String::new()
+        } else {
+            format!("{{{}}}", pairs.join(","))
+        }
+    }
+}
+
+impl fmt::Display for SeriesKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}{}", self.name, self.label_set(None))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Histogram {
+    pub bounds: Vec<f64>,
+    // Observations per bucket, not cumulative; the extra last bucket holds values above every bound
+    pub counts: Vec<u64>,
+    pub count: u64,
+    pub sum: f64,
+}
+
+impl Histogram {
+    pub fn new(bounds: &[f64]) -> Self {
+        Histogram { bounds: bounds.to_vec(), counts: vec![0; bounds.len() + 1], count: 0, sum: 0.0 }
+    }
+    
+    pub fn observe(&mut self, value: f64) {
+        let bucket = self.bounds.iter().position(|bound| value <= *bound).unwrap_or(self.bounds.len());
+        self.counts[bucket] += 1;
+        self.count += 1;
+        self.sum += value;
+    }
+    
+    pub fn mean(&self) -> Option<f64> {
+        (self.count > 0).then(|| self.sum / self.count as f64)
+    }
+}
+
+// Every series a Metrics implementation holds at one moment
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MetricsSnapshot {
+    pub counters: BTreeMap<SeriesKey, u64>,
+    pub gauges: BTreeMap<SeriesKey, f64>,
+    pub histograms: BTreeMap<SeriesKey, Histogram>,
+}
+
+impl MetricsSnapshot {
+    // 0 for a counter that was never incremented
+    pub fn counter(&self, name: &str, labels: &[(&str, &str)]) -> u64 {
+        self.counters.get(&SeriesKey::new(name, labels)).copied().unwrap_or(0)
+    }
+    
+    // Sum of a counter over all of its label sets
+    pub fn counter_total(&self, name: &str) -> u64 {
+        self.counters.iter().filter(|(key, _)| key.name == name).map(|(_, count)| count).sum()
+    }
+    
+    pub fn gauge(&self, name: &str, labels: &[(&str, &str)]) -> Option<f64> {
+        self.gauges.get(&SeriesKey::new(name, labels)).copied()
+    }
+    
+    pub fn histogram(&self, name: &str, labels: &[(&str, &str)]) -> Option<&Histogram> {
+        self.histograms.get(&SeriesKey::new(name, labels))
+    }
+    
+    pub fn is_empty(&self) -> bool {
+        self.counters.is_empty() && self.gauges.is_empty() && self.histograms.is_empty()
+    }
+}
+
+// Concrete implementation of Metrics that keeps every series in memory
+pub struct InMemoryMetrics {
+    buckets: Vec<f64>,
+    state: Mutex<MetricsSnapshot>,
+}
+
+impl InMemoryMetrics {
+    pub fn new() -> Self {
+        InMemoryMetrics::with_buckets(&LATENCY_BUCKETS)
+    }
+    
+    // Histogram bucket bounds for every histogram this instance creates
+    pub fn with_buckets(bounds: &[f64]) -> Self {
+        InMemoryMetrics { buckets: bounds.to_vec(), state: Mutex::new(MetricsSnapshot::default()) }
+    }
+}
+
+impl Default for InMemoryMetrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Metrics for InMemoryMetrics {
+    fn increment_counter(&self, name: &str, labels: &[(&str, &str)]) {
+        if let Ok(mut state) = self.state.lock() {
+            *state.counters.entry(SeriesKey::new(name, labels)).or_insert(0) += 1;
+        }
+    }
+    
+    fn set_gauge(&self, name: &str, labels: &[(&str, &str)], value: f64) {
+        if let Ok(mut state) = self.state.lock() {
+            state.gauges.insert(SeriesKey::new(name, labels), value);
+        }
+    }
+    
+    fn observe_histogram(&self, name: &str, labels: &[(&str, &str)], value: f64) {
+        if let Ok(mut state) = self.state.lock() {
+            state.histograms.entry(SeriesKey::new(name, labels))
+                .or_insert_with(|| Histogram::new(&self.buckets))
+                .observe(value);
+        }
+    }
+    
+    fn snapshot(&self) -> MetricsSnapshot {
+        self.state.lock().map(|state| state.clone()).unwrap_or_default()
+    }
+}
+
+// Renders a snapshot in the Prometheus text exposition format
+pub struct PrometheusExporter;
+
+impl PrometheusExporter {
+    pub fn new() -> Self {
+        PrometheusExporter
+    }
+    
+    pub fn render(&self, snapshot: &MetricsSnapshot) -> String {
+        let mut out = String::new();
+        let mut last_name = None;
+        let mut type_line = |out: &mut String, name: &str, kind: &str| {
+            if last_name.as_deref() != Some(name) {
+                out.push_str(&format!("# TYPE {} {}\n", name, kind));
+                last_name = Some(name.to_string());
+            }
+        };
+        
+        for (key, count) in &snapshot.counters {
+            type_line(&mut out, &key.name, "counter");
+            out.push_str(&format!("{} {}\n", key, count));
+        }
+        for (key, value) in &snapshot.gauges {
+            type_line(&mut out, &key.name, "gauge");
+            out.push_str(&format!("{} {}\n", key, value));
+        }
+        for (key, histogram) in &snapshot.histograms {
+            type_line(&mut out, &key.name, "histogram");
+            let mut cumulative = 0;
+            for (bound, count) in histogram.bounds.iter().zip(&histogram.counts) {
+                cumulative += count;
+                out.push_str(&format!("{}_bucket{} {}\n", key.name, key.label_set(Some(("le", &bound.to_string()))), cumulative));
+            }
+            out.push_str(&format!("{}_bucket{} {}\n", key.name, key.label_set(Some(("le", "+Inf"))), histogram.count));
+            out.push_str(&format!("{}_sum{} {}\n", key.name, key.label_set(None), histogram.sum));
+            out.push_str(&format!("{}_count{} {}\n", key.name, key.label_set(None), histogram.count));
+        }
+        out
+    }
+}
+
+impl Default for PrometheusExporter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Counts one call to `{component}_operations_total` by outcome and records its
+// duration in `{component}_operation_duration_seconds`
+pub(crate) fn record_operation(metrics: &dyn Metrics, component: &str, operation: &str, outcome: &str, started: std::time::Instant) {
+    metrics.increment_counter(&format!("{}_operations_total", component), &[("operation", operation), ("outcome", outcome)]);
+    metrics.observe_histogram(&format!("{}_operation_duration_seconds", component), &[("operation", operation)],
+                              started.elapsed().as_secs_f64());
+}
+
+// Decorator that records a count and latency for every repository call.
+// `count` also sets the `user_repository_users` gauge.
+pub struct MeteredUserRepository<R: UserRepository> {
+    inner: R,
+    metrics: Arc<dyn Metrics>,
+}
+
+impl<R: UserRepository> MeteredUserRepository<R> {
+    pub fn new(inner: R, metrics: Arc<dyn Metrics>) -> Self {
+        MeteredUserRepository { inner, metrics }
+    }
+    
+    fn measure<Out>(&self, operation: &str, call: impl FnOnce() -> Result<Out, String>) -> Result<Out, String> {
+        let started = std::time::Instant::now();
+        let result = call();
+        let outcome = if result.is_ok() { "success" } else { "error" };
+        record_operation(self.metrics.as_ref(), "user_repository", operation, outcome, started);
+        result
+    }
+}
+
+impl<R: UserRepository> UnitOfWork for MeteredUserRepository<R> {
+    fn begin(&self) -> Result<(), String> {
+        self.inner.begin()
+    }
+    
+    fn commit(&self) -> Result<(), String> {
+        self.inner.commit()
+    }
+    
+    fn rollback(&self) -> Result<(), String> {
+        self.inner.rollback()
+    }
+}
+
+impl<R: UserRepository> UserRepository for MeteredUserRepository<R> {
+    fn save(&self, user: &User) -> Result<bool, String> {
+        self.measure("save", || self.inner.save(user))
+    }
+    
+    fn update(&self, user: &User) -> Result<bool, String> {
+        self.measure("update", || self.inner.update(user))
+    }
+    
+    fn find_by_id(&self, user_id: &str) -> Result<Option<User>, String> {
+        self.measure("find_by_id", || self.inner.find_by_id(user_id))
+    }
+    
+    fn find_by_email(&self, email: &str) -> Result<Option<User>, String> {
+        self.measure("find_by_email", || self.inner.find_by_email(email))
+    }
+    
+    fn find_all(&self) -> Result<Vec<User>, String> {
+        self.measure("find_all", || self.inner.find_all())
+    }
+    
+    fn search(&self, query: &str, page: Pagination) -> Result<Page<User>, String> {
+        self.measure("search", || self.inner.search(query, page))
+    }
+    
+    fn count(&self) -> Result<usize, String> {
+        let count = self.measure("count", || self.inner.count())?;
+        self.metrics.set_gauge("user_repository_users", &[], count as f64);
+        Ok(count)
+    }
+    
+    fn delete(&self, user: &User) -> Result<bool, String> {
+        self.measure("delete", || self.inner.delete(user))
+    }
+}
+
+// Decorator that records a count and latency for every email sent. A message
+// the server declined counts as "rejected", distinct from a delivery error.
+pub struct MeteredEmailService<E: EmailService> {
+    inner: E,
+    metrics: Arc<dyn Metrics>,
+}
+
+impl<E: EmailService> MeteredEmailService<E> {
+    pub fn new(inner: E, metrics: Arc<dyn Metrics>) -> Self {
+        MeteredEmailService { inner, metrics }
+    }
+}
+
+impl<E: EmailService> EmailService for MeteredEmailService<E> {
+    fn send(&self, message: &EmailMessage) -> Result<bool, String> {
+        let started = std::time::Instant::now();
+        let result = self.inner.send(message);
+        let outcome = match result {
+            Ok(true) => "success",
+            Ok(false) => "rejected",
+            Err(_) => "error",
+        };
+        record_operation(self.metrics.as_ref(), "email_service", "send", outcome, started);
+        result
+    }
+}
+
+// Tests for the Metrics implementations, the Prometheus exporter and the metered collaborators
+#[cfg(test)]
+mod metrics_tests {
+    use super::*;
+    use std::sync::Arc;
+    use crate::testing::{mock_user_service, MockEmailService, MockUserRepository};
+    
+    #[test]
+    fn counters_gauges_and_histograms_are_kept_per_label_set() {
+        let metrics = InMemoryMetrics::with_buckets(&[0.1, 1.0]);
+        metrics.increment_counter("requests_total", &[("route", "a")]);
+        metrics.increment_counter("requests_total", &[("route", "a")]);
+        metrics.increment_counter("requests_total", &[("route", "b")]);
+        metrics.set_gauge("queue_depth", &[], 4.0);
+        metrics.set_gauge("queue_depth", &[], 2.0);
+        for value in [0.05, 0.5, 3.0] {
+            metrics.observe_histogram("latency_seconds", &[], value);
+        }
+        
+        let snapshot = metrics.snapshot();
+        assert_eq!(snapshot.counter("requests_total", &[("route", "a")]), 2);
+        assert_eq!(snapshot.counter("requests_total", &[("route", "c")]), 0);
+        assert_eq!(snapshot.counter_total("requests_total"), 3);
+        assert_eq!(snapshot.gauge("queue_depth", &[]), Some(2.0));
+        let histogram = snapshot.histogram("latency_seconds", &[]).unwrap();
+        assert_eq!(histogram.counts, vec![1, 1, 1]);
+        assert_eq!(histogram.count, 3);
+        assert_eq!(histogram.mean(), Some(3.55 / 3.0));
+    }
+    
+    #[test]
+    fn label_order_does_not_matter() {
+        let metrics = InMemoryMetrics::new();
+        metrics.increment_counter("ops_total", &[("b", "2"), ("a", "1")]);
+        assert_eq!(metrics.snapshot().counter("ops_total", &[("a", "1"), ("b", "2")]), 1);
+    }
+    
+    #[test]
+    fn prometheus_output_has_types_cumulative_buckets_and_escaped_labels() {
+        let metrics = InMemoryMetrics::with_buckets(&[0.1, 1.0]);
+        metrics.increment_counter("ops_total", &[("operation", "say \"hi\"")]);
+        metrics.set_gauge("users", &[], 3.0);
+        metrics.observe_histogram("latency_seconds", &[("operation", "save")], 0.05);
+        metrics.observe_histogram("latency_seconds", &[("operation", "save")], 0.5);
+        
+        assert_eq!(PrometheusExporter::new().render(&metrics.snapshot()), concat!(
+            "# TYPE ops_total counter\n",
+            "ops_total{operation=\"say \\\"hi\\\"\"} 1\n",
+            "# TYPE users gauge\n",
+            "users 3\n",
+            "# TYPE latency_seconds histogram\n",
+            "latency_seconds_bucket{operation=\"save\",le=\"0.1\"} 1\n",
+            "latency_seconds_bucket{operation=\"save\",le=\"1\"} 2\n",
+            "latency_seconds_bucket{operation=\"save\",le=\"+Inf\"} 2\n",
+            "latency_seconds_sum{operation=\"save\"} 0.55\n",
+            "latency_seconds_count{operation=\"save\"} 2\n",
+        ));
+    }
+    
+    #[test]
+    fn service_operations_are_counted_by_outcome_and_timed() {
+        let (service, mocks) = mock_user_service();
+        let service = service.with_metrics(Arc::new(InMemoryMetrics::new()));
+        let mut user = service.create_user("ada_l".to_string(), "ada@example.com".to_string()).unwrap();
+        service.verify_user(&mut user).unwrap();
+        mocks.repository.spy().fail_on("save", "disk full");
+        service.create_user("grace_h".to_string(), "grace@example.com".to_string()).unwrap_err();
+        
+        let snapshot = service.metrics_snapshot();
+        let ops = "user_service_operations_total";
+        assert_eq!(snapshot.counter(ops, &[("operation", "create_user"), ("outcome", "success")]), 1);
+        assert_eq!(snapshot.counter(ops, &[("operation", "create_user"), ("outcome", "error")]), 1);
+        assert_eq!(snapshot.counter(ops, &[("operation", "verify_user"), ("outcome", "success")]), 1);
+        let latency = snapshot.histogram("user_service_operation_duration_seconds", &[("operation", "create_user")]).unwrap();
+        assert_eq!(latency.count, 2);
+    }
+    
+    #[test]
+    fn a_service_without_metrics_has_an_empty_snapshot() {
+        let (service, _mocks) = mock_user_service();
+        service.create_user("ada_l".to_string(), "ada@example.com".to_string()).unwrap();
+        assert!(service.metrics_snapshot().is_empty());
+    }
+    
+    #[test]
+    fn metered_repository_records_calls_and_the_user_gauge() {
+        let metrics = Arc::new(InMemoryMetrics::new());
+        let inner = MockUserRepository::default();
+        let repository = MeteredUserRepository::new(inner.clone(), metrics.clone());
+        repository.save(&User::new("ada_l".to_string(), "ada@example.com".to_string())).unwrap();
+        inner.spy().fail_on("search", "replica offline");
+        repository.search("", Pagination::default()).unwrap_err();
+        assert_eq!(repository.count().unwrap(), 1);
+        
+        let snapshot = metrics.snapshot();
+        let ops = "user_repository_operations_total";
+        assert_eq!(snapshot.counter(ops, &[("operation", "save"), ("outcome", "success")]), 1);
+        assert_eq!(snapshot.counter(ops, &[("operation", "search"), ("outcome", "error")]), 1);
+        assert_eq!(snapshot.gauge("user_repository_users", &[]), Some(1.0));
+    }
+    
+    #[test]
+    fn metered_email_service_separates_errors_from_successes() {
+        let metrics = Arc::new(InMemoryMetrics::new());
+        let inner = MockEmailService::default();
+        let email = MeteredEmailService::new(inner.clone(), metrics.clone());
+        let message = EmailMessage { to: "ada@example.com".to_string(), subject: "Hi".to_string(), body: "Hello".to_string() };
+        email.send(&message).unwrap();
+        inner.spy().fail_on("send", "SMTP unavailable");
+        email.send(&message).unwrap_err();
+        
+        let snapshot = metrics.snapshot();
+        let ops = "email_service_operations_total";
+        assert_eq!(snapshot.counter(ops, &[("operation", "send"), ("outcome", "success")]), 1);
+        assert_eq!(snapshot.counter(ops, &[("operation", "send"), ("outcome", "error")]), 1);
+        assert_eq!(snapshot.histogram("email_service_operation_duration_seconds", &[("operation", "send")]).unwrap().count, 2);
+    }
+}
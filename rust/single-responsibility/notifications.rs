@@ -0,0 +1,337 @@
+// Push and SMS notifications, with rate limiting and a circuit breaker
+
+use std::sync::Mutex;
+use chrono::{DateTime, Duration, Utc};
+use crate::{Clock, PhoneNumber, SystemClock};
+
+// Notification service trait - defines notification operations.
+// SMS takes a parsed PhoneNumber, so a malformed number never reaches a provider.
+pub trait NotificationService {
+    fn send_push_notification(&self, username: &str, message: &str) -> Result<bool, String>;
+    fn send_sms_notification(&self, phone: &PhoneNumber, message: &str) -> Result<bool, String>;
+}
+
+// Concrete implementation of NotificationService
+pub struct FirebaseNotificationService {
+    api_key: String,
+}
+
+impl FirebaseNotificationService {
+    pub fn new(api_key: String) -> Self {
+        FirebaseNotificationService { api_key }
+    }
+}
+
+impl NotificationService for FirebaseNotificationService {
+    fn send_push_notification(&self, username: &str, message: &str) -> Result<bool, String> {
+        println!("Sending Firebase push notification (API: {}) to {}: {}", self.api_key, username, message);
+        // Firebase push notification logic
+        Ok(true)
+    }
+    
+    fn send_sms_notification(&self, phone: &PhoneNumber, message: &str) -> Result<bool, String> {
+        println!("Sending SMS via Firebase (API: {}) to {}: {}", self.api_key, phone, message);
+        // SMS sending logic
+        Ok(true)
+    }
+}
+
+// NotificationService that posts to an HTTP push gateway with reqwest (enable the `http` feature).
+// Each notification is a form POST to `{endpoint}/push` or `{endpoint}/sms`.
+#[cfg(feature = "http")]
+pub struct HttpNotificationService {
+    client: reqwest::blocking::Client,
+    endpoint: String,
+    api_key: String,
+}
+
+#[cfg(feature = "http")]
+impl HttpNotificationService {
+    pub fn new(endpoint: String, api_key: String) -> Self {
+        HttpNotificationService {
+            client: reqwest::blocking::Client::new(),
+            endpoint: endpoint.trim_end_matches('/').to_string(),
+            api_key,
+        }
+    }
+    
+    fn post(&self, channel: &str, recipient: &str, message: &str) -> Result<bool, String> {
+        let response = self.client
+            .post(format!("{}/{}", self.endpoint, channel))
+            .bearer_auth(&self.api_key)
+            .form(&[("to", recipient), ("message", message)])
+            .send()
+            .map_err(|e| format!("{} notification to {} failed: {}", channel, recipient, e))?;
+        Ok(response.status().is_success())
+    }
+}
+
+#[cfg(feature = "http")]
+impl NotificationService for HttpNotificationService {
+    fn send_push_notification(&self, username: &str, message: &str) -> Result<bool, String> {
+        self.post("push", username, message)
+    }
+    
+    fn send_sms_notification(&self, phone: &PhoneNumber, message: &str) -> Result<bool, String> {
+        self.post("sms", phone.as_str(), message)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CircuitState {
+    // Calls pass through; consecutive failures are counted
+    Closed,
+    // Calls are rejected without reaching the inner service
+    Open,
+    // The cool-down has passed; the next call decides whether to close or reopen
+    HalfOpen,
+}
+
+struct ResilienceState {
+    tokens: u32,
+    last_refill: DateTime<Utc>,
+    circuit: CircuitState,
+    consecutive_failures: u32,
+    opened_at: DateTime<Utc>,
+}
+
+// Decorator that adds a token-bucket rate limit and a circuit breaker to any
+// NotificationService. The wrapped service is unchanged, and so is the code calling it.
+pub struct ResilientNotificationService<N: NotificationService, K: Clock = SystemClock> {
+    inner: N,
+    clock: K,
+    capacity: u32,
+    refill_every: Duration,
+    failure_threshold: u32,
+    open_for: Duration,
+    state: Mutex<ResilienceState>,
+}
+
+impl<N: NotificationService> ResilientNotificationService<N, SystemClock> {
+    // Defaults: bursts of 10, one token per second, open after 3 failures for 30 seconds
+    pub fn new(inner: N) -> Self {
+        Self::with_clock(inner, SystemClock)
+    }
+}
+
+impl<N: NotificationService, K: Clock> ResilientNotificationService<N, K> {
+    pub fn with_clock(inner: N, clock: K) -> Self {
+        let now = clock.now();
+        ResilientNotificationService {
+            inner,
+            clock,
+            capacity: 10,
+            refill_every: Duration::seconds(1),
+            failure_threshold: 3,
+            open_for: Duration::seconds(30),
+            state: Mutex::new(ResilienceState {
+                tokens: 10,
+                last_refill: now,
+                circuit: CircuitState::Closed,
+                consecutive_failures: 0,
+                opened_at: now,
+            }),
+        }
+    }
+    
+    // At most `capacity` calls in a burst, then one more per `refill_every`
+    pub fn rate_limit(mut self, capacity: u32, refill_every: Duration) -> Self {
+        self.capacity = capacity;
+        self.refill_every = refill_every;
+        if let Ok(state) = self.state.get_mut() {
+            state.tokens = capacity;
+        }
+        self
+    }
+    
+    // Open after `failure_threshold` consecutive failures, and try again after `open_for`
+    pub fn circuit_breaker(mut self, failure_threshold: u32, open_for: Duration) -> Self {
+        self.failure_threshold = failure_threshold.max(1);
+        self.open_for = open_for;
+        self
+    }
+    
+    pub fn circuit_state(&self) -> CircuitState {
+        let now = self.clock.now();
+        match self.state.lock() {
+            Ok(mut state) => self.current_circuit(&mut state, now),
+            Err(_) => CircuitState::Open,
+        }
+    }
+    
+    fn current_circuit(&self, state: &mut ResilienceState, now: DateTime<Utc>) -> CircuitState {
+        if state.circuit == CircuitState::Open && now - state.opened_at >= self.open_for {
+            state.circuit = CircuitState::HalfOpen;
+        }
+        state.circuit
+    }
+    
+    fn take_token(&self, state: &mut ResilienceState, now: DateTime<Utc>) -> bool {
+        let interval = self.refill_every.num_milliseconds().max(1);
+        let refills = (now - state.last_refill).num_milliseconds() / interval;
+        if refills > 0 {
+            let added = u32::try_from(refills).unwrap_or(u32::MAX);
+            state.tokens = state.tokens.saturating_add(added).min(self.capacity);
+            state.last_refill += Duration::milliseconds(refills * interval);
+        }
+        if state.tokens == 0 {
+            return false;
+        }
+        state.tokens -= 1;
+        true
+    }
+    
+    // The lock is held across the inner call, so a half-open circuit lets exactly one trial through
+    fn call(&self, channel: &str, send: impl FnOnce(&N) -> Result<bool, String>) -> Result<bool, String> {
+        let now = self.clock.now();
+        let mut state = self.state.lock().map_err(|e| e.to_string())?;
+        if self.current_circuit(&mut state, now) == CircuitState::Open {
+            return Err(format!("Circuit open: {} notifications are paused", channel));
+        }
+        if !self.take_token(&mut state, now) {
+            return Err(format!("Rate limit exceeded for {} notifications", channel));
+        }
+        
+        let result = send(&self.inner);
+        if result.is_ok() {
+            state.circuit = CircuitState::Closed;
+            state.consecutive_failures = 0;
+        } else {
+            state.consecutive_failures += 1;
+            if state.circuit == CircuitState::HalfOpen || state.consecutive_failures >= self.failure_threshold {
+                state.circuit = CircuitState::Open;
+                state.opened_at = now;
+            }
+        }
+        result
+    }
+}
+
+impl<N: NotificationService, K: Clock> NotificationService for ResilientNotificationService<N, K> {
+    fn send_push_notification(&self, username: &str, message: &str) -> Result<bool, String> {
+        self.call("push", |inner| inner.send_push_notification(username, message))
+    }
+    
+    fn send_sms_notification(&self, phone: &PhoneNumber, message: &str) -> Result<bool, String> {
+        self.call("sms", |inner| inner.send_sms_notification(phone, message))
+    }
+}
+
+// Tests for `ResilientNotificationService`, driven by a `ManualClock`
+#[cfg(test)]
+mod resilient_notification_tests {
+    use super::*;
+    use crate::ManualClock;
+    use chrono::Duration;
+    use crate::testing::{test_epoch, MockNotificationService};
+    
+    fn fail(inner: &MockNotificationService, failing: bool) {
+        if failing {
+            inner.spy().fail_all("gateway down");
+        } else {
+            inner.spy().succeed();
+        }
+    }
+    
+    fn calls(inner: &MockNotificationService) -> usize {
+        inner.spy().calls().len()
+    }
+    
+    fn resilient(inner: &MockNotificationService, clock: &ManualClock) -> ResilientNotificationService<MockNotificationService, ManualClock> {
+        ResilientNotificationService::with_clock(inner.clone(), clock.clone())
+            .rate_limit(100, Duration::seconds(1))
+            .circuit_breaker(3, Duration::seconds(30))
+    }
+    
+    fn push(service: &ResilientNotificationService<MockNotificationService, ManualClock>) -> Result<bool, String> {
+        service.send_push_notification("ada", "hello")
+    }
+    
+    #[test]
+    fn consecutive_failures_open_the_circuit() {
+        let (inner, clock) = (MockNotificationService::default(), ManualClock::new(test_epoch()));
+        let service = resilient(&inner, &clock);
+        fail(&inner, true);
+        for _ in 0..2 {
+            assert!(push(&service).is_err());
+            assert_eq!(service.circuit_state(), CircuitState::Closed);
+        }
+        assert!(push(&service).is_err());
+        assert_eq!(service.circuit_state(), CircuitState::Open);
+        
+        assert!(push(&service).unwrap_err().contains("Circuit open"));
+        assert_eq!(calls(&inner), 3, "open circuit must not call the inner service");
+    }
+    
+    #[test]
+    fn a_success_resets_the_failure_count() {
+        let (inner, clock) = (MockNotificationService::default(), ManualClock::new(test_epoch()));
+        let service = resilient(&inner, &clock);
+        fail(&inner, true);
+        push(&service).unwrap_err();
+        push(&service).unwrap_err();
+        fail(&inner, false);
+        push(&service).unwrap();
+        fail(&inner, true);
+        push(&service).unwrap_err();
+        assert_eq!(service.circuit_state(), CircuitState::Closed);
+    }
+    
+    #[test]
+    fn half_open_trial_success_closes_the_circuit() {
+        let (inner, clock) = (MockNotificationService::default(), ManualClock::new(test_epoch()));
+        let service = resilient(&inner, &clock);
+        fail(&inner, true);
+        (0..3).for_each(|_| { push(&service).unwrap_err(); });
+        
+        clock.advance(Duration::seconds(29));
+        assert_eq!(service.circuit_state(), CircuitState::Open);
+        clock.advance(Duration::seconds(1));
+        assert_eq!(service.circuit_state(), CircuitState::HalfOpen);
+        
+        fail(&inner, false);
+        assert_eq!(push(&service), Ok(true));
+        assert_eq!(service.circuit_state(), CircuitState::Closed);
+    }
+    
+    #[test]
+    fn half_open_trial_failure_reopens_the_circuit() {
+        let (inner, clock) = (MockNotificationService::default(), ManualClock::new(test_epoch()));
+        let service = resilient(&inner, &clock);
+        fail(&inner, true);
+        (0..3).for_each(|_| { push(&service).unwrap_err(); });
+        clock.advance(Duration::seconds(30));
+        
+        push(&service).unwrap_err();
+        assert_eq!(service.circuit_state(), CircuitState::Open);
+        clock.advance(Duration::seconds(29));
+        assert_eq!(service.circuit_state(), CircuitState::Open, "cool-down restarts from the failed trial");
+    }
+    
+    #[test]
+    fn bucket_limits_bursts_and_refills_over_time() {
+        let (inner, clock) = (MockNotificationService::default(), ManualClock::new(test_epoch()));
+        let service = resilient(&inner, &clock).rate_limit(2, Duration::seconds(10));
+        push(&service).unwrap();
+        push(&service).unwrap();
+        assert!(push(&service).unwrap_err().contains("Rate limit"));
+        
+        clock.advance(Duration::seconds(10));
+        push(&service).unwrap();
+        assert!(push(&service).is_err());
+        clock.advance(Duration::seconds(60));
+        push(&service).unwrap();
+        push(&service).unwrap();
+        assert!(push(&service).is_err(), "refills are capped at the bucket size");
+        assert_eq!(calls(&inner), 5);
+    }
+    
+    #[test]
+    fn rate_limited_calls_do_not_trip_the_breaker() {
+        let (inner, clock) = (MockNotificationService::default(), ManualClock::new(test_epoch()));
+        let service = resilient(&inner, &clock).rate_limit(1, Duration::seconds(10));
+        push(&service).unwrap();
+        (0..5).for_each(|_| { push(&service).unwrap_err(); });
+        assert_eq!(service.circuit_state(), CircuitState::Closed);
+    }
+}
@@ -0,0 +1,357 @@
+// Side effects recorded for later delivery, and the processor that retries them
+
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use chrono::{DateTime, Duration, Utc};
+use crate::{AnalyticsService, EmailMessage, EmailService, NotificationService};
+
+// A side effect recorded by CorrectUserService for later delivery
+#[derive(Debug, Clone, PartialEq)]
+pub enum SideEffect {
+    Email(EmailMessage),
+    PushNotification { username: String, message: String },
+    AnalyticsEvent { username: String, event: String, metadata: Option<HashMap<String, String>> },
+}
+
+#[derive(Debug, Clone)]
+pub struct OutboxEntry {
+    pub id: u64,
+    pub effect: SideEffect,
+    pub attempts: u32,
+    pub next_attempt_at: DateTime<Utc>,
+    pub last_error: Option<String>,
+}
+
+// Outbox queue - holds side effects until they are delivered or given up on.
+// It only stores entries; delivery and retry decisions belong to OutboxProcessor.
+pub struct OutboxQueue {
+    pending: Mutex<VecDeque<OutboxEntry>>,
+    dead_letters: Mutex<Vec<OutboxEntry>>,
+    next_id: AtomicU64,
+}
+
+impl OutboxQueue {
+    pub fn new() -> Self {
+        OutboxQueue {
+            pending: Mutex::new(VecDeque::new()),
+            dead_letters: Mutex::new(Vec::new()),
+            next_id: AtomicU64::new(1),
+        }
+    }
+    
+    pub fn enqueue(&self, effect: SideEffect) -> Result<u64, String> {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let entry = OutboxEntry {
+            id,
+            effect,
+            attempts: 0,
+            next_attempt_at: Utc::now(),
+            last_error: None,
+        };
+        self.pending.lock().map_err(|e| e.to_string())?.push_back(entry);
+        Ok(id)
+    }
+    
+    pub fn pending_len(&self) -> usize {
+        self.pending.lock().map(|pending| pending.len()).unwrap_or(0)
+    }
+    
+    pub fn dead_letters(&self) -> Vec<OutboxEntry> {
+        self.dead_letters.lock().map(|dead| dead.clone()).unwrap_or_default()
+    }
+    
+    // Removes and returns every entry whose next attempt is due
+    fn take_due(&self, now: DateTime<Utc>) -> Result<Vec<OutboxEntry>, String> {
+        let mut pending = self.pending.lock().map_err(|e| e.to_string())?;
+        let (due, waiting): (VecDeque<_>, VecDeque<_>) = pending.drain(..).partition(|entry| entry.next_attempt_at <= now);
+        *pending = waiting;
+        Ok(due.into())
+    }
+    
+    fn requeue(&self, entry: OutboxEntry) -> Result<(), String> {
+        self.pending.lock().map_err(|e| e.to_string())?.push_back(entry);
+        Ok(())
+    }
+    
+    fn dead_letter(&self, entry: OutboxEntry) -> Result<(), String> {
+        self.dead_letters.lock().map_err(|e| e.to_string())?.push(entry);
+        Ok(())
+    }
+}
+
+impl Default for OutboxQueue {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Retry policy trait - decides how often and how long to wait between attempts
+pub trait RetryPolicy {
+    fn max_attempts(&self) -> u32;
+    fn delay_after(&self, attempt: u32) -> Duration;
+}
+
+// Waits the same delay after every failed attempt
+pub struct FixedBackoff {
+    max_attempts: u32,
+    delay: Duration,
+}
+
+impl FixedBackoff {
+    pub fn new(max_attempts: u32, delay: Duration) -> Self {
+        FixedBackoff { max_attempts, delay }
+    }
+}
+
+impl RetryPolicy for FixedBackoff {
+    fn max_attempts(&self) -> u32 {
+        self.max_attempts
+    }
+    
+    fn delay_after(&self, _attempt: u32) -> Duration {
+        self.delay
+    }
+}
+
+// Doubles the delay after each failed attempt, up to a ceiling
+pub struct ExponentialBackoff {
+    max_attempts: u32,
+    base: Duration,
+    max_delay: Duration,
+}
+
+impl ExponentialBackoff {
+    pub fn new(max_attempts: u32, base: Duration, max_delay: Duration) -> Self {
+        ExponentialBackoff { max_attempts, base, max_delay }
+    }
+}
+
+impl RetryPolicy for ExponentialBackoff {
+    fn max_attempts(&self) -> u32 {
+        self.max_attempts
+    }
+    
+    fn delay_after(&self, attempt: u32) -> Duration {
+        let factor = 2i32.saturating_pow(attempt.saturating_sub(1));
+        (self.base * factor).min(self.max_delay)
+    }
+}
+
+// Outcome of one pass over the outbox
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct OutboxReport {
+    pub delivered: usize,
+    pub retried: usize,
+    pub dead_lettered: usize,
+}
+
+impl fmt::Display for OutboxReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} delivered, {} scheduled for retry, {} dead-lettered", self.delivered, self.retried, self.dead_lettered)
+    }
+}
+
+enum DeliveryFailure {
+    // Worth trying again later, e.g. the SMTP server was unreachable
+    Transient(String),
+    // Can never succeed, e.g. no service is configured for this kind of effect
+    Poison(String),
+}
+
+// Outbox processor - delivers queued side effects through the existing
+// collaborators and applies the retry policy to failures
+pub struct OutboxProcessor<E, A, N, P>
+where
+    E: EmailService,
+    A: AnalyticsService,
+    N: NotificationService,
+    P: RetryPolicy,
+{
+    email_service: E,
+    analytics: A,
+    notification_service: Option<N>,
+    policy: P,
+}
+
+impl<E, A, N, P> OutboxProcessor<E, A, N, P>
+where
+    E: EmailService,
+    A: AnalyticsService,
+    N: NotificationService,
+    P: RetryPolicy,
+{
+    pub fn new(email_service: E, analytics: A, notification_service: Option<N>, policy: P) -> Self {
+        OutboxProcessor {
+            email_service,
+            analytics,
+            notification_service,
+            policy,
+        }
+    }
+    
+    // Attempts every entry that is due at `now`; entries still waiting for
+    // their backoff to elapse are left in the queue
+    pub fn process_due(&self, outbox: &OutboxQueue, now: DateTime<Utc>) -> Result<OutboxReport, String> {
+        let mut report = OutboxReport::default();
+        for mut entry in outbox.take_due(now)? {
+            entry.attempts += 1;
+            match self.deliver(&entry.effect) {
+                Ok(()) => report.delivered += 1,
+                Err(DeliveryFailure::Transient(e)) if entry.attempts < self.policy.max_attempts() => {
+                    entry.next_attempt_at = now + self.policy.delay_after(entry.attempts);
+                    entry.last_error = Some(e);
+                    outbox.requeue(entry)?;
+                    report.retried += 1;
+                }
+                Err(DeliveryFailure::Transient(e)) | Err(DeliveryFailure::Poison(e)) => {
+                    entry.last_error = Some(e);
+                    outbox.dead_letter(entry)?;
+                    report.dead_lettered += 1;
+                }
+            }
+        }
+        Ok(report)
+    }
+    
+    fn deliver(&self, effect: &SideEffect) -> Result<(), DeliveryFailure> {
+        match effect {
+            SideEffect::Email(message) => match self.email_service.send(message) {
+                Ok(true) => Ok(()),
+                Ok(false) => Err(DeliveryFailure::Transient(format!("Email to {} was not accepted", message.to))),
+                Err(e) => Err(DeliveryFailure::Transient(e)),
+            },
+            SideEffect::PushNotification { username, message } => {
+                let Some(ref notification_service) = self.notification_service else {
+                    return Err(DeliveryFailure::Poison("No notification service configured".to_string()));
+                };
+                match notification_service.send_push_notification(username, message) {
+                    Ok(true) => Ok(()),
+                    Ok(false) => Err(DeliveryFailure::Transient(format!("Push notification to {} was not accepted", username))),
+                    Err(e) => Err(DeliveryFailure::Transient(e)),
+                }
+            }
+            SideEffect::AnalyticsEvent { username, event, metadata } => {
+                self.analytics.track_user_event(username, event, metadata.clone());
+                Ok(())
+            }
+        }
+    }
+}
+
+// Tests for OutboxProcessor: retries, exhaustion and poison messages
+#[cfg(test)]
+mod outbox_tests {
+    use super::*;
+    use crate::testing::{MockAnalyticsService, MockEmailService, MockNotificationService};
+    
+    fn welcome_email() -> SideEffect {
+        SideEffect::Email(EmailMessage { to: "ada@example.com".to_string(), subject: "Welcome".to_string(), body: "Hello Ada".to_string() })
+    }
+    
+    fn processor<P: RetryPolicy>(email: &MockEmailService, notifications: Option<MockNotificationService>, policy: P)
+        -> OutboxProcessor<MockEmailService, MockAnalyticsService, MockNotificationService, P> {
+        OutboxProcessor::new(email.clone(), MockAnalyticsService::default(), notifications, policy)
+    }
+    
+    #[test]
+    fn delivered_entries_leave_the_queue() {
+        let email = MockEmailService::default();
+        let outbox = OutboxQueue::new();
+        outbox.enqueue(welcome_email()).unwrap();
+        let report = processor(&email, None, FixedBackoff::new(3, Duration::seconds(10)))
+            .process_due(&outbox, Utc::now())
+            .unwrap();
+        assert_eq!(report, OutboxReport { delivered: 1, retried: 0, dead_lettered: 0 });
+        assert_eq!(outbox.pending_len(), 0);
+        assert_eq!(email.sent().len(), 1);
+    }
+    
+    #[test]
+    fn transient_failure_waits_for_the_backoff_before_retrying() {
+        let email = MockEmailService::default();
+        email.spy().fail_on("send", "SMTP server unreachable");
+        let outbox = OutboxQueue::new();
+        outbox.enqueue(welcome_email()).unwrap();
+        let processor = processor(&email, None, FixedBackoff::new(3, Duration::seconds(10)));
+        let start = Utc::now();
+        
+        assert_eq!(processor.process_due(&outbox, start).unwrap().retried, 1);
+        assert_eq!(processor.process_due(&outbox, start + Duration::seconds(9)).unwrap(), OutboxReport::default());
+        assert_eq!(email.spy().calls_to("send"), 1);
+        
+        email.spy().succeed();
+        let report = processor.process_due(&outbox, start + Duration::seconds(10)).unwrap();
+        assert_eq!(report.delivered, 1);
+        assert_eq!(outbox.pending_len(), 0);
+        assert!(outbox.dead_letters().is_empty());
+    }
+    
+    #[test]
+    fn retries_stop_at_max_attempts_and_the_entry_is_dead_lettered() {
+        let email = MockEmailService::default();
+        email.spy().fail_all("SMTP server unreachable");
+        let outbox = OutboxQueue::new();
+        outbox.enqueue(welcome_email()).unwrap();
+        let processor = processor(&email, None, FixedBackoff::new(3, Duration::seconds(10)));
+        let start = Utc::now();
+        
+        let reports: Vec<_> = (0..5)
+            .map(|pass| processor.process_due(&outbox, start + Duration::seconds(10 * pass)).unwrap())
+            .collect();
+        assert_eq!(reports[0].retried, 1);
+        assert_eq!(reports[1].retried, 1);
+        assert_eq!(reports[2].dead_lettered, 1);
+        assert_eq!(reports[3], OutboxReport::default());
+        assert_eq!(reports[4], OutboxReport::default());
+        
+        assert_eq!(email.spy().calls_to("send"), 3);
+        assert_eq!(outbox.pending_len(), 0);
+        let dead = outbox.dead_letters();
+        assert_eq!(dead.len(), 1);
+        assert_eq!(dead[0].attempts, 3);
+        assert_eq!(dead[0].last_error.as_deref(), Some("SMTP server unreachable"));
+        assert_eq!(dead[0].effect, welcome_email());
+    }
+    
+    #[test]
+    fn poison_message_is_dead_lettered_on_the_first_attempt() {
+        let email = MockEmailService::default();
+        let outbox = OutboxQueue::new();
+        outbox.enqueue(SideEffect::PushNotification { username: "ada".to_string(), message: "Welcome".to_string() }).unwrap();
+        outbox.enqueue(welcome_email()).unwrap();
+        let report = processor(&email, None, FixedBackoff::new(5, Duration::seconds(10)))
+            .process_due(&outbox, Utc::now())
+            .unwrap();
+        
+        assert_eq!(report, OutboxReport { delivered: 1, retried: 0, dead_lettered: 1 });
+        let dead = outbox.dead_letters();
+        assert_eq!(dead.len(), 1);
+        assert_eq!(dead[0].attempts, 1);
+        assert_eq!(dead[0].last_error.as_deref(), Some("No notification service configured"));
+        assert_eq!(outbox.pending_len(), 0);
+    }
+    
+    #[test]
+    fn failing_push_notification_is_transient_when_a_service_is_configured() {
+        let notifications = MockNotificationService::default();
+        notifications.spy().fail_on("send_push_notification", "Firebase unavailable");
+        let outbox = OutboxQueue::new();
+        outbox.enqueue(SideEffect::PushNotification { username: "ada".to_string(), message: "Welcome".to_string() }).unwrap();
+        let report = processor(&MockEmailService::default(), Some(notifications.clone()), FixedBackoff::new(2, Duration::seconds(10)))
+            .process_due(&outbox, Utc::now())
+            .unwrap();
+        assert_eq!(report.retried, 1);
+        assert_eq!(outbox.pending_len(), 1);
+        assert_eq!(notifications.spy().calls_to("send_push_notification"), 1);
+    }
+    
+    #[test]
+    fn exponential_backoff_doubles_up_to_the_ceiling() {
+        let policy = ExponentialBackoff::new(10, Duration::seconds(1), Duration::seconds(30));
+        let delays: Vec<_> = (1..=7).map(|attempt| policy.delay_after(attempt).num_seconds()).collect();
+        assert_eq!(delays, vec![1, 2, 4, 8, 16, 30, 30]);
+        assert_eq!(policy.delay_after(u32::MAX), Duration::seconds(30));
+    }
+}
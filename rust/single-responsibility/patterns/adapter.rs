@@ -0,0 +1,266 @@
+// Adapter: a client with its own API used where the crate expects a NotificationService
+//
+// - LegacyPagerClient stands in for a vendor SDK that cannot be changed: it
+//   addresses numeric pager ids and bare MSISDNs, accepts only short ASCII
+//   text, and reports failure as numbered faults
+// - PagerNotificationAdapter implements NotificationService over it. It maps
+//   usernames to pager ids, E.164 numbers to MSISDNs and messages to pager
+//   text, and translates each fault into the trait's Ok(false) or Err
+//
+// The user service still depends only on NotificationService (DIP), and the
+// vendor's API never leaks past the adapter. Changing vendors means a new
+// adapter, not a change to anything that sends notifications.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use crate::{NotificationService, PhoneNumber};
+
+// Longest text a pager displays
+pub const MAX_PAGE_LENGTH: usize = 80;
+
+// Where a page goes: a pager by its id, or a phone by its MSISDN, the number
+// in international format without the leading "+"
+#[derive(Debug, Clone, PartialEq)]
+pub enum Recipient {
+    Pager(u32),
+    Msisdn(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PageReceipt {
+    pub page_id: u64,
+}
+
+// The vendor's error: a code and a shouted description
+#[derive(Debug, Clone, PartialEq)]
+pub struct PagerFault {
+    pub code: u16,
+    pub text: String,
+}
+
+impl PagerFault {
+    pub const UNAUTHORISED: u16 = 401;
+    pub const UNKNOWN_RECIPIENT: u16 = 404;
+    pub const TEXT_REJECTED: u16 = 413;
+    pub const THROTTLED: u16 = 429;
+    pub const NETWORK_DOWN: u16 = 503;
+    
+    fn new(code: u16, text: &str) -> Self {
+        PagerFault { code, text: text.to_string() }
+    }
+}
+
+impl fmt::Display for PagerFault {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "fault {}: {}", self.code, self.text)
+    }
+}
+
+#[derive(Default)]
+struct PagerNetwork {
+    pagers: Vec<u32>,
+    offline: bool,
+    throttled: bool,
+    pages: Vec<(Recipient, String)>,
+}
+
+// The adaptee. Clones share one simulated network, so a test can keep a
+// handle on what the adapter transmitted.
+#[derive(Clone)]
+pub struct LegacyPagerClient {
+    account: String,
+    network: Arc<Mutex<PagerNetwork>>,
+}
+
+impl LegacyPagerClient {
+    pub fn new(account: &str) -> Self {
+        LegacyPagerClient { account: account.to_string(), network: Arc::new(Mutex::new(PagerNetwork::default())) }
+    }
+    
+    // Pagers that exist on the network; pages to any other id are refused
+    pub fn register_pager(&self, pager_id: u32) {
+        if let Ok(mut network) = self.network.lock() {
+            network.pagers.push(pager_id);
+        }
+    }
+    
+    pub fn set_offline(&self, offline: bool) {
+        if let Ok(mut network) = self.network.lock() {
+            network.offline = offline;
+        }
+    }
+    
+    pub fn set_throttled(&self, throttled: bool) {
+        if let Ok(mut network) = self.network.lock() {
+            network.throttled = throttled;
+        }
+    }
+    
+    // Every page accepted, in order
+    pub fn pages(&self) -> Vec<(Recipient, String)> {
+        self.network.lock().map(|network| network.pages.clone()).unwrap_or_default()
+    }
+    
+    pub fn transmit(&self, recipient: &Recipient, text: &str) -> Result<PageReceipt, PagerFault> {
+        let mut network = self.network.lock().map_err(|_| PagerFault::new(PagerFault::NETWORK_DOWN, "CLIENT STATE LOST"))?;
+        if network.offline {
+            return Err(PagerFault::new(PagerFault::NETWORK_DOWN, "NO CARRIER"));
+        }
+        if self.account.is_empty() {
+            return Err(PagerFault::new(PagerFault::UNAUTHORISED, "ACCOUNT NOT AUTHORISED"));
+        }
+        if network.throttled {
+            return Err(PagerFault::new(PagerFault::THROTTLED, "TOO MANY PAGES"));
+        }
+        if text.is_empty() || text.len() > MAX_PAGE_LENGTH || !text.is_ascii() {
+            return Err(PagerFault::new(PagerFault::TEXT_REJECTED, "TEXT NOT ACCEPTED"));
+        }
+        let known = match recipient {
+            Recipient::Pager(id) => network.pagers.contains(id),
+            Recipient::Msisdn(number) => !number.is_empty() && number.bytes().all(|b| b.is_ascii_digit()),
+        };
+        if !known {
+            return Err(PagerFault::new(PagerFault::UNKNOWN_RECIPIENT, "NO SUCH SUBSCRIBER"));
+        }
+        network.pages.push((recipient.clone(), text.to_string()));
+        Ok(PageReceipt { page_id: network.pages.len() as u64 })
+    }
+}
+
+// The adapter: NotificationService in, LegacyPagerClient out
+pub struct PagerNotificationAdapter {
+    client: LegacyPagerClient,
+    directory: HashMap<String, u32>,
+}
+
+impl PagerNotificationAdapter {
+    pub fn new(client: LegacyPagerClient) -> Self {
+        PagerNotificationAdapter { client, directory: HashMap::new() }
+    }
+    
+    // The pager a user's push notifications go to
+    pub fn with_pager(mut self, username: &str, pager_id: u32) -> Self {
+        self.directory.insert(username.to_string(), pager_id);
+        self
+    }
+    
+    // Pagers show short ASCII text: anything else becomes "?", and long
+    // messages are cut to fit, ending in "..."
+    pub fn page_text(message: &str) -> String {
+        let text: String = message.trim().chars().map(|c| if c.is_ascii() && !c.is_ascii_control() { c } else { '?' }).collect();
+        if text.len() <= MAX_PAGE_LENGTH {
+            return text;
+        }
+        format!("{}...", &text[..MAX_PAGE_LENGTH - 3])
+    }
+    
+    // An unknown recipient is the network's answer, so Ok(false), like a
+    // declined email. Every other fault is an error the caller may retry.
+    fn deliver(&self, recipient: Recipient, message: &str) -> Result<bool, String> {
+        match self.client.transmit(&recipient, &Self::page_text(message)) {
+            Ok(_) => Ok(true),
+            Err(fault) if fault.code == PagerFault::UNKNOWN_RECIPIENT => Ok(false),
+            Err(fault) => Err(match fault.code {
+                PagerFault::UNAUTHORISED => format!("Pager account rejected ({})", fault),
+                PagerFault::THROTTLED | PagerFault::NETWORK_DOWN => format!("Pager network unavailable, try again later ({})", fault),
+                _ => format!("Pager refused the message ({})", fault),
+            }),
+        }
+    }
+}
+
+impl NotificationService for PagerNotificationAdapter {
+    fn send_push_notification(&self, username: &str, message: &str) -> Result<bool, String> {
+        let pager_id = *self.directory.get(username).ok_or_else(|| format!("No pager registered for {}", username))?;
+        self.deliver(Recipient::Pager(pager_id), message)
+    }
+    
+    fn send_sms_notification(&self, phone: &PhoneNumber, message: &str) -> Result<bool, String> {
+        self.deliver(Recipient::Msisdn(phone.as_str().trim_start_matches('+').to_string()), message)
+    }
+}
+
+// Tests for the adapter example, all through NotificationService: how names,
+// numbers and text are mapped for the pager client, and how its faults come back
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::mock_user_service;
+    use crate::{NotificationService, PhoneNumber};
+    
+    // A client with pager 7 on its network, and an adapter sending ada's pushes to it
+    fn pager() -> (LegacyPagerClient, PagerNotificationAdapter) {
+        let client = LegacyPagerClient::new("acme-ops");
+        client.register_pager(7);
+        let adapter = PagerNotificationAdapter::new(client.clone()).with_pager("ada", 7).with_pager("bob", 9);
+        (client, adapter)
+    }
+    
+    #[test]
+    fn push_and_sms_are_mapped_to_pager_recipients() {
+        let (client, adapter) = pager();
+        assert_eq!(adapter.send_push_notification("ada", "Café ☕ at 10"), Ok(true));
+        let phone = PhoneNumber::parse("+1 (415) 555-0132").unwrap();
+        assert_eq!(adapter.send_sms_notification(&phone, "Your code is 123456"), Ok(true));
+        assert_eq!(client.pages(), vec![
+            (Recipient::Pager(7), "Caf? ? at 10".to_string()),
+            (Recipient::Msisdn("14155550132".to_string()), "Your code is 123456".to_string()),
+        ]);
+        
+        assert_eq!(adapter.send_push_notification("grace", "Hi").unwrap_err(), "No pager registered for grace");
+        assert_eq!(client.pages().len(), 2);
+    }
+    
+    #[test]
+    fn long_messages_are_cut_to_fit_a_pager() {
+        let (client, adapter) = pager();
+        adapter.send_push_notification("ada", &"x".repeat(200)).unwrap();
+        let (_, text) = client.pages().pop().unwrap();
+        assert_eq!(text.len(), MAX_PAGE_LENGTH);
+        assert!(text.ends_with("xx..."));
+        assert_eq!(PagerNotificationAdapter::page_text(&"y".repeat(MAX_PAGE_LENGTH)), "y".repeat(MAX_PAGE_LENGTH));
+        assert_eq!(PagerNotificationAdapter::page_text("  two\nlines "), "two?lines");
+    }
+    
+    #[test]
+    fn faults_become_declines_or_errors() {
+        let (client, adapter) = pager();
+        assert_eq!(adapter.send_push_notification("bob", "Hi"), Ok(false), "pager 9 is not on the network");
+        
+        client.set_throttled(true);
+        assert_eq!(adapter.send_push_notification("ada", "Hi").unwrap_err(), "Pager network unavailable, try again later (fault 429: TOO MANY PAGES)");
+        client.set_throttled(false);
+        client.set_offline(true);
+        assert_eq!(adapter.send_push_notification("ada", "Hi").unwrap_err(), "Pager network unavailable, try again later (fault 503: NO CARRIER)");
+        client.set_offline(false);
+        assert_eq!(adapter.send_push_notification("ada", "Hi"), Ok(true));
+        
+        let unauthorised = PagerNotificationAdapter::new(LegacyPagerClient::new("")).with_pager("ada", 7);
+        assert_eq!(unauthorised.send_push_notification("ada", "Hi").unwrap_err(), "Pager account rejected (fault 401: ACCOUNT NOT AUTHORISED)");
+    }
+    
+    #[test]
+    fn user_service_notifies_through_the_adapter() {
+        let (client, adapter) = pager();
+        let (service, mocks) = mock_user_service();
+        let service = service.with_notification_service(Some(adapter.with_pager("ada_l", 7)));
+        let mut user = service.create_user("ada_l".to_string(), "ada@example.com".to_string()).unwrap();
+        service.update_profile(&mut user, Some("+44 20 7946 0958"), None, None).unwrap();
+        service.send_sms(&user, "Your verification code is 123456").unwrap();
+        assert_eq!(client.pages(), vec![
+            (Recipient::Pager(7), "Welcome! Your account has been created.".to_string()),
+            (Recipient::Msisdn("442079460958".to_string()), "Your verification code is 123456".to_string()),
+        ]);
+        
+        // Adapter errors reach the caller as the trait's errors: logged by
+        // registration, which still succeeds, and returned by send_sms
+        service.create_user("grace_h".to_string(), "grace@example.com".to_string()).unwrap();
+        assert_eq!(mocks.logger.errors(), vec![(
+            "Failed to send push notification".to_string(),
+            "No pager registered for grace_h".to_string(),
+        )]);
+        client.set_offline(true);
+        assert!(service.send_sms(&user, "Hello").unwrap_err().starts_with("Pager network unavailable"));
+    }
+}
@@ -0,0 +1,419 @@
+// CQRS: the user service split into a command side and a query side
+//
+// - UserCommandHandler takes CreateUser, UpdateUser and DeleteUser commands.
+//   It validates, persists, appends the resulting DomainEvent to an EventStore
+//   and publishes it on the same EventBus as EventDrivenUserService, so the
+//   existing subscribers keep working. Commands answer with a user id, never
+//   with data to display.
+// - UserProjection is the query side: a read model of UserViews, indexed for
+//   the lookups callers make, and rebuilt from the EventStore at any time.
+//
+// The projection subscribes to the bus, but treats an event only as a signal
+// that the store has grown. It reads what it has not yet applied from the
+// store, by sequence number, so a missed, repeated or late notification
+// cannot skew it, and a replay always ends in the same state.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, RwLock};
+use crate::{Clock, DomainEvent, EventBus, EventSubscriber, IdGenerator, SystemClock, SystemIdGenerator, User, UserId, UserRepository, UserStatus, UserValidator};
+
+// Requests to change state; none of them returns a user
+#[derive(Debug, Clone, PartialEq)]
+pub enum UserCommand {
+    CreateUser { username: String, email: String },
+    UpdateUser { user_id: UserId, username: String, email: String },
+    DeleteUser { user_id: UserId },
+}
+
+// An event as stored: its position in the log, starting at 1
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecordedEvent {
+    pub sequence: u64,
+    pub event: DomainEvent,
+}
+
+// Append-only log of every event the command side has emitted
+#[derive(Default)]
+pub struct EventStore {
+    events: RwLock<Vec<DomainEvent>>,
+}
+
+impl EventStore {
+    pub fn new() -> Self {
+        EventStore { events: RwLock::new(Vec::new()) }
+    }
+    
+    // Returns the sequence number given to the event
+    pub fn append(&self, event: DomainEvent) -> Result<u64, String> {
+        let mut events = self.events.write().map_err(|e| e.to_string())?;
+        events.push(event);
+        Ok(events.len() as u64)
+    }
+    
+    // Events after `sequence`, oldest first; `since(0)` is the whole log
+    pub fn since(&self, sequence: u64) -> Result<Vec<RecordedEvent>, String> {
+        let events = self.events.read().map_err(|e| e.to_string())?;
+        Ok(events
+            .iter()
+            .enumerate()
+            .skip(sequence as usize)
+            .map(|(i, event)| RecordedEvent { sequence: i as u64 + 1, event: event.clone() })
+            .collect())
+    }
+    
+    pub fn len(&self) -> Result<u64, String> {
+        Ok(self.events.read().map_err(|e| e.to_string())?.len() as u64)
+    }
+    
+    pub fn is_empty(&self) -> Result<bool, String> {
+        Ok(self.len()? == 0)
+    }
+}
+
+// Command side: the write path of EventDrivenUserService, addressed by id
+pub struct UserCommandHandler<R, V>
+where
+    R: UserRepository,
+    V: UserValidator,
+{
+    user_repo: R,
+    validator: V,
+    store: Arc<EventStore>,
+    events: EventBus,
+    clock: Arc<dyn Clock>,
+    ids: Arc<dyn IdGenerator>,
+}
+
+impl<R, V> UserCommandHandler<R, V>
+where
+    R: UserRepository,
+    V: UserValidator,
+{
+    pub fn new(user_repo: R, validator: V, store: Arc<EventStore>, events: EventBus) -> Self {
+        UserCommandHandler {
+            user_repo,
+            validator,
+            store,
+            events,
+            clock: Arc::new(SystemClock),
+            ids: Arc::new(SystemIdGenerator),
+        }
+    }
+    
+    pub fn with_clock(mut self, clock: impl Clock + 'static) -> Self {
+        self.clock = Arc::new(clock);
+        self
+    }
+    
+    pub fn with_id_generator(mut self, ids: impl IdGenerator + 'static) -> Self {
+        self.ids = Arc::new(ids);
+        self
+    }
+    
+    pub fn store(&self) -> &Arc<EventStore> {
+        &self.store
+    }
+    
+    // A rejected command changes nothing and emits no event
+    pub fn handle(&self, command: UserCommand) -> Result<UserId, String> {
+        let event = match command {
+            UserCommand::CreateUser { username, email } => self.create(username, email)?,
+            UserCommand::UpdateUser { user_id, username, email } => self.update(user_id, username, email)?,
+            UserCommand::DeleteUser { user_id } => self.delete(user_id)?,
+        };
+        let user_id = event.user_id();
+        self.store.append(event.clone())?;
+        for error in self.events.publish(&event) {
+            eprintln!("Subscriber failed for {:?}: {}", event, error);
+        }
+        Ok(user_id)
+    }
+    
+    fn create(&self, username: String, email: String) -> Result<DomainEvent, String> {
+        let username = self.validator.normalize_username(&username);
+        let user = User::create(self.ids.next_user_id(), username.clone(), email.clone(), self.clock.now());
+        let report = self.validator.validate_user(&user);
+        if !report.is_valid() {
+            return Err(format!("Invalid user data: {}", report));
+        }
+        self.user_repo.save(&user)?;
+        Ok(DomainEvent::UserCreated { user_id: user.id(), username, email })
+    }
+    
+    fn update(&self, user_id: UserId, username: String, email: String) -> Result<DomainEvent, String> {
+        let username = self.validator.normalize_username(&username);
+        let mut user = self.load(user_id)?;
+        user.set_username(username.clone());
+        user.set_email(email.clone());
+        user.touch(self.clock.now());
+        let report = self.validator.validate_user(&user);
+        if !report.is_valid() {
+            return Err(format!("Invalid user data: {}", report));
+        }
+        if !self.user_repo.update(&user)? {
+            return Err(format!("User not found: {}", user_id));
+        }
+        Ok(DomainEvent::UserUpdated { user_id, username, email })
+    }
+    
+    // Soft delete, as in EventDrivenUserService::delete_user
+    fn delete(&self, user_id: UserId) -> Result<DomainEvent, String> {
+        let mut user = self.load(user_id)?;
+        user.transition_to(UserStatus::Deleted)?;
+        user.touch(self.clock.now());
+        if !self.user_repo.update(&user)? {
+            return Err(format!("User not found: {}", user_id));
+        }
+        Ok(DomainEvent::UserDeleted { user_id, username: user.username().to_string() })
+    }
+    
+    fn load(&self, user_id: UserId) -> Result<User, String> {
+        match self.user_repo.find_by_id(&user_id.to_string())? {
+            Some(user) if user.status() != UserStatus::Deleted => Ok(user),
+            _ => Err(format!("User not found: {}", user_id)),
+        }
+    }
+}
+
+// What the query side serves: flat, ready to display, no behaviour.
+// `version` counts the events applied to this user.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UserView {
+    pub user_id: UserId,
+    pub username: String,
+    pub email: String,
+    pub version: u64,
+}
+
+#[derive(Default)]
+struct ReadModel {
+    views: BTreeMap<UserId, UserView>,
+    by_username: HashMap<String, UserId>,
+    position: u64,
+}
+
+impl ReadModel {
+    fn apply(&mut self, recorded: &RecordedEvent) {
+        match &recorded.event {
+            DomainEvent::UserCreated { user_id, username, email } => {
+                let view = UserView { user_id: *user_id, username: username.clone(), email: email.clone(), version: 1 };
+                self.by_username.insert(username.clone(), *user_id);
+                self.views.insert(*user_id, view);
+            }
+            DomainEvent::UserUpdated { user_id, username, email } => {
+                if let Some(view) = self.views.get_mut(user_id) {
+                    self.by_username.remove(&view.username);
+                    self.by_username.insert(username.clone(), *user_id);
+                    view.username = username.clone();
+                    view.email = email.clone();
+                    view.version += 1;
+                }
+            }
+            DomainEvent::UserDeleted { user_id, .. } => {
+                if let Some(view) = self.views.remove(user_id) {
+                    self.by_username.remove(&view.username);
+                }
+            }
+        }
+        self.position = recorded.sequence;
+    }
+}
+
+// Query side: the read model, kept up to date from the EventStore
+pub struct UserProjection {
+    store: Arc<EventStore>,
+    model: RwLock<ReadModel>,
+}
+
+impl UserProjection {
+    // Starts empty; call catch_up, or let the first notification do it
+    pub fn new(store: Arc<EventStore>) -> Self {
+        UserProjection { store, model: RwLock::new(ReadModel::default()) }
+    }
+    
+    // Applies every stored event past the projection's position, once.
+    // Returns how many were applied.
+    pub fn catch_up(&self) -> Result<usize, String> {
+        let mut model = self.model.write().map_err(|e| e.to_string())?;
+        let pending = self.store.since(model.position)?;
+        for recorded in &pending {
+            model.apply(recorded);
+        }
+        Ok(pending.len())
+    }
+    
+    // Throws the read model away and replays the whole store
+    pub fn rebuild(&self) -> Result<usize, String> {
+        *self.model.write().map_err(|e| e.to_string())? = ReadModel::default();
+        self.catch_up()
+    }
+    
+    // Sequence number of the last event applied
+    pub fn position(&self) -> Result<u64, String> {
+        Ok(self.model.read().map_err(|e| e.to_string())?.position)
+    }
+    
+    pub fn find(&self, user_id: UserId) -> Result<Option<UserView>, String> {
+        Ok(self.model.read().map_err(|e| e.to_string())?.views.get(&user_id).cloned())
+    }
+    
+    pub fn find_by_username(&self, username: &str) -> Result<Option<UserView>, String> {
+        let model = self.model.read().map_err(|e| e.to_string())?;
+        Ok(model.by_username.get(username).and_then(|user_id| model.views.get(user_id)).cloned())
+    }
+    
+    // Every user not deleted, in id order
+    pub fn all(&self) -> Result<Vec<UserView>, String> {
+        Ok(self.model.read().map_err(|e| e.to_string())?.views.values().cloned().collect())
+    }
+}
+
+// The event itself is not applied: it only says the store has something new
+impl EventSubscriber for UserProjection {
+    fn handle(&self, _event: &DomainEvent) -> Result<(), String> {
+        self.catch_up().map(|_| ())
+    }
+}
+
+// Wires a command handler and a projection over one store. Extra subscribers
+// (logging, analytics, ...) go on `events` as for EventDrivenUserService.
+pub fn create_cqrs_user_service<R, V>(user_repo: R, validator: V, mut events: EventBus) -> (UserCommandHandler<R, V>, Arc<UserProjection>)
+where
+    R: UserRepository,
+    V: UserValidator,
+{
+    let store = Arc::new(EventStore::new());
+    let projection = Arc::new(UserProjection::new(store.clone()));
+    events.subscribe(projection.clone());
+    (UserCommandHandler::new(user_repo, validator, store, events), projection)
+}
+
+// Tests for the CQRS example: commands reach the read model through events,
+// and replaying the event store always ends in the same views
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use crate::testing::{MockUserRepository, Mocks};
+    use crate::{ActivityLogSubscriber, DefaultUserValidator, EventBus, EventSubscriber, UserId, UserRepository};
+    
+    type Handler = UserCommandHandler<MockUserRepository, DefaultUserValidator>;
+    
+    fn cqrs() -> (Handler, Arc<UserProjection>, Mocks) {
+        let mocks = Mocks::default();
+        let mut events = EventBus::new();
+        events.subscribe(ActivityLogSubscriber::new(mocks.logger.clone()));
+        let (commands, projection) = create_cqrs_user_service(mocks.repository.clone(), DefaultUserValidator::new(), events);
+        let commands = commands.with_clock(mocks.clock.clone()).with_id_generator(mocks.ids.clone());
+        (commands, projection, mocks)
+    }
+    
+    fn create(commands: &Handler, username: &str) -> UserId {
+        commands.handle(UserCommand::CreateUser { username: username.to_string(), email: format!("{}@example.com", username) }).unwrap()
+    }
+    
+    // Creates three users, renames one twice and deletes another
+    fn history(commands: &Handler) -> (UserId, UserId, UserId) {
+        let ada = create(commands, "ada_l");
+        let grace = create(commands, "grace_h");
+        let alan = create(commands, "alan_t");
+        for username in ["ada_lovelace", "countess"] {
+            commands.handle(UserCommand::UpdateUser { user_id: ada, username: username.to_string(), email: "ada@example.com".to_string() }).unwrap();
+        }
+        commands.handle(UserCommand::DeleteUser { user_id: grace }).unwrap();
+        (ada, grace, alan)
+    }
+    
+    fn views(projection: &UserProjection) -> Vec<UserView> {
+        projection.all().unwrap()
+    }
+    
+    #[test]
+    fn commands_reach_the_read_model_through_the_bus() {
+        let (commands, projection, mocks) = cqrs();
+        let (ada, grace, alan) = history(&commands);
+        
+        assert_eq!(projection.find(ada).unwrap(), Some(UserView { user_id: ada, username: "countess".to_string(), email: "ada@example.com".to_string(), version: 3 }));
+        assert_eq!(projection.find_by_username("countess").unwrap().map(|view| view.user_id), Some(ada));
+        assert_eq!(projection.find_by_username("ada_l").unwrap(), None, "old usernames leave the index");
+        assert_eq!(projection.find(grace).unwrap(), None);
+        assert_eq!(views(&projection).iter().map(|view| view.user_id).collect::<Vec<_>>(), vec![ada, alan]);
+        assert_eq!(projection.position().unwrap(), 6);
+        assert_eq!(mocks.logger.activities().len(), 6, "the existing subscribers still see every event");
+    }
+    
+    #[test]
+    fn rejected_commands_emit_no_events() {
+        let (commands, projection, _mocks) = cqrs();
+        let (ada, grace, _) = history(&commands);
+        let rejected = [
+            UserCommand::CreateUser { username: "x!".to_string(), email: "not-an-email".to_string() },
+            UserCommand::UpdateUser { user_id: ada, username: "countess".to_string(), email: "not-an-email".to_string() },
+            UserCommand::UpdateUser { user_id: grace, username: "grace".to_string(), email: "grace@example.com".to_string() },
+            UserCommand::DeleteUser { user_id: grace },
+            UserCommand::DeleteUser { user_id: "user-99".parse().unwrap() },
+        ];
+        for command in rejected {
+            assert!(commands.handle(command.clone()).is_err(), "{:?} was accepted", command);
+        }
+        assert_eq!(commands.store().len().unwrap(), 6);
+        assert_eq!(projection.position().unwrap(), 6);
+        assert_eq!(projection.find(ada).unwrap().unwrap().email, "ada@example.com");
+    }
+    
+    #[test]
+    fn rebuilding_replays_to_the_same_views() {
+        let (commands, projection, _mocks) = cqrs();
+        history(&commands);
+        let live = views(&projection);
+        
+        assert_eq!(projection.rebuild().unwrap(), 6);
+        assert_eq!(views(&projection), live);
+        assert_eq!(projection.rebuild().unwrap(), 6);
+        assert_eq!(views(&projection), live);
+        
+        // A projection built later, over the same store, agrees too
+        let late = UserProjection::new(commands.store().clone());
+        assert_eq!(late.catch_up().unwrap(), 6);
+        assert_eq!(views(&late), live);
+    }
+    
+    #[test]
+    fn repeated_and_missed_notifications_do_not_skew_the_views() {
+        let (commands, projection, _mocks) = cqrs();
+        let (ada, _, _) = history(&commands);
+        let live = views(&projection);
+        
+        for recorded in commands.store().since(0).unwrap() {
+            projection.handle(&recorded.event).unwrap();
+        }
+        assert_eq!(projection.catch_up().unwrap(), 0);
+        assert_eq!(views(&projection), live, "versions are not bumped twice");
+        
+        // An event appended without a notification is picked up by the next one
+        let unnotified = UserProjection::new(commands.store().clone());
+        unnotified.catch_up().unwrap();
+        commands.store().append(crate::DomainEvent::UserUpdated { user_id: ada, username: "ada".to_string(), email: "ada@example.com".to_string() }).unwrap();
+        create(&commands, "barbara_l");
+        assert_eq!(projection.position().unwrap(), 8);
+        assert_eq!(projection.find(ada).unwrap().unwrap().version, 4);
+        assert_eq!(unnotified.catch_up().unwrap(), 2);
+        assert_eq!(views(&unnotified), views(&projection));
+    }
+    
+    #[test]
+    fn replayed_views_agree_with_the_repository() {
+        let (commands, projection, mocks) = cqrs();
+        history(&commands);
+        projection.rebuild().unwrap();
+        
+        let mut stored: Vec<_> = mocks.repository.find_all().unwrap().into_iter()
+            .filter(|user| user.status() != crate::UserStatus::Deleted)
+            .map(|user| (user.id(), user.username().to_string(), user.email().to_string()))
+            .collect();
+        stored.sort();
+        let viewed: Vec<_> = views(&projection).into_iter().map(|view| (view.user_id, view.username, view.email)).collect();
+        assert_eq!(viewed, stored);
+        assert!(EventStore::new().is_empty().unwrap());
+    }
+}
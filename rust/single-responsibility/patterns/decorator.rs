@@ -0,0 +1,382 @@
+// Decorator: behaviour added around an EmailService by wrapping it in another
+//
+// - RetryingEmailService sends again when the inner service returns an error
+// - RateLimitedEmailService refuses sends over a token bucket's limit
+// - AuditingEmailService records every send it sees and how it ended
+//
+// Each decorator is generic over the service it wraps and is an EmailService
+// itself, so any of them can wrap any other, in any order. The order is part of
+// the behaviour: a retry inside the rate limiter spends a token per attempt, and
+// an audit inside the retry records each attempt rather than each message.
+// `compare_stacks` runs the same messages through four orders of the same three.
+
+use std::fmt;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+use chrono::{DateTime, Duration, Utc};
+use crate::{Clock, EmailMessage, EmailService, ManualClock, SystemClock};
+
+// Sends again after an error, up to `max_attempts` sends in all. A declined
+// message (Ok(false)) is the server's answer, not a failure, so it is not retried.
+pub struct RetryingEmailService<E: EmailService> {
+    inner: E,
+    max_attempts: u32,
+}
+
+impl<E: EmailService> RetryingEmailService<E> {
+    pub fn new(inner: E, max_attempts: u32) -> Self {
+        RetryingEmailService { inner, max_attempts: max_attempts.max(1) }
+    }
+}
+
+impl<E: EmailService> EmailService for RetryingEmailService<E> {
+    fn send(&self, message: &EmailMessage) -> Result<bool, String> {
+        let mut attempt = 1;
+        loop {
+            match self.inner.send(message) {
+                Err(_) if attempt < self.max_attempts => attempt += 1,
+                result => return result,
+            }
+        }
+    }
+}
+
+struct TokenBucket {
+    tokens: u32,
+    last_refill: DateTime<Utc>,
+}
+
+// Allows bursts of `capacity` sends, then one more per `refill_every`. A send
+// over the limit returns an error without reaching the inner service.
+pub struct RateLimitedEmailService<E: EmailService, K: Clock = SystemClock> {
+    inner: E,
+    clock: K,
+    capacity: u32,
+    refill_every: Duration,
+    bucket: Mutex<TokenBucket>,
+}
+
+impl<E: EmailService> RateLimitedEmailService<E, SystemClock> {
+    pub fn new(inner: E, capacity: u32, refill_every: Duration) -> Self {
+        Self::with_clock(inner, capacity, refill_every, SystemClock)
+    }
+}
+
+impl<E: EmailService, K: Clock> RateLimitedEmailService<E, K> {
+    pub fn with_clock(inner: E, capacity: u32, refill_every: Duration, clock: K) -> Self {
+        let bucket = Mutex::new(TokenBucket { tokens: capacity, last_refill: clock.now() });
+        RateLimitedEmailService { inner, clock, capacity, refill_every, bucket }
+    }
+    
+    fn take_token(&self) -> Result<bool, String> {
+        let now = self.clock.now();
+        let mut bucket = self.bucket.lock().map_err(|e| e.to_string())?;
+        let interval = self.refill_every.num_milliseconds().max(1);
+        let refills = (now - bucket.last_refill).num_milliseconds() / interval;
+        if refills > 0 {
+            let added = u32::try_from(refills).unwrap_or(u32::MAX);
+            bucket.tokens = bucket.tokens.saturating_add(added).min(self.capacity);
+            bucket.last_refill += Duration::milliseconds(refills * interval);
+        }
+        if bucket.tokens == 0 {
+            return Ok(false);
+        }
+        bucket.tokens -= 1;
+        Ok(true)
+    }
+}
+
+impl<E: EmailService, K: Clock> EmailService for RateLimitedEmailService<E, K> {
+    fn send(&self, message: &EmailMessage) -> Result<bool, String> {
+        if !self.take_token()? {
+            return Err(format!("Rate limit exceeded for email to {}", message.to));
+        }
+        self.inner.send(message)
+    }
+}
+
+// How one send ended, as seen by the auditing decorator
+#[derive(Debug, Clone, PartialEq)]
+pub enum EmailOutcome {
+    Sent,
+    Declined,
+    Failed(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct EmailAuditRecord {
+    pub to: String,
+    pub subject: String,
+    pub outcome: EmailOutcome,
+}
+
+// Shared by clones, so a caller keeps one handle and reads what a decorator
+// deep inside a stack recorded
+#[derive(Clone, Default)]
+pub struct EmailAuditLog {
+    records: Arc<Mutex<Vec<EmailAuditRecord>>>,
+}
+
+impl EmailAuditLog {
+    pub fn new() -> Self {
+        EmailAuditLog::default()
+    }
+    
+    pub fn records(&self) -> Vec<EmailAuditRecord> {
+        self.records.lock().map(|records| records.clone()).unwrap_or_default()
+    }
+    
+    pub fn len(&self) -> usize {
+        self.records.lock().map(|records| records.len()).unwrap_or(0)
+    }
+    
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    
+    fn record(&self, record: EmailAuditRecord) {
+        if let Ok(mut records) = self.records.lock() {
+            records.push(record);
+        }
+    }
+}
+
+// Records every send that reaches it, then passes the result on unchanged
+pub struct AuditingEmailService<E: EmailService> {
+    inner: E,
+    log: EmailAuditLog,
+}
+
+impl<E: EmailService> AuditingEmailService<E> {
+    pub fn new(inner: E, log: EmailAuditLog) -> Self {
+        AuditingEmailService { inner, log }
+    }
+}
+
+impl<E: EmailService> EmailService for AuditingEmailService<E> {
+    fn send(&self, message: &EmailMessage) -> Result<bool, String> {
+        let result = self.inner.send(message);
+        let outcome = match &result {
+            Ok(true) => EmailOutcome::Sent,
+            Ok(false) => EmailOutcome::Declined,
+            Err(e) => EmailOutcome::Failed(e.clone()),
+        };
+        self.log.record(EmailAuditRecord { to: message.to.clone(), subject: message.subject.clone(), outcome });
+        result
+    }
+}
+
+// A server for the comparison: every odd-numbered call fails, so each message
+// is delivered on its second attempt if it gets one
+#[derive(Clone, Default)]
+struct FlakyServer {
+    calls: Arc<AtomicU32>,
+    delivered: Arc<AtomicU32>,
+}
+
+impl EmailService for FlakyServer {
+    fn send(&self, _message: &EmailMessage) -> Result<bool, String> {
+        if self.calls.fetch_add(1, Ordering::SeqCst).is_multiple_of(2) {
+            return Err("SMTP server unavailable".to_string());
+        }
+        self.delivered.fetch_add(1, Ordering::SeqCst);
+        Ok(true)
+    }
+}
+
+// What one stack did with the comparison's messages
+#[derive(Debug, Clone, PartialEq)]
+pub struct StackReport {
+    pub stack: &'static str,
+    pub delivered: u32,
+    pub server_calls: u32,
+    pub audit_records: usize,
+}
+
+impl fmt::Display for StackReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:<30} {:>9} {:>12} {:>13}", self.stack, self.delivered, self.server_calls, self.audit_records)
+    }
+}
+
+const COMPARED_MESSAGES: u32 = 3;
+const RETRY_ATTEMPTS: u32 = 2;
+const BURST: u32 = 3;
+
+fn run_stack(stack: &'static str, build: impl FnOnce(FlakyServer, EmailAuditLog, ManualClock) -> Box<dyn EmailService>) -> StackReport {
+    let (server, log) = (FlakyServer::default(), EmailAuditLog::new());
+    let service = build(server.clone(), log.clone(), ManualClock::new(Utc::now()));
+    for i in 1..=COMPARED_MESSAGES {
+        let message = EmailMessage {
+            to: format!("user{}@example.com", i),
+            subject: "Welcome!".to_string(),
+            body: "Thanks for signing up.".to_string(),
+        };
+        let _ = service.send(&message);
+    }
+    StackReport {
+        stack,
+        delivered: server.delivered.load(Ordering::SeqCst),
+        server_calls: server.calls.load(Ordering::SeqCst),
+        audit_records: log.len(),
+    }
+}
+
+fn limit<E: EmailService>(inner: E, clock: ManualClock) -> RateLimitedEmailService<E, ManualClock> {
+    RateLimitedEmailService::with_clock(inner, BURST, Duration::minutes(1), clock)
+}
+
+// The same three decorators in four orders, outermost first: three messages to
+// a server that fails every other call, at most two attempts per message and a
+// burst of three sends, with no time passing
+pub fn compare_stacks() -> Vec<StackReport> {
+    vec![
+        run_stack("audit > retry > rate limit", |server, log, clock| {
+            Box::new(AuditingEmailService::new(RetryingEmailService::new(limit(server, clock), RETRY_ATTEMPTS), log))
+        }),
+        run_stack("audit > rate limit > retry", |server, log, clock| {
+            Box::new(AuditingEmailService::new(limit(RetryingEmailService::new(server, RETRY_ATTEMPTS), clock), log))
+        }),
+        run_stack("retry > audit > rate limit", |server, log, clock| {
+            Box::new(RetryingEmailService::new(AuditingEmailService::new(limit(server, clock), log), RETRY_ATTEMPTS))
+        }),
+        run_stack("rate limit > retry > audit", |server, log, clock| {
+            Box::new(limit(RetryingEmailService::new(AuditingEmailService::new(server, log), RETRY_ATTEMPTS), clock))
+        }),
+    ]
+}
+
+// Example usage: prints the comparison as a table
+pub fn demonstrate_decorator_stacks() {
+    println!("{} messages, {} attempts each, a burst of {} sends", COMPARED_MESSAGES, RETRY_ATTEMPTS, BURST);
+    println!("{:<30} {:>9} {:>12} {:>13}", "stack (outermost first)", "delivered", "server calls", "audit records");
+    for report in compare_stacks() {
+        println!("{}", report);
+    }
+}
+
+// Tests for the decorator example: each decorator alone, then the same
+// decorators stacked in different orders giving different results
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+    use crate::testing::{test_epoch, MockEmailService};
+    use crate::{EmailMessage, EmailService, ManualClock};
+    
+    fn welcome(to: &str) -> EmailMessage {
+        EmailMessage { to: to.to_string(), subject: "Welcome!".to_string(), body: "Hello".to_string() }
+    }
+    
+    fn failing() -> MockEmailService {
+        let server = MockEmailService::default();
+        server.spy().fail_all("SMTP server unavailable");
+        server
+    }
+    
+    struct DecliningServer;
+    
+    impl EmailService for DecliningServer {
+        fn send(&self, _message: &EmailMessage) -> Result<bool, String> {
+            Ok(false)
+        }
+    }
+    
+    #[test]
+    fn each_decorator_passes_results_through() {
+        let server = failing();
+        let retrying = RetryingEmailService::new(server.clone(), 3);
+        assert_eq!(retrying.send(&welcome("ada@example.com")).unwrap_err(), "SMTP server unavailable");
+        assert_eq!(server.spy().calls_to("send"), 3);
+        server.spy().succeed();
+        assert_eq!(retrying.send(&welcome("ada@example.com")), Ok(true));
+        assert_eq!(server.spy().calls_to("send"), 4);
+        
+        let declining = RetryingEmailService::new(DecliningServer, 3);
+        assert_eq!(declining.send(&welcome("ada@example.com")), Ok(false), "a declined message is not retried");
+        
+        let log = EmailAuditLog::new();
+        let audited = AuditingEmailService::new(DecliningServer, log.clone());
+        audited.send(&welcome("ada@example.com")).unwrap();
+        assert_eq!(log.records(), vec![EmailAuditRecord {
+            to: "ada@example.com".to_string(),
+            subject: "Welcome!".to_string(),
+            outcome: EmailOutcome::Declined,
+        }]);
+    }
+    
+    #[test]
+    fn rate_limit_refills_with_the_clock() {
+        let (server, clock) = (MockEmailService::default(), ManualClock::new(test_epoch()));
+        let limited = RateLimitedEmailService::with_clock(server.clone(), 2, Duration::seconds(30), clock.clone());
+        assert!(limited.send(&welcome("a@example.com")).unwrap());
+        assert!(limited.send(&welcome("b@example.com")).unwrap());
+        assert_eq!(limited.send(&welcome("c@example.com")).unwrap_err(), "Rate limit exceeded for email to c@example.com");
+        assert_eq!(server.spy().calls_to("send"), 2, "a refused send never reaches the server");
+        
+        clock.advance(Duration::seconds(30));
+        assert!(limited.send(&welcome("c@example.com")).unwrap());
+        assert!(limited.send(&welcome("d@example.com")).is_err());
+    }
+    
+    #[test]
+    fn audit_outside_retry_records_messages_and_inside_records_attempts() {
+        let (server, log) = (failing(), EmailAuditLog::new());
+        let outside = AuditingEmailService::new(RetryingEmailService::new(server.clone(), 3), log.clone());
+        outside.send(&welcome("ada@example.com")).unwrap_err();
+        assert_eq!(log.len(), 1);
+        
+        let (server, log) = (failing(), EmailAuditLog::new());
+        let inside = RetryingEmailService::new(AuditingEmailService::new(server.clone(), log.clone()), 3);
+        inside.send(&welcome("ada@example.com")).unwrap_err();
+        assert_eq!(log.len(), 3);
+        assert!(log.records().iter().all(|record| record.outcome == EmailOutcome::Failed("SMTP server unavailable".to_string())));
+    }
+    
+    #[test]
+    fn retry_inside_rate_limit_spends_one_token_per_message() {
+        let (server, clock) = (failing(), ManualClock::new(test_epoch()));
+        let outside = RateLimitedEmailService::with_clock(RetryingEmailService::new(server.clone(), 3), 2, Duration::minutes(1), clock.clone());
+        outside.send(&welcome("a@example.com")).unwrap_err();
+        outside.send(&welcome("b@example.com")).unwrap_err();
+        assert_eq!(server.spy().calls_to("send"), 6, "two tokens bought three attempts each");
+        assert!(outside.send(&welcome("c@example.com")).unwrap_err().starts_with("Rate limit exceeded"));
+        
+        // Inside the limiter each attempt costs a token, and the retry spends
+        // its last attempt on the limiter's refusal
+        let server = failing();
+        let inside = RetryingEmailService::new(RateLimitedEmailService::with_clock(server.clone(), 2, Duration::minutes(1), clock.clone()), 3);
+        assert!(inside.send(&welcome("a@example.com")).unwrap_err().starts_with("Rate limit exceeded"));
+        assert_eq!(server.spy().calls_to("send"), 2);
+    }
+    
+    #[test]
+    fn audit_inside_rate_limit_misses_refused_sends() {
+        let clock = ManualClock::new(test_epoch());
+        let (server, log) = (MockEmailService::default(), EmailAuditLog::new());
+        let outside = AuditingEmailService::new(RateLimitedEmailService::with_clock(server, 1, Duration::minutes(1), clock.clone()), log.clone());
+        outside.send(&welcome("a@example.com")).unwrap();
+        outside.send(&welcome("b@example.com")).unwrap_err();
+        let outcomes: Vec<_> = log.records().into_iter().map(|record| record.outcome).collect();
+        assert_eq!(outcomes, vec![EmailOutcome::Sent, EmailOutcome::Failed("Rate limit exceeded for email to b@example.com".to_string())]);
+        
+        let (server, log) = (MockEmailService::default(), EmailAuditLog::new());
+        let inside = RateLimitedEmailService::with_clock(AuditingEmailService::new(server, log.clone()), 1, Duration::minutes(1), clock);
+        inside.send(&welcome("a@example.com")).unwrap();
+        inside.send(&welcome("b@example.com")).unwrap_err();
+        assert_eq!(log.len(), 1);
+    }
+    
+    #[test]
+    fn compared_stacks_differ_only_by_order() {
+        let counts: Vec<_> = compare_stacks().into_iter()
+            .map(|report| (report.stack, report.delivered, report.server_calls, report.audit_records))
+            .collect();
+        assert_eq!(counts, vec![
+            ("audit > retry > rate limit", 1, 3, 3),
+            ("audit > rate limit > retry", 3, 6, 3),
+            ("retry > audit > rate limit", 1, 3, 6),
+            ("rate limit > retry > audit", 3, 6, 6),
+        ]);
+    }
+}
@@ -0,0 +1,6 @@
+// Larger patterns built from the single-responsibility components above
+pub mod adapter;
+pub mod cqrs;
+pub mod decorator;
+pub mod null_object;
+pub mod strategy;
@@ -0,0 +1,179 @@
+// Null Object: a collaborator that does nothing, in place of no collaborator
+//
+// CorrectUserService holds an Option<N> for notifications, so each use has to
+// branch: announce_new_user wraps the push in `if let Some(..)`, and send_sms
+// has an extra "No notification service configured" arm. A null object
+// implements the trait and does nothing, so an orchestrator always holds a
+// collaborator and calls it unconditionally. What "nothing" means is decided
+// once, in the null object, instead of at every call site.
+//
+// UserAnnouncer is those same side effects written that way. It starts with
+// the null objects and swaps in real collaborators one at a time.
+
+use std::collections::HashMap;
+use crate::{ActivityLogger, AnalyticsService, NotificationService, PhoneNumber, User};
+
+// Delivers nothing. Ok(false) is the trait's "not delivered", with no error to handle.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoopNotificationService;
+
+impl NotificationService for NoopNotificationService {
+    fn send_push_notification(&self, _username: &str, _message: &str) -> Result<bool, String> {
+        Ok(false)
+    }
+    
+    fn send_sms_notification(&self, _phone: &PhoneNumber, _message: &str) -> Result<bool, String> {
+        Ok(false)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoopAnalytics;
+
+impl AnalyticsService for NoopAnalytics {
+    fn track_user_event(&self, _username: &str, _event_name: &str, _metadata: Option<HashMap<String, String>>) {}
+    
+    fn track_user_registration(&self, _username: &str) {}
+    
+    fn track_user_update(&self, _username: &str) {}
+}
+
+// Drops every entry; audits too, through the trait's default log_audit
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoopLogger;
+
+impl ActivityLogger for NoopLogger {
+    fn log_user_activity(&self, _username: &str, _activity: &str) {}
+    
+    fn log_error(&self, _error: &str, _context: &str) {}
+}
+
+// The welcome side effects and SMS of CorrectUserService, with a collaborator
+// always present: no Option, and no branch for a missing one
+pub struct UserAnnouncer<L: ActivityLogger, A: AnalyticsService, N: NotificationService> {
+    logger: L,
+    analytics: A,
+    notifications: N,
+}
+
+impl UserAnnouncer<NoopLogger, NoopAnalytics, NoopNotificationService> {
+    pub fn new() -> Self {
+        UserAnnouncer { logger: NoopLogger, analytics: NoopAnalytics, notifications: NoopNotificationService }
+    }
+}
+
+impl Default for UserAnnouncer<NoopLogger, NoopAnalytics, NoopNotificationService> {
+    fn default() -> Self {
+        UserAnnouncer::new()
+    }
+}
+
+impl<L: ActivityLogger, A: AnalyticsService, N: NotificationService> UserAnnouncer<L, A, N> {
+    pub fn with_logger<L2: ActivityLogger>(self, logger: L2) -> UserAnnouncer<L2, A, N> {
+        UserAnnouncer { logger, analytics: self.analytics, notifications: self.notifications }
+    }
+    
+    pub fn with_analytics<A2: AnalyticsService>(self, analytics: A2) -> UserAnnouncer<L, A2, N> {
+        UserAnnouncer { logger: self.logger, analytics, notifications: self.notifications }
+    }
+    
+    pub fn with_notifications<N2: NotificationService>(self, notifications: N2) -> UserAnnouncer<L, A, N2> {
+        UserAnnouncer { logger: self.logger, analytics: self.analytics, notifications }
+    }
+    
+    // A failed push is logged, never returned: the user exists either way
+    pub fn announce(&self, user: &User) {
+        self.logger.log_user_activity(user.username(), "User created");
+        self.analytics.track_user_registration(user.username());
+        if let Err(e) = self.notifications.send_push_notification(user.username(), "Welcome! Your account has been created.") {
+            self.logger.log_error(&e, "Failed to send push notification");
+        }
+    }
+    
+    // Texts the number on the user's profile. Ok(false) from the null object
+    // means nothing was sent, as from a service that declined.
+    pub fn send_sms(&self, user: &User, message: &str) -> Result<bool, String> {
+        let result = match user.phone() {
+            None => Err(format!("{} has no phone number", user.id())),
+            Some(phone) => self.notifications.send_sms_notification(phone, message),
+        };
+        result.inspect_err(|e| self.logger.log_error(e, "Failed to send SMS"))
+    }
+}
+
+// Tests for the null object example: the no-op collaborators do nothing, and
+// the announcer calls whatever it holds without checking for it first
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{mock_user_service, MockActivityLogger, MockAnalyticsService, MockNotificationService};
+    use crate::{ActivityLogger, AnalyticsService, NotificationService, PhoneNumber, User};
+    
+    fn grace(phone: Option<&str>) -> User {
+        let mut user = User::new("grace_h".to_string(), "grace@example.com".to_string());
+        user.set_phone(phone.map(|raw| PhoneNumber::parse(raw).unwrap()));
+        user
+    }
+    
+    #[test]
+    fn null_objects_do_nothing_and_deliver_nothing() {
+        let phone = PhoneNumber::parse("+14155550132").unwrap();
+        assert_eq!(NoopNotificationService.send_push_notification("grace_h", "Hi"), Ok(false));
+        assert_eq!(NoopNotificationService.send_sms_notification(&phone, "Hi"), Ok(false));
+        
+        // Nothing to observe, only that every method can be called
+        NoopAnalytics.track_user_event("grace_h", "login", None);
+        NoopAnalytics.track_user_registration("grace_h");
+        NoopAnalytics.track_user_update("grace_h");
+        NoopLogger.log_user_activity("grace_h", "User created");
+        NoopLogger.log_error("boom", "context");
+    }
+    
+    #[test]
+    fn announcer_calls_its_collaborators_unconditionally() {
+        let (logger, analytics, notifications) = (MockActivityLogger::default(), MockAnalyticsService::default(), MockNotificationService::default());
+        let announcer = UserAnnouncer::new()
+            .with_logger(logger.clone())
+            .with_analytics(analytics.clone())
+            .with_notifications(notifications.clone());
+        let user = grace(Some("+44 20 7946 0958"));
+        announcer.announce(&user);
+        assert_eq!(announcer.send_sms(&user, "Hello"), Ok(true));
+        assert_eq!(logger.activities(), vec![("grace_h".to_string(), "User created".to_string())]);
+        assert_eq!(analytics.spy().calls(), vec!["track_user_registration(grace_h)"]);
+        assert_eq!(notifications.spy().calls(), vec!["send_push_notification(grace_h)", "send_sms_notification(+442079460958)"]);
+        
+        notifications.spy().fail_all("gateway down");
+        announcer.announce(&user);
+        assert_eq!(announcer.send_sms(&user, "Hello"), Err("gateway down".to_string()));
+        assert_eq!(logger.errors(), vec![
+            ("Failed to send push notification".to_string(), "gateway down".to_string()),
+            ("Failed to send SMS".to_string(), "gateway down".to_string()),
+        ]);
+    }
+    
+    #[test]
+    fn null_objects_stand_in_for_missing_collaborators() {
+        let logger = MockActivityLogger::default();
+        let announcer = UserAnnouncer::default().with_logger(logger.clone());
+        let user = grace(Some("+44 20 7946 0958"));
+        announcer.announce(&user);
+        assert_eq!(announcer.send_sms(&user, "Hello"), Ok(false), "nothing to send it through, and no error");
+        
+        let no_phone = grace(None);
+        assert_eq!(announcer.send_sms(&no_phone, "Hello"), Err(format!("{} has no phone number", no_phone.id())));
+        assert_eq!(logger.errors(), vec![("Failed to send SMS".to_string(), format!("{} has no phone number", no_phone.id()))]);
+    }
+    
+    #[test]
+    fn user_service_without_notifications_and_with_a_null_object() {
+        let (service, _) = mock_user_service();
+        let without = service.with_notification_service(None::<NoopNotificationService>);
+        let mut user = without.create_user("grace_h".to_string(), "grace@example.com".to_string()).unwrap();
+        without.update_profile(&mut user, Some("+44 20 7946 0958"), None, None).unwrap();
+        assert_eq!(without.send_sms(&user, "Hello"), Err("No notification service configured".to_string()));
+        
+        let with_noop = without.with_notification_service(Some(NoopNotificationService));
+        assert_eq!(with_noop.send_sms(&user, "Hello"), Ok(false));
+    }
+}
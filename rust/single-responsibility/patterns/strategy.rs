@@ -0,0 +1,387 @@
+// Strategy: one job, several interchangeable algorithms, chosen at run time
+//
+// - CompressionStrategy: how a backup archive is compressed. NoCompression,
+//   RunLengthEncoding and GzipCompression implement it, and
+//   CompressedBackupService is the context: `set_compression` swaps the
+//   strategy between backups. Each archive starts with its strategy's name,
+//   so an archive written before a switch still restores after it.
+// - RetryPolicy (the outbox's existing strategy trait) gains LinearBackoff
+//   and NoRetry, and SwitchableRetryPolicy, a context that OutboxProcessor
+//   holds like any other policy while a kept handle replaces the policy inside.
+//
+// The contexts never branch on which strategy they hold; adding an algorithm
+// is a new type, not an edit to the backup service or the outbox.
+
+use std::io::{Read, Write};
+use std::path::Path;
+use std::sync::{Arc, RwLock};
+use chrono::Duration;
+use crate::{decode_backup, encode_backup, restore_users, write_backup, BackupManifest, BackupService, RetryPolicy, User, UserRepository};
+
+// Compression strategy trait - turns bytes into fewer bytes and back
+pub trait CompressionStrategy: Send + Sync {
+    // Written into each archive; must be unique among the strategies
+    fn name(&self) -> &'static str;
+    fn compress(&self, data: &[u8]) -> Result<Vec<u8>, String>;
+    fn decompress(&self, data: &[u8]) -> Result<Vec<u8>, String>;
+}
+
+// Stores the bytes as they are
+pub struct NoCompression;
+
+impl CompressionStrategy for NoCompression {
+    fn name(&self) -> &'static str {
+        "none"
+    }
+    
+    fn compress(&self, data: &[u8]) -> Result<Vec<u8>, String> {
+        Ok(data.to_vec())
+    }
+    
+    fn decompress(&self, data: &[u8]) -> Result<Vec<u8>, String> {
+        Ok(data.to_vec())
+    }
+}
+
+// Each run of one byte becomes a (count, byte) pair, runs up to 255 long.
+// Cheap, and good only for long runs: data without them doubles in size.
+pub struct RunLengthEncoding;
+
+impl CompressionStrategy for RunLengthEncoding {
+    fn name(&self) -> &'static str {
+        "rle"
+    }
+    
+    fn compress(&self, data: &[u8]) -> Result<Vec<u8>, String> {
+        let mut out = Vec::new();
+        let mut rest = data;
+        while let Some(&byte) = rest.first() {
+            let run = rest.iter().take(255).take_while(|&&b| b == byte).count();
+            out.extend_from_slice(&[run as u8, byte]);
+            rest = &rest[run..];
+        }
+        Ok(out)
+    }
+    
+    fn decompress(&self, data: &[u8]) -> Result<Vec<u8>, String> {
+        if !data.len().is_multiple_of(2) {
+            return Err("Run-length data is truncated".to_string());
+        }
+        let mut out = Vec::new();
+        for pair in data.chunks(2) {
+            if pair[0] == 0 {
+                return Err("Run-length data has an empty run".to_string());
+            }
+            out.extend(std::iter::repeat_n(pair[1], pair[0] as usize));
+        }
+        Ok(out)
+    }
+}
+
+// Deflate with a gzip header and CRC; `level` runs from 0 (fastest) to 9 (smallest)
+pub struct GzipCompression {
+    level: u32,
+}
+
+impl GzipCompression {
+    pub fn new(level: u32) -> Self {
+        GzipCompression { level: level.min(9) }
+    }
+}
+
+impl Default for GzipCompression {
+    fn default() -> Self {
+        Self::new(6)
+    }
+}
+
+impl CompressionStrategy for GzipCompression {
+    fn name(&self) -> &'static str {
+        "gzip"
+    }
+    
+    fn compress(&self, data: &[u8]) -> Result<Vec<u8>, String> {
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::new(self.level));
+        encoder.write_all(data).map_err(|e| e.to_string())?;
+        encoder.finish().map_err(|e| e.to_string())
+    }
+    
+    fn decompress(&self, data: &[u8]) -> Result<Vec<u8>, String> {
+        let mut out = Vec::new();
+        flate2::read::GzDecoder::new(data).read_to_end(&mut out).map_err(|e| e.to_string())?;
+        Ok(out)
+    }
+}
+
+// The strategies an archive may name, for reading it back
+pub fn compression_named(name: &str) -> Option<Arc<dyn CompressionStrategy>> {
+    match name {
+        "none" => Some(Arc::new(NoCompression)),
+        "rle" => Some(Arc::new(RunLengthEncoding)),
+        "gzip" => Some(Arc::new(GzipCompression::default())),
+        _ => None,
+    }
+}
+
+// Backup context: the archive format of GzipBackupService, with the
+// compression left to a strategy. The archive is the strategy's name on
+// its own line, then the compressed manifest and users.
+pub struct CompressedBackupService {
+    compression: RwLock<Arc<dyn CompressionStrategy>>,
+}
+
+impl CompressedBackupService {
+    pub fn new(compression: impl CompressionStrategy + 'static) -> Self {
+        CompressedBackupService { compression: RwLock::new(Arc::new(compression)) }
+    }
+    
+    // Used by the next backup; archives already written keep their own
+    pub fn set_compression(&self, compression: impl CompressionStrategy + 'static) {
+        if let Ok(mut current) = self.compression.write() {
+            *current = Arc::new(compression);
+        }
+    }
+    
+    pub fn compression(&self) -> &'static str {
+        self.current().name()
+    }
+    
+    fn current(&self) -> Arc<dyn CompressionStrategy> {
+        match self.compression.read() {
+            Ok(current) => current.clone(),
+            Err(poisoned) => poisoned.into_inner().clone(),
+        }
+    }
+    
+    fn read_archive(path: &Path) -> Result<(BackupManifest, Vec<User>), String> {
+        let archive = std::fs::read(path).map_err(|e| format!("Cannot open backup {}: {}", path.display(), e))?;
+        let split = archive.iter().position(|&b| b == b'\n').ok_or("Backup names no compression")?;
+        let name = String::from_utf8_lossy(&archive[..split]);
+        let compression = compression_named(&name).ok_or_else(|| format!("Unknown backup compression {:?}", name))?;
+        let contents = compression
+            .decompress(&archive[split + 1..])
+            .map_err(|e| format!("Backup {} is corrupt: {}", path.display(), e))?;
+        decode_backup(&contents)
+    }
+}
+
+impl BackupService for CompressedBackupService {
+    fn backup(&self, repo: &dyn UserRepository, path: &Path) -> Result<BackupManifest, String> {
+        let compression = self.current();
+        let (manifest, contents) = encode_backup(&repo.find_all()?)?;
+        let compressed = compression.compress(&contents)?;
+        write_backup(path, |mut file| {
+            writeln!(file, "{}", compression.name())?;
+            file.write_all(&compressed)?;
+            file.sync_all()
+        })?;
+        Ok(manifest)
+    }
+    
+    fn verify(&self, path: &Path) -> Result<BackupManifest, String> {
+        Self::read_archive(path).map(|(manifest, _)| manifest)
+    }
+    
+    fn restore(&self, path: &Path, repo: &dyn UserRepository) -> Result<usize, String> {
+        let (_, users) = Self::read_archive(path)?;
+        restore_users(&users, repo)
+    }
+}
+
+// Adds `step` after every failed attempt: step, 2 * step, 3 * step, ...
+pub struct LinearBackoff {
+    max_attempts: u32,
+    step: Duration,
+}
+
+impl LinearBackoff {
+    pub fn new(max_attempts: u32, step: Duration) -> Self {
+        LinearBackoff { max_attempts, step }
+    }
+}
+
+impl RetryPolicy for LinearBackoff {
+    fn max_attempts(&self) -> u32 {
+        self.max_attempts
+    }
+    
+    fn delay_after(&self, attempt: u32) -> Duration {
+        self.step * attempt.min(i32::MAX as u32) as i32
+    }
+}
+
+// One attempt only: the first failure is dead-lettered
+pub struct NoRetry;
+
+impl RetryPolicy for NoRetry {
+    fn max_attempts(&self) -> u32 {
+        1
+    }
+    
+    fn delay_after(&self, _attempt: u32) -> Duration {
+        Duration::zero()
+    }
+}
+
+// Retry context that can change strategy while in use. Clones share the
+// policy, so the processor holds one clone and the caller switches through another.
+#[derive(Clone)]
+pub struct SwitchableRetryPolicy {
+    current: Arc<RwLock<Arc<dyn RetryPolicy + Send + Sync>>>,
+}
+
+impl SwitchableRetryPolicy {
+    pub fn new(policy: impl RetryPolicy + Send + Sync + 'static) -> Self {
+        SwitchableRetryPolicy { current: Arc::new(RwLock::new(Arc::new(policy))) }
+    }
+    
+    // Applies from the next failure on; entries already waiting keep their schedule
+    pub fn switch_to(&self, policy: impl RetryPolicy + Send + Sync + 'static) {
+        if let Ok(mut current) = self.current.write() {
+            *current = Arc::new(policy);
+        }
+    }
+    
+    fn current(&self) -> Arc<dyn RetryPolicy + Send + Sync> {
+        match self.current.read() {
+            Ok(current) => current.clone(),
+            Err(poisoned) => poisoned.into_inner().clone(),
+        }
+    }
+}
+
+impl RetryPolicy for SwitchableRetryPolicy {
+    fn max_attempts(&self) -> u32 {
+        self.current().max_attempts()
+    }
+    
+    fn delay_after(&self, attempt: u32) -> Duration {
+        self.current().delay_after(attempt)
+    }
+}
+
+// Tests for the strategy example: each strategy's behaviour on fixed inputs,
+// and contexts that change strategy between calls
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, Utc};
+    use crate::testing::{MockAnalyticsService, MockEmailService, MockNotificationService};
+    use crate::{BackupService, EmailMessage, ExponentialBackoff, FixedBackoff, InMemoryUserRepository, OutboxProcessor, OutboxQueue, RetryPolicy, SideEffect, User, UserRepository};
+    
+    // 40 a's then 60 b's: two long runs
+    fn runs() -> Vec<u8> {
+        [vec![b'a'; 40], vec![b'b'; 60]].concat()
+    }
+    
+    fn archive_path(test: &str) -> std::path::PathBuf {
+        let module = module_path!().replace("::", "-");
+        std::env::temp_dir().join(format!("{}-{}-{}.backup", module, test, std::process::id()))
+    }
+    
+    fn delays(policy: &dyn RetryPolicy) -> Vec<i64> {
+        (1..=4).map(|attempt| policy.delay_after(attempt).num_seconds()).collect()
+    }
+    
+    #[test]
+    fn compression_strategies_differ_on_fixed_input() {
+        let strategies: [Box<dyn CompressionStrategy>; 3] = [Box::new(NoCompression), Box::new(RunLengthEncoding), Box::new(GzipCompression::default())];
+        let sizes = |data: &[u8]| -> Vec<usize> {
+            strategies.iter().map(|strategy| {
+                let compressed = strategy.compress(data).unwrap();
+                assert_eq!(strategy.decompress(&compressed).unwrap(), data, "{} round trip", strategy.name());
+                compressed.len()
+            }).collect()
+        };
+        
+        assert_eq!(RunLengthEncoding.compress(&runs()).unwrap(), vec![40, b'a', 60, b'b']);
+        let [none, rle, gzip] = sizes(&runs())[..] else { unreachable!() };
+        assert_eq!((none, rle), (100, 4));
+        assert!(rle < gzip && gzip < none, "gzip's header costs more than two runs: {}", gzip);
+        
+        // Without runs, run-length encoding doubles the input and gzip cannot beat its own header
+        let [none, rle, gzip] = sizes(b"abcdefgh")[..] else { unreachable!() };
+        assert_eq!((none, rle), (8, 16));
+        assert!(gzip > none);
+        
+        assert_eq!(RunLengthEncoding.compress(&[7; 300]).unwrap(), vec![255, 7, 45, 7], "runs are capped at 255");
+        assert_eq!(sizes(b""), vec![0, 0, 20]);
+    }
+    
+    #[test]
+    fn damaged_input_is_rejected() {
+        assert!(RunLengthEncoding.decompress(&[3, b'a', 2]).is_err());
+        assert!(RunLengthEncoding.decompress(&[0, b'a']).is_err());
+        assert!(GzipCompression::default().decompress(b"not gzip").is_err());
+        assert!(compression_named("lz4").is_none());
+        assert_eq!(compression_named("rle").unwrap().name(), "rle");
+    }
+    
+    #[test]
+    fn backups_switch_strategy_and_restore_whatever_wrote_them() {
+        let repository = InMemoryUserRepository::new();
+        repository.save(&User::new("ada".to_string(), "ada@example.com".to_string())).unwrap();
+        let backups = CompressedBackupService::new(RunLengthEncoding);
+        let (older, newer) = (archive_path("older"), archive_path("newer"));
+        backups.backup(&repository, &older).unwrap();
+        backups.set_compression(GzipCompression::new(9));
+        assert_eq!(backups.compression(), "gzip");
+        backups.backup(&repository, &newer).unwrap();
+        
+        assert!(std::fs::read(&older).unwrap().starts_with(b"rle\n"));
+        assert!(std::fs::read(&newer).unwrap().starts_with(b"gzip\n"));
+        for path in [&older, &newer] {
+            let target = InMemoryUserRepository::new();
+            assert_eq!(backups.restore(path, &target).unwrap(), 1);
+            assert_eq!(target.find_all().unwrap()[0].username(), "ada");
+        }
+        
+        let mut renamed = std::fs::read(&older).unwrap();
+        renamed.splice(..3, b"lz4".iter().copied());
+        std::fs::write(&older, renamed).unwrap();
+        assert_eq!(backups.verify(&older).unwrap_err(), "Unknown backup compression \"lz4\"");
+        std::fs::remove_file(&older).unwrap();
+        std::fs::remove_file(&newer).unwrap();
+    }
+    
+    #[test]
+    fn backoff_strategies_differ_on_fixed_attempts() {
+        assert_eq!(delays(&FixedBackoff::new(5, Duration::seconds(10))), vec![10, 10, 10, 10]);
+        assert_eq!(delays(&LinearBackoff::new(5, Duration::seconds(10))), vec![10, 20, 30, 40]);
+        assert_eq!(delays(&ExponentialBackoff::new(5, Duration::seconds(10), Duration::seconds(60))), vec![10, 20, 40, 60]);
+        assert_eq!((NoRetry.max_attempts(), delays(&NoRetry)), (1, vec![0, 0, 0, 0]));
+        
+        let switchable = SwitchableRetryPolicy::new(FixedBackoff::new(5, Duration::seconds(10)));
+        let handle = switchable.clone();
+        handle.switch_to(LinearBackoff::new(2, Duration::seconds(10)));
+        assert_eq!((switchable.max_attempts(), delays(&switchable)), (2, vec![10, 20, 30, 40]));
+    }
+    
+    #[test]
+    fn switching_the_policy_reschedules_the_outbox() {
+        let email = MockEmailService::default();
+        email.spy().fail_all("SMTP server unavailable");
+        let policy = SwitchableRetryPolicy::new(FixedBackoff::new(5, Duration::seconds(10)));
+        let processor = OutboxProcessor::new(email.clone(), MockAnalyticsService::default(), None::<MockNotificationService>, policy.clone());
+        let outbox = OutboxQueue::new();
+        outbox.enqueue(SideEffect::Email(EmailMessage { to: "ada@example.com".to_string(), subject: "Hi".to_string(), body: String::new() })).unwrap();
+        let start = Utc::now() + Duration::seconds(1);
+        let pass = |seconds: i64| {
+            let report = processor.process_due(&outbox, start + Duration::seconds(seconds)).unwrap();
+            (report.retried, report.dead_lettered)
+        };
+        
+        assert_eq!(pass(0), (1, 0), "fixed: retry in 10s");
+        policy.switch_to(LinearBackoff::new(5, Duration::seconds(60)));
+        assert_eq!(pass(10), (1, 0), "linear: second failure waits 120s");
+        assert_eq!(pass(129), (0, 0));
+        policy.switch_to(NoRetry);
+        assert_eq!(pass(130), (0, 1));
+        assert_eq!(email.spy().calls_to("send"), 3);
+        assert_eq!(outbox.dead_letters()[0].attempts, 3);
+    }
+    
+    crate::backup_service_contract_tests!(run_length_backup, CompressedBackupService::new(RunLengthEncoding));
+    crate::backup_service_contract_tests!(uncompressed_backup, CompressedBackupService::new(NoCompression));
+    crate::backup_service_contract_tests!(gzip_strategy_backup, CompressedBackupService::new(GzipCompression::default()));
+}
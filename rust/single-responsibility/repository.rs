@@ -0,0 +1,968 @@
+// Where users are stored: the UserRepository trait, its unit of work and the
+// in-memory, database and SQLite implementations
+
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::sync::{Arc, Condvar, Mutex, RwLock};
+use std::thread::{self, ThreadId};
+#[cfg(feature = "sqlite")]
+use chrono::{DateTime, Utc};
+#[cfg(feature = "sqlite")]
+use crate::PhoneNumber;
+use crate::{User, UserId};
+
+// Unit of work - groups repository writes so they are kept or undone together.
+// Writes made between `begin` and `rollback` are undone; `commit` keeps them.
+pub trait UnitOfWork {
+    fn begin(&self) -> Result<(), String>;
+    fn commit(&self) -> Result<(), String>;
+    fn rollback(&self) -> Result<(), String>;
+}
+
+// Which slice of an ordered listing to return
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Pagination {
+    pub offset: usize,
+    pub limit: usize,
+}
+
+impl Pagination {
+    pub fn new(offset: usize, limit: usize) -> Self {
+        Pagination { offset, limit }
+    }
+    
+    pub fn first(limit: usize) -> Self {
+        Pagination { offset: 0, limit }
+    }
+    
+    fn window<T>(&self, items: Vec<T>) -> Page<T> {
+        let total = items.len();
+        let items = items.into_iter().skip(self.offset).take(self.limit).collect();
+        Page { items, offset: self.offset, limit: self.limit, total }
+    }
+}
+
+impl Default for Pagination {
+    fn default() -> Self {
+        Pagination::first(20)
+    }
+}
+
+// One page of results, plus the total number of matches across all pages
+#[derive(Debug, Clone)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub offset: usize,
+    pub limit: usize,
+    pub total: usize,
+}
+
+impl<T> Page<T> {
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+    
+    // None once this page reaches the end of the matches
+    pub fn next_page(&self) -> Option<Pagination> {
+        let end = self.offset + self.items.len();
+        (self.limit > 0 && end < self.total).then(|| Pagination::new(end, self.limit))
+    }
+}
+
+// User repository trait - defines user persistence operations.
+// Listings and searches are ordered by id. Email lookups and searches ignore ASCII case.
+pub trait UserRepository: UnitOfWork {
+    fn save(&self, user: &User) -> Result<bool, String>;
+    fn update(&self, user: &User) -> Result<bool, String>;
+    fn find_by_id(&self, user_id: &str) -> Result<Option<User>, String>;
+    fn find_by_email(&self, email: &str) -> Result<Option<User>, String>;
+    fn find_all(&self) -> Result<Vec<User>, String>;
+    // Users whose username or email contains `query`; an empty query matches everyone
+    fn search(&self, query: &str, page: Pagination) -> Result<Page<User>, String>;
+    fn count(&self) -> Result<usize, String>;
+    fn delete(&self, user: &User) -> Result<bool, String>;
+}
+
+// In-memory UserRepository keyed by user id, safe to share between threads
+pub struct InMemoryUserRepository {
+    users: RwLock<HashMap<UserId, User>>,
+    transaction: Mutex<Option<Transaction>>,
+    transaction_ended: Condvar,
+}
+
+// One open transaction at a time. Other threads wait in `begin`, and in every
+// write, until it ends, so the journal only ever holds the owner's writes and a
+// rollback cannot overwrite anyone else's.
+struct Transaction {
+    owner: ThreadId,
+    undo: Vec<Undo>,
+}
+
+enum Undo {
+    Remove(UserId),
+    Put(User),
+}
+
+impl InMemoryUserRepository {
+    pub fn new() -> Self {
+        InMemoryUserRepository {
+            users: RwLock::new(HashMap::new()),
+            transaction: Mutex::new(None),
+            transaction_ended: Condvar::new(),
+        }
+    }
+    
+    // Applies one write, waiting while another thread's transaction is open. The
+    // transaction lock is held until the write is journalled, so no transaction
+    // can begin or end in between. `write` returns its result and how to undo it.
+    fn write<T>(&self, write: impl FnOnce(&mut HashMap<UserId, User>) -> Result<(T, Option<Undo>), String>) -> Result<T, String> {
+        let me = thread::current().id();
+        let mut transaction = self.transaction.lock().map_err(|e| e.to_string())?;
+        while transaction.as_ref().is_some_and(|open| open.owner != me) {
+            transaction = self.transaction_ended.wait(transaction).map_err(|e| e.to_string())?;
+        }
+        let (result, undo) = write(&mut *self.users.write().map_err(|e| e.to_string())?)?;
+        if let (Some(open), Some(undo)) = (transaction.as_mut(), undo) {
+            open.undo.push(undo);
+        }
+        Ok(result)
+    }
+    
+    // Ends the calling thread's transaction. `finish` gets the undo journal while
+    // the transaction is still held, so a thread waiting in `begin` cannot write
+    // anything the journal would then overwrite.
+    fn end_transaction(&self, finish: impl FnOnce(Vec<Undo>) -> Result<(), String>) -> Result<(), String> {
+        let mut transaction = self.transaction.lock().map_err(|e| e.to_string())?;
+        match transaction.as_mut() {
+            Some(open) if open.owner == thread::current().id() => {
+                let result = finish(std::mem::take(&mut open.undo));
+                *transaction = None;
+                self.transaction_ended.notify_all();
+                result
+            }
+            _ => Err("No open transaction on this thread".to_string()),
+        }
+    }
+}
+
+impl Default for InMemoryUserRepository {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl UnitOfWork for InMemoryUserRepository {
+    fn begin(&self) -> Result<(), String> {
+        let me = thread::current().id();
+        let mut transaction = self.transaction.lock().map_err(|e| e.to_string())?;
+        while let Some(open) = transaction.as_ref() {
+            if open.owner == me {
+                return Err("A transaction is already open on this thread".to_string());
+            }
+            transaction = self.transaction_ended.wait(transaction).map_err(|e| e.to_string())?;
+        }
+        *transaction = Some(Transaction { owner: me, undo: Vec::new() });
+        Ok(())
+    }
+    
+    fn commit(&self) -> Result<(), String> {
+        self.end_transaction(|_| Ok(()))
+    }
+    
+    fn rollback(&self) -> Result<(), String> {
+        self.end_transaction(|undo| {
+            let mut users = self.users.write().map_err(|e| e.to_string())?;
+            for step in undo.into_iter().rev() {
+                match step {
+                    Undo::Remove(id) => {
+                        users.remove(&id);
+                    }
+                    Undo::Put(user) => {
+                        users.insert(user.id(), user);
+                    }
+                }
+            }
+            Ok(())
+        })
+    }
+}
+
+impl UserRepository for InMemoryUserRepository {
+    fn save(&self, user: &User) -> Result<bool, String> {
+        self.write(|users| {
+            if users.contains_key(&user.id()) {
+                return Err(format!("User already exists: {}", user.id()));
+            }
+            users.insert(user.id(), user.clone());
+            Ok((true, Some(Undo::Remove(user.id()))))
+        })
+    }
+    
+    // Returns false when there is no stored user with this id
+    fn update(&self, user: &User) -> Result<bool, String> {
+        self.write(|users| match users.get_mut(&user.id()) {
+            Some(stored) => Ok((true, Some(Undo::Put(std::mem::replace(stored, user.clone()))))),
+            None => Ok((false, None)),
+        })
+    }
+    
+    fn find_by_id(&self, user_id: &str) -> Result<Option<User>, String> {
+        let Ok(id) = user_id.parse::<UserId>() else {
+            return Ok(None);
+        };
+        let users = self.users.read().map_err(|e| e.to_string())?;
+        Ok(users.get(&id).cloned())
+    }
+    
+    fn find_by_email(&self, email: &str) -> Result<Option<User>, String> {
+        let users = self.users.read().map_err(|e| e.to_string())?;
+        Ok(users.values().find(|user| user.email().eq_ignore_ascii_case(email)).cloned())
+    }
+    
+    // Ordered by id, so exports and listings are stable
+    fn find_all(&self) -> Result<Vec<User>, String> {
+        let users = self.users.read().map_err(|e| e.to_string())?;
+        let mut all: Vec<User> = users.values().cloned().collect();
+        all.sort_by_key(|user| user.id());
+        Ok(all)
+    }
+    
+    fn search(&self, query: &str, page: Pagination) -> Result<Page<User>, String> {
+        let query = query.to_ascii_lowercase();
+        let mut matches = self.find_all()?;
+        matches.retain(|user| {
+            user.username().to_ascii_lowercase().contains(&query) || user.email().to_ascii_lowercase().contains(&query)
+        });
+        Ok(page.window(matches))
+    }
+    
+    fn count(&self) -> Result<usize, String> {
+        Ok(self.users.read().map_err(|e| e.to_string())?.len())
+    }
+    
+    fn delete(&self, user: &User) -> Result<bool, String> {
+        self.write(|users| match users.remove(&user.id()) {
+            Some(previous) => Ok((true, Some(Undo::Put(previous)))),
+            None => Ok((false, None)),
+        })
+    }
+}
+
+// Transactions on one repository shared between threads
+#[cfg(test)]
+mod in_memory_transaction_tests {
+    use super::*;
+    
+    fn stored_email(repository: &InMemoryUserRepository, user: &User) -> String {
+        repository.find_by_id(&user.id().to_string()).unwrap().unwrap().email().to_string()
+    }
+    
+    #[test]
+    fn rollback_is_applied_before_a_waiting_transaction_begins() {
+        let repository = Arc::new(InMemoryUserRepository::new());
+        let mut ada = User::new("ada".to_string(), "ada@example.com".to_string());
+        repository.save(&ada).unwrap();
+        
+        repository.begin().unwrap();
+        ada.set_email("rolled-back@example.com".to_string());
+        repository.update(&ada).unwrap();
+        
+        let waiting = {
+            let repository = repository.clone();
+            let mut ada = ada.clone();
+            thread::spawn(move || {
+                repository.begin().unwrap();
+                ada.set_email("committed@example.com".to_string());
+                repository.update(&ada).unwrap();
+                repository.commit().unwrap();
+            })
+        };
+        // Give the other thread time to block in begin
+        thread::sleep(std::time::Duration::from_millis(50));
+        repository.rollback().unwrap();
+        waiting.join().unwrap();
+        
+        assert_eq!(stored_email(&repository, &ada), "committed@example.com");
+    }
+    
+    #[test]
+    fn other_threads_write_after_the_transaction_ends() {
+        let repository = Arc::new(InMemoryUserRepository::new());
+        let mut ada = User::new("ada".to_string(), "ada@example.com".to_string());
+        repository.save(&ada).unwrap();
+        
+        repository.begin().unwrap();
+        ada.set_email("rolled-back@example.com".to_string());
+        repository.update(&ada).unwrap();
+        
+        let writer = {
+            let repository = repository.clone();
+            let mut ada = ada.clone();
+            thread::spawn(move || {
+                ada.set_email("outside@example.com".to_string());
+                repository.update(&ada).unwrap();
+            })
+        };
+        // The write outside the transaction waits for it to end
+        thread::sleep(std::time::Duration::from_millis(50));
+        assert_eq!(stored_email(&repository, &ada), "rolled-back@example.com");
+        repository.rollback().unwrap();
+        writer.join().unwrap();
+        
+        assert_eq!(stored_email(&repository, &ada), "outside@example.com");
+    }
+    
+    #[test]
+    fn only_the_owning_thread_can_end_a_transaction() {
+        let repository = Arc::new(InMemoryUserRepository::new());
+        repository.begin().unwrap();
+        let other = repository.clone();
+        let result = thread::spawn(move || (other.commit(), other.rollback())).join().unwrap();
+        assert!(result.0.is_err() && result.1.is_err());
+        repository.commit().unwrap();
+        assert!(repository.commit().is_err());
+    }
+}
+
+// Lets a caller keep a handle on the repository a service uses
+impl<R: UnitOfWork + ?Sized> UnitOfWork for Arc<R> {
+    fn begin(&self) -> Result<(), String> {
+        (**self).begin()
+    }
+    
+    fn commit(&self) -> Result<(), String> {
+        (**self).commit()
+    }
+    
+    fn rollback(&self) -> Result<(), String> {
+        (**self).rollback()
+    }
+}
+
+impl<R: UserRepository + ?Sized> UserRepository for Arc<R> {
+    fn save(&self, user: &User) -> Result<bool, String> {
+        (**self).save(user)
+    }
+    
+    fn update(&self, user: &User) -> Result<bool, String> {
+        (**self).update(user)
+    }
+    
+    fn find_by_id(&self, user_id: &str) -> Result<Option<User>, String> {
+        (**self).find_by_id(user_id)
+    }
+    
+    fn find_by_email(&self, email: &str) -> Result<Option<User>, String> {
+        (**self).find_by_email(email)
+    }
+    
+    fn find_all(&self) -> Result<Vec<User>, String> {
+        (**self).find_all()
+    }
+    
+    fn search(&self, query: &str, page: Pagination) -> Result<Page<User>, String> {
+        (**self).search(query, page)
+    }
+    
+    fn count(&self) -> Result<usize, String> {
+        (**self).count()
+    }
+    
+    fn delete(&self, user: &User) -> Result<bool, String> {
+        (**self).delete(user)
+    }
+}
+
+// Rc as well, for single-threaded wiring such as a DI container
+impl<R: UnitOfWork + ?Sized> UnitOfWork for Rc<R> {
+    fn begin(&self) -> Result<(), String> {
+        (**self).begin()
+    }
+    
+    fn commit(&self) -> Result<(), String> {
+        (**self).commit()
+    }
+    
+    fn rollback(&self) -> Result<(), String> {
+        (**self).rollback()
+    }
+}
+
+impl<R: UserRepository + ?Sized> UserRepository for Rc<R> {
+    fn save(&self, user: &User) -> Result<bool, String> {
+        (**self).save(user)
+    }
+    
+    fn update(&self, user: &User) -> Result<bool, String> {
+        (**self).update(user)
+    }
+    
+    fn find_by_id(&self, user_id: &str) -> Result<Option<User>, String> {
+        (**self).find_by_id(user_id)
+    }
+    
+    fn find_by_email(&self, email: &str) -> Result<Option<User>, String> {
+        (**self).find_by_email(email)
+    }
+    
+    fn find_all(&self) -> Result<Vec<User>, String> {
+        (**self).find_all()
+    }
+    
+    fn search(&self, query: &str, page: Pagination) -> Result<Page<User>, String> {
+        (**self).search(query, page)
+    }
+    
+    fn count(&self) -> Result<usize, String> {
+        (**self).count()
+    }
+    
+    fn delete(&self, user: &User) -> Result<bool, String> {
+        (**self).delete(user)
+    }
+}
+
+// Concrete implementation of UserRepository
+pub struct DatabaseUserRepository {
+    connection: Option<String>,
+    table: InMemoryUserRepository,
+}
+
+impl DatabaseUserRepository {
+    pub fn new() -> Self {
+        DatabaseUserRepository {
+            connection: None,
+            table: InMemoryUserRepository::new(),
+        }
+    }
+    
+    // Opens the connection up front; it is only opened once
+    pub fn connect(&mut self) -> Result<(), String> {
+        if self.connection.is_none() {
+            println!("Connecting to database...");
+            self.connection = Some("database_connection".to_string());
+        }
+        Ok(())
+    }
+}
+
+impl Default for DatabaseUserRepository {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl UnitOfWork for DatabaseUserRepository {
+    fn begin(&self) -> Result<(), String> {
+        println!("BEGIN transaction");
+        self.table.begin()
+    }
+    
+    fn commit(&self) -> Result<(), String> {
+        println!("COMMIT transaction");
+        self.table.commit()
+    }
+    
+    fn rollback(&self) -> Result<(), String> {
+        println!("ROLLBACK transaction");
+        self.table.rollback()
+    }
+}
+
+impl UserRepository for DatabaseUserRepository {
+    fn save(&self, user: &User) -> Result<bool, String> {
+        println!("Saving user to database: {}", user.username());
+        self.table.save(user)
+    }
+    
+    fn update(&self, user: &User) -> Result<bool, String> {
+        println!("Updating user in database: {}", user.username());
+        self.table.update(user)
+    }
+    
+    fn find_by_id(&self, user_id: &str) -> Result<Option<User>, String> {
+        println!("Finding user with ID: {}", user_id);
+        self.table.find_by_id(user_id)
+    }
+    
+    fn find_by_email(&self, email: &str) -> Result<Option<User>, String> {
+        println!("Finding user with email: {}", email);
+        self.table.find_by_email(email)
+    }
+    
+    fn find_all(&self) -> Result<Vec<User>, String> {
+        println!("Loading all users from database");
+        self.table.find_all()
+    }
+    
+    fn search(&self, query: &str, page: Pagination) -> Result<Page<User>, String> {
+        println!("Searching users for {:?} (offset {}, limit {})", query, page.offset, page.limit);
+        self.table.search(query, page)
+    }
+    
+    fn count(&self) -> Result<usize, String> {
+        self.table.count()
+    }
+    
+    fn delete(&self, user: &User) -> Result<bool, String> {
+        println!("Deleting user from database: {}", user.username());
+        self.table.delete(user)
+    }
+}
+
+// SQLite-backed UserRepository (enable the `sqlite` feature).
+// Ids, statuses and RFC 3339 timestamps are stored as text.
+#[cfg(feature = "sqlite")]
+pub struct SqliteUserRepository {
+    connection: rusqlite::Connection,
+}
+
+#[cfg(feature = "sqlite")]
+impl SqliteUserRepository {
+    pub fn open(path: &str) -> Result<Self, String> {
+        let connection = rusqlite::Connection::open(path).map_err(|e| e.to_string())?;
+        Self::with_connection(connection)
+    }
+    
+    pub fn in_memory() -> Result<Self, String> {
+        let connection = rusqlite::Connection::open_in_memory().map_err(|e| e.to_string())?;
+        Self::with_connection(connection)
+    }
+    
+    fn with_connection(connection: rusqlite::Connection) -> Result<Self, String> {
+        connection
+            .execute(
+                "CREATE TABLE IF NOT EXISTS users (
+                    id TEXT PRIMARY KEY,
+                    username TEXT NOT NULL,
+                    email TEXT NOT NULL,
+                    status TEXT NOT NULL,
+                    created_at TEXT NOT NULL,
+                    updated_at TEXT NOT NULL,
+                    phone TEXT,
+                    display_name TEXT,
+                    locale TEXT
+                )",
+                [],
+            )
+            .map_err(|e| e.to_string())?;
+        Self::add_profile_columns(&connection)?;
+        Ok(SqliteUserRepository { connection })
+    }
+    
+    // Databases created before the profile fields existed get the columns added
+    fn add_profile_columns(connection: &rusqlite::Connection) -> Result<(), String> {
+        let mut statement = connection.prepare("SELECT name FROM pragma_table_info('users')").map_err(|e| e.to_string())?;
+        let existing = statement
+            .query_map([], |row| row.get::<_, String>(0))
+            .map_err(|e| e.to_string())?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| e.to_string())?;
+        for column in ["phone", "display_name", "locale"] {
+            if !existing.iter().any(|name| name == column) {
+                connection
+                    .execute_batch(&format!("ALTER TABLE users ADD COLUMN {} TEXT", column))
+                    .map_err(|e| e.to_string())?;
+            }
+        }
+        Ok(())
+    }
+    
+    fn parse_timestamp(value: &str) -> Result<DateTime<Utc>, String> {
+        DateTime::parse_from_rfc3339(value)
+            .map(|t| t.with_timezone(&Utc))
+            .map_err(|e| format!("Invalid timestamp {}: {}", value, e))
+    }
+    
+    fn query_users(&self, sql: &str, params: &[&dyn rusqlite::ToSql]) -> Result<Vec<User>, String> {
+        let mut statement = self.connection.prepare(sql).map_err(|e| e.to_string())?;
+        let rows = statement
+            .query_map(params, |row| {
+                Ok((
+                    (row.get::<_, String>(0)?, row.get(1)?, row.get(2)?,
+                     row.get::<_, String>(3)?, row.get::<_, String>(4)?, row.get::<_, String>(5)?),
+                    (row.get::<_, Option<String>>(6)?, row.get(7)?, row.get(8)?),
+                ))
+            })
+            .map_err(|e| e.to_string())?;
+        rows.map(|row| {
+            let ((id, username, email, status, created_at, updated_at), (phone, display_name, locale)) =
+                row.map_err(|e| e.to_string())?;
+            let user = User::restore(
+                id.parse()?,
+                username,
+                email,
+                status.parse()?,
+                Self::parse_timestamp(&created_at)?,
+                Self::parse_timestamp(&updated_at)?,
+            );
+            Ok(user.with_profile(phone.map(PhoneNumber::try_from).transpose()?, display_name, locale))
+        })
+        .collect()
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl UnitOfWork for SqliteUserRepository {
+    fn begin(&self) -> Result<(), String> {
+        self.connection.execute_batch("BEGIN").map_err(|e| e.to_string())
+    }
+    
+    fn commit(&self) -> Result<(), String> {
+        self.connection.execute_batch("COMMIT").map_err(|e| e.to_string())
+    }
+    
+    fn rollback(&self) -> Result<(), String> {
+        self.connection.execute_batch("ROLLBACK").map_err(|e| e.to_string())
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl UserRepository for SqliteUserRepository {
+    fn save(&self, user: &User) -> Result<bool, String> {
+        self.connection
+            .execute(
+                "INSERT INTO users (id, username, email, status, created_at, updated_at, phone, display_name, locale)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+                rusqlite::params![
+                    user.id().to_string(),
+                    user.username(),
+                    user.email(),
+                    user.status().to_string(),
+                    user.created_at().to_rfc3339(),
+                    user.updated_at().to_rfc3339(),
+                    user.phone().map(PhoneNumber::as_str),
+                    user.display_name(),
+                    user.locale(),
+                ],
+            )
+            .map(|rows| rows == 1)
+            .map_err(|e| format!("Failed to save {}: {}", user.id(), e))
+    }
+    
+    fn update(&self, user: &User) -> Result<bool, String> {
+        self.connection
+            .execute(
+                "UPDATE users SET username = ?2, email = ?3, status = ?4, updated_at = ?5,
+                                  phone = ?6, display_name = ?7, locale = ?8 WHERE id = ?1",
+                rusqlite::params![
+                    user.id().to_string(),
+                    user.username(),
+                    user.email(),
+                    user.status().to_string(),
+                    user.updated_at().to_rfc3339(),
+                    user.phone().map(PhoneNumber::as_str),
+                    user.display_name(),
+                    user.locale(),
+                ],
+            )
+            .map(|rows| rows == 1)
+            .map_err(|e| format!("Failed to update {}: {}", user.id(), e))
+    }
+    
+    fn find_by_id(&self, user_id: &str) -> Result<Option<User>, String> {
+        let users = self.query_users(
+            "SELECT id, username, email, status, created_at, updated_at, phone, display_name, locale FROM users WHERE id = ?1",
+            &[&user_id],
+        )?;
+        Ok(users.into_iter().next())
+    }
+    
+    fn find_by_email(&self, email: &str) -> Result<Option<User>, String> {
+        let users = self.query_users(
+            "SELECT id, username, email, status, created_at, updated_at, phone, display_name, locale FROM users
+             WHERE email = ?1 COLLATE NOCASE LIMIT 1",
+            &[&email],
+        )?;
+        Ok(users.into_iter().next())
+    }
+    
+    // Ids are stored as text, so order numerically after loading
+    fn find_all(&self) -> Result<Vec<User>, String> {
+        let mut users = self.query_users("SELECT id, username, email, status, created_at, updated_at, phone, display_name, locale FROM users", &[])?;
+        users.sort_by_key(|user| user.id());
+        Ok(users)
+    }
+    
+    // LIKE ignores ASCII case; the query's own wildcards are escaped.
+    // Paging happens in SQL, ordered by the number after "user-".
+    fn search(&self, query: &str, page: Pagination) -> Result<Page<User>, String> {
+        let pattern = format!("%{}%", query.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_"));
+        let filter = "FROM users WHERE username LIKE ?1 ESCAPE '\\' OR email LIKE ?1 ESCAPE '\\'";
+        let total: i64 = self.connection
+            .query_row(&format!("SELECT COUNT(*) {}", filter), [&pattern], |row| row.get(0))
+            .map_err(|e| e.to_string())?;
+        let limit = i64::try_from(page.limit).unwrap_or(i64::MAX);
+        let offset = i64::try_from(page.offset).unwrap_or(i64::MAX);
+        let items = self.query_users(
+            &format!(
+                "SELECT id, username, email, status, created_at, updated_at, phone, display_name, locale {}
+                 ORDER BY CAST(substr(id, 6) AS INTEGER) LIMIT ?2 OFFSET ?3",
+                filter
+            ),
+            &[&pattern, &limit, &offset],
+        )?;
+        Ok(Page { items, offset: page.offset, limit: page.limit, total: total as usize })
+    }
+    
+    fn count(&self) -> Result<usize, String> {
+        self.connection
+            .query_row("SELECT COUNT(*) FROM users", [], |row| row.get::<_, i64>(0))
+            .map(|count| count as usize)
+            .map_err(|e| e.to_string())
+    }
+    
+    fn delete(&self, user: &User) -> Result<bool, String> {
+        self.connection
+            .execute("DELETE FROM users WHERE id = ?1", [user.id().to_string()])
+            .map(|rows| rows == 1)
+            .map_err(|e| format!("Failed to delete {}: {}", user.id(), e))
+    }
+}
+
+// Every repository in this file against the same contract
+#[cfg(test)]
+mod repository_contract {
+    use super::*;
+    
+    crate::repository_contract_tests!(in_memory, InMemoryUserRepository::new());
+    crate::repository_contract_tests!(database, DatabaseUserRepository::new());
+    #[cfg(feature = "sqlite")]
+    crate::repository_contract_tests!(sqlite, SqliteUserRepository::in_memory().unwrap());
+}
+
+// Contract tests every UserRepository must pass. Invoke it from a test
+// module with an expression that builds a fresh, empty repository:
+//
+//     repository_contract_tests!(in_memory_contract, InMemoryUserRepository::new());
+//     repository_contract_tests!(sqlite_contract, SqliteUserRepository::in_memory().unwrap());
+#[macro_export]
+macro_rules! repository_contract_tests {
+    ($name:ident, $make_repository:expr) => {
+        #[cfg(test)]
+        mod $name {
+            use super::*;
+            use $crate::{Pagination, UnitOfWork, User, UserRepository};
+            
+            fn user(username: &str, email: &str) -> User {
+                User::new(username.to_string(), email.to_string())
+            }
+            
+            fn usernames(users: &[User]) -> Vec<&str> {
+                users.iter().map(|user| user.username()).collect()
+            }
+            
+            #[test]
+            fn committed_writes_are_kept() {
+                let repository = $make_repository;
+                let ada = user("ada", "ada@example.com");
+                repository.begin().unwrap();
+                repository.save(&ada).unwrap();
+                repository.commit().unwrap();
+                assert!(repository.find_by_id(&ada.id().to_string()).unwrap().is_some());
+            }
+            
+            #[test]
+            fn rollback_undoes_saves_updates_and_deletes() {
+                let repository = $make_repository;
+                let mut ada = user("ada", "ada@example.com");
+                let grace = user("grace", "grace@example.com");
+                repository.save(&ada).unwrap();
+                repository.save(&grace).unwrap();
+                
+                repository.begin().unwrap();
+                let hopper = user("hopper", "hopper@example.com");
+                repository.save(&hopper).unwrap();
+                ada.set_email("changed@example.com".to_string());
+                repository.update(&ada).unwrap();
+                repository.delete(&grace).unwrap();
+                repository.rollback().unwrap();
+                
+                assert!(repository.find_by_id(&hopper.id().to_string()).unwrap().is_none());
+                let stored = repository.find_by_id(&ada.id().to_string()).unwrap().unwrap();
+                assert_eq!(stored.email(), "ada@example.com");
+                assert!(repository.find_by_id(&grace.id().to_string()).unwrap().is_some());
+            }
+            
+            #[test]
+            fn commit_without_begin_is_an_error() {
+                let repository = $make_repository;
+                assert!(repository.commit().is_err());
+            }
+            
+            #[test]
+            fn saved_user_can_be_found_by_id() {
+                let repository = $make_repository;
+                let ada = user("ada", "ada@example.com");
+                assert_eq!(repository.save(&ada), Ok(true));
+                let found = repository.find_by_id(&ada.id().to_string()).unwrap().expect("saved user");
+                assert_eq!(found.id(), ada.id());
+                assert_eq!(found.username(), "ada");
+                assert_eq!(found.email(), "ada@example.com");
+                assert_eq!(found.status(), ada.status());
+                assert_eq!(found.created_at().timestamp(), ada.created_at().timestamp());
+            }
+            
+            #[test]
+            fn unknown_user_is_not_found() {
+                let repository = $make_repository;
+                let ghost = user("ghost", "ghost@example.com");
+                assert!(repository.find_by_id(&ghost.id().to_string()).unwrap().is_none());
+                assert!(repository.find_by_id("not-an-id").unwrap().is_none());
+            }
+            
+            #[test]
+            fn duplicate_save_is_rejected() {
+                let repository = $make_repository;
+                let mut ada = user("ada", "ada@example.com");
+                repository.save(&ada).unwrap();
+                ada.set_email("other@example.com".to_string());
+                assert!(repository.save(&ada).is_err());
+                let found = repository.find_by_id(&ada.id().to_string()).unwrap().expect("saved user");
+                assert_eq!(found.email(), "ada@example.com");
+            }
+            
+            #[test]
+            fn update_replaces_stored_user() {
+                let repository = $make_repository;
+                let mut ada = user("ada", "ada@example.com");
+                repository.save(&ada).unwrap();
+                ada.set_username("ada_l".to_string());
+                ada.transition_to($crate::UserStatus::Active).unwrap();
+                assert_eq!(repository.update(&ada), Ok(true));
+                let found = repository.find_by_id(&ada.id().to_string()).unwrap().expect("updated user");
+                assert_eq!(found.username(), "ada_l");
+                assert_eq!(found.status(), $crate::UserStatus::Active);
+            }
+            
+            #[test]
+            fn update_of_unknown_user_reports_false() {
+                let repository = $make_repository;
+                let ghost = user("ghost", "ghost@example.com");
+                assert_eq!(repository.update(&ghost), Ok(false));
+                assert!(repository.find_by_id(&ghost.id().to_string()).unwrap().is_none());
+            }
+            
+            #[test]
+            fn find_all_returns_every_user_in_id_order() {
+                let repository = $make_repository;
+                let ada = user("ada", "ada@example.com");
+                let grace = user("grace", "grace@example.com");
+                repository.save(&grace).unwrap();
+                repository.save(&ada).unwrap();
+                let ids: Vec<_> = repository.find_all().unwrap().iter().map(|user| user.id()).collect();
+                assert_eq!(ids, vec![ada.id(), grace.id()]);
+            }
+            
+            #[test]
+            fn profile_fields_are_stored() {
+                let repository = $make_repository;
+                let mut ada = user("ada", "ada@example.com");
+                repository.save(&ada).unwrap();
+                let phone = $crate::PhoneNumber::parse("+44 20 7946 0958").unwrap();
+                ada.set_phone(Some(phone.clone()));
+                ada.set_display_name(Some("Ada Lovelace".to_string()));
+                ada.set_locale(Some("en-GB".to_string()));
+                repository.update(&ada).unwrap();
+                let found = repository.find_by_id(&ada.id().to_string()).unwrap().expect("updated user");
+                assert_eq!(found.phone(), Some(&phone));
+                assert_eq!(found.display_name(), Some("Ada Lovelace"));
+                assert_eq!(found.locale(), Some("en-GB"));
+                
+                ada.set_phone(None);
+                repository.update(&ada).unwrap();
+                assert_eq!(repository.find_by_id(&ada.id().to_string()).unwrap().unwrap().phone(), None);
+            }
+            
+            #[test]
+            fn find_by_email_ignores_case() {
+                let repository = $make_repository;
+                let ada = user("ada", "Ada@Example.com");
+                repository.save(&ada).unwrap();
+                let found = repository.find_by_email("ada@example.COM").unwrap().expect("user by email");
+                assert_eq!(found.id(), ada.id());
+                assert!(repository.find_by_email("grace@example.com").unwrap().is_none());
+            }
+            
+            #[test]
+            fn search_matches_username_or_email() {
+                let repository = $make_repository;
+                repository.save(&user("ada", "countess@example.com")).unwrap();
+                repository.save(&user("grace", "ada.fan@example.com")).unwrap();
+                repository.save(&user("hopper", "hopper@example.com")).unwrap();
+                let page = repository.search("ADA", Pagination::first(10)).unwrap();
+                assert_eq!(usernames(&page.items), vec!["ada", "grace"]);
+                assert_eq!(page.total, 2);
+            }
+            
+            #[test]
+            fn search_pages_through_matches_in_id_order() {
+                let repository = $make_repository;
+                for name in ["u1", "u2", "u3", "u4", "u5"] {
+                    repository.save(&user(name, &format!("{}@example.com", name))).unwrap();
+                }
+                let first = repository.search("", Pagination::first(2)).unwrap();
+                assert_eq!(usernames(&first.items), vec!["u1", "u2"]);
+                assert_eq!(first.total, 5);
+                let second = repository.search("", first.next_page().unwrap()).unwrap();
+                assert_eq!(usernames(&second.items), vec!["u3", "u4"]);
+                let last = repository.search("", second.next_page().unwrap()).unwrap();
+                assert_eq!(usernames(&last.items), vec!["u5"]);
+                assert_eq!(last.next_page(), None);
+            }
+            
+            #[test]
+            fn search_past_the_end_is_empty() {
+                let repository = $make_repository;
+                repository.save(&user("ada", "ada@example.com")).unwrap();
+                let page = repository.search("", Pagination::new(1, 10)).unwrap();
+                assert!(page.is_empty());
+                assert_eq!(page.total, 1);
+                assert_eq!(page.next_page(), None);
+                assert!(repository.search("", Pagination::first(0)).unwrap().is_empty());
+            }
+            
+            #[test]
+            fn search_without_matches_is_empty() {
+                let repository = $make_repository;
+                let page = repository.search("", Pagination::default()).unwrap();
+                assert!(page.is_empty());
+                assert_eq!(page.total, 0);
+                repository.save(&user("ada", "ada@example.com")).unwrap();
+                assert_eq!(repository.search("nobody", Pagination::default()).unwrap().total, 0);
+            }
+            
+            #[test]
+            fn search_treats_wildcards_literally() {
+                let repository = $make_repository;
+                repository.save(&user("ada_l", "ada@example.com")).unwrap();
+                repository.save(&user("adaxl", "adaxl@example.com")).unwrap();
+                let page = repository.search("ada_", Pagination::default()).unwrap();
+                assert_eq!(usernames(&page.items), vec!["ada_l"]);
+                assert_eq!(repository.search("%", Pagination::default()).unwrap().total, 0);
+            }
+            
+            #[test]
+            fn count_follows_saves_and_deletes() {
+                let repository = $make_repository;
+                assert_eq!(repository.count(), Ok(0));
+                let ada = user("ada", "ada@example.com");
+                repository.save(&ada).unwrap();
+                repository.save(&user("grace", "grace@example.com")).unwrap();
+                assert_eq!(repository.count(), Ok(2));
+                repository.delete(&ada).unwrap();
+                assert_eq!(repository.count(), Ok(1));
+            }
+            
+            #[test]
+            fn deleted_user_is_gone() {
+                let repository = $make_repository;
+                let ada = user("ada", "ada@example.com");
+                repository.save(&ada).unwrap();
+                assert_eq!(repository.delete(&ada), Ok(true));
+                assert!(repository.find_by_id(&ada.id().to_string()).unwrap().is_none());
+                assert_eq!(repository.delete(&ada), Ok(false));
+            }
+        }
+    };
+}
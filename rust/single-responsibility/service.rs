@@ -0,0 +1,1756 @@
+// CorrectUserService: orchestrates the collaborators from the other modules.
+// DynUserService is the same service with every collaborator boxed.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+use crate::{default_log_path, record_operation, user_flow_span, ActivityLogger, AnalyticsService, ApiVersion, Argon2PasswordHasher, AuditAction, AuditEvent, AuditRecord, AuditTrail, Clock, CredentialStore, DatabaseUserRepository, DefaultUserFormatter, DefaultUserValidator, EmailMessage, EmailService, EmailTemplateRenderer, FileActivityLogger, FirebaseNotificationService, GoogleAnalyticsService, IdGenerator, InMemoryCredentialStore, InMemorySessionService, Metrics, MetricsSnapshot, NotificationService, OutboxQueue, Page, Pagination, PasswordHasher, PersonalDataBundle, PhoneNumber, PlaceholderTemplateRenderer, SMTPEmailService, SessionService, SideEffect, SystemClock, SystemIdGenerator, User, UserDto, UserExporter, UserFormatter, UserId, UserRepository, UserResponse, UserStatus, UserValidator, ValidationFailure, ValidationReport};
+
+// Errors from CorrectUserService operations that validate input
+#[derive(Debug, Clone, PartialEq)]
+pub enum UserServiceError {
+    Invalid(ValidationReport),
+    Failed(String),
+}
+
+impl fmt::Display for UserServiceError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            UserServiceError::Invalid(report) => write!(f, "Invalid user data: {}", report),
+            UserServiceError::Failed(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for UserServiceError {}
+
+impl From<String> for UserServiceError {
+    fn from(message: String) -> Self {
+        UserServiceError::Failed(message)
+    }
+}
+
+// User service - orchestrates operations using other services. Account and
+// email collaborators default to the in-process implementations; swap them
+// with the matching `with_*` method.
+pub struct CorrectUserService<
+    R,
+    E,
+    L,
+    V,
+    F,
+    A,
+    N,
+    H = Argon2PasswordHasher,
+    C = InMemoryCredentialStore,
+    S = InMemorySessionService,
+    T = PlaceholderTemplateRenderer,
+>
+where
+    R: UserRepository,
+    E: EmailService,
+    L: ActivityLogger,
+    V: UserValidator,
+    F: UserFormatter,
+    A: AnalyticsService,
+    N: NotificationService,
+    H: PasswordHasher,
+    C: CredentialStore,
+    S: SessionService,
+    T: EmailTemplateRenderer,
+{
+    user_repo: R,
+    email_service: E,
+    logger: L,
+    validator: V,
+    formatter: F,
+    analytics: A,
+    notification_service: Option<N>,
+    password_hasher: H,
+    credentials: C,
+    sessions: S,
+    templates: T,
+    outbox: Option<Arc<OutboxQueue>>,
+    audit: Option<AuditBinding>,
+    metrics: Option<Arc<dyn Metrics>>,
+    clock: Arc<dyn Clock>,
+    ids: Arc<dyn IdGenerator>,
+}
+
+// The audit trail a service records to, and who its entries are attributed to
+struct AuditBinding {
+    trail: Arc<dyn AuditTrail>,
+    actor: String,
+}
+
+impl<R, E, L, V, F, A, N> CorrectUserService<R, E, L, V, F, A, N>
+where
+    R: UserRepository,
+    E: EmailService,
+    L: ActivityLogger,
+    V: UserValidator,
+    F: UserFormatter,
+    A: AnalyticsService,
+    N: NotificationService,
+{
+    // Passwords are hashed with Argon2, credentials and sessions are kept in
+    // memory and emails use the built-in templates until replaced
+    pub fn new(
+        user_repo: R,
+        email_service: E,
+        logger: L,
+        validator: V,
+        formatter: F,
+        analytics: A,
+        notification_service: Option<N>,
+    ) -> Self {
+        CorrectUserService {
+            user_repo,
+            email_service,
+            logger,
+            validator,
+            formatter,
+            analytics,
+            notification_service,
+            password_hasher: Argon2PasswordHasher::new(),
+            credentials: InMemoryCredentialStore::new(),
+            sessions: InMemorySessionService::default(),
+            templates: PlaceholderTemplateRenderer::with_defaults(),
+            outbox: None,
+            audit: None,
+            metrics: None,
+            clock: Arc::new(SystemClock),
+            ids: Arc::new(SystemIdGenerator),
+        }
+    }
+}
+
+impl<R, E, L, V, F, A, N, H, C, S, T> CorrectUserService<R, E, L, V, F, A, N, H, C, S, T>
+where
+    R: UserRepository,
+    E: EmailService,
+    L: ActivityLogger,
+    V: UserValidator,
+    F: UserFormatter,
+    A: AnalyticsService,
+    N: NotificationService,
+    H: PasswordHasher,
+    C: CredentialStore,
+    S: SessionService,
+    T: EmailTemplateRenderer,
+{
+    // Queue email, notification and analytics side effects on `outbox` instead
+    // of attempting them inline; an OutboxProcessor delivers them later
+    pub fn with_outbox(mut self, outbox: Arc<OutboxQueue>) -> Self {
+        self.outbox = Some(outbox);
+        self
+    }
+    
+    // Append an entry to `trail` for every change this service makes, attributed to `actor`
+    pub fn with_audit_trail(mut self, trail: Arc<dyn AuditTrail>, actor: &str) -> Self {
+        self.audit = Some(AuditBinding { trail, actor: actor.to_string() });
+        self
+    }
+    
+    // Count every public operation by outcome and record its latency in `metrics`
+    pub fn with_metrics(mut self, metrics: Arc<dyn Metrics>) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+    
+    // Where creation, update and audit timestamps come from; SystemClock by default
+    pub fn with_clock(mut self, clock: impl Clock + 'static) -> Self {
+        self.clock = Arc::new(clock);
+        self
+    }
+    
+    // Where new user ids and anonymization tokens come from; SystemIdGenerator by default
+    pub fn with_id_generator(mut self, ids: impl IdGenerator + 'static) -> Self {
+        self.ids = Arc::new(ids);
+        self
+    }
+    
+    // What the service has recorded so far; empty without `with_metrics`
+    pub fn metrics_snapshot(&self) -> MetricsSnapshot {
+        self.metrics.as_ref().map(|metrics| metrics.snapshot()).unwrap_or_default()
+    }
+    
+    // Runs `call` and, with metrics configured, records it as `operation`
+    fn metered<Out, Err>(&self, operation: &str, call: impl FnOnce() -> Result<Out, Err>) -> Result<Out, Err> {
+        let Some(ref metrics) = self.metrics else {
+            return call();
+        };
+        let started = std::time::Instant::now();
+        let result = call();
+        let outcome = if result.is_ok() { "success" } else { "error" };
+        record_operation(metrics.as_ref(), "user_service", operation, outcome, started);
+        result
+    }
+    
+    pub fn create_user(&self, username: String, email: String) -> Result<User, UserServiceError> {
+        let _flow = user_flow_span("create_user").entered();
+        self.metered("create_user", || {
+            let (user, welcome) = self.in_transaction("User creation failed", || {
+                let (user, welcome) = self.store_new_user(username, email)?;
+                self.record_change(AuditAction::Created, user.id(), None, Some(&user))?;
+                Ok((user, welcome))
+            })?;
+            self.announce_new_user(&user, welcome);
+            Ok(user)
+        })
+    }
+    
+    // The steps of user creation that must all succeed; callers run it in a transaction.
+    // The welcome email is rendered here, so a broken template rolls the user back
+    // instead of leaving an account that was never told about.
+    fn store_new_user(&self, username: String, email: String) -> Result<(User, EmailMessage), UserServiceError> {
+        // Create user object
+        let username = self.validator.normalize_username(&username);
+        let user = User::create(self.ids.next_user_id(), username, email, self.clock.now());
+        
+        // Validate user data
+        self.check(self.validator.validate_user(&user), "User creation failed")?;
+        
+        // Save user
+        self.user_repo.save(&user).inspect_err(|e| self.logger.log_error(e, "Failed to save user"))?;
+        
+        // Render welcome email
+        let welcome = self.render_email("welcome", &user).inspect_err(|e| self.logger.log_error(e, "Failed to render welcome email"))?;
+        Ok((user, welcome))
+    }
+    
+    // Side effects of a committed user creation; a failure here does not undo it
+    fn announce_new_user(&self, user: &User, welcome: EmailMessage) {
+        // Send welcome email
+        if let Err(e) = self.deliver_email(welcome) {
+            self.logger.log_error(&e, "Failed to send welcome email");
+            // Don't fail user creation if email fails
+        }
+        
+        // Log activity
+        self.logger.log_user_activity(user.username(), "User created");
+        
+        // With an outbox, analytics and notifications are queued like the email
+        if let Some(ref outbox) = self.outbox {
+            self.enqueue_welcome_effects(outbox, user);
+            return;
+        }
+        
+        // Track analytics
+        self.analytics.track_user_registration(user.username());
+        
+        // Send push notification if service is available
+        if let Some(ref notification_service) = self.notification_service {
+            if let Err(e) = notification_service.send_push_notification(user.username(), "Welcome! Your account has been created.") {
+                self.logger.log_error(&e, "Failed to send push notification");
+            }
+        }
+    }
+    
+    // Runs `work` inside a repository transaction, rolling back if it fails
+    fn in_transaction<Out>(&self, context: &str, work: impl FnOnce() -> Result<Out, UserServiceError>) -> Result<Out, UserServiceError> {
+        self.user_repo.begin().inspect_err(|e| self.logger.log_error(e, context))?;
+        match work() {
+            Ok(value) => {
+                self.user_repo.commit().inspect_err(|e| self.logger.log_error(e, context))?;
+                Ok(value)
+            }
+            Err(error) => {
+                if let Err(e) = self.user_repo.rollback() {
+                    self.logger.log_error(&e, "Rollback failed");
+                }
+                Err(error)
+            }
+        }
+    }
+    
+    pub fn update_user(&self, user: &mut User, new_username: String, new_email: String) -> Result<(), UserServiceError> {
+        let _flow = user_flow_span("update_user").entered();
+        self.metered("update_user", || {
+            if user.status() == UserStatus::Deleted {
+                let error_msg = format!("Cannot update deleted user {}", user.id());
+                self.logger.log_error(&error_msg, "User update failed");
+                return Err(error_msg.into());
+            }
+            
+            // Validate new data on a copy, so the caller's user is untouched on failure
+            let new_username = self.validator.normalize_username(&new_username);
+            let mut updated = user.clone();
+            updated.set_username(new_username.clone());
+            updated.set_email(new_email.clone());
+            updated.touch(self.clock.now());
+            self.check(self.validator.validate_user(&updated), "User update failed")?;
+            
+            // Update user
+            self.audited(AuditAction::Updated, user, Some(&updated), "Failed to update user", || {
+                self.persist(&updated, "Failed to update user")
+            })?;
+            *user = updated;
+            
+            // Log activity
+            self.logger.log_user_activity(&new_username, "User updated");
+            
+            // Track analytics
+            self.analytics.track_user_update(&new_username);
+            
+            Ok(())
+        })
+    }
+    
+    // Replaces the optional profile fields; None clears a field
+    pub fn update_profile(&self, user: &mut User, phone: Option<&str>, display_name: Option<String>,
+                          locale: Option<String>) -> Result<(), UserServiceError> {
+        let _flow = user_flow_span("update_profile").entered();
+        self.metered("update_profile", || {
+            let phone = phone.map(|raw| self.parse_phone(raw)).transpose()?;
+            let mut updated = user.clone();
+            updated.set_phone(phone);
+            updated.set_display_name(display_name);
+            updated.set_locale(locale);
+            updated.touch(self.clock.now());
+            self.check(self.validator.validate_user(&updated), "Profile update failed")?;
+            
+            self.audited(AuditAction::Updated, user, Some(&updated), "Failed to update profile", || {
+                self.persist(&updated, "Failed to update profile")
+            })?;
+            *user = updated;
+            
+            self.logger.log_user_activity(user.username(), "Profile updated");
+            self.analytics.track_user_update(user.username());
+            Ok(())
+        })
+    }
+    
+    // The validator's phone rules, plus E.164 parsing in case the validator has none
+    fn parse_phone(&self, raw: &str) -> Result<PhoneNumber, UserServiceError> {
+        let mut report = self.validator.validate_phone(raw);
+        let parsed = PhoneNumber::parse(raw);
+        if let (true, Err(reason)) = (report.is_valid(), &parsed) {
+            report.add(ValidationFailure { field: "phone", rule: "e164", reason: reason.clone() });
+        }
+        self.check(report, "Profile update failed")?;
+        Ok(parsed?)
+    }
+    
+    // Texts the number on the user's profile
+    pub fn send_sms(&self, user: &User, message: &str) -> Result<bool, String> {
+        let result = match (user.phone(), &self.notification_service) {
+            (None, _) => Err(format!("{} has no phone number", user.id())),
+            (Some(_), None) => Err("No notification service configured".to_string()),
+            (Some(phone), Some(notification_service)) => notification_service.send_sms_notification(phone, message),
+        };
+        result.inspect_err(|e| self.logger.log_error(e, "Failed to send SMS"))
+    }
+    
+    // Lifecycle transitions: PendingVerification -> Active <-> Suspended, and any -> Deleted
+    pub fn verify_user(&self, user: &mut User) -> Result<(), String> {
+        self.metered("verify_user", || self.change_status(user, Some(UserStatus::PendingVerification), UserStatus::Active, "User verified"))
+    }
+    
+    pub fn suspend_user(&self, user: &mut User) -> Result<(), String> {
+        self.metered("suspend_user", || self.change_status(user, Some(UserStatus::Active), UserStatus::Suspended, "User suspended"))
+    }
+    
+    pub fn reactivate_user(&self, user: &mut User) -> Result<(), String> {
+        self.metered("reactivate_user", || self.change_status(user, Some(UserStatus::Suspended), UserStatus::Active, "User reactivated"))
+    }
+    
+    // Soft delete: the record is kept with status Deleted
+    pub fn delete_user(&self, user: &mut User) -> Result<(), String> {
+        self.metered("delete_user", || self.change_status(user, None, UserStatus::Deleted, "User deleted"))
+    }
+    
+    fn change_status(&self, user: &mut User, expected: Option<UserStatus>, next: UserStatus, activity: &str) -> Result<(), String> {
+        let mut changed = user.clone();
+        let result = match expected {
+            Some(expected) if user.status() != expected => {
+                Err(format!("{} is {}, expected {}", user.id(), user.status(), expected))
+            }
+            _ => changed.transition_to(next),
+        };
+        if let Err(e) = result {
+            self.logger.log_error(&e, activity);
+            return Err(e);
+        }
+        changed.touch(self.clock.now());
+        
+        self.audited(AuditAction::StatusChanged, user, Some(&changed), activity, || self.persist(&changed, activity))?;
+        *user = changed;
+        
+        self.logger.log_user_activity(user.username(), activity);
+        let metadata = HashMap::from([("status".to_string(), next.to_string())]);
+        self.analytics.track_user_event(user.username(), "status_changed", Some(metadata));
+        Ok(())
+    }
+    
+    // Writes an existing user back to the repository; a missing record is an error
+    fn persist(&self, user: &User, context: &str) -> Result<(), String> {
+        let result = match self.user_repo.update(user) {
+            Ok(true) => Ok(()),
+            Ok(false) => Err(format!("User not found: {}", user.id())),
+            Err(e) => Err(e),
+        };
+        result.inspect_err(|e| self.logger.log_error(e, context))
+    }
+    
+    // Data lifecycle: the repository, credential store and exporter do the work; each
+    // operation ends with an audit event that records only the user id.
+    //
+    // Anonymized fields are fresh random values, not derived from the old ones,
+    // so there is nothing to reverse.
+    pub fn anonymize_user(&self, user: &mut User) -> Result<(), String> {
+        let _flow = user_flow_span("anonymize_user").entered();
+        let token = self.ids.token(12);
+        let mut anonymized = user.clone();
+        anonymized.set_username(format!("anonymized-{}", token));
+        anonymized.set_email(format!("{}@anonymized.invalid", token));
+        anonymized.set_phone(None);
+        anonymized.set_display_name(None);
+        anonymized.touch(self.clock.now());
+        self.audited(AuditAction::Anonymized, user, Some(&anonymized), "Anonymization failed", || {
+            self.persist(&anonymized, "Anonymization failed")?;
+            self.remove_credentials(user.id(), "Anonymization failed")
+        })?;
+        *user = anonymized;
+        
+        self.audit(AuditAction::Anonymized, user.id());
+        Ok(())
+    }
+    
+    pub fn export_personal_data(&self, user: &User, exporter: &dyn UserExporter) -> Result<PersonalDataBundle, String> {
+        let _flow = user_flow_span("export_personal_data").entered();
+        let stored = self.user_repo
+            .find_by_id(&user.id().to_string())?
+            .ok_or_else(|| format!("User not found: {}", user.id()))?;
+        let bundle = PersonalDataBundle {
+            user_id: stored.id(),
+            generated_at: self.clock.now(),
+            content_type: exporter.content_type().to_string(),
+            profile: exporter.export_to_string(&[stored])?,
+            has_password: self.credentials.password_hash(user.id())?.is_some(),
+        };
+        
+        self.audit(AuditAction::PersonalDataExported, user.id());
+        Ok(bundle)
+    }
+    
+    // Unlike delete_user, the record is removed from the repository entirely
+    pub fn hard_delete_with_audit(&self, user: User) -> Result<(), String> {
+        let _flow = user_flow_span("hard_delete_user").entered();
+        self.audited(AuditAction::HardDeleted, &user, None, "Hard delete failed", || {
+            if !self.user_repo.delete(&user)? {
+                let error_msg = format!("User not found: {}", user.id());
+                self.logger.log_error(&error_msg, "Hard delete failed");
+                return Err(error_msg);
+            }
+            self.remove_credentials(user.id(), "Hard delete failed")
+        })?;
+        
+        self.audit(AuditAction::HardDeleted, user.id());
+        Ok(())
+    }
+    
+    fn remove_credentials(&self, user_id: UserId, context: &str) -> Result<(), String> {
+        self.credentials.remove_credentials(user_id).map(|_| ()).inspect_err(|e| self.logger.log_error(e, context))
+    }
+    
+    fn audit(&self, action: AuditAction, user_id: UserId) {
+        self.logger.log_audit(&AuditEvent { action, user_id, at: self.clock.now() });
+    }
+    
+    // Runs `write` and appends its audit entry as one unit. With an audit trail both
+    // happen in a repository transaction and the entry is appended last, so a change
+    // is never kept without its entry. Anonymize, hard delete and password changes
+    // record no `before`. Snapshots hold no personal data (see AuditRecord::snapshot),
+    // so no entry, earlier or later, still names an anonymized user.
+    fn audited(&self, action: AuditAction, user: &User, after: Option<&User>, context: &str,
+               write: impl FnOnce() -> Result<(), String>) -> Result<(), String> {
+        if self.audit.is_none() {
+            return write();
+        }
+        let before = matches!(action, AuditAction::Updated | AuditAction::StatusChanged).then_some(user);
+        self.in_transaction(context, || {
+            write()?;
+            self.record_change(action, user.id(), before, after)?;
+            Ok(())
+        })
+        .map_err(|e| e.to_string())
+    }
+    
+    fn record_change(&self, action: AuditAction, user_id: UserId, before: Option<&User>, after: Option<&User>) -> Result<(), String> {
+        let Some(ref audit) = self.audit else {
+            return Ok(());
+        };
+        let record = AuditRecord {
+            actor: audit.actor.clone(),
+            action,
+            user_id,
+            at: self.clock.now(),
+            before: before.map(AuditRecord::snapshot),
+            after: after.map(AuditRecord::snapshot),
+        };
+        audit.trail.append(record).map(|_| ()).inspect_err(|e| self.logger.log_error(e, "Failed to append audit entry"))
+    }
+    
+    // Password flows: the validator judges strength, the hasher hashes and the
+    // credential store keeps the result; the service only sequences them
+    pub fn register_with_password(&self, username: String, email: String, password: &str) -> Result<User, UserServiceError> {
+        let _flow = user_flow_span("register_with_password").entered();
+        self.metered("register_with_password", || {
+            self.check(self.validator.validate_password(password), "User registration failed")?;
+            let hash = self.hash(password, "User registration failed")?;
+            
+            let (user, welcome) = self.in_transaction("User registration failed", || {
+                let (user, welcome) = self.store_new_user(username, email)?;
+                // An account without credentials cannot log in, so this rolls back too
+                self.credentials.set_password_hash(user.id(), hash).inspect_err(|e| self.logger.log_error(e, "Failed to store credentials"))?;
+                self.record_change(AuditAction::Created, user.id(), None, Some(&user))?;
+                Ok((user, welcome))
+            })?;
+            self.announce_new_user(&user, welcome);
+            Ok(user)
+        })
+    }
+    
+    pub fn change_password(&self, user: &User, current_password: &str, new_password: &str) -> Result<(), UserServiceError> {
+        let _flow = user_flow_span("change_password").entered();
+        self.metered("change_password", || {
+            if !self.matches_stored_password(user, current_password)? {
+                let error_msg = format!("Current password is incorrect for {}", user.id());
+                self.logger.log_error(&error_msg, "Password change failed");
+                return Err(error_msg.into());
+            }
+            self.check(self.validator.validate_password(new_password), "Password change failed")?;
+            let hash = self.hash(new_password, "Password change failed")?;
+            
+            self.audited(AuditAction::PasswordChanged, user, None, "Password change failed", || {
+                self.credentials.set_password_hash(user.id(), hash).inspect_err(|e| self.logger.log_error(e, "Failed to store credentials"))
+            })?;
+            
+            self.logger.log_user_activity(user.username(), "Password changed");
+            self.analytics.track_user_event(user.username(), "password_changed", None);
+            if let Err(e) = self.send_email("password_changed", user) {
+                self.logger.log_error(&e, "Failed to send password change email");
+            }
+            Ok(())
+        })
+    }
+    
+    // Ok(false) for a wrong password or a user without credentials;
+    // Err when the account may not log in at all
+    pub fn verify_login(&self, user: &User, password: &str) -> Result<bool, String> {
+        if matches!(user.status(), UserStatus::Suspended | UserStatus::Deleted) {
+            let error_msg = format!("{} is {} and cannot log in", user.id(), user.status());
+            self.logger.log_error(&error_msg, "Login rejected");
+            return Err(error_msg);
+        }
+        
+        let verified = self.matches_stored_password(user, password)?;
+        if verified {
+            self.logger.log_user_activity(user.username(), "Logged in");
+            self.analytics.track_user_event(user.username(), "login", None);
+        } else {
+            self.logger.log_error(&format!("Invalid credentials for {}", user.id()), "Login failed");
+        }
+        Ok(verified)
+    }
+    
+    // Sessions: credentials are checked by verify_login, tokens are owned by the
+    // session service; the service only logs and tracks the outcome
+    pub fn login(&self, user: &User, password: &str) -> Result<String, String> {
+        let _flow = user_flow_span("login").entered();
+        self.metered("login", || {
+            if !self.verify_login(user, password)? {
+                return Err("Invalid username or password".to_string());
+            }
+            let token = self.sessions.create_session(user.id()).inspect_err(|e| self.logger.log_error(e, "Failed to create session"))?;
+            self.analytics.track_user_event(user.username(), "session_started", None);
+            Ok(token)
+        })
+    }
+    
+    pub fn logout(&self, user: &User, token: &str) -> Result<(), String> {
+        if self.sessions.validate_token(token)? != Some(user.id()) {
+            let error_msg = format!("No active session for {}", user.id());
+            self.logger.log_error(&error_msg, "Logout failed");
+            return Err(error_msg);
+        }
+        self.sessions.revoke(token)?;
+        self.logger.log_user_activity(user.username(), "Logged out");
+        self.analytics.track_user_event(user.username(), "session_ended", None);
+        Ok(())
+    }
+    
+    // Resolves a session token to the id of the user it was issued to
+    pub fn authenticate(&self, token: &str) -> Result<Option<UserId>, String> {
+        self.sessions.validate_token(token)
+    }
+    
+    // Content comes from the template renderer, delivery from the email service
+    // (or from the outbox processor, when an outbox is configured)
+    fn send_email(&self, template: &str, user: &User) -> Result<bool, String> {
+        let message = self.render_email(template, user)?;
+        self.deliver_email(message)
+    }
+    
+    fn render_email(&self, template: &str, user: &User) -> Result<EmailMessage, String> {
+        let values = HashMap::from([("username".to_string(), user.username().to_string())]);
+        self.templates.render(template, user.email(), &values)
+    }
+    
+    fn deliver_email(&self, message: EmailMessage) -> Result<bool, String> {
+        match self.outbox {
+            Some(ref outbox) => outbox.enqueue(SideEffect::Email(message)).map(|_| true),
+            None => self.email_service.send(&message),
+        }
+    }
+    
+    fn enqueue_welcome_effects(&self, outbox: &OutboxQueue, user: &User) {
+        let mut effects = vec![SideEffect::AnalyticsEvent {
+            username: user.username().to_string(),
+            event: "user_registered".to_string(),
+            metadata: None,
+        }];
+        if self.notification_service.is_some() {
+            effects.push(SideEffect::PushNotification {
+                username: user.username().to_string(),
+                message: "Welcome! Your account has been created.".to_string(),
+            });
+        }
+        for effect in effects {
+            if let Err(e) = outbox.enqueue(effect) {
+                self.logger.log_error(&e, "Failed to queue side effect");
+            }
+        }
+    }
+    
+    fn matches_stored_password(&self, user: &User, password: &str) -> Result<bool, String> {
+        match self.credentials.password_hash(user.id())? {
+            Some(hash) => self.password_hasher.verify_password(password, &hash),
+            None => Ok(false),
+        }
+    }
+    
+    // Turns a failed validation report into an error, logging it first
+    fn check(&self, report: ValidationReport, context: &str) -> Result<(), UserServiceError> {
+        if report.is_valid() {
+            return Ok(());
+        }
+        let error = UserServiceError::Invalid(report);
+        self.logger.log_error(&error.to_string(), context);
+        Err(error)
+    }
+    
+    fn hash(&self, password: &str, context: &str) -> Result<String, String> {
+        self.password_hasher.hash_password(password).inspect_err(|e| self.logger.log_error(e, context))
+    }
+    
+    // Every user, one page at a time, in id order
+    pub fn list_users(&self, page: Pagination) -> Result<Page<User>, String> {
+        self.metered("list_users", || {
+            self.user_repo.search("", page).inspect_err(|e| self.logger.log_error(e, "Failed to list users"))
+        })
+    }
+    
+    pub fn format_user(&self, user: &User) -> String {
+        self.formatter.format_user_for_display(user)
+    }
+    
+    pub fn get_user_api_data(&self, user: &User, version: ApiVersion) -> UserResponse {
+        self.formatter.format_user_for_api(user, version)
+    }
+    
+    // Creates a user from a JSON `UserDto` request body
+    pub fn create_user_from_json(&self, json: &str) -> Result<User, UserServiceError> {
+        let dto = UserDto::from_json(json)?;
+        self.create_user(dto.username, dto.email)
+    }
+    
+    // Swap a single collaborator, keeping every other one as configured
+    pub fn with_repository<R2: UserRepository>(self, user_repo: R2) -> CorrectUserService<R2, E, L, V, F, A, N, H, C, S, T> {
+        CorrectUserService {
+            user_repo,
+            email_service: self.email_service,
+            logger: self.logger,
+            validator: self.validator,
+            formatter: self.formatter,
+            analytics: self.analytics,
+            notification_service: self.notification_service,
+            password_hasher: self.password_hasher,
+            credentials: self.credentials,
+            sessions: self.sessions,
+            templates: self.templates,
+            outbox: self.outbox,
+            audit: self.audit,
+            metrics: self.metrics,
+            clock: self.clock,
+            ids: self.ids,
+        }
+    }
+    
+    pub fn with_email_service<E2: EmailService>(self, email_service: E2) -> CorrectUserService<R, E2, L, V, F, A, N, H, C, S, T> {
+        CorrectUserService {
+            user_repo: self.user_repo,
+            email_service,
+            logger: self.logger,
+            validator: self.validator,
+            formatter: self.formatter,
+            analytics: self.analytics,
+            notification_service: self.notification_service,
+            password_hasher: self.password_hasher,
+            credentials: self.credentials,
+            sessions: self.sessions,
+            templates: self.templates,
+            outbox: self.outbox,
+            audit: self.audit,
+            metrics: self.metrics,
+            clock: self.clock,
+            ids: self.ids,
+        }
+    }
+    
+    pub fn with_logger<L2: ActivityLogger>(self, logger: L2) -> CorrectUserService<R, E, L2, V, F, A, N, H, C, S, T> {
+        CorrectUserService {
+            user_repo: self.user_repo,
+            email_service: self.email_service,
+            logger,
+            validator: self.validator,
+            formatter: self.formatter,
+            analytics: self.analytics,
+            notification_service: self.notification_service,
+            password_hasher: self.password_hasher,
+            credentials: self.credentials,
+            sessions: self.sessions,
+            templates: self.templates,
+            outbox: self.outbox,
+            audit: self.audit,
+            metrics: self.metrics,
+            clock: self.clock,
+            ids: self.ids,
+        }
+    }
+    
+    pub fn with_validator<V2: UserValidator>(self, validator: V2) -> CorrectUserService<R, E, L, V2, F, A, N, H, C, S, T> {
+        CorrectUserService {
+            user_repo: self.user_repo,
+            email_service: self.email_service,
+            logger: self.logger,
+            validator,
+            formatter: self.formatter,
+            analytics: self.analytics,
+            notification_service: self.notification_service,
+            password_hasher: self.password_hasher,
+            credentials: self.credentials,
+            sessions: self.sessions,
+            templates: self.templates,
+            outbox: self.outbox,
+            audit: self.audit,
+            metrics: self.metrics,
+            clock: self.clock,
+            ids: self.ids,
+        }
+    }
+    
+    pub fn with_formatter<F2: UserFormatter>(self, formatter: F2) -> CorrectUserService<R, E, L, V, F2, A, N, H, C, S, T> {
+        CorrectUserService {
+            user_repo: self.user_repo,
+            email_service: self.email_service,
+            logger: self.logger,
+            validator: self.validator,
+            formatter,
+            analytics: self.analytics,
+            notification_service: self.notification_service,
+            password_hasher: self.password_hasher,
+            credentials: self.credentials,
+            sessions: self.sessions,
+            templates: self.templates,
+            outbox: self.outbox,
+            audit: self.audit,
+            metrics: self.metrics,
+            clock: self.clock,
+            ids: self.ids,
+        }
+    }
+    
+    pub fn with_analytics<A2: AnalyticsService>(self, analytics: A2) -> CorrectUserService<R, E, L, V, F, A2, N, H, C, S, T> {
+        CorrectUserService {
+            user_repo: self.user_repo,
+            email_service: self.email_service,
+            logger: self.logger,
+            validator: self.validator,
+            formatter: self.formatter,
+            analytics,
+            notification_service: self.notification_service,
+            password_hasher: self.password_hasher,
+            credentials: self.credentials,
+            sessions: self.sessions,
+            templates: self.templates,
+            outbox: self.outbox,
+            audit: self.audit,
+            metrics: self.metrics,
+            clock: self.clock,
+            ids: self.ids,
+        }
+    }
+    
+    pub fn with_notification_service<N2: NotificationService>(self, notification_service: Option<N2>) -> CorrectUserService<R, E, L, V, F, A, N2, H, C, S, T> {
+        CorrectUserService {
+            user_repo: self.user_repo,
+            email_service: self.email_service,
+            logger: self.logger,
+            validator: self.validator,
+            formatter: self.formatter,
+            analytics: self.analytics,
+            notification_service,
+            password_hasher: self.password_hasher,
+            credentials: self.credentials,
+            sessions: self.sessions,
+            templates: self.templates,
+            outbox: self.outbox,
+            audit: self.audit,
+            metrics: self.metrics,
+            clock: self.clock,
+            ids: self.ids,
+        }
+    }
+    
+    pub fn with_password_hasher<H2: PasswordHasher>(self, password_hasher: H2) -> CorrectUserService<R, E, L, V, F, A, N, H2, C, S, T> {
+        CorrectUserService {
+            user_repo: self.user_repo,
+            email_service: self.email_service,
+            logger: self.logger,
+            validator: self.validator,
+            formatter: self.formatter,
+            analytics: self.analytics,
+            notification_service: self.notification_service,
+            password_hasher,
+            credentials: self.credentials,
+            sessions: self.sessions,
+            templates: self.templates,
+            outbox: self.outbox,
+            audit: self.audit,
+            metrics: self.metrics,
+            clock: self.clock,
+            ids: self.ids,
+        }
+    }
+    
+    pub fn with_credential_store<C2: CredentialStore>(self, credentials: C2) -> CorrectUserService<R, E, L, V, F, A, N, H, C2, S, T> {
+        CorrectUserService {
+            user_repo: self.user_repo,
+            email_service: self.email_service,
+            logger: self.logger,
+            validator: self.validator,
+            formatter: self.formatter,
+            analytics: self.analytics,
+            notification_service: self.notification_service,
+            password_hasher: self.password_hasher,
+            credentials,
+            sessions: self.sessions,
+            templates: self.templates,
+            outbox: self.outbox,
+            audit: self.audit,
+            metrics: self.metrics,
+            clock: self.clock,
+            ids: self.ids,
+        }
+    }
+    
+    pub fn with_session_service<S2: SessionService>(self, sessions: S2) -> CorrectUserService<R, E, L, V, F, A, N, H, C, S2, T> {
+        CorrectUserService {
+            user_repo: self.user_repo,
+            email_service: self.email_service,
+            logger: self.logger,
+            validator: self.validator,
+            formatter: self.formatter,
+            analytics: self.analytics,
+            notification_service: self.notification_service,
+            password_hasher: self.password_hasher,
+            credentials: self.credentials,
+            sessions,
+            templates: self.templates,
+            outbox: self.outbox,
+            audit: self.audit,
+            metrics: self.metrics,
+            clock: self.clock,
+            ids: self.ids,
+        }
+    }
+    
+    pub fn with_template_renderer<T2: EmailTemplateRenderer>(self, templates: T2) -> CorrectUserService<R, E, L, V, F, A, N, H, C, S, T2> {
+        CorrectUserService {
+            user_repo: self.user_repo,
+            email_service: self.email_service,
+            logger: self.logger,
+            validator: self.validator,
+            formatter: self.formatter,
+            analytics: self.analytics,
+            notification_service: self.notification_service,
+            password_hasher: self.password_hasher,
+            credentials: self.credentials,
+            sessions: self.sessions,
+            templates,
+            outbox: self.outbox,
+            audit: self.audit,
+            metrics: self.metrics,
+            clock: self.clock,
+            ids: self.ids,
+        }
+    }
+}
+
+// Tests that user creation is all-or-nothing: a failure after the user is saved
+// leaves nothing behind in the repository
+#[cfg(test)]
+mod user_creation_rollback_tests {
+    use super::*;
+    use crate::{InMemoryUserRepository, UnitOfWork};
+    use std::sync::Arc;
+    use crate::testing::MockCredentialStore;
+    
+    #[test]
+    fn successful_creation_is_committed() {
+        let repository = Arc::new(InMemoryUserRepository::new());
+        let service = crate::create_user_service().with_repository(repository.clone());
+        let user = service.create_user("ada_l".to_string(), "ada@example.com".to_string()).unwrap();
+        assert!(repository.find_by_id(&user.id().to_string()).unwrap().is_some());
+        // The transaction is closed, so the repository accepts a new one
+        repository.begin().unwrap();
+        repository.rollback().unwrap();
+    }
+    
+    #[test]
+    fn unrenderable_welcome_email_rolls_back_the_user() {
+        let repository = Arc::new(InMemoryUserRepository::new());
+        let service = crate::create_user_service()
+            .with_repository(repository.clone())
+            .with_template_renderer(PlaceholderTemplateRenderer::new());
+        assert!(service.create_user("ada_l".to_string(), "ada@example.com".to_string()).is_err());
+        assert!(repository.find_all().unwrap().is_empty());
+    }
+    
+    #[test]
+    fn failed_credential_store_rolls_back_registration() {
+        let repository = Arc::new(InMemoryUserRepository::new());
+        let credentials = MockCredentialStore::default();
+        credentials.spy().fail_on("set_password_hash", "credential store is full");
+        let service = crate::compose! { passwords: fake }
+            .with_repository(repository.clone())
+            .with_credential_store(credentials);
+        let result = service.register_with_password("ada_l".to_string(), "ada@example.com".to_string(), "Correct-Horse-42");
+        assert!(result.is_err());
+        assert!(repository.find_all().unwrap().is_empty());
+    }
+}
+
+// Tests for the data lifecycle operations, reading audits back through `InMemoryLayer`
+#[cfg(test)]
+mod data_lifecycle_tests {
+    use super::*;
+    use crate::{CapturedEvent, InMemoryLayer, JsonExporter, SequentialIdGenerator};
+    
+    fn audits(events: &[CapturedEvent]) -> Vec<String> {
+        events.iter()
+            .filter(|event| event.target == "audit")
+            .map(|event| format!("{} {}", event.field("action").unwrap_or("?"), event.field("user_id").unwrap_or("?")))
+            .collect()
+    }
+    
+    fn register(service: &impl Fn(String, String) -> User, name: &str) -> User {
+        service(name.to_string(), format!("{}@example.com", name))
+    }
+    
+    #[test]
+    fn anonymization_leaves_nothing_to_reverse() {
+        let service = crate::compose! { logger: tracing, passwords: fake }.with_id_generator(SequentialIdGenerator::new());
+        let create = |name: String, email: String| service.register_with_password(name, email, "Correct-Horse-42").unwrap();
+        let mut ada = register(&create, "ada_l");
+        let mut twin = register(&create, "ada_l2");
+        
+        service.anonymize_user(&mut ada).unwrap();
+        service.anonymize_user(&mut twin).unwrap();
+        assert_eq!(ada.username(), "anonymized-000000000000000000000001", "tokens come from the id generator");
+        assert_eq!(ada.email(), "000000000000000000000001@anonymized.invalid");
+        assert_ne!(ada.username(), twin.username(), "anonymized values are fresh, not derived");
+        assert!(!service.verify_login(&ada, "Correct-Horse-42").unwrap(), "credentials are removed");
+        
+        // The stored record is anonymized too, not just the caller's copy
+        let bundle = service.export_personal_data(&ada, &JsonExporter::new()).unwrap();
+        assert!(!bundle.profile.contains("ada_l") && !bundle.has_password);
+        assert!(bundle.profile.contains(ada.username()));
+    }
+    
+    #[test]
+    fn every_operation_writes_an_audit() {
+        let service = crate::compose! { logger: tracing };
+        let mut user = service.create_user("grace_h".to_string(), "grace@example.com".to_string()).unwrap();
+        let id = user.id().to_string();
+        let (_, events) = InMemoryLayer::capture(|| {
+            service.export_personal_data(&user, &JsonExporter::new()).unwrap();
+            service.anonymize_user(&mut user).unwrap();
+            service.hard_delete_with_audit(user).unwrap();
+        });
+        assert_eq!(audits(&events), vec![
+            format!("personal_data_exported {}", id),
+            format!("anonymized {}", id),
+            format!("hard_deleted {}", id),
+        ]);
+        assert!(events.iter().all(|event| !format!("{:?}", event.fields).contains("grace")));
+    }
+    
+    #[test]
+    fn export_bundles_the_stored_record() {
+        let service = crate::compose! { passwords: fake };
+        let user = service.register_with_password("grace_h".to_string(), "grace@example.com".to_string(), "Correct-Horse-42").unwrap();
+        let bundle = service.export_personal_data(&user, &JsonExporter::new()).unwrap();
+        assert_eq!(bundle.content_type, "application/json");
+        assert!(bundle.has_password);
+        let profile: Vec<User> = serde_json::from_str(&bundle.profile).unwrap();
+        assert_eq!(profile[0].email(), "grace@example.com");
+        assert!(bundle.to_json().unwrap().contains("\"user_id\""));
+    }
+    
+    #[test]
+    fn hard_delete_removes_the_record_and_fails_without_audit_when_missing() {
+        let service = crate::compose! { logger: tracing };
+        let user = service.create_user("grace_h".to_string(), "grace@example.com".to_string()).unwrap();
+        service.hard_delete_with_audit(user.clone()).unwrap();
+        assert!(service.export_personal_data(&user, &JsonExporter::new()).is_err());
+        
+        let (result, events) = InMemoryLayer::capture(|| service.hard_delete_with_audit(user.clone()));
+        assert!(result.is_err());
+        assert!(audits(&events).is_empty());
+    }
+}
+
+// Tests for CorrectUserService::list_users over the mock repository
+#[cfg(test)]
+mod user_listing_tests {
+    use super::*;
+    use crate::testing::mock_user_service;
+    
+    #[test]
+    fn list_users_pages_in_creation_order() {
+        let (service, _mocks) = mock_user_service();
+        for name in ["ada_l", "grace_h", "hopper_g"] {
+            service.create_user(name.to_string(), format!("{}@example.com", name)).unwrap();
+        }
+        let first = service.list_users(Pagination::first(2)).unwrap();
+        let names: Vec<_> = first.items.iter().map(|user| user.username().to_string()).collect();
+        assert_eq!(names, vec!["ada_l", "grace_h"]);
+        assert_eq!(first.total, 3);
+        let last = service.list_users(first.next_page().unwrap()).unwrap();
+        assert_eq!(last.items.len(), 1);
+        assert_eq!(last.next_page(), None);
+    }
+    
+    #[test]
+    fn list_users_on_an_empty_repository_is_an_empty_page() {
+        let (service, _mocks) = mock_user_service();
+        let page = service.list_users(Pagination::default()).unwrap();
+        assert!(page.is_empty());
+        assert_eq!(page.total, 0);
+        assert_eq!(page.next_page(), None);
+    }
+    
+    #[test]
+    fn failed_listing_is_logged() {
+        let (service, mocks) = mock_user_service();
+        mocks.repository.spy().fail_on("search", "replica offline");
+        assert_eq!(service.list_users(Pagination::default()).unwrap_err(), "replica offline");
+        assert_eq!(mocks.logger.errors(), vec![("Failed to list users".to_string(), "replica offline".to_string())]);
+    }
+}
+
+// Tests for phone numbers, profile validation, locale-aware display and SMS
+#[cfg(test)]
+mod user_profile_tests {
+    use super::*;
+    use crate::PhoneValidator;
+    use chrono::{TimeZone, Utc};
+    use crate::testing::mock_user_service;
+    
+    fn user_with_locale(locale: Option<&str>) -> User {
+        let joined = Utc.with_ymd_and_hms(2024, 3, 9, 12, 0, 0).unwrap();
+        User::restore(UserId::generate(), "ada_l".to_string(), "ada@example.com".to_string(),
+                      UserStatus::Active, joined, joined)
+            .with_profile(None, Some("Ada Lovelace".to_string()), locale.map(str::to_string))
+    }
+    
+    #[test]
+    fn phone_input_is_normalized_to_e164() {
+        let validator = PhoneValidator::new();
+        assert_eq!(validator.parse("+1 (415) 555-0132").unwrap().as_str(), "+14155550132");
+        assert_eq!(validator.parse("0049 30.1234567").unwrap().as_str(), "+49301234567");
+        assert_eq!(PhoneNumber::parse("+447946095800").unwrap().to_string(), "+447946095800");
+    }
+    
+    #[test]
+    fn invalid_phone_numbers_are_rejected() {
+        let validator = PhoneValidator::new();
+        for (input, reason) in [
+            ("415 555 0132", "must start with + and a country code"),
+            ("+1 415 CALL-NOW", "must contain only digits after the +"),
+            ("+0 415 555 0132", "country code cannot start with 0"),
+            ("+12345", "must have 7 to 15 digits, not 5"),
+            ("+1234567890123456", "must have 7 to 15 digits, not 16"),
+            ("", "must start with + and a country code"),
+        ] {
+            assert_eq!(validator.parse(input), Err(reason.to_string()), "{:?}", input);
+        }
+        let report = DefaultUserValidator::new().validate_phone("555-0132");
+        assert_eq!(report.failures()[0].rule, "e164");
+        assert!(serde_json::from_str::<PhoneNumber>("\"12345\"").is_err());
+    }
+    
+    #[test]
+    fn display_name_and_locale_are_validated() {
+        let validator = DefaultUserValidator::new();
+        let mut user = user_with_locale(Some("de_DE"));
+        assert_eq!(validator.validate_user(&user).failures()[0].field, "locale");
+        user.set_locale(Some("de-DE".to_string()));
+        user.set_display_name(Some(String::new()));
+        assert_eq!(validator.validate_user(&user).failures()[0].field, "display_name");
+        user.set_display_name(None);
+        assert!(validator.validate_user(&user).is_valid());
+    }
+    
+    #[test]
+    fn display_follows_the_users_locale() {
+        let formatter = DefaultUserFormatter::new();
+        for (locale, expected) in [
+            (None, "Ada Lovelace (ada@example.com)"),
+            (Some("en-US"), "Ada Lovelace (ada@example.com), member since 03/09/2024"),
+            (Some("en-GB"), "Ada Lovelace (ada@example.com), member since 09/03/2024"),
+            (Some("de-DE"), "Ada Lovelace (ada@example.com), Mitglied seit 09.03.2024"),
+            (Some("fr"), "Ada Lovelace (ada@example.com), membre depuis le 09/03/2024"),
+            (Some("ja-JP"), "Ada Lovelace (ada@example.com), 2024年03月09日から利用"),
+            (Some("pt-BR"), "Ada Lovelace (ada@example.com), member since 2024-03-09"),
+        ] {
+            assert_eq!(formatter.format_user_for_display(&user_with_locale(locale)), expected);
+        }
+        let plain = User::new("grace_h".to_string(), "grace@example.com".to_string());
+        assert_eq!(formatter.format_user_for_display(&plain), "grace_h (grace@example.com)");
+    }
+    
+    #[test]
+    fn update_profile_stores_a_parsed_phone() {
+        let (service, mocks) = mock_user_service();
+        let mut ada = service.create_user("ada_l".to_string(), "ada@example.com".to_string()).unwrap();
+        service.update_profile(&mut ada, Some("+1 415 555 0132"), Some("Ada".to_string()), Some("en-US".to_string())).unwrap();
+        assert_eq!(ada.phone().map(PhoneNumber::as_str), Some("+14155550132"));
+        assert_eq!(mocks.repository.stored()[0].display_name(), Some("Ada"));
+        
+        service.anonymize_user(&mut ada).unwrap();
+        assert_eq!((ada.phone(), ada.display_name(), ada.locale()), (None, None, Some("en-US")));
+    }
+    
+    #[test]
+    fn update_profile_rejects_an_invalid_phone() {
+        let (service, mocks) = mock_user_service();
+        let mut ada = service.create_user("ada_l".to_string(), "ada@example.com".to_string()).unwrap();
+        match service.update_profile(&mut ada, Some("call me"), None, None) {
+            Err(UserServiceError::Invalid(report)) => assert_eq!(report.failures()[0].field, "phone"),
+            other => panic!("expected an invalid phone, got {:?}", other),
+        }
+        assert_eq!(ada.phone(), None);
+        assert_eq!(mocks.repository.spy().calls_to("update"), 0);
+    }
+    
+    #[test]
+    fn sms_goes_to_the_profile_phone() {
+        let (service, mocks) = mock_user_service();
+        let mut ada = service.create_user("ada_l".to_string(), "ada@example.com".to_string()).unwrap();
+        assert!(service.send_sms(&ada, "Your code is 123456").is_err());
+        assert_eq!(mocks.logger.errors().last().unwrap().0, "Failed to send SMS");
+        
+        service.update_profile(&mut ada, Some("+44 7946 095800"), None, None).unwrap();
+        assert_eq!(service.send_sms(&ada, "Your code is 123456"), Ok(true));
+        assert_eq!(mocks.notifications.spy().calls(), vec!["send_push_notification(ada_l)", "send_sms_notification(+447946095800)"]);
+    }
+}
+
+// Tests for the audit trail: what the service records, queries over it, and
+// detection of tampered chains
+#[cfg(test)]
+mod audit_trail_tests {
+    use super::*;
+    use crate::{personal_data_fingerprint, verify_audit_chain, AuditEntry, InMemoryAuditTrail};
+    use std::sync::Arc;
+    use chrono::{Duration, Utc};
+    use crate::testing::{mock_user_service, test_epoch, MockUserService, Mocks};
+    
+    fn audited_service() -> (MockUserService, Mocks, Arc<InMemoryAuditTrail>) {
+        let (service, mocks) = mock_user_service();
+        let trail = Arc::new(InMemoryAuditTrail::new());
+        (service.with_audit_trail(trail.clone(), "admin@example.com"), mocks, trail)
+    }
+    
+    fn record(user: &User, at: chrono::DateTime<Utc>) -> AuditRecord {
+        AuditRecord { actor: "admin".to_string(), action: AuditAction::Created, user_id: user.id(), at, before: None, after: Some(user.clone()) }
+    }
+    
+    fn tampered_chain() -> Vec<AuditEntry> {
+        let (service, _mocks, trail) = audited_service();
+        let mut ada = service.create_user("ada_l".to_string(), "ada@example.com".to_string()).unwrap();
+        service.update_user(&mut ada, "ada_lovelace".to_string(), "ada@example.com".to_string()).unwrap();
+        service.verify_user(&mut ada).unwrap();
+        trail.entries().unwrap()
+    }
+    
+    #[test]
+    fn every_change_is_recorded_with_actor_and_snapshots() {
+        let (service, _mocks, trail) = audited_service();
+        let mut ada = service.create_user("ada_l".to_string(), "ada@example.com".to_string()).unwrap();
+        service.update_user(&mut ada, "ada_lovelace".to_string(), "ada@example.com".to_string()).unwrap();
+        service.verify_user(&mut ada).unwrap();
+        service.anonymize_user(&mut ada).unwrap();
+        service.hard_delete_with_audit(ada.clone()).unwrap();
+        
+        let entries = trail.entries().unwrap();
+        let actions: Vec<_> = entries.iter().map(|entry| entry.record.action).collect();
+        assert_eq!(actions, vec![AuditAction::Created, AuditAction::Updated, AuditAction::StatusChanged,
+                                 AuditAction::Anonymized, AuditAction::HardDeleted]);
+        assert!(entries.iter().all(|entry| entry.record.actor == "admin@example.com" && entry.record.user_id == ada.id()));
+        let update = &entries[1].record;
+        assert_eq!(update.before.as_ref().unwrap().username(), personal_data_fingerprint(ada.id(), "ada_l"));
+        assert_eq!(update.after.as_ref().unwrap().username(), personal_data_fingerprint(ada.id(), "ada_lovelace"));
+        assert!(entries[3].record.before.is_none(), "anonymization keeps no copy of the erased data");
+        assert!(entries[4].record.after.is_none());
+        assert_eq!(trail.verify(), Ok(5));
+    }
+    
+    #[test]
+    fn anonymized_user_leaves_no_personal_data_in_the_trail() {
+        let (service, _mocks, trail) = audited_service();
+        let mut ada = service.create_user("ada_l".to_string(), "ada@example.com".to_string()).unwrap();
+        service.update_profile(&mut ada, Some("+1 415 555 0132"), Some("Ada Lovelace".to_string()), Some("en-GB".to_string())).unwrap();
+        service.update_user(&mut ada, "ada_lovelace".to_string(), "countess@example.com".to_string()).unwrap();
+        service.anonymize_user(&mut ada).unwrap();
+        
+        let entries = trail.entries_for_user(ada.id()).unwrap();
+        assert_eq!(entries.len(), 4);
+        let stored = serde_json::to_string(&entries).unwrap();
+        for personal in ["ada_l", "ada@example.com", "4155550132", "Ada Lovelace", "countess"] {
+            assert!(!stored.contains(personal), "{} is still in the trail", personal);
+        }
+        let profile = entries[1].record.after.as_ref().unwrap();
+        assert_eq!(profile.locale(), Some("en-GB"), "non-personal fields are kept");
+        assert_eq!(profile.updated_at(), entries[1].record.at);
+        assert_eq!(trail.verify(), Ok(4));
+    }
+    
+    #[test]
+    fn entries_are_stamped_by_the_service_clock() {
+        let (service, mocks, trail) = audited_service();
+        let mut ada = service.create_user("ada_l".to_string(), "ada@example.com".to_string()).unwrap();
+        mocks.clock.advance(Duration::minutes(5));
+        service.update_user(&mut ada, "ada_lovelace".to_string(), "ada@example.com".to_string()).unwrap();
+        
+        assert_eq!(ada.id().to_string(), "user-1");
+        assert_eq!((ada.created_at(), ada.updated_at()), (test_epoch(), test_epoch() + Duration::minutes(5)));
+        let stamps: Vec<_> = trail.entries().unwrap().iter().map(|entry| entry.record.at).collect();
+        assert_eq!(stamps, vec![test_epoch(), test_epoch() + Duration::minutes(5)]);
+    }
+    
+    #[test]
+    fn failed_changes_leave_no_entry() {
+        let (service, mocks, trail) = audited_service();
+        let mut ada = service.create_user("ada_l".to_string(), "ada@example.com".to_string()).unwrap();
+        mocks.repository.spy().fail_on("update", "disk full");
+        mocks.repository.spy().fail_on("save", "disk full");
+        assert!(service.update_user(&mut ada, "ada_lovelace".to_string(), "ada@example.com".to_string()).is_err());
+        assert!(service.create_user("grace_h".to_string(), "grace@example.com".to_string()).is_err());
+        assert_eq!(trail.entries().unwrap().len(), 1);
+    }
+    
+    #[test]
+    fn failed_append_rolls_the_change_back() {
+        struct Unavailable;
+        impl AuditTrail for Unavailable {
+            fn append(&self, _record: AuditRecord) -> Result<AuditEntry, String> {
+                Err("audit store unavailable".to_string())
+            }
+            fn entries(&self) -> Result<Vec<AuditEntry>, String> {
+                Ok(Vec::new())
+            }
+        }
+        let (service, mocks) = mock_user_service();
+        let service = service.with_audit_trail(Arc::new(Unavailable), "admin");
+        assert!(service.create_user("ada_l".to_string(), "ada@example.com".to_string()).is_err());
+        assert!(mocks.repository.stored().is_empty());
+        assert!(mocks.email.sent().is_empty());
+        assert_eq!(mocks.logger.errors()[0], ("Failed to append audit entry".to_string(), "audit store unavailable".to_string()));
+    }
+    
+    #[test]
+    fn entries_can_be_queried_by_user_and_time_range() {
+        let trail = InMemoryAuditTrail::new();
+        let ada = User::new("ada_l".to_string(), "ada@example.com".to_string());
+        let grace = User::new("grace_h".to_string(), "grace@example.com".to_string());
+        let start = test_epoch();
+        trail.append(record(&ada, start)).unwrap();
+        trail.append(record(&grace, start + Duration::hours(1))).unwrap();
+        trail.append(record(&ada, start + Duration::hours(2))).unwrap();
+        
+        let for_ada: Vec<_> = trail.entries_for_user(ada.id()).unwrap().iter().map(|entry| entry.sequence).collect();
+        assert_eq!(for_ada, vec![0, 2]);
+        let window = trail.entries_between(start + Duration::hours(1), start + Duration::hours(2)).unwrap();
+        assert_eq!(window.len(), 1);
+        assert_eq!(window[0].record.user_id, grace.id());
+        assert!(trail.entries_between(start - Duration::hours(2), start).unwrap().is_empty());
+    }
+    
+    #[test]
+    fn an_empty_or_untouched_chain_verifies() {
+        assert_eq!(verify_audit_chain(&[]), Ok(0));
+        assert_eq!(verify_audit_chain(&tampered_chain()), Ok(3));
+    }
+    
+    #[test]
+    fn altered_entries_are_detected() {
+        let mut entries = tampered_chain();
+        entries[1].record.actor = "someone else".to_string();
+        assert_eq!(verify_audit_chain(&entries), Err("Audit entry 1 has been altered".to_string()));
+    }
+    
+    #[test]
+    fn rehashing_an_altered_entry_breaks_the_next_link() {
+        let mut entries = tampered_chain();
+        let mut altered = entries[0].record.clone();
+        altered.actor = "someone else".to_string();
+        entries[0] = InMemoryAuditTrail::new().append(altered).unwrap();
+        assert_eq!(verify_audit_chain(&entries), Err("Audit entry 1 does not link to the entry before it".to_string()));
+    }
+    
+    #[test]
+    fn dropped_and_reordered_entries_are_detected() {
+        let mut entries = tampered_chain();
+        entries.remove(1);
+        assert_eq!(verify_audit_chain(&entries), Err("Expected audit entry 1, found 2".to_string()));
+        let mut entries = tampered_chain();
+        entries.swap(1, 2);
+        assert!(verify_audit_chain(&entries).is_err());
+    }
+}
+
+// Tests for ids, statuses and the account lifecycle: every transition the
+// status table allows or refuses, and the same paths through the service
+#[cfg(test)]
+mod lifecycle_tests {
+    use super::*;
+    use crate::testing::{mock_user_service, test_epoch, MockUserService, Mocks};
+    use UserStatus::*;
+    
+    const STATUSES: [UserStatus; 4] = [PendingVerification, Active, Suspended, Deleted];
+    
+    fn created(service: &MockUserService) -> User {
+        service.create_user("ada_l".to_string(), "ada@example.com".to_string()).unwrap()
+    }
+    
+    fn stored_status(mocks: &Mocks, user: &User) -> UserStatus {
+        mocks.repository.stored().iter().find(|stored| stored.id() == user.id()).unwrap().status()
+    }
+    
+    #[test]
+    fn user_ids_round_trip_through_text_and_serde() {
+        let id = UserId::generate();
+        assert_eq!(id.to_string().parse::<UserId>(), Ok(id));
+        assert_eq!(serde_json::to_string(&id).unwrap(), format!("\"{}\"", id));
+        assert_eq!(serde_json::from_str::<UserId>(&format!("\"{}\"", id)).unwrap(), id);
+        for bad in ["", "user-", "user-x", "42", "member-7"] {
+            assert!(bad.parse::<UserId>().is_err(), "{}", bad);
+        }
+        assert!(UserId::generate() > id);
+    }
+    
+    #[test]
+    fn statuses_round_trip_through_text() {
+        for status in STATUSES {
+            assert_eq!(status.to_string().parse::<UserStatus>(), Ok(status));
+        }
+        assert!("banned".parse::<UserStatus>().is_err());
+    }
+    
+    #[test]
+    fn transition_table_allows_exactly_the_lifecycle_edges() {
+        let allowed = [
+            (PendingVerification, Active),
+            (PendingVerification, Deleted),
+            (Active, Suspended),
+            (Active, Deleted),
+            (Suspended, Active),
+            (Suspended, Deleted),
+        ];
+        for from in STATUSES {
+            for to in STATUSES {
+                assert_eq!(from.can_transition_to(to), allowed.contains(&(from, to)), "{} -> {}", from, to);
+            }
+        }
+    }
+    
+    #[test]
+    fn refused_transition_leaves_the_user_unchanged() {
+        let mut user = User::create(UserId::generate(), "ada".to_string(), "ada@example.com".to_string(), test_epoch());
+        assert!(user.transition_to(Suspended).is_err());
+        assert_eq!(user.status(), PendingVerification);
+        assert_eq!(user.updated_at(), test_epoch());
+        
+        user.transition_to(Active).unwrap();
+        assert_eq!(user.status(), Active);
+        assert!(user.updated_at() > test_epoch());
+    }
+    
+    #[test]
+    fn service_walks_the_whole_lifecycle_and_persists_each_step() {
+        let (service, mocks) = mock_user_service();
+        let mut user = created(&service);
+        assert_eq!(user.status(), PendingVerification);
+        
+        service.verify_user(&mut user).unwrap();
+        assert_eq!(stored_status(&mocks, &user), Active);
+        service.suspend_user(&mut user).unwrap();
+        assert_eq!(stored_status(&mocks, &user), Suspended);
+        service.reactivate_user(&mut user).unwrap();
+        assert_eq!(stored_status(&mocks, &user), Active);
+        service.delete_user(&mut user).unwrap();
+        assert_eq!(stored_status(&mocks, &user), Deleted);
+        
+        let activities: Vec<String> = mocks.logger.activities().into_iter().map(|(_, activity)| activity).collect();
+        assert_eq!(activities, vec!["User created", "User verified", "User suspended", "User reactivated", "User deleted"]);
+    }
+    
+    #[test]
+    fn pending_users_can_be_deleted_without_verifying() {
+        let (service, mocks) = mock_user_service();
+        let mut user = created(&service);
+        service.delete_user(&mut user).unwrap();
+        assert_eq!(stored_status(&mocks, &user), Deleted);
+    }
+    
+    #[test]
+    fn steps_out_of_order_are_refused_and_logged() {
+        let (service, mocks) = mock_user_service();
+        let mut user = created(&service);
+        
+        assert!(service.suspend_user(&mut user).is_err());
+        assert!(service.reactivate_user(&mut user).is_err());
+        service.verify_user(&mut user).unwrap();
+        assert!(service.verify_user(&mut user).is_err());
+        
+        service.delete_user(&mut user).unwrap();
+        assert!(service.reactivate_user(&mut user).is_err());
+        assert!(service.delete_user(&mut user).is_err());
+        
+        assert_eq!(user.status(), Deleted);
+        assert_eq!(stored_status(&mocks, &user), Deleted);
+        assert_eq!(mocks.logger.errors().len(), 5);
+    }
+    
+    #[test]
+    fn failed_write_keeps_the_previous_status() {
+        let (service, mocks) = mock_user_service();
+        let mut user = created(&service);
+        mocks.repository.spy().fail_on("update", "disk full");
+        assert!(service.verify_user(&mut user).is_err());
+        assert_eq!(user.status(), PendingVerification);
+        assert_eq!(stored_status(&mocks, &user), PendingVerification);
+    }
+}
+
+// Tests for the login and logout flows: credentials checked first, then
+// sessions issued and revoked through the SessionService
+#[cfg(test)]
+mod login_tests {
+    use super::*;
+    use crate::testing::{mock_user_service, MockUserService};
+    
+    fn registered(service: &MockUserService) -> User {
+        service.register_with_password("ada_l".to_string(), "ada@example.com".to_string(), "Secret123").unwrap()
+    }
+    
+    #[test]
+    fn login_issues_a_token_that_authenticates_the_user() {
+        let (service, mocks) = mock_user_service();
+        let user = registered(&service);
+        let token = service.login(&user, "Secret123").unwrap();
+        assert_eq!(service.authenticate(&token), Ok(Some(user.id())));
+        assert_eq!(mocks.analytics.spy().calls_to("track_user_event"), 2);
+        assert!(mocks.analytics.spy().calls().contains(&"track_user_event(ada_l, session_started)".to_string()));
+    }
+    
+    #[test]
+    fn wrong_password_gets_no_session() {
+        let (service, mocks) = mock_user_service();
+        let user = registered(&service);
+        assert_eq!(service.login(&user, "Secret124"), Err("Invalid username or password".to_string()));
+        assert_eq!(mocks.sessions.spy().calls_to("create_session"), 0);
+        assert!(mocks.logger.errors().iter().any(|(context, _)| context == "Login failed"));
+    }
+    
+    #[test]
+    fn user_without_credentials_cannot_log_in() {
+        let (service, mocks) = mock_user_service();
+        let user = service.create_user("grace_h".to_string(), "grace@example.com".to_string()).unwrap();
+        assert_eq!(service.verify_login(&user, "anything"), Ok(false));
+        assert!(service.login(&user, "anything").is_err());
+        assert_eq!(mocks.sessions.spy().calls_to("create_session"), 0);
+    }
+    
+    #[test]
+    fn suspended_and_deleted_users_are_refused_before_the_password_is_checked() {
+        let (service, mocks) = mock_user_service();
+        let mut user = registered(&service);
+        service.verify_user(&mut user).unwrap();
+        service.suspend_user(&mut user).unwrap();
+        assert!(service.login(&user, "Secret123").is_err());
+        service.delete_user(&mut user).unwrap();
+        assert!(service.login(&user, "Secret123").is_err());
+        assert_eq!(mocks.passwords.spy().calls_to("verify_password"), 0);
+        
+        let mut reactivated = registered(&service);
+        service.verify_user(&mut reactivated).unwrap();
+        service.suspend_user(&mut reactivated).unwrap();
+        service.reactivate_user(&mut reactivated).unwrap();
+        assert!(service.login(&reactivated, "Secret123").is_ok());
+    }
+    
+    #[test]
+    fn logout_revokes_only_the_given_session() {
+        let (service, mocks) = mock_user_service();
+        let user = registered(&service);
+        let laptop = service.login(&user, "Secret123").unwrap();
+        let phone = service.login(&user, "Secret123").unwrap();
+        
+        service.logout(&user, &laptop).unwrap();
+        assert_eq!(service.authenticate(&laptop), Ok(None));
+        assert_eq!(service.authenticate(&phone), Ok(Some(user.id())));
+        assert!(service.logout(&user, &laptop).is_err());
+        assert!(mocks.logger.activities().contains(&("ada_l".to_string(), "Logged out".to_string())));
+    }
+    
+    #[test]
+    fn a_user_cannot_log_out_someone_elses_session() {
+        let (service, _) = mock_user_service();
+        let ada = registered(&service);
+        let grace = service.register_with_password("grace_h".to_string(), "grace@example.com".to_string(), "Secret456").unwrap();
+        let token = service.login(&ada, "Secret123").unwrap();
+        assert!(service.logout(&grace, &token).is_err());
+        assert_eq!(service.authenticate(&token), Ok(Some(ada.id())));
+    }
+    
+    #[test]
+    fn changed_password_is_the_only_one_that_logs_in() {
+        let (service, _) = mock_user_service();
+        let user = registered(&service);
+        assert!(service.change_password(&user, "wrong", "Newpass456").is_err());
+        service.change_password(&user, "Secret123", "Newpass456").unwrap();
+        assert!(service.login(&user, "Secret123").is_err());
+        assert!(service.login(&user, "Newpass456").is_ok());
+    }
+}
+
+// Factory function to create a fully configured user service
+pub fn create_user_service() -> CorrectUserService<
+    DatabaseUserRepository,
+    SMTPEmailService,
+    FileActivityLogger,
+    DefaultUserValidator,
+    DefaultUserFormatter,
+    GoogleAnalyticsService,
+    FirebaseNotificationService,
+    Argon2PasswordHasher,
+    InMemoryCredentialStore,
+    InMemorySessionService,
+    PlaceholderTemplateRenderer,
+> {
+    let user_repo = DatabaseUserRepository::new();
+    let email_service = SMTPEmailService::new("smtp.example.com".to_string());
+    let logger = FileActivityLogger::new(default_log_path());
+    let validator = DefaultUserValidator::new();
+    let formatter = DefaultUserFormatter::new();
+    let analytics = GoogleAnalyticsService::new("GA-XXXXX-X".to_string());
+    let notification_service = FirebaseNotificationService::new("firebase-api-key".to_string());
+    
+    CorrectUserService::new(
+        user_repo,
+        email_service,
+        logger,
+        validator,
+        formatter,
+        analytics,
+        Some(notification_service),
+    )
+}
+
+pub type BoxedUserService = CorrectUserService<
+    Box<dyn UserRepository>,
+    Box<dyn EmailService>,
+    Box<dyn ActivityLogger>,
+    Box<dyn UserValidator>,
+    Box<dyn UserFormatter>,
+    Box<dyn AnalyticsService>,
+    Box<dyn NotificationService>,
+    Box<dyn PasswordHasher>,
+    Box<dyn CredentialStore>,
+    Box<dyn SessionService>,
+    Box<dyn EmailTemplateRenderer>,
+>;
+
+// Type-erased CorrectUserService: every collaborator is a trait object, so services
+// wired differently share one type. They can sit in one Vec, and collaborators
+// can be replaced at runtime. Each call costs a virtual dispatch; the orchestration
+// is the same code, reached through Deref.
+pub struct DynUserService {
+    inner: BoxedUserService,
+}
+
+impl DynUserService {
+    pub fn replace_repository(&mut self, user_repo: impl UserRepository + 'static) {
+        self.inner.user_repo = Box::new(user_repo);
+    }
+    
+    pub fn replace_email_service(&mut self, email_service: impl EmailService + 'static) {
+        self.inner.email_service = Box::new(email_service);
+    }
+    
+    pub fn replace_logger(&mut self, logger: impl ActivityLogger + 'static) {
+        self.inner.logger = Box::new(logger);
+    }
+    
+    pub fn replace_notification_service(&mut self, notification_service: Option<Box<dyn NotificationService>>) {
+        self.inner.notification_service = notification_service;
+    }
+}
+
+impl std::ops::Deref for DynUserService {
+    type Target = BoxedUserService;
+    
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl<R, E, L, V, F, A, N, H, C, S, T> From<CorrectUserService<R, E, L, V, F, A, N, H, C, S, T>> for DynUserService
+where
+    R: UserRepository + 'static,
+    E: EmailService + 'static,
+    L: ActivityLogger + 'static,
+    V: UserValidator + 'static,
+    F: UserFormatter + 'static,
+    A: AnalyticsService + 'static,
+    N: NotificationService + 'static,
+    H: PasswordHasher + 'static,
+    C: CredentialStore + 'static,
+    S: SessionService + 'static,
+    T: EmailTemplateRenderer + 'static,
+{
+    fn from(service: CorrectUserService<R, E, L, V, F, A, N, H, C, S, T>) -> Self {
+        DynUserService {
+            inner: CorrectUserService {
+                user_repo: Box::new(service.user_repo),
+                email_service: Box::new(service.email_service),
+                logger: Box::new(service.logger),
+                validator: Box::new(service.validator),
+                formatter: Box::new(service.formatter),
+                analytics: Box::new(service.analytics),
+                notification_service: service
+                    .notification_service
+                    .map(|notifications| Box::new(notifications) as Box<dyn NotificationService>),
+                password_hasher: Box::new(service.password_hasher),
+                credentials: Box::new(service.credentials),
+                sessions: Box::new(service.sessions),
+                templates: Box::new(service.templates),
+                outbox: service.outbox,
+                audit: service.audit,
+                metrics: service.metrics,
+                clock: service.clock,
+                ids: service.ids,
+            },
+        }
+    }
+}
+
+// Example tests built on `testing::mock_user_service`: one collaborator fails and
+// the rest of the flow is checked through the other doubles
+#[cfg(test)]
+mod partial_failure_tests {
+    use super::*;
+    use crate::testing::{mock_user_service, MockUserService};
+    
+    fn create(service: &MockUserService) -> Result<User, UserServiceError> {
+        service.create_user("ada_l".to_string(), "ada@example.com".to_string())
+    }
+    
+    #[test]
+    fn happy_path_touches_every_collaborator_once() {
+        let (service, mocks) = mock_user_service();
+        create(&service).unwrap();
+        assert_eq!(mocks.repository.spy().calls(), vec!["begin()".to_string(), format!("save({})", mocks.repository.stored()[0].id()), "commit()".to_string()]);
+        assert_eq!(mocks.email.sent()[0].subject, "welcome");
+        assert_eq!(mocks.analytics.spy().calls(), vec!["track_user_registration(ada_l)"]);
+        assert_eq!(mocks.notifications.spy().calls_to("send_push_notification"), 1);
+        assert_eq!(mocks.logger.activities(), vec![("ada_l".to_string(), "User created".to_string())]);
+    }
+    
+    #[test]
+    fn email_outage_is_logged_but_does_not_fail_creation() {
+        let (service, mocks) = mock_user_service();
+        mocks.email.spy().fail_all("smtp down");
+        create(&service).unwrap();
+        assert!(mocks.email.sent().is_empty());
+        assert_eq!(mocks.repository.stored().len(), 1);
+        assert_eq!(mocks.logger.errors(), vec![("Failed to send welcome email".to_string(), "smtp down".to_string())]);
+        assert_eq!(mocks.notifications.spy().calls_to("send_push_notification"), 1, "later steps still run");
+    }
+    
+    #[test]
+    fn notification_outage_is_logged_but_does_not_fail_creation() {
+        let (service, mocks) = mock_user_service();
+        mocks.notifications.spy().fail_all("push gateway down");
+        create(&service).unwrap();
+        assert_eq!(mocks.email.sent().len(), 1);
+        assert_eq!(mocks.logger.errors()[0].0, "Failed to send push notification");
+    }
+    
+    #[test]
+    fn failed_save_stops_before_any_side_effect() {
+        let (service, mocks) = mock_user_service();
+        mocks.repository.spy().fail_on("save", "disk full");
+        assert!(create(&service).is_err());
+        assert_eq!(mocks.repository.spy().calls_to("rollback"), 1);
+        assert_eq!(mocks.templates.spy().calls_to("render"), 0);
+        assert!(mocks.email.sent().is_empty());
+        assert!(mocks.analytics.spy().calls().is_empty());
+        assert!(mocks.logger.activities().is_empty());
+    }
+    
+    #[test]
+    fn rejected_input_never_reaches_the_repository() {
+        let (service, mocks) = mock_user_service();
+        mocks.validator.reject("email", "blocked domain");
+        let error = create(&service).unwrap_err();
+        assert!(error.to_string().contains("blocked domain"));
+        assert_eq!(mocks.repository.spy().calls_to("save"), 0);
+    }
+    
+    #[test]
+    fn failed_credentials_roll_back_registration_silently() {
+        let (service, mocks) = mock_user_service();
+        mocks.credentials.spy().fail_on("set_password_hash", "vault sealed");
+        let result = service.register_with_password("ada_l".to_string(), "ada@example.com".to_string(), "anything");
+        assert!(result.is_err());
+        assert!(mocks.repository.stored().is_empty());
+        assert!(mocks.email.sent().is_empty(), "no welcome email for an account that does not exist");
+    }
+    
+    #[test]
+    fn session_store_outage_fails_login_after_checking_the_password() {
+        let (service, mocks) = mock_user_service();
+        let user = service.register_with_password("ada_l".to_string(), "ada@example.com".to_string(), "secret").unwrap();
+        mocks.sessions.spy().fail_all("session store down");
+        assert!(service.login(&user, "secret").is_err());
+        assert_eq!(mocks.passwords.spy().calls_to("verify_password"), 1);
+        assert!(mocks.logger.errors().contains(&("Failed to create session".to_string(), "session store down".to_string())));
+    }
+}
@@ -0,0 +1,525 @@
+// Test doubles for every collaborator, shared by the test modules and the walkthrough
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicU64, Ordering};
+use chrono::{DateTime, Utc};
+use crate::{ActivityLogger, AnalyticsService, ApiVersion, AuditEvent, CorrectUserService, CredentialStore, DefaultUserFormatter, EmailMessage, EmailService, EmailTemplateRenderer, InMemoryCredentialStore, InMemoryUserRepository, ManualClock, NotificationService, Page, Pagination, PasswordHasher, PhoneNumber, SequentialIdGenerator, SessionService, UnitOfWork, User, UserFormatter, UserId, UserRepository, UserResponse, UserValidator, ValidationFailure, ValidationReport};
+
+use chrono::TimeZone;
+
+// Call log and programmed failures shared by all the doubles
+#[derive(Clone, Default)]
+pub struct Spy {
+    calls: Arc<Mutex<Vec<String>>>,
+    failures: Arc<Mutex<HashMap<String, String>>>,
+}
+
+impl Spy {
+    // Every recorded call, as `operation(argument)`
+    pub fn calls(&self) -> Vec<String> {
+        self.calls.lock().map(|calls| calls.clone()).unwrap_or_default()
+    }
+    
+    pub fn calls_to(&self, operation: &str) -> usize {
+        let prefix = format!("{}(", operation);
+        self.calls().iter().filter(|call| call.starts_with(&prefix)).count()
+    }
+    
+    // Makes `operation` fail with `error` until `succeed` is called; "*" fails everything
+    pub fn fail_on(&self, operation: &str, error: &str) {
+        if let Ok(mut failures) = self.failures.lock() {
+            failures.insert(operation.to_string(), error.to_string());
+        }
+    }
+    
+    pub fn fail_all(&self, error: &str) {
+        self.fail_on("*", error);
+    }
+    
+    pub fn succeed(&self) {
+        if let Ok(mut failures) = self.failures.lock() {
+            failures.clear();
+        }
+    }
+    
+    fn record(&self, operation: &str, argument: &str) -> Result<(), String> {
+        self.calls.lock().map_err(|e| e.to_string())?.push(format!("{}({})", operation, argument));
+        let failures = self.failures.lock().map_err(|e| e.to_string())?;
+        match failures.get(operation).or_else(|| failures.get("*")) {
+            Some(error) => Err(error.clone()),
+            None => Ok(()),
+        }
+    }
+    
+    // For collaborators whose methods cannot return an error
+    fn observe(&self, operation: &str, argument: &str) {
+        let _ = self.record(operation, argument);
+    }
+}
+
+// Stores users in memory; every operation, including transactions, can be made to fail
+#[derive(Clone, Default)]
+pub struct MockUserRepository {
+    spy: Spy,
+    users: Arc<InMemoryUserRepository>,
+}
+
+impl MockUserRepository {
+    pub fn spy(&self) -> &Spy {
+        &self.spy
+    }
+    
+    pub fn stored(&self) -> Vec<User> {
+        self.users.find_all().unwrap_or_default()
+    }
+}
+
+impl UnitOfWork for MockUserRepository {
+    fn begin(&self) -> Result<(), String> {
+        self.spy.record("begin", "")?;
+        self.users.begin()
+    }
+    
+    fn commit(&self) -> Result<(), String> {
+        self.spy.record("commit", "")?;
+        self.users.commit()
+    }
+    
+    fn rollback(&self) -> Result<(), String> {
+        self.spy.record("rollback", "")?;
+        self.users.rollback()
+    }
+}
+
+impl UserRepository for MockUserRepository {
+    fn save(&self, user: &User) -> Result<bool, String> {
+        self.spy.record("save", &user.id().to_string())?;
+        self.users.save(user)
+    }
+    
+    fn update(&self, user: &User) -> Result<bool, String> {
+        self.spy.record("update", &user.id().to_string())?;
+        self.users.update(user)
+    }
+    
+    fn find_by_id(&self, user_id: &str) -> Result<Option<User>, String> {
+        self.spy.record("find_by_id", user_id)?;
+        self.users.find_by_id(user_id)
+    }
+    
+    fn find_by_email(&self, email: &str) -> Result<Option<User>, String> {
+        self.spy.record("find_by_email", email)?;
+        self.users.find_by_email(email)
+    }
+    
+    fn find_all(&self) -> Result<Vec<User>, String> {
+        self.spy.record("find_all", "")?;
+        self.users.find_all()
+    }
+    
+    fn search(&self, query: &str, page: Pagination) -> Result<Page<User>, String> {
+        self.spy.record("search", query)?;
+        self.users.search(query, page)
+    }
+    
+    fn count(&self) -> Result<usize, String> {
+        self.spy.record("count", "")?;
+        self.users.count()
+    }
+    
+    fn delete(&self, user: &User) -> Result<bool, String> {
+        self.spy.record("delete", &user.id().to_string())?;
+        self.users.delete(user)
+    }
+}
+
+// Captures delivered emails instead of sending them
+#[derive(Clone, Default)]
+pub struct MockEmailService {
+    spy: Spy,
+    sent: Arc<Mutex<Vec<EmailMessage>>>,
+}
+
+impl MockEmailService {
+    pub fn spy(&self) -> &Spy {
+        &self.spy
+    }
+    
+    // Only the emails that were accepted, not the failed attempts
+    pub fn sent(&self) -> Vec<EmailMessage> {
+        self.sent.lock().map(|sent| sent.clone()).unwrap_or_default()
+    }
+}
+
+impl EmailService for MockEmailService {
+    fn send(&self, message: &EmailMessage) -> Result<bool, String> {
+        self.spy.record("send", &message.to)?;
+        self.sent.lock().map_err(|e| e.to_string())?.push(message.clone());
+        Ok(true)
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct MockActivityLogger {
+    spy: Spy,
+    activities: Arc<Mutex<Vec<(String, String)>>>,
+    errors: Arc<Mutex<Vec<(String, String)>>>,
+    audits: Arc<Mutex<Vec<AuditEvent>>>,
+}
+
+impl MockActivityLogger {
+    pub fn spy(&self) -> &Spy {
+        &self.spy
+    }
+    
+    // (username, activity) pairs
+    pub fn activities(&self) -> Vec<(String, String)> {
+        self.activities.lock().map(|entries| entries.clone()).unwrap_or_default()
+    }
+    
+    // (context, error) pairs
+    pub fn errors(&self) -> Vec<(String, String)> {
+        self.errors.lock().map(|entries| entries.clone()).unwrap_or_default()
+    }
+    
+    pub fn audits(&self) -> Vec<AuditEvent> {
+        self.audits.lock().map(|entries| entries.clone()).unwrap_or_default()
+    }
+}
+
+impl ActivityLogger for MockActivityLogger {
+    fn log_user_activity(&self, username: &str, activity: &str) {
+        self.spy.observe("log_user_activity", activity);
+        if let Ok(mut activities) = self.activities.lock() {
+            activities.push((username.to_string(), activity.to_string()));
+        }
+    }
+    
+    fn log_error(&self, error: &str, context: &str) {
+        self.spy.observe("log_error", context);
+        if let Ok(mut errors) = self.errors.lock() {
+            errors.push((context.to_string(), error.to_string()));
+        }
+    }
+    
+    fn log_audit(&self, event: &AuditEvent) {
+        self.spy.observe("log_audit", &event.action.to_string());
+        if let Ok(mut audits) = self.audits.lock() {
+            audits.push(event.clone());
+        }
+    }
+}
+
+// Accepts everything unless told to reject a field
+#[derive(Clone, Default)]
+pub struct MockUserValidator {
+    spy: Spy,
+    rejections: Arc<Mutex<HashMap<&'static str, String>>>,
+}
+
+impl MockUserValidator {
+    pub fn spy(&self) -> &Spy {
+        &self.spy
+    }
+    
+    // `field` is "username", "email", "password" or "phone"
+    pub fn reject(&self, field: &'static str, reason: &str) {
+        if let Ok(mut rejections) = self.rejections.lock() {
+            rejections.insert(field, reason.to_string());
+        }
+    }
+    
+    fn report(&self, field: &'static str) -> ValidationReport {
+        let mut report = ValidationReport::new();
+        if let Some(reason) = self.rejections.lock().ok().and_then(|rejections| rejections.get(field).cloned()) {
+            report.add(ValidationFailure { field, rule: "mock", reason });
+        }
+        report
+    }
+}
+
+impl UserValidator for MockUserValidator {
+    fn validate_email(&self, email: &str) -> ValidationReport {
+        self.spy.observe("validate_email", email);
+        self.report("email")
+    }
+    
+    fn validate_username(&self, username: &str) -> ValidationReport {
+        self.spy.observe("validate_username", username);
+        self.report("username")
+    }
+    
+    fn validate_password(&self, _password: &str) -> ValidationReport {
+        self.spy.observe("validate_password", "");
+        self.report("password")
+    }
+    
+    fn validate_user(&self, user: &User) -> ValidationReport {
+        self.validate_username(user.username()).merge(self.validate_email(user.email()))
+    }
+    
+    fn validate_phone(&self, phone: &str) -> ValidationReport {
+        self.spy.observe("validate_phone", phone);
+        self.report("phone")
+    }
+}
+
+// Records calls; API responses come from DefaultUserFormatter
+#[derive(Clone, Default)]
+pub struct MockUserFormatter {
+    spy: Spy,
+}
+
+impl MockUserFormatter {
+    pub fn spy(&self) -> &Spy {
+        &self.spy
+    }
+}
+
+impl UserFormatter for MockUserFormatter {
+    fn format_user_for_display(&self, user: &User) -> String {
+        self.spy.observe("format_user_for_display", &user.id().to_string());
+        format!("{} <{}>", user.username(), user.email())
+    }
+    
+    fn format_user_for_api(&self, user: &User, version: ApiVersion) -> UserResponse {
+        self.spy.observe("format_user_for_api", &user.id().to_string());
+        DefaultUserFormatter::new().format_user_for_api(user, version)
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct MockAnalyticsService {
+    spy: Spy,
+}
+
+impl MockAnalyticsService {
+    pub fn spy(&self) -> &Spy {
+        &self.spy
+    }
+}
+
+impl AnalyticsService for MockAnalyticsService {
+    fn track_user_event(&self, username: &str, event_name: &str, _metadata: Option<HashMap<String, String>>) {
+        self.spy.observe("track_user_event", &format!("{}, {}", username, event_name));
+    }
+    
+    fn track_user_registration(&self, username: &str) {
+        self.spy.observe("track_user_registration", username);
+    }
+    
+    fn track_user_update(&self, username: &str) {
+        self.spy.observe("track_user_update", username);
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct MockNotificationService {
+    spy: Spy,
+}
+
+impl MockNotificationService {
+    pub fn spy(&self) -> &Spy {
+        &self.spy
+    }
+}
+
+impl NotificationService for MockNotificationService {
+    fn send_push_notification(&self, username: &str, _message: &str) -> Result<bool, String> {
+        self.spy.record("send_push_notification", username)?;
+        Ok(true)
+    }
+    
+    fn send_sms_notification(&self, phone: &PhoneNumber, _message: &str) -> Result<bool, String> {
+        self.spy.record("send_sms_notification", phone.as_str())?;
+        Ok(true)
+    }
+}
+
+// Hashes are `mock$<password>`, so tests can see what was stored
+#[derive(Clone, Default)]
+pub struct MockPasswordHasher {
+    spy: Spy,
+}
+
+impl MockPasswordHasher {
+    pub fn spy(&self) -> &Spy {
+        &self.spy
+    }
+}
+
+impl PasswordHasher for MockPasswordHasher {
+    fn hash_password(&self, password: &str) -> Result<String, String> {
+        self.spy.record("hash_password", "")?;
+        Ok(format!("mock${}", password))
+    }
+    
+    fn verify_password(&self, password: &str, hash: &str) -> Result<bool, String> {
+        self.spy.record("verify_password", "")?;
+        Ok(hash == format!("mock${}", password))
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct MockCredentialStore {
+    spy: Spy,
+    hashes: Arc<InMemoryCredentialStore>,
+}
+
+impl MockCredentialStore {
+    pub fn spy(&self) -> &Spy {
+        &self.spy
+    }
+}
+
+impl CredentialStore for MockCredentialStore {
+    fn set_password_hash(&self, user_id: UserId, hash: String) -> Result<(), String> {
+        self.spy.record("set_password_hash", &user_id.to_string())?;
+        self.hashes.set_password_hash(user_id, hash)
+    }
+    
+    fn password_hash(&self, user_id: UserId) -> Result<Option<String>, String> {
+        self.spy.record("password_hash", &user_id.to_string())?;
+        self.hashes.password_hash(user_id)
+    }
+    
+    fn remove_credentials(&self, user_id: UserId) -> Result<bool, String> {
+        self.spy.record("remove_credentials", &user_id.to_string())?;
+        self.hashes.remove_credentials(user_id)
+    }
+}
+
+// Issues predictable tokens: `token-1`, `token-2`, ...
+#[derive(Clone, Default)]
+pub struct MockSessionService {
+    spy: Spy,
+    sessions: Arc<Mutex<HashMap<String, UserId>>>,
+    issued: Arc<AtomicU64>,
+}
+
+impl MockSessionService {
+    pub fn spy(&self) -> &Spy {
+        &self.spy
+    }
+}
+
+impl SessionService for MockSessionService {
+    fn create_session(&self, user_id: UserId) -> Result<String, String> {
+        self.spy.record("create_session", &user_id.to_string())?;
+        let token = format!("token-{}", self.issued.fetch_add(1, Ordering::SeqCst) + 1);
+        self.sessions.lock().map_err(|e| e.to_string())?.insert(token.clone(), user_id);
+        Ok(token)
+    }
+    
+    fn validate_token(&self, token: &str) -> Result<Option<UserId>, String> {
+        self.spy.record("validate_token", token)?;
+        Ok(self.sessions.lock().map_err(|e| e.to_string())?.get(token).copied())
+    }
+    
+    fn revoke(&self, token: &str) -> Result<bool, String> {
+        self.spy.record("revoke", token)?;
+        Ok(self.sessions.lock().map_err(|e| e.to_string())?.remove(token).is_some())
+    }
+}
+
+// Renders any template name: the subject is the name, the body lists the values
+#[derive(Clone, Default)]
+pub struct MockTemplateRenderer {
+    spy: Spy,
+}
+
+impl MockTemplateRenderer {
+    pub fn spy(&self) -> &Spy {
+        &self.spy
+    }
+}
+
+impl EmailTemplateRenderer for MockTemplateRenderer {
+    fn render(&self, template: &str, to: &str, values: &HashMap<String, String>) -> Result<EmailMessage, String> {
+        self.spy.record("render", template)?;
+        let mut pairs: Vec<String> = values.iter().map(|(key, value)| format!("{}={}", key, value)).collect();
+        pairs.sort();
+        Ok(EmailMessage { to: to.to_string(), subject: template.to_string(), body: pairs.join("\n") })
+    }
+}
+
+pub type MockUserService = CorrectUserService<
+    MockUserRepository,
+    MockEmailService,
+    MockActivityLogger,
+    MockUserValidator,
+    MockUserFormatter,
+    MockAnalyticsService,
+    MockNotificationService,
+    MockPasswordHasher,
+    MockCredentialStore,
+    MockSessionService,
+    MockTemplateRenderer,
+>;
+
+// Handles on the doubles inside a MockUserService
+#[derive(Clone)]
+pub struct Mocks {
+    pub repository: MockUserRepository,
+    pub email: MockEmailService,
+    pub logger: MockActivityLogger,
+    pub validator: MockUserValidator,
+    pub formatter: MockUserFormatter,
+    pub analytics: MockAnalyticsService,
+    pub notifications: MockNotificationService,
+    pub passwords: MockPasswordHasher,
+    pub credentials: MockCredentialStore,
+    pub sessions: MockSessionService,
+    pub templates: MockTemplateRenderer,
+    // Starts at `test_epoch()`
+    pub clock: ManualClock,
+    // Hands out user-1, user-2, ...
+    pub ids: SequentialIdGenerator,
+}
+
+impl Default for Mocks {
+    fn default() -> Self {
+        Mocks {
+            repository: MockUserRepository::default(),
+            email: MockEmailService::default(),
+            logger: MockActivityLogger::default(),
+            validator: MockUserValidator::default(),
+            formatter: MockUserFormatter::default(),
+            analytics: MockAnalyticsService::default(),
+            notifications: MockNotificationService::default(),
+            passwords: MockPasswordHasher::default(),
+            credentials: MockCredentialStore::default(),
+            sessions: MockSessionService::default(),
+            templates: MockTemplateRenderer::default(),
+            clock: ManualClock::new(test_epoch()),
+            ids: SequentialIdGenerator::new(),
+        }
+    }
+}
+
+// The time a mock service's clock starts at
+pub fn test_epoch() -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2024, 1, 1, 9, 0, 0).unwrap()
+}
+
+// A service wired entirely with doubles, plus handles to assert on them
+pub fn mock_user_service() -> (MockUserService, Mocks) {
+    let mocks = Mocks::default();
+    let service = CorrectUserService::new(
+        mocks.repository.clone(),
+        mocks.email.clone(),
+        mocks.logger.clone(),
+        mocks.validator.clone(),
+        mocks.formatter.clone(),
+        mocks.analytics.clone(),
+        Some(mocks.notifications.clone()),
+    )
+    .with_password_hasher(mocks.passwords.clone())
+    .with_credential_store(mocks.credentials.clone())
+    .with_session_service(mocks.sessions.clone())
+    .with_template_renderer(mocks.templates.clone())
+    .with_clock(mocks.clock.clone())
+    .with_id_generator(mocks.ids.clone());
+    (service, mocks)
+}
@@ -0,0 +1,268 @@
+// The user record and its value types: UserId, UserStatus and PhoneNumber
+
+use std::fmt;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use crate::PhoneValidator;
+
+// User identifier - process-wide incrementing, rendered as "user-<n>"
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct UserId(pub(crate) u64);
+
+static NEXT_USER_ID: AtomicU64 = AtomicU64::new(1);
+
+impl UserId {
+    pub fn generate() -> Self {
+        UserId(NEXT_USER_ID.fetch_add(1, Ordering::Relaxed))
+    }
+}
+
+impl fmt::Display for UserId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "user-{}", self.0)
+    }
+}
+
+impl FromStr for UserId {
+    type Err = String;
+    
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.strip_prefix("user-")
+            .and_then(|n| n.parse().ok())
+            .map(UserId)
+            .ok_or_else(|| format!("Invalid user id: {}", s))
+    }
+}
+
+impl TryFrom<String> for UserId {
+    type Error = String;
+    
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<UserId> for String {
+    fn from(id: UserId) -> Self {
+        id.to_string()
+    }
+}
+
+// Account lifecycle state
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UserStatus {
+    PendingVerification,
+    Active,
+    Suspended,
+    Deleted,
+}
+
+impl UserStatus {
+    pub fn can_transition_to(self, next: UserStatus) -> bool {
+        use UserStatus::*;
+        matches!(
+            (self, next),
+            (PendingVerification, Active)
+                | (PendingVerification, Deleted)
+                | (Active, Suspended)
+                | (Active, Deleted)
+                | (Suspended, Active)
+                | (Suspended, Deleted)
+        )
+    }
+}
+
+impl fmt::Display for UserStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let label = match self {
+            UserStatus::PendingVerification => "pending_verification",
+            UserStatus::Active => "active",
+            UserStatus::Suspended => "suspended",
+            UserStatus::Deleted => "deleted",
+        };
+        write!(f, "{}", label)
+    }
+}
+
+impl FromStr for UserStatus {
+    type Err = String;
+    
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "pending_verification" => Ok(UserStatus::PendingVerification),
+            "active" => Ok(UserStatus::Active),
+            "suspended" => Ok(UserStatus::Suspended),
+            "deleted" => Ok(UserStatus::Deleted),
+            _ => Err(format!("Unknown user status: {}", s)),
+        }
+    }
+}
+
+// Phone number in E.164 form ("+" and up to 15 digits); only PhoneValidator creates one
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct PhoneNumber(pub(crate) String);
+
+impl PhoneNumber {
+    pub fn parse(input: &str) -> Result<Self, String> {
+        PhoneValidator::new().parse(input)
+    }
+    
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for PhoneNumber {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl TryFrom<String> for PhoneNumber {
+    type Error = String;
+    
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        PhoneNumber::parse(&s)
+    }
+}
+
+impl From<PhoneNumber> for String {
+    fn from(phone: PhoneNumber) -> Self {
+        phone.0
+    }
+}
+
+// User entity - only handles user data.
+// The profile fields are optional; the locale is a language tag such as "de-DE".
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct User {
+    id: UserId,
+    username: String,
+    email: String,
+    status: UserStatus,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    phone: Option<PhoneNumber>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    display_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    locale: Option<String>,
+}
+
+impl User {
+    // New accounts get a fresh id and start out awaiting verification
+    pub fn new(username: String, email: String) -> Self {
+        User::create(UserId::generate(), username, email, Utc::now())
+    }
+    
+    // A new account with an id and creation time chosen by the caller, e.g. from
+    // an IdGenerator and a Clock
+    pub fn create(id: UserId, username: String, email: String, now: DateTime<Utc>) -> Self {
+        User {
+            id,
+            username,
+            email,
+            status: UserStatus::PendingVerification,
+            created_at: now,
+            updated_at: now,
+            phone: None,
+            display_name: None,
+            locale: None,
+        }
+    }
+    
+    // Rebuilds a stored user, e.g. when loading from a database row
+    pub fn restore(id: UserId, username: String, email: String, status: UserStatus,
+                   created_at: DateTime<Utc>, updated_at: DateTime<Utc>) -> Self {
+        User { id, username, email, status, created_at, updated_at, phone: None, display_name: None, locale: None }
+    }
+    
+    // Profile fields of a restored user; unlike the setters, keeps `updated_at`
+    pub fn with_profile(mut self, phone: Option<PhoneNumber>, display_name: Option<String>, locale: Option<String>) -> Self {
+        self.phone = phone;
+        self.display_name = display_name;
+        self.locale = locale;
+        self
+    }
+    
+    pub fn id(&self) -> UserId {
+        self.id
+    }
+    
+    pub fn username(&self) -> &str {
+        &self.username
+    }
+    
+    pub fn email(&self) -> &str {
+        &self.email
+    }
+    
+    pub fn status(&self) -> UserStatus {
+        self.status
+    }
+    
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+    
+    pub fn updated_at(&self) -> DateTime<Utc> {
+        self.updated_at
+    }
+    
+    pub fn phone(&self) -> Option<&PhoneNumber> {
+        self.phone.as_ref()
+    }
+    
+    pub fn display_name(&self) -> Option<&str> {
+        self.display_name.as_deref()
+    }
+    
+    pub fn locale(&self) -> Option<&str> {
+        self.locale.as_deref()
+    }
+    
+    pub fn set_username(&mut self, username: String) {
+        self.username = username;
+        self.updated_at = Utc::now();
+    }
+    
+    pub fn set_email(&mut self, email: String) {
+        self.email = email;
+        self.updated_at = Utc::now();
+    }
+    
+    pub fn set_phone(&mut self, phone: Option<PhoneNumber>) {
+        self.phone = phone;
+        self.updated_at = Utc::now();
+    }
+    
+    pub fn set_display_name(&mut self, display_name: Option<String>) {
+        self.display_name = display_name;
+        self.updated_at = Utc::now();
+    }
+    
+    pub fn set_locale(&mut self, locale: Option<String>) {
+        self.locale = locale;
+        self.updated_at = Utc::now();
+    }
+    
+    pub fn transition_to(&mut self, next: UserStatus) -> Result<(), String> {
+        if !self.status.can_transition_to(next) {
+            return Err(format!("Cannot change {} from {} to {}", self.id, self.status, next));
+        }
+        self.status = next;
+        self.updated_at = Utc::now();
+        Ok(())
+    }
+    
+    // Restamps `updated_at`, for callers that read the time from their own Clock
+    pub fn touch(&mut self, at: DateTime<Utc>) {
+        self.updated_at = at;
+    }
+}
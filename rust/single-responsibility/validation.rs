@@ -0,0 +1,719 @@
+// Validation rules, the username policy and the UserValidator built from them
+
+use std::fmt;
+use regex::Regex;
+use unicode_normalization::UnicodeNormalization;
+use unicode_script::{Script, UnicodeScript};
+use unicode_security::mixed_script::MixedScript;
+use unicode_segmentation::UnicodeSegmentation;
+use crate::{PhoneNumber, User};
+
+// One failed validation rule
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValidationFailure {
+    pub field: &'static str,
+    pub rule: &'static str,
+    pub reason: String,
+}
+
+// Every rule that failed, rather than just "invalid"
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ValidationReport {
+    failures: Vec<ValidationFailure>,
+}
+
+impl ValidationReport {
+    pub fn new() -> Self {
+        ValidationReport::default()
+    }
+    
+    pub fn add(&mut self, failure: ValidationFailure) {
+        self.failures.push(failure);
+    }
+    
+    pub fn merge(mut self, other: ValidationReport) -> Self {
+        self.failures.extend(other.failures);
+        self
+    }
+    
+    pub fn is_valid(&self) -> bool {
+        self.failures.is_empty()
+    }
+    
+    pub fn failures(&self) -> &[ValidationFailure] {
+        &self.failures
+    }
+}
+
+impl fmt::Display for ValidationReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_valid() {
+            return write!(f, "valid");
+        }
+        let failures: Vec<String> = self.failures
+            .iter()
+            .map(|failure| format!("{} {} ({})", failure.field, failure.reason, failure.rule))
+            .collect();
+        write!(f, "{}", failures.join("; "))
+    }
+}
+
+// Validation rule trait - one check on one field
+pub trait ValidationRule {
+    fn field(&self) -> &'static str;
+    fn name(&self) -> &'static str;
+    fn check(&self, value: &str) -> Result<(), String>;
+}
+
+// Value length in characters must fall within [min, max]
+pub struct LengthRule {
+    field: &'static str,
+    min: usize,
+    max: Option<usize>,
+}
+
+impl LengthRule {
+    pub fn between(field: &'static str, min: usize, max: usize) -> Self {
+        LengthRule { field, min, max: Some(max) }
+    }
+    
+    pub fn at_least(field: &'static str, min: usize) -> Self {
+        LengthRule { field, min, max: None }
+    }
+}
+
+impl ValidationRule for LengthRule {
+    fn field(&self) -> &'static str {
+        self.field
+    }
+    
+    fn name(&self) -> &'static str {
+        "length"
+    }
+    
+    fn check(&self, value: &str) -> Result<(), String> {
+        let length = value.chars().count();
+        match self.max {
+            Some(max) if length < self.min || length > max => Err(format!("must be {}-{} characters", self.min, max)),
+            None if length < self.min => Err(format!("must be at least {} characters", self.min)),
+            _ => Ok(()),
+        }
+    }
+}
+
+// Value must match a regular expression
+pub struct PatternRule {
+    field: &'static str,
+    name: &'static str,
+    pattern: Regex,
+    reason: &'static str,
+}
+
+impl PatternRule {
+    // Fails if `pattern` is not a valid regular expression
+    pub fn new(field: &'static str, name: &'static str, pattern: &str, reason: &'static str) -> Result<Self, regex::Error> {
+        Ok(PatternRule {
+            field,
+            name,
+            pattern: Regex::new(pattern)?,
+            reason,
+        })
+    }
+}
+
+impl ValidationRule for PatternRule {
+    fn field(&self) -> &'static str {
+        self.field
+    }
+    
+    fn name(&self) -> &'static str {
+        self.name
+    }
+    
+    fn check(&self, value: &str) -> Result<(), String> {
+        if self.pattern.is_match(value) {
+            Ok(())
+        } else {
+            Err(self.reason.to_string())
+        }
+    }
+}
+
+// Every character must satisfy the predicate
+pub struct AllCharsRule {
+    field: &'static str,
+    name: &'static str,
+    predicate: fn(char) -> bool,
+    reason: &'static str,
+}
+
+impl AllCharsRule {
+    pub fn new(field: &'static str, name: &'static str, predicate: fn(char) -> bool, reason: &'static str) -> Self {
+        AllCharsRule { field, name, predicate, reason }
+    }
+}
+
+impl ValidationRule for AllCharsRule {
+    fn field(&self) -> &'static str {
+        self.field
+    }
+    
+    fn name(&self) -> &'static str {
+        self.name
+    }
+    
+    fn check(&self, value: &str) -> Result<(), String> {
+        if value.chars().all(self.predicate) {
+            Ok(())
+        } else {
+            Err(self.reason.to_string())
+        }
+    }
+}
+
+// At least one character must satisfy the predicate
+pub struct ContainsCharRule {
+    field: &'static str,
+    name: &'static str,
+    predicate: fn(char) -> bool,
+    reason: &'static str,
+}
+
+impl ContainsCharRule {
+    pub fn new(field: &'static str, name: &'static str, predicate: fn(char) -> bool, reason: &'static str) -> Self {
+        ContainsCharRule { field, name, predicate, reason }
+    }
+}
+
+impl ValidationRule for ContainsCharRule {
+    fn field(&self) -> &'static str {
+        self.field
+    }
+    
+    fn name(&self) -> &'static str {
+        self.name
+    }
+    
+    fn check(&self, value: &str) -> Result<(), String> {
+        if value.chars().any(self.predicate) {
+            Ok(())
+        } else {
+            Err(self.reason.to_string())
+        }
+    }
+}
+
+// Phone validator - parses what people type into E.164. Spaces, dots, dashes and
+// parentheses are dropped and a leading "00" is read as "+". The result must be
+// "+", a country code that does not start with 0, and 7 to 15 digits in all.
+pub struct PhoneValidator;
+
+impl PhoneValidator {
+    pub fn new() -> Self {
+        PhoneValidator
+    }
+    
+    pub fn parse(&self, input: &str) -> Result<PhoneNumber, String> {
+        let compact: String = input
+            .chars()
+            .filter(|c| !c.is_whitespace() && !matches!(c, '.' | '-' | '(' | ')'))
+            .collect();
+        let digits = match compact.strip_prefix('+').or_else(|| compact.strip_prefix("00")) {
+            Some(digits) => digits,
+            None => return Err("must start with + and a country code".to_string()),
+        };
+        if !digits.chars().all(|c| c.is_ascii_digit()) {
+            return Err("must contain only digits after the +".to_string());
+        }
+        if digits.starts_with('0') {
+            return Err("country code cannot start with 0".to_string());
+        }
+        if !(7..=15).contains(&digits.len()) {
+            return Err(format!("must have 7 to 15 digits, not {}", digits.len()));
+        }
+        Ok(PhoneNumber(format!("+{}", digits)))
+    }
+}
+
+impl Default for PhoneValidator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ValidationRule for PhoneValidator {
+    fn field(&self) -> &'static str {
+        "phone"
+    }
+    
+    fn name(&self) -> &'static str {
+        "e164"
+    }
+    
+    fn check(&self, value: &str) -> Result<(), String> {
+        self.parse(value).map(|_| ())
+    }
+}
+
+// Runs every rule registered for a field and collects all failures
+pub struct ValidatorChain {
+    rules: Vec<Box<dyn ValidationRule>>,
+}
+
+impl ValidatorChain {
+    pub fn new() -> Self {
+        ValidatorChain { rules: Vec::new() }
+    }
+    
+    pub fn rule<R: ValidationRule + 'static>(mut self, rule: R) -> Self {
+        self.rules.push(Box::new(rule));
+        self
+    }
+    
+    pub fn rules(mut self, rules: Vec<Box<dyn ValidationRule>>) -> Self {
+        self.rules.extend(rules);
+        self
+    }
+    
+    pub fn validate(&self, field: &str, value: &str) -> ValidationReport {
+        let mut report = ValidationReport::new();
+        for rule in self.rules.iter().filter(|rule| rule.field() == field) {
+            if let Err(reason) = rule.check(value) {
+                report.add(ValidationFailure {
+                    field: rule.field(),
+                    rule: rule.name(),
+                    reason,
+                });
+            }
+        }
+        report
+    }
+}
+
+impl Default for ValidatorChain {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Tests for each validation rule on its own, then for the chain that runs them
+#[cfg(test)]
+mod validation_rule_tests {
+    use super::*;
+    
+    #[test]
+    fn length_rule_counts_characters_within_its_bounds() {
+        let rule = LengthRule::between("username", 3, 5);
+        assert_eq!((rule.field(), rule.name()), ("username", "length"));
+        assert_eq!(rule.check("ab"), Err("must be 3-5 characters".to_string()));
+        assert_eq!(rule.check("abc"), Ok(()));
+        assert_eq!(rule.check("abcde"), Ok(()));
+        assert_eq!(rule.check("abcdef"), Err("must be 3-5 characters".to_string()));
+        // Five characters, ten bytes
+        assert_eq!(rule.check("ééééé"), Ok(()));
+    }
+    
+    #[test]
+    fn open_ended_length_rule_has_only_a_minimum() {
+        let rule = LengthRule::at_least("password", 8);
+        assert_eq!(rule.check("short"), Err("must be at least 8 characters".to_string()));
+        assert_eq!(rule.check("12345678"), Ok(()));
+        assert_eq!(rule.check(&"x".repeat(1000)), Ok(()));
+    }
+    
+    #[test]
+    fn pattern_rule_reports_its_reason_on_mismatch() {
+        let rule = PatternRule::new("locale", "format", r"^[a-z]{2}$", "must be two letters").unwrap();
+        assert_eq!((rule.field(), rule.name()), ("locale", "format"));
+        assert_eq!(rule.check("en"), Ok(()));
+        assert_eq!(rule.check("eng"), Err("must be two letters".to_string()));
+        assert_eq!(rule.check(""), Err("must be two letters".to_string()));
+    }
+    
+    #[test]
+    fn pattern_rule_rejects_an_invalid_pattern() {
+        let error = PatternRule::new("locale", "format", r"^[a-z{2}$", "must be two letters").err().unwrap();
+        assert!(matches!(error, regex::Error::Syntax(_)), "{:?}", error);
+    }
+    
+    #[test]
+    fn all_chars_rule_fails_on_any_character_outside_the_predicate() {
+        let rule = AllCharsRule::new("username", "charset", |c| c.is_ascii_alphanumeric(), "letters and digits only");
+        assert_eq!((rule.field(), rule.name()), ("username", "charset"));
+        assert_eq!(rule.check("ada1815"), Ok(()));
+        assert_eq!(rule.check("ada lovelace"), Err("letters and digits only".to_string()));
+        // Vacuously true: length is another rule's job
+        assert_eq!(rule.check(""), Ok(()));
+    }
+    
+    #[test]
+    fn contains_char_rule_needs_one_matching_character() {
+        let rule = ContainsCharRule::new("password", "digit", char::is_numeric, "must contain a digit");
+        assert_eq!((rule.field(), rule.name()), ("password", "digit"));
+        assert_eq!(rule.check("Password1"), Ok(()));
+        assert_eq!(rule.check("Password"), Err("must contain a digit".to_string()));
+        assert_eq!(rule.check(""), Err("must contain a digit".to_string()));
+    }
+    
+    #[test]
+    fn phone_validator_parses_what_people_type_into_e164() {
+        let phones = PhoneValidator::new();
+        assert_eq!((phones.field(), phones.name()), ("phone", "e164"));
+        assert_eq!(phones.parse("+44 20 7946 0958").unwrap().as_str(), "+442079460958");
+        assert_eq!(phones.parse("0049 (30) 123-456.78").unwrap().as_str(), "+493012345678");
+        assert_eq!(phones.parse("020 7946 0958"), Err("must start with + and a country code".to_string()));
+        assert_eq!(phones.parse("+44 20 CALL NOW"), Err("must contain only digits after the +".to_string()));
+        assert_eq!(phones.parse("+044 20 7946"), Err("country code cannot start with 0".to_string()));
+        assert_eq!(phones.parse("+123456"), Err("must have 7 to 15 digits, not 6".to_string()));
+        assert_eq!(phones.parse("+1234567890123456"), Err("must have 7 to 15 digits, not 16".to_string()));
+        assert!(phones.check("+1234567").is_ok());
+    }
+    
+    #[test]
+    fn chain_runs_only_the_rules_for_the_field_and_keeps_every_failure() {
+        let chain = ValidatorChain::new()
+            .rule(LengthRule::at_least("password", 8))
+            .rule(ContainsCharRule::new("password", "digit", char::is_numeric, "must contain a digit"))
+            .rule(LengthRule::between("username", 3, 20));
+        
+        let report = chain.validate("password", "abc");
+        assert_eq!(
+            report.failures(),
+            [
+                ValidationFailure { field: "password", rule: "length", reason: "must be at least 8 characters".to_string() },
+                ValidationFailure { field: "password", rule: "digit", reason: "must contain a digit".to_string() },
+            ]
+        );
+        assert_eq!(report.to_string(), "password must be at least 8 characters (length); password must contain a digit (digit)");
+        assert!(chain.validate("username", "abc").is_valid());
+        assert!(chain.validate("email", "").is_valid());
+        assert_eq!(ValidationReport::new().to_string(), "valid");
+    }
+    
+    #[test]
+    fn default_rules_cover_email_password_and_profile_fields() {
+        let rules = DefaultUserValidator::default_rules();
+        assert!(rules.validate("email", "ada@example.com").is_valid());
+        assert_eq!(rules.validate("email", "ada@example").failures()[0].rule, "format");
+        
+        let names: Vec<_> = rules.validate("password", "weak").failures().iter().map(|failure| failure.rule).collect();
+        assert_eq!(names, vec!["length", "uppercase", "digit"]);
+        assert!(rules.validate("password", "Correct1Horse").is_valid());
+        
+        assert!(rules.validate("display_name", "Ada Lovelace").is_valid());
+        assert!(!rules.validate("display_name", "").is_valid());
+        assert!(rules.validate("locale", "de-DE").is_valid());
+        assert!(!rules.validate("locale", "german").is_valid());
+        assert!(!rules.validate("phone", "12345").is_valid());
+    }
+}
+
+// Username policy - the configurable rules for what a username may be.
+// Lengths count grapheme clusters, so "é" written as e + combining accent is
+// one character, and names are NFC-normalized before they are checked.
+#[derive(Debug, Clone)]
+pub struct UsernamePolicy {
+    min_length: usize,
+    max_length: usize,
+    allowed_scripts: Vec<Script>,
+    reserved_names: Vec<String>,
+}
+
+impl Default for UsernamePolicy {
+    fn default() -> Self {
+        UsernamePolicy {
+            min_length: 3,
+            max_length: 20,
+            allowed_scripts: vec![Script::Latin],
+            reserved_names: ["admin", "administrator", "root", "support", "system"]
+                .iter()
+                .map(|name| name.to_string())
+                .collect(),
+        }
+    }
+}
+
+impl UsernamePolicy {
+    pub fn lengths(mut self, min_length: usize, max_length: usize) -> Self {
+        self.min_length = min_length;
+        self.max_length = max_length;
+        self
+    }
+    
+    pub fn allow_script(mut self, script: Script) -> Self {
+        if !self.allowed_scripts.contains(&script) {
+            self.allowed_scripts.push(script);
+        }
+        self
+    }
+    
+    pub fn reserve(mut self, name: &str) -> Self {
+        self.reserved_names.push(name.to_string());
+        self
+    }
+    
+    pub fn normalize(&self, username: &str) -> String {
+        username.nfc().collect()
+    }
+    
+    // The policy expressed as ValidationRules on the "username" field
+    pub fn rules(&self) -> Vec<Box<dyn ValidationRule>> {
+        vec![
+            Box::new(GraphemeLengthRule { min: self.min_length, max: self.max_length }),
+            Box::new(AllCharsRule::new("username", "charset", |c| c.is_alphanumeric() || c == '_',
+                                       "may only contain letters, digits and underscores")),
+            Box::new(ScriptRule { allowed: self.allowed_scripts.clone() }),
+            Box::new(ReservedNameRule {
+                skeletons: self.reserved_names.iter().map(|name| confusable_skeleton(name)).collect(),
+            }),
+        ]
+    }
+}
+
+// Lowercased confusable skeleton: "Admin", "аdmin" (Cyrillic а) and "admin" all match.
+// Case is folded before the skeleton is taken, because the skeleton maps "m" to
+// "rn" but leaves "M" alone; the skeleton can map to capitals ("0" to "O"), so
+// the result is lowercased again.
+fn confusable_skeleton(name: &str) -> String {
+    unicode_security::skeleton(&name.to_lowercase()).collect::<String>().to_lowercase()
+}
+
+struct GraphemeLengthRule {
+    min: usize,
+    max: usize,
+}
+
+impl ValidationRule for GraphemeLengthRule {
+    fn field(&self) -> &'static str {
+        "username"
+    }
+    
+    fn name(&self) -> &'static str {
+        "length"
+    }
+    
+    fn check(&self, value: &str) -> Result<(), String> {
+        let length = value.graphemes(true).count();
+        if length < self.min || length > self.max {
+            return Err(format!("must be {}-{} characters", self.min, self.max));
+        }
+        Ok(())
+    }
+}
+
+// Letters must come from the allowed scripts, and from only one of them,
+// so look-alike letters from another alphabet cannot be mixed in
+struct ScriptRule {
+    allowed: Vec<Script>,
+}
+
+impl ValidationRule for ScriptRule {
+    fn field(&self) -> &'static str {
+        "username"
+    }
+    
+    fn name(&self) -> &'static str {
+        "script"
+    }
+    
+    fn check(&self, value: &str) -> Result<(), String> {
+        let disallowed = value.chars().map(|c| c.script()).find(|script| {
+            !matches!(script, Script::Common | Script::Inherited) && !self.allowed.contains(script)
+        });
+        if let Some(script) = disallowed {
+            return Err(format!("uses the {} script, which is not allowed", script.full_name()));
+        }
+        if !value.is_single_script() {
+            return Err("mixes letters from different scripts".to_string());
+        }
+        Ok(())
+    }
+}
+
+struct ReservedNameRule {
+    skeletons: Vec<String>,
+}
+
+impl ValidationRule for ReservedNameRule {
+    fn field(&self) -> &'static str {
+        "username"
+    }
+    
+    fn name(&self) -> &'static str {
+        "reserved"
+    }
+    
+    fn check(&self, value: &str) -> Result<(), String> {
+        if self.skeletons.contains(&confusable_skeleton(value)) {
+            return Err("is reserved or looks like a reserved name".to_string());
+        }
+        Ok(())
+    }
+}
+
+// Tests for UsernamePolicy through DefaultUserValidator: reserved names in any
+// case or look-alike spelling, emoji, and names outside the allowed scripts
+#[cfg(test)]
+mod username_policy_tests {
+    use super::*;
+    
+    fn failed_rules(validator: &DefaultUserValidator, username: &str) -> Vec<&'static str> {
+        validator.validate_username(username).failures().iter().map(|failure| failure.rule).collect()
+    }
+    
+    #[test]
+    fn reserved_names_are_rejected_in_any_case() {
+        let validator = DefaultUserValidator::new();
+        for name in ["admin", "ADMIN", "AdMin", "SYSTEM", "Root", "sUpPoRt", "Administrator"] {
+            assert_eq!(failed_rules(&validator, name), vec!["reserved"], "{}", name);
+        }
+    }
+    
+    #[test]
+    fn confusable_spellings_of_reserved_names_are_rejected() {
+        let validator = DefaultUserValidator::new();
+        // "rn" reads as "m", and "0" as "o"
+        for name in ["adrnin", "ADRNIN", "r00t", "systern"] {
+            assert_eq!(failed_rules(&validator, name), vec!["reserved"], "{}", name);
+        }
+        // Cyrillic а is also outside the allowed scripts
+        assert_eq!(failed_rules(&validator, "\u{430}dmin"), vec!["script", "reserved"]);
+    }
+    
+    #[test]
+    fn names_that_only_resemble_reserved_ones_are_allowed() {
+        let validator = DefaultUserValidator::new();
+        for name in ["admiral", "administrators", "rooted", "Systems_Team"] {
+            assert!(validator.validate_username(name).is_valid(), "{}", name);
+        }
+    }
+    
+    #[test]
+    fn emoji_are_rejected() {
+        let validator = DefaultUserValidator::new();
+        assert_eq!(failed_rules(&validator, "ada_\u{1F600}"), vec!["charset"]);
+        // A flag is one grapheme made of two code points, neither of them a letter
+        assert_eq!(failed_rules(&validator, "\u{1F1EC}\u{1F1E7}ada"), vec!["charset"]);
+    }
+    
+    #[test]
+    fn cjk_names_need_their_script_allowed() {
+        let latin_only = DefaultUserValidator::new();
+        assert_eq!(failed_rules(&latin_only, "山田太郎"), vec!["script"]);
+        
+        let with_han = DefaultUserValidator::with_username_policy(UsernamePolicy::default().allow_script(Script::Han));
+        assert!(with_han.validate_username("山田太郎").is_valid());
+        // Lengths count characters, not bytes: two ideographs are too short
+        assert_eq!(failed_rules(&with_han, "山田"), vec!["length"]);
+    }
+    
+    #[test]
+    fn allowed_scripts_still_cannot_be_mixed() {
+        let validator = DefaultUserValidator::with_username_policy(UsernamePolicy::default().allow_script(Script::Cyrillic));
+        assert!(validator.validate_username("\u{438}\u{432}\u{430}\u{43D}").is_valid());
+        assert_eq!(failed_rules(&validator, "p\u{430}ypal"), vec!["script"]);
+    }
+    
+    #[test]
+    fn decomposed_accents_are_normalized_and_counted_once() {
+        let validator = DefaultUserValidator::with_username_policy(UsernamePolicy::default().lengths(3, 3));
+        assert!(validator.validate_username("e\u{301}va").is_valid());
+        assert_eq!(validator.normalize_username("e\u{301}va"), "\u{e9}va");
+    }
+}
+
+// Validator trait - defines validation operations
+pub trait UserValidator {
+    fn validate_email(&self, email: &str) -> ValidationReport;
+    fn validate_username(&self, username: &str) -> ValidationReport;
+    fn validate_password(&self, password: &str) -> ValidationReport;
+    fn validate_user(&self, user: &User) -> ValidationReport;
+    
+    // Raw phone input, before it becomes a PhoneNumber
+    fn validate_phone(&self, phone: &str) -> ValidationReport {
+        ValidatorChain::new().rule(PhoneValidator::new()).validate("phone", phone)
+    }
+    
+    // Canonical form to store; validation runs against this form
+    fn normalize_username(&self, username: &str) -> String {
+        username.to_string()
+    }
+}
+
+// Concrete implementation of UserValidator, backed by a ValidatorChain
+pub struct DefaultUserValidator {
+    chain: ValidatorChain,
+    username_policy: UsernamePolicy,
+}
+
+impl DefaultUserValidator {
+    pub fn new() -> Self {
+        DefaultUserValidator::with_username_policy(UsernamePolicy::default())
+    }
+    
+    pub fn with_username_policy(username_policy: UsernamePolicy) -> Self {
+        let chain = DefaultUserValidator::default_rules().rules(username_policy.rules());
+        DefaultUserValidator { chain, username_policy }
+    }
+    
+    // Custom email and password rules; username rules come from the default UsernamePolicy
+    pub fn with_chain(chain: ValidatorChain) -> Self {
+        let username_policy = UsernamePolicy::default();
+        DefaultUserValidator { chain: chain.rules(username_policy.rules()), username_policy }
+    }
+    
+    // Email, password and profile rules; username rules are added from a UsernamePolicy.
+    // The built-in patterns are fixed and covered by tests, so they cannot fail to compile.
+    pub fn default_rules() -> ValidatorChain {
+        let email = PatternRule::new("email", "format", r"^[^\s@]+@[^\s@]+\.[^\s@]+$", "must look like name@example.com");
+        let locale = PatternRule::new("locale", "format", r"^[a-z]{2,3}(-[A-Z]{2})?$", "must be a language tag like en or de-DE");
+        ValidatorChain::new()
+            .rule(email.expect("built-in email pattern"))
+            .rule(LengthRule::at_least("password", 8))
+            .rule(ContainsCharRule::new("password", "uppercase", char::is_uppercase, "must contain an uppercase letter"))
+            .rule(ContainsCharRule::new("password", "lowercase", char::is_lowercase, "must contain a lowercase letter"))
+            .rule(ContainsCharRule::new("password", "digit", char::is_numeric, "must contain a digit"))
+            .rule(PhoneValidator::new())
+            .rule(LengthRule::between("display_name", 1, 64))
+            .rule(locale.expect("built-in locale pattern"))
+    }
+}
+
+impl Default for DefaultUserValidator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl UserValidator for DefaultUserValidator {
+    fn validate_email(&self, email: &str) -> ValidationReport {
+        self.chain.validate("email", email)
+    }
+    
+    fn validate_username(&self, username: &str) -> ValidationReport {
+        self.chain.validate("username", &self.username_policy.normalize(username))
+    }
+    
+    fn validate_password(&self, password: &str) -> ValidationReport {
+        self.chain.validate("password", password)
+    }
+    
+    fn validate_user(&self, user: &User) -> ValidationReport {
+        let mut report = self.validate_username(user.username()).merge(self.validate_email(user.email()));
+        if let Some(display_name) = user.display_name() {
+            report = report.merge(self.chain.validate("display_name", display_name));
+        }
+        if let Some(locale) = user.locale() {
+            report = report.merge(self.chain.validate("locale", locale));
+        }
+        report
+    }
+    
+    fn validate_phone(&self, phone: &str) -> ValidationReport {
+        self.chain.validate("phone", phone)
+    }
+    
+    fn normalize_username(&self, username: &str) -> String {
+        self.username_policy.normalize(username)
+    }
+}
@@ -149,6 +149,8 @@ impl ViolationUserService {
         Ok(true)
     }
     
+    // The number is an unchecked parameter, not part of the user; see `PhoneNumber`
+    // and `CorrectUserService::send_sms` in service.rs
     pub fn send_sms_notification(&self, phone: &str, message: &str) -> Result<bool, String> {
         println!("Sending SMS to {}: {}", phone, message);
         // SMS sending logic
@@ -156,6 +158,8 @@ impl ViolationUserService {
     }
     
     // File handling responsibility (should be separate)
+    // Joining fields with "," also breaks on usernames containing commas, quotes or
+    // newlines; see `UserExporter` / `CsvExporter` in export.rs
     pub fn export_user_to_csv(&self) -> Result<String, String> {
         let csv_data = format!("{},{}", self.username, self.email);
         println!("Exporting user to CSV: {}", csv_data);
@@ -163,6 +167,7 @@ impl ViolationUserService {
         Ok(csv_data)
     }
     
+    // Backup responsibility (should be separate); see `BackupService` in export.rs
     pub fn backup_user_data(&self) -> Result<bool, String> {
         println!("Backing up user data for: {}", self.username);
         // Backup logic
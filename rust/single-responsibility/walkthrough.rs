@@ -0,0 +1,256 @@
+// Runs one scripted scenario against ViolationUserService and CorrectUserService
+// and reports which component handled each part of every action
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use serde::Serialize;
+use crate::{ApiVersion, CsvExporter, UserExporter};
+use super::testing::{mock_user_service, MockUserService, Mocks, Spy};
+use super::violation::ViolationUserService;
+
+// One component's part in a step: the responsibility it exercised and its calls
+#[derive(Debug, Clone, Serialize)]
+pub struct Handling {
+    pub component: String,
+    pub responsibility: String,
+    pub calls: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WalkthroughStep {
+    pub action: String,
+    pub violation: Vec<Handling>,
+    pub correct: Vec<Handling>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WalkthroughReport {
+    pub steps: Vec<WalkthroughStep>,
+}
+
+impl WalkthroughReport {
+    pub fn to_json(&self) -> Result<String, String> {
+        serde_json::to_string_pretty(self).map_err(|e| e.to_string())
+    }
+    
+    // Every responsibility each component exercised over the whole scenario
+    fn responsibilities(handlings: impl Iterator<Item = Handling>) -> BTreeMap<String, BTreeSet<String>> {
+        let mut by_component: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+        for handling in handlings {
+            by_component.entry(handling.component).or_default().insert(handling.responsibility);
+        }
+        by_component
+    }
+    
+    pub fn violation_responsibilities(&self) -> BTreeMap<String, BTreeSet<String>> {
+        Self::responsibilities(self.steps.iter().flat_map(|step| step.violation.clone()))
+    }
+    
+    pub fn correct_responsibilities(&self) -> BTreeMap<String, BTreeSet<String>> {
+        Self::responsibilities(self.steps.iter().flat_map(|step| step.correct.clone()))
+    }
+}
+
+impl fmt::Display for WalkthroughReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (number, step) in self.steps.iter().enumerate() {
+            writeln!(f, "Step {}: {}", number + 1, step.action)?;
+            for (design, handlings) in [("Violation", &step.violation), ("Correct", &step.correct)] {
+                writeln!(f, "  {}:", design)?;
+                for handling in handlings {
+                    writeln!(f, "    {:<22} {:<18} {}", handling.component, handling.responsibility, handling.calls.join(", "))?;
+                }
+            }
+        }
+        let violation = self.violation_responsibilities();
+        let correct = self.correct_responsibilities();
+        let violation_total: usize = violation.values().map(BTreeSet::len).sum();
+        writeln!(f, "Violation: {} responsibilities in {} component(s)", violation_total, violation.len())?;
+        write!(f, "Correct: {} components, at most {} responsibility each",
+               correct.len(), correct.values().map(BTreeSet::len).max().unwrap_or(0))
+    }
+}
+
+// Records each ViolationUserService method the scenario calls, under the
+// responsibility it belongs to
+struct ViolationRecorder {
+    service: ViolationUserService,
+    handled: Vec<Handling>,
+}
+
+impl ViolationRecorder {
+    fn call<Out>(&mut self, responsibility: &str, method: &str, work: impl FnOnce(&mut ViolationUserService) -> Out) -> Out {
+        match self.handled.last_mut().filter(|last| last.responsibility == responsibility) {
+            Some(last) => last.calls.push(method.to_string()),
+            None => self.handled.push(Handling {
+                component: "ViolationUserService".to_string(),
+                responsibility: responsibility.to_string(),
+                calls: vec![method.to_string()],
+            }),
+        }
+        work(&mut self.service)
+    }
+    
+    fn take(&mut self) -> Vec<Handling> {
+        std::mem::take(&mut self.handled)
+    }
+}
+
+// Each double in `Mocks` stands in for one collaborator with one responsibility
+fn collaborators(mocks: &Mocks) -> [(&'static str, &'static str, &Spy); 11] {
+    [
+        ("UserValidator", "validation", mocks.validator.spy()),
+        ("UserRepository", "persistence", mocks.repository.spy()),
+        ("PasswordHasher", "password hashing", mocks.passwords.spy()),
+        ("CredentialStore", "credentials", mocks.credentials.spy()),
+        ("EmailTemplateRenderer", "email content", mocks.templates.spy()),
+        ("EmailService", "email delivery", mocks.email.spy()),
+        ("ActivityLogger", "logging", mocks.logger.spy()),
+        ("AnalyticsService", "analytics", mocks.analytics.spy()),
+        ("NotificationService", "notifications", mocks.notifications.spy()),
+        ("SessionService", "sessions", mocks.sessions.spy()),
+        ("UserFormatter", "formatting", mocks.formatter.spy()),
+    ]
+}
+
+// Runs `work` and reports the calls each double received while it ran,
+// after any components `work` used directly
+fn observe(service: &MockUserService, mocks: &Mocks,
+           work: impl FnOnce(&MockUserService) -> Result<Vec<Handling>, String>) -> Result<Vec<Handling>, String> {
+    let before: Vec<usize> = collaborators(mocks).iter().map(|(_, _, spy)| spy.calls().len()).collect();
+    let mut handled = work(service)?;
+    for ((component, responsibility, spy), seen) in collaborators(mocks).into_iter().zip(before) {
+        let calls: Vec<String> = spy.calls().into_iter().skip(seen).collect();
+        if !calls.is_empty() {
+            handled.push(Handling { component: component.to_string(), responsibility: responsibility.to_string(), calls });
+        }
+    }
+    Ok(handled)
+}
+
+fn step(action: &str, violation: Vec<Handling>, correct: Vec<Handling>) -> WalkthroughStep {
+    WalkthroughStep { action: action.to_string(), violation, correct }
+}
+
+pub fn run() -> Result<WalkthroughReport, String> {
+    let mut violation = ViolationRecorder {
+        service: ViolationUserService::new("ada_l".to_string(), "ada@example.com".to_string()),
+        handled: Vec::new(),
+    };
+    let (service, mocks) = mock_user_service();
+    let mut steps = Vec::new();
+    
+    violation.call("validation", "validate_email", |service| service.validate_email());
+    violation.call("validation", "validate_username", |service| service.validate_username());
+    violation.call("persistence", "connect_to_database", |service| service.connect_to_database())?;
+    violation.call("persistence", "save_user", |service| service.save_user())?;
+    violation.call("email delivery", "send_welcome_email", |service| service.send_welcome_email())?;
+    violation.call("logging", "log_user_activity", |service| service.log_user_activity("User registered"));
+    violation.call("analytics", "track_user_registration", |service| service.track_user_registration());
+    violation.call("notifications", "send_push_notification", |service| service.send_push_notification("Welcome!"))?;
+    let mut ada = None;
+    let correct = observe(&service, &mocks, |service| {
+        ada = Some(service.create_user("ada_l".to_string(), "ada@example.com".to_string()).map_err(|e| e.to_string())?);
+        Ok(Vec::new())
+    })?;
+    let mut ada = ada.ok_or("User was not created")?;
+    steps.push(step("Register a new user", violation.take(), correct));
+    
+    violation.call("persistence", "update_user", |service| {
+        service.update_user("ada_lovelace".to_string(), "countess@example.com".to_string())
+    })?;
+    violation.call("validation", "validate_email", |service| service.validate_email());
+    violation.call("validation", "validate_username", |service| service.validate_username());
+    violation.call("logging", "log_user_activity", |service| service.log_user_activity("User updated"));
+    violation.call("analytics", "track_user_event", |service| service.track_user_event("user_updated", None));
+    let correct = observe(&service, &mocks, |service| {
+        service.update_user(&mut ada, "ada_lovelace".to_string(), "countess@example.com".to_string()).map_err(|e| e.to_string())?;
+        Ok(Vec::new())
+    })?;
+    steps.push(step("Change username and email", violation.take(), correct));
+    
+    violation.call("formatting", "format_user_for_display", |service| service.format_user_for_display());
+    violation.call("formatting", "format_user_for_api", |service| service.format_user_for_api());
+    let correct = observe(&service, &mocks, |service| {
+        service.format_user(&ada);
+        service.get_user_api_data(&ada, ApiVersion::LATEST);
+        Ok(Vec::new())
+    })?;
+    steps.push(step("Show the user", violation.take(), correct));
+    
+    violation.call("export", "export_user_to_csv", |service| service.export_user_to_csv())?;
+    let correct = observe(&service, &mocks, |_| {
+        CsvExporter::new().export_to_string(std::slice::from_ref(&ada))?;
+        Ok(vec![Handling {
+            component: "CsvExporter".to_string(),
+            responsibility: "export".to_string(),
+            calls: vec!["export_to_string".to_string()],
+        }])
+    })?;
+    steps.push(step("Export as CSV", violation.take(), correct));
+    
+    violation.call("persistence", "delete_user", |service| service.delete_user())?;
+    violation.call("logging", "log_user_activity", |service| service.log_user_activity("User deleted"));
+    violation.call("persistence", "close_database_connection", |service| service.close_database_connection());
+    let correct = observe(&service, &mocks, |service| {
+        service.delete_user(&mut ada)?;
+        Ok(Vec::new())
+    })?;
+    steps.push(step("Delete the user", violation.take(), correct));
+    
+    Ok(WalkthroughReport { steps })
+}
+
+// Tests for the walkthrough report (needs the `walkthrough` feature)
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn every_step_is_handled_in_both_designs() {
+        let report = run().unwrap();
+        assert_eq!(report.steps.len(), 5);
+        for step in &report.steps {
+            assert!(!step.violation.is_empty(), "{} has no violation handling", step.action);
+            assert!(!step.correct.is_empty(), "{} has no correct handling", step.action);
+        }
+    }
+    
+    #[test]
+    fn the_violation_has_one_component_with_many_responsibilities() {
+        let responsibilities = run().unwrap().violation_responsibilities();
+        assert_eq!(responsibilities.keys().collect::<Vec<_>>(), vec!["ViolationUserService"]);
+        assert!(responsibilities["ViolationUserService"].len() >= 7);
+    }
+    
+    #[test]
+    fn each_correct_component_has_one_responsibility() {
+        let responsibilities = run().unwrap().correct_responsibilities();
+        assert!(responsibilities.len() >= 7);
+        for (component, handled) in &responsibilities {
+            assert_eq!(handled.len(), 1, "{} handled {:?}", component, handled);
+        }
+    }
+    
+    #[test]
+    fn registration_is_traced_to_each_collaborator() {
+        let report = run().unwrap();
+        let register = &report.steps[0];
+        let components: Vec<_> = register.correct.iter().map(|handling| handling.component.as_str()).collect();
+        for expected in ["UserValidator", "UserRepository", "EmailTemplateRenderer", "EmailService", "ActivityLogger", "AnalyticsService", "NotificationService"] {
+            assert!(components.contains(&expected), "{} missing from {:?}", expected, components);
+        }
+        let repository = register.correct.iter().find(|handling| handling.component == "UserRepository").unwrap();
+        assert!(repository.calls.iter().any(|call| call.starts_with("save(")));
+    }
+    
+    #[test]
+    fn report_renders_as_text_and_json() {
+        let report = run().unwrap();
+        let text = report.to_string();
+        assert!(text.starts_with("Step 1: Register a new user"));
+        assert!(text.contains("ViolationUserService"));
+        let json: serde_json::Value = serde_json::from_str(&report.to_json().unwrap()).unwrap();
+        assert_eq!(json["steps"][3]["correct"][0]["component"], "CsvExporter");
+    }
+}